@@ -1,3 +1,4 @@
+use std::collections::BTreeMap;
 use std::fs::{File, create_dir_all};
 use std::io::Write;
 use std::path::{Path, PathBuf};
@@ -5,17 +6,68 @@ use std::str::FromStr;
 
 use serde::Serialize;
 
+use rust_code_analysis::{
+    Finding, FuncSpace, GradeOptions, JUnitCases, LizardThresholds, Rule, check_rules,
+    checkstyle_xml, code_climate_issues, gitlab_code_quality, junit_xml, lizard_functions,
+    radon_cc, radon_mi,
+};
+
 #[derive(Debug, Clone)]
 pub enum Format {
     Cbor,
+    Checkstyle,
+    Codeclimate,
+    Gitlab,
     Json,
+    Junit,
+    LizardCsv,
+    LizardWarnings,
+    Ndjson,
+    Parquet,
+    RadonCc,
+    RadonMi,
     Toml,
     Yaml,
 }
 
 impl Format {
     pub const fn all() -> &'static [&'static str] {
-        &["cbor", "json", "toml", "yaml"]
+        &[
+            "cbor",
+            "checkstyle",
+            "codeclimate",
+            "gitlab",
+            "json",
+            "junit",
+            "lizard-csv",
+            "lizard-warnings",
+            "ndjson",
+            "parquet",
+            "radon-cc",
+            "radon-mi",
+            "toml",
+            "yaml",
+        ]
+    }
+
+    /// Checks if the spaces of all the files are written together
+    /// at the end of the analysis.
+    pub fn is_collected(&self) -> bool {
+        matches!(self, Self::Parquet | Self::RadonCc | Self::RadonMi)
+    }
+
+    /// Checks if the format serializes the function spaces.
+    pub fn is_serialized(&self) -> bool {
+        matches!(
+            self,
+            Self::Cbor | Self::Json | Self::Ndjson | Self::Toml | Self::Yaml
+        )
+    }
+
+    /// Checks if the format is a report of the violations of the rules
+    /// by the functions of all the files.
+    pub fn is_report(&self) -> bool {
+        matches!(self, Self::Checkstyle | Self::Gitlab | Self::Junit)
     }
 
     pub fn dump_formats<T: Serialize>(
@@ -29,18 +81,137 @@ impl Format {
             match self {
                 Self::Cbor => Cbor::with_writer(space, path, output_path),
                 Self::Json => Json::with_pretty_writer(space, path, output_path, pretty),
+                Self::Ndjson => panic!("Ndjson format can only be printed to stdout"),
+                Self::Codeclimate | Self::LizardCsv | Self::LizardWarnings => {
+                    panic!("{self:?} format can only be printed to stdout")
+                }
+                Self::Checkstyle
+                | Self::Gitlab
+                | Self::Junit
+                | Self::Parquet
+                | Self::RadonCc
+                | Self::RadonMi => {
+                    panic!("{self:?} format is written at the end of the analysis")
+                }
                 Self::Toml => Toml::with_pretty_writer(space, path, output_path, pretty),
                 Self::Yaml => Yaml::with_writer(space, path, output_path),
             }
         } else {
             match self {
                 Self::Json => Json::write_on_stdout_pretty(space, pretty),
+                // One compact object per line, printed as soon as a file is analyzed
+                Self::Ndjson => Json::write_on_stdout(space),
                 Self::Toml => Toml::write_on_stdout_pretty(space, pretty),
                 Self::Yaml => Yaml::write_on_stdout(space),
                 Self::Cbor => panic!("Cbor format cannot be printed to stdout"),
+                Self::Codeclimate | Self::LizardCsv | Self::LizardWarnings => {
+                    panic!("{self:?} format is written from the function spaces")
+                }
+                Self::Parquet => panic!("Parquet format cannot be printed to stdout"),
+                Self::Checkstyle | Self::Gitlab | Self::Junit | Self::RadonCc | Self::RadonMi => {
+                    panic!("{self:?} format is written at the end of the analysis")
+                }
             }
         }
     }
+
+    /// Prints the functions of a file in the shape of the output of lizard's
+    /// `--csv` option or the warnings for the functions exceeding the thresholds.
+    pub fn write_lizard(&self, space: &FuncSpace, thresholds: &LizardThresholds) {
+        let file = space.name.as_deref().unwrap_or_default();
+        let lines: Vec<_> = lizard_functions(space)
+            .into_iter()
+            .filter_map(|function| match self {
+                Self::LizardCsv => Some(function.to_csv(file)),
+                Self::LizardWarnings => thresholds
+                    .is_exceeded_by(&function)
+                    .then(|| function.to_warning(file)),
+                _ => panic!("{self:?} is not a lizard format"),
+            })
+            .collect();
+
+        // The lines of a file are printed together
+        if !lines.is_empty() {
+            print_on_stdout(lines.join("\n"));
+        }
+    }
+
+    /// Prints the violations of the rules by the functions of a file
+    /// as the issues of a Code Climate engine, that is JSON objects
+    /// terminated by a null character.
+    pub fn write_codeclimate(&self, space: &FuncSpace, rules: &[Rule]) {
+        let issues: String = code_climate_issues(&check_rules(space, rules))
+            .iter()
+            .map(|issue| Json::format(issue) + "\0")
+            .collect();
+
+        // The issues of a file are printed together
+        if !issues.is_empty() {
+            write!(std::io::stdout().lock(), "{issues}").unwrap();
+        }
+    }
+
+    /// Writes the spaces of all the files in the shape of the output
+    /// of radon's `cc -j` or `mi -j` command, keyed by the file paths.
+    pub fn write_radon(
+        &self,
+        spaces: &[FuncSpace],
+        options: &GradeOptions,
+        output_path: Option<&PathBuf>,
+        pretty: bool,
+    ) -> std::io::Result<()> {
+        let path = |space: &FuncSpace| space.name.clone().unwrap_or_default();
+        let content = match self {
+            Self::RadonCc => Json::format_pretty_if(
+                spaces
+                    .iter()
+                    .map(|space| (path(space), radon_cc(space, options)))
+                    .collect::<BTreeMap<_, _>>(),
+                pretty,
+            ),
+            Self::RadonMi => Json::format_pretty_if(
+                spaces
+                    .iter()
+                    .map(|space| (path(space), radon_mi(space, options)))
+                    .collect::<BTreeMap<_, _>>(),
+                pretty,
+            ),
+            _ => panic!("{self:?} is not a radon format"),
+        };
+
+        if let Some(output_path) = output_path {
+            std::fs::write(output_path, content)
+        } else {
+            print_on_stdout(content);
+            Ok(())
+        }
+    }
+
+    /// Writes the violations of the rules by the functions of all the files
+    /// as a Checkstyle, a GitLab code quality or a JUnit report.
+    pub fn write_report(
+        &self,
+        files: &BTreeMap<String, Vec<Finding>>,
+        rules: &[Rule],
+        cases: JUnitCases,
+        output_path: Option<&PathBuf>,
+        pretty: bool,
+    ) -> std::io::Result<()> {
+        let findings: Vec<_> = files.values().flatten().cloned().collect();
+        let content = match self {
+            Self::Checkstyle => checkstyle_xml(&findings),
+            Self::Gitlab => Json::format_pretty_if(gitlab_code_quality(&findings), pretty),
+            Self::Junit => junit_xml(files, rules, cases),
+            _ => panic!("{self:?} is not a report format"),
+        };
+
+        if let Some(output_path) = output_path {
+            std::fs::write(output_path, content)
+        } else {
+            print_on_stdout(content);
+            Ok(())
+        }
+    }
 }
 
 impl FromStr for Format {
@@ -49,7 +220,17 @@ impl FromStr for Format {
     fn from_str(format: &str) -> Result<Self, Self::Err> {
         match format {
             "cbor" => Ok(Self::Cbor),
+            "checkstyle" => Ok(Self::Checkstyle),
+            "codeclimate" => Ok(Self::Codeclimate),
+            "gitlab" => Ok(Self::Gitlab),
             "json" => Ok(Self::Json),
+            "junit" => Ok(Self::Junit),
+            "lizard-csv" => Ok(Self::LizardCsv),
+            "lizard-warnings" => Ok(Self::LizardWarnings),
+            "ndjson" => Ok(Self::Ndjson),
+            "parquet" => Ok(Self::Parquet),
+            "radon-cc" => Ok(Self::RadonCc),
+            "radon-mi" => Ok(Self::RadonMi),
             "toml" => Ok(Self::Toml),
             "yaml" => Ok(Self::Yaml),
             format => Err(format!("{format:?} is not a supported format")),
@@ -144,6 +325,16 @@ impl WritePrettyOnStdout for Json {
     }
 }
 
+impl Json {
+    fn format_pretty_if<T: Serialize>(content: T, pretty: bool) -> String {
+        if pretty {
+            Self::format_pretty(content)
+        } else {
+            Self::format(content)
+        }
+    }
+}
+
 impl WriteFile for Json {
     const EXTENSION: &'static str = ".json";
 
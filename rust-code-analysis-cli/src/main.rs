@@ -1,48 +1,105 @@
 mod formats;
+mod profile;
+mod remote;
+mod staged;
+mod tui;
 
 use std::cmp::Ordering;
-use std::collections::{HashMap, hash_map};
+use std::collections::{BTreeMap, HashMap, hash_map};
+use std::fs::File;
+use std::io::{BufReader, BufWriter, Read};
 use std::path::{Path, PathBuf};
 use std::process;
 use std::sync::{Arc, Mutex};
 use std::thread::available_parallelism;
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
 
 use clap::Parser;
 use clap::builder::{PossibleValuesParser, TypedValueParser};
 use globset::{Glob, GlobSet, GlobSetBuilder};
+use tracing_subscriber::EnvFilter;
+use tracing_subscriber::fmt::format::FmtSpan;
 
 use formats::Format;
+use profile::{FileProfile, Profile, TimedMetrics, TimedMetricsCfg};
 
 // Enums
 use rust_code_analysis::LANG;
 
 // Structs
+use rust_code_analysis::cognitive::{CognitiveMode, ComprehensionMode};
+use rust_code_analysis::cyclomatic::CyclomaticOptions;
+use rust_code_analysis::halstead::HalsteadOptions;
+use rust_code_analysis::loc::LocOptions;
 use rust_code_analysis::{
-    CommentRm, CommentRmCfg, ConcurrentRunner, Count, CountCfg, Dump, DumpCfg, FilesData, Find,
-    FindCfg, Function, FunctionCfg, Metrics, MetricsCfg, OpsCfg, OpsCode, PreprocParser,
-    PreprocResults,
+    AnonymousNaming, Badges, Cocomo, CocomoModel, CocomoOptions, CocomoProject, CommentRm,
+    CommentRmCfg, ConcurrentRunner, Count, CountCfg, Coverage, CppFile, CppParser, CrapReport,
+    Debt, Dialect, Dump, DumpCfg, FileClass, FileClassification, FilesData, Find, FindCfg, Finding,
+    FuncSpace, Function, FunctionCfg, FunctionGrade, FunctionReferences, GitHubReviewComment,
+    GitLabDiscussion, Grade, GradeHistory, GradeOptions, Grep, GrepCfg, JUnitCases,
+    LizardThresholds, MemoryFit, MetricsOptions, MetricsSummary, OpsCfg, OpsCode, OutlierMethod,
+    Outliers, Pattern, PreprocParser, PreprocResults, PublicApi, PublicApiCfg, ReviewComment, Rule,
+    Schedule, Sexp, SexpCfg, Snapshot, SnapshotResults, SpaceKindMap, Summary, SymlinkPolicy,
+    TestPatterns, TopFunctions, get_embedded_spaces, get_function_references,
+    get_function_spaces_with_options, get_public_api, guess_language_with_dialects, parse_diff,
+    review_comments, unreferenced_functions,
 };
 
 // Functions
 use rust_code_analysis::{
-    action, fix_includes, get_from_ext, get_function_spaces, get_ops, guess_language, preprocess,
-    read_file, read_file_with_eol, write_file,
+    action, check_rules, classify_generated, code_hash, cpp_file, date_timestamp, dump_root,
+    estimate_analysis_memory, filter_by_grade, fit_analysis_memory, fix_includes, function_grades,
+    get_from_emacs_mode, get_from_ext, get_ops, guess_language, is_component, is_embedding_host,
+    pair_headers, preprocess, read_bytes_with_eol, read_file, read_file_with_eol, timestamp_date,
+    write_file, write_parquet,
 };
 
+// Constants
+use rust_code_analysis::DEFAULT_CRAP_THRESHOLD;
+
 // Traits
 use rust_code_analysis::ParserTrait;
 
+// The violations of the rules, for each analyzed file
+type Findings = BTreeMap<String, Vec<Finding>>;
+
+// A file whose output is deferred until all the files are analyzed
 #[derive(Debug)]
+enum DeferredFile {
+    Space(LANG, Box<FuncSpace>, FileProfile),
+    // The results reused from a snapshot, with the hash of the code
+    Snapshot(SnapshotResults, String, PathBuf),
+}
+
+impl DeferredFile {
+    fn path(&self) -> &Path {
+        match self {
+            Self::Space(_, _, times) => &times.path,
+            Self::Snapshot(_, _, path) => path,
+        }
+    }
+}
+
+#[derive(Clone, Debug)]
 struct Config {
     dump: bool,
+    sexp: bool,
+    byte_ranges: bool,
     in_place: bool,
     comments: bool,
     find_filter: Vec<String>,
     count_filter: Vec<String>,
+    search: Option<Arc<Pattern>>,
+    search_context: usize,
     language: Option<LANG>,
+    dialects: Vec<Dialect>,
     function: bool,
     metrics: bool,
+    metrics_options: MetricsOptions,
+    min_grade: Option<Grade>,
+    lizard: LizardThresholds,
     ops: bool,
+    api: bool,
     output_format: Option<Format>,
     output: Option<PathBuf>,
     pretty: bool,
@@ -51,6 +108,86 @@ struct Config {
     preproc_lock: Option<Arc<Mutex<PreprocResults>>>,
     preproc: Option<Arc<PreprocResults>>,
     count_lock: Option<Arc<Mutex<Count>>>,
+    spaces_lock: Option<Arc<Mutex<Vec<FuncSpace>>>>,
+    summary_lock: Option<Arc<Mutex<Summary>>>,
+    language_summary_lock: Option<Arc<Mutex<MetricsSummary>>>,
+    rules: Vec<Rule>,
+    debt_lock: Option<Arc<Mutex<BTreeMap<String, Debt>>>>,
+    badges_lock: Option<Arc<Mutex<Badges>>>,
+    top_lock: Option<Arc<Mutex<TopFunctions>>>,
+    crap_lock: Option<Arc<Mutex<CrapReport>>>,
+    outliers_lock: Option<Arc<Mutex<Outliers>>>,
+    snapshot: Option<Arc<Snapshot>>,
+    snapshot_lock: Option<Arc<Mutex<Snapshot>>>,
+    findings_lock: Option<Arc<Mutex<Findings>>>,
+    grades_lock: Option<Arc<Mutex<Vec<FunctionGrade>>>>,
+    references_lock: Option<Arc<Mutex<Vec<FunctionReferences>>>>,
+    header_pairs_lock: Option<Arc<Mutex<Vec<CppFile>>>>,
+    profile_lock: Option<Arc<Mutex<Profile>>>,
+    classification_lock: Option<Arc<Mutex<FileClassification>>>,
+    deferred_lock: Option<Arc<Mutex<Vec<DeferredFile>>>>,
+    include_generated: bool,
+    embedded: bool,
+    max_memory: Option<u64>,
+    root: Option<PathBuf>,
+}
+
+// Comments the functions changed by a diff, reading the new files
+// from the current directory and rebuilding the old ones from the diff
+fn review(diff: &Path, cfg: &Config) -> std::io::Result<Vec<ReviewComment>> {
+    let diff = String::from_utf8_lossy(&read_file(diff)?).into_owned();
+    let mut comments = Vec::new();
+    for file in parse_diff(&diff) {
+        let Some(path) = file.new_path.as_deref().map(PathBuf::from) else {
+            continue;
+        };
+        let Some(new) = read_file_with_eol(&path)? else {
+            continue;
+        };
+        let Some(language) = cfg
+            .language
+            .or_else(|| guess_language_with_dialects(&new, &path, &cfg.dialects).0)
+        else {
+            continue;
+        };
+
+        let old = file.old_path.is_some().then(|| file.old_source(&new));
+        let spaces = |source| {
+            get_function_spaces_with_options(
+                &language,
+                source,
+                &path,
+                cfg.preproc.clone(),
+                &cfg.metrics_options,
+            )
+        };
+        let Some(new) = spaces(new) else {
+            continue;
+        };
+        let old = old.and_then(spaces);
+        comments.extend(review_comments(&file, &new, old.as_ref(), &cfg.rules));
+    }
+    Ok(comments)
+}
+
+impl Config {
+    // Checks if only the results aggregated over all the files are printed
+    fn is_aggregated(&self) -> bool {
+        self.summary_lock.is_some()
+            || self.language_summary_lock.is_some()
+            || self.debt_lock.is_some()
+            || self.badges_lock.is_some()
+            || self.top_lock.is_some()
+            || self.crap_lock.is_some()
+            || self.outliers_lock.is_some()
+            || self.grades_lock.is_some()
+    }
+
+    fn classify(&self, class: FileClass) {
+        if let Some(classification) = &self.classification_lock {
+            classification.lock().unwrap().add(class);
+        }
+    }
 }
 
 fn mk_globset(elems: Vec<String>) -> GlobSet {
@@ -67,20 +204,354 @@ fn mk_globset(elems: Vec<String>) -> GlobSet {
     globset.build().map_or(GlobSet::empty(), |globset| globset)
 }
 
+fn init_logging(level: Option<&str>) {
+    let filter = match level {
+        Some(level) => EnvFilter::new(level),
+        None => match EnvFilter::try_from_default_env() {
+            Ok(filter) => filter,
+            // Nothing is logged by default
+            Err(_) => return,
+        },
+    };
+
+    // The duration of each file, parse and metrics pass is printed
+    // when its span is closed
+    tracing_subscriber::fmt()
+        .with_env_filter(filter)
+        .with_span_events(FmtSpan::CLOSE)
+        .with_thread_names(true)
+        .with_writer(std::io::stderr)
+        .init();
+}
+
+// Checks how the analysis of a code fits in the memory cap
+fn memory_fit(size: u64, cfg: &Config) -> MemoryFit {
+    cfg.max_memory.map_or(MemoryFit::Full, |max_memory| {
+        fit_analysis_memory(size, max_memory.saturating_mul(1 << 20))
+    })
+}
+
+// Checks whether the analysis of a code fits in the memory cap,
+// at least for the metrics of its unit
+fn fits_in_memory(size: u64, path: &Path, cfg: &Config) -> bool {
+    let Some(max_memory) = cfg.max_memory else {
+        return true;
+    };
+
+    if memory_fit(size, cfg) == MemoryFit::TooLarge {
+        let memory = estimate_analysis_memory(size) >> 20;
+        eprintln!(
+            "Skipping file too large to analyze in {max_memory} MiB: {path:?} (about {memory} MiB needed)"
+        );
+        return false;
+    }
+
+    true
+}
+
 fn act_on_file(path: PathBuf, cfg: &Config) -> std::io::Result<()> {
+    // Avoid reading the files which cannot be analyzed anyway
+    let size = std::fs::metadata(&path)?.len();
+    if !fits_in_memory(size, &path, cfg) {
+        cfg.classify(FileClass::Ignored);
+        return Ok(());
+    }
+
+    let start = Instant::now();
     let source = if let Some(source) = read_file_with_eol(&path)? {
         source
     } else {
+        cfg.classify(unreadable_class(size));
+        return Ok(());
+    };
+
+    // Report the paths relative to the clone of a remote repository
+    let path = match &cfg.root {
+        Some(root) => path
+            .strip_prefix(root)
+            .map_or(path.clone(), Path::to_path_buf),
+        None => path,
+    };
+
+    act_on_code(source, path, start.elapsed(), cfg)
+}
+
+// The class of a file which is not a text file, unless it is
+// almost empty, as `read_bytes_with_eol` does
+fn unreadable_class(size: u64) -> FileClass {
+    if size <= 3 {
+        FileClass::Ignored
+    } else {
+        FileClass::Binary
+    }
+}
+
+fn act_on_archive_entry(path: PathBuf, data: Vec<u8>, cfg: &Config) -> std::io::Result<()> {
+    if !fits_in_memory(data.len() as u64, &path, cfg) {
+        cfg.classify(FileClass::Ignored);
+        return Ok(());
+    }
+
+    let start = Instant::now();
+    let source = if let Some(source) = read_bytes_with_eol(&data) {
+        source
+    } else {
+        cfg.classify(unreadable_class(data.len() as u64));
+        return Ok(());
+    };
+
+    act_on_code(source, path, start.elapsed(), cfg)
+}
+
+fn act_on_code(
+    source: Vec<u8>,
+    path: PathBuf,
+    read: Duration,
+    cfg: &Config,
+) -> std::io::Result<()> {
+    if !cfg.include_generated
+        && let Some(kind) = classify_generated(&source, &path)
+    {
+        eprintln!("Skipping {kind} file: {path:?}");
+        cfg.classify(FileClass::Ignored);
+        return Ok(());
+    }
+
+    act_on_source(source, path, read, cfg)
+}
+
+fn act_on_metrics(
+    language: LANG,
+    source: Vec<u8>,
+    path: PathBuf,
+    read: Duration,
+    cfg: &Config,
+) -> std::io::Result<()> {
+    let mut options = cfg.metrics_options.clone();
+    if memory_fit(source.len() as u64, cfg) == MemoryFit::UnitOnly {
+        eprintln!("Analyzing only the unit of a file too large for a full analysis: {path:?}");
+        options.unit_only = true;
+    }
+
+    let timed_cfg = TimedMetricsCfg {
+        path: path.clone(),
+        options,
+        start: Instant::now(),
+    };
+    let (space, parse, metrics) =
+        action::<TimedMetrics>(&language, source, &path, cfg.preproc.clone(), timed_cfg);
+    let Some(space) = space else {
+        return Ok(());
+    };
+    let times = FileProfile {
+        path,
+        read,
+        parse,
+        metrics,
+        serialize: Duration::ZERO,
+    };
+
+    act_on_space(language, space, times, cfg)
+}
+
+// Computes the metrics of the scripts embedded in an HTML page, in a template
+// or in a Vue or a Svelte component
+fn act_on_embedded(
+    source: Vec<u8>,
+    path: PathBuf,
+    read: Duration,
+    cfg: &Config,
+) -> std::io::Result<()> {
+    let start = Instant::now();
+    let Some((language, space)) = get_embedded_spaces(&source, &path, &cfg.metrics_options) else {
+        return Ok(());
+    };
+    let times = FileProfile {
+        path,
+        read,
+        parse: Duration::ZERO,
+        metrics: start.elapsed(),
+        serialize: Duration::ZERO,
+    };
+
+    act_on_space(language, space, times, cfg)
+}
+
+// Outputs or collects the metrics of a file, whose times are profiled
+fn act_on_space(
+    language: LANG,
+    space: FuncSpace,
+    times: FileProfile,
+    cfg: &Config,
+) -> std::io::Result<()> {
+    if let Some(deferred) = &cfg.deferred_lock {
+        deferred
+            .lock()
+            .unwrap()
+            .push(DeferredFile::Space(language, Box::new(space), times));
         return Ok(());
+    }
+    output_space(language, space, times, cfg)
+}
+
+fn output_space(
+    language: LANG,
+    mut space: FuncSpace,
+    times: FileProfile,
+    cfg: &Config,
+) -> std::io::Result<()> {
+    let path = times.path.clone();
+    if let Some(summary) = &cfg.summary_lock {
+        summary.lock().unwrap().add(language, &space);
+    }
+    if let Some(summary) = &cfg.language_summary_lock {
+        summary.lock().unwrap().add(language, &space);
+    }
+    if let Some(debts) = &cfg.debt_lock {
+        let debt = Debt::new(&space, &check_rules(&space, &cfg.rules));
+        let path = space.name.clone().unwrap_or_default();
+        debts.lock().unwrap().insert(path, debt);
+    }
+    if let Some(badges) = &cfg.badges_lock {
+        badges.lock().unwrap().add(&space);
+    }
+    if let Some(top) = &cfg.top_lock {
+        top.lock().unwrap().add(&space);
+    }
+    if let Some(crap) = &cfg.crap_lock {
+        crap.lock().unwrap().add(&space);
+    }
+    if let Some(outliers) = &cfg.outliers_lock {
+        outliers.lock().unwrap().add(&space);
+    }
+    if let Some(findings) = &cfg.findings_lock {
+        // The files without findings are kept for the JUnit reports
+        let path = space.name.clone().unwrap_or_default();
+        findings
+            .lock()
+            .unwrap()
+            .insert(path, check_rules(&space, &cfg.rules));
+    }
+    if let Some(grades) = &cfg.grades_lock {
+        grades.lock().unwrap().extend(function_grades(&space));
+    }
+    if let Some(min_grade) = cfg.min_grade {
+        filter_by_grade(&mut space, min_grade);
+    }
+    if let Some(snapshot) = &cfg.snapshot_lock {
+        snapshot.lock().unwrap().insert(&space);
+    }
+
+    let start = Instant::now();
+    if let Some(spaces) = &cfg.spaces_lock {
+        // The spaces of all the files are written together at the end
+        spaces.lock().unwrap().push(space);
+    } else if cfg.findings_lock.is_some() {
+        // The findings of all the files are written together at the end
+    } else if let Some(output_format @ Format::Codeclimate) = &cfg.output_format {
+        output_format.write_codeclimate(&space, &cfg.rules);
+    } else if let Some(output_format @ (Format::LizardCsv | Format::LizardWarnings)) =
+        &cfg.output_format
+    {
+        output_format.write_lizard(&space, &cfg.lizard);
+    } else if let Some(output_format) = &cfg.output_format {
+        output_format.dump_formats(space, path.clone(), cfg.output.as_ref(), cfg.pretty);
+    } else if !cfg.is_aggregated() {
+        dump_root(&space)?;
+    }
+
+    if let Some(profile) = &cfg.profile_lock {
+        let file = FileProfile {
+            serialize: start.elapsed(),
+            ..times
+        };
+        eprintln!("{file}");
+        profile.lock().unwrap().push(file);
+    }
+
+    Ok(())
+}
+
+// Outputs the results of a file imported from a snapshot,
+// whose code has not changed since they have been computed
+fn act_on_snapshot(results: SnapshotResults, hash: String, path: PathBuf, cfg: &Config) {
+    if let Some(deferred) = &cfg.deferred_lock {
+        deferred
+            .lock()
+            .unwrap()
+            .push(DeferredFile::Snapshot(results, hash, path));
+        return;
+    }
+    if let Some(snapshot) = &cfg.snapshot_lock
+        && let Some(name) = path.to_str()
+    {
+        snapshot
+            .lock()
+            .unwrap()
+            .insert_results(name, hash, results.clone());
+    }
+    if let Some(output_format) = &cfg.output_format {
+        output_format.dump_formats(results, path, cfg.output.as_ref(), cfg.pretty);
+    }
+}
+
+// Outputs the deferred files in the order of their paths, so that the outputs
+// do not depend on the order in which the analyses complete
+fn output_deferred(cfg: &Config) {
+    let Some(deferred) = &cfg.deferred_lock else {
+        return;
     };
+    let mut files = std::mem::take(&mut *deferred.lock().unwrap());
+    files.sort_by(|a, b| a.path().cmp(b.path()));
+
+    let cfg = Config {
+        deferred_lock: None,
+        ..cfg.clone()
+    };
+    for file in files {
+        match file {
+            DeferredFile::Space(language, space, times) => {
+                let path = times.path.clone();
+                if let Err(e) = output_space(language, *space, times, &cfg) {
+                    eprintln!("{e:?} for file {path:?}");
+                }
+            }
+            DeferredFile::Snapshot(results, hash, path) => {
+                act_on_snapshot(results, hash, path, &cfg);
+            }
+        }
+    }
+}
+
+fn act_on_source(
+    source: Vec<u8>,
+    path: PathBuf,
+    read: Duration,
+    cfg: &Config,
+) -> std::io::Result<()> {
+    if let Some(results) = cfg
+        .snapshot
+        .as_ref()
+        .zip(path.to_str())
+        .and_then(|(snapshot, name)| snapshot.get(name, &source))
+    {
+        cfg.classify(FileClass::Source);
+        act_on_snapshot(results.clone(), code_hash(&source), path, cfg);
+        return Ok(());
+    }
 
     let language = if let Some(language) = cfg.language {
         language
-    } else if let Some(language) = guess_language(&source, &path).0 {
+    } else if let Some(language) = guess_language_with_dialects(&source, &path, &cfg.dialects).0 {
         language
+    } else if cfg.metrics && (is_component(&path) || cfg.embedded && is_embedding_host(&path)) {
+        cfg.classify(FileClass::Source);
+        return act_on_embedded(source, path, read, cfg);
     } else {
+        cfg.classify(FileClass::Unsupported);
         return Ok(());
     };
+    cfg.classify(FileClass::Source);
 
     let pr = cfg.preproc.clone();
     if cfg.dump {
@@ -89,17 +560,14 @@ fn act_on_file(path: PathBuf, cfg: &Config) -> std::io::Result<()> {
             line_end: cfg.line_end,
         };
         action::<Dump>(&language, source, &path, pr, cfg)
+    } else if cfg.sexp {
+        let cfg = SexpCfg {
+            path: path.clone(),
+            byte_ranges: cfg.byte_ranges,
+        };
+        action::<Sexp>(&language, source, &path, pr, cfg)
     } else if cfg.metrics {
-        if let Some(output_format) = &cfg.output_format {
-            if let Some(space) = get_function_spaces(&language, source, &path, pr) {
-                output_format.dump_formats(space, path, cfg.output.as_ref(), cfg.pretty);
-            }
-            Ok(())
-        } else {
-            let cfg = MetricsCfg { path };
-            let path = cfg.path.clone();
-            action::<Metrics>(&language, source, &path, pr, cfg)
-        }
+        act_on_metrics(language, source, path, read, cfg)
     } else if cfg.ops {
         if let Some(output_format) = &cfg.output_format {
             let ops = get_ops(&language, source, &path, pr).unwrap();
@@ -110,6 +578,25 @@ fn act_on_file(path: PathBuf, cfg: &Config) -> std::io::Result<()> {
             let path = cfg.path.clone();
             action::<OpsCode>(&language, source, &path, pr, cfg)
         }
+    } else if cfg.api {
+        if let Some(output_format) = &cfg.output_format {
+            let api = get_public_api(&language, source, &path, pr);
+            output_format.dump_formats(api, path, cfg.output.as_ref(), cfg.pretty);
+            Ok(())
+        } else {
+            let cfg = PublicApiCfg { path: path.clone() };
+            action::<PublicApi>(&language, source, &path, pr, cfg)
+        }
+    } else if let Some(references) = &cfg.references_lock {
+        let file = get_function_references(&language, source, &path, pr);
+        references.lock().unwrap().push(file);
+        Ok(())
+    } else if let Some(files) = &cfg.header_pairs_lock {
+        if language == LANG::Cpp {
+            let parser = CppParser::new(source, &path, pr);
+            files.lock().unwrap().push(cpp_file(&parser, &path));
+        }
+        Ok(())
     } else if cfg.comments {
         let cfg = CommentRmCfg {
             in_place: cfg.in_place,
@@ -132,17 +619,24 @@ fn act_on_file(path: PathBuf, cfg: &Config) -> std::io::Result<()> {
             line_end: cfg.line_end,
         };
         action::<Find>(&language, source, &path, pr, cfg)
-    } else if cfg.count_lock.is_some() {
+    } else if let Some(pattern) = &cfg.search {
+        let cfg = GrepCfg {
+            path: path.clone(),
+            pattern: pattern.clone(),
+            context: cfg.search_context,
+        };
+        action::<Grep>(&language, source, &path, pr, cfg)
+    } else if let Some(stats) = &cfg.count_lock {
         let cfg = CountCfg {
             filters: cfg.count_filter.clone(),
-            stats: cfg.count_lock.as_ref().unwrap().clone(),
+            stats: stats.clone(),
         };
         action::<Count>(&language, source, &path, pr, cfg)
-    } else if cfg.preproc_lock.is_some() {
+    } else if let Some(preproc_lock) = &cfg.preproc_lock {
         if let Some(language) = guess_language(&source, &path).0
             && language == LANG::Cpp
         {
-            let mut results = cfg.preproc_lock.as_ref().unwrap().lock().unwrap();
+            let mut results = preproc_lock.lock().unwrap();
             preprocess(
                 &PreprocParser::new(source, &path, None),
                 &path,
@@ -169,6 +663,17 @@ fn process_dir_path(all_files: &mut HashMap<String, Vec<PathBuf>>, path: &Path,
     }
 }
 
+fn parse_date(s: &str) -> Result<i64, String> {
+    date_timestamp(s).ok_or_else(|| "expected a date as YYYY-MM-DD".to_string())
+}
+
+fn parse_positive(s: &str) -> Result<f64, String> {
+    match s.parse::<f64>() {
+        Ok(value) if value > 0. && value.is_finite() => Ok(value),
+        _ => Err("expected a positive number".to_string()),
+    }
+}
+
 #[derive(Parser, Debug)]
 #[clap(
     name = "rust-code-analysis-cli",
@@ -177,18 +682,51 @@ fn process_dir_path(all_files: &mut HashMap<String, Vec<PathBuf>>, path: &Path,
     about = "Analyze source code."
 )]
 struct Opts {
-    /// Input files to analyze.
+    /// Input files to analyze, zip and tar archives included.
     #[clap(long, short, value_parser)]
     paths: Vec<PathBuf>,
+    /// Read the code to analyze from stdin.
+    #[clap(long, conflicts_with = "paths")]
+    stdin: bool,
+    /// Shallow clone a remote git repository and analyze it.
+    #[clap(long, conflicts_with_all = ["paths", "stdin"])]
+    git: Option<String>,
+    /// Analyze the files staged in the git repository of the current
+    /// directory, reading their contents from the index.
+    #[clap(long, conflicts_with_all = ["paths", "stdin", "git"])]
+    staged: bool,
+    /// Branch, tag or commit of the remote git repository.
+    #[clap(long, requires = "git", default_value = "HEAD")]
+    git_ref: String,
     /// Output AST to stdout.
     #[clap(long, short)]
     dump: bool,
+    /// Output the AST of each file to stdout as a tree-sitter s-expression,
+    /// preceded by a comment with the path of the file.
+    #[clap(long)]
+    sexp: bool,
+    /// Follow each node of the s-expressions of `--sexp` by its byte range.
+    #[clap(long, requires = "sexp")]
+    byte_ranges: bool,
     /// Remove comments in the specified files.
     #[clap(long, short)]
     comments: bool,
     /// Find nodes of the given type.
     #[clap(long, short, number_of_values = 1)]
     find: Vec<String>,
+    /// Search the nodes matching a structural pattern: a node kind,
+    /// optionally followed by constraints on its children and fields
+    /// in the syntax of the tree-sitter queries.
+    #[clap(long, value_name = "PATTERN", conflicts_with = "search_query")]
+    search: Option<String>,
+    /// Search the nodes matching the tree-sitter query in this file,
+    /// the ones captured as `@match` or all the captured ones.
+    #[clap(long, value_name = "FILE")]
+    search_query: Option<PathBuf>,
+    /// The number of lines printed before and after each node
+    /// found by `--search` or `--search-query`.
+    #[clap(long, value_name = "LINES", default_value_t = 0)]
+    search_context: usize,
     /// Get functions and their spans.
     #[clap(long, short = 'F')]
     function: bool,
@@ -198,9 +736,104 @@ struct Opts {
     /// Compute different metrics.
     #[clap(long, short)]
     metrics: bool,
+    /// Rules used to count the decision points of the cyclomatic complexity.
+    #[clap(long, requires = "metrics", value_name = "PRESET", value_parser = PossibleValuesParser::new(CyclomaticOptions::PRESETS)
+        .map(|s| CyclomaticOptions::preset(&s).unwrap()))]
+    cyclomatic: Option<CyclomaticOptions>,
+    /// Rules used to compute the cognitive complexity.
+    #[clap(long, requires = "metrics", value_name = "MODE", value_parser = PossibleValuesParser::new(CognitiveMode::MODES)
+        .map(|s| CognitiveMode::from_name(&s).unwrap()))]
+    cognitive: Option<CognitiveMode>,
+    /// Rules used to analyze the Python comprehensions and lambdas.
+    #[clap(long, requires = "metrics", value_name = "MODE", value_parser = PossibleValuesParser::new(ComprehensionMode::MODES)
+        .map(|s| ComprehensionMode::from_name(&s).unwrap()))]
+    comprehensions: Option<ComprehensionMode>,
+    /// Rules of the lines of code metrics to exclude: comma separated list.
+    #[clap(long, requires = "metrics", value_name = "RULE", value_delimiter = ',', value_parser = PossibleValuesParser::new(LocOptions::RULES))]
+    loc_exclude: Vec<String>,
+    /// Count the Python docstrings as comment lines only,
+    /// and not as logical lines too.
+    #[clap(long, requires = "metrics")]
+    docstrings_as_comments: bool,
+    /// Stroud number dividing the Halstead effort to estimate the time,
+    /// 18 by default.
+    #[clap(long, requires = "metrics", value_name = "NUMBER", value_parser = parse_positive)]
+    halstead_stroud: Option<f64>,
+    /// Divisor of the Halstead effort raised to 2/3 estimating the bugs,
+    /// 3000 by default.
+    #[clap(long, requires = "metrics", value_name = "NUMBER", value_parser = parse_positive)]
+    halstead_bugs_divisor: Option<f64>,
+    /// Count the macro invocations and analyze the macro definitions as spaces.
+    #[clap(long, requires = "metrics")]
+    macros: bool,
+    /// Analyze the closures and the lambdas spanning at least
+    /// this number of lines as their own spaces.
+    #[clap(long, requires = "metrics", value_name = "LINES")]
+    closure_spaces: Option<usize>,
+    /// Strategy naming the anonymous functions and closures.
+    #[clap(long, requires = "metrics", value_name = "STRATEGY", value_parser = PossibleValuesParser::new(AnonymousNaming::NAMES)
+        .map(|s| AnonymousNaming::from_name(&s).unwrap()))]
+    anonymous_names: Option<AnonymousNaming>,
+    /// Analyze the nodes of a grammar node kind as spaces of a kind,
+    /// or not as spaces with `none`, such as `rust:mod_item=namespace`.
+    #[clap(
+        long,
+        requires = "metrics",
+        number_of_values = 1,
+        value_name = "LANGUAGE:NODE=KIND"
+    )]
+    space_kind: Vec<String>,
+    /// Highest cyclomatic complexity of each grade, from A to E:
+    /// comma separated list.
+    #[clap(
+        long,
+        requires = "metrics",
+        value_name = "BOUNDS",
+        value_delimiter = ','
+    )]
+    cyclomatic_grades: Option<Vec<f64>>,
+    /// Lowest maintainability index of each grade, from A onwards:
+    /// comma separated list.
+    #[clap(
+        long,
+        requires = "metrics",
+        value_name = "BOUNDS",
+        value_delimiter = ','
+    )]
+    mi_grades: Option<Vec<f64>>,
+    /// Only output the spaces graded GRADE or worse,
+    /// and the spaces containing them.
+    #[clap(long, requires = "metrics", value_name = "GRADE", value_parser = PossibleValuesParser::new(Grade::NAMES)
+        .map(|s| Grade::from_name(&s).unwrap()))]
+    min_grade: Option<Grade>,
+    /// The cyclomatic complexity above which the lizard-warnings
+    /// format reports a function.
+    #[clap(long, requires = "metrics", value_name = "CCN", default_value_t = LizardThresholds::default().ccn)]
+    lizard_ccn: usize,
+    /// The number of lines above which the lizard-warnings
+    /// format reports a function.
+    #[clap(long, requires = "metrics", value_name = "LINES", default_value_t = LizardThresholds::default().length)]
+    lizard_length: usize,
+    /// The number of parameters above which the lizard-warnings
+    /// format reports a function.
+    #[clap(long, requires = "metrics", value_name = "PARAMS", default_value_t = LizardThresholds::default().parameter_count)]
+    lizard_arguments: usize,
     /// Retrieve all operands and operators in a code.
     #[clap(long, conflicts_with = "metrics")]
     ops: bool,
+    /// List the public symbols of the files, such as the `pub` items
+    /// in Rust and the exports in JavaScript.
+    #[clap(long, conflicts_with_all = ["metrics", "ops"])]
+    api: bool,
+    /// Print the functions never referenced in the analyzed files,
+    /// which are candidates for dead code, as a table or as JSON.
+    #[clap(long, value_name = "FORMAT", conflicts_with_all = ["metrics", "ops", "api"], value_parser = PossibleValuesParser::new(["table", "json"]))]
+    unreferenced: Option<String>,
+    /// Pair the C/C++ headers with their implementation files, and print
+    /// the mismatches between their declarations and their definitions
+    /// with the combined metrics of their classes, as a table or as JSON.
+    #[clap(long, value_name = "FORMAT", conflicts_with_all = ["metrics", "ops", "api", "unreferenced"], value_parser = PossibleValuesParser::new(["table", "json"]))]
+    header_pairs: Option<String>,
     /// Do action in place.
     #[clap(long, short)]
     in_place: bool,
@@ -213,9 +846,24 @@ struct Opts {
     /// Number of jobs.
     #[clap(long, short = 'j')]
     num_jobs: Option<usize>,
+    /// Order in which the files are analyzed: the largest ones first,
+    /// or in the order in which they are found.
+    #[clap(long, default_value = "largest-first", value_parser = PossibleValuesParser::new(["largest-first", "discovery"])
+        .map(|s| if s == "discovery" { Schedule::Discovery } else { Schedule::LargestFirst }))]
+    schedule: Schedule,
+    /// Handling of the symbolic links found in the directories:
+    /// follow them, skip them, or stop with an error.
+    #[clap(long, default_value = "follow", value_parser = PossibleValuesParser::new(SymlinkPolicy::NAMES)
+        .map(|s| SymlinkPolicy::from_name(&s).unwrap()))]
+    symlinks: SymlinkPolicy,
     /// Language type.
-    #[clap(long, short)]
+    #[clap(long, short, alias = "language")]
     language_type: Option<String>,
+    /// Parse the files of a language as one of its dialects,
+    /// such as `c` for the `.h` headers or `tsx` for the `.ts` files.
+    #[clap(long, value_name = "DIALECT", value_delimiter = ',', value_parser = |s: &str| Dialect::from_name(s)
+        .ok_or_else(|| format!("expected one of {}", Dialect::NAMES.join(", "))))]
+    dialect: Vec<Dialect>,
     /// Output metrics as different formats.
     #[clap(long, short = 'O', value_parser = PossibleValuesParser::new(Format::all())
         .map(|s| s.parse::<Format>().unwrap()))]
@@ -238,17 +886,317 @@ struct Opts {
     /// Print the warnings.
     #[clap(long, short)]
     warning: bool,
+    /// Analyze minified, generated and lock files too.
+    #[clap(long)]
+    include_generated: bool,
+    /// Analyze the scripts embedded in the HTML pages and in the templates.
+    #[clap(long, requires = "metrics")]
+    embedded: bool,
+    /// Output the files in the order of their paths, regardless of the order
+    /// in which their analyses complete, so that the outputs of two runs
+    /// on the same files are identical.
+    #[clap(long, requires = "metrics")]
+    deterministic: bool,
+    /// Print the number of source, binary, unsupported and ignored
+    /// files encountered, as a table or as JSON.
+    #[clap(long, value_name = "FORMAT", value_parser = PossibleValuesParser::new(["table", "json"]))]
+    classification: Option<String>,
+    /// Analyze only the unit of the files whose analysis is estimated
+    /// to need more than this amount of memory, in MiB, and skip the
+    /// files which do not fit in it even so.
+    #[clap(long, value_name = "MIB")]
+    max_memory: Option<u64>,
+    /// Print the number of files, blank, comment and code lines
+    /// of each language as cloc does, as a table or as JSON.
+    #[clap(long, requires = "metrics", value_name = "FORMAT", value_parser = PossibleValuesParser::new(["table", "json"]))]
+    summary: Option<String>,
+    /// Print the number of files, the source lines, the number of functions
+    /// and their mean complexities of each language, as a table or as JSON.
+    #[clap(long, requires = "metrics", value_name = "FORMAT", value_parser = PossibleValuesParser::new(["table", "json"]))]
+    language_summary: Option<String>,
+    /// Glob of the test files, reported apart in the language summary,
+    /// replacing the default ones such as `**/tests/**` or `**/*.spec.*`.
+    #[clap(
+        long,
+        requires = "language_summary",
+        value_name = "GLOB",
+        number_of_values = 1
+    )]
+    test_pattern: Vec<String>,
+    /// Add to the summary a COCOMO estimate of the effort, the schedule
+    /// and the cost of the development of the analyzed code.
+    #[clap(long, requires = "summary")]
+    cocomo: bool,
+    /// The kind of project estimated by COCOMO.
+    #[clap(long, requires = "cocomo", default_value = "organic", value_parser = PossibleValuesParser::new(CocomoProject::NAMES)
+        .map(|s| CocomoProject::from_name(&s).unwrap()))]
+    cocomo_project: CocomoProject,
+    /// The COCOMO model used for the estimate.
+    #[clap(long, requires = "cocomo", default_value = "basic", value_parser = PossibleValuesParser::new(CocomoModel::NAMES)
+        .map(|s| CocomoModel::from_name(&s).unwrap()))]
+    cocomo_model: CocomoModel,
+    /// The effort adjustment factor of the intermediate COCOMO model.
+    #[clap(long, requires = "cocomo", default_value_t = CocomoOptions::default().eaf)]
+    cocomo_eaf: f64,
+    /// The average yearly wage of a developer used to estimate the cost.
+    #[clap(long, requires = "cocomo", value_name = "WAGE", default_value_t = CocomoOptions::default().average_wage)]
+    cocomo_wage: f64,
+    /// The factor multiplying the wages to estimate the cost
+    /// of the whole organization.
+    #[clap(long, requires = "cocomo", default_value_t = CocomoOptions::default().overhead)]
+    cocomo_overhead: f64,
+    /// A JSON file containing the rules checked on the functions,
+    /// used instead of the default ones.
+    #[clap(long, requires = "metrics", value_name = "FILE")]
+    rules: Option<PathBuf>,
+    /// The test cases of the junit format: a test case for each file
+    /// or for each rule, failing when it has some violations.
+    #[clap(long, requires = "metrics", default_value = "file", value_parser = PossibleValuesParser::new(JUnitCases::NAMES)
+        .map(|s| JUnitCases::from_name(&s).unwrap()))]
+    junit_cases: JUnitCases,
+    /// Print the technical debt of each file and of the project,
+    /// estimated as SonarQube does, as a table or as JSON.
+    #[clap(long, requires = "metrics", value_name = "FORMAT", value_parser = PossibleValuesParser::new(["table", "json"]))]
+    debt: Option<String>,
+    /// A unified diff, such as the output of `git diff`, whose changed
+    /// functions are commented with the changes of their metrics.
+    #[clap(long, requires = "metrics", value_name = "DIFF", conflicts_with_all = ["paths", "stdin", "git", "staged"])]
+    review: Option<PathBuf>,
+    /// The API of the review comments.
+    #[clap(long, requires = "review", default_value = "github", value_parser = PossibleValuesParser::new(["github", "gitlab"]))]
+    review_format: String,
+    /// Write in this directory the `complexity.svg` and `mi.svg` badges
+    /// with the grade of the average cyclomatic complexity and
+    /// the maintainability index of the analyzed files.
+    #[clap(long, requires = "metrics", value_name = "DIR")]
+    badges: Option<PathBuf>,
+    /// Print the N worst functions of all the analyzed files,
+    /// ranked by the metric of `--sort-by`.
+    #[clap(long, requires = "metrics", value_name = "N")]
+    top: Option<usize>,
+    /// The metric ranking the functions of `--top`, optionally followed
+    /// by one of its fields, such as `cognitive` or `loc.sloc`.
+    #[clap(long, requires = "top", value_name = "METRIC", default_value = "cognitive", value_parser = |s: &str| TopFunctions::new(s, 0)
+        .map(|_| s.to_string())
+        .ok_or_else(|| "expected a metric such as cognitive, optionally followed by one of its fields such as loc.sloc".to_string()))]
+    sort_by: String,
+    /// The format of the worst functions of `--top`.
+    #[clap(long, requires = "top", default_value = "table", value_parser = PossibleValuesParser::new(["table", "json", "markdown"]))]
+    top_format: String,
+    /// Print the functions whose CRAP score, combining their cyclomatic
+    /// complexity with their coverage read from this `lcov` or Cobertura
+    /// report, is above `--crap-threshold`: the complex functions not
+    /// tested enough.
+    #[clap(long, requires = "metrics", value_name = "FILE")]
+    coverage: Option<PathBuf>,
+    /// The CRAP score above which a function is printed by `--coverage`.
+    #[clap(long, requires = "coverage", value_name = "SCORE", default_value_t = DEFAULT_CRAP_THRESHOLD)]
+    crap_threshold: f64,
+    /// The format of the functions printed by `--coverage`.
+    #[clap(long, requires = "coverage", default_value = "table", value_parser = PossibleValuesParser::new(["table", "json"]))]
+    crap_format: String,
+    /// Report the functions whose value of a metric is a statistical
+    /// outlier among all the analyzed functions, such as `cognitive`
+    /// or `loc.sloc`.
+    #[clap(long, requires = "metrics", value_name = "METRIC", value_parser = |s: &str| Outliers::new(s, OutlierMethod::Iqr)
+        .map(|_| s.to_string())
+        .ok_or_else(|| "expected a metric such as cognitive, optionally followed by one of its fields such as loc.sloc".to_string()))]
+    outliers: Option<String>,
+    /// The method detecting the outliers of `--outliers`.
+    #[clap(long, requires = "outliers", default_value = "iqr", value_parser = PossibleValuesParser::new(OutlierMethod::NAMES)
+        .map(|s| OutlierMethod::from_name(&s).unwrap()))]
+    outlier_method: OutlierMethod,
+    /// The factor of the method of `--outliers`: the interquartile ranges
+    /// beyond the quartiles (1.5 by default) or the standard deviations
+    /// from the mean (3 by default).
+    #[clap(long, requires = "outliers", value_name = "FACTOR")]
+    outlier_factor: Option<f64>,
+    /// Reuse the results of the files whose code has not changed
+    /// from a snapshot written by `--export-snapshot` in a previous run.
+    /// Requires a serialization output format, such as json.
+    #[clap(long, requires = "metrics", value_name = "FILE", conflicts_with_all = ["summary", "language_summary", "debt", "badges", "top", "coverage", "outliers", "profile", "review", "tui"])]
+    import_snapshot: Option<PathBuf>,
+    /// Write the results and the hashes of the analyzed files
+    /// in a snapshot, to be imported by `--import-snapshot`.
+    #[clap(long, requires = "metrics", value_name = "FILE")]
+    export_snapshot: Option<PathBuf>,
+    /// Record the grades of the functions of this run in a SQLite
+    /// database, keeping the ones of the previous runs.
+    #[clap(
+        long,
+        requires = "metrics",
+        value_name = "DB",
+        conflicts_with = "import_snapshot"
+    )]
+    grade_history: Option<PathBuf>,
+    /// Print the functions whose grade dropped since a date, YYYY-MM-DD,
+    /// comparing this run with the last one recorded by `--grade-history`
+    /// at or before that date.
+    #[clap(long, requires = "grade_history", value_name = "DATE", value_parser = parse_date)]
+    grade_drops_since: Option<i64>,
+    /// Print the time spent reading, parsing, computing the metrics
+    /// and serializing each file, and a summary of the slowest files.
+    #[clap(long, requires = "metrics")]
+    profile: bool,
+    /// Browse the analyzed files in the terminal, sorting their functions
+    /// by any metric and inspecting the metrics of each of them.
+    #[clap(long, requires = "metrics", conflicts_with_all = ["output_format", "output", "summary", "language_summary", "debt", "badges", "profile", "review", "top", "coverage"])]
+    tui: bool,
+    /// Print the diagnostic messages up to this level on stderr.
+    /// RUST_LOG is used when not set.
+    #[clap(long, value_parser = PossibleValuesParser::new(["error", "warn", "info", "debug", "trace"]))]
+    log_level: Option<String>,
 }
 
 fn main() {
     let opts = Opts::parse();
 
+    init_logging(opts.log_level.as_deref());
+
     let count_lock = if !opts.count.is_empty() {
         Some(Arc::new(Mutex::new(Count::default())))
     } else {
         None
     };
 
+    let profile_lock = if opts.profile {
+        Some(Arc::new(Mutex::new(Profile::default())))
+    } else {
+        None
+    };
+
+    let classification_lock = opts
+        .classification
+        .is_some()
+        .then(|| Arc::new(Mutex::new(FileClassification::default())));
+
+    let summary_lock = if opts.summary.is_some() {
+        Some(Arc::new(Mutex::new(Summary::default())))
+    } else {
+        None
+    };
+
+    let language_summary_lock = if opts.language_summary.is_some() {
+        let test_patterns = if opts.test_pattern.is_empty() {
+            TestPatterns::default()
+        } else {
+            TestPatterns::new(&opts.test_pattern).unwrap_or_else(|e| {
+                eprintln!("Error: Invalid test pattern: {e}");
+                process::exit(1);
+            })
+        };
+        Some(Arc::new(Mutex::new(MetricsSummary::new(test_patterns))))
+    } else {
+        None
+    };
+
+    let debt_lock = if opts.debt.is_some() {
+        Some(Arc::new(Mutex::new(BTreeMap::new())))
+    } else {
+        None
+    };
+
+    let badges_lock = if opts.badges.is_some() {
+        Some(Arc::new(Mutex::new(Badges::default())))
+    } else {
+        None
+    };
+
+    let top_lock = opts
+        .top
+        .map(|limit| Arc::new(Mutex::new(TopFunctions::new(&opts.sort_by, limit).unwrap())));
+
+    let search = if let Some(pattern) = &opts.search {
+        Some(Pattern::new(pattern))
+    } else {
+        opts.search_query.as_ref().map(|path| {
+            let query = read_file(path).unwrap_or_else(|e| {
+                eprintln!("Error: Cannot read the query {}: {e}", path.display());
+                process::exit(1);
+            });
+            Pattern::from_query(&String::from_utf8_lossy(&query))
+        })
+    };
+    let search = search.map(|pattern| {
+        Arc::new(pattern.unwrap_or_else(|e| {
+            eprintln!("Error: {e}");
+            process::exit(1);
+        }))
+    });
+
+    let crap_lock = opts.coverage.as_ref().map(|path| {
+        let coverage = read_file(path).unwrap_or_else(|e| {
+            eprintln!(
+                "Error: Cannot read the coverage report {}: {e}",
+                path.display()
+            );
+            process::exit(1);
+        });
+        let coverage = Coverage::parse(&String::from_utf8_lossy(&coverage));
+        Arc::new(Mutex::new(CrapReport::new(coverage, opts.crap_threshold)))
+    });
+
+    let outliers_lock = opts.outliers.as_ref().map(|metric| {
+        let outliers = Outliers::new(metric, opts.outlier_method).unwrap();
+        let factor = opts.outlier_factor.unwrap_or(outliers.factor);
+        Arc::new(Mutex::new(outliers.with_factor(factor)))
+    });
+
+    let references_lock = if opts.unreferenced.is_some() {
+        Some(Arc::new(Mutex::new(Vec::new())))
+    } else {
+        None
+    };
+
+    let header_pairs_lock = if opts.header_pairs.is_some() {
+        Some(Arc::new(Mutex::new(Vec::new())))
+    } else {
+        None
+    };
+
+    let rules = match &opts.rules {
+        Some(path) => read_file(path)
+            .map_err(|e| e.to_string())
+            .and_then(|data| serde_json::from_slice(&data).map_err(|e| e.to_string()))
+            .unwrap_or_else(|e| {
+                eprintln!("Error: Cannot read the rules in {}: {e}", path.display());
+                process::exit(1);
+            }),
+        None => Rule::defaults(),
+    };
+
+    let spaces_lock = if opts.tui
+        || opts
+            .output_format
+            .as_ref()
+            .is_some_and(Format::is_collected)
+    {
+        Some(Arc::new(Mutex::new(Vec::new())))
+    } else {
+        None
+    };
+
+    // The database is opened before the analysis, which is not lost
+    // when the database cannot be written
+    let grade_history = opts.grade_history.as_ref().map(|path| {
+        GradeHistory::open(path).unwrap_or_else(|e| {
+            eprintln!(
+                "Error: Cannot open the grade history {}: {e}",
+                path.display()
+            );
+            process::exit(1);
+        })
+    });
+    let grades_lock = grade_history
+        .as_ref()
+        .map(|_| Arc::new(Mutex::new(Vec::new())));
+
+    let findings_lock = if opts.output_format.as_ref().is_some_and(Format::is_report) {
+        Some(Arc::new(Mutex::new(BTreeMap::new())))
+    } else {
+        None
+    };
+
     let (preproc_lock, preproc) = match opts.preproc.len().cmp(&1) {
         Ordering::Equal => {
             let data = read_file(&opts.preproc[0]).unwrap();
@@ -266,12 +1214,96 @@ fn main() {
         Ordering::Less => (None, None),
     };
 
+    let is_parquet = matches!(opts.output_format, Some(Format::Parquet));
+    if is_parquet && !(opts.metrics && opts.output.is_some()) {
+        eprintln!("Error: The parquet format requires the metrics and an output file");
+        process::exit(1);
+    }
+
+    let is_radon = matches!(opts.output_format, Some(Format::RadonCc | Format::RadonMi));
+    if is_radon && !opts.metrics {
+        eprintln!("Error: The radon formats require the metrics");
+        process::exit(1);
+    }
+
+    let is_report = opts.output_format.as_ref().is_some_and(Format::is_report);
+    if is_report && !opts.metrics {
+        eprintln!("Error: The report formats require the metrics");
+        process::exit(1);
+    }
+
     let output_is_dir = opts.output.as_ref().map(|p| p.is_dir()).unwrap_or(false);
-    if (opts.metrics || opts.ops) && opts.output.is_some() && !output_is_dir {
+    if (opts.metrics || opts.ops || opts.api)
+        && opts.output.is_some()
+        && !output_is_dir
+        && !is_parquet
+        && !is_radon
+        && !is_report
+    {
         eprintln!("Error: The output parameter must be a directory");
         process::exit(1);
     }
 
+    if matches!(opts.output_format, Some(Format::Ndjson)) && opts.output.is_some() {
+        eprintln!("Error: The ndjson format can only be printed on stdout");
+        process::exit(1);
+    }
+
+    if matches!(
+        opts.output_format,
+        Some(Format::LizardCsv | Format::LizardWarnings)
+    ) && !(opts.metrics && opts.output.is_none())
+    {
+        eprintln!(
+            "Error: The lizard formats require the metrics and can only be printed on stdout"
+        );
+        process::exit(1);
+    }
+
+    if opts.import_snapshot.is_some()
+        && !opts
+            .output_format
+            .as_ref()
+            .is_some_and(Format::is_serialized)
+    {
+        eprintln!(
+            "Error: The imported snapshot requires the cbor, json, ndjson, toml or yaml format"
+        );
+        process::exit(1);
+    }
+
+    if matches!(opts.output_format, Some(Format::Codeclimate))
+        && !(opts.metrics && opts.output.is_none())
+    {
+        eprintln!(
+            "Error: The codeclimate format requires the metrics and can only be printed on stdout"
+        );
+        process::exit(1);
+    }
+
+    let default_grades = GradeOptions::default();
+    let grades = if opts.cyclomatic_grades.is_some() || opts.mi_grades.is_some() {
+        GradeOptions::new(
+            opts.cyclomatic_grades
+                .unwrap_or_else(|| default_grades.cyclomatic().to_vec()),
+            opts.mi_grades
+                .unwrap_or_else(|| default_grades.mi().to_vec()),
+        )
+        .unwrap_or_else(|| {
+            eprintln!(
+                "Error: At most 5 grade boundaries are allowed, in increasing order for the cyclomatic complexity and in decreasing order for the maintainability index"
+            );
+            process::exit(1);
+        })
+    } else {
+        default_grades
+    };
+
+    let space_kinds = SpaceKindMap::from_entries(&opts.space_kind).unwrap_or_else(|e| {
+        eprintln!("Error: Invalid space kind: {e}");
+        process::exit(1);
+    });
+
     let typ = opts.language_type.unwrap_or_default();
     let language = if preproc_lock.is_some() {
         Some(LANG::Preproc)
@@ -282,7 +1314,7 @@ fn main() {
     } else if typ == "preproc" {
         Some(LANG::Preproc)
     } else {
-        get_from_ext(&typ)
+        get_from_ext(&typ).or_else(|| get_from_emacs_mode(&typ))
     };
 
     let num_jobs = opts
@@ -300,17 +1332,69 @@ fn main() {
     let include = mk_globset(opts.include);
     let exclude = mk_globset(opts.exclude);
 
-    let cfg = Config {
+    // The clone is removed when dropped at the end of the analysis
+    let clone = opts.git.as_ref().map(|url| {
+        eprintln!("Cloning {url} at {}", opts.git_ref);
+        remote::shallow_clone(url, &opts.git_ref).unwrap_or_else(|e| {
+            eprintln!("Error: Cannot clone {url}: {e}");
+            process::exit(1);
+        })
+    });
+    let root = clone.as_ref().map(|dir| dir.path().to_path_buf());
+    let paths = root.iter().cloned().chain(opts.paths).collect();
+
+    let mut cfg = Config {
         dump: opts.dump,
+        sexp: opts.sexp,
+        byte_ranges: opts.byte_ranges,
         in_place: opts.in_place,
         comments: opts.comments,
         find_filter: opts.find,
         count_filter: opts.count,
+        search,
+        search_context: opts.search_context,
         language,
+        dialects: opts.dialect,
         function: opts.function,
         metrics: opts.metrics,
+        metrics_options: MetricsOptions {
+            cyclomatic: opts.cyclomatic.unwrap_or_default(),
+            cognitive: opts.cognitive.unwrap_or_default(),
+            comprehensions: opts.comprehensions.unwrap_or_default(),
+            loc: LocOptions {
+                docstrings_as_comments: opts.docstrings_as_comments,
+                ..opts
+                    .loc_exclude
+                    .iter()
+                    .fold(LocOptions::default(), |options, rule| {
+                        options.without(rule).unwrap()
+                    })
+            },
+            halstead: HalsteadOptions {
+                stroud: opts
+                    .halstead_stroud
+                    .unwrap_or(HalsteadOptions::default().stroud),
+                bugs_divisor: opts
+                    .halstead_bugs_divisor
+                    .unwrap_or(HalsteadOptions::default().bugs_divisor),
+            },
+            macros: opts.macros,
+            closure_spaces: opts.closure_spaces,
+            anonymous: opts.anonymous_names.unwrap_or_default(),
+            grades: grades.clone(),
+            space_kinds,
+            unit_only: false,
+        },
+        min_grade: opts.min_grade,
+        lizard: LizardThresholds {
+            ccn: opts.lizard_ccn,
+            length: opts.lizard_length,
+            parameter_count: opts.lizard_arguments,
+            ..Default::default()
+        },
         ops: opts.ops,
-        output_format: opts.output_format,
+        api: opts.api,
+        output_format: opts.output_format.clone(),
         pretty: opts.pretty,
         output: opts.output.clone(),
         line_start: opts.line_start,
@@ -318,30 +1402,423 @@ fn main() {
         preproc_lock: preproc_lock.clone(),
         preproc,
         count_lock: count_lock.clone(),
+        spaces_lock: spaces_lock.clone(),
+        classification_lock: classification_lock.clone(),
+        deferred_lock: opts.deterministic.then(|| Arc::new(Mutex::new(Vec::new()))),
+        summary_lock: summary_lock.clone(),
+        language_summary_lock: language_summary_lock.clone(),
+        rules: rules.clone(),
+        debt_lock: debt_lock.clone(),
+        badges_lock: badges_lock.clone(),
+        top_lock: top_lock.clone(),
+        crap_lock: crap_lock.clone(),
+        outliers_lock: outliers_lock.clone(),
+        snapshot: None,
+        snapshot_lock: None,
+        references_lock: references_lock.clone(),
+        header_pairs_lock: header_pairs_lock.clone(),
+        findings_lock: findings_lock.clone(),
+        grades_lock: grades_lock.clone(),
+        profile_lock: profile_lock.clone(),
+        include_generated: opts.include_generated,
+        embedded: opts.embedded,
+        max_memory: opts.max_memory,
+        root,
     };
 
-    let files_data = FilesData {
-        include,
-        exclude,
-        paths: opts.paths,
-    };
+    // The results of a snapshot are reused only with the same options
+    let snapshot_options = format!(
+        "{:?} {:?} {:?} {:?}",
+        cfg.language, cfg.dialects, cfg.min_grade, cfg.metrics_options
+    );
+    cfg.snapshot = opts.import_snapshot.as_ref().and_then(|path| {
+        let snapshot = File::open(path)
+            .and_then(|file| Snapshot::read(BufReader::new(file)))
+            .unwrap_or_else(|e| {
+                eprintln!("Error: Cannot read the snapshot {}: {e}", path.display());
+                process::exit(1);
+            });
+        if snapshot.is_compatible(&snapshot_options) {
+            Some(Arc::new(snapshot))
+        } else {
+            eprintln!(
+                "Warning: The snapshot {} has been computed with other options, analyzing all the files",
+                path.display()
+            );
+            None
+        }
+    });
+    let snapshot_lock = opts
+        .export_snapshot
+        .as_ref()
+        .map(|_| Arc::new(Mutex::new(Snapshot::new(snapshot_options))));
+    cfg.snapshot_lock = snapshot_lock.clone();
 
-    let all_files = match ConcurrentRunner::new(num_jobs, act_on_file)
-        .set_proc_dir_paths(process_dir_path)
-        .run(cfg, files_data)
-    {
-        Ok(all_files) => all_files,
-        Err(e) => {
+    let all_files = if opts.stdin {
+        let start = Instant::now();
+        let mut source = Vec::new();
+        if let Err(e) = std::io::stdin().read_to_end(&mut source) {
+            eprintln!("Error: Cannot read from stdin: {e}");
+            process::exit(1);
+        }
+        if !source.ends_with(b"\n") {
+            source.push(b'\n');
+        }
+
+        let path = PathBuf::from("<stdin>");
+        if cfg.language.is_none()
+            && guess_language_with_dialects(&source, &path, &cfg.dialects)
+                .0
+                .is_none()
+        {
+            eprintln!("Error: Cannot detect the language of stdin, use --language");
+            process::exit(1);
+        }
+        if let Err(e) = act_on_source(source, path, start.elapsed(), &cfg) {
             eprintln!("{e:?}");
             process::exit(1);
         }
+        HashMap::new()
+    } else if let Some(diff) = &opts.review {
+        let comments = review(diff, &cfg).unwrap_or_else(|e| {
+            eprintln!("Error: Cannot review {}: {e}", diff.display());
+            process::exit(1);
+        });
+        let comments = if opts.review_format == "gitlab" {
+            serde_json::to_string_pretty(
+                &comments
+                    .iter()
+                    .map(GitLabDiscussion::from)
+                    .collect::<Vec<_>>(),
+            )
+        } else {
+            serde_json::to_string_pretty(
+                &comments
+                    .iter()
+                    .map(GitHubReviewComment::from)
+                    .collect::<Vec<_>>(),
+            )
+        };
+        println!("{}", comments.unwrap());
+        HashMap::new()
+    } else if opts.staged {
+        let files = staged::staged_files().unwrap_or_else(|e| {
+            eprintln!("Error: Cannot read the staged files: {e}");
+            process::exit(1);
+        });
+        for (path, data) in files {
+            if (include.is_empty() || include.is_match(&path))
+                && (exclude.is_empty() || !exclude.is_match(&path))
+            {
+                if let Err(e) = act_on_archive_entry(path.clone(), data, &cfg) {
+                    eprintln!("{e:?} for file {path:?}");
+                }
+            } else {
+                cfg.classify(FileClass::Ignored);
+            }
+        }
+        HashMap::new()
+    } else {
+        let files_data = FilesData {
+            include,
+            exclude,
+            paths,
+            symlinks: opts.symlinks,
+        };
+
+        match ConcurrentRunner::new(num_jobs, act_on_file)
+            .set_proc_dir_paths(process_dir_path)
+            .set_proc_ignored_path(|_, cfg: &Config| cfg.classify(FileClass::Ignored))
+            .set_proc_archive_entry(act_on_archive_entry)
+            .set_schedule(opts.schedule)
+            .run(cfg.clone(), files_data)
+        {
+            Ok(all_files) => all_files,
+            Err(e) => {
+                eprintln!("{e:?}");
+                process::exit(1);
+            }
+        }
     };
 
+    output_deferred(&cfg);
+    // Release the locks shared with the configuration, unwrapped below
+    drop(cfg);
+
     if let Some(count) = count_lock {
         let count = Arc::try_unwrap(count).unwrap().into_inner().unwrap();
         println!("{count}");
     }
 
+    if let Some(classification) = classification_lock {
+        let classification = Arc::try_unwrap(classification)
+            .unwrap()
+            .into_inner()
+            .unwrap();
+        if opts.classification.as_deref() == Some("json") {
+            println!("{}", serde_json::to_string_pretty(&classification).unwrap());
+        } else {
+            println!("{classification}");
+        }
+    }
+
+    if let Some(profile) = profile_lock {
+        let profile = Arc::try_unwrap(profile).unwrap().into_inner().unwrap();
+        eprintln!("{profile}");
+    }
+
+    if let Some(spaces) = spaces_lock {
+        let mut spaces = Arc::try_unwrap(spaces).unwrap().into_inner().unwrap();
+        spaces.sort_by(|a, b| a.name.cmp(&b.name));
+
+        if opts.tui {
+            if let Err(e) = tui::explore(&spaces) {
+                eprintln!("Error: Cannot run the explorer: {e}");
+                process::exit(1);
+            }
+        } else if is_parquet {
+            let output_path = opts.output.as_ref().unwrap();
+            if let Err(e) = File::create(output_path)
+                .map_err(Into::into)
+                .and_then(|file| write_parquet(&spaces, file))
+            {
+                eprintln!("Error: Cannot write {}: {e}", output_path.display());
+                process::exit(1);
+            }
+        } else {
+            let output_format = opts.output_format.as_ref().unwrap();
+            if let Err(e) =
+                output_format.write_radon(&spaces, &grades, opts.output.as_ref(), opts.pretty)
+            {
+                eprintln!("Error: Cannot write the {output_format:?} output: {e}");
+                process::exit(1);
+            }
+        }
+    }
+
+    let mut outliers = outliers_lock.map(|outliers| {
+        let outliers = Arc::try_unwrap(outliers).unwrap().into_inner().unwrap();
+        outliers.findings()
+    });
+
+    if let Some(findings) = findings_lock {
+        let mut findings = Arc::try_unwrap(findings).unwrap().into_inner().unwrap();
+        // The outliers already violating a rule on the same metric are reported once
+        for outlier in outliers.take().into_iter().flatten() {
+            let file = findings.entry(outlier.path.clone()).or_default();
+            if !file
+                .iter()
+                .any(|finding| finding.fingerprint() == outlier.fingerprint())
+            {
+                file.push(outlier);
+            }
+        }
+        let output_format = opts.output_format.as_ref().unwrap();
+        if let Err(e) = output_format.write_report(
+            &findings,
+            &rules,
+            opts.junit_cases,
+            opts.output.as_ref(),
+            opts.pretty,
+        ) {
+            eprintln!("Error: Cannot write the {output_format:?} output: {e}");
+            process::exit(1);
+        }
+    }
+
+    if let Some(summary) = summary_lock {
+        let summary = Arc::try_unwrap(summary).unwrap().into_inner().unwrap();
+        let cocomo = opts.cocomo.then(|| {
+            summary.cocomo(&CocomoOptions {
+                project: opts.cocomo_project,
+                model: opts.cocomo_model,
+                eaf: opts.cocomo_eaf,
+                average_wage: opts.cocomo_wage,
+                overhead: opts.cocomo_overhead,
+            })
+        });
+        if opts.summary.as_deref() == Some("json") {
+            // The estimate follows the entries of the languages
+            #[derive(serde::Serialize)]
+            struct JsonSummary<'a> {
+                #[serde(flatten)]
+                summary: &'a Summary,
+                #[serde(skip_serializing_if = "Option::is_none")]
+                cocomo: Option<Cocomo>,
+            }
+            let summary = JsonSummary {
+                summary: &summary,
+                cocomo,
+            };
+            println!("{}", serde_json::to_string_pretty(&summary).unwrap());
+        } else {
+            println!("{summary}");
+            if let Some(cocomo) = cocomo {
+                println!("{cocomo}");
+            }
+        }
+    }
+
+    if let Some(summary) = language_summary_lock {
+        let summary = Arc::try_unwrap(summary).unwrap().into_inner().unwrap();
+        if opts.language_summary.as_deref() == Some("json") {
+            println!("{}", serde_json::to_string_pretty(&summary).unwrap());
+        } else {
+            println!("{summary}");
+        }
+    }
+
+    if let Some(debts) = debt_lock {
+        let debts = Arc::try_unwrap(debts).unwrap().into_inner().unwrap();
+        let mut total = Debt::default();
+        for debt in debts.values() {
+            total.merge(debt);
+        }
+
+        if opts.debt.as_deref() == Some("json") {
+            #[derive(serde::Serialize)]
+            struct JsonDebt {
+                files: BTreeMap<String, Debt>,
+                total: Debt,
+            }
+            let debt = JsonDebt {
+                files: debts,
+                total,
+            };
+            println!("{}", serde_json::to_string_pretty(&debt).unwrap());
+        } else {
+            for (path, debt) in debts {
+                println!("{path}: {debt}");
+            }
+            println!("Total: {total}");
+        }
+    }
+
+    if let Some(badges) = badges_lock {
+        let badges = Arc::try_unwrap(badges).unwrap().into_inner().unwrap();
+        let output_dir = opts.badges.as_ref().unwrap();
+        if let Err(e) = std::fs::create_dir_all(output_dir).and_then(|_| {
+            write_file(
+                &output_dir.join("complexity.svg"),
+                badges.complexity_svg(&grades).as_bytes(),
+            )?;
+            write_file(
+                &output_dir.join("mi.svg"),
+                badges.mi_svg(&grades).as_bytes(),
+            )
+        }) {
+            eprintln!(
+                "Error: Cannot write the badges in {}: {e}",
+                output_dir.display()
+            );
+            process::exit(1);
+        }
+    }
+
+    // The outliers not written in a report
+    for outlier in outliers.into_iter().flatten() {
+        println!(
+            "{}:{}: {}",
+            outlier.path,
+            outlier.start_line,
+            outlier.message()
+        );
+    }
+
+    if let (Some(snapshot), Some(path)) = (snapshot_lock, &opts.export_snapshot) {
+        let snapshot = Arc::try_unwrap(snapshot).unwrap().into_inner().unwrap();
+        if let Err(e) = File::create(path).and_then(|file| snapshot.write(BufWriter::new(file))) {
+            eprintln!("Error: Cannot write the snapshot {}: {e}", path.display());
+            process::exit(1);
+        }
+    }
+
+    if let Some(top) = top_lock {
+        let top = Arc::try_unwrap(top).unwrap().into_inner().unwrap();
+        match opts.top_format.as_str() {
+            "json" => println!("{}", serde_json::to_string_pretty(&top).unwrap()),
+            "markdown" => print!("{}", top.to_markdown()),
+            _ => println!("{top}"),
+        }
+    }
+
+    if let Some(crap) = crap_lock {
+        let crap = Arc::try_unwrap(crap).unwrap().into_inner().unwrap();
+        if opts.crap_format == "json" {
+            println!("{}", serde_json::to_string_pretty(&crap).unwrap());
+        } else {
+            println!("{crap}");
+        }
+    }
+
+    if let Some(references) = references_lock {
+        let mut files = Arc::try_unwrap(references).unwrap().into_inner().unwrap();
+        files.sort_by(|a, b| a.name.cmp(&b.name));
+        let functions = unreferenced_functions(&files);
+
+        if opts.unreferenced.as_deref() == Some("json") {
+            println!("{}", serde_json::to_string_pretty(&functions).unwrap());
+        } else {
+            for function in functions {
+                println!(
+                    "{}:{}: {} ({} confidence)",
+                    function.path, function.start_line, function.name, function.confidence
+                );
+            }
+        }
+    }
+
+    if let (Some(mut history), Some(grades)) = (grade_history, grades_lock) {
+        let mut grades = Arc::try_unwrap(grades).unwrap().into_inner().unwrap();
+        grades.sort_by(|a, b| (&a.path, a.start_line).cmp(&(&b.path, b.start_line)));
+        let now = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map_or(0, |time| time.as_secs() as i64);
+        let path = opts.grade_history.as_ref().unwrap();
+        if let Err(e) = history.record(now, &grades) {
+            eprintln!(
+                "Error: Cannot write the grade history {}: {e}",
+                path.display()
+            );
+            process::exit(1);
+        }
+
+        if let Some(since) = opts.grade_drops_since {
+            let drops = history.grade_drops(since).unwrap_or_else(|e| {
+                eprintln!(
+                    "Error: Cannot read the grade history {}: {e}",
+                    path.display()
+                );
+                process::exit(1);
+            });
+            for drop in drops {
+                println!(
+                    "{}:{}: {} dropped from {} to {} since {}",
+                    drop.path,
+                    drop.start_line,
+                    drop.qualified_name,
+                    drop.old,
+                    drop.new,
+                    timestamp_date(drop.since)
+                );
+            }
+        }
+    }
+
+    if let Some(files) = header_pairs_lock {
+        let mut files = Arc::try_unwrap(files).unwrap().into_inner().unwrap();
+        files.sort_by(|a, b| a.name.cmp(&b.name));
+        let pairs = pair_headers(&files);
+
+        if opts.header_pairs.as_deref() == Some("json") {
+            println!("{}", serde_json::to_string_pretty(&pairs).unwrap());
+        } else {
+            for pair in pairs {
+                print!("{pair}");
+            }
+        }
+    }
+
     if let Some(preproc) = preproc_lock {
         let mut data = Arc::try_unwrap(preproc).unwrap().into_inner().unwrap();
         fix_includes(&mut data.files, &all_files);
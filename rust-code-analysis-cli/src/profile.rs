@@ -0,0 +1,115 @@
+use std::fmt;
+use std::path::PathBuf;
+use std::time::{Duration, Instant};
+
+use rust_code_analysis::{Callback, FuncSpace, MetricsOptions, ParserTrait, metrics_with_options};
+
+// Number of files shown in the summary of the slowest files
+const SLOWEST_FILES: usize = 10;
+
+/// Time spent in each phase of the analysis of a file.
+#[derive(Debug, Default)]
+pub struct FileProfile {
+    pub path: PathBuf,
+    pub read: Duration,
+    pub parse: Duration,
+    pub metrics: Duration,
+    pub serialize: Duration,
+}
+
+impl FileProfile {
+    pub fn total(&self) -> Duration {
+        self.read + self.parse + self.metrics + self.serialize
+    }
+}
+
+fn ms(duration: Duration) -> f64 {
+    duration.as_secs_f64() * 1000.
+}
+
+impl fmt::Display for FileProfile {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "{:>10.2} ms (read {:.2}, parse {:.2}, metrics {:.2}, serialize {:.2})  {}",
+            ms(self.total()),
+            ms(self.read),
+            ms(self.parse),
+            ms(self.metrics),
+            ms(self.serialize),
+            self.path.display()
+        )
+    }
+}
+
+/// Timings of all the analyzed files.
+#[derive(Debug, Default)]
+pub struct Profile {
+    files: Vec<FileProfile>,
+}
+
+impl Profile {
+    pub fn push(&mut self, file: FileProfile) {
+        self.files.push(file);
+    }
+}
+
+impl fmt::Display for Profile {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let mut total = FileProfile::default();
+        for file in &self.files {
+            total.read += file.read;
+            total.parse += file.parse;
+            total.metrics += file.metrics;
+            total.serialize += file.serialize;
+        }
+
+        writeln!(f, "Profile of {} files", self.files.len())?;
+        writeln!(f, "Phases (summed over all the threads):")?;
+        for (name, duration) in [
+            ("read", total.read),
+            ("parse", total.parse),
+            ("metrics", total.metrics),
+            ("serialize", total.serialize),
+        ] {
+            let percent = if total.total().is_zero() {
+                0.
+            } else {
+                100. * duration.as_secs_f64() / total.total().as_secs_f64()
+            };
+            writeln!(f, "  {name:<10}{:>12.2} ms {percent:>6.1}%", ms(duration))?;
+        }
+
+        let mut files: Vec<_> = self.files.iter().collect();
+        files.sort_by_key(|file| std::cmp::Reverse(file.total()));
+        write!(f, "Slowest files:")?;
+        for file in files.iter().take(SLOWEST_FILES) {
+            write!(f, "\n{file}")?;
+        }
+
+        Ok(())
+    }
+}
+
+pub struct TimedMetricsCfg {
+    pub path: PathBuf,
+    pub options: MetricsOptions,
+    // When the parsing started
+    pub start: Instant,
+}
+
+/// Computes the metrics of a code, measuring separately
+/// the time spent parsing it and computing its metrics.
+pub struct TimedMetrics;
+
+impl Callback for TimedMetrics {
+    type Res = (Option<FuncSpace>, Duration, Duration);
+    type Cfg = TimedMetricsCfg;
+
+    fn call<T: ParserTrait>(cfg: Self::Cfg, parser: &T) -> Self::Res {
+        let parse = cfg.start.elapsed();
+        let start = Instant::now();
+        let space = metrics_with_options(parser, &cfg.path, &cfg.options);
+        (space, parse, start.elapsed())
+    }
+}
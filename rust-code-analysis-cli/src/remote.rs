@@ -0,0 +1,47 @@
+use std::ffi::OsStr;
+use std::io;
+use std::path::Path;
+use std::process::{Command, Stdio};
+
+use tempfile::TempDir;
+
+/// Runs a git command in a directory and returns its output.
+pub fn git<S: AsRef<OsStr>>(args: &[S], dir: &Path) -> io::Result<Vec<u8>> {
+    let output = Command::new("git")
+        .args(args)
+        .current_dir(dir)
+        .stdin(Stdio::null())
+        .output()?;
+    if output.status.success() {
+        Ok(output.stdout)
+    } else {
+        Err(io::Error::other(format!(
+            "git {} failed: {}",
+            args[0].as_ref().to_string_lossy(),
+            String::from_utf8_lossy(&output.stderr).trim()
+        )))
+    }
+}
+
+/// Fetches a single revision of a remote repository into a temporary
+/// directory, which is removed when the returned value is dropped.
+///
+/// The reference can be a branch, a tag or a commit hash.
+pub fn shallow_clone(url: &str, reference: &str) -> io::Result<TempDir> {
+    // The directory must not be hidden, otherwise it would be skipped
+    // when looking for the files to analyze
+    let dir = tempfile::Builder::new()
+        .prefix("rust-code-analysis-")
+        .tempdir()?;
+    let path = dir.path();
+
+    git(&["init", "--quiet"], path)?;
+    git(&["remote", "add", "origin", url], path)?;
+    git(
+        &["fetch", "--quiet", "--depth", "1", "origin", reference],
+        path,
+    )?;
+    git(&["checkout", "--quiet", "FETCH_HEAD"], path)?;
+
+    Ok(dir)
+}
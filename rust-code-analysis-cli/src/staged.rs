@@ -0,0 +1,57 @@
+use std::ffi::{OsStr, OsString};
+use std::io;
+use std::path::{Path, PathBuf};
+
+use crate::remote::git;
+
+/// Returns the files added, copied, modified or renamed in the index
+/// of the git repository containing the current directory, with their
+/// staged contents instead of the ones of the working tree.
+///
+/// The paths are relative to the root of the repository.
+pub fn staged_files() -> io::Result<Vec<(PathBuf, Vec<u8>)>> {
+    let root = git(&["rev-parse", "--show-toplevel"], Path::new("."))?;
+    let root = path_from_bytes(root.strip_suffix(b"\n").unwrap_or(&root));
+
+    let names = git(
+        &[
+            "diff",
+            "--cached",
+            "--name-only",
+            "-z",
+            "--diff-filter=ACMR",
+        ],
+        &root,
+    )?;
+
+    names
+        .split(|byte| *byte == 0)
+        .filter(|name| !name.is_empty())
+        .map(|name| {
+            let name = path_from_bytes(name);
+            // The stage 0 of a path is the blob of the merged file in the
+            // index, which cannot be mistaken for another revision syntax
+            let mut object = OsString::from(":0:");
+            object.push(&name);
+            let data = git(
+                &[OsStr::new("cat-file"), OsStr::new("blob"), &object],
+                &root,
+            )?;
+            Ok((name, data))
+        })
+        .collect()
+}
+
+// Converts a path printed by git, which is not necessarily valid UTF-8
+#[cfg(unix)]
+fn path_from_bytes(bytes: &[u8]) -> PathBuf {
+    use std::os::unix::ffi::OsStrExt;
+
+    PathBuf::from(OsStr::from_bytes(bytes))
+}
+
+// Git prints the paths in UTF-8 on the other platforms
+#[cfg(not(unix))]
+fn path_from_bytes(bytes: &[u8]) -> PathBuf {
+    PathBuf::from(String::from_utf8_lossy(bytes).as_ref())
+}
@@ -0,0 +1,383 @@
+use std::cmp::Ordering;
+use std::collections::BTreeMap;
+use std::io;
+
+use ratatui::crossterm::event::{self, Event, KeyCode, KeyEventKind};
+use ratatui::layout::{Constraint, Layout, Rect};
+use ratatui::style::{Modifier, Style};
+use ratatui::text::Line;
+use ratatui::widgets::{Block, Borders, Cell, List, ListState, Paragraph, Row, Table, TableState};
+use ratatui::{DefaultTerminal, Frame};
+use serde_json::Value;
+
+use rust_code_analysis::{FuncSpace, SpaceKind};
+
+// The metric used to sort the functions when the explorer opens
+const DEFAULT_METRIC: &str = "cyclomatic.sum";
+
+const HELP: &str = "q: quit  tab: switch pane  ↑/↓: move  s/S: next/previous metric  r: reverse  pgup/pgdn: scroll details";
+
+/// A function of an analyzed file.
+struct Function {
+    file: usize,
+    name: String,
+    kind: SpaceKind,
+    start_line: usize,
+    end_line: usize,
+    // The numeric fields of the metrics, keyed by `metric.field`
+    metrics: BTreeMap<String, f64>,
+}
+
+impl Function {
+    fn metric(&self, name: &str) -> f64 {
+        self.metrics.get(name).copied().unwrap_or(f64::NAN)
+    }
+}
+
+// Flattens the metrics of a space into their numeric fields
+fn flatten_metrics(space: &FuncSpace) -> BTreeMap<String, f64> {
+    let Ok(Value::Object(metrics)) = serde_json::to_value(&space.metrics) else {
+        return BTreeMap::new();
+    };
+    metrics
+        .iter()
+        .filter_map(|(metric, fields)| Some((metric, fields.as_object()?)))
+        .flat_map(|(metric, fields)| {
+            fields.iter().filter_map(move |(field, value)| {
+                // The undefined values are serialized as null
+                let value = match value {
+                    Value::Number(value) => value.as_f64()?,
+                    Value::Null => f64::NAN,
+                    _ => return None,
+                };
+                Some((format!("{metric}.{field}"), value))
+            })
+        })
+        .collect()
+}
+
+fn collect_functions(space: &FuncSpace, file: usize, functions: &mut Vec<Function>) {
+    for space in &space.spaces {
+        if space.kind == SpaceKind::Function {
+            functions.push(Function {
+                file,
+                name: space
+                    .qualified_name
+                    .clone()
+                    .or_else(|| space.name.clone())
+                    .unwrap_or_else(|| "<anonymous>".to_string()),
+                kind: space.kind,
+                start_line: space.start_line,
+                end_line: space.end_line,
+                metrics: flatten_metrics(space),
+            });
+        }
+        collect_functions(space, file, functions);
+    }
+}
+
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum Focus {
+    Files,
+    Functions,
+}
+
+struct Explorer {
+    files: Vec<String>,
+    functions: Vec<Function>,
+    // The metrics of all the functions, in alphabetical order
+    metrics: Vec<String>,
+    metric: usize,
+    descending: bool,
+    focus: Focus,
+    // The first entry is the one of all the files
+    file_state: ListState,
+    function_state: TableState,
+    // The indexes of the functions of the selected file, sorted by the metric
+    visible: Vec<usize>,
+    // The first line of the details shown in their panel
+    details_scroll: u16,
+}
+
+impl Explorer {
+    fn new(spaces: &[FuncSpace]) -> Self {
+        let mut functions = Vec::new();
+        for (file, space) in spaces.iter().enumerate() {
+            collect_functions(space, file, &mut functions);
+        }
+        let mut metrics: Vec<_> = functions
+            .iter()
+            .flat_map(|function| function.metrics.keys().cloned())
+            .collect();
+        metrics.sort();
+        metrics.dedup();
+
+        let mut explorer = Self {
+            files: spaces
+                .iter()
+                .map(|space| space.name.clone().unwrap_or_default())
+                .collect(),
+            metric: metrics
+                .iter()
+                .position(|metric| metric == DEFAULT_METRIC)
+                .unwrap_or_default(),
+            metrics,
+            functions,
+            descending: true,
+            focus: Focus::Files,
+            file_state: ListState::default().with_selected(Some(0)),
+            function_state: TableState::default(),
+            visible: Vec::new(),
+            details_scroll: 0,
+        };
+        explorer.update();
+        explorer
+    }
+
+    fn metric_name(&self) -> &str {
+        self.metrics.get(self.metric).map_or("", String::as_str)
+    }
+
+    // Selects and sorts the functions of the selected file
+    fn update(&mut self) {
+        let file = self.file_state.selected().unwrap_or_default();
+        let metric = self.metric_name();
+        let mut visible: Vec<_> = (0..self.functions.len())
+            .filter(|i| file == 0 || self.functions[*i].file == file - 1)
+            .collect();
+        visible.sort_by(|a, b| {
+            let (a, b) = (
+                self.functions[*a].metric(metric),
+                self.functions[*b].metric(metric),
+            );
+            // The undefined values are always last
+            match (a.is_nan(), b.is_nan()) {
+                (true, true) => Ordering::Equal,
+                (true, false) => Ordering::Greater,
+                (false, true) => Ordering::Less,
+                _ if self.descending => b.total_cmp(&a),
+                _ => a.total_cmp(&b),
+            }
+        });
+        self.visible = visible;
+        self.function_state
+            .select((!self.visible.is_empty()).then_some(0));
+        self.details_scroll = 0;
+    }
+
+    fn selected_function(&self) -> Option<&Function> {
+        self.function_state
+            .selected()
+            .and_then(|i| self.visible.get(i))
+            .map(|i| &self.functions[*i])
+    }
+
+    fn move_selection(&mut self, down: bool) {
+        match self.focus {
+            Focus::Files => {
+                if down {
+                    self.file_state.select_next();
+                } else {
+                    self.file_state.select_previous();
+                }
+                // The state does not know the number of entries
+                let last = self.files.len();
+                if self.file_state.selected().is_some_and(|i| i > last) {
+                    self.file_state.select(Some(last));
+                }
+                self.update();
+            }
+            Focus::Functions => {
+                if down {
+                    self.function_state.select_next();
+                } else {
+                    self.function_state.select_previous();
+                }
+                let last = self.visible.len().saturating_sub(1);
+                if self.function_state.selected().is_some_and(|i| i > last) {
+                    self.function_state.select(Some(last));
+                }
+                self.details_scroll = 0;
+            }
+        }
+    }
+
+    fn cycle_metric(&mut self, next: bool) {
+        if self.metrics.is_empty() {
+            return;
+        }
+        let len = self.metrics.len();
+        self.metric = if next {
+            (self.metric + 1) % len
+        } else {
+            (self.metric + len - 1) % len
+        };
+        self.update();
+    }
+
+    // Handles a key, returning false when the explorer is closed
+    fn handle_key(&mut self, key: KeyCode) -> bool {
+        match key {
+            KeyCode::Char('q') | KeyCode::Esc => return false,
+            KeyCode::Tab | KeyCode::BackTab | KeyCode::Left | KeyCode::Right => {
+                self.focus = match self.focus {
+                    Focus::Files => Focus::Functions,
+                    Focus::Functions => Focus::Files,
+                };
+            }
+            KeyCode::Down | KeyCode::Char('j') => self.move_selection(true),
+            KeyCode::Up | KeyCode::Char('k') => self.move_selection(false),
+            KeyCode::Char('s') => self.cycle_metric(true),
+            KeyCode::Char('S') => self.cycle_metric(false),
+            KeyCode::PageDown => self.details_scroll = self.details_scroll.saturating_add(5),
+            KeyCode::PageUp => self.details_scroll = self.details_scroll.saturating_sub(5),
+            KeyCode::Char('r') => {
+                self.descending = !self.descending;
+                self.update();
+            }
+            _ => {}
+        }
+        true
+    }
+
+    fn block(&self, title: String, focus: Focus) -> Block<'static> {
+        let block = Block::default().borders(Borders::ALL).title(title);
+        if self.focus == focus {
+            block.border_style(Style::default().add_modifier(Modifier::BOLD))
+        } else {
+            block
+        }
+    }
+
+    fn draw(&mut self, frame: &mut Frame) {
+        let [main, help] =
+            Layout::vertical([Constraint::Min(0), Constraint::Length(1)]).areas(frame.area());
+        let [files, right] =
+            Layout::horizontal([Constraint::Percentage(30), Constraint::Percentage(70)])
+                .areas(main);
+        let [functions, details] =
+            Layout::vertical([Constraint::Percentage(60), Constraint::Percentage(40)]).areas(right);
+
+        self.draw_files(frame, files);
+        self.draw_functions(frame, functions);
+        self.draw_details(frame, details);
+        frame.render_widget(Paragraph::new(HELP), help);
+    }
+
+    fn draw_files(&mut self, frame: &mut Frame, area: Rect) {
+        let entries = std::iter::once(format!("All files ({})", self.files.len()))
+            .chain(self.files.iter().cloned());
+        let list = List::new(entries)
+            .block(self.block("Files".to_string(), Focus::Files))
+            .highlight_style(Style::default().add_modifier(Modifier::REVERSED));
+        frame.render_stateful_widget(list, area, &mut self.file_state);
+    }
+
+    fn draw_functions(&mut self, frame: &mut Frame, area: Rect) {
+        let metric = self.metric_name().to_string();
+        let all_files = self.file_state.selected() == Some(0);
+        let rows = self.visible.iter().map(|i| {
+            let function = &self.functions[*i];
+            let mut cells = vec![Cell::from(function.name.clone())];
+            if all_files {
+                cells.push(Cell::from(self.files[function.file].clone()));
+            }
+            cells.push(Cell::from(format!(
+                "{}-{}",
+                function.start_line, function.end_line
+            )));
+            cells.push(Cell::from(format_value(function.metric(&metric))));
+            Row::new(cells)
+        });
+
+        let mut header = vec!["Function"];
+        let mut widths = vec![Constraint::Fill(2)];
+        if all_files {
+            header.push("File");
+            widths.push(Constraint::Fill(2));
+        }
+        header.extend(["Lines", metric.as_str()]);
+        widths.extend([Constraint::Length(11), Constraint::Length(24)]);
+
+        let order = if self.descending { "↓" } else { "↑" };
+        let title = format!("Functions ({}) by {metric} {order}", self.visible.len());
+        let table = Table::new(rows, widths)
+            .header(Row::new(header).style(Style::default().add_modifier(Modifier::BOLD)))
+            .block(self.block(title, Focus::Functions))
+            .row_highlight_style(Style::default().add_modifier(Modifier::REVERSED));
+        frame.render_stateful_widget(table, area, &mut self.function_state);
+    }
+
+    fn draw_details(&mut self, frame: &mut Frame, area: Rect) {
+        let block = Block::default().borders(Borders::ALL).title("Details");
+        let Some(function) = self.selected_function() else {
+            frame.render_widget(Paragraph::new("No function").block(block), area);
+            return;
+        };
+
+        let mut lines = vec![
+            Line::from(format!(
+                "{} ({}) in {}, lines {}-{}",
+                function.name,
+                function.kind,
+                self.files[function.file],
+                function.start_line,
+                function.end_line
+            )),
+            Line::default(),
+        ];
+        // The metrics are laid out in columns filling the width of the panel
+        let width = 36;
+        let columns = (usize::from(area.width.saturating_sub(2)) / width).max(1);
+        let cells: Vec<_> = function
+            .metrics
+            .iter()
+            .map(|(name, value)| format!("{name:<26}{:>9} ", format_value(*value)))
+            .collect();
+        lines.extend(cells.chunks(columns).map(|row| Line::from(row.concat())));
+        // The scrolling stops when the last metrics are shown
+        let max_scroll = u16::try_from(lines.len())
+            .unwrap_or(u16::MAX)
+            .saturating_sub(area.height.saturating_sub(2));
+        self.details_scroll = self.details_scroll.min(max_scroll);
+        frame.render_widget(
+            Paragraph::new(lines)
+                .block(block)
+                .scroll((self.details_scroll, 0)),
+            area,
+        );
+    }
+}
+
+fn format_value(value: f64) -> String {
+    if value.is_nan() {
+        "-".to_string()
+    } else if value.fract() == 0. && value.abs() < 1e15 {
+        format!("{value}")
+    } else {
+        format!("{value:.2}")
+    }
+}
+
+fn run(terminal: &mut DefaultTerminal, explorer: &mut Explorer) -> io::Result<()> {
+    loop {
+        terminal.draw(|frame| explorer.draw(frame))?;
+        if let Event::Key(key) = event::read()?
+            && key.kind == KeyEventKind::Press
+            && !explorer.handle_key(key.code)
+        {
+            return Ok(());
+        }
+    }
+}
+
+/// Browses the functions of the analyzed files in the terminal,
+/// sorted by any of their metrics.
+pub fn explore(spaces: &[FuncSpace]) -> io::Result<()> {
+    let mut explorer = Explorer::new(spaces);
+    // The terminal is restored on panics too
+    let mut terminal = ratatui::try_init()?;
+    let result = run(&mut terminal, &mut explorer);
+    ratatui::restore();
+    result
+}
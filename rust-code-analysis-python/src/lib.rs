@@ -3,9 +3,15 @@
 //! This module provides Python access to the rust-code-analysis library,
 //! enabling computation of code metrics for Python, Rust, and other languages.
 
-use pyo3::prelude::*;
 use ::rust_code_analysis as rca;
-use std::path::Path;
+use globset::{Glob, GlobSet, GlobSetBuilder};
+use pyo3::prelude::*;
+use pyo3::types::PyBytes;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::mpsc::{self, Receiver};
+use std::thread::{self, JoinHandle, available_parallelism};
 
 mod types;
 
@@ -17,71 +23,932 @@ use types::*;
 ///     source: Source code as a string
 ///     path: File path (used for language detection and naming)
 ///     language: Optional language override ("python", "rust", "java", etc.)
+///     dialect: Optional dialect of the language ("c", "tsx", "flow", etc.)
+///     functions_only: Keep only the functions among the nested spaces
+///     kinds: Optional kinds of the nested spaces to keep
 ///
 /// Returns:
-///     FuncSpace containing all metrics for the code
+///     FuncSpace containing all metrics for the code. When some kinds
+///     are kept, the spaces of the other kinds are removed before being
+///     converted, and their nested spaces are moved to their parents.
 ///
 /// Raises:
-///     ValueError: If the language cannot be determined or is unsupported
+///     ValueError: If the language cannot be determined or is unsupported,
+///         if the dialect is not a dialect of the language, or if both
+///         functions_only and kinds are given
 ///
 /// Example:
 ///     >>> import rust_code_analysis as rca
 ///     >>> result = rca.analyze("def foo(): pass", "example.py")
 ///     >>> print(result.metrics.cyclomatic.sum)
 #[pyfunction]
-#[pyo3(signature = (source, path, language=None))]
-fn analyze(source: &str, path: &str, language: Option<&str>) -> PyResult<PyFuncSpace> {
+#[pyo3(signature = (source, path, language=None, dialect=None, functions_only=false, kinds=None))]
+fn analyze(
+    py: Python<'_>,
+    source: &str,
+    path: &str,
+    language: Option<&str>,
+    dialect: Option<&str>,
+    functions_only: bool,
+    kinds: Option<Vec<PySpaceKind>>,
+) -> PyResult<PyFuncSpace> {
+    let kinds = space_kinds(functions_only, kinds)?;
+    // The code is parsed without holding the GIL, so that the threads
+    // of a pool can analyze their sources in parallel
+    py.detach(|| analyze_source(source, path, language, dialect, kinds.as_deref()))
+}
+
+// Returns the kinds of the nested spaces to keep, all of them if `None`
+fn space_kinds(
+    functions_only: bool,
+    kinds: Option<Vec<PySpaceKind>>,
+) -> PyResult<Option<Vec<rca::SpaceKind>>> {
+    match (functions_only, kinds) {
+        (true, Some(_)) => Err(PyErr::new::<pyo3::exceptions::PyValueError, _>(
+            "functions_only and kinds cannot be used together",
+        )),
+        (true, None) => Ok(Some(vec![rca::SpaceKind::Function])),
+        (false, kinds) => Ok(kinds.map(|kinds| kinds.into_iter().map(Into::into).collect())),
+    }
+}
+
+// Removes the nested spaces whose kind is not kept, moving their
+// own nested spaces to their parent in their place
+fn prune_spaces(space: &mut rca::FuncSpace, kinds: &[rca::SpaceKind]) {
+    for mut subspace in std::mem::take(&mut space.spaces) {
+        prune_spaces(&mut subspace, kinds);
+        if kinds.contains(&subspace.kind) {
+            space.spaces.push(subspace);
+        } else {
+            space.spaces.append(&mut subspace.spaces);
+        }
+    }
+}
+
+fn analyze_source(
+    source: &str,
+    path: &str,
+    language: Option<&str>,
+    dialect: Option<&str>,
+    kinds: Option<&[rca::SpaceKind]>,
+) -> PyResult<PyFuncSpace> {
+    let mut space = source_space(source, path, language, dialect)?;
+    if let Some(kinds) = kinds {
+        prune_spaces(&mut space, kinds);
+    }
+
+    Ok(convert_func_space(space))
+}
+
+// Returns the language of a code, guessed if not given, as the requested dialect
+fn source_language(
+    source: &[u8],
+    path: &Path,
+    language: Option<&str>,
+    dialect: Option<&str>,
+) -> PyResult<Option<rca::LANG>> {
+    let lang = match language {
+        Some(lang_str) => Some(parse_language(lang_str)?),
+        None => rca::guess_language(source, path).0,
+    };
+    match (lang, dialect) {
+        (Some(lang), Some(dialect)) => parse_dialect(dialect, lang).map(Some),
+        (lang, _) => Ok(lang),
+    }
+}
+
+// Returns the language of a code as `source_language`, failing when it is unknown
+fn known_language(
+    source: &[u8],
+    path: &Path,
+    language: Option<&str>,
+    dialect: Option<&str>,
+) -> PyResult<rca::LANG> {
+    source_language(source, path, language, dialect)?.ok_or_else(|| {
+        PyErr::new::<pyo3::exceptions::PyValueError, _>(format!(
+            "Could not determine language from file extension: '{}'",
+            path.display()
+        ))
+    })
+}
+
+// Returns the spaces of a code, guessing its language if not given
+fn source_space(
+    source: &str,
+    path: &str,
+    language: Option<&str>,
+    dialect: Option<&str>,
+) -> PyResult<rca::FuncSpace> {
     let path = Path::new(path);
     let source_bytes = source.as_bytes().to_vec();
 
-    let lang = match language {
-        Some(lang_str) => rca::get_from_ext(lang_str).ok_or_else(|| {
+    let space = match source_language(&source_bytes, path, language, dialect)? {
+        Some(lang) => rca::get_function_spaces(&lang, source_bytes, path, None),
+        // The scripts of the Vue and Svelte components
+        None if rca::is_component(path) => {
+            rca::get_embedded_spaces(&source_bytes, path, &rca::MetricsOptions::default())
+                .map(|(_, space)| space)
+        }
+        None => {
+            return Err(PyErr::new::<pyo3::exceptions::PyValueError, _>(format!(
+                "Could not determine language from file extension: '{}'",
+                path.display()
+            )));
+        }
+    };
+    space.ok_or_else(|| {
+        PyErr::new::<pyo3::exceptions::PyValueError, _>("Failed to parse source code")
+    })
+}
+
+/// Analyze the spaces of source code overlapping a range of lines,
+/// such as the lines selected in an editor.
+///
+/// The whole code is parsed, so that the spaces are analyzed in their
+/// context, and only the spaces overlapping the lines are kept.
+///
+/// Args:
+///     source: The source code to analyze
+///     path: File path (used for language detection)
+///     start_line: The first line of the range, starting from 1
+///     end_line: The last line of the range, included
+///     language: Optional language override
+///     dialect: Optional dialect of the language
+///
+/// Returns:
+///     FuncSpace of the whole code, whose nested spaces are the ones
+///     overlapping the lines. Each space keeps the metrics of all its code.
+///
+/// Raises:
+///     ValueError: If the language cannot be determined or is unsupported,
+///         or if the dialect is not a dialect of the language
+///
+/// Example:
+///     >>> import rust_code_analysis as rca
+///     >>> code = "def foo():\n    pass\n\ndef bar():\n    pass\n"
+///     >>> result = rca.analyze_range(code, "example.py", 4, 5)
+///     >>> print(result[0].name)
+///     bar
+#[pyfunction]
+#[pyo3(signature = (source, path, start_line, end_line, language=None, dialect=None))]
+fn analyze_range(
+    py: Python<'_>,
+    source: &str,
+    path: &str,
+    start_line: usize,
+    end_line: usize,
+    language: Option<&str>,
+    dialect: Option<&str>,
+) -> PyResult<PyFuncSpace> {
+    py.detach(|| {
+        let mut space = source_space(source, path, language, dialect)?;
+        rca::filter_by_range(&mut space, start_line, end_line);
+        Ok(convert_func_space(space))
+    })
+}
+
+/// Map the lines of source code to the innermost spaces containing them.
+///
+/// The map is run-length encoded, so that the line hits of a coverage
+/// report or of a profiler can be attributed to the functions efficiently,
+/// through the list returned by LineMap.to_lines().
+///
+/// Args:
+///     source: The source code to analyze
+///     path: File path (used for language detection)
+///     language: Optional language override
+///     dialect: Optional dialect of the language
+///
+/// Returns:
+///     LineMap with the spaces of the code and the runs of lines of each of
+///     them, a line shared by nested spaces belonging to the innermost one
+///
+/// Raises:
+///     ValueError: If the language cannot be determined or is unsupported,
+///         or if the dialect is not a dialect of the language
+///
+/// Example:
+///     >>> import rust_code_analysis as rca
+///     >>> code = "def foo():\n    pass\n\ndef bar():\n    pass\n"
+///     >>> lines = rca.line_map(code, "example.py")
+///     >>> print(lines.space_at(4).qualified_name)
+///     bar
+#[pyfunction]
+#[pyo3(signature = (source, path, language=None, dialect=None))]
+fn line_map(
+    py: Python<'_>,
+    source: &str,
+    path: &str,
+    language: Option<&str>,
+    dialect: Option<&str>,
+) -> PyResult<PyLineMap> {
+    py.detach(|| {
+        let space = source_space(source, path, language, dialect)?;
+        Ok(rca::LineMap::new(&space).into())
+    })
+}
+
+/// Get the tokens of source code, the leaves of its syntax tree.
+///
+/// Args:
+///     source: The source code to tokenize
+///     path: File path (used for language detection)
+///     language: Optional language override
+///     dialect: Optional dialect of the language
+///
+/// Returns:
+///     List of Token in the order of the code, with their kind in the
+///     grammar of the language, their text and their span. The comments
+///     are single tokens.
+///
+/// Raises:
+///     ValueError: If the language cannot be determined or is unsupported,
+///         or if the dialect is not a dialect of the language
+///
+/// Example:
+///     >>> import rust_code_analysis as rca
+///     >>> tokens = rca.tokens("x = 1", "example.py")
+///     >>> print([token.text for token in tokens])
+///     ['x', '=', '1']
+#[pyfunction]
+#[pyo3(signature = (source, path, language=None, dialect=None))]
+fn tokens(
+    py: Python<'_>,
+    source: &str,
+    path: &str,
+    language: Option<&str>,
+    dialect: Option<&str>,
+) -> PyResult<Vec<PyToken>> {
+    py.detach(|| {
+        let path = Path::new(path);
+        let source_bytes = source.as_bytes().to_vec();
+        let lang = known_language(&source_bytes, path, language, dialect)?;
+        Ok(rca::get_tokens(&lang, source_bytes, path, None)
+            .into_iter()
+            .map(Into::into)
+            .collect())
+    })
+}
+
+/// Count the occurrences of some types of nodes in source code.
+///
+/// Args:
+///     source: The source code to analyze
+///     path: File path (used for language detection)
+///     kinds: Types of nodes to count, matched as by the count command: a node
+///         kind or a part of it, a kind identifier, or one of the "call",
+///         "comment", "error", "string" and "function" categories
+///     language: Optional language override
+///     dialect: Optional dialect of the language
+///
+/// Returns:
+///     List of NodeCount, one for each type in the same order, with the
+///     number of nodes of the type and their spans in the order of the code
+///
+/// Raises:
+///     ValueError: If the language cannot be determined or is unsupported,
+///         or if the dialect is not a dialect of the language
+///
+/// Example:
+///     >>> import rust_code_analysis as rca
+///     >>> counts = rca.count_nodes("a.unwrap().unwrap()", "example.rs", ["call_expression"])
+///     >>> print(counts[0].count)
+///     2
+#[pyfunction]
+#[pyo3(signature = (source, path, kinds, language=None, dialect=None))]
+fn count_nodes(
+    py: Python<'_>,
+    source: &str,
+    path: &str,
+    kinds: Vec<String>,
+    language: Option<&str>,
+    dialect: Option<&str>,
+) -> PyResult<Vec<PyNodeCount>> {
+    py.detach(|| {
+        let path = Path::new(path);
+        let source_bytes = source.as_bytes().to_vec();
+        let lang = known_language(&source_bytes, path, language, dialect)?;
+        Ok(
+            rca::get_node_counts(&lang, source_bytes, path, None, &kinds)
+                .into_iter()
+                .map(Into::into)
+                .collect(),
+        )
+    })
+}
+
+/// Get the syntax tree of source code as a tree-sitter s-expression.
+///
+/// Args:
+///     source: The source code to parse
+///     path: File path (used for language detection)
+///     language: Optional language override
+///     dialect: Optional dialect of the language
+///     byte_ranges: Follow each node by the range of its bytes,
+///         such as (identifier [0..1])
+///
+/// Returns:
+///     The s-expression of the syntax tree, with the named nodes,
+///     the missing ones and the names of their fields
+///
+/// Raises:
+///     ValueError: If the language cannot be determined or is unsupported,
+///         or if the dialect is not a dialect of the language
+///
+/// Example:
+///     >>> import rust_code_analysis as rca
+///     >>> print(rca.to_sexp("x = 1", "example.py"))
+///     (module (expression_statement (assignment left: (identifier) right: (integer))))
+#[pyfunction]
+#[pyo3(signature = (source, path, language=None, dialect=None, byte_ranges=false))]
+fn to_sexp(
+    py: Python<'_>,
+    source: &str,
+    path: &str,
+    language: Option<&str>,
+    dialect: Option<&str>,
+    byte_ranges: bool,
+) -> PyResult<String> {
+    py.detach(|| {
+        let path = Path::new(path);
+        let source_bytes = source.as_bytes().to_vec();
+        let lang = known_language(&source_bytes, path, language, dialect)?;
+        Ok(rca::get_sexp(&lang, source_bytes, path, None, byte_ranges))
+    })
+}
+
+/// Analyze a file from disk.
+///
+/// Args:
+///     path: Path to the file to analyze
+///     language: Optional language override
+///     dialect: Optional dialect of the language
+///     encoding: Optional encoding of the file ("utf-8", "utf-16le",
+///         "utf-16be" or "latin-1"), detected if not given
+///     functions_only: Keep only the functions among the nested spaces
+///     kinds: Optional kinds of the nested spaces to keep
+///
+/// Returns:
+///     FuncSpace containing all metrics, see analyze() for the kinds
+///
+/// Raises:
+///     IOError: If the file cannot be read
+///     ValueError: If the language cannot be determined, the encoding
+///         is unsupported, the file is not a text file or both
+///         functions_only and kinds are given
+///
+/// Example:
+///     >>> import rust_code_analysis as rca
+///     >>> result = rca.analyze_file("src/main.py")
+#[pyfunction]
+#[pyo3(signature = (path, language=None, dialect=None, encoding=None, functions_only=false, kinds=None))]
+fn analyze_file(
+    py: Python<'_>,
+    path: &str,
+    language: Option<&str>,
+    dialect: Option<&str>,
+    encoding: Option<&str>,
+    functions_only: bool,
+    kinds: Option<Vec<PySpaceKind>>,
+) -> PyResult<PyFuncSpace> {
+    let data = std::fs::read(path).map_err(|e| {
+        PyErr::new::<pyo3::exceptions::PyIOError, _>(format!(
+            "Failed to read file '{}': {}",
+            path, e
+        ))
+    })?;
+    let encoding = match encoding {
+        Some(name) => rca::Encoding::from_name(name).ok_or_else(|| {
             PyErr::new::<pyo3::exceptions::PyValueError, _>(format!(
-                "Unsupported language: '{}'. Use supported_languages() to see available options.",
-                lang_str
+                "Unsupported encoding: '{}'. Supported encodings: {}",
+                name,
+                rca::Encoding::NAMES.join(", ")
+            ))
+        })?,
+        None => rca::detect_encoding(&data).ok_or_else(|| {
+            PyErr::new::<pyo3::exceptions::PyValueError, _>(format!(
+                "The file '{}' is not a text file",
+                path
             ))
         })?,
-        None => rca::guess_language(&source_bytes, path)
-            .0
-            .ok_or_else(|| {
-                PyErr::new::<pyo3::exceptions::PyValueError, _>(format!(
-                    "Could not determine language from file extension: '{}'",
-                    path.display()
-                ))
-            })?,
     };
+    let source = String::from_utf8_lossy(&encoding.decode(&data)).into_owned();
+    analyze(py, &source, path, language, dialect, functions_only, kinds)
+}
 
-    let space = rca::get_function_spaces(&lang, source_bytes, path, None)
+fn parse_language(lang_str: &str) -> PyResult<rca::LANG> {
+    rca::get_from_ext(lang_str)
+        .or_else(|| rca::get_from_emacs_mode(lang_str))
         .ok_or_else(|| {
-            PyErr::new::<pyo3::exceptions::PyValueError, _>("Failed to parse source code")
+            PyErr::new::<pyo3::exceptions::PyValueError, _>(format!(
+                "Unsupported language: '{}'. Use supported_languages() to see available options.",
+                lang_str
+            ))
+        })
+}
+
+fn parse_dialect(name: &str, lang: rca::LANG) -> PyResult<rca::LANG> {
+    let dialect = rca::Dialect::from_name(name).ok_or_else(|| {
+        PyErr::new::<pyo3::exceptions::PyValueError, _>(format!(
+            "Unsupported dialect: '{}'. Supported dialects: {}",
+            name,
+            rca::Dialect::NAMES.join(", ")
+        ))
+    })?;
+    if !dialect.is_dialect_of(lang) {
+        return Err(PyErr::new::<pyo3::exceptions::PyValueError, _>(format!(
+            "'{}' is not a dialect of {}",
+            name,
+            lang.get_name()
+        )));
+    }
+    Ok(dialect.language())
+}
+
+fn mk_globset(globs: Option<Vec<String>>) -> PyResult<GlobSet> {
+    let mut builder = GlobSetBuilder::new();
+    for glob in globs.unwrap_or_default() {
+        let glob = Glob::new(&glob).map_err(|e| {
+            PyErr::new::<pyo3::exceptions::PyValueError, _>(format!(
+                "Invalid glob '{}': {}",
+                glob, e
+            ))
         })?;
+        builder.add(glob);
+    }
+    builder.build().map_err(|e| {
+        PyErr::new::<pyo3::exceptions::PyValueError, _>(format!("Invalid globs: {}", e))
+    })
+}
 
-    Ok(convert_func_space(&space))
+fn parse_symlinks(symlinks: Option<&str>) -> PyResult<rca::SymlinkPolicy> {
+    symlinks.map_or(Ok(rca::SymlinkPolicy::default()), |name| {
+        rca::SymlinkPolicy::from_name(name).ok_or_else(|| {
+            PyErr::new::<pyo3::exceptions::PyValueError, _>(format!(
+                "Unsupported symlinks policy: '{}'. Expected one of: {}",
+                name,
+                rca::SymlinkPolicy::NAMES.join(", ")
+            ))
+        })
+    })
 }
 
-/// Analyze a file from disk.
+fn mk_files_data(
+    paths: Vec<PathBuf>,
+    include: Option<Vec<String>>,
+    exclude: Option<Vec<String>>,
+    symlinks: Option<&str>,
+) -> PyResult<rca::FilesData> {
+    Ok(rca::FilesData {
+        include: mk_globset(include)?,
+        exclude: mk_globset(exclude)?,
+        paths,
+        symlinks: parse_symlinks(symlinks)?,
+    })
+}
+
+// The number of threads set with `set_num_threads`, 0 for the default
+static NUM_THREADS: AtomicUsize = AtomicUsize::new(0);
+
+fn default_num_threads() -> usize {
+    available_parallelism().map_or(2, |n| n.get())
+}
+
+// Returns the number of threads analyzing a batch: the `jobs` argument
+// of the call, then the value set with `set_num_threads`, then the
+// available parallelism
+fn num_jobs(jobs: Option<usize>) -> PyResult<usize> {
+    match jobs {
+        Some(0) => Err(PyErr::new::<pyo3::exceptions::PyValueError, _>(
+            "The number of jobs must be at least 1",
+        )),
+        Some(jobs) => Ok(jobs),
+        None => Ok(get_num_threads()),
+    }
+}
+
+fn batch_error(error: rca::ConcurrentErrors) -> PyErr {
+    PyErr::new::<pyo3::exceptions::PyRuntimeError, _>(format!(
+        "Failed to analyze files: {:?}",
+        error
+    ))
+}
+
+fn analyze_paths(
+    py: Python<'_>,
+    paths: Vec<PathBuf>,
+    include: Option<Vec<String>>,
+    exclude: Option<Vec<String>>,
+    language: Option<&str>,
+    jobs: Option<usize>,
+    symlinks: Option<&str>,
+) -> PyResult<PyBatchResult> {
+    let language = language.map(parse_language).transpose()?;
+    let files_data = mk_files_data(paths, include, exclude, symlinks)?;
+    let jobs = num_jobs(jobs)?;
+
+    // The worker threads need the GIL to forward their logs to Python
+    let results = py
+        .detach(|| rca::analyze_batch(files_data, language, jobs))
+        .map_err(batch_error)?;
+    Ok(results.into())
+}
+
+type BatchItem = Result<rca::FuncSpace, rca::FileError>;
+
+/// Iterator over the results of a batch analyzed in background
+#[pyclass]
+pub struct PyBatchIterator {
+    receiver: Mutex<Receiver<BatchItem>>,
+    handle: Mutex<Option<JoinHandle<Result<rca::FileClassification, rca::ConcurrentErrors>>>>,
+}
+
+#[pymethods]
+impl PyBatchIterator {
+    fn __iter__(slf: PyRef<'_, Self>) -> PyRef<'_, Self> {
+        slf
+    }
+
+    fn __next__(&self, py: Python<'_>) -> PyResult<Option<Py<PyAny>>> {
+        // Release the GIL while waiting for the next file to be analyzed
+        let item = py.detach(|| self.receiver.lock().unwrap().recv());
+        match item {
+            Ok(Ok(space)) => Ok(Some(Py::new(py, convert_func_space(space))?.into_any())),
+            Ok(Err(error)) => Ok(Some(Py::new(py, PyFileError::from(&error))?.into_any())),
+            Err(_) => {
+                // The channel is closed, so the batch has been analyzed
+                let handle = self.handle.lock().unwrap().take();
+                if let Some(handle) = handle {
+                    match py.detach(|| handle.join()) {
+                        Ok(res) => {
+                            res.map_err(batch_error)?;
+                        }
+                        Err(_) => {
+                            return Err(PyErr::new::<pyo3::exceptions::PyRuntimeError, _>(
+                                "The analysis of the files panicked",
+                            ));
+                        }
+                    }
+                }
+                Ok(None)
+            }
+        }
+    }
+}
+
+/// Analyze a batch of files and directories in parallel.
+///
+/// A file which cannot be analyzed does not stop the analysis: it is
+/// reported in the errors of the result. Files whose language cannot be
+/// determined are skipped.
 ///
 /// Args:
-///     path: Path to the file to analyze
+///     paths: List of files and directories to analyze
+///     include: Optional list of globs selecting the files to analyze
+///     exclude: Optional list of globs selecting the files to skip
 ///     language: Optional language override
+///     jobs: Optional number of threads, see set_num_threads
+///     symlinks: Optional handling of the symbolic links found in the
+///         directories: "follow" (the default), "skip" or "error"
 ///
 /// Returns:
-///     FuncSpace containing all metrics
+///     BatchResult with the spaces of the analyzed files and the errors
 ///
 /// Raises:
-///     IOError: If the file cannot be read
-///     ValueError: If the language cannot be determined
+///     ValueError: If a glob, the language or the symlinks policy is invalid
 ///
 /// Example:
 ///     >>> import rust_code_analysis as rca
-///     >>> result = rca.analyze_file("src/main.py")
+///     >>> result = rca.analyze_files(["src/"], include=["*.py"])
+///     >>> for error in result.errors:
+///     ...     print(error.path, error.kind, error.message)
 #[pyfunction]
-#[pyo3(signature = (path, language=None))]
-fn analyze_file(path: &str, language: Option<&str>) -> PyResult<PyFuncSpace> {
-    let source = std::fs::read_to_string(path).map_err(|e| {
-        PyErr::new::<pyo3::exceptions::PyIOError, _>(format!("Failed to read file '{}': {}", path, e))
+#[pyo3(signature = (paths, include=None, exclude=None, language=None, jobs=None, symlinks=None))]
+fn analyze_files(
+    py: Python<'_>,
+    paths: Vec<String>,
+    include: Option<Vec<String>>,
+    exclude: Option<Vec<String>>,
+    language: Option<&str>,
+    jobs: Option<usize>,
+    symlinks: Option<&str>,
+) -> PyResult<PyBatchResult> {
+    analyze_paths(
+        py,
+        paths.into_iter().map(PathBuf::from).collect(),
+        include,
+        exclude,
+        language,
+        jobs,
+        symlinks,
+    )
+}
+
+/// Analyze a batch of sources already in memory in parallel, such as
+/// the files read from a database or from a git index, without writing
+/// them to temporary files.
+///
+/// A source which cannot be analyzed does not stop the analysis: it is
+/// reported in the errors of the result. Sources whose language cannot be
+/// determined are skipped.
+///
+/// Args:
+///     sources: List of (source, path) or (source, path, language) tuples,
+///         where the path names the results and guesses the language
+///         when it is not given
+///     jobs: Optional number of threads, see set_num_threads
+///
+/// Returns:
+///     BatchResult with the spaces of the analyzed sources, sorted by path,
+///     and the errors
+///
+/// Raises:
+///     TypeError: If a source is not a tuple of strings
+///     ValueError: If a language is invalid
+///
+/// Example:
+///     >>> import rust_code_analysis as rca
+///     >>> result = rca.analyze_many([
+///     ...     ("def foo():\n    pass\n", "foo.py"),
+///     ...     ("fn bar() {}\n", "bar", "rust"),
+///     ... ])
+///     >>> [space.name for space in result.spaces]
+///     ['bar', 'foo.py']
+#[pyfunction]
+#[pyo3(signature = (sources, jobs=None))]
+fn analyze_many(
+    py: Python<'_>,
+    sources: Vec<Bound<'_, PyAny>>,
+    jobs: Option<usize>,
+) -> PyResult<PyBatchResult> {
+    let sources = sources
+        .iter()
+        .map(|item| {
+            let (source, path, language) = match item.extract::<(String, String, Option<String>)>()
+            {
+                Ok(tuple) => tuple,
+                Err(_) => {
+                    let (source, path) = item.extract::<(String, String)>()?;
+                    (source, path, None)
+                }
+            };
+            source_file(source, path, language.as_deref())
+        })
+        .collect::<PyResult<Vec<_>>>()?;
+    let jobs = num_jobs(jobs)?;
+
+    // The worker threads need the GIL to forward their logs to Python
+    let results = py.detach(|| rca::analyze_sources(sources, jobs));
+    Ok(results.into())
+}
+
+// Builds a source of a batch from its fields
+fn source_file(source: String, path: String, language: Option<&str>) -> PyResult<rca::SourceFile> {
+    Ok(rca::SourceFile {
+        path: PathBuf::from(path),
+        code: source.into_bytes(),
+        language: language.map(parse_language).transpose()?,
+    })
+}
+
+/// Analyze a batch of files and directories in parallel and export the
+/// metrics of all their spaces as an Arrow IPC stream.
+///
+/// The table is built without creating any Python object for the spaces,
+/// so it is the fastest way to load the metrics of large code bases into
+/// pandas or polars. It contains one row per space with the file, name,
+/// qualified_name, kind, start_line and end_line columns, followed by one column per metric
+/// value, such as cyclomatic_sum or loc_sloc. Files which cannot be analyzed
+/// are skipped, use analyze_files to inspect their errors.
+///
+/// Args:
+///     paths: List of files and directories to analyze
+///     include: Optional list of globs selecting the files to analyze
+///     exclude: Optional list of globs selecting the files to skip
+///     language: Optional language override
+///     jobs: Optional number of threads, see set_num_threads
+///     symlinks: Optional handling of the symbolic links found in the
+///         directories: "follow" (the default), "skip" or "error"
+///
+/// Returns:
+///     The bytes of an Arrow IPC stream
+///
+/// Raises:
+///     ValueError: If a glob, the language or the symlinks policy is invalid
+///
+/// Example:
+///     >>> import pyarrow as pa
+///     >>> import rust_code_analysis as rca
+///     >>> data = rca.analyze_files_arrow(["src/"], include=["*.py"])
+///     >>> df = pa.ipc.open_stream(data).read_all().to_pandas()
+#[pyfunction]
+#[pyo3(signature = (paths, include=None, exclude=None, language=None, jobs=None, symlinks=None))]
+fn analyze_files_arrow<'py>(
+    py: Python<'py>,
+    paths: Vec<String>,
+    include: Option<Vec<String>>,
+    exclude: Option<Vec<String>>,
+    language: Option<&str>,
+    jobs: Option<usize>,
+    symlinks: Option<&str>,
+) -> PyResult<Bound<'py, PyBytes>> {
+    let language = language.map(parse_language).transpose()?;
+    let jobs = num_jobs(jobs)?;
+    let files_data = mk_files_data(
+        paths.into_iter().map(PathBuf::from).collect(),
+        include,
+        exclude,
+        symlinks,
+    )?;
+
+    // The GIL is not needed until the bytes are handed to Python
+    let data = py.detach(|| {
+        let results = rca::analyze_batch(files_data, language, jobs).map_err(batch_error)?;
+        let mut data = Vec::new();
+        rca::write_arrow_ipc(&results.spaces, &mut data).map_err(|e| {
+            PyErr::new::<pyo3::exceptions::PyRuntimeError, _>(format!(
+                "Failed to export the metrics: {}",
+                e
+            ))
+        })?;
+        Ok::<_, PyErr>(data)
     })?;
-    analyze(&source, path, language)
+
+    Ok(PyBytes::new(py, &data))
+}
+
+/// Analyze a batch of files and directories in parallel, yielding the
+/// result of each file as soon as its analysis completes.
+///
+/// The files are analyzed in background, so the results are yielded in
+/// the order in which the analyses complete. At most as many results as
+/// the threads are held until they are consumed, so the memory used does
+/// not grow with the number of files. Files whose language cannot
+/// be determined are skipped.
+///
+/// Args:
+///     paths: List of files and directories to analyze
+///     include: Optional list of globs selecting the files to analyze
+///     exclude: Optional list of globs selecting the files to skip
+///     language: Optional language override
+///     jobs: Optional number of threads, see set_num_threads
+///     symlinks: Optional handling of the symbolic links found in the
+///         directories: "follow" (the default), "skip" or "error"
+///
+/// Returns:
+///     An iterator yielding a FuncSpace for each analyzed file
+///     and a FileError for each file which cannot be analyzed
+///
+/// Raises:
+///     ValueError: If a glob, the language or the symlinks policy is invalid
+///
+/// Example:
+///     >>> import rust_code_analysis as rca
+///     >>> for result in rca.iter_analyze_files(["src/"], include=["*.py"]):
+///     ...     if isinstance(result, rca.PyFileError):
+///     ...         print(result.path, result.message)
+///     ...     else:
+///     ...         print(result.name, result.metrics.cyclomatic.sum)
+#[pyfunction]
+#[pyo3(signature = (paths, include=None, exclude=None, language=None, jobs=None, symlinks=None))]
+fn iter_analyze_files(
+    paths: Vec<String>,
+    include: Option<Vec<String>>,
+    exclude: Option<Vec<String>>,
+    language: Option<&str>,
+    jobs: Option<usize>,
+    symlinks: Option<&str>,
+) -> PyResult<PyBatchIterator> {
+    let language = language.map(parse_language).transpose()?;
+    let jobs = num_jobs(jobs)?;
+    let files_data = mk_files_data(
+        paths.into_iter().map(PathBuf::from).collect(),
+        include,
+        exclude,
+        symlinks,
+    )?;
+
+    // The results not yet consumed are bounded, so the analysis
+    // waits for the iterator when it is consumed slowly
+    let (sender, receiver) = mpsc::sync_channel(jobs);
+    let handle = thread::spawn(move || {
+        rca::analyze_batch_with(files_data, language, jobs, move |res| {
+            // The receiver is dropped when the iterator is not consumed anymore
+            let _ = sender.send(res);
+        })
+    });
+
+    Ok(PyBatchIterator {
+        receiver: Mutex::new(receiver),
+        handle: Mutex::new(Some(handle)),
+    })
+}
+
+/// Analyze the files contained in an archive.
+///
+/// Zip archives (.zip, .whl, .jar) and tar archives (.tar, .tar.gz, .tgz)
+/// are supported. Files are decompressed in memory and analyzed in parallel.
+/// Files whose language cannot be determined are skipped.
+///
+/// Args:
+///     path: Path to the archive
+///     include: Optional list of globs selecting the files to analyze
+///     exclude: Optional list of globs selecting the files to skip
+///     language: Optional language override
+///     jobs: Optional number of threads, see set_num_threads
+///
+/// Returns:
+///     BatchResult with the spaces of the analyzed files and the errors.
+///     Each path is the archive path followed by the path inside the archive.
+///
+/// Raises:
+///     IOError: If the archive cannot be read
+///     ValueError: If the file is not a supported archive
+///
+/// Example:
+///     >>> import rust_code_analysis as rca
+///     >>> result = rca.analyze_archive("foo-1.0.tar.gz", include=["*.py"])
+///     >>> for space in result.spaces:
+///     ...     print(space.name, space.metrics.cyclomatic.sum)
+#[pyfunction]
+#[pyo3(signature = (path, include=None, exclude=None, language=None, jobs=None))]
+fn analyze_archive(
+    py: Python<'_>,
+    path: &str,
+    include: Option<Vec<String>>,
+    exclude: Option<Vec<String>>,
+    language: Option<&str>,
+    jobs: Option<usize>,
+) -> PyResult<PyBatchResult> {
+    let archive = PathBuf::from(path);
+    if rca::ArchiveKind::from_path(&archive).is_none() {
+        return Err(PyErr::new::<pyo3::exceptions::PyValueError, _>(format!(
+            "Unsupported archive: '{}'",
+            path
+        )));
+    }
+    std::fs::File::open(&archive).map_err(|e| {
+        PyErr::new::<pyo3::exceptions::PyIOError, _>(format!(
+            "Failed to read archive '{}': {}",
+            path, e
+        ))
+    })?;
+
+    analyze_paths(py, vec![archive], include, exclude, language, jobs, None)
+}
+
+/// Set the number of threads analyzing the batches of files.
+///
+/// By default the number of threads is the available parallelism of the
+/// machine, which can oversubscribe the CPUs of a container with a CPU quota.
+/// The jobs argument of a call takes precedence over this value.
+///
+/// Args:
+///     n: Number of threads, or None to restore the default
+///
+/// Raises:
+///     ValueError: If the number of threads is 0
+///
+/// Example:
+///     >>> import rust_code_analysis as rca
+///     >>> rca.set_num_threads(2)
+#[pyfunction]
+fn set_num_threads(n: Option<usize>) -> PyResult<()> {
+    if n == Some(0) {
+        return Err(PyErr::new::<pyo3::exceptions::PyValueError, _>(
+            "The number of threads must be at least 1",
+        ));
+    }
+    NUM_THREADS.store(n.unwrap_or(0), Ordering::Relaxed);
+    Ok(())
+}
+
+/// Get the number of threads analyzing the batches of files.
+///
+/// Returns:
+///     The number set with set_num_threads, or the available parallelism
+#[pyfunction]
+fn get_num_threads() -> usize {
+    match NUM_THREADS.load(Ordering::Relaxed) {
+        0 => default_num_threads(),
+        n => n,
+    }
+}
+
+/// Describe the metrics, to explain their values in reports and user interfaces.
+///
+/// Returns:
+///     List of MetricDescription, one for each metric, with its name,
+///     its formula, its range and its recommended thresholds
+///
+/// Example:
+///     >>> import rust_code_analysis as rca
+///     >>> cyclomatic = next(m for m in rca.describe_metrics() if m.key == "cyclomatic")
+///     >>> cyclomatic.threshold_field, cyclomatic.warning, cyclomatic.critical
+///     ('sum', 10.0, 20.0)
+#[pyfunction]
+fn describe_metrics() -> Vec<PyMetricDescription> {
+    rca::describe_metrics().iter().map(Into::into).collect()
 }
 
 /// Get list of supported languages.
@@ -145,9 +1012,18 @@ fn language_from_extension(extension: &str) -> Option<&'static str> {
 /// Main functions:
 ///     - analyze(source, path, language=None): Analyze source code string
 ///     - analyze_file(path, language=None): Analyze a file from disk
+///     - analyze_files(paths, include=None, exclude=None, language=None): Analyze files in parallel
+///     - iter_analyze_files(paths, include=None, exclude=None, language=None): Stream the results of files
+///     - analyze_files_arrow(paths, include=None, exclude=None, language=None): Export files as an Arrow table
+///     - analyze_archive(path, include=None, exclude=None, language=None): Analyze an archive
 ///     - supported_languages(): List supported language identifiers
 ///     - language_from_extension(ext): Get language from file extension
 ///
+/// Logging:
+///     The diagnostic messages of the analysis, such as the files which
+///     cannot be analyzed, are forwarded to the `logging` module, under
+///     the `rust_code_analysis` logger.
+///
 /// Example:
 ///     >>> import rust_code_analysis as rca
 ///     >>>
@@ -167,16 +1043,37 @@ fn language_from_extension(extension: &str) -> Option<&'static str> {
 ///     >>> # Get function-level metrics
 ///     >>> for func in result.get_functions():
 ///     ...     print(f"{func.name}: CC={func.metrics.cyclomatic.sum}")
-#[pymodule]
+///
+/// The module does not need the GIL, so it can be imported by the
+/// free-threaded builds of CPython without enabling the GIL again.
+#[pymodule(gil_used = false)]
 fn rust_code_analysis(m: &Bound<'_, PyModule>) -> PyResult<()> {
+    // Another extension may have already installed a logger
+    let _ = pyo3_log::try_init();
+
     m.add_function(wrap_pyfunction!(analyze, m)?)?;
+    m.add_function(wrap_pyfunction!(analyze_range, m)?)?;
     m.add_function(wrap_pyfunction!(analyze_file, m)?)?;
+    m.add_function(wrap_pyfunction!(line_map, m)?)?;
+    m.add_function(wrap_pyfunction!(tokens, m)?)?;
+    m.add_function(wrap_pyfunction!(to_sexp, m)?)?;
+    m.add_function(wrap_pyfunction!(count_nodes, m)?)?;
+    m.add_function(wrap_pyfunction!(analyze_files, m)?)?;
+    m.add_function(wrap_pyfunction!(analyze_many, m)?)?;
+    m.add_function(wrap_pyfunction!(iter_analyze_files, m)?)?;
+    m.add_function(wrap_pyfunction!(analyze_files_arrow, m)?)?;
+    m.add_function(wrap_pyfunction!(analyze_archive, m)?)?;
+    m.add_function(wrap_pyfunction!(set_num_threads, m)?)?;
+    m.add_function(wrap_pyfunction!(get_num_threads, m)?)?;
+    m.add_function(wrap_pyfunction!(describe_metrics, m)?)?;
     m.add_function(wrap_pyfunction!(supported_languages, m)?)?;
     m.add_function(wrap_pyfunction!(language_from_extension, m)?)?;
 
     // Register all classes
     m.add_class::<PySpaceKind>()?;
     m.add_class::<PyFuncSpace>()?;
+    m.add_class::<PySpaceIterator>()?;
+    m.add_class::<PySpaceWalker>()?;
     m.add_class::<PyCodeMetrics>()?;
     m.add_class::<PyCyclomaticMetrics>()?;
     m.add_class::<PyCognitiveMetrics>()?;
@@ -190,6 +1087,28 @@ fn rust_code_analysis(m: &Bound<'_, PyModule>) -> PyResult<()> {
     m.add_class::<PyWmcMetrics>()?;
     m.add_class::<PyNpmMetrics>()?;
     m.add_class::<PyNpaMetrics>()?;
+    m.add_class::<PyPpcMetrics>()?;
+    m.add_class::<PyUnsafetyMetrics>()?;
+    m.add_class::<PyCallbacksMetrics>()?;
+    m.add_class::<PyAnnotationsMetrics>()?;
+    m.add_class::<PyAccessorsMetrics>()?;
+    m.add_class::<PyAssertionsMetrics>()?;
+    m.add_class::<PyLocalsMetrics>()?;
+    m.add_class::<PyKotlinFuncsMetrics>()?;
+    m.add_class::<PyIndentationMetrics>()?;
+    m.add_class::<PyParseQuality>()?;
+    m.add_class::<PyFileMetadata>()?;
+    m.add_class::<PyParseWarning>()?;
+    m.add_class::<PyMetricDescription>()?;
+    m.add_class::<PyFileError>()?;
+    m.add_class::<PyFileClassification>()?;
+    m.add_class::<PyLineSpace>()?;
+    m.add_class::<PyLineMap>()?;
+    m.add_class::<PyToken>()?;
+    m.add_class::<PyNodeSpan>()?;
+    m.add_class::<PyNodeCount>()?;
+    m.add_class::<PyBatchResult>()?;
+    m.add_class::<PyBatchIterator>()?;
 
     Ok(())
 }
@@ -201,15 +1120,348 @@ mod tests {
     #[test]
     fn test_analyze_python() {
         let source = "def foo():\n    pass";
-        let result = analyze(source, "test.py", None).unwrap();
-        assert!(result.metrics.nom.functions >= 1.0);
+        let result = analyze_source(source, "test.py", None, None, None).unwrap();
+        assert!(result.metrics().nom.functions >= 1.0);
     }
 
     #[test]
     fn test_analyze_rust() {
         let source = "fn main() { }";
-        let result = analyze(source, "test.rs", None).unwrap();
-        assert!(result.metrics.nom.functions >= 1.0);
+        let result = analyze_source(source, "test.rs", None, None, None).unwrap();
+        assert!(result.metrics().nom.functions >= 1.0);
+    }
+
+    #[test]
+    fn test_analyze_dialect() {
+        let source = "function Foo(): JSX.Element {\n    return <div>{1}</div>;\n}\n";
+        let result = analyze_source(source, "foo.ts", None, Some("tsx"), None).unwrap();
+        assert_eq!(result.metrics().nom.functions, 1.0);
+
+        assert!(analyze_source(source, "foo.ts", None, Some("cobol"), None).is_err());
+        assert!(analyze_source(source, "foo.ts", None, Some("c"), None).is_err());
+    }
+
+    #[test]
+    fn test_parse_quality() {
+        let clean = analyze_source("def foo():\n    pass\n", "foo.py", None, None, None).unwrap();
+        assert!(clean.parse_quality().unwrap().is_clean());
+
+        let broken = analyze_source("x = (1 + 2\n", "foo.py", None, None, None).unwrap();
+        let quality = broken.parse_quality().unwrap();
+        assert_eq!(quality.errors, 1);
+        assert!(quality.score < 1.);
+        let warnings = broken.warnings();
+        assert_eq!(warnings.len(), 1);
+        assert_eq!(warnings[0].kind, "error");
+    }
+
+    #[test]
+    fn test_space_traversal() {
+        let source =
+            "class A:\n    def foo(self):\n        pass\n\ndef bar():\n    return lambda: 1\n";
+        let result = analyze_source(source, "test.py", None, None, None).unwrap();
+
+        assert_eq!(result.__len__(), 2);
+        assert_eq!(result.__getitem__(0).unwrap().name().as_deref(), Some("A"));
+        assert_eq!(
+            result.__getitem__(-1).unwrap().name().as_deref(),
+            Some("bar")
+        );
+        assert!(result.__getitem__(2).is_err());
+        assert!(result.__getitem__(-3).is_err());
+
+        let mut iterator = result.__iter__();
+        let names: Vec<_> = std::iter::from_fn(|| iterator.__next__())
+            .map(|space| space.name())
+            .collect();
+        assert_eq!(names, [Some("A".into()), Some("bar".into())]);
+
+        let mut walker = result.walk(Some(PySpaceKind::Function));
+        let names: Vec<_> = std::iter::from_fn(|| walker.__next__())
+            .filter_map(|space| space.name())
+            .collect();
+        // The lambdas are part of their function by default
+        assert_eq!(names, ["foo", "bar"]);
+    }
+
+    #[test]
+    fn test_analyze_component() {
+        let source = "<template>\n  <p>{{ n }}</p>\n</template>\n<script setup lang=\"ts\">\nfunction inc(n: number): number {\n  return n + 1;\n}\n</script>\n";
+        let result = analyze_source(source, "Counter.vue", None, None, None).unwrap();
+        let script = result.__getitem__(0).unwrap();
+        assert_eq!(script.name().as_deref(), Some("<script>"));
+        assert_eq!(script.start_line(), 4);
+        assert_eq!(result.metadata().unwrap().template_lines, Some(1));
+    }
+
+    #[test]
+    fn test_analyze_kinds() {
+        let source = "class Foo:\n    def bar(self):\n        def baz():\n            pass\n\ndef qux():\n    pass\n";
+        let names = |space: &PyFuncSpace| -> Vec<_> {
+            (0..space.__len__())
+                .filter_map(|i| space.__getitem__(i as isize).unwrap().name())
+                .collect()
+        };
+
+        let kinds = space_kinds(true, None).unwrap();
+        let result = analyze_source(source, "foo.py", None, None, kinds.as_deref()).unwrap();
+        // The methods of the classes are moved to the unit space
+        assert_eq!(names(&result), ["bar", "qux"]);
+        assert_eq!(names(&result.__getitem__(0).unwrap()), ["baz"]);
+        assert_eq!(result.metrics().nom.functions, 3.0);
+
+        let kinds = space_kinds(false, Some(vec![PySpaceKind::Class])).unwrap();
+        let result = analyze_source(source, "foo.py", None, None, kinds.as_deref()).unwrap();
+        assert_eq!(names(&result), ["Foo"]);
+        assert_eq!(result.__getitem__(0).unwrap().__len__(), 0);
+
+        assert!(space_kinds(false, None).unwrap().is_none());
+        assert!(space_kinds(true, Some(vec![PySpaceKind::Function])).is_err());
+    }
+
+    #[test]
+    fn test_analyze_range() {
+        let source = "def foo():\n    pass\n\ndef bar():\n    pass\n";
+        let space = |start_line, end_line| {
+            let mut space = source_space(source, "foo.py", None, None).unwrap();
+            rca::filter_by_range(&mut space, start_line, end_line);
+            convert_func_space(space)
+        };
+
+        assert_eq!(
+            space(4, 5).__getitem__(0).unwrap().name().as_deref(),
+            Some("bar")
+        );
+        assert_eq!(space(2, 4).__len__(), 2);
+        assert_eq!(space(3, 3).__len__(), 0);
+    }
+
+    #[test]
+    fn test_line_map() {
+        let source = "def foo():\n    pass\n\ndef bar():\n    pass\n";
+        let space = source_space(source, "foo.py", None, None).unwrap();
+        let map = PyLineMap::from(rca::LineMap::new(&space));
+
+        let lines = map.to_lines();
+        assert_eq!(&lines[1..6], [1, 1, 0, 2, 2]);
+        assert_eq!(
+            map.space_at(4).unwrap().qualified_name.as_deref(),
+            Some("foo.bar")
+        );
+        assert!(map.space_at(lines.len()).is_none());
+    }
+
+    #[test]
+    fn test_tokens() {
+        let texts = |source: &str, path: &str, language: Option<&str>| -> Vec<String> {
+            let source_bytes = source.as_bytes().to_vec();
+            let path = Path::new(path);
+            let lang = source_language(&source_bytes, path, language, None)
+                .unwrap()
+                .unwrap();
+            rca::get_tokens(&lang, source_bytes, path, None)
+                .into_iter()
+                .map(|token| PyToken::from(token).text)
+                .collect()
+        };
+
+        assert_eq!(
+            texts("x = 1  # one\n", "foo.py", None),
+            ["x", "=", "1", "# one"]
+        );
+        assert_eq!(texts("let a;", "foo", Some("js")), ["let", "a", ";"]);
+        assert!(
+            source_language(b"", Path::new("foo"), None, None)
+                .unwrap()
+                .is_none()
+        );
+    }
+
+    #[test]
+    fn test_count_nodes() {
+        let source = "fn foo() {\n    bar().unwrap();\n    // baz\n}\n";
+        let path = Path::new("foo.rs");
+        let lang = known_language(source.as_bytes(), path, None, None).unwrap();
+        let kinds = ["call_expression".to_owned(), "comment".to_owned()];
+        let counts: Vec<PyNodeCount> =
+            rca::get_node_counts(&lang, source.as_bytes().to_vec(), path, None, &kinds)
+                .into_iter()
+                .map(Into::into)
+                .collect();
+
+        assert_eq!(counts[0].count, 2);
+        assert_eq!(counts[0].spans[0].start_line, 2);
+        assert_eq!(counts[0].spans[0].start_column, 5);
+        assert_eq!(counts[1].kind, "comment");
+        assert_eq!(counts[1].spans[0].start_line, 3);
+    }
+
+    #[test]
+    fn test_to_sexp() {
+        let sexp = |source: &str, path: &str, byte_ranges| {
+            let source_bytes = source.as_bytes().to_vec();
+            let path = Path::new(path);
+            let lang = known_language(&source_bytes, path, None, None).unwrap();
+            rca::get_sexp(&lang, source_bytes, path, None, byte_ranges)
+        };
+
+        assert_eq!(
+            sexp("x = 1", "foo.py", false),
+            "(module (expression_statement (assignment left: (identifier) right: (integer))))"
+        );
+        assert_eq!(
+            sexp("x", "foo.py", true),
+            "(module [0..1] (expression_statement [0..1] (identifier [0..1])))"
+        );
+        assert!(known_language(b"", Path::new("foo"), None, None).is_err());
+    }
+
+    #[test]
+    fn test_space_filter() {
+        let source = "class Handler:\n    def handle(self, a, b):\n        if a and b:\n            return 1\n        return 2\n\ndef handler_x(a):\n    return a\n";
+        let result = analyze_source(source, "test.py", None, None, None).unwrap();
+        let filter = |kind, name_matches, bounds: &[(&str, f64)]| {
+            let bounds: Vec<_> = bounds
+                .iter()
+                .map(|(key, value)| (key.to_string(), *value))
+                .collect();
+            space_query(kind, name_matches, &bounds).map(|query| {
+                let mut spaces = Vec::new();
+                result.select(&query, &mut spaces);
+                spaces
+                    .iter()
+                    .filter_map(|space| space.name())
+                    .collect::<Vec<_>>()
+            })
+        };
+
+        assert_eq!(filter(None, None, &[]).unwrap().len(), 4);
+        assert_eq!(
+            filter(Some(PySpaceKind::Function), Some("^handle"), &[]).unwrap(),
+            ["handle", "handler_x"]
+        );
+        assert_eq!(
+            filter(
+                Some(PySpaceKind::Function),
+                None,
+                &[("min_cyclomatic", 3.), ("max_loc_sloc", 10.)]
+            )
+            .unwrap(),
+            ["handle"]
+        );
+        assert!(filter(None, None, &[("max_mi", 0.)]).unwrap().is_empty());
+
+        assert!(filter(None, Some("("), &[]).is_err());
+        for key in ["min_foo", "max_loc_foo", "cyclomatic"] {
+            assert!(filter(None, None, &[(key, 1.)]).is_err());
+        }
+    }
+
+    #[test]
+    fn test_metrics_eq_hash() {
+        use std::collections::hash_map::DefaultHasher;
+        use std::hash::{Hash, Hasher};
+
+        let hash = |metrics: &PyCodeMetrics| {
+            let mut hasher = DefaultHasher::new();
+            metrics.hash(&mut hasher);
+            hasher.finish()
+        };
+        let source = "def foo(a):\n    if a:\n        return 1\n";
+        let foo = analyze_source(source, "foo.py", None, None, None)
+            .unwrap()
+            .metrics();
+        let bar = analyze_source(source, "bar.py", None, None, None)
+            .unwrap()
+            .metrics();
+        let empty = analyze_source("a = 1\n", "empty.py", None, None, None)
+            .unwrap()
+            .metrics();
+
+        assert_eq!(foo, bar);
+        assert_eq!(hash(&foo), hash(&bar));
+        assert_ne!(foo, empty);
+        assert!(empty.cyclomatic < foo.cyclomatic);
+
+        // The averages of a file without functions are NaN,
+        // yet its metrics are equal to themselves
+        let other = analyze_source("a = 1\n", "other.py", None, None, None)
+            .unwrap()
+            .metrics();
+        assert!(empty.cognitive.average.is_nan());
+        assert_eq!(empty, other);
+        assert_eq!(hash(&empty), hash(&other));
+        assert_eq!(empty.cmp(&other), std::cmp::Ordering::Equal);
+    }
+
+    #[test]
+    fn test_metrics_sub() {
+        let old = analyze_source("def foo(a):\n    return a\n", "foo.py", None, None, None)
+            .unwrap()
+            .metrics();
+        let source = "def foo(a):\n    if a:\n        return 1\n    return a\n";
+        let new = analyze_source(source, "foo.py", None, None, None)
+            .unwrap()
+            .metrics();
+
+        let delta = new.__sub__(&old);
+        assert_eq!(delta.cyclomatic.sum, 1.);
+        assert_eq!(delta.loc.sloc, 2.);
+        assert_eq!(old.__sub__(&new).cyclomatic.sum, -1.);
+        assert_eq!(new.__sub__(&new).nargs.total_functions, 0.);
+    }
+
+    #[test]
+    fn test_analyze_from_threads() {
+        // The spaces can be shared by the threads of a free-threaded build
+        fn assert_send_sync<T: Send + Sync>() {}
+        assert_send_sync::<PyFuncSpace>();
+
+        let source = "def foo(a):\n    if a:\n        return 1\n";
+        let sums: Vec<_> = (0..8)
+            .map(|_| {
+                std::thread::spawn(move || {
+                    analyze_source(source, "foo.py", None, None, None)
+                        .unwrap()
+                        .metrics()
+                        .cyclomatic
+                        .sum
+                })
+            })
+            .collect::<Vec<_>>()
+            .into_iter()
+            .map(|handle| handle.join().unwrap())
+            .collect();
+        assert!(sums.iter().all(|sum| *sum == sums[0]));
+    }
+
+    #[test]
+    fn test_source_file() {
+        let source = source_file("fn f() {}\n".to_owned(), "f".to_owned(), Some("rust")).unwrap();
+        assert_eq!(source.language, Some(rca::LANG::Rust));
+        assert_eq!(source.path, PathBuf::from("f"));
+        assert!(source_file(String::new(), "f".to_owned(), Some("cobol")).is_err());
+        assert!(
+            source_file(String::new(), "f.py".to_owned(), None)
+                .unwrap()
+                .language
+                .is_none()
+        );
+    }
+
+    #[test]
+    fn test_num_threads() {
+        assert_eq!(num_jobs(Some(3)).unwrap(), 3);
+        assert!(num_jobs(Some(0)).is_err());
+        assert!(set_num_threads(Some(0)).is_err());
+
+        set_num_threads(Some(2)).unwrap();
+        assert_eq!(get_num_threads(), 2);
+        assert_eq!(num_jobs(None).unwrap(), 2);
+        assert_eq!(num_jobs(Some(4)).unwrap(), 4);
+
+        set_num_threads(None).unwrap();
+        assert_eq!(get_num_threads(), default_num_threads());
     }
 
     #[test]
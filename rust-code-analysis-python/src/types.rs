@@ -1,5 +1,110 @@
-use pyo3::prelude::*;
 use ::rust_code_analysis::{self as rca, FuncSpace, SpaceKind};
+use numpy::ndarray::Array2;
+use numpy::{IntoPyArray, PyArray2};
+use pyo3::prelude::*;
+use pyo3::types::PyDict;
+use std::cmp::Ordering;
+use std::hash::{Hash, Hasher};
+use std::sync::Arc;
+
+// Floats implement neither Eq, Ord nor Hash: they are compared and
+// hashed through the same normalized value, so that the averages of
+// the files without functions, which are NaN, are equal to themselves
+trait MetricHash {
+    fn metric_hash<H: Hasher>(&self, state: &mut H);
+}
+
+trait MetricCmp {
+    fn metric_cmp(&self, other: &Self) -> Ordering;
+}
+
+// 0.0 and -0.0 are equal, and so are all the NaNs
+fn normalize(value: f64) -> f64 {
+    if value == 0. {
+        0.
+    } else if value.is_nan() {
+        f64::NAN
+    } else {
+        value
+    }
+}
+
+impl MetricHash for f64 {
+    fn metric_hash<H: Hasher>(&self, state: &mut H) {
+        normalize(*self).to_bits().hash(state);
+    }
+}
+
+impl MetricCmp for f64 {
+    fn metric_cmp(&self, other: &Self) -> Ordering {
+        normalize(*self).total_cmp(&normalize(*other))
+    }
+}
+
+// Subtracts two metrics field by field, producing the signed
+// differences as a metric of the same type
+trait MetricSub {
+    fn metric_sub(&self, other: &Self) -> Self;
+}
+
+impl MetricSub for f64 {
+    fn metric_sub(&self, other: &Self) -> Self {
+        self - other
+    }
+}
+
+// Implements the comparisons, Hash and the subtraction on a metric
+// class by comparing, hashing and subtracting all its fields
+macro_rules! impl_metric {
+    ($name:ident { $($field:ident),* }) => {
+        impl PartialEq for $name {
+            fn eq(&self, other: &Self) -> bool {
+                self.cmp(other) == Ordering::Equal
+            }
+        }
+
+        impl Eq for $name {}
+
+        impl PartialOrd for $name {
+            fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+                Some(self.cmp(other))
+            }
+        }
+
+        // The fields are compared in order of declaration
+        impl Ord for $name {
+            fn cmp(&self, other: &Self) -> Ordering {
+                Ordering::Equal$(.then_with(|| self.$field.metric_cmp(&other.$field)))*
+            }
+        }
+
+        impl MetricCmp for $name {
+            fn metric_cmp(&self, other: &Self) -> Ordering {
+                self.cmp(other)
+            }
+        }
+
+        impl Hash for $name {
+            fn hash<H: Hasher>(&self, state: &mut H) {
+                $(self.$field.metric_hash(state);)*
+            }
+        }
+
+        impl MetricHash for $name {
+            fn metric_hash<H: Hasher>(&self, state: &mut H) {
+                self.hash(state);
+            }
+        }
+
+        impl MetricSub for $name {
+            fn metric_sub(&self, other: &Self) -> Self {
+                $name {
+                    $($field: self.$field.metric_sub(&other.$field),)*
+                }
+            }
+        }
+    };
+}
 
 /// Space kind enum - the type of code space being analyzed
 #[pyclass(eq, eq_int)]
@@ -14,6 +119,7 @@ pub enum PySpaceKind {
     Unit = 6,
     Namespace = 7,
     Interface = 8,
+    Macro = 9,
 }
 
 impl From<SpaceKind> for PySpaceKind {
@@ -28,12 +134,30 @@ impl From<SpaceKind> for PySpaceKind {
             SpaceKind::Unit => PySpaceKind::Unit,
             SpaceKind::Namespace => PySpaceKind::Namespace,
             SpaceKind::Interface => PySpaceKind::Interface,
+            SpaceKind::Macro => PySpaceKind::Macro,
+        }
+    }
+}
+
+impl From<PySpaceKind> for SpaceKind {
+    fn from(kind: PySpaceKind) -> Self {
+        match kind {
+            PySpaceKind::Unknown => SpaceKind::Unknown,
+            PySpaceKind::Function => SpaceKind::Function,
+            PySpaceKind::Class => SpaceKind::Class,
+            PySpaceKind::Struct => SpaceKind::Struct,
+            PySpaceKind::Trait => SpaceKind::Trait,
+            PySpaceKind::Impl => SpaceKind::Impl,
+            PySpaceKind::Unit => SpaceKind::Unit,
+            PySpaceKind::Namespace => SpaceKind::Namespace,
+            PySpaceKind::Interface => SpaceKind::Interface,
+            PySpaceKind::Macro => SpaceKind::Macro,
         }
     }
 }
 
 /// Cyclomatic complexity metrics
-#[pyclass(get_all)]
+#[pyclass(get_all, frozen, eq, ord, hash)]
 #[derive(Clone, Debug)]
 pub struct PyCyclomaticMetrics {
     pub sum: f64,
@@ -42,6 +166,13 @@ pub struct PyCyclomaticMetrics {
     pub max: f64,
 }
 
+impl_metric!(PyCyclomaticMetrics {
+    sum,
+    average,
+    min,
+    max
+});
+
 impl From<&rca::cyclomatic::Stats> for PyCyclomaticMetrics {
     fn from(stats: &rca::cyclomatic::Stats) -> Self {
         PyCyclomaticMetrics {
@@ -55,6 +186,11 @@ impl From<&rca::cyclomatic::Stats> for PyCyclomaticMetrics {
 
 #[pymethods]
 impl PyCyclomaticMetrics {
+    /// Get the signed differences between the values of two metrics
+    pub fn __sub__(&self, other: &Self) -> Self {
+        self.metric_sub(other)
+    }
+
     fn __repr__(&self) -> String {
         format!(
             "CyclomaticMetrics(sum={}, average={:.2}, min={}, max={})",
@@ -64,7 +200,7 @@ impl PyCyclomaticMetrics {
 }
 
 /// Cognitive complexity metrics
-#[pyclass(get_all)]
+#[pyclass(get_all, frozen, eq, ord, hash)]
 #[derive(Clone, Debug)]
 pub struct PyCognitiveMetrics {
     pub sum: f64,
@@ -73,6 +209,13 @@ pub struct PyCognitiveMetrics {
     pub max: f64,
 }
 
+impl_metric!(PyCognitiveMetrics {
+    sum,
+    average,
+    min,
+    max
+});
+
 impl From<&rca::cognitive::Stats> for PyCognitiveMetrics {
     fn from(stats: &rca::cognitive::Stats) -> Self {
         PyCognitiveMetrics {
@@ -86,6 +229,11 @@ impl From<&rca::cognitive::Stats> for PyCognitiveMetrics {
 
 #[pymethods]
 impl PyCognitiveMetrics {
+    /// Get the signed differences between the values of two metrics
+    pub fn __sub__(&self, other: &Self) -> Self {
+        self.metric_sub(other)
+    }
+
     fn __repr__(&self) -> String {
         format!(
             "CognitiveMetrics(sum={}, average={:.2}, min={}, max={})",
@@ -95,7 +243,7 @@ impl PyCognitiveMetrics {
 }
 
 /// Halstead software science metrics
-#[pyclass(get_all)]
+#[pyclass(get_all, frozen, eq, ord, hash)]
 #[derive(Clone, Debug)]
 pub struct PyHalsteadMetrics {
     /// η1 - number of distinct operators
@@ -128,6 +276,23 @@ pub struct PyHalsteadMetrics {
     pub bugs: f64,
 }
 
+impl_metric!(PyHalsteadMetrics {
+    n1,
+    big_n1,
+    n2,
+    big_n2,
+    length,
+    estimated_program_length,
+    purity_ratio,
+    vocabulary,
+    volume,
+    difficulty,
+    level,
+    effort,
+    time,
+    bugs
+});
+
 impl From<&rca::halstead::Stats> for PyHalsteadMetrics {
     fn from(stats: &rca::halstead::Stats) -> Self {
         PyHalsteadMetrics {
@@ -151,6 +316,11 @@ impl From<&rca::halstead::Stats> for PyHalsteadMetrics {
 
 #[pymethods]
 impl PyHalsteadMetrics {
+    /// Get the signed differences between the values of two metrics
+    pub fn __sub__(&self, other: &Self) -> Self {
+        self.metric_sub(other)
+    }
+
     fn __repr__(&self) -> String {
         format!(
             "HalsteadMetrics(volume={:.2}, difficulty={:.2}, effort={:.2}, bugs={:.3})",
@@ -160,7 +330,7 @@ impl PyHalsteadMetrics {
 }
 
 /// Lines of code metrics
-#[pyclass(get_all)]
+#[pyclass(get_all, frozen, eq, ord, hash)]
 #[derive(Clone, Debug)]
 pub struct PyLocMetrics {
     /// Source lines of code
@@ -190,6 +360,29 @@ pub struct PyLocMetrics {
     pub blank_max: f64,
 }
 
+impl_metric!(PyLocMetrics {
+    sloc,
+    ploc,
+    lloc,
+    cloc,
+    blank,
+    sloc_average,
+    ploc_average,
+    lloc_average,
+    cloc_average,
+    blank_average,
+    sloc_min,
+    sloc_max,
+    ploc_min,
+    ploc_max,
+    lloc_min,
+    lloc_max,
+    cloc_min,
+    cloc_max,
+    blank_min,
+    blank_max
+});
+
 impl From<&rca::loc::Stats> for PyLocMetrics {
     fn from(stats: &rca::loc::Stats) -> Self {
         PyLocMetrics {
@@ -219,6 +412,11 @@ impl From<&rca::loc::Stats> for PyLocMetrics {
 
 #[pymethods]
 impl PyLocMetrics {
+    /// Get the signed differences between the values of two metrics
+    pub fn __sub__(&self, other: &Self) -> Self {
+        self.metric_sub(other)
+    }
+
     fn __repr__(&self) -> String {
         format!(
             "LocMetrics(sloc={}, ploc={}, lloc={}, cloc={}, blank={})",
@@ -228,7 +426,7 @@ impl PyLocMetrics {
 }
 
 /// Maintainability Index metrics
-#[pyclass(get_all)]
+#[pyclass(get_all, frozen, eq, ord, hash)]
 #[derive(Clone, Debug)]
 pub struct PyMaintainabilityIndex {
     /// Original MI formula (can be negative)
@@ -239,6 +437,12 @@ pub struct PyMaintainabilityIndex {
     pub mi_visual_studio: f64,
 }
 
+impl_metric!(PyMaintainabilityIndex {
+    mi_original,
+    mi_sei,
+    mi_visual_studio
+});
+
 impl From<&rca::mi::Stats> for PyMaintainabilityIndex {
     fn from(stats: &rca::mi::Stats) -> Self {
         PyMaintainabilityIndex {
@@ -251,6 +455,11 @@ impl From<&rca::mi::Stats> for PyMaintainabilityIndex {
 
 #[pymethods]
 impl PyMaintainabilityIndex {
+    /// Get the signed differences between the values of two metrics
+    pub fn __sub__(&self, other: &Self) -> Self {
+        self.metric_sub(other)
+    }
+
     fn __repr__(&self) -> String {
         format!(
             "MaintainabilityIndex(original={:.2}, sei={:.2}, visual_studio={:.2})",
@@ -260,7 +469,7 @@ impl PyMaintainabilityIndex {
 }
 
 /// ABC metric (Assignments, Branches, Conditions)
-#[pyclass(get_all)]
+#[pyclass(get_all, frozen, eq, ord, hash)]
 #[derive(Clone, Debug)]
 pub struct PyAbcMetrics {
     /// Number of assignments
@@ -282,6 +491,22 @@ pub struct PyAbcMetrics {
     pub conditions_max: f64,
 }
 
+impl_metric!(PyAbcMetrics {
+    assignments,
+    branches,
+    conditions,
+    magnitude,
+    assignments_average,
+    branches_average,
+    conditions_average,
+    assignments_min,
+    assignments_max,
+    branches_min,
+    branches_max,
+    conditions_min,
+    conditions_max
+});
+
 impl From<&rca::abc::Stats> for PyAbcMetrics {
     fn from(stats: &rca::abc::Stats) -> Self {
         PyAbcMetrics {
@@ -304,6 +529,11 @@ impl From<&rca::abc::Stats> for PyAbcMetrics {
 
 #[pymethods]
 impl PyAbcMetrics {
+    /// Get the signed differences between the values of two metrics
+    pub fn __sub__(&self, other: &Self) -> Self {
+        self.metric_sub(other)
+    }
+
     fn __repr__(&self) -> String {
         format!(
             "AbcMetrics(A={}, B={}, C={}, magnitude={:.2})",
@@ -313,7 +543,7 @@ impl PyAbcMetrics {
 }
 
 /// Number of Methods metrics
-#[pyclass(get_all)]
+#[pyclass(get_all, frozen, eq, ord, hash)]
 #[derive(Clone, Debug)]
 pub struct PyNomMetrics {
     /// Number of functions
@@ -331,6 +561,19 @@ pub struct PyNomMetrics {
     pub closures_max: f64,
 }
 
+impl_metric!(PyNomMetrics {
+    functions,
+    closures,
+    total,
+    functions_average,
+    closures_average,
+    average,
+    functions_min,
+    functions_max,
+    closures_min,
+    closures_max
+});
+
 impl From<&rca::nom::Stats> for PyNomMetrics {
     fn from(stats: &rca::nom::Stats) -> Self {
         PyNomMetrics {
@@ -350,6 +593,11 @@ impl From<&rca::nom::Stats> for PyNomMetrics {
 
 #[pymethods]
 impl PyNomMetrics {
+    /// Get the signed differences between the values of two metrics
+    pub fn __sub__(&self, other: &Self) -> Self {
+        self.metric_sub(other)
+    }
+
     fn __repr__(&self) -> String {
         format!(
             "NomMetrics(functions={}, closures={}, total={})",
@@ -359,7 +607,7 @@ impl PyNomMetrics {
 }
 
 /// Number of Arguments metrics
-#[pyclass(get_all)]
+#[pyclass(get_all, frozen, eq, ord, hash)]
 #[derive(Clone, Debug)]
 pub struct PyNargsMetrics {
     /// Total function arguments
@@ -380,6 +628,19 @@ pub struct PyNargsMetrics {
     pub closures_max: f64,
 }
 
+impl_metric!(PyNargsMetrics {
+    total_functions,
+    total_closures,
+    average_functions,
+    average_closures,
+    total,
+    average,
+    functions_min,
+    functions_max,
+    closures_min,
+    closures_max
+});
+
 impl From<&rca::nargs::Stats> for PyNargsMetrics {
     fn from(stats: &rca::nargs::Stats) -> Self {
         PyNargsMetrics {
@@ -399,6 +660,11 @@ impl From<&rca::nargs::Stats> for PyNargsMetrics {
 
 #[pymethods]
 impl PyNargsMetrics {
+    /// Get the signed differences between the values of two metrics
+    pub fn __sub__(&self, other: &Self) -> Self {
+        self.metric_sub(other)
+    }
+
     fn __repr__(&self) -> String {
         format!(
             "NargsMetrics(total={}, average={:.2})",
@@ -408,7 +674,7 @@ impl PyNargsMetrics {
 }
 
 /// Number of Exit Points metrics
-#[pyclass(get_all)]
+#[pyclass(get_all, frozen, eq, ord, hash)]
 #[derive(Clone, Debug)]
 pub struct PyNexitsMetrics {
     pub sum: f64,
@@ -417,6 +683,13 @@ pub struct PyNexitsMetrics {
     pub max: f64,
 }
 
+impl_metric!(PyNexitsMetrics {
+    sum,
+    average,
+    min,
+    max
+});
+
 impl From<&rca::exit::Stats> for PyNexitsMetrics {
     fn from(stats: &rca::exit::Stats) -> Self {
         PyNexitsMetrics {
@@ -430,6 +703,11 @@ impl From<&rca::exit::Stats> for PyNexitsMetrics {
 
 #[pymethods]
 impl PyNexitsMetrics {
+    /// Get the signed differences between the values of two metrics
+    pub fn __sub__(&self, other: &Self) -> Self {
+        self.metric_sub(other)
+    }
+
     fn __repr__(&self) -> String {
         format!(
             "NexitsMetrics(sum={}, average={:.2})",
@@ -439,7 +717,7 @@ impl PyNexitsMetrics {
 }
 
 /// Weighted Methods per Class metrics (OO-specific)
-#[pyclass(get_all)]
+#[pyclass(get_all, frozen, eq, ord, hash)]
 #[derive(Clone, Debug)]
 pub struct PyWmcMetrics {
     /// Sum of CC for all methods in classes
@@ -448,30 +726,53 @@ pub struct PyWmcMetrics {
     pub interfaces: f64,
     /// Total WMC
     pub total: f64,
+    /// Sum of cognitive complexity for all methods in classes
+    pub classes_cognitive: f64,
+    /// Sum of cognitive complexity for all methods in interfaces
+    pub interfaces_cognitive: f64,
+    /// Total WMC weighted by cognitive complexity
+    pub total_cognitive: f64,
 }
 
+impl_metric!(PyWmcMetrics {
+    classes,
+    interfaces,
+    total,
+    classes_cognitive,
+    interfaces_cognitive,
+    total_cognitive
+});
+
 impl From<&rca::wmc::Stats> for PyWmcMetrics {
     fn from(stats: &rca::wmc::Stats) -> Self {
         PyWmcMetrics {
             classes: stats.class_wmc_sum(),
             interfaces: stats.interface_wmc_sum(),
             total: stats.total_wmc(),
+            classes_cognitive: stats.class_cognitive_wmc_sum(),
+            interfaces_cognitive: stats.interface_cognitive_wmc_sum(),
+            total_cognitive: stats.total_cognitive_wmc(),
         }
     }
 }
 
 #[pymethods]
 impl PyWmcMetrics {
+    /// Get the signed differences between the values of two metrics
+    pub fn __sub__(&self, other: &Self) -> Self {
+        self.metric_sub(other)
+    }
+
     fn __repr__(&self) -> String {
         format!(
-            "WmcMetrics(classes={}, interfaces={}, total={})",
-            self.classes, self.interfaces, self.total
+            "WmcMetrics(classes={}, interfaces={}, total={}, total_cognitive={})",
+            self.classes, self.interfaces, self.total, self.total_cognitive
         )
     }
 }
 
 /// Number of Public Methods metrics (OO-specific)
-#[pyclass(get_all)]
+#[pyclass(get_all, frozen, eq, ord, hash)]
 #[derive(Clone, Debug)]
 pub struct PyNpmMetrics {
     pub classes: f64,
@@ -479,6 +780,12 @@ pub struct PyNpmMetrics {
     pub total: f64,
 }
 
+impl_metric!(PyNpmMetrics {
+    classes,
+    interfaces,
+    total
+});
+
 impl From<&rca::npm::Stats> for PyNpmMetrics {
     fn from(stats: &rca::npm::Stats) -> Self {
         PyNpmMetrics {
@@ -491,6 +798,11 @@ impl From<&rca::npm::Stats> for PyNpmMetrics {
 
 #[pymethods]
 impl PyNpmMetrics {
+    /// Get the signed differences between the values of two metrics
+    pub fn __sub__(&self, other: &Self) -> Self {
+        self.metric_sub(other)
+    }
+
     fn __repr__(&self) -> String {
         format!(
             "NpmMetrics(classes={}, interfaces={}, total={})",
@@ -500,7 +812,7 @@ impl PyNpmMetrics {
 }
 
 /// Number of Public Attributes metrics (OO-specific)
-#[pyclass(get_all)]
+#[pyclass(get_all, frozen, eq, ord, hash)]
 #[derive(Clone, Debug)]
 pub struct PyNpaMetrics {
     pub classes: f64,
@@ -508,6 +820,12 @@ pub struct PyNpaMetrics {
     pub total: f64,
 }
 
+impl_metric!(PyNpaMetrics {
+    classes,
+    interfaces,
+    total
+});
+
 impl From<&rca::npa::Stats> for PyNpaMetrics {
     fn from(stats: &rca::npa::Stats) -> Self {
         PyNpaMetrics {
@@ -520,6 +838,11 @@ impl From<&rca::npa::Stats> for PyNpaMetrics {
 
 #[pymethods]
 impl PyNpaMetrics {
+    /// Get the signed differences between the values of two metrics
+    pub fn __sub__(&self, other: &Self) -> Self {
+        self.metric_sub(other)
+    }
+
     fn __repr__(&self) -> String {
         format!(
             "NpaMetrics(classes={}, interfaces={}, total={})",
@@ -528,137 +851,1439 @@ impl PyNpaMetrics {
     }
 }
 
-/// Aggregate of all code metrics for a space
-#[pyclass(get_all)]
+/// Preprocessor Conditionals metrics (C/C++-specific)
+#[pyclass(get_all, frozen, eq, ord, hash)]
 #[derive(Clone, Debug)]
-pub struct PyCodeMetrics {
-    pub cyclomatic: PyCyclomaticMetrics,
-    pub cognitive: PyCognitiveMetrics,
-    pub halstead: PyHalsteadMetrics,
-    pub loc: PyLocMetrics,
-    pub mi: PyMaintainabilityIndex,
-    pub abc: PyAbcMetrics,
-    pub nom: PyNomMetrics,
-    pub nargs: PyNargsMetrics,
-    pub nexits: PyNexitsMetrics,
-    pub wmc: PyWmcMetrics,
-    pub npm: PyNpmMetrics,
-    pub npa: PyNpaMetrics,
+pub struct PyPpcMetrics {
+    pub conditionals: f64,
+    pub branches: f64,
+    pub nesting_max: f64,
 }
 
-impl From<&rca::CodeMetrics> for PyCodeMetrics {
-    fn from(metrics: &rca::CodeMetrics) -> Self {
-        PyCodeMetrics {
-            cyclomatic: (&metrics.cyclomatic).into(),
-            cognitive: (&metrics.cognitive).into(),
-            halstead: (&metrics.halstead).into(),
-            loc: (&metrics.loc).into(),
-            mi: (&metrics.mi).into(),
-            abc: (&metrics.abc).into(),
-            nom: (&metrics.nom).into(),
-            nargs: (&metrics.nargs).into(),
-            nexits: (&metrics.nexits).into(),
-            wmc: (&metrics.wmc).into(),
-            npm: (&metrics.npm).into(),
-            npa: (&metrics.npa).into(),
+impl_metric!(PyPpcMetrics {
+    conditionals,
+    branches,
+    nesting_max
+});
+
+impl From<&rca::ppc::Stats> for PyPpcMetrics {
+    fn from(stats: &rca::ppc::Stats) -> Self {
+        PyPpcMetrics {
+            conditionals: stats.conditionals_sum(),
+            branches: stats.branches_sum(),
+            nesting_max: stats.nesting_max(),
         }
     }
 }
 
 #[pymethods]
-impl PyCodeMetrics {
+impl PyPpcMetrics {
+    /// Get the signed differences between the values of two metrics
+    pub fn __sub__(&self, other: &Self) -> Self {
+        self.metric_sub(other)
+    }
+
     fn __repr__(&self) -> String {
         format!(
-            "CodeMetrics(cc={}, cognitive={}, sloc={}, mi={:.2})",
-            self.cyclomatic.sum, self.cognitive.sum, self.loc.sloc, self.mi.mi_visual_studio
+            "PpcMetrics(conditionals={}, branches={}, nesting_max={})",
+            self.conditionals, self.branches, self.nesting_max
         )
     }
 }
 
-/// A function space containing metrics and nested spaces
-#[pyclass]
+/// Unsafe code metrics (Rust-specific)
+#[pyclass(get_all, frozen, eq, ord, hash)]
 #[derive(Clone, Debug)]
-pub struct PyFuncSpace {
-    #[pyo3(get)]
-    pub name: Option<String>,
-    #[pyo3(get)]
-    pub start_line: usize,
-    #[pyo3(get)]
-    pub end_line: usize,
-    #[pyo3(get)]
-    pub kind: PySpaceKind,
-    #[pyo3(get)]
-    pub metrics: PyCodeMetrics,
-    spaces: Vec<PyFuncSpace>,
+pub struct PyUnsafetyMetrics {
+    pub blocks: f64,
+    pub functions: f64,
+    pub lines: f64,
+    pub ratio: f64,
+}
+
+impl_metric!(PyUnsafetyMetrics {
+    blocks,
+    functions,
+    lines,
+    ratio
+});
+
+impl From<&rca::unsafety::Stats> for PyUnsafetyMetrics {
+    fn from(stats: &rca::unsafety::Stats) -> Self {
+        PyUnsafetyMetrics {
+            blocks: stats.blocks_sum(),
+            functions: stats.functions_sum(),
+            lines: stats.lines(),
+            ratio: stats.ratio(),
+        }
+    }
 }
 
 #[pymethods]
-impl PyFuncSpace {
-    /// Get nested spaces (functions, classes, etc.)
-    #[getter]
-    fn spaces(&self) -> Vec<PyFuncSpace> {
-        self.spaces.clone()
+impl PyUnsafetyMetrics {
+    /// Get the signed differences between the values of two metrics
+    pub fn __sub__(&self, other: &Self) -> Self {
+        self.metric_sub(other)
     }
 
-    /// Recursively collect all function spaces
-    fn get_functions(&self) -> Vec<PyFuncSpace> {
-        let mut result = Vec::new();
-        self.collect_by_kind(&mut result, PySpaceKind::Function);
-        result
+    fn __repr__(&self) -> String {
+        format!(
+            "UnsafetyMetrics(blocks={}, functions={}, lines={}, ratio={:.2})",
+            self.blocks, self.functions, self.lines, self.ratio
+        )
     }
+}
 
-    /// Recursively collect all class spaces
-    fn get_classes(&self) -> Vec<PyFuncSpace> {
-        let mut result = Vec::new();
-        self.collect_by_kind(&mut result, PySpaceKind::Class);
-        result
+/// Callback nesting and promise chain metrics (JavaScript/TypeScript-specific)
+#[pyclass(get_all, frozen, eq, ord, hash)]
+#[derive(Clone, Debug)]
+pub struct PyCallbacksMetrics {
+    pub nesting_max: f64,
+    pub chain_max: f64,
+}
+
+impl_metric!(PyCallbacksMetrics {
+    nesting_max,
+    chain_max
+});
+
+impl From<&rca::callbacks::Stats> for PyCallbacksMetrics {
+    fn from(stats: &rca::callbacks::Stats) -> Self {
+        PyCallbacksMetrics {
+            nesting_max: stats.nesting_max(),
+            chain_max: stats.chain_max(),
+        }
     }
+}
 
-    /// Recursively collect all spaces of any kind
-    fn get_all_spaces(&self) -> Vec<PyFuncSpace> {
-        let mut result = Vec::new();
-        self.collect_all(&mut result);
-        result
+#[pymethods]
+impl PyCallbacksMetrics {
+    /// Get the signed differences between the values of two metrics
+    pub fn __sub__(&self, other: &Self) -> Self {
+        self.metric_sub(other)
     }
 
     fn __repr__(&self) -> String {
         format!(
-            "FuncSpace(name={:?}, kind={:?}, lines={}-{}, cc={})",
-            self.name,
-            self.kind,
-            self.start_line,
-            self.end_line,
-            self.metrics.cyclomatic.sum
+            "CallbacksMetrics(nesting_max={}, chain_max={})",
+            self.nesting_max, self.chain_max
         )
     }
 }
 
-impl PyFuncSpace {
-    fn collect_by_kind(&self, result: &mut Vec<PyFuncSpace>, kind: PySpaceKind) {
-        if self.kind == kind {
-            result.push(self.clone());
+/// Annotation and throws clause metrics (Java-specific)
+#[pyclass(get_all, frozen, eq, ord, hash)]
+#[derive(Clone, Debug)]
+pub struct PyAnnotationsMetrics {
+    pub annotations: f64,
+    pub throws: f64,
+}
+
+impl_metric!(PyAnnotationsMetrics {
+    annotations,
+    throws
+});
+
+impl From<&rca::annotations::Stats> for PyAnnotationsMetrics {
+    fn from(stats: &rca::annotations::Stats) -> Self {
+        PyAnnotationsMetrics {
+            annotations: stats.annotations_sum(),
+            throws: stats.throws_sum(),
+        }
+    }
+}
+
+#[pymethods]
+impl PyAnnotationsMetrics {
+    /// Get the signed differences between the values of two metrics
+    pub fn __sub__(&self, other: &Self) -> Self {
+        self.metric_sub(other)
+    }
+
+    fn __repr__(&self) -> String {
+        format!(
+            "AnnotationsMetrics(annotations={}, throws={})",
+            self.annotations, self.throws
+        )
+    }
+}
+
+/// Property getter and setter metrics
+#[pyclass(get_all, frozen, eq, ord, hash)]
+#[derive(Clone, Debug)]
+pub struct PyAccessorsMetrics {
+    pub getters: f64,
+    pub setters: f64,
+    pub total: f64,
+    pub functions: f64,
+}
+
+impl_metric!(PyAccessorsMetrics {
+    getters,
+    setters,
+    total,
+    functions
+});
+
+impl From<&rca::accessors::Stats> for PyAccessorsMetrics {
+    fn from(stats: &rca::accessors::Stats) -> Self {
+        PyAccessorsMetrics {
+            getters: stats.getters_sum(),
+            setters: stats.setters_sum(),
+            total: stats.total(),
+            functions: stats.functions_sum(),
+        }
+    }
+}
+
+#[pymethods]
+impl PyAccessorsMetrics {
+    /// Get the signed differences between the values of two metrics
+    pub fn __sub__(&self, other: &Self) -> Self {
+        self.metric_sub(other)
+    }
+
+    fn __repr__(&self) -> String {
+        format!(
+            "AccessorsMetrics(getters={}, setters={}, total={}, functions={})",
+            self.getters, self.setters, self.total, self.functions
+        )
+    }
+}
+
+/// Assertion metrics, to measure the quality of the tests
+#[pyclass(get_all, frozen, eq, ord, hash)]
+#[derive(Clone, Debug)]
+pub struct PyAssertionsMetrics {
+    pub sum: f64,
+    pub average: f64,
+    pub min: f64,
+    pub max: f64,
+}
+
+impl_metric!(PyAssertionsMetrics {
+    sum,
+    average,
+    min,
+    max
+});
+
+impl From<&rca::assertions::Stats> for PyAssertionsMetrics {
+    fn from(stats: &rca::assertions::Stats) -> Self {
+        PyAssertionsMetrics {
+            sum: stats.assertions_sum(),
+            average: stats.assertions_average(),
+            min: stats.assertions_min(),
+            max: stats.assertions_max(),
         }
-        for space in &self.spaces {
-            space.collect_by_kind(result, kind);
+    }
+}
+
+#[pymethods]
+impl PyAssertionsMetrics {
+    /// Get the signed differences between the values of two metrics
+    pub fn __sub__(&self, other: &Self) -> Self {
+        self.metric_sub(other)
+    }
+
+    fn __repr__(&self) -> String {
+        format!(
+            "AssertionsMetrics(sum={}, average={:.2})",
+            self.sum, self.average
+        )
+    }
+}
+
+/// Local variable metrics
+#[pyclass(get_all, frozen, eq, ord, hash)]
+#[derive(Clone, Debug)]
+pub struct PyLocalsMetrics {
+    pub sum: f64,
+    pub average: f64,
+    pub min: f64,
+    pub max: f64,
+}
+
+impl_metric!(PyLocalsMetrics {
+    sum,
+    average,
+    min,
+    max
+});
+
+impl From<&rca::locals::Stats> for PyLocalsMetrics {
+    fn from(stats: &rca::locals::Stats) -> Self {
+        PyLocalsMetrics {
+            sum: stats.locals_sum(),
+            average: stats.locals_average(),
+            min: stats.locals_min(),
+            max: stats.locals_max(),
         }
     }
+}
+
+#[pymethods]
+impl PyLocalsMetrics {
+    /// Get the signed differences between the values of two metrics
+    pub fn __sub__(&self, other: &Self) -> Self {
+        self.metric_sub(other)
+    }
+
+    fn __repr__(&self) -> String {
+        format!(
+            "LocalsMetrics(sum={}, average={:.2})",
+            self.sum, self.average
+        )
+    }
+}
 
-    fn collect_all(&self, result: &mut Vec<PyFuncSpace>) {
-        result.push(self.clone());
-        for space in &self.spaces {
-            space.collect_all(result);
+/// Suspend and extension function metrics (Kotlin-specific)
+#[pyclass(get_all, frozen, eq, ord, hash)]
+#[derive(Clone, Debug)]
+pub struct PyKotlinFuncsMetrics {
+    pub suspend_functions: f64,
+    pub extension_functions: f64,
+    pub receiver_lambdas: f64,
+}
+
+impl_metric!(PyKotlinFuncsMetrics {
+    suspend_functions,
+    extension_functions,
+    receiver_lambdas
+});
+
+impl From<&rca::kotlin_funcs::Stats> for PyKotlinFuncsMetrics {
+    fn from(stats: &rca::kotlin_funcs::Stats) -> Self {
+        PyKotlinFuncsMetrics {
+            suspend_functions: stats.suspend_functions_sum(),
+            extension_functions: stats.extension_functions_sum(),
+            receiver_lambdas: stats.receiver_lambdas_sum(),
         }
     }
 }
 
+#[pymethods]
+impl PyKotlinFuncsMetrics {
+    /// Get the signed differences between the values of two metrics
+    pub fn __sub__(&self, other: &Self) -> Self {
+        self.metric_sub(other)
+    }
+
+    fn __repr__(&self) -> String {
+        format!(
+            "KotlinFuncsMetrics(suspend_functions={}, extension_functions={}, receiver_lambdas={})",
+            self.suspend_functions, self.extension_functions, self.receiver_lambdas
+        )
+    }
+}
+
+/// Indentation metrics
+#[pyclass(get_all, frozen, eq, ord, hash)]
+#[derive(Clone, Debug)]
+pub struct PyIndentationMetrics {
+    pub tab_lines: f64,
+    pub space_lines: f64,
+    pub mixed_lines: f64,
+    pub level_max: f64,
+    pub level_average: f64,
+    pub trailing_whitespace: f64,
+}
+
+impl_metric!(PyIndentationMetrics {
+    tab_lines,
+    space_lines,
+    mixed_lines,
+    level_max,
+    level_average,
+    trailing_whitespace
+});
+
+impl From<&rca::indentation::Stats> for PyIndentationMetrics {
+    fn from(stats: &rca::indentation::Stats) -> Self {
+        PyIndentationMetrics {
+            tab_lines: stats.tab_lines(),
+            space_lines: stats.space_lines(),
+            mixed_lines: stats.mixed_lines(),
+            level_max: stats.level_max(),
+            level_average: stats.level_average(),
+            trailing_whitespace: stats.trailing_whitespace(),
+        }
+    }
+}
+
+#[pymethods]
+impl PyIndentationMetrics {
+    /// The indentation style: "none", "tabs", "spaces" or "mixed"
+    #[getter]
+    fn style(&self) -> &'static str {
+        match (
+            self.tab_lines > 0.,
+            self.space_lines > 0.,
+            self.mixed_lines > 0.,
+        ) {
+            (false, false, false) => "none",
+            (true, false, false) => "tabs",
+            (false, true, false) => "spaces",
+            _ => "mixed",
+        }
+    }
+
+    /// Get the signed differences between the values of two metrics
+    pub fn __sub__(&self, other: &Self) -> Self {
+        self.metric_sub(other)
+    }
+
+    fn __repr__(&self) -> String {
+        format!(
+            "IndentationMetrics(style={}, level_max={}, level_average={:.2}, trailing_whitespace={})",
+            self.style(),
+            self.level_max,
+            self.level_average,
+            self.trailing_whitespace
+        )
+    }
+}
+
+/// Aggregate of all code metrics for a space
+#[pyclass(get_all, frozen, eq, ord, hash)]
+#[derive(Clone, Debug)]
+pub struct PyCodeMetrics {
+    pub cyclomatic: PyCyclomaticMetrics,
+    pub cognitive: PyCognitiveMetrics,
+    pub halstead: PyHalsteadMetrics,
+    pub loc: PyLocMetrics,
+    pub mi: PyMaintainabilityIndex,
+    pub abc: PyAbcMetrics,
+    pub nom: PyNomMetrics,
+    pub nargs: PyNargsMetrics,
+    pub nexits: PyNexitsMetrics,
+    pub wmc: PyWmcMetrics,
+    pub npm: PyNpmMetrics,
+    pub npa: PyNpaMetrics,
+    pub ppc: PyPpcMetrics,
+    pub unsafety: PyUnsafetyMetrics,
+    pub callbacks: PyCallbacksMetrics,
+    pub annotations: PyAnnotationsMetrics,
+    pub accessors: PyAccessorsMetrics,
+    pub assertions: PyAssertionsMetrics,
+    pub locals: PyLocalsMetrics,
+    pub kotlin_funcs: PyKotlinFuncsMetrics,
+    pub indentation: PyIndentationMetrics,
+}
+
+impl_metric!(PyCodeMetrics {
+    cyclomatic,
+    cognitive,
+    halstead,
+    loc,
+    mi,
+    abc,
+    nom,
+    nargs,
+    nexits,
+    wmc,
+    npm,
+    npa,
+    ppc,
+    unsafety,
+    callbacks,
+    annotations,
+    accessors,
+    assertions,
+    locals,
+    kotlin_funcs,
+    indentation
+});
+
+impl From<&rca::CodeMetrics> for PyCodeMetrics {
+    fn from(metrics: &rca::CodeMetrics) -> Self {
+        PyCodeMetrics {
+            cyclomatic: (&metrics.cyclomatic).into(),
+            cognitive: (&metrics.cognitive).into(),
+            halstead: (&metrics.halstead).into(),
+            loc: (&metrics.loc).into(),
+            mi: (&metrics.mi).into(),
+            abc: (&metrics.abc).into(),
+            nom: (&metrics.nom).into(),
+            nargs: (&metrics.nargs).into(),
+            nexits: (&metrics.nexits).into(),
+            wmc: (&metrics.wmc).into(),
+            npm: (&metrics.npm).into(),
+            npa: (&metrics.npa).into(),
+            ppc: (&metrics.ppc).into(),
+            unsafety: (&metrics.unsafety).into(),
+            callbacks: (&metrics.callbacks).into(),
+            annotations: (&metrics.annotations).into(),
+            accessors: (&metrics.accessors).into(),
+            assertions: (&metrics.assertions).into(),
+            locals: (&metrics.locals).into(),
+            kotlin_funcs: (&metrics.kotlin_funcs).into(),
+            indentation: (&metrics.indentation).into(),
+        }
+    }
+}
+
+#[pymethods]
+impl PyCodeMetrics {
+    /// Get the signed differences between the values of two metrics
+    pub fn __sub__(&self, other: &Self) -> Self {
+        self.metric_sub(other)
+    }
+
+    fn __repr__(&self) -> String {
+        format!(
+            "CodeMetrics(cc={}, cognitive={}, sloc={}, mi={:.2})",
+            self.cyclomatic.sum, self.cognitive.sum, self.loc.sloc, self.mi.mi_visual_studio
+        )
+    }
+}
+
+/// A function space containing metrics and nested spaces
+///
+/// The spaces of a file share the same Rust tree: the nested spaces and
+/// the metrics are converted to Python objects only when they are accessed.
+#[pyclass]
+#[derive(Clone, Debug)]
+pub struct PyFuncSpace {
+    // The tree of the whole file
+    root: Arc<FuncSpace>,
+    // Indices of the space in the nested spaces, starting from the root
+    indices: Arc<[usize]>,
+}
+
+#[pymethods]
+impl PyFuncSpace {
+    #[getter]
+    pub fn name(&self) -> Option<String> {
+        self.space().name.clone()
+    }
+
+    /// Get the name prefixed by the module of the file and by the names
+    /// of the enclosing spaces, such as "foo.Foo.bar"
+    #[getter]
+    pub fn qualified_name(&self) -> Option<String> {
+        self.space().qualified_name.clone()
+    }
+
+    /// Get an identifier which does not depend on the position
+    /// of the space, to track it across versions of a file
+    #[getter]
+    pub fn id(&self) -> String {
+        self.space().id.clone()
+    }
+
+    #[getter]
+    pub fn start_line(&self) -> usize {
+        self.space().start_line
+    }
+
+    #[getter]
+    pub fn end_line(&self) -> usize {
+        self.space().end_line
+    }
+
+    #[getter]
+    pub fn kind(&self) -> PySpaceKind {
+        self.space().kind.into()
+    }
+
+    /// Get the visibility of the space, such as "public", "protected",
+    /// "private", "crate" or "module-private"
+    #[getter]
+    pub fn visibility(&self) -> Option<String> {
+        self.space()
+            .visibility
+            .map(|visibility| visibility.to_string())
+    }
+
+    /// Check if the space is an async function or a coroutine
+    #[getter]
+    pub fn is_async(&self) -> bool {
+        self.space().flags.is_async
+    }
+
+    /// Check if the space is a generator function
+    #[getter]
+    pub fn is_generator(&self) -> bool {
+        self.space().flags.is_generator
+    }
+
+    /// Check if the space is a constructor
+    #[getter]
+    pub fn is_constructor(&self) -> bool {
+        self.space().flags.is_constructor
+    }
+
+    /// Check if the space is a static method or an associated function
+    #[getter]
+    pub fn is_static(&self) -> bool {
+        self.space().flags.is_static
+    }
+
+    /// Check if the space is a test function
+    #[getter]
+    pub fn is_test(&self) -> bool {
+        self.space().flags.is_test
+    }
+
+    /// Check if the space is a property getter
+    #[getter]
+    pub fn is_getter(&self) -> bool {
+        self.space().flags.is_getter
+    }
+
+    /// Check if the space is a property setter
+    #[getter]
+    pub fn is_setter(&self) -> bool {
+        self.space().flags.is_setter
+    }
+
+    /// Get the names of the decorators, the annotations or the attributes
+    /// of the space, such as "staticmethod", "Override" or "test"
+    #[getter]
+    pub fn decorators(&self) -> Vec<String> {
+        self.space().decorators.clone()
+    }
+
+    /// Get the quality of the parse of the code, only set for the unit space
+    #[getter]
+    pub fn parse_quality(&self) -> Option<PyParseQuality> {
+        self.space().parse_quality.as_ref().map(Into::into)
+    }
+
+    /// Get the syntax errors of the code, only set for the unit space
+    #[getter]
+    pub fn warnings(&self) -> Vec<PyParseWarning> {
+        self.space().warnings.iter().map(Into::into).collect()
+    }
+
+    /// Get the metadata of the file, only set for the unit space
+    #[getter]
+    pub fn metadata(&self) -> Option<PyFileMetadata> {
+        self.space().metadata.as_ref().map(Into::into)
+    }
+
+    /// Get the grade of the cyclomatic complexity, from "A" to "F"
+    #[getter]
+    pub fn cyclomatic_grade(&self) -> String {
+        self.space().grades.cyclomatic.to_string()
+    }
+
+    /// Get the grade of the maintainability index, from "A" to "F"
+    #[getter]
+    pub fn mi_grade(&self) -> String {
+        self.space().grades.mi.to_string()
+    }
+
+    /// Get the metrics of the space
+    #[getter]
+    pub fn metrics(&self) -> PyCodeMetrics {
+        (&self.space().metrics).into()
+    }
+
+    /// Get nested spaces (functions, classes, etc.)
+    #[getter]
+    pub fn spaces(&self) -> Vec<PyFuncSpace> {
+        (0..self.space().spaces.len())
+            .map(|i| self.child(i))
+            .collect()
+    }
+
+    /// Get the number of nested spaces
+    pub fn __len__(&self) -> usize {
+        self.space().spaces.len()
+    }
+
+    /// Get a nested space by its index, which can be negative
+    pub fn __getitem__(&self, index: isize) -> PyResult<PyFuncSpace> {
+        let len = self.space().spaces.len();
+        let position = if index < 0 {
+            len.checked_sub(index.unsigned_abs())
+        } else {
+            Some(index as usize).filter(|position| *position < len)
+        };
+        position
+            .map(|position| self.child(position))
+            .ok_or_else(|| {
+                PyErr::new::<pyo3::exceptions::PyIndexError, _>("space index out of range")
+            })
+    }
+
+    /// Iterate over the nested spaces
+    pub fn __iter__(&self) -> PySpaceIterator {
+        PySpaceIterator {
+            space: self.clone(),
+            index: 0,
+        }
+    }
+
+    /// Iterate over the space and all its nested spaces, depth first,
+    /// optionally keeping only the spaces of a kind
+    ///
+    /// The spaces are created while iterating, so the traversal can be
+    /// stopped early without visiting the whole tree.
+    #[pyo3(signature = (kind=None))]
+    pub fn walk(&self, kind: Option<PySpaceKind>) -> PySpaceWalker {
+        PySpaceWalker {
+            stack: vec![self.clone()],
+            kind: kind.map(Into::into),
+        }
+    }
+
+    /// Get the space and its nested spaces matching all the conditions,
+    /// depth first in the order of the code
+    ///
+    /// The conditions are checked in Rust, without creating
+    /// the spaces which do not match:
+    ///
+    /// * `kind` - The kind of the spaces.
+    /// * `name_matches` - A regular expression matching any part of the name.
+    /// * `min_<metric>` and `max_<metric>` - The lowest and the highest values,
+    ///   both included, of a metric such as `cyclomatic` or of one of its
+    ///   fields such as `loc_sloc`. Without a field, the `sum` field is used,
+    ///   or the first field of the metrics without one.
+    #[pyo3(signature = (kind=None, name_matches=None, **bounds))]
+    pub fn filter(
+        &self,
+        kind: Option<PySpaceKind>,
+        name_matches: Option<&str>,
+        bounds: Option<&Bound<'_, PyDict>>,
+    ) -> PyResult<Vec<PyFuncSpace>> {
+        let bounds = bounds
+            .into_iter()
+            .flatten()
+            .map(|(key, value)| Ok((key.extract()?, value.extract()?)))
+            .collect::<PyResult<Vec<(String, f64)>>>()?;
+        let query = space_query(kind, name_matches, &bounds)?;
+
+        let mut result = Vec::new();
+        self.select(&query, &mut result);
+        Ok(result)
+    }
+
+    /// Get the metrics of the space and of all its nested spaces
+    /// as a NumPy matrix, with one row per space and one column per metric
+    ///
+    /// Returns a tuple with the matrix, the names of its columns and
+    /// the (file, id) pair of the space of each row.
+    pub fn to_numpy<'py>(&self, py: Python<'py>) -> PyResult<NumpyMatrix<'py>> {
+        let mut matrix = py.detach(|| rca::spaces_to_matrix([self.space()]));
+        // The rows belong to the file of the root space
+        if !self.indices.is_empty() {
+            let file = self.root.name.clone().unwrap_or_default();
+            for (row_file, _) in &mut matrix.index {
+                row_file.clone_from(&file);
+            }
+        }
+        numpy_matrix(py, matrix)
+    }
+
+    /// Recursively collect all function spaces
+    fn get_functions(&self) -> Vec<PyFuncSpace> {
+        let mut result = Vec::new();
+        self.collect(&mut result, Some(SpaceKind::Function));
+        result
+    }
+
+    /// Recursively collect all class spaces
+    fn get_classes(&self) -> Vec<PyFuncSpace> {
+        let mut result = Vec::new();
+        self.collect(&mut result, Some(SpaceKind::Class));
+        result
+    }
+
+    /// Recursively collect all spaces of any kind
+    fn get_all_spaces(&self) -> Vec<PyFuncSpace> {
+        let mut result = Vec::new();
+        self.collect(&mut result, None);
+        result
+    }
+
+    fn __repr__(&self) -> String {
+        let space = self.space();
+        format!(
+            "FuncSpace(name={:?}, kind={:?}, lines={}-{}, cc={})",
+            space.name,
+            PySpaceKind::from(space.kind),
+            space.start_line,
+            space.end_line,
+            space.metrics.cyclomatic.cyclomatic_sum()
+        )
+    }
+}
+
+impl PyFuncSpace {
+    fn space(&self) -> &FuncSpace {
+        self.indices
+            .iter()
+            .fold(&self.root, |space, i| &space.spaces[*i])
+    }
+
+    fn child(&self, index: usize) -> PyFuncSpace {
+        PyFuncSpace {
+            root: self.root.clone(),
+            indices: self.indices.iter().copied().chain([index]).collect(),
+        }
+    }
+
+    pub(crate) fn select(&self, query: &rca::SpaceQuery, result: &mut Vec<PyFuncSpace>) {
+        if query.matches(self.space()) {
+            result.push(self.clone());
+        }
+        for i in 0..self.space().spaces.len() {
+            self.child(i).select(query, result);
+        }
+    }
+
+    fn collect(&self, result: &mut Vec<PyFuncSpace>, kind: Option<SpaceKind>) {
+        if kind.is_none_or(|kind| self.space().kind == kind) {
+            result.push(self.clone());
+        }
+        for space in self.spaces() {
+            space.collect(result, kind);
+        }
+    }
+}
+
+// Builds the query of the conditions passed to `PyFuncSpace::filter`
+pub(crate) fn space_query(
+    kind: Option<PySpaceKind>,
+    name_matches: Option<&str>,
+    bounds: &[(String, f64)],
+) -> PyResult<rca::SpaceQuery> {
+    let mut query = rca::SpaceQuery::new();
+    if let Some(kind) = kind {
+        query = query.with_kind(kind.into());
+    }
+    if let Some(pattern) = name_matches {
+        query = query.with_name(pattern).map_err(|e| {
+            PyErr::new::<pyo3::exceptions::PyValueError, _>(format!("Invalid name pattern: {e}"))
+        })?;
+    }
+    for (key, value) in bounds {
+        let (min, max, metric) = match (key.strip_prefix("min_"), key.strip_prefix("max_")) {
+            (Some(metric), _) => (Some(*value), None, metric),
+            (_, Some(metric)) => (None, Some(*value), metric),
+            _ => {
+                return Err(PyErr::new::<pyo3::exceptions::PyTypeError, _>(format!(
+                    "filter() got an unexpected keyword argument '{key}'"
+                )));
+            }
+        };
+        query = metric_name(metric)
+            .and_then(|metric| query.with_bound(&metric, min, max))
+            .ok_or_else(|| {
+                PyErr::new::<pyo3::exceptions::PyValueError, _>(format!("Unknown metric: {metric}"))
+            })?;
+    }
+    Ok(query)
+}
+
+// Converts the name of a metric in a keyword argument, such as `loc_sloc`,
+// into the name of the metric and of its field, such as `loc.sloc`
+fn metric_name(name: &str) -> Option<String> {
+    if rca::describe_metric(name).is_some() {
+        return Some(name.to_owned());
+    }
+    name.match_indices('_').find_map(|(i, _)| {
+        let (metric, field) = (&name[..i], &name[i + 1..]);
+        rca::describe_metric(metric)
+            .filter(|description| description.fields.contains(&field))
+            .map(|_| format!("{metric}.{field}"))
+    })
+}
+
+/// Iterator over the nested spaces of a space
+#[pyclass]
+pub struct PySpaceIterator {
+    space: PyFuncSpace,
+    index: usize,
+}
+
+#[pymethods]
+impl PySpaceIterator {
+    fn __iter__(slf: PyRef<'_, Self>) -> PyRef<'_, Self> {
+        slf
+    }
+
+    pub fn __next__(&mut self) -> Option<PyFuncSpace> {
+        if self.index < self.space.space().spaces.len() {
+            self.index += 1;
+            Some(self.space.child(self.index - 1))
+        } else {
+            None
+        }
+    }
+}
+
+/// Depth first iterator over a space and all its nested spaces
+#[pyclass]
+pub struct PySpaceWalker {
+    // The spaces to visit, the next one is at the end
+    stack: Vec<PyFuncSpace>,
+    kind: Option<SpaceKind>,
+}
+
+#[pymethods]
+impl PySpaceWalker {
+    fn __iter__(slf: PyRef<'_, Self>) -> PyRef<'_, Self> {
+        slf
+    }
+
+    pub fn __next__(&mut self) -> Option<PyFuncSpace> {
+        while let Some(space) = self.stack.pop() {
+            // The nested spaces are pushed in reverse order
+            // to be visited in the order of the code
+            let len = space.space().spaces.len();
+            self.stack.extend((0..len).rev().map(|i| space.child(i)));
+            if self.kind.is_none_or(|kind| space.space().kind == kind) {
+                return Some(space);
+            }
+        }
+        None
+    }
+}
+
+/// A NumPy matrix of metrics, with the names of its columns
+/// and the (file, id) pair of each row
+pub type NumpyMatrix<'py> = (
+    Bound<'py, PyArray2<f64>>,
+    Vec<String>,
+    Vec<(String, String)>,
+);
+
+// Moves the values of a matrix into a NumPy array, without copying them
+fn numpy_matrix(py: Python<'_>, matrix: rca::MetricsMatrix) -> PyResult<NumpyMatrix<'_>> {
+    let shape = (matrix.rows(), matrix.columns.len());
+    let values = Array2::from_shape_vec(shape, matrix.values)
+        .map_err(|e| PyErr::new::<pyo3::exceptions::PyRuntimeError, _>(e.to_string()))?;
+    Ok((values.into_pyarray(py), matrix.columns, matrix.index))
+}
+
+/// The quality of the parse of a code
+#[pyclass(get_all, frozen)]
+#[derive(Clone, Debug)]
+pub struct PyParseQuality {
+    /// Number of ERROR nodes, the code the parser could not recognize
+    pub errors: usize,
+    /// Number of MISSING nodes, the tokens inserted to recover from an error
+    pub missing: usize,
+    /// Number of nodes of the syntax tree
+    pub nodes: usize,
+    /// Share of the nodes neither in an ERROR node nor MISSING, from 0 to 1
+    pub score: f64,
+}
+
+impl From<&rca::ParseQuality> for PyParseQuality {
+    fn from(quality: &rca::ParseQuality) -> Self {
+        PyParseQuality {
+            errors: quality.errors,
+            missing: quality.missing,
+            nodes: quality.nodes,
+            score: quality.score,
+        }
+    }
+}
+
+#[pymethods]
+impl PyParseQuality {
+    /// Check if the code was parsed without errors
+    pub fn is_clean(&self) -> bool {
+        self.errors == 0 && self.missing == 0
+    }
+
+    fn __repr__(&self) -> String {
+        format!(
+            "ParseQuality(errors={}, missing={}, score={:.2})",
+            self.errors, self.missing, self.score
+        )
+    }
+}
+
+/// The metadata of an analyzed file
+#[pyclass(get_all, frozen)]
+#[derive(Clone, Debug)]
+pub struct PyFileMetadata {
+    /// Size of the file in bytes
+    pub size: u64,
+    /// FNV-1a hash of the analyzed code, as 16 hexadecimal digits
+    pub hash: String,
+    /// Language of the code
+    pub language: &'static str,
+    /// Number of lines of the code
+    pub lines: usize,
+    /// Line endings of the code: "none", "lf", "crlf", "cr" or "mixed"
+    pub line_ending: &'static str,
+    /// Last modification time, in seconds since the Unix epoch,
+    /// None when the file is not on the filesystem
+    pub modified: Option<u64>,
+    /// Path of the first file with the same code,
+    /// when the file is analyzed in a batch
+    pub duplicate_of: Option<String>,
+    /// Number of non-blank lines of the template of a Vue or a Svelte component
+    pub template_lines: Option<usize>,
+}
+
+impl From<&rca::FileMetadata> for PyFileMetadata {
+    fn from(metadata: &rca::FileMetadata) -> Self {
+        PyFileMetadata {
+            size: metadata.size,
+            hash: metadata.hash.clone(),
+            language: metadata.language,
+            lines: metadata.lines,
+            line_ending: metadata.line_ending.name(),
+            modified: metadata.modified,
+            duplicate_of: metadata.duplicate_of.clone(),
+            template_lines: metadata.template_lines,
+        }
+    }
+}
+
+#[pymethods]
+impl PyFileMetadata {
+    fn __repr__(&self) -> String {
+        format!(
+            "FileMetadata(size={}, hash={}, language={}, lines={})",
+            self.size, self.hash, self.language, self.lines
+        )
+    }
+}
+
+/// A syntax error of a code, whose metrics are computed anyway
+#[pyclass(get_all, frozen)]
+#[derive(Clone, Debug)]
+pub struct PyParseWarning {
+    /// Kind of the syntax error: "error" or "missing"
+    pub kind: &'static str,
+    /// First line of the syntax error
+    pub start_line: usize,
+    /// First column of the syntax error
+    pub start_column: usize,
+    /// Last line of the syntax error
+    pub end_line: usize,
+    /// Column after the syntax error
+    pub end_column: usize,
+    /// Description of the syntax error
+    pub message: String,
+}
+
+impl From<&rca::ParseWarning> for PyParseWarning {
+    fn from(warning: &rca::ParseWarning) -> Self {
+        PyParseWarning {
+            kind: match warning.kind {
+                rca::ParseWarningKind::Error => "error",
+                rca::ParseWarningKind::Missing => "missing",
+            },
+            start_line: warning.start_line,
+            start_column: warning.start_column,
+            end_line: warning.end_line,
+            end_column: warning.end_column,
+            message: warning.message.clone(),
+        }
+    }
+}
+
+#[pymethods]
+impl PyParseWarning {
+    fn __repr__(&self) -> String {
+        format!(
+            "ParseWarning(line={}, column={}, message={:?})",
+            self.start_line, self.start_column, self.message
+        )
+    }
+}
+
+/// The description of a metric, to explain its values
+#[pyclass(get_all, frozen)]
+#[derive(Clone, Debug)]
+pub struct PyMetricDescription {
+    /// Key of the metric, such as "cyclomatic"
+    pub key: &'static str,
+    /// Human readable name of the metric
+    pub name: &'static str,
+    /// What the metric measures
+    pub description: &'static str,
+    /// Summary of how the metric is computed
+    pub formula: &'static str,
+    /// Fields of the metric
+    pub fields: Vec<&'static str>,
+    /// Lowest value of the metric, None if unbounded
+    pub min: Option<f64>,
+    /// Highest value of the metric, None if unbounded
+    pub max: Option<f64>,
+    /// Whether the higher values are the better ones
+    pub higher_is_better: bool,
+    /// Field the thresholds apply to, None without thresholds
+    pub threshold_field: Option<&'static str>,
+    /// Value from which a function should be reviewed
+    pub warning: Option<f64>,
+    /// Value from which a function should be refactored
+    pub critical: Option<f64>,
+}
+
+impl From<&rca::MetricDescription> for PyMetricDescription {
+    fn from(metric: &rca::MetricDescription) -> Self {
+        PyMetricDescription {
+            key: metric.key,
+            name: metric.name,
+            description: metric.description,
+            formula: metric.formula,
+            fields: metric.fields.to_vec(),
+            min: metric.min,
+            max: metric.max,
+            higher_is_better: metric.higher_is_better,
+            threshold_field: metric.thresholds.map(|thresholds| thresholds.field),
+            warning: metric.thresholds.map(|thresholds| thresholds.warning),
+            critical: metric.thresholds.map(|thresholds| thresholds.critical),
+        }
+    }
+}
+
+#[pymethods]
+impl PyMetricDescription {
+    fn __repr__(&self) -> String {
+        format!(
+            "MetricDescription(key={:?}, name={:?})",
+            self.key, self.name
+        )
+    }
+}
+
+/// An error occurred analyzing a file of a batch
+#[pyclass(get_all)]
+#[derive(Clone, Debug)]
+pub struct PyFileError {
+    /// Path of the file
+    pub path: String,
+    /// Error kind: "io", "encoding" or "parse"
+    pub kind: String,
+    /// Description of the error
+    pub message: String,
+}
+
+impl From<&rca::FileError> for PyFileError {
+    fn from(error: &rca::FileError) -> Self {
+        PyFileError {
+            path: error.path.display().to_string(),
+            kind: error.kind.to_string(),
+            message: error.message.clone(),
+        }
+    }
+}
+
+#[pymethods]
+impl PyFileError {
+    fn __repr__(&self) -> String {
+        format!(
+            "FileError(path={:?}, kind={}, message={:?})",
+            self.path, self.kind, self.message
+        )
+    }
+}
+
+/// Number of files of each class encountered by the analysis of a batch
+#[pyclass(get_all)]
+#[derive(Clone, Debug)]
+pub struct PyFileClassification {
+    /// Number of source files, analyzed even when their analysis fails
+    pub source: usize,
+    /// Number of files which are not text files
+    pub binary: usize,
+    /// Number of text files whose language is not supported
+    pub unsupported: usize,
+    /// Number of files excluded by the globs, hidden or empty
+    pub ignored: usize,
+}
+
+impl From<&rca::FileClassification> for PyFileClassification {
+    fn from(classification: &rca::FileClassification) -> Self {
+        PyFileClassification {
+            source: classification.source,
+            binary: classification.binary,
+            unsupported: classification.unsupported,
+            ignored: classification.ignored,
+        }
+    }
+}
+
+#[pymethods]
+impl PyFileClassification {
+    /// Get the number of files encountered by the analysis
+    pub fn total(&self) -> usize {
+        self.source + self.binary + self.unsupported + self.ignored
+    }
+
+    fn __repr__(&self) -> String {
+        format!(
+            "FileClassification(source={}, binary={}, unsupported={}, ignored={})",
+            self.source, self.binary, self.unsupported, self.ignored
+        )
+    }
+}
+
+/// Token of a code, a leaf of its syntax tree
+#[pyclass(get_all)]
+#[derive(Clone, Debug)]
+pub struct PyToken {
+    /// Kind of the token in the grammar of the language, such as "identifier"
+    pub kind: String,
+    /// Text of the token
+    pub text: String,
+    /// Line where the token starts, starting from 1
+    pub start_line: usize,
+    /// Column where the token starts, in bytes, starting from 1
+    pub start_column: usize,
+    /// Line where the token ends, starting from 1
+    pub end_line: usize,
+    /// Column following the end of the token, in bytes, starting from 1
+    pub end_column: usize,
+    /// Offset of the first byte of the token in the UTF-8 code
+    pub start_byte: usize,
+    /// Offset following the last byte of the token in the UTF-8 code
+    pub end_byte: usize,
+}
+
+impl From<rca::Token> for PyToken {
+    fn from(token: rca::Token) -> Self {
+        PyToken {
+            kind: token.kind.to_owned(),
+            text: token.text,
+            start_line: token.start_line,
+            start_column: token.start_column,
+            end_line: token.end_line,
+            end_column: token.end_column,
+            start_byte: token.start_byte,
+            end_byte: token.end_byte,
+        }
+    }
+}
+
+#[pymethods]
+impl PyToken {
+    fn __repr__(&self) -> String {
+        format!(
+            "Token(kind={:?}, text={:?}, line={}, column={})",
+            self.kind, self.text, self.start_line, self.start_column
+        )
+    }
+}
+
+/// Space of a LineMap
+#[pyclass(get_all)]
+#[derive(Clone, Debug)]
+pub struct PyLineSpace {
+    /// Identifier of the space, as in FuncSpace
+    pub id: String,
+    /// Name of the space prefixed by the names of its enclosing spaces
+    pub qualified_name: Option<String>,
+    /// Kind of the space
+    pub kind: PySpaceKind,
+    /// First line of the space
+    pub start_line: usize,
+    /// Last line of the space
+    pub end_line: usize,
+}
+
+impl From<rca::LineSpace> for PyLineSpace {
+    fn from(space: rca::LineSpace) -> Self {
+        PyLineSpace {
+            id: space.id,
+            qualified_name: space.qualified_name,
+            kind: space.kind.into(),
+            start_line: space.start_line,
+            end_line: space.end_line,
+        }
+    }
+}
+
+#[pymethods]
+impl PyLineSpace {
+    fn __repr__(&self) -> String {
+        format!(
+            "LineSpace(name={:?}, kind={:?}, lines={}-{})",
+            self.qualified_name, self.kind, self.start_line, self.end_line
+        )
+    }
+}
+
+/// Run-length encoded map from the lines of a file to the innermost
+/// space containing each of them
+#[pyclass(get_all)]
+#[derive(Clone, Debug)]
+pub struct PyLineMap {
+    /// Spaces of the file, depth first in the order of the code,
+    /// starting from the unit space
+    pub spaces: Vec<PyLineSpace>,
+    /// Runs of lines as (start_line, end_line, space) tuples, space
+    /// being the index of the space of the lines in spaces
+    pub runs: Vec<(usize, usize, usize)>,
+}
+
+impl From<rca::LineMap> for PyLineMap {
+    fn from(map: rca::LineMap) -> Self {
+        PyLineMap {
+            spaces: map.spaces.into_iter().map(Into::into).collect(),
+            runs: map
+                .runs
+                .into_iter()
+                .map(|run| (run.start_line, run.end_line, run.space))
+                .collect(),
+        }
+    }
+}
+
+#[pymethods]
+impl PyLineMap {
+    /// Get the index in spaces of the space of each line, to be indexed by
+    /// the line numbers: the lines 0 and out of the unit space map to 0
+    pub fn to_lines(&self) -> Vec<usize> {
+        let last = self.runs.last().map_or(0, |run| run.1);
+        let mut lines = vec![0; last + 1];
+        for &(start_line, end_line, space) in &self.runs {
+            lines[start_line..=end_line].fill(space);
+        }
+        lines
+    }
+
+    /// Get the innermost space containing a line, if any
+    pub fn space_at(&self, line: usize) -> Option<PyLineSpace> {
+        let index = self.runs.partition_point(|run| run.1 < line);
+        self.runs
+            .get(index)
+            .filter(|run| run.0 <= line)
+            .map(|run| self.spaces[run.2].clone())
+    }
+
+    fn __repr__(&self) -> String {
+        format!(
+            "LineMap(spaces={}, runs={})",
+            self.spaces.len(),
+            self.runs.len()
+        )
+    }
+}
+
+/// Span of a node of a code
+#[pyclass(get_all)]
+#[derive(Clone, Debug)]
+pub struct PyNodeSpan {
+    /// Kind of the node in the grammar of the language, such as "call_expression"
+    pub kind: String,
+    /// Line where the node starts, starting from 1
+    pub start_line: usize,
+    /// Column where the node starts, in bytes, starting from 1
+    pub start_column: usize,
+    /// Line where the node ends, starting from 1
+    pub end_line: usize,
+    /// Column following the end of the node, in bytes, starting from 1
+    pub end_column: usize,
+    /// Offset of the first byte of the node in the UTF-8 code
+    pub start_byte: usize,
+    /// Offset following the last byte of the node in the UTF-8 code
+    pub end_byte: usize,
+}
+
+impl From<rca::NodeSpan> for PyNodeSpan {
+    fn from(span: rca::NodeSpan) -> Self {
+        PyNodeSpan {
+            kind: span.kind.to_owned(),
+            start_line: span.start_line,
+            start_column: span.start_column,
+            end_line: span.end_line,
+            end_column: span.end_column,
+            start_byte: span.start_byte,
+            end_byte: span.end_byte,
+        }
+    }
+}
+
+#[pymethods]
+impl PyNodeSpan {
+    fn __repr__(&self) -> String {
+        format!(
+            "NodeSpan(kind={:?}, line={}, column={})",
+            self.kind, self.start_line, self.start_column
+        )
+    }
+}
+
+/// Occurrences of a type of nodes in a code
+#[pyclass(get_all)]
+#[derive(Clone, Debug)]
+pub struct PyNodeCount {
+    /// Type of nodes, as given to count_nodes
+    pub kind: String,
+    /// Number of nodes of the type
+    pub count: usize,
+    /// Spans of the nodes of the type, in the order of the code
+    pub spans: Vec<PyNodeSpan>,
+}
+
+impl From<rca::NodeCount> for PyNodeCount {
+    fn from(count: rca::NodeCount) -> Self {
+        PyNodeCount {
+            kind: count.kind,
+            count: count.count,
+            spans: count.spans.into_iter().map(Into::into).collect(),
+        }
+    }
+}
+
+#[pymethods]
+impl PyNodeCount {
+    fn __repr__(&self) -> String {
+        format!("NodeCount(kind={:?}, count={})", self.kind, self.count)
+    }
+}
+
+/// Results of the analysis of a batch of files
+#[pyclass(get_all)]
+#[derive(Clone, Debug)]
+pub struct PyBatchResult {
+    /// Spaces of the successfully analyzed files, sorted by path
+    pub spaces: Vec<PyFuncSpace>,
+    /// Errors occurred analyzing the other files, sorted by path
+    pub errors: Vec<PyFileError>,
+    /// Classes of the files which have been read
+    pub classification: PyFileClassification,
+}
+
+impl From<rca::BatchResults> for PyBatchResult {
+    fn from(results: rca::BatchResults) -> Self {
+        PyBatchResult {
+            spaces: results.spaces.into_iter().map(convert_func_space).collect(),
+            errors: results.errors.iter().map(Into::into).collect(),
+            classification: (&results.classification).into(),
+        }
+    }
+}
+
+#[pymethods]
+impl PyBatchResult {
+    /// Get the metrics of all the spaces of the analyzed files
+    /// as a NumPy matrix, with one row per space and one column per metric
+    ///
+    /// Returns a tuple with the matrix, the names of its columns and
+    /// the (file, id) pair of the space of each row.
+    pub fn to_numpy<'py>(&self, py: Python<'py>) -> PyResult<NumpyMatrix<'py>> {
+        let matrix = py
+            .detach(|| rca::spaces_to_matrix(self.spaces.iter().map(|space| space.root.as_ref())));
+        numpy_matrix(py, matrix)
+    }
+
+    fn __repr__(&self) -> String {
+        format!(
+            "BatchResult(spaces={}, errors={})",
+            self.spaces.len(),
+            self.errors.len()
+        )
+    }
+}
+
 /// Convert from rust-code-analysis FuncSpace to PyFuncSpace
-pub fn convert_func_space(space: &FuncSpace) -> PyFuncSpace {
+pub fn convert_func_space(space: FuncSpace) -> PyFuncSpace {
     PyFuncSpace {
-        name: space.name.clone(),
-        start_line: space.start_line,
-        end_line: space.end_line,
-        kind: space.kind.into(),
-        metrics: (&space.metrics).into(),
-        spaces: space.spaces.iter().map(convert_func_space).collect(),
+        root: Arc::new(space),
+        indices: Arc::new([]),
     }
 }
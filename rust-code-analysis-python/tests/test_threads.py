@@ -0,0 +1,44 @@
+"""Checks that the bindings can be used from many threads at once.
+
+On the free-threaded builds of CPython the module must be imported
+without enabling the GIL again.
+"""
+
+import sys
+import sysconfig
+from concurrent.futures import ThreadPoolExecutor
+
+import rust_code_analysis as rca
+
+SOURCE = """
+def foo(a):
+    if a:
+        return [b for b in range(a) if b % 2]
+    return lambda: a
+"""
+
+
+def analyze(i):
+    result = rca.analyze(SOURCE, f"foo{i}.py")
+    functions = list(result.walk(kind=rca.PySpaceKind.Function))
+    return result.metrics.cyclomatic.sum, [f.name for f in functions]
+
+
+def main():
+    if sysconfig.get_config_var("Py_GIL_DISABLED"):
+        assert not sys._is_gil_enabled(), "importing the module enabled the GIL"
+
+    expected = analyze(0)
+    with ThreadPoolExecutor(max_workers=8) as pool:
+        results = list(pool.map(analyze, range(200)))
+    assert all(result == expected for result in results), results
+
+    rca.set_num_threads(2)
+    assert rca.get_num_threads() == 2
+    rca.set_num_threads(None)
+
+    print(f"OK: {len(results)} analyses on {sys.version}")
+
+
+if __name__ == "__main__":
+    main()
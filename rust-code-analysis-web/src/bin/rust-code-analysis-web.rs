@@ -1,8 +1,11 @@
+use std::path::PathBuf;
+use std::process;
 use std::thread::available_parallelism;
 
 use clap::Parser;
 
-use rust_code_analysis_web::server::run;
+use rust_code_analysis_web::cache::DEFAULT_CACHE_SIZE;
+use rust_code_analysis_web::server::{ServerOptions, run_with_options};
 
 #[derive(Parser, Debug)]
 #[clap(
@@ -21,6 +24,17 @@ struct Opts {
     /// Port for the web server.
     #[clap(long, short, default_value = "8080")]
     port: u16,
+    /// Size of the metrics responses kept in the cache, in MiB,
+    /// 0 to disable it.
+    #[clap(long, value_name = "MIB", default_value_t = DEFAULT_CACHE_SIZE >> 20)]
+    cache_size: usize,
+    /// File containing the token the clients must send
+    /// in an `Authorization: Bearer` header.
+    #[clap(long, value_name = "FILE")]
+    token_file: Option<PathBuf>,
+    /// Number of requests per minute allowed to each client.
+    #[clap(long, value_parser = clap::value_parser!(u32).range(1..))]
+    rate_limit: Option<u32>,
 }
 
 #[actix_web::main]
@@ -33,7 +47,30 @@ async fn main() {
             .get()
     });
 
-    if let Err(e) = run(&opts.host, opts.port, num_jobs).await {
+    // The token is read from a file, so that it is not shown
+    // in the list of the processes
+    let token = opts
+        .token_file
+        .as_ref()
+        .map(|path| match std::fs::read_to_string(path) {
+            Ok(token) if !token.trim().is_empty() => token.trim().to_string(),
+            Ok(_) => {
+                eprintln!("Error: The token file {path:?} is empty");
+                process::exit(1);
+            }
+            Err(e) => {
+                eprintln!("Error: Cannot read the token file {path:?}: {e}");
+                process::exit(1);
+            }
+        });
+
+    let options = ServerOptions {
+        cache_size: opts.cache_size.saturating_mul(1 << 20),
+        token,
+        rate_limit: opts.rate_limit,
+    };
+
+    if let Err(e) = run_with_options(&opts.host, opts.port, num_jobs, options).await {
         eprintln!(
             "Cannot run the server at {}:{}: {}",
             opts.host, opts.port, e
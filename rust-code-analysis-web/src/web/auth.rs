@@ -0,0 +1,221 @@
+use actix_web::{
+    HttpResponse,
+    body::{EitherBody, MessageBody},
+    dev::{ServiceRequest, ServiceResponse},
+    http::header::{AUTHORIZATION, RETRY_AFTER, WWW_AUTHENTICATE},
+    middleware::Next,
+    web,
+};
+use std::collections::HashMap;
+use std::net::IpAddr;
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+// Number of clients above which the idle ones are forgotten
+const MAX_CLIENTS: usize = 10_000;
+// Number of clients kept when they are forgotten, leaving room
+// for the new ones until the next time
+const KEPT_CLIENTS: usize = MAX_CLIENTS - MAX_CLIENTS / 10;
+
+#[derive(Debug)]
+struct Bucket {
+    tokens: f64,
+    last: Instant,
+}
+
+/// A rate limiter allowing each client a number of requests per minute.
+///
+/// Each client has a bucket of `requests_per_minute` tokens, refilled
+/// continuously, so the requests can come in bursts as long as their
+/// average rate does not exceed the limit.
+#[derive(Debug)]
+pub struct RateLimiter {
+    requests_per_minute: u32,
+    buckets: Mutex<HashMap<IpAddr, Bucket>>,
+}
+
+impl RateLimiter {
+    /// Creates a rate limiter allowing `requests_per_minute` requests
+    /// per minute to each client.
+    ///
+    /// # Panics
+    ///
+    /// If `requests_per_minute` is 0.
+    pub fn new(requests_per_minute: u32) -> Self {
+        assert!(requests_per_minute > 0, "The rate limit must be positive");
+        Self {
+            requests_per_minute,
+            buckets: Mutex::new(HashMap::new()),
+        }
+    }
+
+    /// Checks if a client can send a request at a given time.
+    ///
+    /// If the client has exceeded its limit, returns the time to wait
+    /// before sending the next request.
+    pub fn check(&self, client: IpAddr, now: Instant) -> Result<(), Duration> {
+        let capacity = f64::from(self.requests_per_minute);
+        let refill_per_second = capacity / 60.;
+        let mut buckets = self.buckets.lock().unwrap();
+        if buckets.len() >= MAX_CLIENTS && !buckets.contains_key(&client) {
+            forget_clients(&mut buckets, now);
+        }
+
+        let bucket = buckets.entry(client).or_insert(Bucket {
+            tokens: capacity,
+            last: now,
+        });
+        let elapsed = now.duration_since(bucket.last).as_secs_f64();
+        bucket.tokens = (bucket.tokens + elapsed * refill_per_second).min(capacity);
+        bucket.last = now;
+
+        if bucket.tokens >= 1. {
+            bucket.tokens -= 1.;
+            Ok(())
+        } else {
+            Err(Duration::from_secs_f64(
+                (1. - bucket.tokens) / refill_per_second,
+            ))
+        }
+    }
+}
+
+// Forgets the clients idle for a minute, which have a full bucket anyway,
+// then the least recently seen ones until at most `KEPT_CLIENTS` are left
+fn forget_clients(buckets: &mut HashMap<IpAddr, Bucket>, now: Instant) {
+    buckets.retain(|_, bucket| now.duration_since(bucket.last) < Duration::from_secs(60));
+    if buckets.len() <= KEPT_CLIENTS {
+        return;
+    }
+
+    let mut clients: Vec<_> = buckets
+        .iter()
+        .map(|(client, bucket)| (bucket.last, *client))
+        .collect();
+    let excess = clients.len() - KEPT_CLIENTS;
+    clients.select_nth_unstable(excess);
+    for (_, client) in &clients[..excess] {
+        buckets.remove(client);
+    }
+}
+
+/// The access control of the server.
+///
+/// By default, the server accepts all the requests.
+#[derive(Debug, Default)]
+pub struct AccessControl {
+    /// The token the clients must send in an `Authorization: Bearer` header.
+    ///
+    /// If `None`, the requests are not authenticated.
+    pub token: Option<String>,
+    /// The rate limiter of the clients.
+    ///
+    /// If `None`, the requests are not limited.
+    pub rate_limiter: Option<RateLimiter>,
+}
+
+impl AccessControl {
+    // Checks if a request carries the token of the server
+    fn is_authorized(&self, req: &ServiceRequest) -> bool {
+        let Some(token) = &self.token else {
+            return true;
+        };
+        req.headers()
+            .get(AUTHORIZATION)
+            .and_then(|value| value.to_str().ok())
+            .and_then(|value| value.strip_prefix("Bearer "))
+            .is_some_and(|sent| constant_time_eq(sent.trim().as_bytes(), token.as_bytes()))
+    }
+}
+
+// Compares two byte strings in a time which does not depend on
+// their first difference, so the token cannot be guessed by timing
+fn constant_time_eq(a: &[u8], b: &[u8]) -> bool {
+    a.len() == b.len() && a.iter().zip(b).fold(0, |diff, (a, b)| diff | (a ^ b)) == 0
+}
+
+/// Rejects the requests exceeding the rate limit of their client
+/// and the ones without the token of the server.
+///
+/// The `/ping` route is always accessible, so that the health
+/// of the server can be checked without a token.
+pub async fn check_access(
+    req: ServiceRequest,
+    next: Next<impl MessageBody>,
+) -> Result<ServiceResponse<EitherBody<impl MessageBody>>, actix_web::Error> {
+    let Some(access) = req.app_data::<web::Data<AccessControl>>().cloned() else {
+        return Ok(next.call(req).await?.map_into_left_body());
+    };
+
+    if let (Some(limiter), Some(addr)) = (&access.rate_limiter, req.peer_addr())
+        && let Err(wait) = limiter.check(addr.ip(), Instant::now())
+    {
+        let res = HttpResponse::TooManyRequests()
+            .insert_header((RETRY_AFTER, (wait.as_secs_f64().ceil() as u64).to_string()))
+            .finish();
+        return Ok(req.into_response(res).map_into_right_body());
+    }
+
+    if req.path() != "/ping" && !access.is_authorized(&req) {
+        let res = HttpResponse::Unauthorized()
+            .insert_header((WWW_AUTHENTICATE, "Bearer"))
+            .finish();
+        return Ok(req.into_response(res).map_into_right_body());
+    }
+
+    Ok(next.call(req).await?.map_into_left_body())
+}
+
+#[cfg(test)]
+mod tests {
+    use std::net::Ipv4Addr;
+
+    use super::*;
+
+    #[test]
+    fn test_rate_limiter() {
+        let limiter = RateLimiter::new(2);
+        let client = IpAddr::V4(Ipv4Addr::LOCALHOST);
+        let other = IpAddr::V4(Ipv4Addr::new(10, 0, 0, 1));
+        let now = Instant::now();
+
+        assert!(limiter.check(client, now).is_ok());
+        assert!(limiter.check(client, now).is_ok());
+        assert_eq!(limiter.check(client, now), Err(Duration::from_secs(30)));
+        assert!(limiter.check(other, now).is_ok());
+
+        // A token is refilled every 30 seconds
+        assert!(limiter.check(client, now + Duration::from_secs(30)).is_ok());
+        assert!(
+            limiter
+                .check(client, now + Duration::from_secs(30))
+                .is_err()
+        );
+    }
+
+    #[test]
+    fn test_rate_limiter_max_clients() {
+        let limiter = RateLimiter::new(1);
+        let client = |i: usize| IpAddr::V4(Ipv4Addr::from(i as u32));
+        let now = Instant::now();
+
+        for i in 0..=MAX_CLIENTS {
+            let time = now + Duration::from_millis(i as u64);
+            assert!(limiter.check(client(i), time).is_ok());
+        }
+        assert_eq!(limiter.buckets.lock().unwrap().len(), KEPT_CLIENTS + 1);
+
+        // No client is idle, so the least recently seen ones are forgotten
+        let later = now + Duration::from_secs(20);
+        assert!(limiter.check(client(MAX_CLIENTS), later).is_err());
+        assert!(limiter.check(client(MAX_CLIENTS - 1), later).is_err());
+        assert!(limiter.check(client(0), later).is_ok());
+    }
+
+    #[test]
+    fn test_constant_time_eq() {
+        assert!(constant_time_eq(b"secret", b"secret"));
+        assert!(!constant_time_eq(b"secret", b"secreT"));
+        assert!(!constant_time_eq(b"secret", b"secrets"));
+    }
+}
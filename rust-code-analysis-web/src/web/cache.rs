@@ -0,0 +1,260 @@
+use actix_web::http::header::{EntityTag, IfNoneMatch};
+use serde_json::Value;
+use sha2::{Digest, Sha256};
+use std::collections::{HashMap, VecDeque};
+use std::io::{self, Write};
+use std::sync::Mutex;
+
+/// Default number of bytes of the responses kept by the cache of the server.
+pub const DEFAULT_CACHE_SIZE: usize = 64 * 1024 * 1024;
+
+/// The SHA-256 digest of a request, the key of its response.
+pub type RequestDigest = [u8; 32];
+
+#[derive(Debug)]
+struct Entry {
+    value: Value,
+    size: usize,
+}
+
+#[derive(Debug, Default)]
+struct Entries {
+    values: HashMap<RequestDigest, Entry>,
+    // The keys in order of insertion, the oldest one is evicted first
+    order: VecDeque<RequestDigest>,
+    // The sum of the sizes of the entries
+    size: usize,
+}
+
+/// A cache of the responses of the server, keyed by the SHA-256 digest
+/// of the requests which produced them.
+///
+/// The editors send the same code again and again, so its results
+/// are computed once, until they are evicted by newer ones.
+#[derive(Debug)]
+pub struct ResponseCache {
+    max_size: usize,
+    entries: Mutex<Entries>,
+}
+
+impl Default for ResponseCache {
+    fn default() -> Self {
+        Self::new(DEFAULT_CACHE_SIZE)
+    }
+}
+
+impl ResponseCache {
+    /// Creates a cache keeping at most `max_size` bytes of responses,
+    /// measured as the length of their JSON.
+    ///
+    /// A cache with no size keeps nothing.
+    pub fn new(max_size: usize) -> Self {
+        Self {
+            max_size,
+            entries: Mutex::new(Entries::default()),
+        }
+    }
+
+    /// Returns the response cached for a request.
+    pub fn get(&self, key: &CacheKey) -> Option<Value> {
+        let digest = key.digest();
+        let entries = self.entries.lock().unwrap();
+        entries.values.get(&digest).map(|entry| entry.value.clone())
+    }
+
+    /// Caches the response of a request, evicting the oldest ones
+    /// until it fits in the cache.
+    ///
+    /// A response larger than the whole cache is not cached.
+    pub fn insert(&self, key: &CacheKey, value: Value) {
+        // The key is stored twice, in the map and in the queue
+        let size = json_size(&value) + 2 * size_of::<RequestDigest>();
+        if size > self.max_size {
+            return;
+        }
+        let digest = key.digest();
+        let mut entries = self.entries.lock().unwrap();
+        if entries.values.contains_key(&digest) {
+            return;
+        }
+        while entries.size + size > self.max_size
+            && let Some(oldest) = entries.order.pop_front()
+        {
+            if let Some(entry) = entries.values.remove(&oldest) {
+                entries.size -= entry.size;
+            }
+        }
+        entries.values.insert(digest, Entry { value, size });
+        entries.order.push_back(digest);
+        entries.size += size;
+    }
+}
+
+// Counts the bytes written into it
+struct ByteCount(usize);
+
+impl Write for ByteCount {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        self.0 += buf.len();
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        Ok(())
+    }
+}
+
+// Returns the length of the JSON of a value, without serializing it in memory
+fn json_size(value: &Value) -> usize {
+    let mut count = ByteCount(0);
+    // Writing into a counter cannot fail
+    let _ = serde_json::to_writer(&mut count, value);
+    count.0
+}
+
+/// A request whose response only depends on its content.
+///
+/// Its digest is the key of its response in the cache,
+/// and the entity tag of its response.
+pub struct CacheKey<'a> {
+    /// The route of the request.
+    pub route: &'a str,
+    /// The name of the file of the code.
+    pub file_name: &'a str,
+    /// The dialect of the language of the code.
+    pub dialect: Option<&'a str>,
+    /// Flag to consider only unit space metrics.
+    pub unit: bool,
+    /// The code to analyze.
+    pub code: &'a [u8],
+}
+
+impl CacheKey<'_> {
+    /// Returns the SHA-256 digest of the request, so that two requests
+    /// sharing the key of a response in the cache are the same one.
+    pub fn digest(&self) -> RequestDigest {
+        let mut hasher = Sha256::new();
+        let dialect = self.dialect.unwrap_or_default();
+        for field in [self.route, self.file_name, dialect].map(str::as_bytes) {
+            // The lengths keep the fields apart
+            hasher.update((field.len() as u64).to_le_bytes());
+            hasher.update(field);
+        }
+        hasher.update([u8::from(self.dialect.is_some()), u8::from(self.unit)]);
+        hasher.update(self.code);
+        hasher.finalize().into()
+    }
+}
+
+/// Returns the entity tag of the response of a request with a given digest.
+///
+/// The version of the server is in the tag too, so the responses
+/// cached by the clients are not reused by a newer server.
+pub fn entity_tag(digest: &RequestDigest) -> EntityTag {
+    let hex: String = digest.iter().map(|byte| format!("{byte:02x}")).collect();
+    EntityTag::new_strong(format!("{}-{hex}", env!("CARGO_PKG_VERSION")))
+}
+
+/// Checks if a client already has the response with the given entity tag.
+pub fn is_not_modified(if_none_match: Option<&IfNoneMatch>, etag: &EntityTag) -> bool {
+    match if_none_match {
+        Some(IfNoneMatch::Any) => true,
+        Some(IfNoneMatch::Items(tags)) => tags.iter().any(|tag| tag.weak_eq(etag)),
+        None => false,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::*;
+
+    fn key(code: &[u8]) -> CacheKey<'_> {
+        CacheKey {
+            route: "/metrics",
+            file_name: "foo.py",
+            dialect: None,
+            unit: false,
+            code,
+        }
+    }
+
+    #[test]
+    fn test_response_cache_eviction() {
+        // Each entry takes the byte of its JSON and its two keys
+        let cache = ResponseCache::new(2 * 65);
+        cache.insert(&key(b"1"), json!(1));
+        cache.insert(&key(b"2"), json!(2));
+        cache.insert(&key(b"1"), json!(1));
+        cache.insert(&key(b"3"), json!(3));
+
+        assert_eq!(cache.get(&key(b"1")), None);
+        assert_eq!(cache.get(&key(b"2")), Some(json!(2)));
+        assert_eq!(cache.get(&key(b"3")), Some(json!(3)));
+
+        // A larger response evicts as many responses as needed
+        cache.insert(&key(b"4"), json!(1000));
+        assert_eq!(cache.get(&key(b"2")), None);
+        assert_eq!(cache.get(&key(b"3")), None);
+        assert_eq!(cache.get(&key(b"4")), Some(json!(1000)));
+
+        // A response larger than the cache is not cached
+        cache.insert(&key(b"5"), json!("a".repeat(100)));
+        assert_eq!(cache.get(&key(b"5")), None);
+        assert_eq!(cache.get(&key(b"4")), Some(json!(1000)));
+
+        let cache = ResponseCache::new(0);
+        cache.insert(&key(b"1"), json!(1));
+        assert_eq!(cache.get(&key(b"1")), None);
+    }
+
+    #[test]
+    fn test_response_cache_request() {
+        let cache = ResponseCache::default();
+        cache.insert(&key(b"a = 1"), json!(1));
+
+        // The responses are only shared by the same requests
+        let unit = CacheKey {
+            unit: true,
+            ..key(b"a = 1")
+        };
+        let dialect = CacheKey {
+            dialect: Some(""),
+            ..key(b"a = 1")
+        };
+        assert_eq!(cache.get(&key(b"a = 1")), Some(json!(1)));
+        assert_eq!(cache.get(&key(b"a = 2")), None);
+        assert_eq!(cache.get(&unit), None);
+        assert_eq!(cache.get(&dialect), None);
+    }
+
+    #[test]
+    fn test_cache_key() {
+        let key = CacheKey {
+            route: "/metrics",
+            file_name: "foo.py",
+            dialect: None,
+            unit: false,
+            code: b"a = 1",
+        };
+        let unit = CacheKey { unit: true, ..key };
+        let other = CacheKey {
+            code: b"a = 2",
+            ..key
+        };
+
+        assert_ne!(key.digest(), unit.digest());
+        assert_ne!(key.digest(), other.digest());
+        assert_eq!(
+            entity_tag(&key.digest()),
+            entity_tag(&CacheKey { ..key }.digest())
+        );
+        assert_ne!(entity_tag(&key.digest()), entity_tag(&other.digest()));
+
+        let etag = entity_tag(&key.digest());
+        let (version, hex) = etag.tag().rsplit_once('-').unwrap();
+        assert_eq!(version, env!("CARGO_PKG_VERSION"));
+        assert_eq!(hex.len(), 64);
+    }
+}
@@ -11,6 +11,8 @@ pub struct WebCommentPayload {
     pub file_name: String,
     /// Source code with comments to be removed.
     pub code: String,
+    /// Dialect of the source code language.
+    pub dialect: Option<String>,
 }
 
 /// Server response containing the source code without comments.
@@ -29,6 +31,8 @@ pub struct WebCommentResponse {
 pub struct WebCommentInfo {
     /// Source code filename.
     pub file_name: String,
+    /// Dialect of the source code language.
+    pub dialect: Option<String>,
 }
 
 /// Server request configuration.
@@ -12,6 +12,8 @@ pub struct WebFunctionPayload {
     pub file_name: String,
     /// Source code with function spans to be retrieved.
     pub code: String,
+    /// Dialect of the source code language.
+    pub dialect: Option<String>,
 }
 
 /// Server response containing function spans for the requested source code.
@@ -28,6 +30,8 @@ pub struct WebFunctionResponse {
 pub struct WebFunctionInfo {
     /// Source code filename.
     pub file_name: String,
+    /// Dialect of the source code language.
+    pub dialect: Option<String>,
 }
 
 /// Server request configuration.
@@ -0,0 +1,284 @@
+use async_graphql::{
+    EmptyMutation, EmptySubscription, Error, InputObject, Json, Object, Result, Schema,
+};
+use serde_json::Value;
+use std::path::Path;
+
+use rust_code_analysis::{
+    FuncSpace, SpaceQuery, describe_metric, describe_metric_field, get_function_spaces,
+    metric_value,
+};
+
+use super::metrics::{INVALID_KIND, INVALID_METRIC, SPACE_KINDS};
+use super::server::guess_dialect_language;
+
+/// The schema of the GraphQL endpoint.
+pub type AnalysisSchema = Schema<QueryRoot, EmptyMutation, EmptySubscription>;
+
+/// The maximum depth of the fields of a query, which allows
+/// the spaces nested a dozen times to be queried.
+pub const MAX_QUERY_DEPTH: usize = 16;
+
+/// The maximum number of fields of a query.
+pub const MAX_QUERY_COMPLEXITY: usize = 512;
+
+/// Builds the schema of the GraphQL endpoint.
+///
+/// The depth and the complexity of the queries are limited, so that
+/// a small query cannot make the server walk the spaces endlessly.
+pub fn analysis_schema() -> AnalysisSchema {
+    Schema::build(QueryRoot, EmptyMutation, EmptySubscription)
+        .limit_depth(MAX_QUERY_DEPTH)
+        .limit_complexity(MAX_QUERY_COMPLEXITY)
+        .finish()
+}
+
+/// A source code to analyze.
+#[derive(Debug, InputObject)]
+pub struct FileInput {
+    /// Source code filename, used to detect its language.
+    pub file_name: String,
+    /// Source code to analyze.
+    pub code: String,
+    /// Dialect of the source code language.
+    pub dialect: Option<String>,
+}
+
+/// The inclusive range of the values of a metric.
+#[derive(Debug, InputObject)]
+pub struct MetricBound {
+    /// The metric, optionally followed by one of its fields,
+    /// such as `cyclomatic` or `loc.sloc`.
+    pub metric: String,
+    /// The lowest value of the metric.
+    pub min: Option<f64>,
+    /// The highest value of the metric.
+    pub max: Option<f64>,
+}
+
+// Returns the items of a page, starting from `offset`
+fn page<T>(items: impl Iterator<Item = T>, offset: usize, limit: Option<usize>) -> Vec<T> {
+    items
+        .skip(offset)
+        .take(limit.unwrap_or(usize::MAX))
+        .collect()
+}
+
+/// The root of the queries.
+pub struct QueryRoot;
+
+#[Object]
+impl QueryRoot {
+    /// Analyzes some codes, returning at most `limit` of them from `offset`.
+    ///
+    /// The codes out of the page are not analyzed.
+    async fn files(
+        &self,
+        files: Vec<FileInput>,
+        #[graphql(default)] offset: usize,
+        limit: Option<usize>,
+    ) -> FilePage {
+        FilePage {
+            total_count: files.len(),
+            items: page(files.into_iter().map(File::analyze), offset, limit),
+        }
+    }
+}
+
+/// A page of analyzed files.
+pub struct FilePage {
+    total_count: usize,
+    items: Vec<File>,
+}
+
+#[Object]
+impl FilePage {
+    /// The number of files of all the pages.
+    async fn total_count(&self) -> usize {
+        self.total_count
+    }
+
+    /// The files of the page.
+    async fn items(&self) -> &[File] {
+        &self.items
+    }
+}
+
+/// An analyzed file.
+pub struct File {
+    file_name: String,
+    language: Option<&'static str>,
+    error: Option<&'static str>,
+    space: Option<FuncSpace>,
+}
+
+impl File {
+    fn analyze(input: FileInput) -> Self {
+        let path = Path::new(&input.file_name);
+        let code = input.code.into_bytes();
+        let (language, error, space) =
+            match guess_dialect_language(&code, path, input.dialect.as_deref()) {
+                Ok((language, name)) => (
+                    Some(name),
+                    None,
+                    get_function_spaces(&language, code, path, None),
+                ),
+                Err(error) => (None, Some(error), None),
+            };
+        Self {
+            file_name: input.file_name,
+            language,
+            error,
+            space,
+        }
+    }
+}
+
+#[Object]
+impl File {
+    /// Source code filename.
+    async fn file_name(&self) -> &str {
+        &self.file_name
+    }
+
+    /// Source code programming language.
+    async fn language(&self) -> Option<&str> {
+        self.language
+    }
+
+    /// The error which prevented the analysis of the code.
+    async fn error(&self) -> Option<&str> {
+        self.error
+    }
+
+    /// The unit space of the code, containing all its nested spaces.
+    async fn unit(&self) -> Option<Space<'_>> {
+        self.space.as_ref().map(Space)
+    }
+
+    /// The spaces of the code matching all the given conditions, depth
+    /// first in the order of the code, returning at most `limit` of them
+    /// from `offset`.
+    ///
+    /// `name` is a regular expression searched in the names of the spaces.
+    async fn spaces(
+        &self,
+        kind: Option<String>,
+        name: Option<String>,
+        #[graphql(default)] bounds: Vec<MetricBound>,
+        #[graphql(default)] offset: usize,
+        limit: Option<usize>,
+    ) -> Result<SpacePage<'_>> {
+        if kind
+            .as_deref()
+            .is_some_and(|kind| !SPACE_KINDS.contains(&kind))
+        {
+            return Err(Error::new(INVALID_KIND));
+        }
+        let mut query = SpaceQuery::new();
+        if let Some(name) = name {
+            query = query
+                .with_name(&name)
+                .map_err(|e| Error::new(e.to_string()))?;
+        }
+        for bound in bounds {
+            query = query
+                .with_bound(&bound.metric, bound.min, bound.max)
+                .ok_or_else(|| Error::new(INVALID_METRIC))?;
+        }
+
+        let spaces: Vec<_> = self
+            .space
+            .as_ref()
+            .map(|space| query.select(space))
+            .unwrap_or_default()
+            .into_iter()
+            .filter(|space| {
+                kind.as_deref()
+                    .is_none_or(|kind| space.kind.to_string() == kind)
+            })
+            .collect();
+        Ok(SpacePage {
+            total_count: spaces.len(),
+            items: page(spaces.into_iter().map(Space), offset, limit),
+        })
+    }
+}
+
+/// A page of spaces.
+pub struct SpacePage<'a> {
+    total_count: usize,
+    items: Vec<Space<'a>>,
+}
+
+#[Object]
+impl<'a> SpacePage<'a> {
+    /// The number of spaces of all the pages.
+    async fn total_count(&self) -> usize {
+        self.total_count
+    }
+
+    /// The spaces of the page.
+    async fn items(&self) -> &[Space<'a>] {
+        &self.items
+    }
+}
+
+/// A space of a code, such as a function or a class.
+pub struct Space<'a>(&'a FuncSpace);
+
+#[Object]
+impl<'a> Space<'a> {
+    /// The name of the space.
+    async fn name(&self) -> Option<&str> {
+        self.0.name.as_deref()
+    }
+
+    /// The name of the space qualified by the names of its parents.
+    async fn qualified_name(&self) -> Option<&str> {
+        self.0.qualified_name.as_deref()
+    }
+
+    /// The kind of the space, such as `function`.
+    async fn kind(&self) -> String {
+        self.0.kind.to_string()
+    }
+
+    /// The first line of the space.
+    async fn start_line(&self) -> usize {
+        self.0.start_line
+    }
+
+    /// The last line of the space.
+    async fn end_line(&self) -> usize {
+        self.0.end_line
+    }
+
+    /// The value of a metric, optionally followed by one of its fields,
+    /// such as `cyclomatic` or `loc.sloc`.
+    async fn metric(&self, name: String) -> Result<Option<f64>> {
+        if describe_metric_field(&name).is_none() {
+            return Err(Error::new(INVALID_METRIC));
+        }
+        Ok(metric_value(self.0, &name))
+    }
+
+    /// The serialized metrics, only the given ones if any.
+    async fn metrics(&self, names: Option<Vec<String>>) -> Result<Json<Value>> {
+        let mut metrics = serde_json::to_value(&self.0.metrics)?;
+        if let Some(names) = names {
+            if !names.iter().all(|name| describe_metric(name).is_some()) {
+                return Err(Error::new(INVALID_METRIC));
+            }
+            if let Value::Object(values) = &mut metrics {
+                values.retain(|name, _| names.contains(name));
+            }
+        }
+        Ok(Json(metrics))
+    }
+
+    /// The spaces directly nested in the space.
+    async fn spaces(&self) -> Vec<Space<'a>> {
+        self.0.spaces.iter().map(Space).collect()
+    }
+}
@@ -1,8 +1,27 @@
 use serde::{Deserialize, Serialize};
 use serde_json::{self, Value};
+use sha2::{Digest, Sha256};
 use std::path::PathBuf;
 
-use rust_code_analysis::{Callback, FuncSpace, ParserTrait, metrics};
+use rust_code_analysis::{Callback, FuncSpace, ParserTrait, describe_metrics, metrics};
+
+use super::cache::RequestDigest;
+
+pub(crate) const INVALID_METRIC: &str = "The metrics parameter contains an unknown metric";
+pub(crate) const INVALID_KIND: &str = "The kinds parameter contains an unknown space kind";
+
+// The kinds of the spaces which can be requested
+pub(crate) const SPACE_KINDS: &[&str] = &[
+    "function",
+    "class",
+    "struct",
+    "trait",
+    "impl",
+    "unit",
+    "namespace",
+    "interface",
+    "macro",
+];
 
 /// Payload containing source code used to compute metrics.
 #[derive(Debug, Deserialize, Serialize)]
@@ -15,6 +34,8 @@ pub struct WebMetricsPayload {
     pub code: String,
     /// Flag to consider only unit space metrics.
     pub unit: bool,
+    /// Dialect of the source code language.
+    pub dialect: Option<String>,
 }
 
 /// Server response containing metrics for every space present in
@@ -41,6 +62,106 @@ pub struct WebMetricsInfo {
     ///
     /// If `None`, the entire code is considered.
     pub unit: Option<String>,
+    /// Dialect of the source code language.
+    pub dialect: Option<String>,
+}
+
+/// Parts of the metrics requested by a client, as comma-separated lists.
+///
+/// The payloads are dominated by the fields ignored by most clients,
+/// so they can request only some metrics of some spaces.
+#[derive(Debug, Default, Deserialize)]
+pub struct WebMetricsFilter {
+    /// The metrics to return, such as `cyclomatic,loc`.
+    ///
+    /// If `None`, all the metrics are returned.
+    pub metrics: Option<String>,
+    /// The kinds of the nested spaces to return, such as `function`.
+    /// The nested spaces of the removed spaces are kept, and the unit
+    /// space of the code is always returned.
+    ///
+    /// If `None`, all the spaces are returned.
+    pub kinds: Option<String>,
+}
+
+// Splits a comma-separated list
+fn split_list(list: &str) -> impl Iterator<Item = &str> {
+    list.split(',')
+        .map(str::trim)
+        .filter(|item| !item.is_empty())
+}
+
+impl WebMetricsFilter {
+    /// Checks that the requested metrics and space kinds exist.
+    pub fn check(&self) -> Result<(), &'static str> {
+        let metrics = describe_metrics();
+        if let Some(list) = &self.metrics
+            && !split_list(list).all(|name| metrics.iter().any(|metric| metric.key == name))
+        {
+            return Err(INVALID_METRIC);
+        }
+        if let Some(list) = &self.kinds
+            && !split_list(list).all(|kind| SPACE_KINDS.contains(&kind))
+        {
+            return Err(INVALID_KIND);
+        }
+        Ok(())
+    }
+
+    /// Returns the SHA-256 digest of a request whose response is filtered,
+    /// which is the digest of the request when nothing is filtered.
+    pub fn digest(&self, key: RequestDigest) -> RequestDigest {
+        if self.metrics.is_none() && self.kinds.is_none() {
+            return key;
+        }
+        let mut hasher = Sha256::new();
+        hasher.update(key);
+        for list in [&self.metrics, &self.kinds] {
+            let list = list.as_deref().map(str::as_bytes);
+            // The lengths keep the lists apart
+            hasher.update([u8::from(list.is_some())]);
+            hasher.update((list.unwrap_or_default().len() as u64).to_le_bytes());
+            hasher.update(list.unwrap_or_default());
+        }
+        hasher.finalize().into()
+    }
+
+    /// Removes the parts of a metrics response which are not requested.
+    pub fn apply(&self, response: &mut Value) {
+        let metrics: Option<Vec<_>> = self.metrics.as_deref().map(|l| split_list(l).collect());
+        let kinds: Option<Vec<_>> = self.kinds.as_deref().map(|l| split_list(l).collect());
+        if let Some(space) = response.get_mut("spaces").filter(|space| space.is_object()) {
+            filter_space(space, metrics.as_deref(), kinds.as_deref());
+        }
+    }
+}
+
+fn filter_space(space: &mut Value, metrics: Option<&[&str]>, kinds: Option<&[&str]>) {
+    if let Some(metrics) = metrics
+        && let Some(Value::Object(values)) = space.get_mut("metrics")
+    {
+        values.retain(|name, _| metrics.contains(&name.as_str()));
+    }
+    let Some(Value::Array(spaces)) = space.get_mut("spaces") else {
+        return;
+    };
+    let mut kept = Vec::with_capacity(spaces.len());
+    for mut subspace in std::mem::take(spaces) {
+        filter_space(&mut subspace, metrics, kinds);
+        let is_kept = kinds.is_none_or(|kinds| {
+            subspace
+                .get("kind")
+                .and_then(Value::as_str)
+                .is_some_and(|kind| kinds.contains(&kind))
+        });
+        if is_kept {
+            kept.push(subspace);
+        } else if let Some(Value::Array(nested)) = subspace.get_mut("spaces") {
+            // The nested spaces take the place of the removed space
+            kept.append(nested);
+        }
+    }
+    *spaces = kept;
 }
 
 /// Server request configuration.
@@ -1,4 +1,9 @@
+pub mod auth;
+pub mod cache;
 pub mod comment;
 pub mod function;
+pub mod graphql;
 pub mod metrics;
+pub mod openapi;
 pub mod server;
+pub mod stats;
@@ -0,0 +1,513 @@
+use serde_json::{Map, Value, json};
+
+use rust_code_analysis::{LineEnding, MetricDescription, describe_metrics};
+
+// The fields of the metrics which are not numbers
+const STRING_FIELDS: &[&str] = &["style"];
+
+fn schema_ref(name: &str) -> Value {
+    json!({ "$ref": format!("#/components/schemas/{name}") })
+}
+
+fn json_content(schema: &str) -> Value {
+    json!({ "application/json": { "schema": schema_ref(schema) } })
+}
+
+// The body of the requests, either a JSON payload or the code itself
+fn request_body(payload: &str) -> Value {
+    json!({
+        "required": true,
+        "content": {
+            "application/json": { "schema": schema_ref(payload) },
+            "application/octet-stream": {
+                "schema": { "type": "string", "format": "binary" }
+            }
+        }
+    })
+}
+
+// The query parameters of the requests sending the code itself
+fn plain_parameters(unit: bool) -> Vec<Value> {
+    let mut parameters = vec![
+        json!({
+            "name": "file_name",
+            "in": "query",
+            "description": "Name of the file of the code, used to detect its language. \
+                            Required with an `application/octet-stream` body.",
+            "schema": { "type": "string" }
+        }),
+        json!({
+            "name": "dialect",
+            "in": "query",
+            "description": "Dialect of the language of the code, such as `c` or `tsx`.",
+            "schema": { "type": "string" }
+        }),
+    ];
+    if unit {
+        parameters.push(json!({
+            "name": "unit",
+            "in": "query",
+            "description": "`1` or `true` to compute the metrics of the unit space only.",
+            "schema": { "type": "string" }
+        }));
+    }
+    parameters
+}
+
+// The responses of the analysis of a code
+fn responses(ok: Value) -> Value {
+    let mut responses = error_responses();
+    responses.insert("200".to_string(), ok);
+    Value::Object(responses)
+}
+
+// The errors of the analysis of a code
+fn error_responses() -> Map<String, Value> {
+    let responses = json!({
+        "401": { "description": "The token of the server is missing or wrong." },
+        "404": {
+            "description": "The language or the dialect of the code is unknown.",
+            "content": json_content("Error")
+        },
+        "429": {
+            "description": "The client exceeded its rate limit.",
+            "headers": {
+                "Retry-After": {
+                    "description": "Seconds to wait before the next request.",
+                    "schema": { "type": "integer" }
+                }
+            }
+        }
+    });
+    match responses {
+        Value::Object(responses) => responses,
+        _ => unreachable!(),
+    }
+}
+
+fn metrics_parameters() -> Vec<Value> {
+    let mut parameters = vec![json!({
+        "name": "If-None-Match",
+        "in": "header",
+        "description": "The `ETag` of a previous response, \
+                        answered with a 304 status if it is unchanged.",
+        "schema": { "type": "string" }
+    })];
+    parameters.extend(plain_parameters(true));
+    parameters.extend(filter_parameters());
+    parameters
+}
+
+// The query parameters selecting the parts of the metrics
+fn filter_parameters() -> Vec<Value> {
+    vec![
+        json!({
+            "name": "metrics",
+            "in": "query",
+            "description": "Comma-separated metrics to return, such as `cyclomatic,loc`. \
+                            All the metrics are returned by default.",
+            "schema": { "type": "string" }
+        }),
+        json!({
+            "name": "kinds",
+            "in": "query",
+            "description": "Comma-separated kinds of the nested spaces to return, such as \
+                            `function`. The nested spaces of the other spaces take their \
+                            place. All the spaces are returned by default.",
+            "schema": { "type": "string" }
+        }),
+    ]
+}
+
+fn metrics_responses() -> Value {
+    let mut responses = responses(json!({
+        "description": "The metrics of the spaces of the code.",
+        "headers": {
+            "ETag": {
+                "description": "The digest of the request.",
+                "schema": { "type": "string" }
+            }
+        },
+        "content": json_content("WebMetricsResponse")
+    }));
+    responses["304"] = json!({ "description": "The metrics are unchanged." });
+    responses["400"] = json!({
+        "description": "The requested metrics or space kinds are unknown.",
+        "content": json_content("Error")
+    });
+    responses
+}
+
+fn metric_schema(metric: &MetricDescription) -> Value {
+    let properties: Map<String, Value> = metric
+        .fields
+        .iter()
+        .map(|field| {
+            let schema = if STRING_FIELDS.contains(field) {
+                json!({ "type": "string" })
+            } else {
+                // The undefined values, such as the averages
+                // of the spaces without functions, are null
+                json!({ "type": "number", "nullable": true })
+            };
+            (field.to_string(), schema)
+        })
+        .collect();
+    json!({
+        "type": "object",
+        "title": metric.name,
+        "description": metric.description,
+        "properties": properties
+    })
+}
+
+fn code_metrics_schema() -> Value {
+    let properties: Map<String, Value> = describe_metrics()
+        .iter()
+        .map(|metric| (metric.key.to_string(), metric_schema(metric)))
+        .collect();
+    json!({
+        "type": "object",
+        "description": "The metrics of a space. The metrics which do not apply \
+                        to the language of the code are omitted.",
+        "properties": properties
+    })
+}
+
+fn string_enum(values: &[&str]) -> Value {
+    json!({ "type": "string", "enum": values })
+}
+
+fn schemas() -> Value {
+    let grade = string_enum(&["A", "B", "C", "D", "E", "F"]);
+    json!({
+        "Error": {
+            "type": "object",
+            "required": ["id", "error"],
+            "properties": {
+                "id": { "type": "string" },
+                "error": { "type": "string" }
+            }
+        },
+        "AstPayload": {
+            "type": "object",
+            "required": ["id", "file_name", "code", "comment", "span"],
+            "properties": {
+                "id": { "type": "string" },
+                "file_name": { "type": "string" },
+                "code": { "type": "string" },
+                "comment": { "type": "boolean", "description": "If `true`, the comments are ignored." },
+                "span": { "type": "boolean", "description": "If `true`, the spans of the nodes are computed." },
+                "dialect": { "type": "string", "nullable": true }
+            }
+        },
+        "AstNode": {
+            "type": "object",
+            "properties": {
+                "Type": { "type": "string" },
+                "TextValue": { "type": "string" },
+                "Span": {
+                    "type": "array",
+                    "nullable": true,
+                    "description": "The first line, the first column, the last line \
+                                    and the column after the node.",
+                    "items": { "type": "integer" }
+                },
+                "Children": { "type": "array", "items": schema_ref("AstNode") }
+            }
+        },
+        "AstResponse": {
+            "type": "object",
+            "properties": {
+                "id": { "type": "string" },
+                "root": { "allOf": [schema_ref("AstNode")], "nullable": true }
+            }
+        },
+        "WebCommentPayload": {
+            "type": "object",
+            "required": ["id", "file_name", "code"],
+            "properties": {
+                "id": { "type": "string" },
+                "file_name": { "type": "string" },
+                "code": { "type": "string" },
+                "dialect": { "type": "string", "nullable": true }
+            }
+        },
+        "WebCommentResponse": {
+            "type": "object",
+            "properties": {
+                "id": { "type": "string" },
+                "code": {
+                    "type": "array",
+                    "nullable": true,
+                    "description": "The bytes of the code without comments, \
+                                    null if the code has no comments.",
+                    "items": { "type": "integer" }
+                }
+            }
+        },
+        "WebFunctionPayload": {
+            "type": "object",
+            "required": ["id", "file_name", "code"],
+            "properties": {
+                "id": { "type": "string" },
+                "file_name": { "type": "string" },
+                "code": { "type": "string" },
+                "dialect": { "type": "string", "nullable": true }
+            }
+        },
+        "FunctionSpan": {
+            "type": "object",
+            "properties": {
+                "name": { "type": "string" },
+                "start_line": { "type": "integer" },
+                "end_line": { "type": "integer" },
+                "error": { "type": "boolean" }
+            }
+        },
+        "WebFunctionResponse": {
+            "type": "object",
+            "properties": {
+                "id": { "type": "string" },
+                "spans": { "type": "array", "items": schema_ref("FunctionSpan") }
+            }
+        },
+        "WebMetricsPayload": {
+            "type": "object",
+            "required": ["id", "file_name", "code", "unit"],
+            "properties": {
+                "id": { "type": "string" },
+                "file_name": { "type": "string" },
+                "code": { "type": "string" },
+                "unit": { "type": "boolean", "description": "If `true`, only the metrics of the unit space are computed." },
+                "dialect": { "type": "string", "nullable": true }
+            }
+        },
+        "WebMetricsResponse": {
+            "type": "object",
+            "properties": {
+                "id": { "type": "string" },
+                "language": { "type": "string" },
+                "spaces": { "allOf": [schema_ref("FuncSpace")], "nullable": true }
+            }
+        },
+        "FuncSpace": {
+            "type": "object",
+            "description": "A space of the code, such as a function, a class or the whole file.",
+            "properties": {
+                "name": { "type": "string", "nullable": true },
+                "qualified_name": { "type": "string", "nullable": true },
+                "id": { "type": "string" },
+                "start_line": { "type": "integer" },
+                "end_line": { "type": "integer" },
+                "kind": string_enum(&[
+                    "unknown", "function", "class", "struct", "trait", "impl",
+                    "unit", "namespace", "interface", "macro",
+                ]),
+                "visibility": string_enum(&[
+                    "public", "protected", "private", "crate", "module-private",
+                ]),
+                "flags": {
+                    "type": "object",
+                    "properties": {
+                        "is_async": { "type": "boolean" },
+                        "is_generator": { "type": "boolean" },
+                        "is_constructor": { "type": "boolean" },
+                        "is_static": { "type": "boolean" },
+                        "is_test": { "type": "boolean" },
+                        "is_getter": { "type": "boolean" },
+                        "is_setter": { "type": "boolean" }
+                    }
+                },
+                "decorators": { "type": "array", "items": { "type": "string" } },
+                "parse_quality": schema_ref("ParseQuality"),
+                "warnings": { "type": "array", "items": schema_ref("ParseWarning") },
+                "metadata": schema_ref("FileMetadata"),
+                "spaces": { "type": "array", "items": schema_ref("FuncSpace") },
+                "metrics": schema_ref("CodeMetrics"),
+                "grades": {
+                    "type": "object",
+                    "properties": { "cyclomatic": grade, "mi": grade }
+                }
+            }
+        },
+        "ParseQuality": {
+            "type": "object",
+            "description": "The quality of the parse of a code with syntax errors.",
+            "properties": {
+                "errors": { "type": "integer" },
+                "missing": { "type": "integer" },
+                "nodes": { "type": "integer" },
+                "score": { "type": "number" }
+            }
+        },
+        "ParseWarning": {
+            "type": "object",
+            "properties": {
+                "kind": string_enum(&["error", "missing"]),
+                "start_line": { "type": "integer" },
+                "start_column": { "type": "integer" },
+                "end_line": { "type": "integer" },
+                "end_column": { "type": "integer" },
+                "message": { "type": "string" }
+            }
+        },
+        "FileMetadata": {
+            "type": "object",
+            "properties": {
+                "size": { "type": "integer" },
+                "hash": { "type": "string" },
+                "language": { "type": "string" },
+                "lines": { "type": "integer" },
+                "line_ending": string_enum(LineEnding::NAMES),
+                "modified": { "type": "integer" }
+            }
+        },
+        "CodeMetrics": code_metrics_schema()
+    })
+}
+
+/// Returns the OpenAPI 3 document describing the routes of the server
+/// and the schemas of their payloads and of their responses.
+///
+/// The schemas of the metrics are generated from their descriptions,
+/// so they follow the metrics computed by the library.
+pub fn openapi() -> Value {
+    json!({
+        "openapi": "3.0.3",
+        "info": {
+            "title": "rust-code-analysis-web",
+            "description": "Compute and export the metrics of source code.",
+            "version": env!("CARGO_PKG_VERSION")
+        },
+        // The token is only required when the server is run with one
+        "security": [{}, { "bearerAuth": [] }],
+        "paths": {
+            "/ping": {
+                "get": {
+                    "summary": "Check if the server is running",
+                    "security": [],
+                    "responses": { "200": { "description": "The server is running." } }
+                }
+            },
+            "/ast": {
+                "post": {
+                    "summary": "Get the syntax tree of a code",
+                    "requestBody": { "required": true, "content": json_content("AstPayload") },
+                    "responses": responses(json!({
+                        "description": "The syntax tree of the code.",
+                        "content": json_content("AstResponse")
+                    }))
+                }
+            },
+            "/comment": {
+                "post": {
+                    "summary": "Remove the comments of a code",
+                    "parameters": plain_parameters(false),
+                    "requestBody": request_body("WebCommentPayload"),
+                    "responses": responses(json!({
+                        "description": "The code without comments, as a JSON response \
+                                        for a JSON request, or as bytes otherwise.",
+                        "content": {
+                            "application/json": { "schema": schema_ref("WebCommentResponse") },
+                            "application/octet-stream": {
+                                "schema": { "type": "string", "format": "binary" }
+                            }
+                        }
+                    }))
+                }
+            },
+            "/function": {
+                "post": {
+                    "summary": "Get the spans of the functions of a code",
+                    "parameters": plain_parameters(false),
+                    "requestBody": request_body("WebFunctionPayload"),
+                    "responses": responses(json!({
+                        "description": "The spans of the functions.",
+                        "content": json_content("WebFunctionResponse")
+                    }))
+                }
+            },
+            "/metrics": {
+                "post": {
+                    "summary": "Compute the metrics of a code",
+                    "parameters": metrics_parameters(),
+                    "requestBody": request_body("WebMetricsPayload"),
+                    "responses": metrics_responses()
+                },
+                "get": {
+                    "summary": "Get the operational metrics of the server",
+                    "responses": {
+                        "200": {
+                            "description": "The metrics in the text format of Prometheus.",
+                            "content": { "text/plain": { "schema": { "type": "string" } } }
+                        }
+                    }
+                }
+            },
+            "/metrics/stream": {
+                "get": {
+                    "summary": "Stream codes and receive their metrics",
+                    "description": "Upgrades the connection to a WebSocket. Each message \
+                                    of the client is a `WebMetricsPayload`, and each message \
+                                    of the server is a `WebMetricsResponse` or an `Error`, \
+                                    sent as soon as it is computed, so in any order.",
+                    "parameters": filter_parameters(),
+                    "responses": {
+                        "101": { "description": "The connection is upgraded to a WebSocket." },
+                        "400": { "description": "The request is not a WebSocket handshake, or the requested metrics or space kinds are unknown." },
+                        "400": { "description": "The request is not a WebSocket handshake." }
+                    }
+                }
+            },
+            "/graphql": {
+                "post": {
+                    "summary": "Query the metrics of some codes with GraphQL",
+                    "description": "Executes a GraphQL request, whose `files` query analyzes \
+                                    the given codes and selects their spaces and the fields \
+                                    of their metrics. The errors of the query are returned \
+                                    in the `errors` field of a `200` response.",
+                    "requestBody": {
+                        "required": true,
+                        "content": {
+                            "application/json": {
+                                "schema": {
+                                    "type": "object",
+                                    "required": ["query"],
+                                    "properties": {
+                                        "query": { "type": "string" },
+                                        "operationName": { "type": "string" },
+                                        "variables": { "type": "object" }
+                                    }
+                                }
+                            }
+                        }
+                    },
+                    "responses": {
+                        "200": {
+                            "description": "The GraphQL response.",
+                            "content": { "application/json": { "schema": { "type": "object" } } }
+                        }
+                    }
+                }
+            },
+            "/openapi.json": {
+                "get": {
+                    "summary": "Get this document",
+                    "responses": {
+                        "200": {
+                            "description": "The OpenAPI document of the server.",
+                            "content": { "application/json": { "schema": { "type": "object" } } }
+                        }
+                    }
+                }
+            }
+        },
+        "components": {
+            "securitySchemes": {
+                "bearerAuth": { "type": "http", "scheme": "bearer" }
+            },
+            "schemas": schemas()
+        }
+    })
+}
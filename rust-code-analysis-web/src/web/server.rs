@@ -1,18 +1,48 @@
 use actix_web::{
-    App, HttpRequest, HttpResponse, HttpServer, guard, http,
+    App, HttpMessage, HttpRequest, HttpResponse, HttpServer,
+    body::MessageBody,
+    dev::{ServiceRequest, ServiceResponse},
+    guard,
+    http::{
+        self,
+        header::{ETag, IfNoneMatch},
+    },
+    middleware::{Next, from_fn},
     web::{self, BytesMut, Query},
 };
-use futures::StreamExt;
+use actix_ws::{AggregatedMessage, AggregatedMessageStream, Session};
+use futures::{StreamExt, future};
 use serde::{Deserialize, Serialize};
-use std::path::PathBuf;
-
+use serde_json::Value;
+use std::path::{Path, PathBuf};
+use std::pin::pin;
+use std::sync::Arc;
+use std::time::Instant;
+
+use super::auth::{AccessControl, RateLimiter, check_access};
+use super::cache::{CacheKey, DEFAULT_CACHE_SIZE, ResponseCache, entity_tag, is_not_modified};
 use super::comment::{WebCommentCallback, WebCommentCfg, WebCommentInfo, WebCommentPayload};
 use super::function::{WebFunctionCallback, WebFunctionCfg, WebFunctionInfo, WebFunctionPayload};
-use super::metrics::{WebMetricsCallback, WebMetricsCfg, WebMetricsInfo, WebMetricsPayload};
+use super::graphql::{AnalysisSchema, analysis_schema};
+use super::metrics::{
+    WebMetricsCallback, WebMetricsCfg, WebMetricsFilter, WebMetricsInfo, WebMetricsPayload,
+};
+use super::openapi::openapi;
+use super::stats::{Analysis, ServerStats};
 
-use rust_code_analysis::{AstCallback, AstCfg, AstPayload, LANG, action, guess_language};
+use rust_code_analysis::{
+    AstCallback, AstCfg, AstPayload, Dialect, LANG, action, guess_language_with_dialects,
+};
 
 const INVALID_LANGUAGE: &str = "The file extension doesn't correspond to a valid language";
+const INVALID_DIALECT: &str = "The dialect doesn't correspond to a valid dialect";
+const INVALID_MESSAGE: &str = "The message is not a valid metrics payload";
+const ANALYSIS_FAILED: &str = "The analysis of the code failed";
+
+// Maximum size of the codes sent to the server
+const MAX_PAYLOAD_SIZE: usize = 1024 * 1024 * 4;
+// Number of codes of a stream analyzed at the same time
+const STREAM_CONCURRENCY: usize = 4;
 
 #[derive(Debug, Deserialize, Serialize)]
 struct Error {
@@ -29,191 +59,470 @@ async fn get_code(mut body: web::Payload) -> Result<Vec<u8>, actix_web::Error> {
     Ok(code.to_vec())
 }
 
-async fn ast_parser(item: web::Json<AstPayload>) -> HttpResponse {
+// Guesses the language of a code, parsing it as the requested dialect
+pub(crate) fn guess_dialect_language<'a>(
+    buf: &[u8],
+    path: &Path,
+    dialect: Option<&str>,
+) -> Result<(LANG, &'a str), &'static str> {
+    match dialect.map(|name| Dialect::from_name(name).ok_or(INVALID_DIALECT)) {
+        Some(Err(error)) => Err(error),
+        dialect => {
+            let dialects: Vec<_> = dialect.into_iter().flatten().collect();
+            match guess_language_with_dialects(buf, path, &dialects) {
+                (Some(language), name) => Ok((language, name)),
+                (None, _) => Err(INVALID_LANGUAGE),
+            }
+        }
+    }
+}
+
+// Guesses the language of a code, parsing it as the requested dialect,
+// and records it for the server metrics
+fn guess_web_language<'a>(
+    req: &HttpRequest,
+    buf: &[u8],
+    path: &Path,
+    dialect: Option<&str>,
+) -> Result<(LANG, &'a str), &'static str> {
+    let guess = guess_dialect_language(buf, path, dialect);
+    req.extensions_mut().insert(Analysis {
+        language: guess.as_ref().ok().map(|(language, _)| language.get_name()),
+        syntax_errors: false,
+    });
+    guess
+}
+
+async fn ast_parser(item: web::Json<AstPayload>, req: HttpRequest) -> HttpResponse {
     let path = PathBuf::from(&item.file_name);
     let payload = item.into_inner();
     let buf = payload.code.into_bytes();
-    let (language, _) = guess_language(&buf, path);
-    if let Some(language) = language {
-        let cfg = AstCfg {
-            id: payload.id,
-            comment: payload.comment,
-            span: payload.span,
-        };
-
-        // TODO: the 4th arg should be preproc data
-        HttpResponse::Ok().json(action::<AstCallback>(
-            &language,
-            buf,
-            &PathBuf::from(""),
-            None,
-            cfg,
-        ))
-    } else {
-        HttpResponse::NotFound().json(Error {
+    match guess_web_language(&req, &buf, &path, payload.dialect.as_deref()) {
+        Ok((language, _)) => {
+            let cfg = AstCfg {
+                id: payload.id,
+                comment: payload.comment,
+                span: payload.span,
+            };
+
+            // TODO: the 4th arg should be preproc data
+            HttpResponse::Ok().json(action::<AstCallback>(
+                &language,
+                buf,
+                &PathBuf::from(""),
+                None,
+                cfg,
+            ))
+        }
+        Err(error) => HttpResponse::NotFound().json(Error {
             id: payload.id,
-            error: INVALID_LANGUAGE,
-        })
+            error,
+        }),
     }
 }
 
-async fn comment_removal_json(item: web::Json<WebCommentPayload>) -> HttpResponse {
+async fn comment_removal_json(
+    item: web::Json<WebCommentPayload>,
+    req: HttpRequest,
+) -> HttpResponse {
     let path = PathBuf::from(&item.file_name);
     let payload = item.into_inner();
     let buf = payload.code.into_bytes();
-    let (language, _) = guess_language(&buf, path);
-    if let Some(language) = language {
-        let cfg = WebCommentCfg { id: payload.id };
-        let language = if language == LANG::Cpp {
-            LANG::Ccomment
-        } else {
-            language
-        };
-        HttpResponse::Ok().json(action::<WebCommentCallback>(
-            &language,
-            buf,
-            &PathBuf::from(""),
-            None,
-            cfg,
-        ))
-    } else {
-        HttpResponse::NotFound().json(Error {
+    match guess_web_language(&req, &buf, &path, payload.dialect.as_deref()) {
+        Ok((language, _)) => {
+            let cfg = WebCommentCfg { id: payload.id };
+            let language = if language == LANG::Cpp {
+                LANG::Ccomment
+            } else {
+                language
+            };
+            HttpResponse::Ok().json(action::<WebCommentCallback>(
+                &language,
+                buf,
+                &PathBuf::from(""),
+                None,
+                cfg,
+            ))
+        }
+        Err(error) => HttpResponse::NotFound().json(Error {
             id: payload.id,
-            error: INVALID_LANGUAGE,
-        })
+            error,
+        }),
     }
 }
 
 async fn comment_removal_plain(
     body: web::Payload,
     info: Query<WebCommentInfo>,
+    req: HttpRequest,
 ) -> Result<HttpResponse, actix_web::Error> {
     let buf = get_code(body).await?;
     let path = PathBuf::from(&info.file_name);
-    let (language, _) = guess_language(&buf, path);
-    if let Some(language) = language {
-        let cfg = WebCommentCfg { id: "".to_string() };
-        let res = action::<WebCommentCallback>(&language, buf, &PathBuf::from(""), None, cfg);
-        if let Some(res_code) = res.code {
-            Ok(HttpResponse::Ok()
-                .append_header((http::header::CONTENT_TYPE, "application/octet-stream"))
-                .body(res_code))
-        } else {
-            Ok(HttpResponse::NoContent()
-                .append_header((http::header::CONTENT_TYPE, "application/octet-stream"))
-                .body(()))
+    match guess_web_language(&req, &buf, &path, info.dialect.as_deref()) {
+        Ok((language, _)) => {
+            let cfg = WebCommentCfg { id: "".to_string() };
+            let res = action::<WebCommentCallback>(&language, buf, &PathBuf::from(""), None, cfg);
+            if let Some(res_code) = res.code {
+                Ok(HttpResponse::Ok()
+                    .append_header((http::header::CONTENT_TYPE, "application/octet-stream"))
+                    .body(res_code))
+            } else {
+                Ok(HttpResponse::NoContent()
+                    .append_header((http::header::CONTENT_TYPE, "application/octet-stream"))
+                    .body(()))
+            }
         }
-    } else {
-        Ok(HttpResponse::NotFound()
+        Err(error) => Ok(HttpResponse::NotFound()
             .append_header((http::header::CONTENT_TYPE, "text/plain"))
-            .body(format!("error: {INVALID_LANGUAGE}")))
+            .body(format!("error: {error}"))),
     }
 }
 
-async fn metrics_json(item: web::Json<WebMetricsPayload>) -> HttpResponse {
-    let path = PathBuf::from(&item.file_name);
+// Computes the metrics of a code in a given language,
+// or reuses the ones cached for the same request
+fn metrics_response(
+    key: &CacheKey,
+    cache: &ResponseCache,
+    language: LANG,
+    name: &str,
+    id: String,
+) -> Value {
+    if let Some(mut response) = cache.get(key) {
+        response["id"] = Value::String(id);
+        return response;
+    }
+    let cfg = WebMetricsCfg {
+        id,
+        path: PathBuf::from(key.file_name),
+        unit: key.unit,
+        language: name.to_string(),
+    };
+    let response =
+        action::<WebMetricsCallback>(&language, key.code.to_vec(), &PathBuf::from(""), None, cfg);
+    cache.insert(key, response.clone());
+    response
+}
+
+// Computes the metrics of a code, or reuses the ones cached for the same request
+fn cached_metrics(
+    req: &HttpRequest,
+    key: &CacheKey,
+    filter: &WebMetricsFilter,
+    cache: &ResponseCache,
+    if_none_match: Option<web::Header<IfNoneMatch>>,
+    id: String,
+) -> Result<HttpResponse, &'static str> {
+    // The filtered responses are different representations of the metrics
+    let etag = entity_tag(&filter.digest(key.digest()));
+    // The metrics only depend on the request, so a client
+    // which has them already does not need them again
+    if is_not_modified(if_none_match.as_deref(), &etag) {
+        return Ok(HttpResponse::NotModified()
+            .insert_header(ETag(etag))
+            .finish());
+    }
+
+    let path = PathBuf::from(key.file_name);
+    let (language, name) = guess_web_language(req, key.code, &path, key.dialect)?;
+    let mut response = metrics_response(key, cache, language, name, id);
+    if response["spaces"].get("parse_quality").is_some()
+        && let Some(analysis) = req.extensions_mut().get_mut::<Analysis>()
+    {
+        analysis.syntax_errors = true;
+    }
+    filter.apply(&mut response);
+
+    Ok(HttpResponse::Ok().insert_header(ETag(etag)).json(response))
+}
+
+async fn metrics_json(
+    item: web::Json<WebMetricsPayload>,
+    filter: Query<WebMetricsFilter>,
+    req: HttpRequest,
+    cache: web::Data<ResponseCache>,
+    if_none_match: Option<web::Header<IfNoneMatch>>,
+) -> HttpResponse {
     let payload = item.into_inner();
-    let buf = payload.code.into_bytes();
-    let (language, name) = guess_language(&buf, &path);
-    if let Some(language) = language {
-        let cfg = WebMetricsCfg {
+    if let Err(error) = filter.check() {
+        return HttpResponse::BadRequest().json(Error {
             id: payload.id,
-            path,
-            unit: payload.unit,
-            language: name.to_string(),
-        };
-        HttpResponse::Ok().json(action::<WebMetricsCallback>(
-            &language,
-            buf,
-            &PathBuf::from(""),
-            None,
-            cfg,
-        ))
-    } else {
-        HttpResponse::NotFound().json(Error {
+            error,
+        });
+    }
+    let key = CacheKey {
+        route: "/metrics",
+        file_name: &payload.file_name,
+        dialect: payload.dialect.as_deref(),
+        unit: payload.unit,
+        code: payload.code.as_bytes(),
+    };
+    match cached_metrics(
+        &req,
+        &key,
+        &filter,
+        &cache,
+        if_none_match,
+        payload.id.clone(),
+    ) {
+        Ok(response) => response,
+        Err(error) => HttpResponse::NotFound().json(Error {
             id: payload.id,
-            error: INVALID_LANGUAGE,
-        })
+            error,
+        }),
     }
 }
 
 async fn metrics_plain(
     body: web::Payload,
     info: Query<WebMetricsInfo>,
+    filter: Query<WebMetricsFilter>,
+    req: HttpRequest,
+    cache: web::Data<ResponseCache>,
+    if_none_match: Option<web::Header<IfNoneMatch>>,
 ) -> Result<HttpResponse, actix_web::Error> {
+    if let Err(error) = filter.check() {
+        return Ok(HttpResponse::BadRequest()
+            .append_header((http::header::CONTENT_TYPE, "text/plain"))
+            .body(format!("error: {error}")));
+    }
     let buf = get_code(body).await?;
-    let path = PathBuf::from(&info.file_name);
-    let (language, name) = guess_language(&buf, &path);
-    if let Some(language) = language {
-        let cfg = WebMetricsCfg {
-            id: "".to_string(),
-            path,
-            unit: info.unit.as_ref().is_some_and(|s| s == "1" || s == "true"),
-            language: name.to_string(),
-        };
-        Ok(HttpResponse::Ok().json(action::<WebMetricsCallback>(
-            &language,
-            buf,
-            &PathBuf::from(""),
-            None,
-            cfg,
-        )))
-    } else {
-        Ok(HttpResponse::NotFound()
+    let key = CacheKey {
+        route: "/metrics",
+        file_name: &info.file_name,
+        dialect: info.dialect.as_deref(),
+        unit: info.unit.as_ref().is_some_and(|s| s == "1" || s == "true"),
+        code: &buf,
+    };
+    match cached_metrics(&req, &key, &filter, &cache, if_none_match, "".to_string()) {
+        Ok(response) => Ok(response),
+        Err(error) => Ok(HttpResponse::NotFound()
+            .append_header((http::header::CONTENT_TYPE, "text/plain"))
+            .body(format!("error: {error}"))),
+    }
+}
+
+// Computes the metrics of a message of a stream,
+// which is a JSON metrics payload
+fn stream_metrics(message: &[u8], cache: &ResponseCache, filter: &WebMetricsFilter) -> Value {
+    let Ok(payload) = serde_json::from_slice::<WebMetricsPayload>(message) else {
+        return serde_json::to_value(Error {
+            id: String::new(),
+            error: INVALID_MESSAGE,
+        })
+        .unwrap();
+    };
+    let key = CacheKey {
+        route: "/metrics",
+        file_name: &payload.file_name,
+        dialect: payload.dialect.as_deref(),
+        unit: payload.unit,
+        code: payload.code.as_bytes(),
+    };
+    let path = PathBuf::from(&payload.file_name);
+    match guess_dialect_language(key.code, &path, key.dialect) {
+        Ok((language, name)) => {
+            let mut response = metrics_response(&key, cache, language, name, payload.id.clone());
+            filter.apply(&mut response);
+            response
+        }
+        Err(error) => serde_json::to_value(Error {
+            id: payload.id,
+            error,
+        })
+        .unwrap(),
+    }
+}
+
+// The identifier of a message of a stream, read when its analysis fails
+#[derive(Deserialize)]
+struct MessageId {
+    #[serde(default)]
+    id: String,
+}
+
+// Answers a message of a stream whose analysis failed, such as
+// when it panicked, so that the client does not wait for its response
+fn stream_failure(message: &[u8]) -> Value {
+    let id = serde_json::from_slice::<MessageId>(message)
+        .map(|message| message.id)
+        .unwrap_or_default();
+    serde_json::to_value(Error {
+        id,
+        error: ANALYSIS_FAILED,
+    })
+    .unwrap()
+}
+
+// Answers the messages of a stream as soon as their metrics are computed,
+// so their responses can come in a different order
+async fn stream_session(
+    mut session: Session,
+    messages: AggregatedMessageStream,
+    cache: Arc<ResponseCache>,
+    filter: Arc<WebMetricsFilter>,
+) {
+    // The stream of the responses holds handles of the session,
+    // so it is dropped before closing the session
+    {
+        let control = session.clone();
+        let mut responses = pin!(messages
+            // The stream ends when the client closes it or sends an invalid frame
+            .take_while(|message| {
+                future::ready(
+                    matches!(message, Ok(message) if !matches!(message, AggregatedMessage::Close(_))),
+                )
+            })
+            .filter_map(move |message| {
+                let mut control = control.clone();
+                async move {
+                    match message.ok()? {
+                        AggregatedMessage::Text(text) => Some(text.into_bytes()),
+                        AggregatedMessage::Binary(bytes) => Some(bytes),
+                        AggregatedMessage::Ping(bytes) => {
+                            control.pong(&bytes).await.ok();
+                            None
+                        }
+                        _ => None,
+                    }
+                }
+            })
+            .map(move |message| {
+                let (cache, filter) = (cache.clone(), filter.clone());
+                async move {
+                    let analyzed = message.clone();
+                    web::block(move || stream_metrics(&analyzed, &cache, &filter))
+                        .await
+                        .unwrap_or_else(|_| stream_failure(&message))
+                }
+            })
+            // The next messages are not read while too many codes are analyzed
+            .buffer_unordered(STREAM_CONCURRENCY));
+
+        while let Some(response) = responses.next().await {
+            if session.text(response.to_string()).await.is_err() {
+                return;
+            }
+        }
+    }
+    session.close(None).await.ok();
+}
+
+async fn metrics_stream(
+    req: HttpRequest,
+    body: web::Payload,
+    filter: Query<WebMetricsFilter>,
+    cache: web::Data<ResponseCache>,
+) -> Result<HttpResponse, actix_web::Error> {
+    // The parts of the metrics requested for all the messages
+    if let Err(error) = filter.check() {
+        return Ok(HttpResponse::BadRequest()
             .append_header((http::header::CONTENT_TYPE, "text/plain"))
-            .body(format!("error: {INVALID_LANGUAGE}")))
+            .body(format!("error: {error}")));
     }
+    let (response, session, messages) = actix_ws::handle(&req, body)?;
+    let messages = messages
+        .max_frame_size(MAX_PAYLOAD_SIZE)
+        .aggregate_continuations()
+        .max_continuation_size(MAX_PAYLOAD_SIZE);
+    actix_rt::spawn(stream_session(
+        session,
+        messages,
+        cache.into_inner(),
+        Arc::new(filter.into_inner()),
+    ));
+    Ok(response)
 }
 
-async fn function_json(item: web::Json<WebFunctionPayload>, _req: HttpRequest) -> HttpResponse {
+async fn function_json(item: web::Json<WebFunctionPayload>, req: HttpRequest) -> HttpResponse {
     let path = PathBuf::from(&item.file_name);
     let payload = item.into_inner();
     let buf = payload.code.into_bytes();
-    let (language, _) = guess_language(&buf, path);
-    if let Some(language) = language {
-        let cfg = WebFunctionCfg { id: payload.id };
-        HttpResponse::Ok().json(action::<WebFunctionCallback>(
-            &language,
-            buf,
-            &PathBuf::from(""),
-            None,
-            cfg,
-        ))
-    } else {
-        HttpResponse::NotFound().json(Error {
+    match guess_web_language(&req, &buf, &path, payload.dialect.as_deref()) {
+        Ok((language, _)) => {
+            let cfg = WebFunctionCfg { id: payload.id };
+            HttpResponse::Ok().json(action::<WebFunctionCallback>(
+                &language,
+                buf,
+                &PathBuf::from(""),
+                None,
+                cfg,
+            ))
+        }
+        Err(error) => HttpResponse::NotFound().json(Error {
             id: payload.id,
-            error: INVALID_LANGUAGE,
-        })
+            error,
+        }),
     }
 }
 
 async fn function_plain(
     body: web::Payload,
     info: Query<WebFunctionInfo>,
+    req: HttpRequest,
 ) -> Result<HttpResponse, actix_web::Error> {
     let buf = get_code(body).await?;
     let path = PathBuf::from(&info.file_name);
-    let (language, _) = guess_language(&buf, path);
-    if let Some(language) = language {
-        let cfg = WebFunctionCfg { id: "".to_string() };
-        Ok(HttpResponse::Ok().json(action::<WebFunctionCallback>(
-            &language,
-            buf,
-            &PathBuf::from(""),
-            None,
-            cfg,
-        )))
-    } else {
-        Ok(HttpResponse::NotFound()
+    match guess_web_language(&req, &buf, &path, info.dialect.as_deref()) {
+        Ok((language, _)) => {
+            let cfg = WebFunctionCfg { id: "".to_string() };
+            Ok(HttpResponse::Ok().json(action::<WebFunctionCallback>(
+                &language,
+                buf,
+                &PathBuf::from(""),
+                None,
+                cfg,
+            )))
+        }
+        Err(error) => Ok(HttpResponse::NotFound()
             .append_header((http::header::CONTENT_TYPE, "text/plain"))
-            .body(format!("error: {INVALID_LANGUAGE}")))
+            .body(format!("error: {error}"))),
     }
 }
 
+async fn graphql(
+    item: web::Json<async_graphql::Request>,
+    schema: web::Data<AnalysisSchema>,
+) -> HttpResponse {
+    HttpResponse::Ok().json(schema.execute(item.into_inner()).await)
+}
+
 async fn ping() -> HttpResponse {
     HttpResponse::Ok().body(())
 }
 
+async fn openapi_json() -> HttpResponse {
+    HttpResponse::Ok().json(openapi())
+}
+
+async fn server_metrics(stats: web::Data<ServerStats>) -> HttpResponse {
+    HttpResponse::Ok()
+        .append_header((http::header::CONTENT_TYPE, "text/plain; version=0.0.4"))
+        .body(stats.render())
+}
+
+// Records each response in the server metrics
+async fn record_stats(
+    req: ServiceRequest,
+    next: Next<impl MessageBody>,
+) -> Result<ServiceResponse<impl MessageBody>, actix_web::Error> {
+    let start = Instant::now();
+    let stats = req.app_data::<web::Data<ServerStats>>().cloned();
+    let res = next.call(req).await?;
+    if let Some(stats) = stats {
+        let req = res.request();
+        // The unmatched paths are not recorded one by one,
+        // to bound the number of series of the metrics
+        stats.record(
+            req.match_pattern().as_deref().unwrap_or("unmatched"),
+            req.method().as_str(),
+            res.status().as_u16(),
+            start.elapsed(),
+            req.extensions().get::<Analysis>().copied(),
+        );
+    }
+    Ok(res)
+}
+
 /// Runs an HTTP Server which provides a series of services.
 ///
 /// Each service corresponds to a functionality of the main library and can be
@@ -239,11 +548,74 @@ async fn ping() -> HttpResponse {
 /// }
 /// ```
 pub async fn run(host: &str, port: u16, n_threads: usize) -> std::io::Result<()> {
-    let max_size = 1024 * 1024 * 4;
+    run_with_options(host, port, n_threads, ServerOptions::default()).await
+}
+
+/// The options of the server.
+#[derive(Debug)]
+pub struct ServerOptions {
+    /// Number of bytes of the responses of the last distinct requests
+    /// computing metrics kept in the cache, 0 to disable the cache.
+    ///
+    /// The responses carry an `ETag` header, the digest of the request:
+    /// a request sending it back in an `If-None-Match` header is answered
+    /// with a `304 Not Modified` status, without computing the metrics again.
+    pub cache_size: usize,
+    /// Token the clients must send in an `Authorization: Bearer` header.
+    ///
+    /// If `None`, the requests are not authenticated.
+    pub token: Option<String>,
+    /// Number of requests per minute allowed to each client,
+    /// identified by its IP address.
+    ///
+    /// If `None` or 0, the requests are not limited.
+    pub rate_limit: Option<u32>,
+}
+
+impl Default for ServerOptions {
+    fn default() -> Self {
+        Self {
+            cache_size: DEFAULT_CACHE_SIZE,
+            token: None,
+            rate_limit: None,
+        }
+    }
+}
+
+/// Runs an HTTP Server with the given options.
+///
+/// See [`run`] for the other arguments.
+///
+/// [`run`]: fn.run.html
+pub async fn run_with_options(
+    host: &str,
+    port: u16,
+    n_threads: usize,
+    options: ServerOptions,
+) -> std::io::Result<()> {
+    let max_size = MAX_PAYLOAD_SIZE;
+    // The cache, the metrics and the rate limits are shared by all the workers
+    let cache = web::Data::new(ResponseCache::new(options.cache_size));
+    let stats = web::Data::new(ServerStats::default());
+    let schema = web::Data::new(analysis_schema());
+    let access = web::Data::new(AccessControl {
+        token: options.token,
+        rate_limiter: options
+            .rate_limit
+            .filter(|limit| *limit > 0)
+            .map(RateLimiter::new),
+    });
 
     HttpServer::new(move || {
         App::new()
+            // The rejected requests are recorded in the metrics too
+            .wrap(from_fn(check_access))
+            .wrap(from_fn(record_stats))
             .app_data(web::JsonConfig::default().limit(max_size))
+            .app_data(cache.clone())
+            .app_data(stats.clone())
+            .app_data(access.clone())
+            .app_data(schema.clone())
             .service(
                 web::resource("/ast")
                     .guard(guard::Header("content-type", "application/json"))
@@ -286,6 +658,14 @@ pub async fn run(host: &str, port: u16, n_threads: usize) -> std::io::Result<()>
                     .app_data(web::PayloadConfig::default().limit(max_size))
                     .route(web::post().to(function_plain)),
             )
+            .service(
+                web::resource("/graphql")
+                    .guard(guard::Header("content-type", "application/json"))
+                    .route(web::post().to(graphql)),
+            )
+            .service(web::resource("/metrics").route(web::get().to(server_metrics)))
+            .service(web::resource("/metrics/stream").route(web::get().to(metrics_stream)))
+            .service(web::resource("/openapi.json").route(web::get().to(openapi_json)))
             .service(web::resource("/ping").route(web::get().to(ping)))
     })
     .workers(n_threads)
@@ -304,6 +684,7 @@ mod tests {
     use serde_json::json;
     use serde_json::value::Value;
 
+    use super::super::graphql::MAX_QUERY_DEPTH;
     use super::*;
 
     #[actix_rt::test]
@@ -332,6 +713,7 @@ mod tests {
                 code: "int x = 1;".to_string(),
                 comment: false,
                 span: true,
+                dialect: None,
             })
             .to_request();
 
@@ -407,6 +789,7 @@ mod tests {
                 code: "var x = \"hello world\";".to_string(),
                 comment: false,
                 span: true,
+                dialect: None,
             })
             .to_request();
 
@@ -459,6 +842,7 @@ mod tests {
                 id: "1234".to_string(),
                 file_name: "foo.c".to_string(),
                 code: "int x = 1; // hello".to_string(),
+                dialect: None,
             })
             .to_request();
 
@@ -484,6 +868,7 @@ mod tests {
                 id: "1234".to_string(),
                 file_name: "foo.unexisting_extension".to_string(),
                 code: "int x = 1; // hello".to_string(),
+                dialect: None,
             })
             .to_request();
 
@@ -509,6 +894,7 @@ mod tests {
                 id: "1234".to_string(),
                 file_name: "foo.c".to_string(),
                 code: "int x = 1;".to_string(),
+                dialect: None,
             })
             .to_request();
 
@@ -620,7 +1006,9 @@ mod tests {
     #[actix_rt::test]
     async fn test_web_metrics_json() {
         let app = test::init_service(
-            App::new().service(web::resource("/metrics").route(web::post().to(metrics_json))),
+            App::new()
+                .app_data(web::Data::new(ResponseCache::default()))
+                .service(web::resource("/metrics").route(web::post().to(metrics_json))),
         )
         .await;
         let req = test::TestRequest::post()
@@ -630,6 +1018,7 @@ mod tests {
                 file_name: "test.py".to_string(),
                 code: "# -*- Mode: Objective-C++; tab-width: 2; indent-tabs-mode: nil; c-basic-offset: 2 -*-\n\ndef foo():\n    pass\n".to_string(),
                 unit: false,
+                dialect: None,
             })
             .to_request();
 
@@ -640,6 +1029,7 @@ mod tests {
             "spaces": {"kind": "unit",
                        "start_line": 1,
                        "end_line": 4,
+                       "metadata": {"size": 107, "hash": "b419c07c20eb840c", "language": "python", "lines": 4, "line_ending": "lf"},
                        "metrics": {"cyclomatic": {"sum": 2.0, "average": 1.0, "min":1.0, "max":1.0},
                                    "cognitive": {"sum": 0.0, "average": 0.0, "min":0.0, "max":0.0},
                                    "nargs": {"total_functions": 0.0, "average_functions": 0.0, "total_closures": 0.0, "average_closures": 0.0, "total": 0.0, "average": 0.0, "closures_max": 0.0, "closures_min": 0.0, "functions_max": 0.0, "functions_min": 0.0},
@@ -663,9 +1053,19 @@ mod tests {
                                    "mi": {"mi_original": 139.974_331_558_152_1,
                                           "mi_sei": 161.414_455_240_662_22,
                                           "mi_visual_studio": 81.856_334_244_533_39},
-                                   "abc": {"assignments": 0.0, "branches": 0.0, "conditions": 0.0, "magnitude": 0.0, "assignments_average": 0.0, "branches_average": 0.0, "conditions_average": 0.0, "assignments_min": 0.0, "assignments_max": 0.0, "branches_min": 0.0, "branches_max": 0.0, "conditions_min": 0.0, "conditions_max": 0.0}},
+                                   "abc": {"assignments": 0.0, "branches": 0.0, "conditions": 0.0, "magnitude": 0.0, "assignments_average": 0.0, "branches_average": 0.0, "conditions_average": 0.0, "assignments_min": 0.0, "assignments_max": 0.0, "branches_min": 0.0, "branches_max": 0.0, "conditions_min": 0.0, "conditions_max": 0.0},
+                                   "npm": {"classes": 0.0, "interfaces": 0.0, "class_methods": 0.0, "interface_methods": 0.0, "classes_average": null, "interfaces_average": null, "total": 0.0, "total_methods": 0.0, "average": null},
+                                   "npa": {"classes": 0.0, "interfaces": 0.0, "class_attributes": 0.0, "interface_attributes": 0.0, "classes_average": null, "interfaces_average": null, "total": 0.0, "total_attributes": 0.0, "average": null},
+                                   "accessors": {"getters": 0.0, "setters": 0.0, "total": 0.0, "functions": 1.0},
+                                   "assertions": {"sum": 0.0, "average": 0.0, "min": 0.0, "max": 0.0},
+                                   "locals": {"sum": 0.0, "average": 0.0, "min": 0.0, "max": 0.0},
+                                   "indentation": {"style": "spaces", "tab_lines": 0.0, "space_lines": 1.0, "mixed_lines": 0.0, "level_max": 1.0, "level_average": 0.333_333_333_333_333_3, "trailing_whitespace": 0.0}},
                        "name": "test.py",
+                       "qualified_name": null,
+                       "id": "824c60c46a59c4ce",
+                       "grades": {"cyclomatic": "A", "mi": "A"},
                        "spaces": [{"kind": "function",
+                                   "visibility": "public",
                                    "start_line": 3,
                                    "end_line": 4,
                                    "metrics": {"cyclomatic": {"sum": 1.0, "average": 1.0, "min":1.0, "max":1.0},
@@ -691,8 +1091,15 @@ mod tests {
                                                "mi": {"mi_original": 151.433_315_883_223_23,
                                                       "mi_sei": 142.873_061_717_489_78,
                                                       "mi_visual_studio": 88.557_494_668_551_6},
-                                               "abc": {"assignments": 0.0, "branches": 0.0, "conditions": 0.0, "magnitude": 0.0, "assignments_average": 0.0, "branches_average": 0.0, "conditions_average": 0.0, "assignments_min": 0.0, "assignments_max": 0.0, "branches_min": 0.0, "branches_max": 0.0, "conditions_min": 0.0, "conditions_max": 0.0}},
+                                               "abc": {"assignments": 0.0, "branches": 0.0, "conditions": 0.0, "magnitude": 0.0, "assignments_average": 0.0, "branches_average": 0.0, "conditions_average": 0.0, "assignments_min": 0.0, "assignments_max": 0.0, "branches_min": 0.0, "branches_max": 0.0, "conditions_min": 0.0, "conditions_max": 0.0},
+                                               "accessors": {"getters": 0.0, "setters": 0.0, "total": 0.0, "functions": 1.0},
+                                               "assertions": {"sum": 0.0, "average": 0.0, "min": 0.0, "max": 0.0},
+                                               "locals": {"sum": 0.0, "average": 0.0, "min": 0.0, "max": 0.0},
+                                               "indentation": {"style": "spaces", "tab_lines": 0.0, "space_lines": 1.0, "mixed_lines": 0.0, "level_max": 1.0, "level_average": 0.5, "trailing_whitespace": 0.0}},
                                    "name": "foo",
+                                   "qualified_name": "test.foo",
+                                   "id": "66f63e35f6238464",
+                                   "grades": {"cyclomatic": "A", "mi": "A"},
                                    "spaces": []}]}
         });
 
@@ -702,7 +1109,9 @@ mod tests {
     #[actix_rt::test]
     async fn test_web_metrics_json_unit() {
         let app = test::init_service(
-            App::new().service(web::resource("/metrics").route(web::post().to(metrics_json))),
+            App::new()
+                .app_data(web::Data::new(ResponseCache::default()))
+                .service(web::resource("/metrics").route(web::post().to(metrics_json))),
         )
         .await;
         let req = test::TestRequest::post()
@@ -712,6 +1121,7 @@ mod tests {
                 file_name: "test.py".to_string(),
                 code: "def foo():\n    pass\n".to_string(),
                 unit: true,
+                dialect: None,
             })
             .to_request();
 
@@ -722,6 +1132,7 @@ mod tests {
             "spaces": {"kind": "unit",
                        "start_line": 1,
                        "end_line": 2,
+                       "metadata": {"size": 20, "hash": "f5d3ab637da2f458", "language": "python", "lines": 2, "line_ending": "lf"},
                        "metrics": {"cyclomatic": {"sum": 2.0, "average": 1.0, "min":1.0, "max":1.0},
                                    "cognitive": {"sum": 0.0, "average": 0.0 , "min":0.0, "max":0.0},
                                    "nargs": {"total_functions": 0.0, "average_functions": 0.0, "total_closures": 0.0, "average_closures": 0.0, "total": 0.0, "average": 0.0, "closures_max": 0.0, "closures_min": 0.0, "functions_max": 0.0, "functions_min": 0.0},
@@ -745,8 +1156,17 @@ mod tests {
                                    "mi": {"mi_original": 151.203_315_883_223_2,
                                           "mi_sei": 142.643_061_717_489_76,
                                           "mi_visual_studio": 88.422_991_744_574_97},
-                                   "abc": {"assignments": 0.0, "branches": 0.0, "conditions": 0.0, "magnitude": 0.0, "assignments_average": 0.0, "branches_average": 0.0, "conditions_average": 0.0, "assignments_min": 0.0, "assignments_max": 0.0, "branches_min": 0.0, "branches_max": 0.0, "conditions_min": 0.0, "conditions_max": 0.0}},
+                                   "abc": {"assignments": 0.0, "branches": 0.0, "conditions": 0.0, "magnitude": 0.0, "assignments_average": 0.0, "branches_average": 0.0, "conditions_average": 0.0, "assignments_min": 0.0, "assignments_max": 0.0, "branches_min": 0.0, "branches_max": 0.0, "conditions_min": 0.0, "conditions_max": 0.0},
+                                   "npm": {"classes": 0.0, "interfaces": 0.0, "class_methods": 0.0, "interface_methods": 0.0, "classes_average": null, "interfaces_average": null, "total": 0.0, "total_methods": 0.0, "average": null},
+                                   "npa": {"classes": 0.0, "interfaces": 0.0, "class_attributes": 0.0, "interface_attributes": 0.0, "classes_average": null, "interfaces_average": null, "total": 0.0, "total_attributes": 0.0, "average": null},
+                                   "accessors": {"getters": 0.0, "setters": 0.0, "total": 0.0, "functions": 1.0},
+                                   "assertions": {"sum": 0.0, "average": 0.0, "min": 0.0, "max": 0.0},
+                                   "locals": {"sum": 0.0, "average": 0.0, "min": 0.0, "max": 0.0},
+                                   "indentation": {"style": "spaces", "tab_lines": 0.0, "space_lines": 1.0, "mixed_lines": 0.0, "level_max": 1.0, "level_average": 0.5, "trailing_whitespace": 0.0}},
                        "name": "test.py",
+                       "qualified_name": null,
+                       "id": "824c60c46a59c4ce",
+                       "grades": {"cyclomatic": "A", "mi": "A"},
                        "spaces": []}
         });
 
@@ -756,7 +1176,9 @@ mod tests {
     #[actix_rt::test]
     async fn test_web_metrics_plain() {
         let app = test::init_service(
-            App::new().service(web::resource("/metrics").route(web::post().to(metrics_plain))),
+            App::new()
+                .app_data(web::Data::new(ResponseCache::default()))
+                .service(web::resource("/metrics").route(web::post().to(metrics_plain))),
         )
         .await;
         let req = test::TestRequest::post()
@@ -772,6 +1194,7 @@ mod tests {
             "spaces": {"kind": "unit",
                        "start_line": 1,
                        "end_line": 2,
+                       "metadata": {"size": 20, "hash": "f5d3ab637da2f458", "language": "python", "lines": 2, "line_ending": "lf"},
                        "metrics": {"cyclomatic": {"sum": 2.0, "average": 1.0, "min": 1.0,"max": 1.0},
                                    "cognitive": {"sum": 0.0, "average": 0.0 , "min":0.0, "max":0.0},
                                    "nargs": {"total_functions": 0.0, "average_functions": 0.0, "total_closures": 0.0, "average_closures": 0.0, "total": 0.0, "average": 0.0, "closures_max": 0.0, "closures_min": 0.0, "functions_max": 0.0, "functions_min": 0.0},
@@ -795,9 +1218,19 @@ mod tests {
                                    "mi": {"mi_original": 151.203_315_883_223_2,
                                           "mi_sei": 142.643_061_717_489_76,
                                           "mi_visual_studio": 88.422_991_744_574_97},
-                                   "abc": {"assignments": 0.0, "branches": 0.0, "conditions": 0.0, "magnitude": 0.0, "assignments_average": 0.0, "branches_average": 0.0, "conditions_average": 0.0, "assignments_min": 0.0, "assignments_max": 0.0, "branches_min": 0.0, "branches_max": 0.0, "conditions_min": 0.0, "conditions_max": 0.0}},
+                                   "abc": {"assignments": 0.0, "branches": 0.0, "conditions": 0.0, "magnitude": 0.0, "assignments_average": 0.0, "branches_average": 0.0, "conditions_average": 0.0, "assignments_min": 0.0, "assignments_max": 0.0, "branches_min": 0.0, "branches_max": 0.0, "conditions_min": 0.0, "conditions_max": 0.0},
+                                   "npm": {"classes": 0.0, "interfaces": 0.0, "class_methods": 0.0, "interface_methods": 0.0, "classes_average": null, "interfaces_average": null, "total": 0.0, "total_methods": 0.0, "average": null},
+                                   "npa": {"classes": 0.0, "interfaces": 0.0, "class_attributes": 0.0, "interface_attributes": 0.0, "classes_average": null, "interfaces_average": null, "total": 0.0, "total_attributes": 0.0, "average": null},
+                                   "accessors": {"getters": 0.0, "setters": 0.0, "total": 0.0, "functions": 1.0},
+                                   "assertions": {"sum": 0.0, "average": 0.0, "min": 0.0, "max": 0.0},
+                                   "locals": {"sum": 0.0, "average": 0.0, "min": 0.0, "max": 0.0},
+                                   "indentation": {"style": "spaces", "tab_lines": 0.0, "space_lines": 1.0, "mixed_lines": 0.0, "level_max": 1.0, "level_average": 0.5, "trailing_whitespace": 0.0}},
                        "name": "test.py",
+                       "qualified_name": null,
+                       "id": "824c60c46a59c4ce",
+                       "grades": {"cyclomatic": "A", "mi": "A"},
                        "spaces": [{"kind": "function",
+                                   "visibility": "public",
                                    "start_line": 1,
                                    "end_line": 2,
                                    "metrics": {"cyclomatic": {"sum": 1.0, "average": 1.0, "min": 1.0,"max": 1.0},
@@ -823,14 +1256,690 @@ mod tests {
                                                "mi": {"mi_original": 151.433_315_883_223_23,
                                                       "mi_sei": 142.873_061_717_489_78,
                                                       "mi_visual_studio": 88.557_494_668_551_6},
-                                               "abc": {"assignments": 0.0, "branches": 0.0, "conditions": 0.0, "magnitude": 0.0, "assignments_average": 0.0, "branches_average": 0.0, "conditions_average": 0.0, "assignments_min": 0.0, "assignments_max": 0.0, "branches_min": 0.0, "branches_max": 0.0, "conditions_min": 0.0, "conditions_max": 0.0}},
+                                               "abc": {"assignments": 0.0, "branches": 0.0, "conditions": 0.0, "magnitude": 0.0, "assignments_average": 0.0, "branches_average": 0.0, "conditions_average": 0.0, "assignments_min": 0.0, "assignments_max": 0.0, "branches_min": 0.0, "branches_max": 0.0, "conditions_min": 0.0, "conditions_max": 0.0},
+                                               "accessors": {"getters": 0.0, "setters": 0.0, "total": 0.0, "functions": 1.0},
+                                               "assertions": {"sum": 0.0, "average": 0.0, "min": 0.0, "max": 0.0},
+                                               "locals": {"sum": 0.0, "average": 0.0, "min": 0.0, "max": 0.0},
+                                               "indentation": {"style": "spaces", "tab_lines": 0.0, "space_lines": 1.0, "mixed_lines": 0.0, "level_max": 1.0, "level_average": 0.5, "trailing_whitespace": 0.0}},
                                    "name": "foo",
+                                   "qualified_name": "test.foo",
+                                   "id": "66f63e35f6238464",
+                                   "grades": {"cyclomatic": "A", "mi": "A"},
                                    "spaces": []}]}
         });
 
         assert_eq!(res, expected);
     }
 
+    #[actix_rt::test]
+    async fn test_web_metrics_dialect() {
+        let app = test::init_service(
+            App::new()
+                .app_data(web::Data::new(ResponseCache::default()))
+                .service(web::resource("/metrics").route(web::post().to(metrics_json))),
+        )
+        .await;
+        let req = test::TestRequest::post()
+            .uri("/metrics")
+            .set_json(WebMetricsPayload {
+                id: "1234".to_string(),
+                file_name: "foo.h".to_string(),
+                code: "int foo(void);\n".to_string(),
+                unit: true,
+                dialect: Some("c".to_string()),
+            })
+            .to_request();
+
+        let res: Value = test::call_and_read_body_json(&app, req).await;
+        assert_eq!(res["language"], "c");
+        assert_eq!(res["spaces"]["kind"], "unit");
+
+        let req = test::TestRequest::post()
+            .uri("/metrics")
+            .set_json(WebMetricsPayload {
+                id: "1234".to_string(),
+                file_name: "foo.h".to_string(),
+                code: "int foo(void);\n".to_string(),
+                unit: true,
+                dialect: Some("cobol".to_string()),
+            })
+            .to_request();
+
+        let res: Value = test::call_and_read_body_json(&app, req).await;
+        let expected = json!({
+            "id": "1234",
+            "error": INVALID_DIALECT
+        });
+
+        assert_eq!(res, expected);
+    }
+
+    #[actix_rt::test]
+    async fn test_web_metrics_etag() {
+        let app = test::init_service(
+            App::new()
+                .app_data(web::Data::new(ResponseCache::default()))
+                .service(web::resource("/metrics").route(web::post().to(metrics_json))),
+        )
+        .await;
+        let payload = |id: &str, code: &str| WebMetricsPayload {
+            id: id.to_string(),
+            file_name: "foo.py".to_string(),
+            code: code.to_string(),
+            unit: true,
+            dialect: None,
+        };
+
+        let req = test::TestRequest::post()
+            .uri("/metrics")
+            .set_json(payload("1", "def foo():\n    pass\n"))
+            .to_request();
+        let resp = test::call_service(&app, req).await;
+        assert_eq!(resp.status(), StatusCode::OK);
+        let etag = resp.headers().get(http::header::ETAG).unwrap().clone();
+
+        // The cached response is sent with the identifier of the request
+        let req = test::TestRequest::post()
+            .uri("/metrics")
+            .set_json(payload("2", "def foo():\n    pass\n"))
+            .to_request();
+        let resp = test::call_service(&app, req).await;
+        assert_eq!(resp.headers().get(http::header::ETAG), Some(&etag));
+        let res: Value = test::read_body_json(resp).await;
+        assert_eq!(res["id"], "2");
+        assert_eq!(res["spaces"]["kind"], "unit");
+
+        let req = test::TestRequest::post()
+            .uri("/metrics")
+            .insert_header((http::header::IF_NONE_MATCH, etag.clone()))
+            .set_json(payload("3", "def foo():\n    pass\n"))
+            .to_request();
+        let resp = test::call_service(&app, req).await;
+        assert_eq!(resp.status(), StatusCode::NOT_MODIFIED);
+        assert_eq!(resp.headers().get(http::header::ETAG), Some(&etag));
+
+        let req = test::TestRequest::post()
+            .uri("/metrics")
+            .insert_header((http::header::IF_NONE_MATCH, etag.clone()))
+            .set_json(payload("4", "def bar():\n    pass\n"))
+            .to_request();
+        let resp = test::call_service(&app, req).await;
+        assert_eq!(resp.status(), StatusCode::OK);
+        assert_ne!(resp.headers().get(http::header::ETAG), Some(&etag));
+    }
+
+    #[actix_rt::test]
+    async fn test_web_metrics_filter() {
+        let app = test::init_service(
+            App::new()
+                .app_data(web::Data::new(ResponseCache::default()))
+                .service(web::resource("/metrics").route(web::post().to(metrics_json))),
+        )
+        .await;
+        let request = |uri: &str| {
+            test::TestRequest::post()
+                .uri(uri)
+                .set_json(WebMetricsPayload {
+                    id: "1".to_string(),
+                    file_name: "foo.py".to_string(),
+                    code: "class A:\n    def foo(self):\n        pass\n".to_string(),
+                    unit: false,
+                    dialect: None,
+                })
+                .to_request()
+        };
+
+        let resp = test::call_service(&app, request("/metrics")).await;
+        let etag = resp.headers().get(http::header::ETAG).unwrap().clone();
+
+        let resp = test::call_service(
+            &app,
+            request("/metrics?metrics=cyclomatic,loc&kinds=function"),
+        )
+        .await;
+        assert_eq!(resp.status(), StatusCode::OK);
+        // The filtered response has its own entity tag
+        assert_ne!(resp.headers().get(http::header::ETAG), Some(&etag));
+        let res: Value = test::read_body_json(resp).await;
+        let metrics: Vec<_> = res["spaces"]["metrics"]
+            .as_object()
+            .unwrap()
+            .keys()
+            .collect();
+        assert_eq!(metrics, ["cyclomatic", "loc"]);
+        // The function of the removed class takes its place
+        let spaces = res["spaces"]["spaces"].as_array().unwrap();
+        assert_eq!(spaces.len(), 1);
+        assert_eq!(spaces[0]["kind"], "function");
+        assert_eq!(spaces[0]["name"], "foo");
+        assert_eq!(spaces[0]["metrics"]["cyclomatic"]["sum"], 1.0);
+        assert!(spaces[0]["metrics"].get("halstead").is_none());
+
+        let resp = test::call_service(&app, request("/metrics?metrics=foo")).await;
+        assert_eq!(resp.status(), StatusCode::BAD_REQUEST);
+        let res: Value = test::read_body_json(resp).await;
+        assert_eq!(
+            res,
+            json!({"id": "1", "error": "The metrics parameter contains an unknown metric"})
+        );
+
+        let resp = test::call_service(&app, request("/metrics?kinds=function,foo")).await;
+        assert_eq!(resp.status(), StatusCode::BAD_REQUEST);
+    }
+
+    #[actix_rt::test]
+    async fn test_web_graphql() {
+        let app = test::init_service(
+            App::new()
+                .app_data(web::Data::new(analysis_schema()))
+                .service(web::resource("/graphql").route(web::post().to(graphql))),
+        )
+        .await;
+        let request = |query: &str| {
+            test::TestRequest::post()
+                .uri("/graphql")
+                .set_json(json!({
+                    "query": query,
+                    "variables": {
+                        "files": [
+                            {
+                                "fileName": "foo.py",
+                                "code": "class A:\n    def foo(self, x):\n        return x or 1\n\ndef bar():\n    pass\n"
+                            },
+                            { "fileName": "foo.unknown", "code": "foo" }
+                        ]
+                    }
+                }))
+                .to_request()
+        };
+
+        let req = request(
+            "query($files: [FileInput!]!) {
+                files(files: $files) {
+                    totalCount
+                    items {
+                        fileName
+                        language
+                        error
+                        spaces(kind: \"function\", bounds: [{ metric: \"cyclomatic\", min: 2 }]) {
+                            totalCount
+                            items { name qualifiedName startLine metric(name: \"loc.sloc\") }
+                        }
+                    }
+                }
+            }",
+        );
+        let res: Value = test::call_and_read_body_json(&app, req).await;
+        assert_eq!(
+            res["data"],
+            json!({
+                "files": {
+                    "totalCount": 2,
+                    "items": [
+                        {
+                            "fileName": "foo.py",
+                            "language": "python",
+                            "error": null,
+                            "spaces": {
+                                "totalCount": 1,
+                                "items": [
+                                    {
+                                        "name": "foo",
+                                        "qualifiedName": "foo.A.foo",
+                                        "startLine": 2,
+                                        "metric": 2.0
+                                    }
+                                ]
+                            }
+                        },
+                        {
+                            "fileName": "foo.unknown",
+                            "language": null,
+                            "error": "The file extension doesn't correspond to a valid language",
+                            "spaces": { "totalCount": 0, "items": [] }
+                        }
+                    ]
+                }
+            })
+        );
+
+        // The pages of the files and of the spaces
+        let req = request(
+            "query($files: [FileInput!]!) {
+                files(files: $files, limit: 1) {
+                    items {
+                        unit { metrics(names: [\"cyclomatic\"]) }
+                        spaces(offset: 1, limit: 2) { totalCount items { name kind } }
+                    }
+                }
+            }",
+        );
+        let res: Value = test::call_and_read_body_json(&app, req).await;
+        let file = &res["data"]["files"]["items"][0];
+        assert_eq!(res["data"]["files"]["items"].as_array().unwrap().len(), 1);
+        assert_eq!(
+            file["unit"]["metrics"],
+            json!({ "cyclomatic": { "sum": 5.0, "average": 1.25, "min": 1.0, "max": 2.0 } })
+        );
+        assert_eq!(
+            file["spaces"],
+            json!({
+                "totalCount": 4,
+                "items": [{ "name": "A", "kind": "class" }, { "name": "foo", "kind": "function" }]
+            })
+        );
+
+        let req = request(
+            "query($files: [FileInput!]!) {
+                files(files: $files) { items { unit { metric(name: \"foo\") } } }
+            }",
+        );
+        let res: Value = test::call_and_read_body_json(&app, req).await;
+        assert_eq!(
+            res["errors"][0]["message"],
+            "The metrics parameter contains an unknown metric"
+        );
+
+        // The queries walking the spaces too deeply are rejected
+        let nested = "spaces { ".repeat(MAX_QUERY_DEPTH) + "name" + &" }".repeat(MAX_QUERY_DEPTH);
+        let req = request(&format!(
+            "query($files: [FileInput!]!) {{
+                files(files: $files) {{ items {{ unit {{ {nested} }} }} }}
+            }}"
+        ));
+        let res: Value = test::call_and_read_body_json(&app, req).await;
+        assert_eq!(res["data"], Value::Null);
+        assert_eq!(res["errors"][0]["message"], "Query is nested too deep.");
+    }
+
+    #[actix_rt::test]
+    async fn test_web_server_metrics() {
+        let app = test::init_service(
+            App::new()
+                .wrap(from_fn(record_stats))
+                .app_data(web::Data::new(ResponseCache::default()))
+                .app_data(web::Data::new(ServerStats::default()))
+                .service(
+                    web::resource("/metrics")
+                        .guard(guard::Header("content-type", "application/json"))
+                        .route(web::post().to(metrics_json)),
+                )
+                .service(
+                    web::resource("/graphql")
+                        .guard(guard::Header("content-type", "application/json"))
+                        .route(web::post().to(graphql)),
+                )
+                .service(web::resource("/metrics").route(web::get().to(server_metrics))),
+        )
+        .await;
+        for (file_name, code) in [
+            ("foo.py", "def foo():\n    pass\n"),
+            ("bar.py", "def bar(:\n"),
+            ("foo.unknown", "foo"),
+        ] {
+            let req = test::TestRequest::post()
+                .uri("/metrics")
+                .set_json(WebMetricsPayload {
+                    id: "1234".to_string(),
+                    file_name: file_name.to_string(),
+                    code: code.to_string(),
+                    unit: true,
+                    dialect: None,
+                })
+                .to_request();
+            test::call_service(&app, req).await;
+        }
+
+        let req = test::TestRequest::get().uri("/metrics").to_request();
+        let res = test::call_and_read_body(&app, req).await;
+        let res = std::str::from_utf8(&res).unwrap();
+        for line in [
+            "# TYPE rca_web_requests_total counter",
+            "rca_web_requests_total{route=\"/metrics\",method=\"POST\",status=\"200\"} 2",
+            "rca_web_requests_total{route=\"/metrics\",method=\"POST\",status=\"404\"} 1",
+            "rca_web_request_duration_seconds_bucket{route=\"/metrics\",le=\"+Inf\"} 3",
+            "rca_web_request_duration_seconds_count{route=\"/metrics\"} 3",
+            "rca_web_parse_failures_total{reason=\"unknown_language\"} 1",
+            "rca_web_parse_failures_total{reason=\"syntax_errors\"} 1",
+            "rca_web_analyses_total{language=\"python\"} 2",
+        ] {
+            assert!(res.lines().any(|l| l == line), "{line} not in\n{res}");
+        }
+    }
+
+    #[actix_rt::test]
+    async fn test_web_stream_metrics() {
+        let cache = ResponseCache::default();
+        let message = json!({
+            "id": "1",
+            "file_name": "foo.py",
+            "code": "def foo():\n    pass\n",
+            "unit": true,
+        });
+        let res = stream_metrics(
+            message.to_string().as_bytes(),
+            &cache,
+            &WebMetricsFilter::default(),
+        );
+        assert_eq!(res["id"], "1");
+        assert_eq!(res["language"], "python");
+        assert_eq!(res["spaces"]["kind"], "unit");
+
+        let message = json!({
+            "id": "2",
+            "file_name": "foo.unknown",
+            "code": "foo",
+            "unit": true,
+        });
+        let res = stream_metrics(
+            message.to_string().as_bytes(),
+            &cache,
+            &WebMetricsFilter::default(),
+        );
+        assert_eq!(res, json!({"id": "2", "error": INVALID_LANGUAGE}));
+
+        let res = stream_metrics(b"foo", &cache, &WebMetricsFilter::default());
+        assert_eq!(res, json!({"id": "", "error": INVALID_MESSAGE}));
+    }
+
+    // Encodes a frame sent by a client, masked with a null key
+    fn client_frame(opcode: u8, payload: &[u8]) -> Vec<u8> {
+        let mut frame = vec![0x80 | opcode];
+        if payload.len() < 126 {
+            frame.push(0x80 | payload.len() as u8);
+        } else {
+            frame.push(0x80 | 126);
+            frame.extend_from_slice(&(payload.len() as u16).to_be_bytes());
+        }
+        frame.extend_from_slice(&[0; 4]);
+        frame.extend_from_slice(payload);
+        frame
+    }
+
+    // Decodes the frames sent by the server
+    fn server_frames(mut data: &[u8]) -> Vec<(u8, Vec<u8>)> {
+        let mut frames = Vec::new();
+        while !data.is_empty() {
+            let opcode = data[0] & 0x0f;
+            let (len, start) = match data[1] & 0x7f {
+                126 => (u16::from_be_bytes([data[2], data[3]]) as usize, 4),
+                127 => (
+                    u64::from_be_bytes(data[2..10].try_into().unwrap()) as usize,
+                    10,
+                ),
+                len => (len as usize, 2),
+            };
+            frames.push((opcode, data[start..start + len].to_vec()));
+            data = &data[start + len..];
+        }
+        frames
+    }
+
+    #[actix_rt::test]
+    async fn test_web_metrics_stream() {
+        let app = test::init_service(
+            App::new()
+                .app_data(web::Data::new(ResponseCache::default()))
+                .service(web::resource("/metrics/stream").route(web::get().to(metrics_stream))),
+        )
+        .await;
+
+        let mut payload = Vec::new();
+        for (id, file_name) in [("1", "foo.py"), ("2", "foo.rs"), ("3", "foo.unknown")] {
+            let message = json!({
+                "id": id,
+                "file_name": file_name,
+                "code": "a = 1\n",
+                "unit": true,
+            });
+            payload.extend(client_frame(0x1, message.to_string().as_bytes()));
+        }
+        payload.extend(client_frame(0x9, b"ping"));
+        payload.extend(client_frame(0x8, &[]));
+
+        let req = test::TestRequest::get()
+            .uri("/metrics/stream")
+            .insert_header((http::header::CONNECTION, "upgrade"))
+            .insert_header((http::header::UPGRADE, "websocket"))
+            .insert_header((http::header::SEC_WEBSOCKET_VERSION, "13"))
+            .insert_header((http::header::SEC_WEBSOCKET_KEY, "dGhlIHNhbXBsZSBub25jZQ=="))
+            .set_payload(payload)
+            .to_request();
+        let resp = test::call_service(&app, req).await;
+        assert_eq!(resp.status(), StatusCode::SWITCHING_PROTOCOLS);
+
+        let frames = server_frames(&test::read_body(resp).await);
+        assert!(frames.contains(&(0xA, b"ping".to_vec())));
+        assert_eq!(frames.last().unwrap().0, 0x8);
+
+        // The responses come as soon as they are computed
+        let mut responses: Vec<Value> = frames
+            .iter()
+            .filter(|(opcode, _)| *opcode == 0x1)
+            .map(|(_, data)| serde_json::from_slice(data).unwrap())
+            .collect();
+        responses.sort_by_key(|res| res["id"].as_str().unwrap().to_string());
+        assert_eq!(responses.len(), 3);
+        assert_eq!(responses[0]["language"], "python");
+        assert_eq!(responses[1]["language"], "rust");
+        assert_eq!(responses[2]["error"], INVALID_LANGUAGE);
+    }
+
+    #[test]
+    async fn test_web_stream_failure() {
+        let message = json!({
+            "id": "1234",
+            "file_name": "foo.py",
+            "code": "a = 1\n",
+            "unit": true,
+        });
+        assert_eq!(
+            stream_failure(message.to_string().as_bytes()),
+            json!({
+                "id": "1234",
+                "error": ANALYSIS_FAILED
+            })
+        );
+        // A message without an identifier is answered all the same
+        assert_eq!(
+            stream_failure(b"{}"),
+            json!({
+                "id": "",
+                "error": ANALYSIS_FAILED
+            })
+        );
+    }
+
+    // Checks that a response only contains the fields declared by its schema
+    fn check_schema(doc: &Value, schema: &Value, value: &Value, path: &str) {
+        if let Some(reference) = schema["$ref"].as_str() {
+            let name = reference.trim_start_matches("#/components/schemas/");
+            let schema = &doc["components"]["schemas"][name];
+            assert!(schema.is_object(), "{path}: {reference} is not defined");
+            return check_schema(doc, schema, value, path);
+        }
+        if value.is_null() {
+            assert_eq!(schema["nullable"], json!(true), "{path} is null");
+            return;
+        }
+        if let Some(all_of) = schema["allOf"].as_array() {
+            return all_of
+                .iter()
+                .for_each(|schema| check_schema(doc, schema, value, path));
+        }
+        if let Some(values) = schema["enum"].as_array() {
+            assert!(
+                values.contains(value),
+                "{path}: {value} is not in {values:?}"
+            );
+        }
+        match schema["type"].as_str().unwrap() {
+            "object" => {
+                for (key, value) in value.as_object().unwrap() {
+                    let property = &schema["properties"][key];
+                    assert!(property.is_object(), "{path}.{key} is not declared");
+                    check_schema(doc, property, value, &format!("{path}.{key}"));
+                }
+            }
+            "array" => {
+                for (i, value) in value.as_array().unwrap().iter().enumerate() {
+                    check_schema(doc, &schema["items"], value, &format!("{path}[{i}]"));
+                }
+            }
+            "integer" => assert!(value.is_u64(), "{path} is not an integer"),
+            "number" => assert!(value.is_number(), "{path} is not a number"),
+            "string" => assert!(value.is_string(), "{path} is not a string"),
+            "boolean" => assert!(value.is_boolean(), "{path} is not a boolean"),
+            kind => panic!("{path}: unknown type {kind}"),
+        }
+    }
+
+    #[actix_rt::test]
+    async fn test_web_openapi() {
+        let app = test::init_service(
+            App::new()
+                .app_data(web::Data::new(ResponseCache::default()))
+                .service(
+                    web::resource("/metrics")
+                        .guard(guard::Header("content-type", "application/json"))
+                        .route(web::post().to(metrics_json)),
+                )
+                .service(
+                    web::resource("/function")
+                        .guard(guard::Header("content-type", "application/json"))
+                        .route(web::post().to(function_json)),
+                )
+                .service(web::resource("/ast").route(web::post().to(ast_parser)))
+                .service(web::resource("/openapi.json").route(web::get().to(openapi_json))),
+        )
+        .await;
+        let req = test::TestRequest::get().uri("/openapi.json").to_request();
+        let doc: Value = test::call_and_read_body_json(&app, req).await;
+        assert_eq!(doc["openapi"], "3.0.3");
+        assert_eq!(doc["info"]["version"], env!("CARGO_PKG_VERSION"));
+
+        let schema = |name: &str| json!({ "$ref": format!("#/components/schemas/{name}") });
+        for code in [
+            "import os\n\n@decorator\nclass Foo:\n    def bar(self, x):\n        assert x\n        return [y for y in x if y]\n\nasync def baz():\n    await os.foo()\n",
+            "def bar(:\n    pass\n",
+        ] {
+            let req = test::TestRequest::post()
+                .uri("/metrics")
+                .set_json(WebMetricsPayload {
+                    id: "1234".to_string(),
+                    file_name: "foo.py".to_string(),
+                    code: code.to_string(),
+                    unit: false,
+                    dialect: None,
+                })
+                .to_request();
+            let res: Value = test::call_and_read_body_json(&app, req).await;
+            check_schema(&doc, &schema("WebMetricsResponse"), &res, "metrics");
+
+            let req = test::TestRequest::post()
+                .uri("/function")
+                .set_json(WebFunctionPayload {
+                    id: "1234".to_string(),
+                    file_name: "foo.py".to_string(),
+                    code: code.to_string(),
+                    dialect: None,
+                })
+                .to_request();
+            let res: Value = test::call_and_read_body_json(&app, req).await;
+            check_schema(&doc, &schema("WebFunctionResponse"), &res, "function");
+
+            let req = test::TestRequest::post()
+                .uri("/ast")
+                .set_json(AstPayload {
+                    id: "1234".to_string(),
+                    file_name: "foo.py".to_string(),
+                    code: code.to_string(),
+                    comment: false,
+                    span: true,
+                    dialect: None,
+                })
+                .to_request();
+            let res: Value = test::call_and_read_body_json(&app, req).await;
+            check_schema(&doc, &schema("AstResponse"), &res, "ast");
+        }
+    }
+
+    #[actix_rt::test]
+    async fn test_web_access_control() {
+        let app = test::init_service(
+            App::new()
+                .wrap(from_fn(check_access))
+                .app_data(web::Data::new(AccessControl {
+                    token: Some("secret".to_string()),
+                    rate_limiter: Some(RateLimiter::new(3)),
+                }))
+                .service(web::resource("/function").route(web::post().to(function_json)))
+                .service(web::resource("/ping").route(web::get().to(ping))),
+        )
+        .await;
+        let client = "127.0.0.1:12345".parse().unwrap();
+        let function = |token: Option<&str>| {
+            let req = test::TestRequest::post()
+                .uri("/function")
+                .peer_addr(client)
+                .set_json(WebFunctionPayload {
+                    id: "1234".to_string(),
+                    file_name: "foo.py".to_string(),
+                    code: "def foo():\n    pass\n".to_string(),
+                    dialect: None,
+                });
+            match token {
+                Some(token) => req
+                    .insert_header((http::header::AUTHORIZATION, format!("Bearer {token}")))
+                    .to_request(),
+                None => req.to_request(),
+            }
+        };
+
+        let resp = test::call_service(&app, function(None)).await;
+        assert_eq!(resp.status(), StatusCode::UNAUTHORIZED);
+        let resp = test::call_service(&app, function(Some("wrong"))).await;
+        assert_eq!(resp.status(), StatusCode::UNAUTHORIZED);
+
+        // The health of the server is checked without a token
+        let req = test::TestRequest::with_uri("/ping")
+            .peer_addr(client)
+            .to_request();
+        let resp = test::call_service(&app, req).await;
+        assert_eq!(resp.status(), StatusCode::OK);
+
+        // The rejected requests count towards the limit too
+        let resp = test::call_service(&app, function(Some("secret"))).await;
+        assert_eq!(resp.status(), StatusCode::TOO_MANY_REQUESTS);
+        assert_eq!(resp.headers().get(http::header::RETRY_AFTER).unwrap(), "20");
+    }
+
+    #[actix_rt::test]
+    async fn test_web_authorized() {
+        let app = test::init_service(
+            App::new()
+                .wrap(from_fn(check_access))
+                .app_data(web::Data::new(AccessControl {
+                    token: Some("secret".to_string()),
+                    rate_limiter: None,
+                }))
+                .service(web::resource("/function").route(web::post().to(function_json))),
+        )
+        .await;
+        let req = test::TestRequest::post()
+            .uri("/function")
+            .insert_header((http::header::AUTHORIZATION, "Bearer secret"))
+            .set_json(WebFunctionPayload {
+                id: "1234".to_string(),
+                file_name: "foo.py".to_string(),
+                code: "def foo():\n    pass\n".to_string(),
+                dialect: None,
+            })
+            .to_request();
+        let resp = test::call_service(&app, req).await;
+        assert_eq!(resp.status(), StatusCode::OK);
+    }
+
     #[actix_rt::test]
     async fn test_web_function_json() {
         let app = test::init_service(
@@ -843,6 +1952,7 @@ mod tests {
                 id: "1234".to_string(),
                 file_name: "test.py".to_string(),
                 code: "def foo():\n    pass\n\ndef bar():\n    pass".to_string(),
+                dialect: None,
             })
             .to_request();
 
@@ -0,0 +1,198 @@
+use std::collections::BTreeMap;
+use std::fmt::Write;
+use std::sync::Mutex;
+use std::time::Duration;
+
+/// Upper bounds, in seconds, of the buckets of the histogram of the latencies.
+pub const LATENCY_BUCKETS: &[f64] = &[0.001, 0.005, 0.01, 0.05, 0.1, 0.5, 1., 5.];
+
+/// The analysis of the code of a request.
+///
+/// It is stored in the extensions of the request by its handler,
+/// and it is recorded in the [`ServerStats`] when the response is sent.
+///
+/// [`ServerStats`]: struct.ServerStats.html
+#[derive(Clone, Copy, Debug, Default)]
+pub struct Analysis {
+    /// The language of the code, `None` if it is unknown.
+    pub language: Option<&'static str>,
+    /// If `true`, the code has syntax errors.
+    pub syntax_errors: bool,
+}
+
+#[derive(Debug, Default)]
+struct Histogram {
+    // The number of observations of each bucket, not cumulated
+    buckets: Vec<u64>,
+    sum: f64,
+    count: u64,
+}
+
+impl Histogram {
+    fn observe(&mut self, value: f64) {
+        if self.buckets.is_empty() {
+            self.buckets = vec![0; LATENCY_BUCKETS.len()];
+        }
+        if let Some(bucket) = LATENCY_BUCKETS.iter().position(|bound| value <= *bound) {
+            self.buckets[bucket] += 1;
+        }
+        self.sum += value;
+        self.count += 1;
+    }
+}
+
+#[derive(Debug, Default)]
+struct Stats {
+    // Keyed by route, method and status
+    requests: BTreeMap<(String, String, u16), u64>,
+    // Keyed by route
+    latencies: BTreeMap<String, Histogram>,
+    languages: BTreeMap<&'static str, u64>,
+    unknown_languages: u64,
+    syntax_errors: u64,
+}
+
+/// The operational metrics of the server.
+///
+/// They are exposed in the text format of Prometheus,
+/// so that the server can be monitored like any other service.
+#[derive(Debug, Default)]
+pub struct ServerStats {
+    stats: Mutex<Stats>,
+}
+
+impl ServerStats {
+    /// Records a response of the server.
+    ///
+    /// * `route` - The route pattern of the request, such as `/metrics`.
+    /// * `method` - The method of the request.
+    /// * `status` - The status code of the response.
+    /// * `latency` - The time taken to handle the request.
+    /// * `analysis` - The analysis of the code of the request, if any.
+    pub fn record(
+        &self,
+        route: &str,
+        method: &str,
+        status: u16,
+        latency: Duration,
+        analysis: Option<Analysis>,
+    ) {
+        let mut stats = self.stats.lock().unwrap();
+        *stats
+            .requests
+            .entry((route.to_string(), method.to_string(), status))
+            .or_default() += 1;
+        stats
+            .latencies
+            .entry(route.to_string())
+            .or_default()
+            .observe(latency.as_secs_f64());
+
+        let Some(analysis) = analysis else {
+            return;
+        };
+        match analysis.language {
+            Some(language) => *stats.languages.entry(language).or_default() += 1,
+            None => stats.unknown_languages += 1,
+        }
+        if analysis.syntax_errors {
+            stats.syntax_errors += 1;
+        }
+    }
+
+    /// Renders the metrics in the text format of Prometheus.
+    pub fn render(&self) -> String {
+        let stats = self.stats.lock().unwrap();
+        let mut out = String::new();
+
+        header(
+            &mut out,
+            "rca_web_requests_total",
+            "counter",
+            "Number of handled HTTP requests.",
+        );
+        for ((route, method, status), count) in &stats.requests {
+            writeln!(
+                out,
+                "rca_web_requests_total{{route=\"{route}\",method=\"{method}\",status=\"{status}\"}} {count}"
+            )
+            .unwrap();
+        }
+
+        header(
+            &mut out,
+            "rca_web_request_duration_seconds",
+            "histogram",
+            "Latency of the HTTP requests in seconds.",
+        );
+        for (route, histogram) in &stats.latencies {
+            let mut cumulated = 0;
+            for (bound, count) in LATENCY_BUCKETS.iter().zip(&histogram.buckets) {
+                cumulated += count;
+                writeln!(
+                    out,
+                    "rca_web_request_duration_seconds_bucket{{route=\"{route}\",le=\"{bound}\"}} {cumulated}"
+                )
+                .unwrap();
+            }
+            writeln!(
+                out,
+                "rca_web_request_duration_seconds_bucket{{route=\"{route}\",le=\"+Inf\"}} {}",
+                histogram.count
+            )
+            .unwrap();
+            writeln!(
+                out,
+                "rca_web_request_duration_seconds_sum{{route=\"{route}\"}} {}",
+                histogram.sum
+            )
+            .unwrap();
+            writeln!(
+                out,
+                "rca_web_request_duration_seconds_count{{route=\"{route}\"}} {}",
+                histogram.count
+            )
+            .unwrap();
+        }
+
+        header(
+            &mut out,
+            "rca_web_parse_failures_total",
+            "counter",
+            "Number of codes which could not be parsed.",
+        );
+        writeln!(
+            out,
+            "rca_web_parse_failures_total{{reason=\"unknown_language\"}} {}",
+            stats.unknown_languages
+        )
+        .unwrap();
+        writeln!(
+            out,
+            "rca_web_parse_failures_total{{reason=\"syntax_errors\"}} {}",
+            stats.syntax_errors
+        )
+        .unwrap();
+
+        header(
+            &mut out,
+            "rca_web_analyses_total",
+            "counter",
+            "Number of analyzed codes by language.",
+        );
+        for (language, count) in &stats.languages {
+            writeln!(
+                out,
+                "rca_web_analyses_total{{language=\"{language}\"}} {count}"
+            )
+            .unwrap();
+        }
+
+        out
+    }
+}
+
+fn header(out: &mut String, name: &str, kind: &str, help: &str) {
+    writeln!(out, "# HELP {name} {help}").unwrap();
+    writeln!(out, "# TYPE {name} {kind}").unwrap();
+}
@@ -0,0 +1,775 @@
+use std::io::Write;
+use std::path::{Path, PathBuf};
+
+use serde::Serialize;
+use termcolor::{Color, ColorChoice, StandardStream, StandardStreamLock};
+
+use crate::checker::Checker;
+use crate::langs::*;
+use crate::languages::*;
+use crate::macros::implement_metric_trait;
+use crate::node::Node;
+use crate::spaces::qualified_name_separator;
+use crate::traits::*;
+
+use crate::tools::{color, intense_color};
+
+/// The kind of a public symbol.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ApiSymbolKind {
+    /// A module or a namespace
+    Module,
+    /// A class, a struct, a union or an object
+    Class,
+    /// An interface or a trait
+    Interface,
+    /// An enum
+    Enum,
+    /// A type alias
+    Type,
+    /// A free function
+    Function,
+    /// A method or a constructor
+    Method,
+    /// A constant or a variable
+    Variable,
+    /// A field, a property or an enum constant
+    Field,
+    /// A binding exported by name, whose declaration is elsewhere
+    Export,
+}
+
+impl ApiSymbolKind {
+    // Returns true if the members of a symbol can be public
+    fn is_container(self) -> bool {
+        matches!(
+            self,
+            Self::Module | Self::Class | Self::Interface | Self::Enum
+        )
+    }
+}
+
+/// A symbol of the public API of a code.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize)]
+pub struct ApiSymbol {
+    /// The name of the symbol, qualified by the names of its containers
+    pub name: String,
+    /// The kind of the symbol
+    pub kind: ApiSymbolKind,
+    /// The first line of the symbol
+    pub start_line: usize,
+    /// The last line of the symbol
+    pub end_line: usize,
+}
+
+/// The public API of a file.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize)]
+pub struct PublicApiSymbols {
+    /// The path of the file
+    pub name: String,
+    /// The public symbols, in the order of the source code
+    pub symbols: Vec<ApiSymbol>,
+}
+
+/// The visibility of a declaration found in a code.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Visibility<'a> {
+    /// A public symbol, with its kind and its name
+    Public(ApiSymbolKind, &'a str),
+    /// A symbol which is not public, hiding its members too
+    Private,
+    /// A scope which is not a symbol, such as a Rust `impl` block,
+    /// qualifying its members with a name
+    Scope(&'a str),
+}
+
+pub trait Exports
+where
+    Self: Checker,
+{
+    /// Returns the visibility of a node, `None` if it is not a declaration.
+    fn get_visibility<'a>(_node: &Node, _code: &'a [u8]) -> Option<Visibility<'a>> {
+        None
+    }
+}
+
+fn text<'a>(node: &Node, code: &'a [u8]) -> Option<&'a str> {
+    std::str::from_utf8(&code[node.start_byte()..node.end_byte()]).ok()
+}
+
+fn name<'a>(node: &Node, code: &'a [u8]) -> Option<&'a str> {
+    text(&node.child_by_field_name("name")?, code)
+}
+
+// Returns the public declaration of a named node, or a private one
+// if the node has no name
+fn public<'a>(node: &Node, code: &'a [u8], kind: ApiSymbolKind) -> Visibility<'a> {
+    name(node, code).map_or(Visibility::Private, |name| Visibility::Public(kind, name))
+}
+
+fn is_grandchild_of(node: &Node, id: u16) -> bool {
+    node.get_parent(2)
+        .is_some_and(|parent| parent.kind_id() == id)
+}
+
+impl Exports for RustCode {
+    fn get_visibility<'a>(node: &Node, code: &'a [u8]) -> Option<Visibility<'a>> {
+        use Rust::*;
+
+        // `pub(crate)`, `pub(super)` and `pub(in path)` are not public
+        let is_pub = || {
+            node.children().any(|child| {
+                child.kind_id() == VisibilityModifier && text(&child, code) == Some("pub")
+            })
+        };
+        let visible = |kind| {
+            if is_pub() {
+                public(node, code, kind)
+            } else {
+                Visibility::Private
+            }
+        };
+
+        let declaration = match node.kind_id().into() {
+            // The items of a public trait are public
+            FunctionItem | FunctionSignatureItem if is_grandchild_of(node, TraitItem as u16) => {
+                public(node, code, ApiSymbolKind::Method)
+            }
+            FunctionItem | FunctionSignatureItem if is_grandchild_of(node, ImplItem as u16) => {
+                visible(ApiSymbolKind::Method)
+            }
+            FunctionItem | FunctionSignatureItem => visible(ApiSymbolKind::Function),
+            ModItem => visible(ApiSymbolKind::Module),
+            StructItem | UnionItem => visible(ApiSymbolKind::Class),
+            TraitItem => visible(ApiSymbolKind::Interface),
+            EnumItem => visible(ApiSymbolKind::Enum),
+            TypeItem => visible(ApiSymbolKind::Type),
+            ConstItem | StaticItem => visible(ApiSymbolKind::Variable),
+            // The items of a trait implementation belong to the trait
+            ImplItem if node.child_by_field_name("trait").is_some() => Visibility::Private,
+            ImplItem => node
+                .child_by_field_name("type")
+                .and_then(|name| text(&name, code))
+                .map_or(Visibility::Private, Visibility::Scope),
+            _ => return None,
+        };
+        Some(declaration)
+    }
+}
+
+impl Exports for PythonCode {
+    fn get_visibility<'a>(node: &Node, code: &'a [u8]) -> Option<Visibility<'a>> {
+        // The names starting with an underscore are private by convention,
+        // except for the special methods such as `__init__`
+        let visible = |name: &'a str, kind| {
+            if name.starts_with('_') && !(name.starts_with("__") && name.ends_with("__")) {
+                Visibility::Private
+            } else {
+                Visibility::Public(kind, name)
+            }
+        };
+        let in_class = |node: &Node| {
+            let mut node = *node;
+            while let Some(parent) = node
+                .parent()
+                .filter(|parent| parent.kind_id() == Python::DecoratedDefinition)
+            {
+                node = parent;
+            }
+            is_grandchild_of(&node, Python::ClassDefinition as u16)
+        };
+
+        let declaration = match node.kind_id().into() {
+            Python::ClassDefinition => visible(name(node, code)?, ApiSymbolKind::Class),
+            Python::FunctionDefinition => {
+                let kind = if in_class(node) {
+                    ApiSymbolKind::Method
+                } else {
+                    ApiSymbolKind::Function
+                };
+                visible(name(node, code)?, kind)
+            }
+            Python::Assignment => {
+                let left = node.child_by_field_name("left")?;
+                if left.kind_id() != Python::Identifier {
+                    return None;
+                }
+                // The assignments are in an expression statement
+                let kind = if node.parent().is_some_and(|parent| in_class(&parent)) {
+                    ApiSymbolKind::Field
+                } else {
+                    ApiSymbolKind::Variable
+                };
+                visible(text(&left, code)?, kind)
+            }
+            _ => return None,
+        };
+        Some(declaration)
+    }
+}
+
+macro_rules! js_declaration {
+    ($lang:ident, $node:ident, $code:ident) => {{
+        use $lang::*;
+
+        let is_exported = |node: &Node| {
+            node.parent()
+                .is_some_and(|parent| parent.kind_id() == ExportStatement)
+        };
+        let exported = |kind| {
+            if is_exported($node) {
+                public($node, $code, kind)
+            } else {
+                Visibility::Private
+            }
+        };
+
+        // The anonymous values exported by default are named `default`
+        if let Some(parent) = $node.parent()
+            && parent.kind_id() == ExportStatement
+            && parent
+                .child_by_field_name("value")
+                .is_some_and(|value| value.id() == $node.id())
+        {
+            let kind = match $node.kind_id().into() {
+                Class => ApiSymbolKind::Class,
+                FunctionExpression | GeneratorFunction | ArrowFunction => ApiSymbolKind::Function,
+                _ => ApiSymbolKind::Export,
+            };
+            return Some(Visibility::Public(kind, "default"));
+        }
+
+        let declaration = match $node.kind_id().into() {
+            FunctionDeclaration | GeneratorFunctionDeclaration => exported(ApiSymbolKind::Function),
+            ClassDeclaration => exported(ApiSymbolKind::Class),
+            // Each declarator of `export const a = 1, b = 2;` is exported
+            VariableDeclarator => match $node.child_by_field_name("name") {
+                Some(name)
+                    if name.kind_id() == Identifier
+                        && $node.parent().is_some_and(|parent| is_exported(&parent)) =>
+                {
+                    Visibility::Public(ApiSymbolKind::Variable, text(&name, $code)?)
+                }
+                _ => Visibility::Private,
+            },
+            ExportSpecifier => {
+                let name = $node
+                    .child_by_field_name("alias")
+                    .or_else(|| $node.child_by_field_name("name"))?;
+                Visibility::Public(ApiSymbolKind::Export, text(&name, $code)?)
+            }
+            // The `#name` members are private
+            MethodDefinition => match $node.child_by_field_name("name") {
+                Some(name) if name.kind_id() != PrivatePropertyIdentifier => {
+                    Visibility::Public(ApiSymbolKind::Method, text(&name, $code)?)
+                }
+                _ => Visibility::Private,
+            },
+            _ => return None,
+        };
+        Some(declaration)
+    }};
+}
+
+macro_rules! ts_declaration {
+    ($lang:ident, $node:ident, $code:ident) => {{
+        use $lang::*;
+
+        let exported = |kind| {
+            if $node
+                .parent()
+                .is_some_and(|parent| parent.kind_id() == ExportStatement)
+            {
+                public($node, $code, kind)
+            } else {
+                Visibility::Private
+            }
+        };
+
+        match $node.kind_id().into() {
+            AbstractClassDeclaration => Some(exported(ApiSymbolKind::Class)),
+            InterfaceDeclaration => Some(exported(ApiSymbolKind::Interface)),
+            EnumDeclaration => Some(exported(ApiSymbolKind::Enum)),
+            TypeAliasDeclaration => Some(exported(ApiSymbolKind::Type)),
+            InternalModule | Module => Some(exported(ApiSymbolKind::Module)),
+            MethodSignature | AbstractMethodSignature => {
+                Some(public($node, $code, ApiSymbolKind::Method))
+            }
+            PropertySignature => Some(public($node, $code, ApiSymbolKind::Field)),
+            // The `private` and `protected` members are not public
+            MethodDefinition
+                if $node.children().any(|child| {
+                    child.kind_id() == AccessibilityModifier
+                        && text(&child, $code) != Some("public")
+                }) =>
+            {
+                Some(Visibility::Private)
+            }
+            _ => js_declaration!($lang, $node, $code),
+        }
+    }};
+}
+
+impl Exports for MozjsCode {
+    fn get_visibility<'a>(node: &Node, code: &'a [u8]) -> Option<Visibility<'a>> {
+        js_declaration!(Mozjs, node, code)
+    }
+}
+
+impl Exports for JavascriptCode {
+    fn get_visibility<'a>(node: &Node, code: &'a [u8]) -> Option<Visibility<'a>> {
+        js_declaration!(Javascript, node, code)
+    }
+}
+
+impl Exports for TypescriptCode {
+    fn get_visibility<'a>(node: &Node, code: &'a [u8]) -> Option<Visibility<'a>> {
+        ts_declaration!(Typescript, node, code)
+    }
+}
+
+impl Exports for TsxCode {
+    fn get_visibility<'a>(node: &Node, code: &'a [u8]) -> Option<Visibility<'a>> {
+        ts_declaration!(Tsx, node, code)
+    }
+}
+
+impl Exports for JavaCode {
+    fn get_visibility<'a>(node: &Node, code: &'a [u8]) -> Option<Visibility<'a>> {
+        use Java::*;
+
+        let has_modifier = |id: Java| {
+            let id = id as u16;
+            node.children()
+                .any(|child| child.kind_id() == Modifiers && child.is_child(id))
+        };
+        // The members of an interface are implicitly public
+        let in_interface = node.parent().is_some_and(|parent| {
+            matches!(parent.kind_id().into(), InterfaceBody | AnnotationTypeBody)
+        });
+        let is_public = has_modifier(Public) || in_interface && !has_modifier(Private);
+        let visible = |kind| {
+            if is_public {
+                public(node, code, kind)
+            } else {
+                Visibility::Private
+            }
+        };
+
+        let declaration = match node.kind_id().into() {
+            ClassDeclaration | RecordDeclaration => visible(ApiSymbolKind::Class),
+            InterfaceDeclaration | AnnotationTypeDeclaration => visible(ApiSymbolKind::Interface),
+            EnumDeclaration => visible(ApiSymbolKind::Enum),
+            MethodDeclaration | ConstructorDeclaration => visible(ApiSymbolKind::Method),
+            EnumConstant => public(node, code, ApiSymbolKind::Field),
+            // The declarators of a public field are walked
+            FieldDeclaration | ConstantDeclaration if is_public => return None,
+            FieldDeclaration | ConstantDeclaration => Visibility::Private,
+            VariableDeclarator
+                if node.parent().is_some_and(|parent| {
+                    matches!(
+                        parent.kind_id().into(),
+                        FieldDeclaration | ConstantDeclaration
+                    )
+                }) =>
+            {
+                public(node, code, ApiSymbolKind::Field)
+            }
+            VariableDeclarator => Visibility::Private,
+            _ => return None,
+        };
+        Some(declaration)
+    }
+}
+
+impl Exports for KotlinCode {
+    fn get_visibility<'a>(node: &Node, code: &'a [u8]) -> Option<Visibility<'a>> {
+        use Kotlin::*;
+
+        // The declarations are public by default
+        let is_public = !node.children().any(|child| {
+            child.kind_id() == Modifiers
+                && child.children().any(|modifier| {
+                    modifier.kind_id() == VisibilityModifier
+                        && text(&modifier, code) != Some("public")
+                })
+        });
+        let in_class = node
+            .parent()
+            .is_some_and(|parent| matches!(parent.kind_id().into(), ClassBody | EnumClassBody));
+        let visible = |kind| {
+            if is_public {
+                public(node, code, kind)
+            } else {
+                Visibility::Private
+            }
+        };
+
+        let declaration = match node.kind_id().into() {
+            ClassDeclaration if node.is_child(Interface as u16) => {
+                visible(ApiSymbolKind::Interface)
+            }
+            ClassDeclaration | ObjectDeclaration => visible(ApiSymbolKind::Class),
+            // The members of a companion object are qualified by its class
+            CompanionObject if is_public => return None,
+            CompanionObject => Visibility::Private,
+            FunctionDeclaration if in_class => visible(ApiSymbolKind::Method),
+            FunctionDeclaration => visible(ApiSymbolKind::Function),
+            PropertyDeclaration if is_public => {
+                let kind = if in_class {
+                    ApiSymbolKind::Field
+                } else {
+                    ApiSymbolKind::Variable
+                };
+                let name = node
+                    .children()
+                    .find(|child| child.kind_id() == VariableDeclaration)
+                    .and_then(|variable| variable.child(0))
+                    .and_then(|name| text(&name, code));
+                name.map_or(Visibility::Private, |name| Visibility::Public(kind, name))
+            }
+            PropertyDeclaration => Visibility::Private,
+            _ => return None,
+        };
+        Some(declaration)
+    }
+}
+
+implement_metric_trait!([Exports], CppCode, PreprocCode, CcommentCode);
+
+/// Detects the public API of a code.
+///
+/// The public symbols are the `pub` items in Rust, the exports
+/// in JavaScript and TypeScript, the public declarations in Java
+/// and Kotlin and the names not starting with an underscore in Python.
+/// The members of a symbol are public only if the symbol is public too.
+///
+/// Returns a vector containing the [`ApiSymbol`] of each public symbol
+///
+/// [`ApiSymbol`]: struct.ApiSymbol.html
+pub fn public_api<T: ParserTrait>(parser: &T) -> Vec<ApiSymbol> {
+    let code = parser.get_code();
+    let separator = qualified_name_separator(parser.get_language());
+    let mut symbols = Vec::new();
+
+    // The nodes are visited in the order of the source code,
+    // along with the qualified name of their container
+    let mut stack = vec![(parser.get_root(), None::<String>)];
+    while let Some((node, prefix)) = stack.pop() {
+        let qualify = |name: &str| match &prefix {
+            Some(prefix) => format!("{prefix}{separator}{name}"),
+            None => name.to_owned(),
+        };
+        let members = match T::Exports::get_visibility(&node, code) {
+            Some(Visibility::Public(kind, name)) => {
+                let name = qualify(name);
+                symbols.push(ApiSymbol {
+                    name: name.clone(),
+                    kind,
+                    start_line: node.start_row() + 1,
+                    end_line: node.end_row() + 1,
+                });
+                kind.is_container().then_some(Some(name))
+            }
+            Some(Visibility::Scope(name)) => Some(Some(qualify(name))),
+            Some(Visibility::Private) => None,
+            None => Some(prefix),
+        };
+        if let Some(prefix) = members {
+            let children: Vec<_> = node.children().collect();
+            stack.extend(
+                children
+                    .into_iter()
+                    .rev()
+                    .map(|child| (child, prefix.clone())),
+            );
+        }
+    }
+
+    symbols
+}
+
+/// Returns the public API of a file.
+///
+/// * `parser` - The parser of the code.
+/// * `path` - The path of the file.
+pub fn public_api_symbols<T: ParserTrait>(parser: &T, path: &Path) -> PublicApiSymbols {
+    PublicApiSymbols {
+        name: path.to_str().unwrap_or_default().to_owned(),
+        symbols: public_api(parser),
+    }
+}
+
+fn dump_symbol(
+    symbol: &ApiSymbol,
+    stdout: &mut StandardStreamLock,
+    last: bool,
+) -> std::io::Result<()> {
+    let pref = if last { "   `- " } else { "   |- " };
+
+    color(stdout, Color::Blue)?;
+    write!(stdout, "{pref}")?;
+
+    intense_color(stdout, Color::Yellow)?;
+    write!(stdout, "{:?} ", symbol.kind)?;
+
+    intense_color(stdout, Color::Magenta)?;
+    write!(stdout, "{}: ", symbol.name)?;
+
+    color(stdout, Color::Green)?;
+    write!(stdout, "from line ")?;
+
+    color(stdout, Color::White)?;
+    write!(stdout, "{}", symbol.start_line)?;
+
+    color(stdout, Color::Green)?;
+    write!(stdout, " to line ")?;
+
+    color(stdout, Color::White)?;
+    writeln!(stdout, "{}.", symbol.end_line)
+}
+
+fn dump_symbols(symbols: &[ApiSymbol], path: PathBuf) -> std::io::Result<()> {
+    if let Some((last, symbols)) = symbols.split_last() {
+        let stdout = StandardStream::stdout(ColorChoice::Always);
+        let mut stdout = stdout.lock();
+
+        intense_color(&mut stdout, Color::Yellow)?;
+        writeln!(&mut stdout, "In file {}", path.to_str().unwrap_or("..."))?;
+
+        for symbol in symbols {
+            dump_symbol(symbol, &mut stdout, false)?;
+        }
+        dump_symbol(last, &mut stdout, true)?;
+        color(&mut stdout, Color::White)?;
+    }
+    Ok(())
+}
+
+/// Configuration options for detecting the public API of a code.
+#[derive(Debug)]
+pub struct PublicApiCfg {
+    /// Path to the file containing the code
+    pub path: PathBuf,
+}
+
+pub struct PublicApi {
+    _guard: (),
+}
+
+impl Callback for PublicApi {
+    type Res = std::io::Result<()>;
+    type Cfg = PublicApiCfg;
+
+    fn call<T: ParserTrait>(cfg: Self::Cfg, parser: &T) -> Self::Res {
+        dump_symbols(&public_api(parser), cfg.path)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::path::Path;
+
+    use super::*;
+
+    fn check<T: ParserTrait>(source: &str, path: &str) -> Vec<String> {
+        let path = Path::new(path);
+        let parser = T::new(source.as_bytes().to_vec(), path, None);
+        public_api(&parser)
+            .into_iter()
+            .map(|symbol| format!("{:?} {}", symbol.kind, symbol.name))
+            .collect()
+    }
+
+    #[test]
+    fn rust_public_api() {
+        let api = check::<RustParser>(
+            "pub struct S { pub a: u8 }
+            pub(crate) fn h() {}
+            impl S {
+                pub fn new() -> Self { S { a: 0 } }
+                fn private(&self) {}
+            }
+            impl std::fmt::Display for S {
+                fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result { Ok(()) }
+            }
+            pub trait T { fn t(&self); }
+            pub mod m {
+                pub const C: u8 = 1;
+                pub(super) fn f() {}
+            }
+            mod tests {
+                pub fn test() {}
+            }",
+            "foo.rs",
+        );
+        insta::assert_debug_snapshot!(api, @r###"
+        [
+            "Class S",
+            "Method S::new",
+            "Interface T",
+            "Method T::t",
+            "Module m",
+            "Variable m::C",
+        ]
+        "###);
+    }
+
+    #[test]
+    fn python_public_api() {
+        let api = check::<PythonParser>(
+            "X = 1
+_Y = 2
+class A:
+    Z = 3
+    def __init__(self):
+        self.a = 1
+    @property
+    def m(self):
+        def inner():
+            pass
+    def _p(self):
+        pass
+class _B:
+    def m(self):
+        pass
+def f():
+    pass",
+            "foo.py",
+        );
+        insta::assert_debug_snapshot!(api, @r###"
+        [
+            "Variable X",
+            "Class A",
+            "Field A.Z",
+            "Method A.__init__",
+            "Method A.m",
+            "Function f",
+        ]
+        "###);
+    }
+
+    #[test]
+    fn javascript_public_api() {
+        let api = check::<JavascriptParser>(
+            "export function f() { function g() {} }
+            export const a = 1, b = () => 2;
+            export class C { m() {} #p() {} }
+            export { a as z, f };
+            export default function () {}
+            class K { q() {} }
+            const c = 3;",
+            "foo.js",
+        );
+        insta::assert_debug_snapshot!(api, @r###"
+        [
+            "Function f",
+            "Variable a",
+            "Variable b",
+            "Class C",
+            "Method C.m",
+            "Export z",
+            "Export f",
+            "Function default",
+        ]
+        "###);
+    }
+
+    #[test]
+    fn typescript_public_api() {
+        let api = check::<TypescriptParser>(
+            "export class C { m(): void {} private p(): void {} protected q(): void {} }
+            export interface I { x: number; y(): void }
+            export type U = number;
+            export enum E { A, B }
+            export namespace N { export function f(): void {} function g(): void {} }
+            interface J {}
+            export default 42;",
+            "foo.ts",
+        );
+        insta::assert_debug_snapshot!(api, @r###"
+        [
+            "Class C",
+            "Method C.m",
+            "Interface I",
+            "Field I.x",
+            "Method I.y",
+            "Type U",
+            "Enum E",
+            "Module N",
+            "Function N.f",
+            "Export default",
+        ]
+        "###);
+    }
+
+    #[test]
+    fn java_public_api() {
+        let api = check::<JavaParser>(
+            "public class A {
+                public int x, y;
+                private int z;
+                public A() {}
+                public void m() {}
+                void p() {}
+                public interface I { void q(); }
+                public enum E { ONE, TWO }
+                static { int local = 0; }
+            }
+            class B {
+                public void m() {}
+            }",
+            "A.java",
+        );
+        insta::assert_debug_snapshot!(api, @r###"
+        [
+            "Class A",
+            "Field A.x",
+            "Field A.y",
+            "Method A.A",
+            "Method A.m",
+            "Interface A.I",
+            "Method A.I.q",
+            "Enum A.E",
+            "Field A.E.ONE",
+            "Field A.E.TWO",
+        ]
+        "###);
+    }
+
+    #[test]
+    fn kotlin_public_api() {
+        let api = check::<KotlinParser>(
+            "class A {
+                fun m() {}
+                private fun p() {}
+                val x = 1
+                companion object {
+                    fun create(): A = A()
+                }
+            }
+            internal fun f() {}
+            object O {}
+            interface I
+            val v = 2",
+            "foo.kt",
+        );
+        insta::assert_debug_snapshot!(api, @r###"
+        [
+            "Class A",
+            "Method A.m",
+            "Field A.x",
+            "Method A.create",
+            "Class O",
+            "Interface I",
+            "Variable v",
+        ]
+        "###);
+    }
+
+    #[test]
+    fn cpp_public_api() {
+        assert!(check::<CppParser>("int f() { return 0; }", "foo.cpp").is_empty());
+    }
+}
@@ -0,0 +1,270 @@
+use std::fs::File;
+use std::io::{self, Read};
+use std::path::{Component, Path, PathBuf};
+
+use flate2::read::GzDecoder;
+
+/// The list of supported archive formats.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ArchiveKind {
+    /// A zip archive, as well as a wheel or a jar
+    Zip,
+    /// An uncompressed tar archive
+    Tar,
+    /// A gzip-compressed tar archive
+    TarGz,
+}
+
+impl ArchiveKind {
+    /// Detects the format of an archive using the name of the file.
+    ///
+    /// Returns `None` if the file is not a supported archive.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use std::path::Path;
+    ///
+    /// use rust_code_analysis::ArchiveKind;
+    ///
+    /// let path = Path::new("foo-1.0.tar.gz");
+    ///
+    /// assert_eq!(ArchiveKind::from_path(path), Some(ArchiveKind::TarGz));
+    /// ```
+    pub fn from_path(path: &Path) -> Option<Self> {
+        let name = path.file_name()?.to_str()?.to_lowercase();
+        if name.ends_with(".tar.gz") || name.ends_with(".tgz") {
+            Some(Self::TarGz)
+        } else if name.ends_with(".tar") {
+            Some(Self::Tar)
+        } else if name.ends_with(".zip") || name.ends_with(".whl") || name.ends_with(".jar") {
+            Some(Self::Zip)
+        } else {
+            None
+        }
+    }
+}
+
+// The size of the largest file decompressed from an archive
+const MAX_ENTRY_SIZE: u64 = 64 * 1024 * 1024;
+
+// Entries trying to escape the archive are skipped
+fn is_safe_entry(path: &Path) -> bool {
+    path.components()
+        .all(|component| matches!(component, Component::Normal(_) | Component::CurDir))
+}
+
+// Reads an entry without trusting the size declared by the archive,
+// returning None for the entries larger than the maximum size
+fn read_entry<R: Read>(reader: R, path: &Path, max_size: u64) -> io::Result<Option<Vec<u8>>> {
+    let mut data = Vec::new();
+    reader.take(max_size + 1).read_to_end(&mut data)?;
+    if data.len() as u64 > max_size {
+        tracing::warn!(
+            path = %path.display(),
+            "Skipping an archive entry larger than {max_size} bytes"
+        );
+        return Ok(None);
+    }
+    Ok(Some(data))
+}
+
+fn visit_zip<Filter, Visit>(path: &Path, filter: Filter, mut visit: Visit) -> io::Result<()>
+where
+    Filter: Fn(&Path) -> bool,
+    Visit: FnMut(PathBuf, Vec<u8>) -> io::Result<()>,
+{
+    let mut archive = zip::ZipArchive::new(File::open(path)?)?;
+    for i in 0..archive.len() {
+        let mut file = archive.by_index(i)?;
+        if !file.is_file() {
+            continue;
+        }
+        let Some(name) = file.enclosed_name() else {
+            continue;
+        };
+        let entry_path = path.join(name);
+        if !filter(&entry_path) {
+            continue;
+        }
+        if let Some(data) = read_entry(&mut file, &entry_path, MAX_ENTRY_SIZE)? {
+            visit(entry_path, data)?;
+        }
+    }
+
+    Ok(())
+}
+
+fn visit_tar<R, Filter, Visit>(
+    path: &Path,
+    reader: R,
+    filter: Filter,
+    mut visit: Visit,
+) -> io::Result<()>
+where
+    R: Read,
+    Filter: Fn(&Path) -> bool,
+    Visit: FnMut(PathBuf, Vec<u8>) -> io::Result<()>,
+{
+    let mut archive = tar::Archive::new(reader);
+    for entry in archive.entries()? {
+        let mut entry = entry?;
+        if !entry.header().entry_type().is_file() {
+            continue;
+        }
+        let name = entry.path()?.into_owned();
+        if !is_safe_entry(&name) {
+            continue;
+        }
+        let entry_path = path.join(name);
+        if !filter(&entry_path) {
+            continue;
+        }
+        if let Some(data) = read_entry(&mut entry, &entry_path, MAX_ENTRY_SIZE)? {
+            visit(entry_path, data)?;
+        }
+    }
+
+    Ok(())
+}
+
+/// Decompresses in memory, one at a time, the files contained in an archive.
+///
+/// The path of each file is the path of the archive followed by the path
+/// of the file inside the archive, such as `foo.zip/src/main.rs`.
+/// The files larger than 64 MiB once decompressed are skipped.
+///
+/// * `filter` - Function that, given the path of a file, checks whether
+///   that file has to be decompressed.
+/// * `visit` - Function that processes the path and the content of
+///   each decompressed file.
+///
+/// # Examples
+///
+/// ```no_run
+/// use std::path::Path;
+///
+/// use rust_code_analysis::visit_archive;
+///
+/// let path = Path::new("foo.zip");
+///
+/// visit_archive(
+///     path,
+///     |path| path.extension().is_some_and(|ext| ext == "rs"),
+///     |path, data| {
+///         println!("{}: {} bytes", path.display(), data.len());
+///         Ok(())
+///     },
+/// )
+/// .unwrap();
+/// ```
+pub fn visit_archive<Filter, Visit>(path: &Path, filter: Filter, visit: Visit) -> io::Result<()>
+where
+    Filter: Fn(&Path) -> bool,
+    Visit: FnMut(PathBuf, Vec<u8>) -> io::Result<()>,
+{
+    let _span = tracing::debug_span!("archive", path = %path.display()).entered();
+
+    match ArchiveKind::from_path(path) {
+        Some(ArchiveKind::Zip) => visit_zip(path, filter, visit),
+        Some(ArchiveKind::Tar) => visit_tar(path, File::open(path)?, filter, visit),
+        Some(ArchiveKind::TarGz) => {
+            visit_tar(path, GzDecoder::new(File::open(path)?), filter, visit)
+        }
+        None => Err(io::Error::new(
+            io::ErrorKind::InvalidInput,
+            format!("{} is not a supported archive", path.display()),
+        )),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io::Write;
+
+    use flate2::Compression;
+    use flate2::write::GzEncoder;
+    use pretty_assertions::assert_eq;
+
+    use super::*;
+
+    fn collect(path: &Path) -> Vec<(PathBuf, Vec<u8>)> {
+        let mut entries = Vec::new();
+        visit_archive(
+            path,
+            |path| path.extension().is_some_and(|ext| ext == "py"),
+            |path, data| {
+                entries.push((path, data));
+                Ok(())
+            },
+        )
+        .unwrap();
+        entries
+    }
+
+    #[test]
+    fn test_archive_kind() {
+        assert_eq!(
+            ArchiveKind::from_path(Path::new("foo.whl")),
+            Some(ArchiveKind::Zip)
+        );
+        assert_eq!(
+            ArchiveKind::from_path(Path::new("foo.TGZ")),
+            Some(ArchiveKind::TarGz)
+        );
+        assert_eq!(
+            ArchiveKind::from_path(Path::new("foo.tar")),
+            Some(ArchiveKind::Tar)
+        );
+        assert_eq!(ArchiveKind::from_path(Path::new("foo.gz")), None);
+    }
+
+    #[test]
+    fn test_read_entry() {
+        let path = Path::new("foo.zip/foo.py");
+        assert_eq!(
+            read_entry(&b"a = 42\n"[..], path, 7).unwrap(),
+            Some(b"a = 42\n".to_vec())
+        );
+        // The entries larger than the maximum size are skipped
+        assert_eq!(read_entry(&b"a = 42\n"[..], path, 6).unwrap(), None);
+    }
+
+    #[test]
+    fn test_visit_zip() {
+        let path = std::env::temp_dir().join("test_visit_zip.zip");
+        let mut writer = zip::ZipWriter::new(File::create(&path).unwrap());
+        let options = zip::write::SimpleFileOptions::default();
+        writer.start_file("pkg/foo.py", options).unwrap();
+        writer.write_all(b"a = 42\n").unwrap();
+        writer.start_file("pkg/README.md", options).unwrap();
+        writer.write_all(b"# foo\n").unwrap();
+        writer.finish().unwrap();
+
+        assert_eq!(
+            collect(&path),
+            vec![(path.join("pkg/foo.py"), b"a = 42\n".to_vec())]
+        );
+    }
+
+    #[test]
+    fn test_visit_tar_gz() {
+        let path = std::env::temp_dir().join("test_visit_tar_gz.tar.gz");
+        let encoder = GzEncoder::new(File::create(&path).unwrap(), Compression::default());
+        let mut builder = tar::Builder::new(encoder);
+        let files: [(&str, &[u8]); 2] = [("foo/bar.py", b"b = 1\n"), ("foo/bar.rs", b"fn f(){}")];
+        for (name, data) in files {
+            let mut header = tar::Header::new_gnu();
+            header.set_size(data.len() as u64);
+            header.set_mode(0o644);
+            header.set_cksum();
+            builder.append_data(&mut header, name, data).unwrap();
+        }
+        builder.into_inner().unwrap().finish().unwrap();
+
+        assert_eq!(
+            collect(&path),
+            vec![(path.join("foo/bar.py"), b"b = 1\n".to_vec())]
+        );
+    }
+}
@@ -26,6 +26,8 @@ pub struct AstPayload {
     /// If `true`, the start and end positions of a node in a code
     /// are considered
     pub span: bool,
+    /// The dialect of the language of the code, such as `c` or `tsx`
+    pub dialect: Option<String>,
 }
 
 /// The response of an `AST` request.
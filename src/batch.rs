@@ -0,0 +1,777 @@
+use std::collections::HashMap;
+use std::fmt;
+use std::panic::{self, AssertUnwindSafe};
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex, OnceLock, Weak};
+
+use serde::Serialize;
+use sha2::{Digest, Sha256};
+
+use crate::classification::{FileClass, FileClassification};
+use crate::concurrent_files::{ConcurrentErrors, ConcurrentRunner, FilesData};
+use crate::embedded::{get_embedded_spaces, is_component};
+use crate::langs::{LANG, get_function_spaces};
+use crate::metadata::FileMetadata;
+use crate::spaces::{FuncSpace, MetricsOptions, rename_unit};
+use crate::tools::{guess_language, read_bytes_with_eol};
+
+/// The kinds of errors which can occur when analyzing a file.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum FileErrorKind {
+    /// The file cannot be read
+    Io,
+    /// The file is not a text file
+    Encoding,
+    /// The code of the file cannot be analyzed
+    Parse,
+}
+
+impl fmt::Display for FileErrorKind {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let s = match self {
+            FileErrorKind::Io => "io",
+            FileErrorKind::Encoding => "encoding",
+            FileErrorKind::Parse => "parse",
+        };
+        write!(f, "{s}")
+    }
+}
+
+/// An error occurred analyzing a file of a batch.
+#[derive(Clone, Debug, Serialize)]
+pub struct FileError {
+    /// The path of the file
+    pub path: PathBuf,
+    /// The error kind
+    pub kind: FileErrorKind,
+    /// A description of the error
+    pub message: String,
+}
+
+impl FileError {
+    fn new(path: &Path, kind: FileErrorKind, message: impl Into<String>) -> Self {
+        Self {
+            path: path.to_path_buf(),
+            kind,
+            message: message.into(),
+        }
+    }
+}
+
+/// The results of the analysis of a batch of files.
+#[derive(Debug, Default, Serialize)]
+pub struct BatchResults {
+    /// The function spaces of the successfully analyzed files,
+    /// sorted by path
+    pub spaces: Vec<FuncSpace>,
+    /// The errors occurred analyzing the other files, sorted by path
+    pub errors: Vec<FileError>,
+    /// The classes of the files which have been read, including the ones
+    /// excluded by the globs. The files which cannot be read are only
+    /// reported as errors
+    pub classification: FileClassification,
+}
+
+type OnResultFunction = dyn Fn(Result<FuncSpace, FileError>) + Send + Sync;
+
+type Analysis = Result<Option<FuncSpace>, FileError>;
+
+// The language and the SHA-256 digest of a code: the files
+// with the same key have the same metrics
+type CodeKey = (&'static str, [u8; 32]);
+
+// The first file with a code, and the analysis of the code which is
+// kept only while it is shared by the files analyzed at the same time
+struct KnownCode {
+    original: PathBuf,
+    analysis: Weak<OnceLock<Analysis>>,
+}
+
+struct BatchCfg {
+    language: Option<LANG>,
+    on_result: Box<OnResultFunction>,
+    // The codes met so far. A code analyzed by a thread is waited
+    // by the other threads analyzing the same code
+    analyses: Mutex<HashMap<CodeKey, KnownCode>>,
+    classification: Mutex<FileClassification>,
+}
+
+impl BatchCfg {
+    fn classify(&self, class: FileClass) {
+        self.classification.lock().unwrap().add(class);
+    }
+}
+
+fn analyze_data(path: &Path, data: &[u8], language: Option<LANG>, cfg: &BatchCfg) -> Analysis {
+    let Some(source) = read_bytes_with_eol(data) else {
+        return if data.len() <= 3 {
+            // An almost empty file, nothing to analyze
+            cfg.classify(FileClass::Ignored);
+            Ok(None)
+        } else {
+            cfg.classify(FileClass::Binary);
+            Err(FileError::new(
+                path,
+                FileErrorKind::Encoding,
+                "The file is not a valid text file",
+            ))
+        };
+    };
+
+    // Files whose language is unknown are not source files,
+    // except for the components embedding their scripts
+    let language = language.or_else(|| guess_language(&source, path).0);
+    if language.is_none() && !is_component(path) {
+        tracing::debug!("Skipping file with an unknown language");
+        cfg.classify(FileClass::Unsupported);
+        return Ok(None);
+    }
+    cfg.classify(FileClass::Source);
+
+    let key = (
+        language.map_or("component", |language| language.get_name()),
+        Sha256::digest(&source).into(),
+    );
+    let (analysis, original) = shared_analysis(key, path, cfg);
+    let mut source = Some(source);
+    let result = analysis.get_or_init(|| analyze_code(path, source.take().unwrap(), language));
+    match (source, original) {
+        // The code has been analyzed for this file
+        (None, None) => result.clone(),
+        // The code has been analyzed again for this file, since its
+        // previous analysis is not kept once it has been handed out
+        (None, Some(original)) => {
+            let mut result = result.clone();
+            if let Ok(Some(space)) = &mut result {
+                space.metadata = space.metadata.take().map(|metadata| FileMetadata {
+                    duplicate_of: original.to_str().map(str::to_owned),
+                    ..metadata
+                });
+            }
+            result
+        }
+        (Some(_), _) => {
+            tracing::debug!("Reusing the analysis of a file with the same code");
+            // The scripts of the components are in JavaScript or in TypeScript,
+            // whose modules are named after the files in the same way
+            duplicate(result, path, language.unwrap_or(LANG::Javascript))
+        }
+    }
+}
+
+// Returns the analysis of a code, shared with the threads analyzing
+// the same code, and the first file with the code if it is not this one
+fn shared_analysis(
+    key: CodeKey,
+    path: &Path,
+    cfg: &BatchCfg,
+) -> (Arc<OnceLock<Analysis>>, Option<PathBuf>) {
+    let mut analyses = cfg.analyses.lock().unwrap();
+    match analyses.get_mut(&key) {
+        Some(known) => {
+            let analysis = known.analysis.upgrade().unwrap_or_else(|| {
+                let analysis = Arc::default();
+                known.analysis = Arc::downgrade(&analysis);
+                analysis
+            });
+            (analysis, Some(known.original.clone()))
+        }
+        None => {
+            let analysis = Arc::default();
+            analyses.insert(
+                key,
+                KnownCode {
+                    original: path.to_path_buf(),
+                    analysis: Arc::downgrade(&analysis),
+                },
+            );
+            (analysis, None)
+        }
+    }
+}
+
+fn analyze_code(path: &Path, source: Vec<u8>, language: Option<LANG>) -> Analysis {
+    match panic::catch_unwind(AssertUnwindSafe(|| match language {
+        Some(language) => get_function_spaces(&language, source, path, None),
+        None => {
+            get_embedded_spaces(&source, path, &MetricsOptions::default()).map(|(_, space)| space)
+        }
+    })) {
+        Ok(Some(space)) => Ok(Some(space)),
+        // A component without scripts
+        Ok(None) if language.is_none() => Ok(None),
+        Ok(None) => Err(FileError::new(
+            path,
+            FileErrorKind::Parse,
+            "Failed to compute the metrics",
+        )),
+        Err(_) => Err(FileError::new(
+            path,
+            FileErrorKind::Parse,
+            "The analysis of the code panicked",
+        )),
+    }
+}
+
+// Copies the analysis of a file to another file with the same code,
+// marking the latter as a duplicate of the former
+fn duplicate(analysis: &Analysis, path: &Path, language: LANG) -> Analysis {
+    match analysis {
+        Ok(Some(space)) => {
+            let mut space = space.clone();
+            let analyzed = space.name.clone();
+            rename_unit(&mut space, path, language);
+            space.metadata = space.metadata.map(|metadata| FileMetadata {
+                duplicate_of: metadata.duplicate_of.clone().or(analyzed),
+                ..metadata.for_file(path)
+            });
+            Ok(Some(space))
+        }
+        Ok(None) => Ok(None),
+        Err(error) => Err(FileError {
+            path: path.to_path_buf(),
+            ..error.clone()
+        }),
+    }
+}
+
+fn store(path: &Path, data: &[u8], language: Option<LANG>, cfg: &BatchCfg) {
+    match analyze_data(path, data, language, cfg) {
+        Ok(Some(space)) => (cfg.on_result)(Ok(space)),
+        Ok(None) => {}
+        Err(error) => {
+            tracing::warn!(kind = %error.kind, "{}", error.message);
+            (cfg.on_result)(Err(error))
+        }
+    }
+}
+
+fn analyze_batch_file(path: PathBuf, cfg: &Arc<BatchCfg>) -> std::io::Result<()> {
+    match std::fs::read(&path) {
+        Ok(data) => store(&path, &data, cfg.language, cfg),
+        Err(e) => (cfg.on_result)(Err(FileError::new(&path, FileErrorKind::Io, e.to_string()))),
+    }
+    Ok(())
+}
+
+fn analyze_batch_entry(path: PathBuf, data: Vec<u8>, cfg: &Arc<BatchCfg>) -> std::io::Result<()> {
+    store(&path, &data, cfg.language, cfg);
+    Ok(())
+}
+
+/// Computes the metrics of a batch of files concurrently, calling
+/// a function with the result of each file as soon as it is available.
+///
+/// The order in which the results are passed to the function
+/// depends on the order in which the analyses complete, so does the file
+/// which is analyzed among the files with the same code, while the other
+/// ones are marked as its duplicates.
+/// See [`analyze_batch`] for the handling of errors.
+///
+/// * `files_data` - The files to analyze.
+/// * `language` - The language of the files. If `None`, the language
+///   of each file is guessed.
+/// * `num_jobs` - Number of jobs utilized to analyze files concurrently.
+/// * `on_result` - Function called with either the function space
+///   or the error of each file.
+///
+/// Returns the classes of the files, as in [`BatchResults::classification`].
+///
+/// # Examples
+///
+/// ```
+/// use std::path::PathBuf;
+///
+/// use globset::GlobSet;
+/// use rust_code_analysis::{FilesData, SymlinkPolicy, analyze_batch_with};
+///
+/// let files_data = FilesData {
+///     include: GlobSet::empty(),
+///     exclude: GlobSet::empty(),
+///     paths: vec![PathBuf::from("src/lib.rs")],
+///     symlinks: SymlinkPolicy::default(),
+/// };
+///
+/// analyze_batch_with(files_data, None, 2, |res| match res {
+///     Ok(space) => println!("{:?}: {}", space.name, space.metrics.cyclomatic),
+///     Err(error) => eprintln!("{:?}: {}", error.path, error.message),
+/// })
+/// .unwrap();
+/// ```
+///
+/// [`analyze_batch`]: fn.analyze_batch.html
+/// [`BatchResults::classification`]: struct.BatchResults.html#structfield.classification
+pub fn analyze_batch_with<OnResult>(
+    mut files_data: FilesData,
+    language: Option<LANG>,
+    num_jobs: usize,
+    on_result: OnResult,
+) -> Result<FileClassification, ConcurrentErrors>
+where
+    OnResult: 'static + Fn(Result<FuncSpace, FileError>) + Send + Sync,
+{
+    files_data.paths.retain(|path| {
+        let exists = path.exists();
+        if !exists {
+            on_result(Err(FileError::new(
+                path,
+                FileErrorKind::Io,
+                "No such file or directory",
+            )));
+        }
+        exists
+    });
+
+    let cfg = Arc::new(BatchCfg {
+        language,
+        on_result: Box::new(on_result),
+        analyses: Mutex::new(HashMap::new()),
+        classification: Mutex::new(FileClassification::default()),
+    });
+
+    ConcurrentRunner::new(num_jobs, analyze_batch_file)
+        .set_proc_ignored_path(|_, cfg: &Arc<BatchCfg>| cfg.classify(FileClass::Ignored))
+        .set_proc_archive_entry(analyze_batch_entry)
+        .set_proc_archive_error(|path, e, cfg: &Arc<BatchCfg>| {
+            (cfg.on_result)(Err(FileError::new(path, FileErrorKind::Io, e.to_string())))
+        })
+        .run(cfg.clone(), files_data)?;
+
+    let classification = std::mem::take(&mut *cfg.classification.lock().unwrap());
+    Ok(classification)
+}
+
+/// Computes the metrics of a batch of files concurrently.
+///
+/// A file which cannot be analyzed does not stop the analysis of the batch,
+/// but it is reported as a [`FileError`]. Files whose language cannot be
+/// detected are skipped. When the `archive` feature is enabled, the files
+/// contained in the archives passed as paths are analyzed too.
+///
+/// The files with the same code, such as vendored copies, are recognized
+/// by the SHA-256 digest of their code: the metadata of their results names
+/// the first of them in the order of the paths as
+/// [`FileMetadata::duplicate_of`]. The files with the same code analyzed
+/// at the same time share a single analysis, which is not kept afterwards
+/// so that the memory used does not grow with the results.
+///
+/// * `files_data` - The files to analyze.
+/// * `language` - The language of the files. If `None`, the language
+///   of each file is guessed.
+/// * `num_jobs` - Number of jobs utilized to analyze files concurrently.
+///
+/// # Examples
+///
+/// ```
+/// use std::path::PathBuf;
+///
+/// use globset::GlobSet;
+/// use rust_code_analysis::{FilesData, SymlinkPolicy, analyze_batch};
+///
+/// let files_data = FilesData {
+///     include: GlobSet::empty(),
+///     exclude: GlobSet::empty(),
+///     paths: vec![PathBuf::from("src/lib.rs")],
+///     symlinks: SymlinkPolicy::default(),
+/// };
+///
+/// let results = analyze_batch(files_data, None, 2).unwrap();
+/// assert_eq!(results.spaces.len(), 1);
+/// assert!(results.errors.is_empty());
+/// ```
+///
+/// [`FileError`]: struct.FileError.html
+/// [`FileMetadata::duplicate_of`]: struct.FileMetadata.html#structfield.duplicate_of
+pub fn analyze_batch(
+    files_data: FilesData,
+    language: Option<LANG>,
+    num_jobs: usize,
+) -> Result<BatchResults, ConcurrentErrors> {
+    let results = Arc::new(Mutex::new(BatchResults::default()));
+    let shared_results = results.clone();
+
+    let classification = analyze_batch_with(files_data, language, num_jobs, move |res| {
+        let mut results = shared_results.lock().unwrap();
+        match res {
+            Ok(space) => results.spaces.push(space),
+            Err(error) => results.errors.push(error),
+        }
+    })?;
+
+    let mut results = std::mem::take(&mut *results.lock().unwrap());
+    results.classification = classification;
+    Ok(sort_results(results))
+}
+
+/// A file to analyze in a batch whose code is already in memory,
+/// such as a file read from a database or from a git index.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct SourceFile {
+    /// The path of the file, naming its results
+    pub path: PathBuf,
+    /// The code of the file
+    pub code: Vec<u8>,
+    /// The language of the code. If `None`, it is guessed
+    /// from the path and the code
+    pub language: Option<LANG>,
+}
+
+/// Computes the metrics of a batch of codes already in memory concurrently,
+/// without writing them to temporary files.
+///
+/// The results are the ones of [`analyze_batch`], sorted by path,
+/// and the codes are analyzed from the largest one to balance the jobs.
+///
+/// * `sources` - The codes to analyze.
+/// * `num_jobs` - Number of jobs utilized to analyze the codes concurrently.
+///
+/// # Examples
+///
+/// ```
+/// use std::path::PathBuf;
+///
+/// use rust_code_analysis::{LANG, SourceFile, analyze_sources};
+///
+/// let sources = vec![
+///     SourceFile {
+///         path: PathBuf::from("foo.py"),
+///         code: b"def foo():\n    pass\n".to_vec(),
+///         language: None,
+///     },
+///     SourceFile {
+///         path: PathBuf::from("bar"),
+///         code: b"fn bar() {}\n".to_vec(),
+///         language: Some(LANG::Rust),
+///     },
+/// ];
+///
+/// let results = analyze_sources(sources, 2);
+/// assert_eq!(results.spaces.len(), 2);
+/// assert_eq!(results.spaces[0].name.as_deref(), Some("bar"));
+/// ```
+///
+/// [`analyze_batch`]: fn.analyze_batch.html
+pub fn analyze_sources(mut sources: Vec<SourceFile>, num_jobs: usize) -> BatchResults {
+    let results = Arc::new(Mutex::new(BatchResults::default()));
+    let shared_results = results.clone();
+    let cfg = BatchCfg {
+        language: None,
+        on_result: Box::new(move |res| {
+            let mut results = shared_results.lock().unwrap();
+            match res {
+                Ok(space) => results.spaces.push(space),
+                Err(error) => results.errors.push(error),
+            }
+        }),
+        analyses: Mutex::new(HashMap::new()),
+        classification: Mutex::new(FileClassification::default()),
+    };
+
+    sources.sort_by_key(|source| std::cmp::Reverse(source.code.len()));
+    let (sender, receiver) = crossbeam::channel::unbounded();
+    for source in sources {
+        sender.send(source).unwrap();
+    }
+    drop(sender);
+
+    std::thread::scope(|scope| {
+        for _ in 0..num_jobs.max(1) {
+            scope.spawn(|| {
+                for source in receiver.iter() {
+                    store(&source.path, &source.code, source.language, &cfg);
+                }
+            });
+        }
+    });
+
+    let mut results = std::mem::take(&mut *results.lock().unwrap());
+    results.classification = cfg.classification.into_inner().unwrap();
+    sort_results(results)
+}
+
+// Sorts the results by path and marks the duplicates
+fn sort_results(mut results: BatchResults) -> BatchResults {
+    results.spaces.sort_by(|a, b| a.name.cmp(&b.name));
+    results.errors.sort_by(|a, b| a.path.cmp(&b.path));
+    mark_duplicates(&mut results.spaces);
+    results
+}
+
+// Marks the files with the same code as duplicates of the first of them,
+// regardless of which one has been analyzed. The spaces are sorted by path
+fn mark_duplicates(spaces: &mut [FuncSpace]) {
+    let mut originals = HashMap::new();
+    for space in spaces.iter() {
+        if let Some(metadata) = &space.metadata {
+            let analyzed = metadata.duplicate_of.clone().or_else(|| space.name.clone());
+            originals
+                .entry(analyzed)
+                .or_insert_with(|| space.name.clone());
+        }
+    }
+    for space in spaces {
+        if let Some(metadata) = &mut space.metadata {
+            let analyzed = metadata.duplicate_of.clone().or_else(|| space.name.clone());
+            metadata.duplicate_of = originals
+                .get(&analyzed)
+                .cloned()
+                .flatten()
+                .filter(|original| Some(original) != space.name.as_ref());
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use globset::GlobSet;
+    use pretty_assertions::assert_eq;
+
+    use crate::concurrent_files::SymlinkPolicy;
+
+    use super::*;
+
+    #[test]
+    fn test_analyze_batch() {
+        let dir = std::env::temp_dir().join("test_analyze_batch");
+        std::fs::create_dir_all(&dir).unwrap();
+        let good = dir.join("good.py");
+        let binary = dir.join("binary.py");
+        let unknown = dir.join("unknown.txt");
+        std::fs::write(&good, "def foo():\n    pass\n").unwrap();
+        std::fs::write(&binary, b"\x7FELF\x02\x01\x01\x00\xC3\x28\xA0\xA1").unwrap();
+        std::fs::write(&unknown, "hello world\n").unwrap();
+        let missing = dir.join("missing.py");
+
+        let files_data = FilesData {
+            include: GlobSet::empty(),
+            exclude: GlobSet::empty(),
+            paths: vec![
+                good.clone(),
+                binary.clone(),
+                unknown.clone(),
+                missing.clone(),
+            ],
+            symlinks: SymlinkPolicy::default(),
+        };
+        let results = analyze_batch(files_data, None, 2).unwrap();
+
+        assert_eq!(results.spaces.len(), 1);
+        assert_eq!(results.spaces[0].name.as_deref(), good.to_str());
+
+        let errors: Vec<_> = results
+            .errors
+            .iter()
+            .map(|error| (error.path.clone(), error.kind))
+            .collect();
+        assert_eq!(
+            errors,
+            vec![
+                (binary, FileErrorKind::Encoding),
+                (missing, FileErrorKind::Io)
+            ]
+        );
+        assert_eq!(
+            results.classification,
+            FileClassification {
+                source: 1,
+                binary: 1,
+                unsupported: 1,
+                ignored: 0,
+            }
+        );
+
+        // The files excluded by the globs are ignored
+        let files_data = FilesData {
+            include: GlobSet::empty(),
+            exclude: globset::GlobSetBuilder::new()
+                .add(globset::Glob::new("*.txt").unwrap())
+                .build()
+                .unwrap(),
+            paths: vec![good, unknown],
+            symlinks: SymlinkPolicy::default(),
+        };
+        let results = analyze_batch(files_data, None, 2).unwrap();
+        assert_eq!(
+            results.classification,
+            FileClassification {
+                source: 1,
+                binary: 0,
+                unsupported: 0,
+                ignored: 1,
+            }
+        );
+    }
+
+    #[test]
+    fn test_analyze_sources() {
+        let source = |path: &str, code: &str, language| SourceFile {
+            path: PathBuf::from(path),
+            code: code.as_bytes().to_vec(),
+            language,
+        };
+        let sources = vec![
+            source("b.py", "def foo(a):\n    return a or 1\n", None),
+            source("a", "fn bar() {}\n", Some(LANG::Rust)),
+            source("c.py", "def foo(a):\n    return a or 1\n", None),
+            source("d.txt", "hello world\n", None),
+            source("e.py", "\x00\x01\x02\x03\x04", None),
+        ];
+        let results = analyze_sources(sources, 2);
+
+        let spaces: Vec<_> = results
+            .spaces
+            .iter()
+            .map(|space| {
+                let metadata = space.metadata.as_ref().unwrap();
+                (
+                    space.name.clone().unwrap(),
+                    metadata.language,
+                    metadata.duplicate_of.clone(),
+                )
+            })
+            .collect();
+        assert_eq!(
+            spaces,
+            [
+                ("a".to_owned(), "rust", None),
+                ("b.py".to_owned(), "python", None),
+                ("c.py".to_owned(), "python", Some("b.py".to_owned())),
+            ]
+        );
+        let errors: Vec<_> = results
+            .errors
+            .iter()
+            .map(|error| (error.path.clone(), error.kind))
+            .collect();
+        assert_eq!(errors, [(PathBuf::from("e.py"), FileErrorKind::Encoding)]);
+        assert_eq!(
+            results.classification,
+            FileClassification {
+                source: 3,
+                binary: 1,
+                unsupported: 1,
+                ignored: 0,
+            }
+        );
+    }
+
+    #[test]
+    fn test_analyze_batch_duplicates() {
+        let dir = std::env::temp_dir().join("test_analyze_batch_duplicates");
+        std::fs::create_dir_all(&dir).unwrap();
+        let paths: Vec<_> = ["a.py", "b.py", "c.py", "d.py"]
+            .iter()
+            .map(|name| dir.join(name))
+            .collect();
+        let code = "def foo(a):\n    return a or 1\n";
+        for path in &paths[1..] {
+            std::fs::write(path, code).unwrap();
+        }
+        std::fs::write(&paths[0], "def bar():\n    pass\n").unwrap();
+
+        let files_data = FilesData {
+            include: GlobSet::empty(),
+            exclude: GlobSet::empty(),
+            paths: paths.iter().rev().cloned().collect(),
+            symlinks: SymlinkPolicy::default(),
+        };
+        let results = analyze_batch(files_data, None, 2).unwrap();
+
+        let spaces: Vec<_> = results
+            .spaces
+            .iter()
+            .map(|space| {
+                let metadata = space.metadata.as_ref().unwrap();
+                (space.name.clone(), metadata.duplicate_of.clone())
+            })
+            .collect();
+        let name = |i: usize| paths[i].to_str().map(str::to_owned);
+        assert_eq!(
+            spaces,
+            vec![
+                (name(0), None),
+                (name(1), None),
+                (name(2), name(1)),
+                (name(3), name(1)),
+            ]
+        );
+        // The copies have the same metrics
+        assert_eq!(
+            results.spaces[3].metrics.cyclomatic.cyclomatic_sum(),
+            results.spaces[1].metrics.cyclomatic.cyclomatic_sum()
+        );
+        assert_eq!(results.spaces[3].spaces[0].name.as_deref(), Some("foo"));
+        // The units of the copies are told apart by their paths
+        assert_ne!(results.spaces[2].id, results.spaces[1].id);
+        assert_ne!(results.spaces[3].id, results.spaces[2].id);
+    }
+
+    #[cfg(feature = "archive")]
+    #[test]
+    fn test_analyze_batch_corrupt_archive() {
+        let dir = std::env::temp_dir().join("test_analyze_batch_corrupt_archive");
+        std::fs::create_dir_all(&dir).unwrap();
+        let archive = dir.join("corrupt.zip");
+        let good = dir.join("good.py");
+        std::fs::write(&archive, b"PK\x03\x04 this is not a zip archive").unwrap();
+        std::fs::write(&good, "def foo():\n    pass\n").unwrap();
+
+        let files_data = FilesData {
+            include: GlobSet::empty(),
+            exclude: GlobSet::empty(),
+            paths: vec![archive.clone(), good.clone()],
+            symlinks: SymlinkPolicy::default(),
+        };
+        let results = analyze_batch(files_data, None, 2).unwrap();
+
+        // The archive is reported, while the other files are analyzed
+        assert_eq!(results.spaces.len(), 1);
+        assert_eq!(results.spaces[0].name.as_deref(), good.to_str());
+        let errors: Vec<_> = results
+            .errors
+            .iter()
+            .map(|error| (error.path.clone(), error.kind))
+            .collect();
+        assert_eq!(errors, vec![(archive, FileErrorKind::Io)]);
+    }
+
+    #[test]
+    fn test_analyze_sources_duplicates_one_job() {
+        let code = b"def foo(a):\n    return a or 1\n";
+        let sources = ["a.py", "b.py", "c.py"]
+            .iter()
+            .map(|path| SourceFile {
+                path: PathBuf::from(path),
+                code: code.to_vec(),
+                language: None,
+            })
+            .collect();
+        // A single job analyzes the files one at a time, so that
+        // no analysis is shared and the copies are analyzed again
+        let results = analyze_sources(sources, 1);
+
+        let spaces: Vec<_> = results
+            .spaces
+            .iter()
+            .map(|space| {
+                let metadata = space.metadata.as_ref().unwrap();
+                (space.name.clone().unwrap(), metadata.duplicate_of.clone())
+            })
+            .collect();
+        assert_eq!(
+            spaces,
+            [
+                ("a.py".to_owned(), None),
+                ("b.py".to_owned(), Some("a.py".to_owned())),
+                ("c.py".to_owned(), Some("a.py".to_owned())),
+            ]
+        );
+        assert_ne!(results.spaces[0].id, results.spaces[1].id);
+        // The functions are named after the module of their copy
+        assert_eq!(
+            results.spaces[1].spaces[0].qualified_name.as_deref(),
+            Some("b.foo")
+        );
+    }
+}
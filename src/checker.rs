@@ -108,6 +108,14 @@ pub trait Checker {
     fn is_error(node: &Node) -> bool {
         node.has_error()
     }
+
+    fn is_macro_definition(_: &Node) -> bool {
+        false
+    }
+
+    fn is_comprehension(_: &Node) -> bool {
+        false
+    }
 }
 
 impl Checker for PreprocCode {
@@ -294,6 +302,16 @@ impl Checker for PythonCode {
         node.kind_id() == Python::Lambda
     }
 
+    fn is_comprehension(node: &Node) -> bool {
+        matches!(
+            node.kind_id().into(),
+            Python::ListComprehension
+                | Python::SetComprehension
+                | Python::DictionaryComprehension
+                | Python::GeneratorExpression
+        )
+    }
+
     fn is_call(node: &Node) -> bool {
         node.kind_id() == Python::Call
     }
@@ -488,19 +506,24 @@ impl Checker for TypescriptCode {
     }
 
     fn is_func_space(node: &Node) -> bool {
-        matches!(
-            node.kind_id().into(),
+        match node.kind_id().into() {
             Typescript::Program
-                | Typescript::FunctionExpression
-                | Typescript::Class
-                | Typescript::GeneratorFunction
-                | Typescript::FunctionDeclaration
-                | Typescript::MethodDefinition
-                | Typescript::GeneratorFunctionDeclaration
-                | Typescript::ClassDeclaration
-                | Typescript::InterfaceDeclaration
-                | Typescript::ArrowFunction
-        )
+            | Typescript::FunctionExpression
+            | Typescript::Class
+            | Typescript::GeneratorFunction
+            | Typescript::FunctionDeclaration
+            | Typescript::MethodDefinition
+            | Typescript::GeneratorFunctionDeclaration
+            | Typescript::ClassDeclaration
+            | Typescript::AbstractClassDeclaration
+            | Typescript::InterfaceDeclaration
+            | Typescript::ArrowFunction => true,
+            // Only the type aliases of object types declare members
+            Typescript::TypeAliasDeclaration => node
+                .child_by_field_name("value")
+                .is_some_and(|value| value.kind_id() == Typescript::ObjectType),
+            _ => false,
+        }
     }
 
     is_js_func_and_closure_checker!(TypescriptParser, Typescript);
@@ -547,19 +570,24 @@ impl Checker for TsxCode {
     }
 
     fn is_func_space(node: &Node) -> bool {
-        matches!(
-            node.kind_id().into(),
+        match node.kind_id().into() {
             Tsx::Program
-                | Tsx::FunctionExpression
-                | Tsx::Class
-                | Tsx::GeneratorFunction
-                | Tsx::FunctionDeclaration
-                | Tsx::MethodDefinition
-                | Tsx::GeneratorFunctionDeclaration
-                | Tsx::ClassDeclaration
-                | Tsx::InterfaceDeclaration
-                | Tsx::ArrowFunction
-        )
+            | Tsx::FunctionExpression
+            | Tsx::Class
+            | Tsx::GeneratorFunction
+            | Tsx::FunctionDeclaration
+            | Tsx::MethodDefinition
+            | Tsx::GeneratorFunctionDeclaration
+            | Tsx::ClassDeclaration
+            | Tsx::AbstractClassDeclaration
+            | Tsx::InterfaceDeclaration
+            | Tsx::ArrowFunction => true,
+            // Only the type aliases of object types declare members
+            Tsx::TypeAliasDeclaration => node
+                .child_by_field_name("value")
+                .is_some_and(|value| value.kind_id() == Tsx::ObjectType),
+            _ => false,
+        }
     }
 
     is_js_func_and_closure_checker!(TsxParser, Tsx);
@@ -626,6 +654,10 @@ impl Checker for RustCode {
         node.kind_id() == Rust::FunctionItem
     }
 
+    fn is_macro_definition(node: &Node) -> bool {
+        node.kind_id() == Rust::MacroDefinition
+    }
+
     fn is_closure(node: &Node) -> bool {
         node.kind_id() == Rust::ClosureExpression
     }
@@ -671,12 +703,15 @@ impl Checker for KotlinCode {
         false
     }
 
-    fn is_func_space(_: &Node) -> bool {
-        false
+    fn is_func_space(node: &Node) -> bool {
+        matches!(
+            node.kind_id().into(),
+            Kotlin::SourceFile | Kotlin::ClassDeclaration | Kotlin::ObjectDeclaration
+        )
     }
 
-    fn is_func(_: &Node) -> bool {
-        false
+    fn is_func(node: &Node) -> bool {
+        node.kind_id() == Kotlin::FunctionDeclaration
     }
 
     fn is_closure(_: &Node) -> bool {
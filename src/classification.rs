@@ -0,0 +1,147 @@
+use std::fmt;
+
+use serde::Serialize;
+
+/// The classes of the files encountered by an analysis.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum FileClass {
+    /// A file whose code is analyzed, even when its analysis fails
+    Source,
+    /// A file which is not a text file
+    Binary,
+    /// A text file whose language is not supported
+    Unsupported,
+    /// A file skipped because of the options of the analysis, such as
+    /// a file excluded by the globs, a hidden file, a generated file,
+    /// an empty file or a file too large to analyze
+    Ignored,
+}
+
+impl FileClass {
+    /// All the classes, in the order in which they are reported.
+    pub const ALL: [Self; 4] = [Self::Source, Self::Binary, Self::Unsupported, Self::Ignored];
+}
+
+impl fmt::Display for FileClass {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let s = match self {
+            FileClass::Source => "source",
+            FileClass::Binary => "binary",
+            FileClass::Unsupported => "unsupported",
+            FileClass::Ignored => "ignored",
+        };
+        write!(f, "{s}")
+    }
+}
+
+/// The number of files of each class encountered by an analysis,
+/// to audit which part of the scanned files has been analyzed.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize)]
+pub struct FileClassification {
+    /// The number of source files
+    pub source: usize,
+    /// The number of binary files
+    pub binary: usize,
+    /// The number of files whose language is not supported
+    pub unsupported: usize,
+    /// The number of ignored files
+    pub ignored: usize,
+}
+
+impl FileClassification {
+    /// Counts a file of a class.
+    pub fn add(&mut self, class: FileClass) {
+        *self.get_mut(class) += 1;
+    }
+
+    /// Adds the files of another classification.
+    pub fn merge(&mut self, other: &Self) {
+        for class in FileClass::ALL {
+            *self.get_mut(class) += other.get(class);
+        }
+    }
+
+    /// Returns the number of files of a class.
+    pub fn get(&self, class: FileClass) -> usize {
+        match class {
+            FileClass::Source => self.source,
+            FileClass::Binary => self.binary,
+            FileClass::Unsupported => self.unsupported,
+            FileClass::Ignored => self.ignored,
+        }
+    }
+
+    /// Returns the number of files encountered by the analysis.
+    pub fn total(&self) -> usize {
+        FileClass::ALL.iter().map(|class| self.get(*class)).sum()
+    }
+
+    fn get_mut(&mut self, class: FileClass) -> &mut usize {
+        match class {
+            FileClass::Source => &mut self.source,
+            FileClass::Binary => &mut self.binary,
+            FileClass::Unsupported => &mut self.unsupported,
+            FileClass::Ignored => &mut self.ignored,
+        }
+    }
+}
+
+impl fmt::Display for FileClassification {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let total = self.total();
+        for class in FileClass::ALL {
+            let files = self.get(class);
+            let percentage = if total == 0 {
+                0.
+            } else {
+                files as f64 * 100. / total as f64
+            };
+            writeln!(
+                f,
+                "{:<12}{files:>10}{percentage:>9.1}%",
+                format!("{class}:")
+            )?;
+        }
+        write!(f, "{:<12}{total:>10}", "total:")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use pretty_assertions::assert_eq;
+
+    use super::*;
+
+    #[test]
+    fn file_classification() {
+        let mut classification = FileClassification::default();
+        for class in [
+            FileClass::Source,
+            FileClass::Source,
+            FileClass::Source,
+            FileClass::Binary,
+            FileClass::Ignored,
+        ] {
+            classification.add(class);
+        }
+        let mut other = FileClassification::default();
+        other.add(FileClass::Unsupported);
+        classification.merge(&other);
+
+        assert_eq!(classification.get(FileClass::Source), 3);
+        assert_eq!(classification.total(), 6);
+        assert_eq!(
+            classification.to_string(),
+            "source:              3     50.0%
+binary:              1     16.7%
+unsupported:         1     16.7%
+ignored:             1     16.7%
+total:               6"
+        );
+        assert_eq!(
+            serde_json::to_string(&classification).unwrap(),
+            r#"{"source":3,"binary":1,"unsupported":1,"ignored":1}"#
+        );
+    }
+}
@@ -0,0 +1,226 @@
+use std::fmt;
+
+use num_format::{Locale, ToFormattedString};
+use serde::Serialize;
+
+/// The kind of a project estimated by the COCOMO model.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum CocomoProject {
+    /// A small team with good experience working with
+    /// less than rigid requirements
+    #[default]
+    Organic,
+    /// A medium team with mixed experience working with
+    /// a mix of rigid and less than rigid requirements
+    SemiDetached,
+    /// A team developing a project with tight constraints
+    Embedded,
+}
+
+impl CocomoProject {
+    /// The names of the kinds of project.
+    pub const NAMES: &'static [&'static str] = &["organic", "semi-detached", "embedded"];
+
+    /// Returns the kind of project with the given name.
+    ///
+    /// Returns `None` if the kind of project does not exist.
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name {
+            "organic" => Some(Self::Organic),
+            "semi-detached" => Some(Self::SemiDetached),
+            "embedded" => Some(Self::Embedded),
+            _ => None,
+        }
+    }
+
+    // The coefficients of the effort of the basic and of the
+    // intermediate model, followed by the ones of the schedule
+    fn coefficients(&self, model: CocomoModel) -> (f64, f64, f64, f64) {
+        let (basic, intermediate, b, d) = match self {
+            Self::Organic => (2.4, 3.2, 1.05, 0.38),
+            Self::SemiDetached => (3.0, 3.0, 1.12, 0.35),
+            Self::Embedded => (3.6, 2.8, 1.20, 0.32),
+        };
+        match model {
+            CocomoModel::Basic => (basic, b, 2.5, d),
+            CocomoModel::Intermediate => (intermediate, b, 2.5, d),
+        }
+    }
+}
+
+impl fmt::Display for CocomoProject {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let name = match self {
+            Self::Organic => "organic",
+            Self::SemiDetached => "semi-detached",
+            Self::Embedded => "embedded",
+        };
+        write!(f, "{name}")
+    }
+}
+
+/// The COCOMO model used to estimate a project.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum CocomoModel {
+    /// The basic model, which only depends on the lines of code
+    #[default]
+    Basic,
+    /// The intermediate model, which also depends
+    /// on an effort adjustment factor
+    Intermediate,
+}
+
+impl CocomoModel {
+    /// The names of the models.
+    pub const NAMES: &'static [&'static str] = &["basic", "intermediate"];
+
+    /// Returns the model with the given name.
+    ///
+    /// Returns `None` if the model does not exist.
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name {
+            "basic" => Some(Self::Basic),
+            "intermediate" => Some(Self::Intermediate),
+            _ => None,
+        }
+    }
+}
+
+/// The options of a COCOMO estimate.
+///
+/// The default options are the ones used by scc: an organic project
+/// estimated with the basic model, developed by people paid 56286
+/// per year with an overhead of 2.4.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct CocomoOptions {
+    /// The kind of the project
+    pub project: CocomoProject,
+    /// The model used for the estimate
+    pub model: CocomoModel,
+    /// The effort adjustment factor of the intermediate model,
+    /// that is the product of its cost drivers
+    pub eaf: f64,
+    /// The average yearly wage of a developer
+    pub average_wage: f64,
+    /// The factor multiplying the wages to get the cost
+    /// of the whole organization
+    pub overhead: f64,
+}
+
+impl Default for CocomoOptions {
+    fn default() -> Self {
+        Self {
+            project: CocomoProject::default(),
+            model: CocomoModel::default(),
+            eaf: 1.0,
+            average_wage: 56286.,
+            overhead: 2.4,
+        }
+    }
+}
+
+/// A COCOMO estimate of the development of a project.
+#[derive(Clone, Copy, Debug, PartialEq, Serialize)]
+pub struct Cocomo {
+    /// The kind of the project
+    pub project: CocomoProject,
+    /// The effort, in person-months
+    pub effort: f64,
+    /// The schedule, in months
+    pub schedule: f64,
+    /// The number of people required
+    pub people: f64,
+    /// The cost
+    pub cost: f64,
+}
+
+impl Cocomo {
+    /// Estimates the development of a project.
+    ///
+    /// * `sloc` - The lines of code of the project.
+    /// * `options` - The options of the estimate.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rust_code_analysis::{Cocomo, CocomoOptions};
+    ///
+    /// let cocomo = Cocomo::new(10_000, &CocomoOptions::default());
+    ///
+    /// // 2.4 * 10^1.05 person-months
+    /// assert!((cocomo.effort - 26.93).abs() < 0.01);
+    /// ```
+    pub fn new(sloc: usize, options: &CocomoOptions) -> Self {
+        let (a, b, c, d) = options.project.coefficients(options.model);
+        let eaf = match options.model {
+            CocomoModel::Basic => 1.0,
+            CocomoModel::Intermediate => options.eaf,
+        };
+
+        let effort = a * (sloc as f64 / 1000.).powf(b) * eaf;
+        let schedule = c * effort.powf(d);
+        let people = if schedule > 0. { effort / schedule } else { 0. };
+        let cost = effort * options.average_wage / 12. * options.overhead;
+
+        Self {
+            project: options.project,
+            effort,
+            schedule,
+            people,
+            cost,
+        }
+    }
+}
+
+/// Prints the estimate as scc does.
+impl fmt::Display for Cocomo {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let project = self.project;
+        writeln!(
+            f,
+            "Estimated Cost to Develop ({project}) ${}",
+            (self.cost.round() as u64).to_formatted_string(&Locale::en)
+        )?;
+        writeln!(
+            f,
+            "Estimated Schedule Effort ({project}) {:.2} months",
+            self.schedule
+        )?;
+        write!(
+            f,
+            "Estimated People Required ({project}) {:.2}",
+            self.people
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn cocomo_basic() {
+        insta::assert_snapshot!(
+            Cocomo::new(10_000, &CocomoOptions::default()),
+            @r###"
+            Estimated Cost to Develop (organic) $303,139
+            Estimated Schedule Effort (organic) 8.74 months
+            Estimated People Required (organic) 3.08
+            "###
+        );
+    }
+
+    #[test]
+    fn cocomo_intermediate() {
+        let options = CocomoOptions {
+            project: CocomoProject::Embedded,
+            model: CocomoModel::Intermediate,
+            eaf: 1.5,
+            ..Default::default()
+        };
+        let cocomo = Cocomo::new(10_000, &options);
+        assert!((cocomo.effort - 2.8 * 10f64.powf(1.2) * 1.5).abs() < 1e-9);
+        assert_eq!(Cocomo::new(0, &options).people, 0.);
+    }
+}
@@ -3,33 +3,115 @@ use std::path::{Path, PathBuf};
 use std::sync::Arc;
 use std::thread;
 
-use crossbeam::channel::{Receiver, Sender, unbounded};
+use crossbeam::channel::{Receiver, Sender, bounded};
 use globset::GlobSet;
 use walkdir::{DirEntry, WalkDir};
 
 type ProcFilesFunction<Config> = dyn Fn(PathBuf, &Config) -> std::io::Result<()> + Send + Sync;
 
+type ProcEntryFunction<Config> =
+    dyn Fn(PathBuf, Vec<u8>, &Config) -> std::io::Result<()> + Send + Sync;
+
 type ProcDirPathsFunction<Config> =
     dyn Fn(&mut HashMap<String, Vec<PathBuf>>, &Path, &Config) + Send + Sync;
 
 type ProcPathFunction<Config> = dyn Fn(&Path, &Config) + Send + Sync;
 
+type ProcErrorFunction<Config> = dyn Fn(&Path, std::io::Error, &Config) + Send + Sync;
+
 // Null functions removed at compile time
 fn null_proc_dir_paths<Config>(_: &mut HashMap<String, Vec<PathBuf>>, _: &Path, _: &Config) {}
 fn null_proc_path<Config>(_: &Path, _: &Config) {}
+fn null_proc_entry<Config>(_: PathBuf, _: Vec<u8>, _: &Config) -> std::io::Result<()> {
+    Ok(())
+}
+
+fn warn_archive_error<Config>(path: &Path, e: std::io::Error, _: &Config) {
+    eprintln!("Warning: Cannot read archive {path:?}: {e}");
+}
 
 #[derive(Debug)]
 struct JobItem<Config> {
     path: PathBuf,
+    // The content of a file extracted from an archive
+    data: Option<Vec<u8>>,
     cfg: Arc<Config>,
 }
 
 type JobReceiver<Config> = Receiver<Option<JobItem<Config>>>;
 type JobSender<Config> = Sender<Option<JobItem<Config>>>;
 
-fn consumer<Config, ProcFiles>(receiver: JobReceiver<Config>, func: Arc<ProcFiles>)
-where
+/// The order in which the files are processed.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum Schedule {
+    /// The largest files are processed first, once all the files are found,
+    /// so that a large file does not keep a thread busy while
+    /// the other ones are idle at the end of the run.
+    ///
+    /// The files extracted from the archives are processed as soon as
+    /// they are decompressed, to keep only a few of them in memory.
+    #[default]
+    LargestFirst,
+    /// The files are processed in the order in which they are found,
+    /// while the directories are still explored.
+    Discovery,
+}
+
+// Sends the jobs to the consumers, holding the ones of the files on disk
+// until all of them are found when the largest files are processed first
+struct JobQueue<'a, Config> {
+    sender: &'a JobSender<Config>,
+    // The sizes and the jobs of the files found so far
+    pending: Option<Vec<(u64, JobItem<Config>)>>,
+}
+
+impl<'a, Config> JobQueue<'a, Config> {
+    fn new(sender: &'a JobSender<Config>, schedule: Schedule) -> Self {
+        Self {
+            sender,
+            pending: (schedule == Schedule::LargestFirst).then(Vec::new),
+        }
+    }
+
+    fn send(&mut self, job: JobItem<Config>) -> Result<(), ConcurrentErrors> {
+        match &mut self.pending {
+            // The content of an archive entry is not held, otherwise
+            // the whole archive would be decompressed in memory
+            Some(pending) if job.data.is_none() => {
+                let size = std::fs::metadata(&job.path).map_or(0, |metadata| metadata.len());
+                pending.push((size, job));
+                Ok(())
+            }
+            _ => self
+                .sender
+                .send(Some(job))
+                .map_err(|e| ConcurrentErrors::Sender(e.to_string())),
+        }
+    }
+
+    fn flush(&mut self) -> Result<(), ConcurrentErrors> {
+        let Some(mut pending) = self.pending.take() else {
+            return Ok(());
+        };
+        // The sort is stable, so the files of the same size
+        // are processed in the order in which they are found
+        pending.sort_by(|(a, _), (b, _)| b.cmp(a));
+        for (_, job) in pending {
+            self.sender
+                .send(Some(job))
+                .map_err(|e| ConcurrentErrors::Sender(e.to_string()))?;
+        }
+        Ok(())
+    }
+}
+
+fn consumer<Config, ProcFiles, ProcEntry>(
+    receiver: JobReceiver<Config>,
+    func: Arc<ProcFiles>,
+    entry_func: Arc<ProcEntry>,
+) where
     ProcFiles: Fn(PathBuf, &Config) -> std::io::Result<()> + Send + Sync,
+    ProcEntry: Fn(PathBuf, Vec<u8>, &Config) -> std::io::Result<()> + Send + Sync,
 {
     while let Ok(job) = receiver.recv() {
         if job.is_none() {
@@ -38,8 +120,14 @@ where
         // Cannot panic because of the check immediately above.
         let job = job.unwrap();
         let path = job.path.clone();
+        let _span = tracing::info_span!("file", path = %path.display()).entered();
 
-        if let Err(err) = func(job.path, &job.cfg) {
+        let res = if let Some(data) = job.data {
+            entry_func(job.path, data, &job.cfg)
+        } else {
+            func(job.path, &job.cfg)
+        };
+        if let Err(err) = res {
             eprintln!("{err:?} for file {path:?}");
         }
     }
@@ -48,14 +136,111 @@ where
 fn send_file<T>(
     path: PathBuf,
     cfg: &Arc<T>,
-    sender: &JobSender<T>,
+    queue: &mut JobQueue<T>,
+) -> Result<(), ConcurrentErrors> {
+    send_job(path, None, cfg, queue)
+}
+
+fn send_job<T>(
+    path: PathBuf,
+    data: Option<Vec<u8>>,
+    cfg: &Arc<T>,
+    queue: &mut JobQueue<T>,
+) -> Result<(), ConcurrentErrors> {
+    queue.send(JobItem {
+        path,
+        data,
+        cfg: Arc::clone(cfg),
+    })
+}
+
+#[cfg(feature = "archive")]
+fn is_archive(path: &Path) -> bool {
+    crate::archive::ArchiveKind::from_path(path).is_some()
+}
+
+#[cfg(not(feature = "archive"))]
+fn is_archive(_: &Path) -> bool {
+    false
+}
+
+#[cfg(not(feature = "archive"))]
+fn send_archive<T>(
+    _: &Path,
+    _: &GlobSet,
+    _: &GlobSet,
+    _: &Arc<T>,
+    _: &ProcErrorFunction<T>,
+    _: &mut JobQueue<T>,
+) -> Result<(), ConcurrentErrors> {
+    Ok(())
+}
+
+#[cfg(feature = "archive")]
+fn send_archive<T>(
+    path: &Path,
+    include: &GlobSet,
+    exclude: &GlobSet,
+    cfg: &Arc<T>,
+    proc_error: &ProcErrorFunction<T>,
+    queue: &mut JobQueue<T>,
 ) -> Result<(), ConcurrentErrors> {
-    sender
-        .send(Some(JobItem {
-            path,
-            cfg: Arc::clone(cfg),
-        }))
-        .map_err(|e| ConcurrentErrors::Sender(e.to_string()))
+    let mut send_error = None;
+    let res = crate::archive::visit_archive(
+        path,
+        |path| {
+            (include.is_empty() || include.is_match(path))
+                && (exclude.is_empty() || !exclude.is_match(path))
+        },
+        |path, data| {
+            send_job(path, Some(data), cfg, queue).map_err(|e| {
+                send_error = Some(e);
+                std::io::Error::other("Cannot send an archive entry")
+            })
+        },
+    );
+    if let Some(e) = send_error {
+        return Err(e);
+    }
+    if let Err(e) = res {
+        proc_error(path, e, cfg);
+    }
+
+    Ok(())
+}
+
+/// The handling of the symbolic links found in the directories.
+///
+/// The paths to explore are always followed, even when they are
+/// symbolic links.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum SymlinkPolicy {
+    /// The symbolic links to files and directories are followed.
+    /// A link to one of its own ancestors, which would make the
+    /// exploration endless, and a broken link are skipped with a warning.
+    #[default]
+    Follow,
+    /// The symbolic links are skipped.
+    Skip,
+    /// A symbolic link stops the exploration with an error.
+    Error,
+}
+
+impl SymlinkPolicy {
+    /// The names of the available policies.
+    pub const NAMES: &'static [&'static str] = &["follow", "skip", "error"];
+
+    /// Returns the policy with the given name.
+    ///
+    /// Returns `None` if the policy does not exist.
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name {
+            "follow" => Some(Self::Follow),
+            "skip" => Some(Self::Skip),
+            "error" => Some(Self::Error),
+            _ => None,
+        }
+    }
 }
 
 fn is_hidden(entry: &DirEntry) -> bool {
@@ -66,21 +251,27 @@ fn is_hidden(entry: &DirEntry) -> bool {
         .unwrap_or(false)
 }
 
-fn explore<Config, ProcDirPaths, ProcPath>(
+fn explore<Config, ProcDirPaths, ProcPath, ProcIgnored>(
     files_data: FilesData,
     cfg: &Arc<Config>,
     proc_dir_paths: ProcDirPaths,
     proc_path: ProcPath,
-    sender: &JobSender<Config>,
+    proc_ignored: ProcIgnored,
+    // The function processing the archives which cannot be read,
+    // when the archives are decompressed
+    proc_archive_error: Option<&ProcErrorFunction<Config>>,
+    queue: &mut JobQueue<Config>,
 ) -> Result<HashMap<String, Vec<PathBuf>>, ConcurrentErrors>
 where
     ProcDirPaths: Fn(&mut HashMap<String, Vec<PathBuf>>, &Path, &Config) + Send + Sync,
     ProcPath: Fn(&Path, &Config) + Send + Sync,
+    ProcIgnored: Fn(&Path, &Config) + Send + Sync,
 {
     let FilesData {
         mut paths,
         ref include,
         ref exclude,
+        symlinks,
     } = files_data;
 
     let mut all_files: HashMap<String, Vec<PathBuf>> = HashMap::new();
@@ -92,28 +283,74 @@ where
         }
         if path.is_dir() {
             for entry in WalkDir::new(path)
+                .follow_links(symlinks == SymlinkPolicy::Follow)
                 .into_iter()
-                .filter_entry(|e| !is_hidden(e))
+                .filter_entry(|e| {
+                    let hidden = is_hidden(e);
+                    // The files of the hidden directories are not explored
+                    if hidden && e.file_type().is_file() {
+                        proc_ignored(e.path(), cfg);
+                    }
+                    !hidden
+                })
             {
                 let entry = match entry {
                     Ok(entry) => entry,
+                    Err(e) if e.loop_ancestor().is_some() => {
+                        eprintln!("Warning: Skipping symlink cycle: {e}");
+                        continue;
+                    }
+                    Err(e)
+                        if e.io_error().map(|e| e.kind()) == Some(std::io::ErrorKind::NotFound) =>
+                    {
+                        eprintln!("Warning: Skipping broken symlink: {e}");
+                        continue;
+                    }
                     Err(e) => return Err(ConcurrentErrors::Sender(e.to_string())),
                 };
+                if entry.path_is_symlink() && entry.depth() > 0 {
+                    match symlinks {
+                        SymlinkPolicy::Skip => {
+                            if entry.path().is_file() {
+                                proc_ignored(entry.path(), cfg);
+                            }
+                            continue;
+                        }
+                        SymlinkPolicy::Error => {
+                            return Err(ConcurrentErrors::Producer(format!(
+                                "Symlink found: {:?}",
+                                entry.path()
+                            )));
+                        }
+                        SymlinkPolicy::Follow => {}
+                    }
+                }
                 let path = entry.path().to_path_buf();
+                if !path.is_file() {
+                    continue;
+                }
                 if (include.is_empty() || include.is_match(&path))
                     && (exclude.is_empty() || !exclude.is_match(&path))
-                    && path.is_file()
                 {
                     proc_dir_paths(&mut all_files, &path, cfg);
-                    send_file(path, cfg, sender)?;
+                    send_file(path, cfg, queue)?;
+                } else {
+                    proc_ignored(&path, cfg);
                 }
             }
-        } else if (include.is_empty() || include.is_match(&path))
-            && (exclude.is_empty() || !exclude.is_match(&path))
-            && path.is_file()
+        } else if let Some(proc_archive_error) = proc_archive_error
+            && is_archive(&path)
         {
-            proc_path(&path, cfg);
-            send_file(path, cfg, sender)?;
+            send_archive(&path, include, exclude, cfg, proc_archive_error, queue)?;
+        } else if path.is_file() {
+            if (include.is_empty() || include.is_match(&path))
+                && (exclude.is_empty() || !exclude.is_match(&path))
+            {
+                proc_path(&path, cfg);
+                send_file(path, cfg, queue)?;
+            } else {
+                proc_ignored(&path, cfg);
+            }
         }
     }
 
@@ -150,6 +387,8 @@ pub struct FilesData {
     pub exclude: GlobSet,
     /// List of file paths.
     pub paths: Vec<PathBuf>,
+    /// The handling of the symbolic links found in the directories.
+    pub symlinks: SymlinkPolicy,
 }
 
 /// A runner to process files concurrently.
@@ -157,7 +396,11 @@ pub struct ConcurrentRunner<Config> {
     proc_files: Box<ProcFilesFunction<Config>>,
     proc_dir_paths: Box<ProcDirPathsFunction<Config>>,
     proc_path: Box<ProcPathFunction<Config>>,
+    proc_ignored: Box<ProcPathFunction<Config>>,
+    proc_entry: Option<Box<ProcEntryFunction<Config>>>,
+    proc_archive_error: Box<ProcErrorFunction<Config>>,
     num_jobs: usize,
+    schedule: Schedule,
 }
 
 impl<Config: 'static + Send + Sync> ConcurrentRunner<Config> {
@@ -175,7 +418,11 @@ impl<Config: 'static + Send + Sync> ConcurrentRunner<Config> {
             proc_files: Box::new(proc_files),
             proc_dir_paths: Box::new(null_proc_dir_paths),
             proc_path: Box::new(null_proc_path),
+            proc_ignored: Box::new(null_proc_path),
+            proc_entry: None,
+            proc_archive_error: Box::new(warn_archive_error),
             num_jobs,
+            schedule: Schedule::default(),
         }
     }
 
@@ -199,6 +446,54 @@ impl<Config: 'static + Send + Sync> ConcurrentRunner<Config> {
         self
     }
 
+    /// Sets the function to process a file which is not processed,
+    /// because it is hidden, excluded by the globs, or a skipped
+    /// symbolic link.
+    pub fn set_proc_ignored_path<ProcIgnored>(mut self, proc_ignored: ProcIgnored) -> Self
+    where
+        ProcIgnored: 'static + Fn(&Path, &Config) + Send + Sync,
+    {
+        self.proc_ignored = Box::new(proc_ignored);
+        self
+    }
+
+    /// Sets the function to process a file extracted from an archive.
+    ///
+    /// When this function is set, the archives passed as paths are
+    /// decompressed in memory and each of their files, which satisfies
+    /// the include and exclude globs, is processed with its content.
+    /// Archive support requires the `archive` feature.
+    pub fn set_proc_archive_entry<ProcEntry>(mut self, proc_entry: ProcEntry) -> Self
+    where
+        ProcEntry: 'static + Fn(PathBuf, Vec<u8>, &Config) -> std::io::Result<()> + Send + Sync,
+    {
+        self.proc_entry = Some(Box::new(proc_entry));
+        self
+    }
+
+    /// Sets the function to process an archive which cannot be read,
+    /// such as a corrupt one.
+    ///
+    /// By default, a warning is printed and the other paths are processed.
+    pub fn set_proc_archive_error<ProcArchiveError>(
+        mut self,
+        proc_archive_error: ProcArchiveError,
+    ) -> Self
+    where
+        ProcArchiveError: 'static + Fn(&Path, std::io::Error, &Config) + Send + Sync,
+    {
+        self.proc_archive_error = Box::new(proc_archive_error);
+        self
+    }
+
+    /// Sets the order in which the files are processed.
+    ///
+    /// By default, the largest files are processed first.
+    pub fn set_schedule(mut self, schedule: Schedule) -> Self {
+        self.schedule = schedule;
+        self
+    }
+
     /// Runs the producer-consumer approach to process the files
     /// contained in a directory and in its own subdirectories.
     ///
@@ -212,21 +507,35 @@ impl<Config: 'static + Send + Sync> ConcurrentRunner<Config> {
     ) -> Result<HashMap<String, Vec<PathBuf>>, ConcurrentErrors> {
         let cfg = Arc::new(config);
 
-        let (sender, receiver) = unbounded();
+        // The producer waits for the consumers when they are busy,
+        // so that the decompressed archive entries do not pile up
+        let (sender, receiver) = bounded(2 * self.num_jobs);
+
+        let with_archives = self.proc_entry.is_some();
+        let proc_entry = Arc::new(
+            self.proc_entry
+                .unwrap_or_else(|| Box::new(null_proc_entry::<Config>)),
+        );
 
         let producer = {
             let sender = sender.clone();
+            let schedule = self.schedule;
 
             match thread::Builder::new()
                 .name(String::from("Producer"))
                 .spawn(move || {
-                    explore(
+                    let mut queue = JobQueue::new(&sender, schedule);
+                    let all_files = explore(
                         files_data,
                         &cfg,
                         self.proc_dir_paths,
                         self.proc_path,
-                        &sender,
-                    )
+                        self.proc_ignored,
+                        with_archives.then_some(&*self.proc_archive_error),
+                        &mut queue,
+                    )?;
+                    queue.flush()?;
+                    Ok(all_files)
                 }) {
                 Ok(producer) => producer,
                 Err(e) => return Err(ConcurrentErrors::Thread(e.to_string())),
@@ -238,11 +547,12 @@ impl<Config: 'static + Send + Sync> ConcurrentRunner<Config> {
         for i in 0..self.num_jobs {
             let receiver = receiver.clone();
             let proc_files = proc_files.clone();
+            let proc_entry = proc_entry.clone();
 
             let t = match thread::Builder::new()
                 .name(format!("Consumer {i}"))
                 .spawn(move || {
-                    consumer(receiver, proc_files);
+                    consumer(receiver, proc_files, proc_entry);
                 }) {
                 Ok(receiver) => receiver,
                 Err(e) => return Err(ConcurrentErrors::Thread(e.to_string())),
@@ -278,3 +588,121 @@ impl<Config: 'static + Send + Sync> ConcurrentRunner<Config> {
         all_files
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Mutex;
+
+    use pretty_assertions::assert_eq;
+
+    use super::*;
+
+    fn processed_files(paths: &[PathBuf], schedule: Schedule) -> Vec<String> {
+        processed_files_with(paths, schedule, SymlinkPolicy::default()).unwrap()
+    }
+
+    fn processed_files_with(
+        paths: &[PathBuf],
+        schedule: Schedule,
+        symlinks: SymlinkPolicy,
+    ) -> Result<Vec<String>, ConcurrentErrors> {
+        let files_data = FilesData {
+            include: GlobSet::empty(),
+            exclude: GlobSet::empty(),
+            paths: paths.to_vec(),
+            symlinks,
+        };
+        let processed = Arc::new(Mutex::new(Vec::new()));
+        // A single thread processes the files in the order of the schedule
+        ConcurrentRunner::new(1, |path: PathBuf, cfg: &Arc<Mutex<Vec<String>>>| {
+            let name = path.file_name().unwrap().to_string_lossy().into_owned();
+            cfg.lock().unwrap().push(name);
+            Ok(())
+        })
+        .set_schedule(schedule)
+        .run(processed.clone(), files_data)?;
+        let processed = processed.lock().unwrap().clone();
+        Ok(processed)
+    }
+
+    #[test]
+    fn schedule_files() {
+        let dir = std::env::temp_dir().join("test_schedule_files");
+        std::fs::create_dir_all(&dir).unwrap();
+        let paths: Vec<_> = [("small.py", 1), ("large.py", 100), ("medium.py", 10)]
+            .iter()
+            .map(|(name, lines)| {
+                let path = dir.join(name);
+                std::fs::write(&path, "a = 1\n".repeat(*lines)).unwrap();
+                path
+            })
+            .collect();
+
+        assert_eq!(
+            processed_files(&paths, Schedule::LargestFirst),
+            ["large.py", "medium.py", "small.py"]
+        );
+        assert_eq!(
+            processed_files(&paths, Schedule::Discovery),
+            ["small.py", "large.py", "medium.py"]
+        );
+    }
+
+    #[test]
+    fn schedule_archive_entries() {
+        let dir = std::env::temp_dir().join("test_schedule_archive_entries");
+        std::fs::create_dir_all(&dir).unwrap();
+        let file = dir.join("a.py");
+        std::fs::write(&file, "a = 1\n").unwrap();
+
+        let (sender, receiver) = bounded(4);
+        let cfg = Arc::new(());
+        let mut queue = JobQueue::new(&sender, Schedule::LargestFirst);
+        send_file(file, &cfg, &mut queue).unwrap();
+        send_job(
+            PathBuf::from("b.py"),
+            Some(b"b = 1\n".to_vec()),
+            &cfg,
+            &mut queue,
+        )
+        .unwrap();
+
+        // The archive entry is sent at once, the file once all are found
+        let job = receiver.try_recv().unwrap().unwrap();
+        assert_eq!(job.path, PathBuf::from("b.py"));
+        assert!(receiver.try_recv().is_err());
+        queue.flush().unwrap();
+        let job = receiver.try_recv().unwrap().unwrap();
+        assert_eq!(job.path, dir.join("a.py"));
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn symlink_policies() {
+        use std::os::unix::fs::symlink;
+
+        let dir = std::env::temp_dir().join("test_symlink_policies");
+        let _ = std::fs::remove_dir_all(&dir);
+        std::fs::create_dir_all(dir.join("src/sub")).unwrap();
+        std::fs::write(dir.join("src/a.py"), "a = 1\n").unwrap();
+        std::fs::write(dir.join("b.py"), "b = 1\n").unwrap();
+        symlink(dir.join("b.py"), dir.join("src/link.py")).unwrap();
+        // A link to an ancestor, which makes the exploration endless
+        symlink(dir.join("src"), dir.join("src/sub/cycle")).unwrap();
+        symlink(dir.join("missing.py"), dir.join("src/broken.py")).unwrap();
+        let paths = [dir.join("src")];
+        let files = |symlinks| {
+            processed_files_with(&paths, Schedule::LargestFirst, symlinks).map(|mut files| {
+                files.sort();
+                files
+            })
+        };
+
+        assert_eq!(files(SymlinkPolicy::Follow).unwrap(), ["a.py", "link.py"]);
+        assert_eq!(files(SymlinkPolicy::Skip).unwrap(), ["a.py"]);
+        assert!(matches!(
+            files(SymlinkPolicy::Error),
+            Err(ConcurrentErrors::Producer(_))
+        ));
+    }
+}
@@ -1,9 +1,11 @@
 extern crate num_format;
 
 use num_format::{Locale, ToFormattedString};
+use serde::Serialize;
 use std::fmt;
 use std::sync::{Arc, Mutex};
 
+use crate::node::Node;
 use crate::traits::*;
 
 /// Counts the types of nodes specified in the input slice
@@ -36,6 +38,92 @@ pub fn count<T: ParserTrait>(parser: &T, filters: &[String]) -> (usize, usize) {
     (good, total)
 }
 
+/// The span of a node of a code.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize)]
+pub struct NodeSpan {
+    /// The kind of the node in the grammar of the language
+    pub kind: &'static str,
+    /// The line where the node starts, starting from 1
+    pub start_line: usize,
+    /// The column where the node starts, in bytes, starting from 1
+    pub start_column: usize,
+    /// The line where the node ends, starting from 1
+    pub end_line: usize,
+    /// The column following the end of the node, in bytes, starting from 1
+    pub end_column: usize,
+    /// The offset of the first byte of the node in the code
+    pub start_byte: usize,
+    /// The offset following the last byte of the node in the code
+    pub end_byte: usize,
+}
+
+impl NodeSpan {
+    pub(crate) fn new(node: &Node) -> Self {
+        let (start_row, start_column) = node.start_position();
+        let (end_row, end_column) = node.end_position();
+        Self {
+            kind: node.kind(),
+            start_line: start_row + 1,
+            start_column: start_column + 1,
+            end_line: end_row + 1,
+            end_column: end_column + 1,
+            start_byte: node.start_byte(),
+            end_byte: node.end_byte(),
+        }
+    }
+}
+
+/// The occurrences of a type of nodes in a code.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize)]
+pub struct NodeCount {
+    /// The type of nodes, as given to [`count_nodes`]
+    ///
+    /// [`count_nodes`]: fn.count_nodes.html
+    pub kind: String,
+    /// The number of nodes of the type
+    pub count: usize,
+    /// The spans of the nodes of the type, in the order of the code
+    pub spans: Vec<NodeSpan>,
+}
+
+/// Counts the occurrences of each type of nodes specified in the input
+/// slice, returning one count per type in the same order.
+///
+/// The types are matched as in [`count`], so a type can be a node kind,
+/// a part of it, a kind identifier or one of the `call`, `comment`,
+/// `error`, `string` and `function` categories. Nested nodes are all
+/// counted, as the `unwrap` calls chained on another call.
+///
+/// [`count`]: fn.count.html
+pub fn count_nodes<T: ParserTrait>(parser: &T, kinds: &[String]) -> Vec<NodeCount> {
+    let filters: Vec<_> = kinds
+        .iter()
+        .map(|kind| parser.get_filters(std::slice::from_ref(kind)))
+        .collect();
+    let mut counts: Vec<_> = kinds
+        .iter()
+        .map(|kind| NodeCount {
+            kind: kind.clone(),
+            count: 0,
+            spans: Vec::new(),
+        })
+        .collect();
+    let mut stack = vec![parser.get_root()];
+
+    while let Some(node) = stack.pop() {
+        for (filter, count) in filters.iter().zip(counts.iter_mut()) {
+            if filter.any(&node) {
+                count.count += 1;
+                count.spans.push(NodeSpan::new(&node));
+            }
+        }
+        let start = stack.len();
+        stack.extend(node.children());
+        stack[start..].reverse();
+    }
+    counts
+}
+
 /// Configuration options for counting different
 /// types of nodes in a code.
 #[derive(Debug)]
@@ -87,3 +175,86 @@ impl fmt::Display for Count {
         )
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use std::path::Path;
+
+    use pretty_assertions::assert_eq;
+
+    use crate::{LANG, get_node_counts};
+
+    use super::*;
+
+    fn spans(source: &str, path: &str, lang: LANG, kinds: &[&str]) -> Vec<(String, Vec<usize>)> {
+        let kinds: Vec<_> = kinds.iter().map(|kind| kind.to_string()).collect();
+        get_node_counts(
+            &lang,
+            source.as_bytes().to_vec(),
+            Path::new(path),
+            None,
+            &kinds,
+        )
+        .into_iter()
+        .map(|count| {
+            assert_eq!(count.count, count.spans.len());
+            let lines = count.spans.iter().map(|span| span.start_line).collect();
+            (count.kind, lines)
+        })
+        .collect()
+    }
+
+    #[test]
+    fn rust_count_nodes() {
+        let source = "fn foo() {
+    let a = bar().unwrap();
+    // baz
+    let b = a.unwrap().unwrap();
+}
+";
+        assert_eq!(
+            spans(
+                source,
+                "foo.rs",
+                LANG::Rust,
+                &["call_expression", "comment", "match"]
+            ),
+            [
+                ("call_expression".to_owned(), vec![2, 2, 4, 4]),
+                ("comment".to_owned(), vec![3]),
+                ("match".to_owned(), vec![]),
+            ]
+        );
+    }
+
+    #[test]
+    fn c_count_nodes() {
+        let source = "void foo() {
+start:
+    goto end;
+end:
+    goto start;
+}
+";
+        let counts = get_node_counts(
+            &LANG::Cpp,
+            source.as_bytes().to_vec(),
+            Path::new("foo.c"),
+            None,
+            &["goto_statement".to_owned()],
+        );
+        assert_eq!(counts[0].count, 2);
+        assert_eq!(
+            counts[0].spans[1],
+            NodeSpan {
+                kind: "goto_statement",
+                start_line: 5,
+                start_column: 5,
+                end_line: 5,
+                end_column: 16,
+                start_byte: 43,
+                end_byte: 54,
+            }
+        );
+    }
+}
@@ -0,0 +1,412 @@
+use std::collections::{BTreeMap, HashMap};
+use std::fmt;
+use std::path::{Path, PathBuf};
+
+use regex::Regex;
+use serde::Serialize;
+
+use crate::spaces::{FuncSpace, SpaceKind};
+
+/// The CRAP score above which a function is reported by default.
+pub const DEFAULT_CRAP_THRESHOLD: f64 = 30.;
+
+/// The lines of the files executed by the tests, read from a coverage report.
+///
+/// The paths of the report are matched with the paths of the analyzed files
+/// by their last components, so that a report written with absolute paths,
+/// or with paths relative to another directory, applies to relative paths.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct Coverage {
+    // The number of executions of the instrumented lines of each file
+    files: HashMap<PathBuf, BTreeMap<usize, u64>>,
+}
+
+// Removes the `./` prefix of a path, which prevents matching its components
+fn report_path(path: &str) -> PathBuf {
+    let path = Path::new(path.trim());
+    path.strip_prefix(".").unwrap_or(path).to_path_buf()
+}
+
+impl Coverage {
+    /// Reads a coverage report in the `lcov` or in the Cobertura XML format,
+    /// detecting its format from its content.
+    pub fn parse(report: &str) -> Self {
+        if report.trim_start().starts_with('<') {
+            Self::from_cobertura(report)
+        } else {
+            Self::from_lcov(report)
+        }
+    }
+
+    /// Reads a coverage report in the `lcov` format, whose `SF` records
+    /// start the files and whose `DA` records count the executions of
+    /// their lines.
+    pub fn from_lcov(report: &str) -> Self {
+        let mut coverage = Self::default();
+        let mut lines = None;
+        for record in report.lines() {
+            if let Some(path) = record.strip_prefix("SF:") {
+                lines = Some(coverage.files.entry(report_path(path)).or_default());
+            } else if let Some(data) = record.strip_prefix("DA:")
+                && let Some(lines) = lines.as_mut()
+            {
+                let mut fields = data.split(',');
+                if let (Some(Ok(line)), Some(Ok(hits))) = (
+                    fields.next().map(|line| line.trim().parse()),
+                    fields.next().map(|hits| hits.trim().parse::<u64>()),
+                ) {
+                    *lines.entry(line).or_default() += hits;
+                }
+            } else if record.trim() == "end_of_record" {
+                lines = None;
+            }
+        }
+        coverage
+    }
+
+    /// Reads a coverage report in the Cobertura XML format, whose `class`
+    /// elements name the files and whose `line` elements count the
+    /// executions of their lines.
+    pub fn from_cobertura(report: &str) -> Self {
+        let tags = Regex::new(r"<(class|line)\b([^>]*)>").unwrap();
+        let attributes = Regex::new(r#"([\w-]+)\s*=\s*"([^"]*)""#).unwrap();
+
+        let mut coverage = Self::default();
+        let mut path = None;
+        for tag in tags.captures_iter(report) {
+            let attribute = |name: &str| {
+                attributes
+                    .captures_iter(&tag[2])
+                    .find(|attribute| &attribute[1] == name)
+                    .map(|attribute| attribute[2].to_owned())
+            };
+            if &tag[1] == "class" {
+                path = attribute("filename").map(|name| report_path(&name));
+            } else if let Some(path) = &path
+                && let (Some(Ok(line)), Some(Ok(hits))) = (
+                    attribute("number").map(|line| line.parse()),
+                    attribute("hits").map(|hits| hits.parse::<u64>()),
+                )
+            {
+                *coverage
+                    .files
+                    .entry(path.clone())
+                    .or_default()
+                    .entry(line)
+                    .or_default() += hits;
+            }
+        }
+        coverage
+    }
+
+    /// Checks if the report does not contain any file.
+    pub fn is_empty(&self) -> bool {
+        self.files.is_empty()
+    }
+
+    // Returns the lines of the file of the report matching a path
+    fn file_lines(&self, path: &Path) -> Option<&BTreeMap<usize, u64>> {
+        let path = path.strip_prefix(".").unwrap_or(path);
+        self.files.get(path).or_else(|| {
+            self.files
+                .iter()
+                .filter(|(report, _)| path.ends_with(report) || report.ends_with(path))
+                // The longest path is the most specific one
+                .max_by_key(|(report, _)| (report.components().count(), *report))
+                .map(|(_, lines)| lines)
+        })
+    }
+
+    /// Returns the ratio between the executed lines and the instrumented
+    /// lines of a file from `start_line` to `end_line`, both included.
+    ///
+    /// Returns `None` if the file is not in the report or if none of
+    /// these lines is instrumented.
+    pub fn line_coverage(&self, path: &Path, start_line: usize, end_line: usize) -> Option<f64> {
+        let (executed, instrumented) = self.file_lines(path)?.range(start_line..=end_line).fold(
+            (0, 0),
+            |(executed, instrumented), (_, hits)| {
+                (executed + usize::from(*hits > 0), instrumented + 1)
+            },
+        );
+        (instrumented > 0).then(|| executed as f64 / instrumented as f64)
+    }
+}
+
+/// Computes the CRAP score, the Change Risk Anti-Patterns score,
+/// of a function from its cyclomatic complexity and from the ratio
+/// of its lines covered by the tests, between 0 and 1.
+///
+/// The score is `complexity² × (1 - coverage)³ + complexity`, so a fully
+/// tested function has a score equal to its complexity while the score of
+/// an untested one grows with the square of its complexity.
+pub fn crap_score(complexity: f64, coverage: f64) -> f64 {
+    complexity.powi(2) * (1. - coverage).powi(3) + complexity
+}
+
+/// A function whose CRAP score is above the threshold of a report.
+#[derive(Clone, Debug, PartialEq, Serialize)]
+pub struct CrapFunction {
+    /// The path of the file containing the function
+    pub path: String,
+    /// The name of the function
+    pub name: String,
+    /// The first line of the function
+    pub start_line: usize,
+    /// The last line of the function
+    pub end_line: usize,
+    /// The cyclomatic complexity of the function
+    pub cyclomatic: f64,
+    /// The percentage of the instrumented lines of the function
+    /// executed by the tests
+    pub coverage: f64,
+    /// The CRAP score of the function
+    pub crap: f64,
+}
+
+/// The functions of all the analyzed files whose CRAP score is above
+/// a threshold, which are the complex functions not tested enough.
+///
+/// The functions whose lines are not in the coverage report are
+/// considered as untested.
+#[derive(Clone, Debug, PartialEq, Serialize)]
+pub struct CrapReport {
+    /// The CRAP score above which a function is reported
+    pub threshold: f64,
+    #[serde(skip)]
+    coverage: Coverage,
+    functions: Vec<CrapFunction>,
+}
+
+impl CrapReport {
+    /// Creates an empty report of the functions whose CRAP score,
+    /// computed with a coverage report, is above a threshold.
+    pub fn new(coverage: Coverage, threshold: f64) -> Self {
+        Self {
+            threshold,
+            coverage,
+            functions: Vec::new(),
+        }
+    }
+
+    /// Adds the functions of a file to the report.
+    ///
+    /// * `space` - The root function space of the file.
+    pub fn add(&mut self, space: &FuncSpace) {
+        let path = space.name.clone().unwrap_or_default();
+        self.add_functions(&path, space);
+        // The ties are broken by the position of the functions, so the report
+        // does not depend on the order in which the files are added
+        self.functions.sort_by(|a, b| {
+            b.crap
+                .total_cmp(&a.crap)
+                .then_with(|| a.path.cmp(&b.path))
+                .then_with(|| a.start_line.cmp(&b.start_line))
+        });
+    }
+
+    fn add_functions(&mut self, path: &str, space: &FuncSpace) {
+        for subspace in &space.spaces {
+            if subspace.kind == SpaceKind::Function {
+                let cyclomatic = subspace.metrics.cyclomatic.cyclomatic();
+                let coverage = self
+                    .coverage
+                    .line_coverage(Path::new(path), subspace.start_line, subspace.end_line)
+                    .unwrap_or_default();
+                let crap = crap_score(cyclomatic, coverage);
+                if crap > self.threshold {
+                    self.functions.push(CrapFunction {
+                        path: path.to_owned(),
+                        name: subspace
+                            .qualified_name
+                            .clone()
+                            .or_else(|| subspace.name.clone())
+                            .unwrap_or_default(),
+                        start_line: subspace.start_line,
+                        end_line: subspace.end_line,
+                        cyclomatic,
+                        coverage: coverage * 100.,
+                        crap,
+                    });
+                }
+            }
+            self.add_functions(path, subspace);
+        }
+    }
+
+    /// Returns the reported functions, from the highest CRAP score.
+    pub fn functions(&self) -> &[CrapFunction] {
+        &self.functions
+    }
+}
+
+/// Prints the reported functions as a table aligned on the widest values.
+impl fmt::Display for CrapReport {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let header = ["Function", "Location", "Cyclomatic", "Coverage", "CRAP"].map(String::from);
+        let rows: Vec<_> = self
+            .functions
+            .iter()
+            .map(|function| {
+                [
+                    function.name.clone(),
+                    format!(
+                        "{}:{}-{}",
+                        function.path, function.start_line, function.end_line
+                    ),
+                    format!("{}", function.cyclomatic),
+                    format!("{:.1}%", function.coverage),
+                    format!("{:.2}", function.crap),
+                ]
+            })
+            .collect();
+        let mut widths = header.clone().map(|cell| cell.chars().count());
+        for row in &rows {
+            for (width, cell) in widths.iter_mut().zip(row) {
+                *width = (*width).max(cell.chars().count());
+            }
+        }
+
+        let write_row =
+            |f: &mut fmt::Formatter, [name, location, cyclomatic, coverage, crap]: &[String; 5]| {
+                let [
+                    name_width,
+                    location_width,
+                    cyclomatic_width,
+                    coverage_width,
+                    crap_width,
+                ] = widths;
+                write!(
+                    f,
+                    "{name:<name_width$}  {location:<location_width$}  {cyclomatic:>cyclomatic_width$}  {coverage:>coverage_width$}  {crap:>crap_width$}"
+                )
+            };
+        write_row(f, &header)?;
+        write!(f, "\n{}", "-".repeat(widths.iter().sum::<usize>() + 8))?;
+        for row in &rows {
+            writeln!(f)?;
+            write_row(f, row)?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use pretty_assertions::assert_eq;
+
+    use crate::{LANG, get_function_spaces};
+
+    use super::*;
+
+    const SOURCE: &str = "def foo(a, b, c):
+    if a:
+        return 1
+    if b:
+        return 2
+    if c:
+        return 3
+    return 4
+
+def bar(a):
+    if a:
+        return 1
+    return 2
+";
+
+    #[test]
+    fn coverage_reports() {
+        let lcov = "TN:\nSF:/home/user/project/src/foo.py\nDA:1,1\nDA:2,1\nDA:3,0\nDA:4,1,abc\nend_of_record\nSF:./src/bar.py\nDA:1,0\nend_of_record\n";
+        let coverage = Coverage::parse(lcov);
+        let path = Path::new("src/foo.py");
+        assert_eq!(coverage.line_coverage(path, 1, 4), Some(0.75));
+        assert_eq!(coverage.line_coverage(path, 3, 3), Some(0.));
+        assert_eq!(coverage.line_coverage(path, 5, 8), None);
+        assert_eq!(
+            coverage.line_coverage(Path::new("./src/bar.py"), 1, 1),
+            Some(0.)
+        );
+        assert_eq!(coverage.line_coverage(Path::new("baz.py"), 1, 1), None);
+
+        let cobertura = r#"<?xml version="1.0" ?>
+<coverage line-rate="0.75">
+  <sources><source>/home/user/project</source></sources>
+  <packages><package name="src"><classes>
+    <class name="foo.py" filename="src/foo.py" line-rate="0.75">
+      <lines>
+        <line number="1" hits="1"/>
+        <line number="2" hits="1"/>
+        <line number="3" hits="0"/>
+        <line number="4" hits="1" branch="true" condition-coverage="50% (1/2)"/>
+      </lines>
+    </class>
+  </classes></package></packages>
+</coverage>
+"#;
+        let coverage = Coverage::parse(cobertura);
+        assert_eq!(coverage.line_coverage(path, 1, 4), Some(0.75));
+        assert_eq!(
+            coverage.line_coverage(Path::new("/tmp/project/src/foo.py"), 2, 3),
+            Some(0.5)
+        );
+        assert!(Coverage::parse("").is_empty());
+    }
+
+    #[test]
+    fn crap_scores() {
+        assert_eq!(crap_score(4., 1.), 4.);
+        assert_eq!(crap_score(4., 0.), 20.);
+        assert_eq!(crap_score(10., 0.5), 22.5);
+
+        let space = get_function_spaces(
+            &LANG::Python,
+            SOURCE.as_bytes().to_vec(),
+            Path::new("foo.py"),
+            None,
+        )
+        .unwrap();
+        // A quarter of `foo` is tested, `bar` is not in the report
+        let lcov = (1..=8)
+            .map(|line| format!("DA:{line},{}\n", u8::from(line % 4 == 0)))
+            .collect::<String>();
+        let coverage = Coverage::from_lcov(&format!("SF:foo.py\n{lcov}end_of_record\n"));
+
+        let mut report = CrapReport::new(coverage.clone(), 5.);
+        report.add(&space);
+        insta::assert_snapshot!(report, @r###"
+        Function  Location      Cyclomatic  Coverage   CRAP
+        ---------------------------------------------------
+        foo.foo   foo.py:1-8             4     25.0%  10.75
+        foo.bar   foo.py:10-13           2      0.0%   6.00
+        "###);
+        insta::assert_json_snapshot!(report, @r###"
+        {
+          "threshold": 5.0,
+          "functions": [
+            {
+              "path": "foo.py",
+              "name": "foo.foo",
+              "start_line": 1,
+              "end_line": 8,
+              "cyclomatic": 4.0,
+              "coverage": 25.0,
+              "crap": 10.75
+            },
+            {
+              "path": "foo.py",
+              "name": "foo.bar",
+              "start_line": 10,
+              "end_line": 13,
+              "cyclomatic": 2.0,
+              "coverage": 0.0,
+              "crap": 6.0
+            }
+          ]
+        }
+        "###);
+
+        let mut report = CrapReport::new(coverage, DEFAULT_CRAP_THRESHOLD);
+        report.add(&space);
+        assert!(report.functions().is_empty());
+    }
+}
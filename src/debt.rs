@@ -0,0 +1,180 @@
+use std::fmt;
+
+use serde::Serialize;
+use serde::ser::{SerializeStruct, Serializer};
+
+use crate::grades::Grade;
+use crate::rules::Finding;
+use crate::spaces::FuncSpace;
+
+/// The minutes needed to develop a line of code,
+/// used by SonarQube to compute the debt ratio.
+pub const DEVELOPMENT_MINUTES_PER_LINE: f64 = 30.;
+
+// The minutes of a working day, as counted by SonarQube
+const MINUTES_PER_DAY: u64 = 8 * 60;
+
+// The highest debt ratio, in percent, of the ratings from `A` to `D`
+const RATINGS: [f64; 4] = [5., 10., 20., 50.];
+
+/// The technical debt of a file or of a project, estimated
+/// as SonarQube does with the SQALE method.
+///
+/// The debt is the sum of the remediation costs of the findings, while
+/// the debt ratio is the debt divided by the cost of developing the code,
+/// which is [`DEVELOPMENT_MINUTES_PER_LINE`] for each line of code.
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct Debt {
+    /// The minutes needed to fix the findings
+    pub minutes: f64,
+    /// The lines of code, without blank lines and comments
+    pub lines: f64,
+}
+
+impl Debt {
+    /// Returns the debt of a file.
+    ///
+    /// * `space` - The root function space of the file.
+    /// * `findings` - The findings of the file.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use std::path::Path;
+    ///
+    /// use rust_code_analysis::{Debt, Grade, LANG, Rule, check_rules, get_function_spaces};
+    ///
+    /// let source_code = "def foo(a, b, c, d, e, f, g, h):\n    return a\n";
+    /// let path = Path::new("foo.py");
+    ///
+    /// let space =
+    ///     get_function_spaces(&LANG::Python, source_code.as_bytes().to_vec(), path, None).unwrap();
+    /// let debt = Debt::new(&space, &check_rules(&space, &Rule::defaults()));
+    ///
+    /// assert_eq!(debt.minutes, 20.);
+    /// assert_eq!(debt.rating(), Grade::D);
+    /// ```
+    pub fn new(space: &FuncSpace, findings: &[Finding]) -> Self {
+        Self {
+            // An empty sum of floats is -0
+            minutes: findings
+                .iter()
+                .fold(0., |minutes, finding| minutes + finding.remediation),
+            lines: space.metrics.loc.ploc(),
+        }
+    }
+
+    /// Merges a second debt into the first one.
+    pub fn merge(&mut self, other: &Debt) {
+        self.minutes += other.minutes;
+        self.lines += other.lines;
+    }
+
+    /// Returns the debt ratio, in percent.
+    pub fn ratio(&self) -> f64 {
+        if self.lines == 0. {
+            0.
+        } else {
+            100. * self.minutes / (self.lines * DEVELOPMENT_MINUTES_PER_LINE)
+        }
+    }
+
+    /// Returns the maintainability rating, from `A` to `E`,
+    /// for a debt ratio up to 5%, 10%, 20%, 50% and above.
+    pub fn rating(&self) -> Grade {
+        let ratio = self.ratio();
+        match RATINGS.iter().position(|max| ratio <= *max) {
+            Some(0) => Grade::A,
+            Some(1) => Grade::B,
+            Some(2) => Grade::C,
+            Some(_) => Grade::D,
+            None => Grade::E,
+        }
+    }
+}
+
+/// Formats some minutes as SonarQube does, such as `1d 2h` or
+/// `3h 20min`, with working days of 8 hours.
+pub fn format_minutes(minutes: f64) -> String {
+    let minutes = minutes.round() as u64;
+    let (days, hours, minutes) = (
+        minutes / MINUTES_PER_DAY,
+        minutes % MINUTES_PER_DAY / 60,
+        minutes % 60,
+    );
+    match (days, hours) {
+        (0, 0) => format!("{minutes}min"),
+        (0, _) if minutes > 0 => format!("{hours}h {minutes}min"),
+        (0, _) => format!("{hours}h"),
+        (_, 0) => format!("{days}d"),
+        _ => format!("{days}d {hours}h"),
+    }
+}
+
+impl fmt::Display for Debt {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "{} (ratio {:.1}%, rating {})",
+            format_minutes(self.minutes),
+            self.ratio(),
+            self.rating()
+        )
+    }
+}
+
+/// Serializes the debt with the names of the SonarQube measures.
+impl Serialize for Debt {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        let mut st = serializer.serialize_struct("debt", 3)?;
+        st.serialize_field("sqale_index", &self.minutes)?;
+        st.serialize_field("sqale_debt_ratio", &self.ratio())?;
+        st.serialize_field("sqale_rating", &self.rating())?;
+        st.end()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn debt_format() {
+        assert_eq!(format_minutes(0.), "0min");
+        assert_eq!(format_minutes(45.), "45min");
+        assert_eq!(format_minutes(120.), "2h");
+        assert_eq!(format_minutes(200.), "3h 20min");
+        assert_eq!(format_minutes(480.), "1d");
+        assert_eq!(format_minutes(610.), "1d 2h");
+    }
+
+    #[test]
+    fn debt_ratio() {
+        let mut debt = Debt {
+            minutes: 30.,
+            lines: 100.,
+        };
+        assert_eq!(debt.ratio(), 1.);
+        assert_eq!(debt.rating(), Grade::A);
+
+        debt.merge(&Debt {
+            minutes: 570.,
+            lines: 0.,
+        });
+        assert_eq!(debt.rating(), Grade::C);
+        insta::assert_snapshot!(debt, @r###"
+        1d 2h (ratio 20.0%, rating C)
+        "###);
+        insta::assert_json_snapshot!(debt, @r###"
+        {
+          "sqale_index": 600.0,
+          "sqale_debt_ratio": 20.0,
+          "sqale_rating": "C"
+        }
+        "###);
+        assert_eq!(Debt::default().rating(), Grade::A);
+    }
+}
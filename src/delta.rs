@@ -0,0 +1,317 @@
+use std::collections::{BTreeMap, HashMap};
+use std::fmt;
+use std::ops::Sub;
+
+use serde::Serialize;
+use serde_json::Value;
+
+use crate::spaces::{CodeMetrics, FuncSpace, SpaceKind};
+
+type MetricValues = BTreeMap<String, BTreeMap<String, f64>>;
+
+// Collects the values of each metric, by metric and by field,
+// as they are serialized. Values which are not finite are skipped.
+pub(crate) fn metric_values(metrics: &CodeMetrics) -> MetricValues {
+    let Ok(Value::Object(metrics)) = serde_json::to_value(metrics) else {
+        return MetricValues::new();
+    };
+
+    metrics
+        .into_iter()
+        .filter_map(|(metric, stats)| {
+            let Value::Object(stats) = stats else {
+                return None;
+            };
+            let fields = stats
+                .into_iter()
+                .filter_map(|(field, value)| value.as_f64().map(|value| (field, value)))
+                .collect();
+            Some((metric, fields))
+        })
+        .collect()
+}
+
+/// The signed differences between the values of two sets of metrics.
+///
+/// The differences are grouped by metric and by field, using the same
+/// names of the serialized metrics, such as `cyclomatic` and `sum`.
+/// A value which is missing on one of the two sides is considered as 0.
+#[derive(Clone, Debug, Default, PartialEq, Serialize)]
+#[serde(transparent)]
+pub struct CodeMetricsDelta(MetricValues);
+
+impl CodeMetricsDelta {
+    fn between(new: Option<&CodeMetrics>, old: Option<&CodeMetrics>) -> Self {
+        let new = new.map(metric_values).unwrap_or_default();
+        let mut old = old.map(metric_values).unwrap_or_default();
+
+        let mut delta = MetricValues::new();
+        for (metric, fields) in new {
+            let mut old_fields = old.remove(&metric).unwrap_or_default();
+            let mut fields: BTreeMap<_, _> = fields
+                .into_iter()
+                .map(|(field, value)| {
+                    let old_value = old_fields.remove(&field).unwrap_or_default();
+                    (field, value - old_value)
+                })
+                .collect();
+            fields.extend(old_fields.into_iter().map(|(field, value)| (field, -value)));
+            delta.insert(metric, fields);
+        }
+        for (metric, fields) in old {
+            let fields = fields
+                .into_iter()
+                .map(|(field, value)| (field, -value))
+                .collect();
+            delta.insert(metric, fields);
+        }
+
+        Self(delta)
+    }
+
+    /// Returns the difference of a field of a metric, such as
+    /// `delta.get("cyclomatic", "sum")`.
+    ///
+    /// Returns `None` if the metric or the field does not exist.
+    pub fn get(&self, metric: &str, field: &str) -> Option<f64> {
+        self.0.get(metric)?.get(field).copied()
+    }
+
+    /// Returns an iterator over the metric, the field and the difference
+    /// of all the values, sorted by metric and by field.
+    pub fn iter(&self) -> impl Iterator<Item = (&str, &str, f64)> {
+        self.0.iter().flat_map(|(metric, fields)| {
+            fields
+                .iter()
+                .map(move |(field, value)| (metric.as_str(), field.as_str(), *value))
+        })
+    }
+
+    /// Checks whether all the differences are 0.
+    pub fn is_zero(&self) -> bool {
+        self.iter().all(|(_, _, value)| value == 0.)
+    }
+}
+
+impl Sub for &CodeMetrics {
+    type Output = CodeMetricsDelta;
+
+    fn sub(self, other: &CodeMetrics) -> CodeMetricsDelta {
+        CodeMetricsDelta::between(Some(self), Some(other))
+    }
+}
+
+impl fmt::Display for CodeMetricsDelta {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let mut first = true;
+        for (metric, field, value) in self.iter().filter(|(_, _, value)| *value != 0.) {
+            if !first {
+                writeln!(f)?;
+            }
+            first = false;
+            write!(f, "{metric}.{field}: {value:+}")?;
+        }
+        Ok(())
+    }
+}
+
+/// The kinds of changes of a function space between two versions of a code.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum SpaceChange {
+    /// The space exists only in the new version
+    Added,
+    /// The space exists only in the old version
+    Removed,
+    /// The metrics of the space, or of one of its subspaces, have changed
+    Modified,
+    /// The metrics of the space and of all its subspaces are the same
+    Unchanged,
+}
+
+impl fmt::Display for SpaceChange {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let s = match self {
+            SpaceChange::Added => "added",
+            SpaceChange::Removed => "removed",
+            SpaceChange::Modified => "modified",
+            SpaceChange::Unchanged => "unchanged",
+        };
+        write!(f, "{s}")
+    }
+}
+
+/// The changes of the metrics of a function space between
+/// two versions of a code.
+#[derive(Clone, Debug, Serialize)]
+pub struct FuncSpaceDelta {
+    /// The name of the function space
+    pub name: Option<String>,
+    /// The identifier of the function space
+    pub id: String,
+    /// The space kind
+    pub kind: SpaceKind,
+    /// The kind of change
+    pub change: SpaceChange,
+    /// The changes of all subspaces contained in the function space
+    pub spaces: Vec<FuncSpaceDelta>,
+    /// The signed differences of the metrics, new minus old
+    pub metrics: CodeMetricsDelta,
+}
+
+impl FuncSpaceDelta {
+    // Returns the changes of a space which exists only in the new version
+    pub(crate) fn added(space: &FuncSpace) -> Self {
+        Self::new(Some(space), None)
+    }
+
+    fn new(new: Option<&FuncSpace>, old: Option<&FuncSpace>) -> Self {
+        // At least one of the two spaces exists
+        let space = new.or(old).unwrap();
+
+        let mut old_spaces: Vec<_> = old
+            .map(|old| old.spaces.iter().map(Some).collect())
+            .unwrap_or_default();
+        let mut spaces = Vec::new();
+        if let Some(new) = new {
+            let positions: HashMap<_, _> = old_spaces
+                .iter()
+                .enumerate()
+                .filter_map(|(i, old)| old.map(|old| (old.id.as_str(), i)))
+                .collect();
+            for subspace in &new.spaces {
+                let old_subspace = positions
+                    .get(subspace.id.as_str())
+                    .and_then(|i| old_spaces[*i].take());
+                spaces.push(Self::new(Some(subspace), old_subspace));
+            }
+        }
+        spaces.extend(
+            old_spaces
+                .into_iter()
+                .flatten()
+                .map(|old_subspace| Self::new(None, Some(old_subspace))),
+        );
+
+        let metrics =
+            CodeMetricsDelta::between(new.map(|new| &new.metrics), old.map(|old| &old.metrics));
+        let change = match (new, old) {
+            (Some(_), None) => SpaceChange::Added,
+            (None, _) => SpaceChange::Removed,
+            _ if metrics.is_zero()
+                && spaces
+                    .iter()
+                    .all(|space| space.change == SpaceChange::Unchanged) =>
+            {
+                SpaceChange::Unchanged
+            }
+            _ => SpaceChange::Modified,
+        };
+
+        Self {
+            name: space.name.clone(),
+            id: space.id.clone(),
+            kind: space.kind,
+            change,
+            spaces,
+            metrics,
+        }
+    }
+}
+
+impl FuncSpace {
+    /// Compares the metrics of a function space with the metrics
+    /// of an old version of the same space.
+    ///
+    /// The subspaces of the two versions are matched through their
+    /// identifiers, so a function which has been moved is compared
+    /// with its old version. The name and the identifier of the result
+    /// are the ones of the new version.
+    ///
+    /// * `old` - The old version of the function space.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use std::path::Path;
+    ///
+    /// use rust_code_analysis::{LANG, SpaceChange, get_function_spaces};
+    ///
+    /// let path = Path::new("foo.py");
+    /// let old = "def foo(a):\n    return a\n";
+    /// let new = "def foo(a):\n    if a:\n        return 1\n    return a\n";
+    ///
+    /// let old = get_function_spaces(&LANG::Python, old.as_bytes().to_vec(), path, None).unwrap();
+    /// let new = get_function_spaces(&LANG::Python, new.as_bytes().to_vec(), path, None).unwrap();
+    /// let delta = new.diff(&old);
+    ///
+    /// assert_eq!(delta.spaces[0].change, SpaceChange::Modified);
+    /// assert_eq!(delta.spaces[0].metrics.get("cyclomatic", "sum"), Some(1.));
+    /// ```
+    pub fn diff(&self, old: &FuncSpace) -> FuncSpaceDelta {
+        FuncSpaceDelta::new(Some(self), Some(old))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::path::Path;
+
+    use pretty_assertions::assert_eq;
+
+    use super::*;
+    use crate::langs::{LANG, get_function_spaces};
+
+    fn space(source: &str) -> FuncSpace {
+        get_function_spaces(
+            &LANG::Python,
+            source.as_bytes().to_vec(),
+            Path::new("foo.py"),
+            None,
+        )
+        .unwrap()
+    }
+
+    #[test]
+    fn test_code_metrics_sub() {
+        let old = space("def foo(a):\n    return a\n");
+        let new = space("def foo(a, b):\n    if a:\n        return b\n    return a\n");
+
+        let delta = &new.metrics - &old.metrics;
+        assert_eq!(delta.get("cyclomatic", "sum"), Some(1.));
+        assert_eq!(delta.get("nargs", "total_functions"), Some(1.));
+        assert_eq!(delta.get("loc", "sloc"), Some(2.));
+        assert_eq!(delta.get("foo", "sum"), None);
+
+        assert!((&new.metrics - &new.metrics).is_zero());
+    }
+
+    #[test]
+    fn test_func_space_diff() {
+        let old = space("def foo(a):\n    return a\n\ndef bar():\n    pass\n");
+        let new = space("def baz():\n    pass\n\ndef foo(a):\n    if a:\n        return 1\n");
+
+        let delta = new.diff(&old);
+        assert_eq!(delta.change, SpaceChange::Modified);
+
+        let changes: Vec<_> = delta
+            .spaces
+            .iter()
+            .map(|space| (space.name.as_deref().unwrap(), space.change))
+            .collect();
+        assert_eq!(
+            changes,
+            vec![
+                ("baz", SpaceChange::Added),
+                ("foo", SpaceChange::Modified),
+                ("bar", SpaceChange::Removed)
+            ]
+        );
+
+        assert_eq!(delta.spaces[0].metrics.get("cyclomatic", "sum"), Some(1.));
+        assert_eq!(delta.spaces[1].metrics.get("cyclomatic", "sum"), Some(1.));
+        assert_eq!(delta.spaces[2].metrics.get("cyclomatic", "sum"), Some(-1.));
+
+        assert_eq!(old.diff(&old).change, SpaceChange::Unchanged);
+    }
+}
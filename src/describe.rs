@@ -0,0 +1,544 @@
+use serde::Serialize;
+
+/// The recommended thresholds of a metric for a function.
+#[derive(Clone, Copy, Debug, PartialEq, Serialize)]
+pub struct MetricThresholds {
+    /// The field of the metric the thresholds apply to, such as `sum`
+    pub field: &'static str,
+    /// The value from which a function should be reviewed
+    pub warning: f64,
+    /// The value from which a function should be refactored
+    pub critical: f64,
+}
+
+/// The description of a metric, to explain its values in reports
+/// and user interfaces.
+#[derive(Clone, Copy, Debug, PartialEq, Serialize)]
+pub struct MetricDescription {
+    /// The key of the metric in the serialized metrics, such as `cyclomatic`
+    pub key: &'static str,
+    /// The human readable name of the metric
+    pub name: &'static str,
+    /// What the metric measures
+    pub description: &'static str,
+    /// A summary of how the metric is computed
+    pub formula: &'static str,
+    /// The fields of the metric in the serialized metrics
+    pub fields: &'static [&'static str],
+    /// The lowest value of the metric, if it is bounded
+    pub min: Option<f64>,
+    /// The highest value of the metric, if it is bounded
+    pub max: Option<f64>,
+    /// If `true`, the higher values are the better ones
+    pub higher_is_better: bool,
+    /// The recommended thresholds, for the metrics having
+    /// widely used ones
+    ///
+    /// For the metrics whose lower values are better, the critical
+    /// threshold is higher than the warning one, and vice versa.
+    pub thresholds: Option<MetricThresholds>,
+}
+
+const METRICS: &[MetricDescription] = &[
+    MetricDescription {
+        key: "nargs",
+        name: "Number of Arguments",
+        description: "The number of arguments of the functions and of the closures.",
+        formula: "The count of the parameters in the signature of each function",
+        fields: &[
+            "total_functions",
+            "total_closures",
+            "average_functions",
+            "average_closures",
+            "total",
+            "average",
+            "functions_min",
+            "functions_max",
+            "closures_min",
+            "closures_max",
+        ],
+        min: Some(0.),
+        max: None,
+        higher_is_better: false,
+        thresholds: Some(MetricThresholds {
+            field: "total_functions",
+            warning: 5.,
+            critical: 7.,
+        }),
+    },
+    MetricDescription {
+        key: "nexits",
+        name: "Number of Exit Points",
+        description: "The number of exit points of the functions, such as the `return` statements.",
+        formula: "The count of the return statements of each function",
+        fields: &["sum", "average", "min", "max"],
+        min: Some(0.),
+        max: None,
+        higher_is_better: false,
+        thresholds: Some(MetricThresholds {
+            field: "sum",
+            warning: 3.,
+            critical: 5.,
+        }),
+    },
+    MetricDescription {
+        key: "cognitive",
+        name: "Cognitive Complexity",
+        description: "How hard the control flow of the code is to understand.",
+        formula: "1 for each break in the linear flow, such as a branch or a loop, \
+                  plus 1 for each level of nesting of the break",
+        fields: &["sum", "average", "min", "max"],
+        min: Some(0.),
+        max: None,
+        higher_is_better: false,
+        thresholds: Some(MetricThresholds {
+            field: "sum",
+            warning: 15.,
+            critical: 25.,
+        }),
+    },
+    MetricDescription {
+        key: "cyclomatic",
+        name: "Cyclomatic Complexity",
+        description: "The number of linearly independent paths through the code.",
+        formula: "1 + the number of decision points, such as the branches, \
+                  the loops and the boolean operators",
+        fields: &["sum", "average", "min", "max"],
+        min: Some(1.),
+        max: None,
+        higher_is_better: false,
+        thresholds: Some(MetricThresholds {
+            field: "sum",
+            warning: 10.,
+            critical: 20.,
+        }),
+    },
+    MetricDescription {
+        key: "halstead",
+        name: "Halstead Metrics",
+        description: "The size, the difficulty and the effort of the code, \
+                      computed from its operators and its operands.",
+        formula: "volume = (N1 + N2) * log2(n1 + n2), difficulty = n1 / 2 * N2 / n2, \
+                  effort = difficulty * volume, where n1 and n2 are the distinct operators \
+                  and operands and N1 and N2 their occurrences",
+        fields: &[
+            "n1",
+            "N1",
+            "n2",
+            "N2",
+            "length",
+            "estimated_program_length",
+            "purity_ratio",
+            "vocabulary",
+            "volume",
+            "difficulty",
+            "level",
+            "effort",
+            "time",
+            "bugs",
+        ],
+        min: Some(0.),
+        max: None,
+        higher_is_better: false,
+        thresholds: None,
+    },
+    MetricDescription {
+        key: "loc",
+        name: "Lines of Code",
+        description: "The lines of the code, by kind.",
+        formula: "sloc counts all the lines, ploc the lines of code, lloc the statements, \
+                  cloc the comment lines and blank the empty lines",
+        fields: &[
+            "sloc",
+            "ploc",
+            "lloc",
+            "cloc",
+            "blank",
+            "sloc_average",
+            "ploc_average",
+            "lloc_average",
+            "cloc_average",
+            "blank_average",
+            "sloc_min",
+            "sloc_max",
+            "cloc_min",
+            "cloc_max",
+            "ploc_min",
+            "ploc_max",
+            "lloc_min",
+            "lloc_max",
+            "blank_min",
+            "blank_max",
+        ],
+        min: Some(0.),
+        max: None,
+        higher_is_better: false,
+        thresholds: Some(MetricThresholds {
+            field: "sloc",
+            warning: 50.,
+            critical: 100.,
+        }),
+    },
+    MetricDescription {
+        key: "nom",
+        name: "Number of Methods",
+        description: "The number of functions and of closures.",
+        formula: "The count of the function and closure definitions",
+        fields: &[
+            "functions",
+            "closures",
+            "functions_average",
+            "closures_average",
+            "total",
+            "average",
+            "functions_min",
+            "functions_max",
+            "closures_min",
+            "closures_max",
+        ],
+        min: Some(0.),
+        max: None,
+        higher_is_better: false,
+        thresholds: None,
+    },
+    MetricDescription {
+        key: "mi",
+        name: "Maintainability Index",
+        description: "How easy the code is to maintain, from its size, \
+                      its complexity and its comments.",
+        formula: "171 - 5.2 * ln(Halstead volume) - 0.23 * cyclomatic - 16.2 * ln(sloc), \
+                  rescaled from 0 to 100 by the Visual Studio variant",
+        fields: &["mi_original", "mi_sei", "mi_visual_studio"],
+        min: None,
+        max: None,
+        higher_is_better: true,
+        thresholds: Some(MetricThresholds {
+            field: "mi_visual_studio",
+            warning: 20.,
+            critical: 10.,
+        }),
+    },
+    MetricDescription {
+        key: "abc",
+        name: "ABC Metric",
+        description: "The size of the code, as its Assignments, Branches and Conditions.",
+        formula: "magnitude = sqrt(A^2 + B^2 + C^2)",
+        fields: &[
+            "assignments",
+            "branches",
+            "conditions",
+            "magnitude",
+            "assignments_average",
+            "branches_average",
+            "conditions_average",
+            "assignments_min",
+            "assignments_max",
+            "branches_min",
+            "branches_max",
+            "conditions_min",
+            "conditions_max",
+        ],
+        min: Some(0.),
+        max: None,
+        higher_is_better: false,
+        thresholds: None,
+    },
+    MetricDescription {
+        key: "wmc",
+        name: "Weighted Methods per Class",
+        description: "The complexity of the classes.",
+        formula: "The sum of the cyclomatic complexities of the methods of a class, \
+                  and the sum of their cognitive complexities",
+        fields: &[
+            "classes",
+            "interfaces",
+            "total",
+            "classes_cognitive",
+            "interfaces_cognitive",
+            "total_cognitive",
+        ],
+        min: Some(0.),
+        max: None,
+        higher_is_better: false,
+        thresholds: None,
+    },
+    MetricDescription {
+        key: "npm",
+        name: "Number of Public Methods",
+        description: "The public methods of the classes and of the interfaces.",
+        formula: "The count of the public methods, averaged over all the methods",
+        fields: &[
+            "classes",
+            "interfaces",
+            "class_methods",
+            "interface_methods",
+            "classes_average",
+            "interfaces_average",
+            "total",
+            "total_methods",
+            "average",
+        ],
+        min: Some(0.),
+        max: None,
+        higher_is_better: false,
+        thresholds: None,
+    },
+    MetricDescription {
+        key: "npa",
+        name: "Number of Public Attributes",
+        description: "The public attributes of the classes and of the interfaces.",
+        formula: "The count of the public attributes, averaged over all the attributes",
+        fields: &[
+            "classes",
+            "interfaces",
+            "class_attributes",
+            "interface_attributes",
+            "classes_average",
+            "interfaces_average",
+            "total",
+            "total_attributes",
+            "average",
+        ],
+        min: Some(0.),
+        max: None,
+        higher_is_better: false,
+        thresholds: None,
+    },
+    MetricDescription {
+        key: "ppc",
+        name: "Preprocessor Conditionals",
+        description: "The preprocessor conditionals, which select the code to compile.",
+        formula: "The count of the `#if`, `#ifdef` and `#ifndef` directives, \
+                  of their branches and their maximum nesting",
+        fields: &["conditionals", "branches", "nesting_max"],
+        min: Some(0.),
+        max: None,
+        higher_is_better: false,
+        thresholds: None,
+    },
+    MetricDescription {
+        key: "unsafety",
+        name: "Unsafety",
+        description: "The `unsafe` code of Rust.",
+        formula: "The count of the `unsafe` blocks and functions, and the ratio \
+                  of their lines to the lines of code",
+        fields: &["blocks", "functions", "lines", "ratio"],
+        min: Some(0.),
+        max: None,
+        higher_is_better: false,
+        thresholds: None,
+    },
+    MetricDescription {
+        key: "callbacks",
+        name: "Callbacks",
+        description: "The nesting of the callbacks and the length of the promise chains.",
+        formula: "The maximum nesting of the functions passed as arguments \
+                  and the longest chain of `.then()` calls",
+        fields: &["nesting_max", "chain_max"],
+        min: Some(0.),
+        max: None,
+        higher_is_better: false,
+        thresholds: None,
+    },
+    MetricDescription {
+        key: "annotations",
+        name: "Annotations",
+        description: "The annotations and the declared exceptions.",
+        formula: "The count of the annotations and of the exceptions \
+                  of the `throws` clauses",
+        fields: &["annotations", "throws"],
+        min: Some(0.),
+        max: None,
+        higher_is_better: false,
+        thresholds: None,
+    },
+    MetricDescription {
+        key: "accessors",
+        name: "Accessors",
+        description: "The property getters and setters, apart from the other functions.",
+        formula: "The count of the getters, of the setters \
+                  and of the functions which are not accessors",
+        fields: &["getters", "setters", "total", "functions"],
+        min: Some(0.),
+        max: None,
+        higher_is_better: false,
+        thresholds: None,
+    },
+    MetricDescription {
+        key: "assertions",
+        name: "Assertions",
+        description: "The assertions of the functions, to measure the quality of the tests.",
+        formula: "The count of the assertion statements, of the assertion macros \
+                  and of the calls to the assertion APIs",
+        fields: &["sum", "average", "min", "max"],
+        min: Some(0.),
+        max: None,
+        higher_is_better: true,
+        thresholds: None,
+    },
+    MetricDescription {
+        key: "locals",
+        name: "Local Variables",
+        description: "The local variables declared in the functions.",
+        formula: "The count of the variables declared in the body of a function, \
+                  including the loop variables and the names bound by the destructuring patterns",
+        fields: &["sum", "average", "min", "max"],
+        min: Some(0.),
+        max: None,
+        higher_is_better: false,
+        thresholds: None,
+    },
+    MetricDescription {
+        key: "kotlin_funcs",
+        name: "Kotlin Functions",
+        description: "The Kotlin functions running in a different context.",
+        formula: "The count of the `suspend` functions, of the extension functions \
+                  and of the function types with a receiver",
+        fields: &[
+            "suspend_functions",
+            "extension_functions",
+            "receiver_lambdas",
+        ],
+        min: Some(0.),
+        max: None,
+        higher_is_better: false,
+        thresholds: None,
+    },
+    MetricDescription {
+        key: "macro_calls",
+        name: "Macro Calls",
+        description: "The macro invocations, whose logic is not measured by the other metrics.",
+        formula: "The count of the macro invocations and of the statements \
+                  which are macro invocations",
+        fields: &[
+            "invocations",
+            "statements",
+            "macro_statements",
+            "dominated_functions",
+        ],
+        min: Some(0.),
+        max: None,
+        higher_is_better: false,
+        thresholds: None,
+    },
+    MetricDescription {
+        key: "indentation",
+        name: "Indentation",
+        description: "The indentation of the lines, whose nesting is a proxy of the complexity.",
+        formula: "The count of the lines indented with tabs, with spaces or with both, \
+                  the maximum and the average number of indentation units of the lines \
+                  relative to the first one, and the count of the lines ending with whitespace",
+        fields: &[
+            "style",
+            "tab_lines",
+            "space_lines",
+            "mixed_lines",
+            "level_max",
+            "level_average",
+            "trailing_whitespace",
+        ],
+        min: Some(0.),
+        max: None,
+        higher_is_better: false,
+        thresholds: None,
+    },
+];
+
+/// Returns the descriptions of all the metrics, in the order
+/// of the serialized metrics.
+///
+/// # Examples
+///
+/// ```
+/// use rust_code_analysis::describe_metrics;
+///
+/// let cyclomatic = describe_metrics()
+///     .iter()
+///     .find(|metric| metric.key == "cyclomatic")
+///     .unwrap();
+///
+/// assert_eq!(cyclomatic.name, "Cyclomatic Complexity");
+/// ```
+pub fn describe_metrics() -> &'static [MetricDescription] {
+    METRICS
+}
+
+/// Returns the description of a metric from its key, such as `cyclomatic`.
+pub fn describe_metric(key: &str) -> Option<&'static MetricDescription> {
+    METRICS.iter().find(|metric| metric.key == key)
+}
+
+/// Resolves a metric, optionally followed by one of its fields such as
+/// `loc.sloc`, to its description and the name of its field.
+///
+/// Without a field, the `sum` field is used, or the first field
+/// of the metrics without one.
+///
+/// Returns `None` if the metric or the field does not exist.
+pub fn describe_metric_field(name: &str) -> Option<(&'static MetricDescription, &'static str)> {
+    let (key, field) = match name.split_once('.') {
+        Some((key, field)) => (key, Some(field)),
+        None => (name, None),
+    };
+    let metric = describe_metric(key)?;
+    let field = match field {
+        Some(field) => metric.fields.iter().find(|f| **f == field)?,
+        None => metric
+            .fields
+            .iter()
+            .find(|f| **f == "sum")
+            .or_else(|| metric.fields.first())?,
+    };
+    Some((metric, field))
+}
+
+#[cfg(test)]
+mod tests {
+    use std::path::Path;
+
+    use super::*;
+    use crate::delta::metric_values;
+    use crate::langs::{LANG, get_function_spaces};
+
+    #[test]
+    fn all_metrics_described() {
+        for (language, path, source) in [
+            (LANG::Rust, "foo.rs", "unsafe fn foo() { println!(\"\"); }"),
+            (
+                LANG::Java,
+                "Foo.java",
+                "class Foo { public int a; void f() {} }",
+            ),
+            (LANG::Cpp, "foo.cpp", "#ifdef A\nint a;\n#endif\n"),
+            (LANG::Kotlin, "foo.kt", "suspend fun foo() {}"),
+            (LANG::Javascript, "foo.js", "f(() => g());"),
+        ] {
+            let space =
+                get_function_spaces(&language, source.as_bytes().to_vec(), Path::new(path), None)
+                    .unwrap();
+            for (metric, fields) in metric_values(&space.metrics) {
+                let description =
+                    describe_metric(&metric).unwrap_or_else(|| panic!("{metric} is not described"));
+                for field in fields.keys() {
+                    assert!(
+                        description.fields.contains(&field.as_str()),
+                        "{metric}.{field} is not described"
+                    );
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn thresholds_fields() {
+        for metric in describe_metrics() {
+            if let Some(thresholds) = metric.thresholds {
+                assert!(metric.fields.contains(&thresholds.field));
+                assert_eq!(
+                    thresholds.critical > thresholds.warning,
+                    !metric.higher_is_better
+                );
+            }
+        }
+    }
+}
@@ -0,0 +1,233 @@
+use std::fmt;
+use std::path::Path;
+
+use crate::langs::LANG;
+use crate::tools::guess_language;
+
+/// The dialects of the supported languages.
+///
+/// A dialect selects the grammar used to parse the files of a language
+/// when their extension is ambiguous, such as the `.js` files, which can
+/// contain Flow type annotations, or the language reported for them,
+/// such as the `.h` headers, which can contain either C or C++ code.
+///
+/// The versions of a language, such as the C++ standards or Python 2,
+/// are not dialects, since the same grammar parses all of them.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum Dialect {
+    /// The C language
+    C,
+    /// The C++ language
+    Cpp,
+    /// The JavaScript language with the Mozilla extensions
+    Mozjs,
+    /// The standard JavaScript language
+    Javascript,
+    /// JavaScript with the Flow type annotations
+    Flow,
+    /// The TypeScript language, where `<T>expr` is a type assertion
+    Typescript,
+    /// TypeScript with JSX elements
+    Tsx,
+}
+
+impl Dialect {
+    /// The names of the dialects.
+    pub const NAMES: &'static [&'static str] = &[
+        "c",
+        "c++",
+        "mozjs",
+        "javascript",
+        "flow",
+        "typescript",
+        "tsx",
+    ];
+
+    /// Returns the dialect with the given name, ignoring the case.
+    ///
+    /// The standards of C and C++, such as `c99` or `c++17`, are not
+    /// dialects, since they would not change how the code is parsed.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rust_code_analysis::Dialect;
+    ///
+    /// assert_eq!(Dialect::from_name("c++"), Some(Dialect::Cpp));
+    /// assert_eq!(Dialect::from_name("Flow"), Some(Dialect::Flow));
+    /// assert_eq!(Dialect::from_name("c++17"), None);
+    /// ```
+    pub fn from_name(name: &str) -> Option<Self> {
+        let name = name.to_lowercase();
+        let dialect = match name.as_str() {
+            "c" => Dialect::C,
+            "c++" | "cpp" | "cxx" => Dialect::Cpp,
+            "mozjs" => Dialect::Mozjs,
+            "javascript" | "js" | "ecmascript" => Dialect::Javascript,
+            "flow" => Dialect::Flow,
+            "typescript" | "ts" => Dialect::Typescript,
+            "tsx" => Dialect::Tsx,
+            _ => return None,
+        };
+        Some(dialect)
+    }
+
+    /// Returns the name of the dialect.
+    pub fn name(&self) -> &'static str {
+        match self {
+            Dialect::C => "c",
+            Dialect::Cpp => "c++",
+            Dialect::Mozjs => "mozjs",
+            Dialect::Javascript => "javascript",
+            Dialect::Flow => "flow",
+            Dialect::Typescript => "typescript",
+            Dialect::Tsx => "tsx",
+        }
+    }
+
+    /// Returns the language whose grammar parses the dialect.
+    ///
+    /// The grammar of C and C++ is shared, so these dialects
+    /// only change the reported language name.
+    /// The Flow annotations are parsed by the `TSX` grammar,
+    /// which accepts both the type annotations and the JSX elements.
+    pub fn language(&self) -> LANG {
+        match self {
+            Dialect::C | Dialect::Cpp => LANG::Cpp,
+            Dialect::Mozjs => LANG::Mozjs,
+            Dialect::Javascript => LANG::Javascript,
+            Dialect::Flow | Dialect::Tsx => LANG::Tsx,
+            Dialect::Typescript => LANG::Typescript,
+        }
+    }
+
+    /// Returns `true` if the dialect applies to the files
+    /// detected as written in the given language.
+    pub fn is_dialect_of(&self, language: LANG) -> bool {
+        match self {
+            Dialect::C | Dialect::Cpp => language == LANG::Cpp,
+            Dialect::Mozjs | Dialect::Javascript | Dialect::Flow => {
+                matches!(language, LANG::Mozjs | LANG::Javascript)
+            }
+            Dialect::Typescript | Dialect::Tsx => {
+                matches!(language, LANG::Typescript | LANG::Tsx)
+            }
+        }
+    }
+}
+
+impl fmt::Display for Dialect {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.name())
+    }
+}
+
+/// Guesses the language of a code as [`guess_language`] does,
+/// then selects the grammar of the first of the dialects
+/// which applies to the detected language.
+///
+/// Returns a tuple containing a [`LANG`] as first argument
+/// and the language name, or the dialect name, as a second one.
+///
+/// # Examples
+///
+/// ```
+/// use std::path::Path;
+///
+/// use rust_code_analysis::{Dialect, LANG, guess_language_with_dialects};
+///
+/// let source_code = "int a = 42;";
+/// let path = Path::new("foo.h");
+///
+/// assert_eq!(
+///     guess_language_with_dialects(source_code.as_bytes(), path, &[Dialect::C]),
+///     (Some(LANG::Cpp), "c")
+/// );
+/// ```
+///
+/// [`guess_language`]: fn.guess_language.html
+/// [`LANG`]: enum.LANG.html
+pub fn guess_language_with_dialects<'a, P: AsRef<Path>>(
+    buf: &[u8],
+    path: P,
+    dialects: &[Dialect],
+) -> (Option<LANG>, &'a str) {
+    let (language, name) = guess_language(buf, path);
+    match language.and_then(|language| {
+        dialects
+            .iter()
+            .find(|dialect| dialect.is_dialect_of(language))
+    }) {
+        Some(dialect) => (Some(dialect.language()), dialect.name()),
+        None => (language, name),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::path::Path;
+
+    use super::*;
+    use crate::langs::get_function_spaces;
+
+    #[test]
+    fn dialect_names() {
+        for name in Dialect::NAMES {
+            assert_eq!(Dialect::from_name(name).unwrap().name(), *name);
+        }
+        assert_eq!(Dialect::from_name("C"), Some(Dialect::C));
+        assert_eq!(Dialect::from_name("cpp"), Some(Dialect::Cpp));
+        // The language versions parsed by the same grammar are not dialects
+        assert_eq!(Dialect::from_name("c99"), None);
+        assert_eq!(Dialect::from_name("c++20"), None);
+        assert_eq!(Dialect::from_name("python3"), None);
+    }
+
+    #[test]
+    fn guess_with_dialects() {
+        let source = b"int a = 42;";
+        assert_eq!(
+            guess_language_with_dialects(source, "foo.h", &[]),
+            (Some(LANG::Cpp), "c/c++")
+        );
+        assert_eq!(
+            guess_language_with_dialects(source, "foo.h", &[Dialect::Flow, Dialect::C]),
+            (Some(LANG::Cpp), "c")
+        );
+        // The dialects of other languages are ignored
+        assert_eq!(
+            guess_language_with_dialects(b"a = 1", "foo.py", &[Dialect::Tsx]),
+            (Some(LANG::Python), "python")
+        );
+        assert_eq!(
+            guess_language_with_dialects(b"var a = 1;", "foo.js", &[Dialect::Flow]),
+            (Some(LANG::Tsx), "flow")
+        );
+    }
+
+    #[test]
+    fn tsx_dialect() {
+        let source = "function Foo(): JSX.Element {\n    return <div>{1}</div>;\n}\n";
+        let path = Path::new("foo.ts");
+
+        let (language, _) = guess_language_with_dialects(source.as_bytes(), path, &[Dialect::Tsx]);
+        let space = get_function_spaces(&language.unwrap(), source.as_bytes().to_vec(), path, None)
+            .unwrap();
+
+        assert_eq!(space.spaces.len(), 1);
+        assert_eq!(space.spaces[0].name.as_deref(), Some("Foo"));
+    }
+
+    #[test]
+    fn flow_dialect() {
+        let source = "// @flow\nfunction foo(a: number, b: ?string): number {\n    return a;\n}\n";
+        let path = Path::new("foo.js");
+
+        let (language, _) = guess_language_with_dialects(source.as_bytes(), path, &[Dialect::Flow]);
+        let space = get_function_spaces(&language.unwrap(), source.as_bytes().to_vec(), path, None)
+            .unwrap();
+
+        assert_eq!(space.spaces[0].name.as_deref(), Some("foo"));
+        assert_eq!(space.spaces[0].metrics.nargs.fn_args_sum(), 2.);
+    }
+}
@@ -0,0 +1,230 @@
+use std::ops::Range;
+
+/// A hunk of a unified diff.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct Hunk {
+    /// The first line of the hunk in the old file
+    pub old_start: usize,
+    /// The number of lines of the hunk in the old file
+    pub old_lines: usize,
+    /// The first line of the hunk in the new file
+    pub new_start: usize,
+    /// The number of lines of the hunk in the new file
+    pub new_lines: usize,
+    /// The lines of the hunk, starting with ` `, `-` or `+`
+    /// and including their line terminators
+    pub lines: Vec<String>,
+}
+
+impl Hunk {
+    /// Returns the lines of the new file shown in the hunk.
+    pub fn new_range(&self) -> Range<usize> {
+        self.new_start..self.new_start + self.new_lines
+    }
+}
+
+/// The changes of a file in a unified diff.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct FileDiff {
+    /// The path of the old file, `None` if the file has been added
+    pub old_path: Option<String>,
+    /// The path of the new file, `None` if the file has been deleted
+    pub new_path: Option<String>,
+    /// The hunks of the file
+    pub hunks: Vec<Hunk>,
+}
+
+// Removes the `a/` and `b/` prefixes added by git to the paths
+fn diff_path(path: &str, prefix: &str) -> Option<String> {
+    // The path can be followed by a tab and a timestamp
+    let path = path.split('\t').next().unwrap_or_default().trim_end();
+    if path == "/dev/null" {
+        None
+    } else {
+        Some(path.strip_prefix(prefix).unwrap_or(path).to_owned())
+    }
+}
+
+// Parses a range of a hunk header, such as `12,3` or `12`
+fn hunk_range(range: &str) -> Option<(usize, usize)> {
+    match range.split_once(',') {
+        Some((start, lines)) => Some((start.parse().ok()?, lines.parse().ok()?)),
+        None => Some((range.parse().ok()?, 1)),
+    }
+}
+
+// Parses a hunk header, such as `@@ -12,3 +12,4 @@ fn foo() {`
+fn hunk_header(line: &str) -> Option<Hunk> {
+    let mut ranges = line.strip_prefix("@@ ")?.split(' ');
+    let (old_start, old_lines) = hunk_range(ranges.next()?.strip_prefix('-')?)?;
+    let (new_start, new_lines) = hunk_range(ranges.next()?.strip_prefix('+')?)?;
+    Some(Hunk {
+        old_start,
+        old_lines,
+        new_start,
+        new_lines,
+        lines: Vec::new(),
+    })
+}
+
+/// Parses the files of a unified diff, such as the output of `git diff`.
+///
+/// The `a/` and `b/` prefixes of the paths written by git are removed.
+///
+/// # Examples
+///
+/// ```
+/// use rust_code_analysis::parse_diff;
+///
+/// let diff = "--- a/foo.py\n+++ b/foo.py\n@@ -1,2 +1,2 @@\n def foo():\n-    return 1\n+    return 2\n";
+/// let files = parse_diff(diff);
+///
+/// assert_eq!(files[0].new_path.as_deref(), Some("foo.py"));
+/// assert_eq!(files[0].hunks[0].new_range(), 1..3);
+/// ```
+pub fn parse_diff(diff: &str) -> Vec<FileDiff> {
+    let mut files: Vec<FileDiff> = Vec::new();
+    // The lines of the new and of the old file remaining in the current hunk
+    let mut remaining: (usize, usize) = (0, 0);
+
+    for line in diff.split_inclusive('\n') {
+        let kind = line.as_bytes()[0];
+        let in_hunk = remaining != (0, 0) && matches!(kind, b' ' | b'-' | b'+' | b'\r' | b'\n');
+        // `\ No newline at end of file` follows the last line of a hunk
+        if in_hunk || kind == b'\\' {
+            let Some(hunk) = files.last_mut().and_then(|file| file.hunks.last_mut()) else {
+                continue;
+            };
+            match kind {
+                b'-' => remaining.0 = remaining.0.saturating_sub(1),
+                b'+' => remaining.1 = remaining.1.saturating_sub(1),
+                b'\\' => {}
+                _ => remaining = (remaining.0.saturating_sub(1), remaining.1.saturating_sub(1)),
+            }
+            // The space of an empty context line can be stripped
+            if matches!(kind, b'\r' | b'\n') {
+                hunk.lines.push(format!(" {line}"));
+            } else {
+                hunk.lines.push(line.to_owned());
+            }
+        } else if let Some(path) = line.strip_prefix("--- ") {
+            files.push(FileDiff {
+                old_path: diff_path(path, "a/"),
+                ..Default::default()
+            });
+        } else if let Some(path) = line.strip_prefix("+++ ") {
+            if let Some(file) = files.last_mut() {
+                file.new_path = diff_path(path, "b/");
+            }
+        } else if let Some(hunk) = hunk_header(line)
+            && let Some(file) = files.last_mut()
+        {
+            remaining = (hunk.old_lines, hunk.new_lines);
+            file.hunks.push(hunk);
+        }
+    }
+
+    files
+}
+
+impl FileDiff {
+    /// Returns the content of the old file, rebuilt by reverting
+    /// the hunks on the content of the new file.
+    ///
+    /// * `new` - The content of the new file.
+    pub fn old_source(&self, new: &[u8]) -> Vec<u8> {
+        let new_lines: Vec<_> = new.split_inclusive(|byte| *byte == b'\n').collect();
+        let mut old = Vec::with_capacity(new.len());
+        let mut next = 0;
+
+        for hunk in &self.hunks {
+            // A hunk without new lines is after its start line
+            let start = if hunk.new_lines == 0 {
+                hunk.new_start
+            } else {
+                hunk.new_start.saturating_sub(1)
+            };
+            while next < start.min(new_lines.len()) {
+                old.extend_from_slice(new_lines[next]);
+                next += 1;
+            }
+
+            let mut last = b' ';
+            for line in &hunk.lines {
+                match line.as_bytes()[0] {
+                    b' ' => {
+                        if let Some(line) = new_lines.get(next) {
+                            old.extend_from_slice(line);
+                        }
+                        next += 1;
+                    }
+                    b'-' => old.extend_from_slice(&line.as_bytes()[1..]),
+                    b'+' => next += 1,
+                    // The removed line has no terminator
+                    b'\\' if last == b'-' && old.last() == Some(&b'\n') => {
+                        old.pop();
+                    }
+                    _ => {}
+                }
+                last = line.as_bytes()[0];
+            }
+        }
+        for line in new_lines.iter().skip(next) {
+            old.extend_from_slice(line);
+        }
+
+        old
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const DIFF: &str = "diff --git a/foo.py b/foo.py\n\
+        index 1234567..89abcde 100644\n\
+        --- a/foo.py\n\
+        +++ b/foo.py\n\
+        @@ -1,3 +1,5 @@\n \
+        def foo(a):\n\
+        -    return a\n\
+        +    if a:\n\
+        +        return 1\n\
+        +    return 0\n\n\
+        @@ -8 +9,0 @@ def bar():\n\
+        -    pass\n\
+        diff --git a/new.py b/new.py\n\
+        new file mode 100644\n\
+        --- /dev/null\n\
+        +++ b/new.py\n\
+        @@ -0,0 +1 @@\n\
+        +x = 1\n";
+
+    #[test]
+    fn diff_parse() {
+        let files = parse_diff(DIFF);
+        assert_eq!(files.len(), 2);
+        assert_eq!(files[0].old_path.as_deref(), Some("foo.py"));
+        assert_eq!(files[0].hunks.len(), 2);
+        assert_eq!(files[0].hunks[0].lines.len(), 6);
+        assert_eq!(files[0].hunks[1].new_range(), 9..9);
+        assert_eq!(files[1].old_path, None);
+        assert_eq!(files[1].new_path.as_deref(), Some("new.py"));
+        assert_eq!(files[1].hunks[0].new_range(), 1..2);
+    }
+
+    #[test]
+    fn diff_old_source() {
+        let files = parse_diff(DIFF);
+        let new = "def foo(a):\n    if a:\n        return 1\n    return 0\n\nb = 1\nc = 2\n\ndef bar():\n";
+        assert_eq!(
+            String::from_utf8(files[0].old_source(new.as_bytes())).unwrap(),
+            "def foo(a):\n    return a\n\nb = 1\nc = 2\n\ndef bar():\n    pass\n"
+        );
+        assert!(files[1].old_source(b"x = 1\n").is_empty());
+
+        let files =
+            parse_diff("--- a/foo\n+++ b/foo\n@@ -1 +1 @@\n-a\n\\ No newline at end of file\n+b\n");
+        assert_eq!(files[0].old_source(b"b\n"), b"a");
+    }
+}
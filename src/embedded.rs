@@ -0,0 +1,356 @@
+use std::collections::HashMap;
+use std::path::Path;
+use std::sync::OnceLock;
+
+use regex::bytes::Regex;
+
+use crate::langs::{LANG, get_function_spaces_with_options};
+use crate::metadata::FileMetadata;
+use crate::spaces::{FuncSpace, MetricsOptions, set_ids};
+
+static SCRIPT: OnceLock<Regex> = OnceLock::new();
+static ATTRIBUTE: OnceLock<Regex> = OnceLock::new();
+static TEMPLATE: OnceLock<Regex> = OnceLock::new();
+static SECTION: OnceLock<Regex> = OnceLock::new();
+
+// Extensions of the HTML pages and of the templates producing them
+const HOST_EXTENSIONS: &[&str] = &[
+    "htm",
+    "html",
+    "xhtml",
+    "ejs",
+    "erb",
+    "hbs",
+    "handlebars",
+    "j2",
+    "jinja",
+    "jinja2",
+    "liquid",
+    "mustache",
+    "njk",
+    "twig",
+];
+
+// Extensions of the single-file components, whose templates are reported
+const COMPONENT_EXTENSIONS: &[&str] = &["vue", "svelte"];
+
+// The name of the spaces of the blocks
+const SCRIPT_NAME: &str = "<script>";
+
+/// A block of code embedded in a host file,
+/// such as a `<script>` element of an HTML page.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct EmbeddedBlock {
+    /// The language of the code
+    pub language: LANG,
+    /// The line of the host file where the block starts
+    pub start_line: usize,
+    /// The line of the host file where the block ends
+    pub end_line: usize,
+    /// The code of the host file where everything out of the block
+    /// is blanked, so that the positions in the code of the block
+    /// are the ones in the host file
+    pub code: Vec<u8>,
+}
+
+/// Checks whether a file may embed some code, such as an HTML page
+/// or a template, from its extension.
+pub fn is_embedding_host(path: &Path) -> bool {
+    path.extension()
+        .and_then(|ext| ext.to_str())
+        .is_some_and(|ext| HOST_EXTENSIONS.contains(&ext.to_lowercase().as_str()))
+}
+
+/// Checks whether a file is a Vue or a Svelte single-file component
+/// from its extension.
+pub fn is_component(path: &Path) -> bool {
+    path.extension()
+        .and_then(|ext| ext.to_str())
+        .is_some_and(|ext| COMPONENT_EXTENSIONS.contains(&ext.to_lowercase().as_str()))
+}
+
+// Returns the number of non-blank lines of the template of a component:
+// the `<template>` section of a Vue component, and the markup out of
+// the `<script>` and `<style>` sections of a Svelte one
+fn template_lines(code: &[u8], path: &Path) -> usize {
+    let vue = path
+        .extension()
+        .is_some_and(|ext| ext.eq_ignore_ascii_case("vue"));
+    let template = if vue {
+        let template = TEMPLATE
+            .get_or_init(|| Regex::new(r"(?is)<template\b[^>]*>(.*)</template\s*>").unwrap());
+        template
+            .captures(code)
+            .and_then(|captures| captures.get(1))
+            .map_or(Vec::new(), |body| body.as_bytes().to_vec())
+    } else {
+        let section = SECTION.get_or_init(|| {
+            Regex::new(r"(?is)<(script|style)\b[^>]*>.*?</(?:script|style)\s*>").unwrap()
+        });
+        section.replace_all(code, &b""[..]).into_owned()
+    };
+    template
+        .split(|byte| *byte == b'\n')
+        .filter(|line| !line.iter().all(u8::is_ascii_whitespace))
+        .count()
+}
+
+// Returns the language of a script from the values
+// of its `lang` and `type` attributes
+fn script_language(attributes: &[u8]) -> Option<LANG> {
+    let attribute = ATTRIBUTE.get_or_init(|| {
+        Regex::new(r#"(?i)\b(lang|type)\s*=\s*(?:"([^"]*)"|'([^']*)'|([^\s"'>]+))"#).unwrap()
+    });
+    let mut values = HashMap::new();
+    for captures in attribute.captures_iter(attributes) {
+        let value = captures
+            .get(2)
+            .or_else(|| captures.get(3))
+            .or_else(|| captures.get(4))
+            .map_or(&b""[..], |value| value.as_bytes());
+        values.insert(
+            captures[1].to_ascii_lowercase(),
+            String::from_utf8_lossy(value).trim().to_lowercase(),
+        );
+    }
+
+    let name = match (values.get(&b"lang"[..]), values.get(&b"type"[..])) {
+        (Some(lang), _) => lang.as_str(),
+        (None, Some(kind)) => kind
+            .strip_prefix("text/")
+            .or_else(|| kind.strip_prefix("application/"))
+            .unwrap_or(kind),
+        (None, None) => "",
+    };
+    match name {
+        "" | "js" | "jsx" | "javascript" | "ecmascript" | "module" | "babel" => {
+            Some(LANG::Javascript)
+        }
+        "ts" | "typescript" => Some(LANG::Typescript),
+        "tsx" => Some(LANG::Tsx),
+        // The data blocks and the templates are not scripts
+        _ => None,
+    }
+}
+
+/// Extracts the scripts embedded in an HTML page or in a template,
+/// in the order of the code.
+///
+/// The language of each script is given by its `lang` or `type` attribute,
+/// and it is `JavaScript` by default. The data blocks, such as the JSON
+/// ones, the templates and the empty scripts are skipped.
+pub fn extract_scripts(code: &[u8]) -> Vec<EmbeddedBlock> {
+    let script =
+        SCRIPT.get_or_init(|| Regex::new(r"(?is)<script\b([^>]*)>(.*?)</script\s*>").unwrap());
+    let line_of = |offset: usize| code[..offset].iter().filter(|b| **b == b'\n').count() + 1;
+
+    script
+        .captures_iter(code)
+        .filter_map(|captures| {
+            let body = captures.get(2)?;
+            if body.as_bytes().iter().all(u8::is_ascii_whitespace) {
+                return None;
+            }
+            let language = script_language(&captures[1])?;
+            let tag = captures.get(0)?;
+            let code = code
+                .iter()
+                .enumerate()
+                .map(|(i, byte)| {
+                    if body.range().contains(&i) || *byte == b'\n' {
+                        *byte
+                    } else {
+                        b' '
+                    }
+                })
+                .collect();
+            Some(EmbeddedBlock {
+                language,
+                start_line: line_of(tag.start()),
+                end_line: line_of(tag.end()),
+                code,
+            })
+        })
+        .collect()
+}
+
+// Returns the language parsing the code of all the blocks
+fn host_language(blocks: &[EmbeddedBlock]) -> Option<LANG> {
+    let language = blocks.first()?.language;
+    if blocks.iter().all(|block| block.language == language) {
+        Some(language)
+    } else {
+        // TSX parses both the JavaScript and the TypeScript code
+        Some(LANG::Tsx)
+    }
+}
+
+/// Computes the metrics of the scripts embedded in an HTML page
+/// or in a template.
+///
+/// Returns the language used to compute the metrics of the whole file and
+/// its unit space, whose metrics cover all the scripts and whose nested
+/// spaces are the unit spaces of the scripts, named `<script>`.
+/// The lines of all the spaces are the ones of the host file.
+///
+/// The metadata of a Vue or a Svelte component also contain
+/// the number of lines of its template.
+///
+/// Returns `None` if the file does not contain any script.
+///
+/// # Examples
+///
+/// ```
+/// use std::path::Path;
+///
+/// use rust_code_analysis::{LANG, MetricsOptions, get_embedded_spaces};
+///
+/// let page = "<html>\n<script>\nfunction foo() {}\n</script>\n</html>\n";
+///
+/// let (language, space) =
+///     get_embedded_spaces(page.as_bytes(), Path::new("index.html"), &MetricsOptions::default())
+///         .unwrap();
+/// assert_eq!(language, LANG::Javascript);
+/// assert_eq!(space.spaces[0].name.as_deref(), Some("<script>"));
+/// assert_eq!(space.spaces[0].spaces[0].start_line, 3);
+/// ```
+pub fn get_embedded_spaces(
+    code: &[u8],
+    path: &Path,
+    options: &MetricsOptions,
+) -> Option<(LANG, FuncSpace)> {
+    let blocks = extract_scripts(code);
+    let language = host_language(&blocks)?;
+
+    let mut scripts = Vec::with_capacity(blocks.len());
+    let mut host_code = vec![b' '; code.len()];
+    for block in blocks {
+        for (host_byte, byte) in host_code.iter_mut().zip(&block.code) {
+            if *byte != b' ' {
+                *host_byte = *byte;
+            }
+        }
+        let mut script =
+            get_function_spaces_with_options(&block.language, block.code, path, None, options)?;
+        script.name = Some(SCRIPT_NAME.to_owned());
+        script.start_line = block.start_line;
+        script.end_line = block.end_line;
+        // The file is described by the host space
+        script.parse_quality = None;
+        script.warnings.clear();
+        script.metadata = None;
+        scripts.push(script);
+    }
+
+    let mut space = get_function_spaces_with_options(&language, host_code, path, None, options)?;
+    space.spaces = scripts;
+    space.metadata = Some(FileMetadata {
+        template_lines: is_component(path).then(|| template_lines(code, path)),
+        ..FileMetadata::new(path, code, language)
+    });
+    // The spaces of different scripts may have the same names
+    set_ids(&mut space, &mut HashMap::new());
+
+    Some((language, space))
+}
+
+#[cfg(test)]
+mod tests {
+    use pretty_assertions::assert_eq;
+
+    use super::*;
+
+    #[test]
+    fn embedded_scripts() {
+        let page = "<html>\n<head>\n<script src=\"lib.js\"></script>\n<script type=\"application/json\">{\"a\": 1}</script>\n<SCRIPT type='module'>\nfunction foo(a) {\n  return a || 1;\n}\n</SCRIPT>\n</head>\n<body>\n<script lang=\"ts\">\n  const bar = (b: number): number => b;\n</script>\n</body>\n</html>\n";
+
+        let blocks = extract_scripts(page.as_bytes());
+        let blocks: Vec<_> = blocks
+            .iter()
+            .map(|block| (block.language, block.start_line, block.end_line))
+            .collect();
+        assert_eq!(
+            blocks,
+            [(LANG::Javascript, 5, 9), (LANG::Typescript, 12, 14)]
+        );
+
+        let (language, space) = get_embedded_spaces(
+            page.as_bytes(),
+            Path::new("index.html"),
+            &Default::default(),
+        )
+        .unwrap();
+        assert_eq!(language, LANG::Tsx);
+        assert_eq!(space.name.as_deref(), Some("index.html"));
+        let spaces: Vec<_> = space
+            .spaces
+            .iter()
+            .flat_map(|script| {
+                std::iter::once(script)
+                    .chain(&script.spaces)
+                    .map(|space| (space.name.clone(), space.start_line, space.end_line))
+            })
+            .collect();
+        assert_eq!(
+            spaces,
+            [
+                (Some("<script>".to_owned()), 5, 9),
+                (Some("foo".to_owned()), 6, 8),
+                (Some("<script>".to_owned()), 12, 14),
+                (Some("<anonymous>".to_owned()), 13, 13),
+            ]
+        );
+        // The metrics of the file cover all the scripts
+        assert_eq!(space.metrics.nom.total(), 2.);
+        assert_eq!(space.metrics.cyclomatic.cyclomatic_sum(), 4.);
+        assert_eq!(space.metadata.unwrap().lines, 16);
+        assert_ne!(space.spaces[0].id, space.spaces[1].id);
+
+        assert!(
+            get_embedded_spaces(b"<p>Hello</p>", Path::new("a.html"), &Default::default())
+                .is_none()
+        );
+        assert!(is_embedding_host(Path::new("page.HTML")));
+        assert!(!is_embedding_host(Path::new("main.js")));
+    }
+
+    #[test]
+    fn embedded_components() {
+        let vue = "<template>\n  <div>\n\n    <p>{{ count }}</p>\n  </div>\n</template>\n\n<script setup lang=\"ts\">\nfunction increment(n: number): number {\n  return n > 0 ? n + 1 : 1;\n}\n</script>\n\n<style scoped>\np { color: red; }\n</style>\n";
+
+        let (language, space) = get_embedded_spaces(
+            vue.as_bytes(),
+            Path::new("Counter.vue"),
+            &Default::default(),
+        )
+        .unwrap();
+        assert_eq!(language, LANG::Typescript);
+        assert_eq!(space.spaces[0].start_line, 8);
+        assert_eq!(space.spaces[0].spaces[0].name.as_deref(), Some("increment"));
+        assert_eq!(space.metrics.cyclomatic.cyclomatic_sum(), 3.);
+        assert_eq!(space.metadata.unwrap().template_lines, Some(3));
+
+        let svelte = "<script>\n  export let name;\n  function greet() {\n    alert(name);\n  }\n</script>\n\n<h1>Hello {name}!</h1>\n<button on:click={greet}>Greet</button>\n\n<style>\n  h1 { color: red; }\n</style>\n";
+
+        let (language, space) = get_embedded_spaces(
+            svelte.as_bytes(),
+            Path::new("Hello.svelte"),
+            &Default::default(),
+        )
+        .unwrap();
+        assert_eq!(language, LANG::Javascript);
+        assert_eq!(space.spaces[0].spaces[0].name.as_deref(), Some("greet"));
+        assert_eq!(space.metadata.unwrap().template_lines, Some(2));
+
+        // The pages are not components
+        let (_, space) = get_embedded_spaces(
+            b"<script>\nvar a = 1;\n</script>\n",
+            Path::new("index.html"),
+            &Default::default(),
+        )
+        .unwrap();
+        assert_eq!(space.metadata.unwrap().template_lines, None);
+        assert!(is_component(Path::new("App.Vue")));
+        assert!(!is_component(Path::new("index.html")));
+    }
+}
@@ -0,0 +1,418 @@
+use std::fmt;
+
+use serde::Serialize;
+
+/// The encodings of the source files which are transcoded to `UTF-8`
+/// before being parsed.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum Encoding {
+    /// `UTF-8`, with or without a byte order mark
+    Utf8,
+    /// `UTF-16`, little endian
+    Utf16Le,
+    /// `UTF-16`, big endian
+    Utf16Be,
+    /// `ISO-8859-1`, where each byte is a character
+    Latin1,
+}
+
+// The number of bytes at the start of a code used to detect its encoding
+const DETECTION_SIZE: usize = 512;
+
+impl Encoding {
+    /// The names of the encodings.
+    pub const NAMES: &'static [&'static str] = &["utf-8", "utf-16le", "utf-16be", "latin-1"];
+
+    /// Returns the encoding with the given name, ignoring the case.
+    ///
+    /// The usual aliases of the encodings, such as `utf8` or `iso-8859-1`,
+    /// are accepted too.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rust_code_analysis::Encoding;
+    ///
+    /// assert_eq!(Encoding::from_name("ISO-8859-1"), Some(Encoding::Latin1));
+    /// assert_eq!(Encoding::from_name("ebcdic"), None);
+    /// ```
+    pub fn from_name(name: &str) -> Option<Self> {
+        let encoding = match name.to_lowercase().replace('_', "-").as_str() {
+            "utf-8" | "utf8" => Encoding::Utf8,
+            "utf-16le" | "utf16le" | "utf-16" | "utf16" => Encoding::Utf16Le,
+            "utf-16be" | "utf16be" => Encoding::Utf16Be,
+            "latin-1" | "latin1" | "iso-8859-1" | "iso8859-1" => Encoding::Latin1,
+            _ => return None,
+        };
+        Some(encoding)
+    }
+
+    /// Returns the name of the encoding.
+    pub fn name(&self) -> &'static str {
+        match self {
+            Encoding::Utf8 => "utf-8",
+            Encoding::Utf16Le => "utf-16le",
+            Encoding::Utf16Be => "utf-16be",
+            Encoding::Latin1 => "latin-1",
+        }
+    }
+
+    /// Transcodes a code in this encoding to `UTF-8`,
+    /// removing its byte order mark.
+    ///
+    /// The invalid sequences of a `UTF-16` code are replaced
+    /// by `U+FFFD`, while a `UTF-8` code is returned as is.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rust_code_analysis::Encoding;
+    ///
+    /// let code = b"\xFF\xFEa\x00 \x00=\x00 \x001\x00";
+    ///
+    /// assert_eq!(Encoding::Utf16Le.decode(code), b"a = 1".to_vec());
+    /// ```
+    pub fn decode(&self, data: &[u8]) -> Vec<u8> {
+        match self {
+            Encoding::Utf8 => data.strip_prefix(b"\xEF\xBB\xBF").unwrap_or(data).to_vec(),
+            Encoding::Utf16Le => decode_utf16(
+                data.strip_prefix(b"\xFF\xFE").unwrap_or(data),
+                u16::from_le_bytes,
+            ),
+            Encoding::Utf16Be => decode_utf16(
+                data.strip_prefix(b"\xFE\xFF").unwrap_or(data),
+                u16::from_be_bytes,
+            ),
+            Encoding::Latin1 => data
+                .iter()
+                .map(|byte| char::from(*byte))
+                .collect::<String>()
+                .into_bytes(),
+        }
+    }
+}
+
+impl fmt::Display for Encoding {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.name())
+    }
+}
+
+/// The line endings of a code.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum LineEnding {
+    /// The code has a single line
+    #[default]
+    None,
+    /// `LF`, as on Unix
+    Lf,
+    /// `CR LF`, as on Windows
+    Crlf,
+    /// A lone `CR`, as on the classic Mac OS
+    Cr,
+    /// More than one kind of line endings
+    Mixed,
+}
+
+impl LineEnding {
+    /// The names of the line endings.
+    pub const NAMES: &'static [&'static str] = &["none", "lf", "crlf", "cr", "mixed"];
+
+    /// Returns the name of the line ending.
+    pub fn name(&self) -> &'static str {
+        match self {
+            LineEnding::None => "none",
+            LineEnding::Lf => "lf",
+            LineEnding::Crlf => "crlf",
+            LineEnding::Cr => "cr",
+            LineEnding::Mixed => "mixed",
+        }
+    }
+
+    /// Detects the line endings of a code.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rust_code_analysis::LineEnding;
+    ///
+    /// assert_eq!(LineEnding::detect(b"a = 1\r\nb = 2\r\n"), LineEnding::Crlf);
+    /// assert_eq!(LineEnding::detect(b"a = 1\r\nb = 2\n"), LineEnding::Mixed);
+    /// ```
+    pub fn detect(code: &[u8]) -> Self {
+        let mut ending = LineEnding::None;
+        let mut bytes = code.iter().peekable();
+        while let Some(byte) = bytes.next() {
+            let next = match byte {
+                b'\n' => LineEnding::Lf,
+                b'\r' if bytes.next_if_eq(&&b'\n').is_some() => LineEnding::Crlf,
+                b'\r' => LineEnding::Cr,
+                _ => continue,
+            };
+            if ending == LineEnding::None {
+                ending = next;
+            } else if ending != next {
+                return LineEnding::Mixed;
+            }
+        }
+        ending
+    }
+}
+
+impl fmt::Display for LineEnding {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.name())
+    }
+}
+
+/// Prepares a `UTF-8` code for its parsing, removing its byte order mark
+/// and replacing each lone `CR` with a `LF`.
+///
+/// The grammars only end the lines with `LF`, which also ends the `CR LF`
+/// line endings, so the lines of a code ended by lone `CR`s would be a
+/// single line. The replacement keeps the offsets of the bytes of the code.
+///
+/// # Examples
+///
+/// ```
+/// use rust_code_analysis::normalize_line_endings;
+///
+/// let code = b"\xEF\xBB\xBFa = 1\rb = 2\r\n".to_vec();
+///
+/// assert_eq!(normalize_line_endings(code), b"a = 1\nb = 2\r\n".to_vec());
+/// ```
+pub fn normalize_line_endings(mut code: Vec<u8>) -> Vec<u8> {
+    if code.starts_with(b"\xEF\xBB\xBF") {
+        code.drain(..3);
+    }
+    for i in 0..code.len() {
+        if code[i] == b'\r' && code.get(i + 1) != Some(&b'\n') {
+            code[i] = b'\n';
+        }
+    }
+    code
+}
+
+fn decode_utf16(data: &[u8], to_unit: fn([u8; 2]) -> u16) -> Vec<u8> {
+    let units = data.chunks_exact(2).map(|pair| to_unit([pair[0], pair[1]]));
+    char::decode_utf16(units)
+        .map(|c| c.unwrap_or(char::REPLACEMENT_CHARACTER))
+        .collect::<String>()
+        .into_bytes()
+}
+
+// Detects a UTF-16 code without a byte order mark from the NUL bytes
+// of its ASCII characters, which are either the odd or the even bytes
+fn detect_utf16(head: &[u8]) -> Option<Encoding> {
+    let pairs = head.len() / 2;
+    if pairs < 2 {
+        return None;
+    }
+    let (mut even, mut odd) = (0, 0);
+    for pair in head.chunks_exact(2) {
+        match pair {
+            [0, b] if *b != 0 => even += 1,
+            [b, 0] if *b != 0 => odd += 1,
+            _ => {}
+        }
+    }
+    // Most of the characters of a code are ASCII
+    if odd * 10 >= pairs * 7 && even == 0 {
+        Some(Encoding::Utf16Le)
+    } else if even * 10 >= pairs * 7 && odd == 0 {
+        Some(Encoding::Utf16Be)
+    } else {
+        None
+    }
+}
+
+// Checks whether a byte is a control character which is not used in a text
+fn is_binary_byte(byte: u8) -> bool {
+    byte < 0x20 && !matches!(byte, b'\t' | b'\n' | b'\r' | b'\x0C' | b'\x1B')
+}
+
+/// Detects the encoding of a code.
+///
+/// The encoding is detected from the byte order mark of the code, if any.
+/// Otherwise a code whose ASCII characters are interleaved with `NUL` bytes
+/// is `UTF-16`, a valid `UTF-8` code, or a code containing valid non-ASCII
+/// `UTF-8` characters, is `UTF-8`, and any other text is `Latin-1`.
+///
+/// When the code is the start of a larger code, a `UTF-8` character
+/// truncated at its end does not prevent its detection.
+///
+/// Returns `None` if the code is not a text, such as a binary file.
+///
+/// # Examples
+///
+/// ```
+/// use rust_code_analysis::{Encoding, detect_encoding};
+///
+/// assert_eq!(detect_encoding("a = 'é'".as_bytes()), Some(Encoding::Utf8));
+/// assert_eq!(detect_encoding(b"a = '\xE9'"), Some(Encoding::Latin1));
+/// assert_eq!(detect_encoding(b"\x7FELF\x02\x01\x01\x00"), None);
+/// ```
+pub fn detect_encoding(data: &[u8]) -> Option<Encoding> {
+    if data.starts_with(b"\xEF\xBB\xBF") {
+        return Some(Encoding::Utf8);
+    } else if data.starts_with(b"\xFF\xFE") {
+        return Some(Encoding::Utf16Le);
+    } else if data.starts_with(b"\xFE\xFF") {
+        return Some(Encoding::Utf16Be);
+    }
+
+    let head = &data[..DETECTION_SIZE.min(data.len())];
+    if let Some(encoding) = detect_utf16(head) {
+        return Some(encoding);
+    }
+    if head.iter().copied().any(is_binary_byte) {
+        return None;
+    }
+
+    match std::str::from_utf8(data) {
+        Ok(_) => Some(Encoding::Utf8),
+        // A character truncated at the end of the code
+        Err(e) if e.error_len().is_none() => Some(Encoding::Utf8),
+        Err(_) => {
+            // A UTF-8 code with a few invalid bytes is still UTF-8
+            let utf8 = data.utf8_chunks().any(|chunk| !chunk.valid().is_ascii());
+            Some(if utf8 {
+                Encoding::Utf8
+            } else {
+                Encoding::Latin1
+            })
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn utf16(code: &str, to_bytes: fn(u16) -> [u8; 2]) -> Vec<u8> {
+        code.encode_utf16().flat_map(to_bytes).collect()
+    }
+
+    #[test]
+    fn encoding_names() {
+        for name in Encoding::NAMES {
+            assert_eq!(Encoding::from_name(name).unwrap().name(), *name);
+        }
+        assert_eq!(Encoding::from_name("UTF_8"), Some(Encoding::Utf8));
+    }
+
+    #[test]
+    fn detect_encodings() {
+        let code = "def foo():\n    return 'àé'\n";
+
+        assert_eq!(detect_encoding(code.as_bytes()), Some(Encoding::Utf8));
+        assert_eq!(
+            detect_encoding(&utf16(code, u16::to_le_bytes)),
+            Some(Encoding::Utf16Le)
+        );
+        assert_eq!(
+            detect_encoding(&utf16(code, u16::to_be_bytes)),
+            Some(Encoding::Utf16Be)
+        );
+        assert_eq!(
+            detect_encoding(b"def foo():\n    return '\xE0\xE9'\n"),
+            Some(Encoding::Latin1)
+        );
+        // The byte order marks
+        assert_eq!(detect_encoding(b"\xFF\xFEa\x00"), Some(Encoding::Utf16Le));
+        assert_eq!(detect_encoding(b"\xFE\xFF\x00a"), Some(Encoding::Utf16Be));
+        // A character truncated by the end of the head of a code
+        assert_eq!(
+            detect_encoding(&"a = 'é'".as_bytes()[..6]),
+            Some(Encoding::Utf8)
+        );
+        // A UTF-8 code with an invalid byte
+        assert_eq!(
+            detect_encoding(b"a = '\xC3\xA9'\nb = '\xFF'\n"),
+            Some(Encoding::Utf8)
+        );
+        assert_eq!(detect_encoding(b"\x00\x00\x01\x02\x03binary"), None);
+    }
+
+    #[test]
+    fn decode_encodings() {
+        let code = "def foo():\n    return 'àé'\n";
+
+        assert_eq!(Encoding::Utf8.decode(code.as_bytes()), code.as_bytes());
+        assert_eq!(
+            Encoding::Utf16Le.decode(&utf16(&format!("\u{FEFF}{code}"), u16::to_le_bytes)),
+            code.as_bytes()
+        );
+        assert_eq!(
+            Encoding::Utf16Be.decode(&utf16(code, u16::to_be_bytes)),
+            code.as_bytes()
+        );
+        assert_eq!(
+            Encoding::Latin1.decode(b"def foo():\n    return '\xE0\xE9'\n"),
+            code.as_bytes()
+        );
+    }
+
+    #[test]
+    fn line_endings() {
+        let endings = [
+            LineEnding::None,
+            LineEnding::Lf,
+            LineEnding::Crlf,
+            LineEnding::Cr,
+            LineEnding::Mixed,
+        ];
+        assert_eq!(endings.map(|ending| ending.name()), LineEnding::NAMES);
+        assert_eq!(LineEnding::detect(b"a = 1"), LineEnding::None);
+        assert_eq!(LineEnding::detect(b"a = 1\nb = 2\n"), LineEnding::Lf);
+        assert_eq!(LineEnding::detect(b"a = 1\r\nb = 2"), LineEnding::Crlf);
+        assert_eq!(LineEnding::detect(b"a = 1\rb = 2\r"), LineEnding::Cr);
+        assert_eq!(LineEnding::detect(b"a = 1\rb = 2\n"), LineEnding::Mixed);
+        assert_eq!(LineEnding::Crlf.to_string(), "crlf");
+    }
+
+    #[test]
+    fn line_endings_metrics() {
+        let code = "// foo\nfn foo() {\n    let a = 1;\n\n    /* bar\n       baz */\n    a\n}\n";
+        let analyze = |code: Vec<u8>| {
+            let space = crate::get_function_spaces(
+                &crate::LANG::Rust,
+                code,
+                std::path::Path::new("foo.rs"),
+                None,
+            )
+            .unwrap();
+            let loc = &space.metrics.loc;
+            let function = &space.spaces[0];
+            let metadata = space.metadata.unwrap();
+            (
+                (loc.sloc(), loc.ploc(), loc.cloc(), loc.blank()),
+                (function.start_line, function.end_line),
+                (metadata.lines, metadata.line_ending),
+            )
+        };
+
+        let (lf_loc, lf_lines, lf_metadata) = analyze(code.as_bytes().to_vec());
+        assert_eq!(lf_metadata, (8, LineEnding::Lf));
+        let endings = ["\r\n", "\r", "\n"];
+        let mixed: String = code
+            .split_inclusive('\n')
+            .zip(endings.iter().cycle())
+            .map(|(line, ending)| format!("{}{ending}", line.trim_end_matches('\n')))
+            .collect();
+        for (code, line_ending) in [
+            (code.replace('\n', "\r\n"), LineEnding::Crlf),
+            (code.replace('\n', "\r"), LineEnding::Cr),
+            (mixed, LineEnding::Mixed),
+        ] {
+            let with_bom = [b"\xEF\xBB\xBF", code.as_bytes()].concat();
+            for code in [code.into_bytes(), with_bom] {
+                let (loc, lines, metadata) = analyze(code);
+                assert_eq!(loc, lf_loc);
+                assert_eq!(lines, lf_lines);
+                assert_eq!(metadata, (8, line_ending));
+            }
+        }
+    }
+}
@@ -0,0 +1,197 @@
+use std::fmt;
+use std::path::Path;
+use std::sync::OnceLock;
+
+use aho_corasick::AhoCorasick;
+use serde::Serialize;
+
+static MARKERS: OnceLock<AhoCorasick> = OnceLock::new();
+
+// Markers used by code generators to flag their output
+const GENERATED_MARKERS: &[&str] = &[
+    "@generated",
+    "DO NOT EDIT",
+    "<auto-generated",
+    "autogenerated file",
+    "auto-generated file",
+];
+
+// Comment appended by bundlers and minifiers
+const SOURCEMAP_MARKER: &[u8] = b"sourceMappingURL=";
+
+// File names of the most common dependency lockfiles
+const LOCKFILES: &[&str] = &[
+    "Cargo.lock",
+    "package-lock.json",
+    "npm-shrinkwrap.json",
+    "yarn.lock",
+    "pnpm-lock.yaml",
+    "poetry.lock",
+    "Pipfile.lock",
+    "Gemfile.lock",
+    "composer.lock",
+    "go.sum",
+];
+
+// Number of lines at the beginning of a file where a marker is searched
+const HEADER_LINES: usize = 10;
+
+// Average line length above which a file is considered as minified
+const MAX_AVERAGE_LINE_LENGTH: usize = 200;
+
+/// The kinds of machine-produced files.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum GeneratedKind {
+    /// A minified file
+    Minified,
+    /// A file produced by a code generator
+    Generated,
+    /// A dependency lockfile
+    Lockfile,
+}
+
+impl fmt::Display for GeneratedKind {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let s = match self {
+            GeneratedKind::Minified => "minified",
+            GeneratedKind::Generated => "generated",
+            GeneratedKind::Lockfile => "lockfile",
+        };
+        write!(f, "{s}")
+    }
+}
+
+fn is_lockfile(path: &Path) -> bool {
+    path.file_name()
+        .and_then(|name| name.to_str())
+        .is_some_and(|name| LOCKFILES.contains(&name))
+}
+
+fn has_min_name(path: &Path) -> bool {
+    path.file_stem()
+        .and_then(|stem| stem.to_str())
+        .is_some_and(|stem| stem.ends_with(".min") || stem.ends_with("-min"))
+}
+
+fn has_long_lines(buf: &[u8]) -> bool {
+    let (lines, length) = buf
+        .split(|c| *c == b'\n')
+        .filter(|line| !line.iter().all(u8::is_ascii_whitespace))
+        .fold((0, 0), |(lines, length), line| {
+            (lines + 1, length + line.len())
+        });
+
+    lines != 0 && length / lines > MAX_AVERAGE_LINE_LENGTH
+}
+
+fn has_generated_marker(buf: &[u8]) -> bool {
+    let markers = MARKERS.get_or_init(|| AhoCorasick::new(GENERATED_MARKERS).unwrap());
+    buf.split(|c| *c == b'\n')
+        .take(HEADER_LINES)
+        .any(|line| markers.is_match(line))
+}
+
+fn has_sourcemap(buf: &[u8]) -> bool {
+    buf.rsplit(|c| *c == b'\n')
+        .find(|line| !line.iter().all(u8::is_ascii_whitespace))
+        .is_some_and(|line| {
+            line.windows(SOURCEMAP_MARKER.len())
+                .any(|w| w == SOURCEMAP_MARKER)
+        })
+}
+
+/// Detects whether a code has been produced by a tool rather than
+/// written by a human.
+///
+/// The detection relies on a series of heuristics: the name of the file,
+/// the average length of its lines, the presence of a sourcemap comment at
+/// its end and of a generator marker, such as `@generated`
+/// or `DO NOT EDIT`, in its first lines.
+///
+/// Returns `None` when the code does not look machine-produced.
+///
+/// # Examples
+///
+/// ```
+/// use rust_code_analysis::{GeneratedKind, classify_generated};
+///
+/// let source_code = "// @generated by protoc\nint a = 42;\n";
+///
+/// assert_eq!(
+///     classify_generated(source_code.as_bytes(), "foo.c"),
+///     Some(GeneratedKind::Generated)
+/// );
+/// ```
+pub fn classify_generated<P: AsRef<Path>>(buf: &[u8], path: P) -> Option<GeneratedKind> {
+    let path = path.as_ref();
+    if is_lockfile(path) {
+        Some(GeneratedKind::Lockfile)
+    } else if has_min_name(path) || has_long_lines(buf) {
+        Some(GeneratedKind::Minified)
+    } else if has_generated_marker(buf) || has_sourcemap(buf) {
+        Some(GeneratedKind::Generated)
+    } else {
+        None
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use pretty_assertions::assert_eq;
+
+    use super::*;
+
+    #[test]
+    fn test_classify_lockfile() {
+        assert_eq!(
+            classify_generated(b"[[package]]\n", "foo/Cargo.lock"),
+            Some(GeneratedKind::Lockfile)
+        );
+        assert_eq!(
+            classify_generated(b"{}\n", "package-lock.json"),
+            Some(GeneratedKind::Lockfile)
+        );
+    }
+
+    #[test]
+    fn test_classify_minified() {
+        assert_eq!(
+            classify_generated(b"var a = 42;\n", "foo.min.js"),
+            Some(GeneratedKind::Minified)
+        );
+
+        let line = "a=1;".repeat(100);
+        let buf = format!("{line}\n{line}\n");
+        assert_eq!(
+            classify_generated(buf.as_bytes(), "foo.js"),
+            Some(GeneratedKind::Minified)
+        );
+    }
+
+    #[test]
+    fn test_classify_generated() {
+        let buf = b"// Code generated by protoc-gen-go. DO NOT EDIT.\nint a = 42;\n";
+        assert_eq!(
+            classify_generated(buf, "foo.c"),
+            Some(GeneratedKind::Generated)
+        );
+
+        let buf = b"var a = 42;\n//# sourceMappingURL=foo.js.map\n";
+        assert_eq!(
+            classify_generated(buf, "foo.js"),
+            Some(GeneratedKind::Generated)
+        );
+
+        // A marker after the header is not taken into account
+        let buf = format!("{}// @generated\n", "int a = 42;\n".repeat(20));
+        assert_eq!(classify_generated(buf.as_bytes(), "foo.c"), None);
+    }
+
+    #[test]
+    fn test_classify_handwritten() {
+        let buf = b"def foo():\n    return 42\n\n\nfoo()\n";
+        assert_eq!(classify_generated(buf, "foo.py"), None);
+        assert_eq!(classify_generated(b"", "foo.py"), None);
+    }
+}
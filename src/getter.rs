@@ -1,6 +1,6 @@
 use crate::metrics::halstead::HalsteadType;
 
-use crate::spaces::SpaceKind;
+use crate::spaces::{FunctionFlags, SpaceKind, SpaceVisibility};
 use crate::traits::Search;
 
 use crate::*;
@@ -20,6 +20,146 @@ macro_rules! get_operator {
     };
 }
 
+macro_rules! js_function_flags {
+    ($language:ident) => {
+        fn get_function_flags(node: &Node, code: &[u8]) -> FunctionFlags {
+            use $language::*;
+
+            if !matches!(
+                node.kind_id().into(),
+                FunctionExpression
+                    | MethodDefinition
+                    | GeneratorFunction
+                    | FunctionDeclaration
+                    | GeneratorFunctionDeclaration
+                    | ArrowFunction
+            ) {
+                return FunctionFlags::default();
+            }
+
+            // The callbacks of `it` and `test` are the tests of Jest and Mocha
+            let is_test = node
+                .parent()
+                .filter(|parent| parent.kind_id() == Arguments)
+                .and_then(|arguments| arguments.parent())
+                .and_then(|call| call.child_by_field_name("function"))
+                .is_some_and(|function| matches!(text(&function, code), "it" | "test"));
+
+            FunctionFlags {
+                is_async: node.is_child(Async as u16),
+                is_generator: matches!(
+                    node.kind_id().into(),
+                    GeneratorFunction | GeneratorFunctionDeclaration
+                ) || node.is_child(STAR as u16),
+                is_constructor: node.kind_id() == MethodDefinition
+                    && node
+                        .child_by_field_name("name")
+                        .is_some_and(|name| text(&name, code) == "constructor"),
+                is_static: node.is_child(Static as u16),
+                is_test,
+                is_getter: node.kind_id() == MethodDefinition && node.is_child(Get as u16),
+                is_setter: node.kind_id() == MethodDefinition && node.is_child(Set as u16),
+            }
+        }
+    };
+}
+
+macro_rules! js_decorators {
+    ($language:ident) => {
+        fn get_decorators<'a>(node: &Node, code: &'a [u8]) -> Vec<&'a str> {
+            use $language::*;
+
+            // The decorators of the class members can precede them
+            // in the class body
+            let mut decorators = Vec::new();
+            let mut sibling = node.previous_sibling();
+            while let Some(decorator) = sibling.filter(|sibling| sibling.kind_id() == Decorator) {
+                decorators.push(decorator_name(text(&decorator, code)));
+                sibling = decorator.previous_sibling();
+            }
+            decorators.reverse();
+            decorators.extend(
+                node.children()
+                    .filter(|child| child.kind_id() == Decorator)
+                    .map(|decorator| decorator_name(text(&decorator, code))),
+            );
+            decorators
+        }
+    };
+}
+
+macro_rules! js_space_visibility {
+    ($language:ident) => {
+        fn get_space_visibility(node: &Node, code: &[u8]) -> Option<SpaceVisibility> {
+            use $language::*;
+
+            match node.kind_id().into() {
+                // The class members are public unless they have a `#` name
+                // or a `TypeScript` accessibility modifier
+                MethodDefinition => {
+                    if let Some(modifier) = node
+                        .children()
+                        .find(|child| child.kind() == "accessibility_modifier")
+                    {
+                        return Some(match text(&modifier, code) {
+                            "private" => SpaceVisibility::Private,
+                            "protected" => SpaceVisibility::Protected,
+                            _ => SpaceVisibility::Public,
+                        });
+                    }
+                    let is_private = node
+                        .child_by_field_name("name")
+                        .is_some_and(|name| text(&name, code).starts_with('#'));
+                    Some(if is_private {
+                        SpaceVisibility::Private
+                    } else {
+                        SpaceVisibility::Public
+                    })
+                }
+                // The declarations of a module are visible outside of it
+                // only when they are exported
+                FunctionDeclaration | GeneratorFunctionDeclaration | ClassDeclaration => {
+                    match node.parent().map(|parent| parent.kind_id().into()) {
+                        Some(ExportStatement) => Some(SpaceVisibility::Public),
+                        Some(Program) => Some(SpaceVisibility::ModulePrivate),
+                        _ => None,
+                    }
+                }
+                _ => None,
+            }
+        }
+    };
+}
+
+fn text<'a>(node: &Node, code: &'a [u8]) -> &'a str {
+    std::str::from_utf8(&code[node.start_byte()..node.end_byte()]).unwrap_or_default()
+}
+
+// Returns the name of a decorator or of an annotation without its `@`
+// and its arguments, such as `app.route` for `@app.route("/")`
+fn decorator_name(decorator: &str) -> &str {
+    let decorator = decorator.trim_start_matches('@');
+    decorator
+        .find('(')
+        .map_or(decorator, |end| &decorator[..end])
+        .trim()
+}
+
+// Checks if a node contains a node of the given kind, without looking
+// inside the nodes of the `skip` kinds, such as the nested functions
+fn contains_kind(node: &Node, kind: u16, skip: &[u16]) -> bool {
+    let mut stack: Vec<_> = node.children().collect();
+    while let Some(node) = stack.pop() {
+        if node.kind_id() == kind {
+            return true;
+        }
+        if !skip.contains(&node.kind_id()) {
+            stack.extend(node.children());
+        }
+    }
+    false
+}
+
 pub trait Getter {
     fn get_func_name<'a>(node: &Node, code: &'a [u8]) -> Option<&'a str> {
         Self::get_func_space_name(node, code)
@@ -39,6 +179,18 @@ pub trait Getter {
         SpaceKind::Unknown
     }
 
+    fn get_function_flags(_node: &Node, _code: &[u8]) -> FunctionFlags {
+        FunctionFlags::default()
+    }
+
+    fn get_decorators<'a>(_node: &Node, _code: &'a [u8]) -> Vec<&'a str> {
+        Vec::new()
+    }
+
+    fn get_space_visibility(_node: &Node, _code: &[u8]) -> Option<SpaceVisibility> {
+        None
+    }
+
     fn get_op_type(_node: &Node) -> HalsteadType {
         HalsteadType::Unknown
     }
@@ -49,9 +201,33 @@ pub trait Getter {
 }
 
 impl Getter for PythonCode {
+    fn get_func_space_name<'a>(node: &Node, code: &'a [u8]) -> Option<&'a str> {
+        // Closures are named as in the Python tracebacks
+        match node.kind_id().into() {
+            Python::Lambda => Some("<lambda>"),
+            Python::ListComprehension => Some("<listcomp>"),
+            Python::SetComprehension => Some("<setcomp>"),
+            Python::DictionaryComprehension => Some("<dictcomp>"),
+            Python::GeneratorExpression => Some("<genexpr>"),
+            _ => {
+                if let Some(name) = node.child_by_field_name("name") {
+                    let code = &code[name.start_byte()..name.end_byte()];
+                    std::str::from_utf8(code).ok()
+                } else {
+                    Some("<anonymous>")
+                }
+            }
+        }
+    }
+
     fn get_space_kind(node: &Node) -> SpaceKind {
         match node.kind_id().into() {
-            Python::FunctionDefinition => SpaceKind::Function,
+            Python::FunctionDefinition
+            | Python::Lambda
+            | Python::ListComprehension
+            | Python::SetComprehension
+            | Python::DictionaryComprehension
+            | Python::GeneratorExpression => SpaceKind::Function,
             Python::ClassDefinition => SpaceKind::Class,
             Python::Module => SpaceKind::Unit,
             _ => SpaceKind::Unknown,
@@ -89,6 +265,101 @@ impl Getter for PythonCode {
     fn get_operator_id_as_str(id: u16) -> &'static str {
         Into::<Python>::into(id).into()
     }
+
+    fn get_function_flags(node: &Node, code: &[u8]) -> FunctionFlags {
+        if node.kind_id() != Python::FunctionDefinition {
+            return FunctionFlags::default();
+        }
+
+        let name = Self::get_func_space_name(node, code).unwrap_or_default();
+        let decorated = node
+            .parent()
+            .filter(|parent| parent.kind_id() == Python::DecoratedDefinition);
+        let in_class = decorated
+            .unwrap_or(*node)
+            .get_parent(2)
+            .is_some_and(|parent| parent.kind_id() == Python::ClassDefinition);
+        let decorators = Self::get_decorators(node, code);
+
+        FunctionFlags {
+            is_async: node.is_child(Python::Async as u16),
+            // The yields of the nested functions and classes are not counted
+            is_generator: node.child_by_field_name("body").is_some_and(|body| {
+                contains_kind(
+                    &body,
+                    Python::Yield as u16,
+                    &[
+                        Python::FunctionDefinition as u16,
+                        Python::ClassDefinition as u16,
+                        Python::Lambda as u16,
+                    ],
+                )
+            }),
+            is_constructor: in_class && matches!(name, "__init__" | "__new__"),
+            is_static: decorated.is_some_and(|decorated| {
+                decorated.children().any(|decorator| {
+                    decorator.kind_id() == Python::Decorator
+                        && text(&decorator, code) == "@staticmethod"
+                })
+            }),
+            is_test: name.starts_with("test"),
+            is_getter: in_class
+                && decorators.iter().any(|decorator| {
+                    matches!(
+                        *decorator,
+                        "property" | "cached_property" | "functools.cached_property"
+                    )
+                }),
+            is_setter: in_class
+                && decorators
+                    .iter()
+                    .any(|decorator| decorator.ends_with(".setter")),
+        }
+    }
+
+    fn get_decorators<'a>(node: &Node, code: &'a [u8]) -> Vec<&'a str> {
+        node.parent()
+            .filter(|parent| parent.kind_id() == Python::DecoratedDefinition)
+            .map(|decorated| {
+                decorated
+                    .children()
+                    .filter(|child| child.kind_id() == Python::Decorator)
+                    .map(|decorator| decorator_name(text(&decorator, code)))
+                    .collect()
+            })
+            .unwrap_or_default()
+    }
+
+    fn get_space_visibility(node: &Node, code: &[u8]) -> Option<SpaceVisibility> {
+        if !matches!(
+            node.kind_id().into(),
+            Python::FunctionDefinition | Python::ClassDefinition
+        ) {
+            return None;
+        }
+
+        // The visibility follows the naming conventions: the names starting
+        // with two underscores are mangled in the classes, while the names
+        // starting with one underscore are internal
+        let name = Self::get_func_space_name(node, code).unwrap_or_default();
+        let in_class = node
+            .parent()
+            .filter(|parent| parent.kind_id() == Python::DecoratedDefinition)
+            .unwrap_or(*node)
+            .get_parent(2)
+            .is_some_and(|parent| parent.kind_id() == Python::ClassDefinition);
+        Some(if name.starts_with("__") && name.ends_with("__") {
+            SpaceVisibility::Public
+        } else if name.starts_with("__") && in_class {
+            SpaceVisibility::Private
+        } else if name.starts_with('_') && in_class {
+            SpaceVisibility::Protected
+        } else if name.starts_with('_') {
+            SpaceVisibility::ModulePrivate
+        } else {
+            SpaceVisibility::Public
+        })
+    }
 }
 
 impl Getter for MozjsCode {
@@ -157,6 +428,12 @@ impl Getter for MozjsCode {
     }
 
     get_operator!(Mozjs);
+
+    js_function_flags!(Mozjs);
+
+    js_decorators!(Mozjs);
+
+    js_space_visibility!(Mozjs);
 }
 
 impl Getter for JavascriptCode {
@@ -225,6 +502,12 @@ impl Getter for JavascriptCode {
     }
 
     get_operator!(Javascript);
+
+    js_function_flags!(Javascript);
+
+    js_decorators!(Javascript);
+
+    js_space_visibility!(Javascript);
 }
 
 impl Getter for TypescriptCode {
@@ -238,8 +521,8 @@ impl Getter for TypescriptCode {
             | FunctionDeclaration
             | GeneratorFunctionDeclaration
             | ArrowFunction => SpaceKind::Function,
-            Class | ClassDeclaration => SpaceKind::Class,
-            InterfaceDeclaration => SpaceKind::Interface,
+            Class | ClassDeclaration | AbstractClassDeclaration => SpaceKind::Class,
+            InterfaceDeclaration | TypeAliasDeclaration => SpaceKind::Interface,
             Program => SpaceKind::Unit,
             _ => SpaceKind::Unknown,
         }
@@ -294,6 +577,12 @@ impl Getter for TypescriptCode {
     }
 
     get_operator!(Typescript);
+
+    js_function_flags!(Typescript);
+
+    js_decorators!(Typescript);
+
+    js_space_visibility!(Typescript);
 }
 
 impl Getter for TsxCode {
@@ -307,8 +596,8 @@ impl Getter for TsxCode {
             | FunctionDeclaration
             | GeneratorFunctionDeclaration
             | ArrowFunction => SpaceKind::Function,
-            Class | ClassDeclaration => SpaceKind::Class,
-            InterfaceDeclaration => SpaceKind::Interface,
+            Class | ClassDeclaration | AbstractClassDeclaration => SpaceKind::Class,
+            InterfaceDeclaration | TypeAliasDeclaration => SpaceKind::Interface,
             Program => SpaceKind::Unit,
             _ => SpaceKind::Unknown,
         }
@@ -363,6 +652,12 @@ impl Getter for TsxCode {
     }
 
     get_operator!(Tsx);
+
+    js_function_flags!(Tsx);
+
+    js_decorators!(Tsx);
+
+    js_space_visibility!(Tsx);
 }
 
 impl Getter for RustCode {
@@ -387,6 +682,7 @@ impl Getter for RustCode {
             FunctionItem | ClosureExpression => SpaceKind::Function,
             TraitItem => SpaceKind::Trait,
             ImplItem => SpaceKind::Impl,
+            MacroDefinition => SpaceKind::Macro,
             SourceFile => SpaceKind::Unit,
             _ => SpaceKind::Unknown,
         }
@@ -427,6 +723,114 @@ impl Getter for RustCode {
     }
 
     get_operator!(Rust);
+
+    fn get_function_flags(node: &Node, code: &[u8]) -> FunctionFlags {
+        use Rust::*;
+
+        if node.kind_id() != FunctionItem {
+            return FunctionFlags::default();
+        }
+
+        // The associated functions have no `self` parameter
+        let container = node
+            .get_parent(2)
+            .filter(|parent| matches!(parent.kind_id().into(), ImplItem | TraitItem));
+        let is_static = container.is_some()
+            && !node
+                .child_by_field_name("parameters")
+                .is_some_and(|parameters| parameters.is_child(SelfParameter as u16));
+        // The constructors are the associated functions returning their type
+        let is_constructor = is_static
+            && node.child_by_field_name("return_type").is_some_and(|ty| {
+                let ty = text(&ty, code);
+                ty == "Self"
+                    || container
+                        .and_then(|container| container.child_by_field_name("type"))
+                        .is_some_and(|name| text(&name, code) == ty)
+            });
+
+        // The tests have a `#[test]` attribute, or one ending with `::test`
+        // such as `#[tokio::test]`
+        let mut is_test = false;
+        let mut sibling = node.previous_sibling();
+        while let Some(item) = sibling.filter(|sibling| sibling.kind_id() == AttributeItem) {
+            is_test |= item
+                .children()
+                .find(|child| child.kind_id() == Attribute)
+                .and_then(|attribute| attribute.child(0))
+                .is_some_and(|path| {
+                    let path = text(&path, code);
+                    path == "test" || path.ends_with("::test")
+                });
+            sibling = item.previous_sibling();
+        }
+
+        FunctionFlags {
+            is_async: node
+                .children()
+                .any(|child| child.kind_id() == FunctionModifiers && child.is_child(Async as u16)),
+            is_generator: false,
+            is_constructor,
+            is_static,
+            is_test,
+            ..FunctionFlags::default()
+        }
+    }
+
+    fn get_decorators<'a>(node: &Node, code: &'a [u8]) -> Vec<&'a str> {
+        use Rust::*;
+
+        // The attributes are the items preceding the node,
+        // named by their path such as `test` or `tokio::main`
+        let mut attributes = Vec::new();
+        let mut sibling = node.previous_sibling();
+        while let Some(item) = sibling.filter(|sibling| sibling.kind_id() == AttributeItem) {
+            if let Some(path) = item
+                .children()
+                .find(|child| child.kind_id() == Attribute)
+                .and_then(|attribute| attribute.child(0))
+            {
+                attributes.push(text(&path, code));
+            }
+            sibling = item.previous_sibling();
+        }
+        attributes.reverse();
+        attributes
+    }
+
+    fn get_space_visibility(node: &Node, code: &[u8]) -> Option<SpaceVisibility> {
+        use Rust::*;
+
+        if !matches!(node.kind_id().into(), FunctionItem | TraitItem) {
+            return None;
+        }
+
+        if let Some(modifier) = node
+            .children()
+            .find(|child| child.kind_id() == VisibilityModifier)
+        {
+            let modifier: String = text(&modifier, code).split_whitespace().collect();
+            return Some(match modifier.as_str() {
+                "pub" => SpaceVisibility::Public,
+                "pub(crate)" => SpaceVisibility::Crate,
+                "pub(self)" => SpaceVisibility::Private,
+                // `pub(super)` and `pub(in path)`
+                _ => SpaceVisibility::ModulePrivate,
+            });
+        }
+
+        // The items of the traits and of the trait implementations
+        // are as visible as their trait
+        let in_trait = node.get_parent(2).is_some_and(|parent| {
+            parent.kind_id() == TraitItem
+                || (parent.kind_id() == ImplItem && parent.child_by_field_name("trait").is_some())
+        });
+        Some(if in_trait {
+            SpaceVisibility::Public
+        } else {
+            SpaceVisibility::Private
+        })
+    }
 }
 
 impl Getter for CppCode {
@@ -480,7 +884,9 @@ impl Getter for CppCode {
         use Cpp::*;
 
         match node.kind_id().into() {
-            FunctionDefinition | FunctionDefinition2 | FunctionDefinition3 => SpaceKind::Function,
+            FunctionDefinition | FunctionDefinition2 | FunctionDefinition3 | LambdaExpression => {
+                SpaceKind::Function
+            }
             StructSpecifier => SpaceKind::Struct,
             ClassSpecifier => SpaceKind::Class,
             NamespaceDefinition => SpaceKind::Namespace,
@@ -513,6 +919,129 @@ impl Getter for CppCode {
     }
 
     get_operator!(Cpp);
+
+    fn get_function_flags(node: &Node, code: &[u8]) -> FunctionFlags {
+        use Cpp::*;
+
+        if !matches!(
+            node.kind_id().into(),
+            FunctionDefinition | FunctionDefinition2 | FunctionDefinition3
+        ) {
+            return FunctionFlags::default();
+        }
+
+        let in_class = node
+            .parent()
+            .is_some_and(|parent| parent.kind_id() == FieldDeclarationList);
+        // A constructor is named as its class, such as `A` in the class `A`
+        // or `A::A` outside of it
+        let is_constructor = node
+            .child_by_field_name("declarator")
+            .and_then(|declarator| declarator.child_by_field_name("declarator"))
+            .is_some_and(|name| match name.kind_id().into() {
+                QualifiedIdentifier | QualifiedIdentifier2 | QualifiedIdentifier3
+                | QualifiedIdentifier4 => {
+                    let scope = name.child_by_field_name("scope");
+                    let name = name.child_by_field_name("name");
+                    scope.zip(name).is_some_and(|(scope, name)| {
+                        text(&scope, code).rsplit("::").next() == Some(text(&name, code))
+                    })
+                }
+                Identifier | FieldIdentifier => {
+                    in_class
+                        && node
+                            .get_parent(2)
+                            .filter(|class| {
+                                matches!(class.kind_id().into(), ClassSpecifier | StructSpecifier)
+                            })
+                            .and_then(|class| class.child_by_field_name("name"))
+                            .is_some_and(|class| text(&class, code) == text(&name, code))
+                }
+                _ => false,
+            });
+
+        FunctionFlags {
+            is_constructor,
+            // The static free functions are not methods
+            is_static: in_class
+                && node.children().any(|child| {
+                    child.kind_id() == StorageClassSpecifier && text(&child, code) == "static"
+                }),
+            ..FunctionFlags::default()
+        }
+    }
+
+    fn get_space_visibility(node: &Node, code: &[u8]) -> Option<SpaceVisibility> {
+        use Cpp::*;
+
+        if !matches!(
+            node.kind_id().into(),
+            FunctionDefinition
+                | FunctionDefinition2
+                | FunctionDefinition3
+                | ClassSpecifier
+                | StructSpecifier
+        ) {
+            return None;
+        }
+
+        let parent = node.parent()?;
+        match parent.kind_id().into() {
+            // The members of a class are private until the first access
+            // specifier, while the members of a struct are public
+            FieldDeclarationList => {
+                let mut sibling = node.previous_sibling();
+                while let Some(previous) = sibling {
+                    if previous.kind_id() == AccessSpecifier {
+                        return Some(match text(&previous, code) {
+                            "private" => SpaceVisibility::Private,
+                            "protected" => SpaceVisibility::Protected,
+                            _ => SpaceVisibility::Public,
+                        });
+                    }
+                    sibling = previous.previous_sibling();
+                }
+                Some(
+                    if parent
+                        .parent()
+                        .is_some_and(|class| class.kind_id() == ClassSpecifier)
+                    {
+                        SpaceVisibility::Private
+                    } else {
+                        SpaceVisibility::Public
+                    },
+                )
+            }
+            // The static free functions have an internal linkage
+            TranslationUnit | DeclarationList => {
+                let is_static = node.children().any(|child| {
+                    child.kind_id() == StorageClassSpecifier && text(&child, code) == "static"
+                });
+                let is_method = node
+                    .child_by_field_name("declarator")
+                    .and_then(|declarator| declarator.child_by_field_name("declarator"))
+                    .is_some_and(|name| {
+                        matches!(
+                            name.kind_id().into(),
+                            QualifiedIdentifier
+                                | QualifiedIdentifier2
+                                | QualifiedIdentifier3
+                                | QualifiedIdentifier4
+                        )
+                    });
+                if is_method {
+                    // The visibility of a method defined outside of its class
+                    // is declared in the class
+                    None
+                } else if is_static {
+                    Some(SpaceVisibility::ModulePrivate)
+                } else {
+                    Some(SpaceVisibility::Public)
+                }
+            }
+            _ => None,
+        }
+    }
 }
 
 impl Getter for PreprocCode {}
@@ -573,6 +1102,189 @@ impl Getter for JavaCode {
             _ => typ.into(),
         }
     }
+
+    fn get_function_flags(node: &Node, code: &[u8]) -> FunctionFlags {
+        use Java::*;
+
+        if !matches!(
+            node.kind_id().into(),
+            MethodDeclaration | ConstructorDeclaration
+        ) {
+            return FunctionFlags::default();
+        }
+
+        let modifiers = node.children().find(|child| child.kind_id() == Modifiers);
+        let is_static = modifiers.is_some_and(|modifiers| modifiers.is_child(Static as u16));
+        // The accessors follow the bean conventions: `getName()` or `isEmpty()`
+        // return a value, while `setName(name)` takes a single parameter
+        let is_bean = |prefix: &str, parameters: usize| {
+            let name = node
+                .child_by_field_name("name")
+                .map(|name| text(&name, code))
+                .unwrap_or_default();
+            !is_static
+                && node.kind_id() == MethodDeclaration
+                && name
+                    .strip_prefix(prefix)
+                    .and_then(|property| property.chars().next())
+                    .is_some_and(char::is_uppercase)
+                && node.child_by_field_name("parameters").is_some_and(|list| {
+                    list.children()
+                        .filter(|parameter| parameter.kind_id() == FormalParameter)
+                        .count()
+                        == parameters
+                })
+        };
+        let returns_value = node
+            .child_by_field_name("type")
+            .is_some_and(|ty| ty.kind_id() != VoidType);
+        FunctionFlags {
+            is_constructor: node.kind_id() == ConstructorDeclaration,
+            is_static,
+            is_getter: returns_value && (is_bean("get", 0) || is_bean("is", 0)),
+            is_setter: is_bean("set", 1),
+            // Such as `@Test`, `@ParameterizedTest` or `@RepeatedTest`
+            is_test: modifiers.is_some_and(|modifiers| {
+                modifiers.children().any(|annotation| {
+                    matches!(annotation.kind_id().into(), MarkerAnnotation | Annotation)
+                        && annotation
+                            .child_by_field_name("name")
+                            .is_some_and(|name| text(&name, code).ends_with("Test"))
+                })
+            }),
+            ..FunctionFlags::default()
+        }
+    }
+
+    fn get_decorators<'a>(node: &Node, code: &'a [u8]) -> Vec<&'a str> {
+        use Java::*;
+
+        node.children()
+            .find(|child| child.kind_id() == Modifiers)
+            .map(|modifiers| {
+                modifiers
+                    .children()
+                    .filter(|child| matches!(child.kind_id().into(), MarkerAnnotation | Annotation))
+                    .map(|annotation| decorator_name(text(&annotation, code)))
+                    .collect()
+            })
+            .unwrap_or_default()
+    }
+
+    fn get_space_visibility(node: &Node, _code: &[u8]) -> Option<SpaceVisibility> {
+        use Java::*;
+
+        if !matches!(
+            node.kind_id().into(),
+            MethodDeclaration | ConstructorDeclaration | ClassDeclaration | InterfaceDeclaration
+        ) {
+            return None;
+        }
+
+        let modifiers = node.children().find(|child| child.kind_id() == Modifiers);
+        let has_modifier =
+            |kind: Java| modifiers.is_some_and(|modifiers| modifiers.is_child(kind as u16));
+        Some(if has_modifier(Public) {
+            SpaceVisibility::Public
+        } else if has_modifier(Protected) {
+            SpaceVisibility::Protected
+        } else if has_modifier(Private) {
+            SpaceVisibility::Private
+        } else if node
+            .parent()
+            .is_some_and(|parent| parent.kind_id() == InterfaceBody)
+        {
+            // The interface members are implicitly public
+            SpaceVisibility::Public
+        } else {
+            SpaceVisibility::ModulePrivate
+        })
+    }
 }
 
-impl Getter for KotlinCode {}
+impl Getter for KotlinCode {
+    fn get_space_kind(node: &Node) -> SpaceKind {
+        use Kotlin::*;
+
+        match node.kind_id().into() {
+            ClassDeclaration if node.is_child(Interface as u16) => SpaceKind::Interface,
+            ClassDeclaration | ObjectDeclaration => SpaceKind::Class,
+            FunctionDeclaration => SpaceKind::Function,
+            SourceFile => SpaceKind::Unit,
+            _ => SpaceKind::Unknown,
+        }
+    }
+
+    fn get_function_flags(node: &Node, code: &[u8]) -> FunctionFlags {
+        use Kotlin::*;
+
+        if node.kind_id() != FunctionDeclaration {
+            return FunctionFlags::default();
+        }
+
+        let modifiers = node.children().find(|child| child.kind_id() == Modifiers);
+        FunctionFlags {
+            is_async: modifiers.is_some_and(|modifiers| {
+                modifiers.children().any(|modifier| {
+                    modifier.kind_id() == FunctionModifier && text(&modifier, code) == "suspend"
+                })
+            }),
+            // The members of a companion object are static on the JVM
+            is_static: node
+                .get_parent(2)
+                .is_some_and(|parent| parent.kind_id() == CompanionObject),
+            is_test: modifiers.is_some_and(|modifiers| {
+                modifiers.children().any(|annotation| {
+                    annotation.kind_id() == Annotation
+                        && annotation
+                            .children()
+                            .find(|child| child.kind_id() == UserType)
+                            .is_some_and(|name| text(&name, code).ends_with("Test"))
+                })
+            }),
+            ..FunctionFlags::default()
+        }
+    }
+
+    fn get_decorators<'a>(node: &Node, code: &'a [u8]) -> Vec<&'a str> {
+        use Kotlin::*;
+
+        node.children()
+            .find(|child| child.kind_id() == Modifiers)
+            .map(|modifiers| {
+                modifiers
+                    .children()
+                    .filter(|child| child.kind_id() == Annotation)
+                    .map(|annotation| decorator_name(text(&annotation, code)))
+                    .collect()
+            })
+            .unwrap_or_default()
+    }
+
+    fn get_space_visibility(node: &Node, code: &[u8]) -> Option<SpaceVisibility> {
+        use Kotlin::*;
+
+        if !matches!(
+            node.kind_id().into(),
+            FunctionDeclaration | ClassDeclaration | ObjectDeclaration
+        ) {
+            return None;
+        }
+
+        // The declarations are public by default
+        let modifier = node
+            .children()
+            .find(|child| child.kind_id() == Modifiers)
+            .and_then(|modifiers| {
+                modifiers
+                    .children()
+                    .find(|child| child.kind_id() == VisibilityModifier)
+            });
+        Some(match modifier.map(|modifier| text(&modifier, code)) {
+            Some("private") => SpaceVisibility::Private,
+            Some("protected") => SpaceVisibility::Protected,
+            Some("internal") => SpaceVisibility::Crate,
+            _ => SpaceVisibility::Public,
+        })
+    }
+}
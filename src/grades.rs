@@ -0,0 +1,271 @@
+use std::fmt;
+
+use serde::Serialize;
+
+use crate::spaces::{FuncSpace, SpaceKind};
+
+/// A letter grade ranking the severity of a metric value,
+/// from `A`, the best one, to `F`, the worst one.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize)]
+pub enum Grade {
+    /// The best grade
+    #[default]
+    A,
+    B,
+    C,
+    D,
+    E,
+    /// The worst grade
+    F,
+}
+
+impl Grade {
+    /// The names of the grades, from the best to the worst one.
+    pub const NAMES: &'static [&'static str] = &["A", "B", "C", "D", "E", "F"];
+
+    const ALL: [Self; 6] = [Self::A, Self::B, Self::C, Self::D, Self::E, Self::F];
+
+    /// Returns the grade with the given name, ignoring its case.
+    ///
+    /// Returns `None` if the grade does not exist.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rust_code_analysis::Grade;
+    ///
+    /// assert_eq!(Grade::from_name("c"), Some(Grade::C));
+    /// assert_eq!(Grade::from_name("G"), None);
+    /// ```
+    pub fn from_name(name: &str) -> Option<Self> {
+        Self::NAMES
+            .iter()
+            .position(|grade| grade.eq_ignore_ascii_case(name))
+            .map(|index| Self::ALL[index])
+    }
+
+    // The grades past `F` are clamped to `F`
+    pub(crate) fn from_index(index: usize) -> Self {
+        Self::ALL[index.min(Self::ALL.len() - 1)]
+    }
+}
+
+impl fmt::Display for Grade {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{self:?}")
+    }
+}
+
+/// The boundaries used to grade the metrics of a space.
+///
+/// The default boundaries are the ones used by radon:
+/// a cyclomatic complexity up to 5 is graded `A`, up to 10 `B`, up to 20 `C`,
+/// up to 30 `D`, up to 40 `E` and `F` above, while a maintainability index,
+/// on the Visual Studio scale, from 20 is graded `A`, from 10 `B`
+/// and `C` below.
+#[derive(Clone, Debug, PartialEq)]
+pub struct GradeOptions {
+    cyclomatic: Vec<f64>,
+    mi: Vec<f64>,
+}
+
+impl Default for GradeOptions {
+    fn default() -> Self {
+        Self {
+            cyclomatic: vec![5., 10., 20., 30., 40.],
+            mi: vec![20., 10.],
+        }
+    }
+}
+
+impl GradeOptions {
+    /// Returns the options with the given boundaries.
+    ///
+    /// * `cyclomatic` - The highest cyclomatic complexity of each grade,
+    ///   from `A` onwards, in increasing order.
+    /// * `mi` - The lowest maintainability index of each grade,
+    ///   from `A` onwards, in decreasing order.
+    ///
+    /// A value beyond the last boundary gets the following grade, so at
+    /// most 5 boundaries can be given. Returns `None` if there are too
+    /// many boundaries or if they are not in the expected order.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rust_code_analysis::{Grade, GradeOptions};
+    ///
+    /// let options = GradeOptions::new(vec![10., 20.], vec![50.]).unwrap();
+    ///
+    /// assert_eq!(options.cyclomatic_grade(15.), Grade::B);
+    /// assert_eq!(options.cyclomatic_grade(25.), Grade::C);
+    /// assert_eq!(options.mi_grade(40.), Grade::B);
+    /// assert!(GradeOptions::new(vec![20., 10.], vec![]).is_none());
+    /// ```
+    pub fn new(cyclomatic: Vec<f64>, mi: Vec<f64>) -> Option<Self> {
+        let max = Grade::ALL.len() - 1;
+        let valid = cyclomatic.len() <= max
+            && mi.len() <= max
+            && cyclomatic.windows(2).all(|pair| pair[0] < pair[1])
+            && mi.windows(2).all(|pair| pair[0] > pair[1]);
+
+        valid.then_some(Self { cyclomatic, mi })
+    }
+
+    /// Returns the boundaries of the grades of the cyclomatic complexity.
+    pub fn cyclomatic(&self) -> &[f64] {
+        &self.cyclomatic
+    }
+
+    /// Returns the boundaries of the grades of the maintainability index.
+    pub fn mi(&self) -> &[f64] {
+        &self.mi
+    }
+
+    /// Returns the grade of a cyclomatic complexity.
+    pub fn cyclomatic_grade(&self, cyclomatic: f64) -> Grade {
+        Grade::from_index(
+            self.cyclomatic
+                .iter()
+                .filter(|boundary| cyclomatic > **boundary)
+                .count(),
+        )
+    }
+
+    /// Returns the grade of a maintainability index
+    /// on the Visual Studio scale.
+    pub fn mi_grade(&self, mi: f64) -> Grade {
+        Grade::from_index(self.mi.iter().filter(|boundary| mi < **boundary).count())
+    }
+}
+
+/// The grades of the metrics of a space.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize)]
+pub struct Grades {
+    /// The grade of the cyclomatic complexity of a function,
+    /// or of the average cyclomatic complexity of the other spaces
+    pub cyclomatic: Grade,
+    /// The grade of the maintainability index on the Visual Studio scale
+    pub mi: Grade,
+}
+
+impl Grades {
+    /// Returns the worst grade of a space.
+    pub fn worst(&self) -> Grade {
+        self.cyclomatic.max(self.mi)
+    }
+}
+
+pub(crate) fn set_grades(space: &mut FuncSpace, options: &GradeOptions) {
+    let cyclomatic = match space.kind {
+        SpaceKind::Function => space.metrics.cyclomatic.cyclomatic(),
+        _ => space.metrics.cyclomatic.cyclomatic_average(),
+    };
+    space.grades = Grades {
+        cyclomatic: options.cyclomatic_grade(cyclomatic),
+        mi: options.mi_grade(space.metrics.mi.mi_visual_studio()),
+    };
+
+    for subspace in &mut space.spaces {
+        set_grades(subspace, options);
+    }
+}
+
+// Removes the subspaces graded better than `min` which do not
+// contain any space graded `min` or worse
+fn retain_grades(space: &mut FuncSpace, min: Grade) -> bool {
+    space
+        .spaces
+        .retain_mut(|subspace| retain_grades(subspace, min));
+    !space.spaces.is_empty() || space.grades.worst() >= min
+}
+
+/// Removes the subspaces of a space whose grades are all better than `min`,
+/// unless they contain a space graded `min` or worse.
+///
+/// The space itself is always kept.
+///
+/// # Examples
+///
+/// ```
+/// use std::path::Path;
+///
+/// use rust_code_analysis::{Grade, LANG, filter_by_grade, get_function_spaces};
+///
+/// let source_code = "def foo():\n    return 42\n";
+/// let path = Path::new("foo.py");
+///
+/// let mut space =
+///     get_function_spaces(&LANG::Python, source_code.as_bytes().to_vec(), path, None).unwrap();
+/// filter_by_grade(&mut space, Grade::C);
+///
+/// assert!(space.spaces.is_empty());
+/// ```
+pub fn filter_by_grade(space: &mut FuncSpace, min: Grade) {
+    space
+        .spaces
+        .retain_mut(|subspace| retain_grades(subspace, min));
+}
+
+#[cfg(test)]
+mod tests {
+    use std::path::Path;
+
+    use super::*;
+    use crate::langs::{LANG, get_function_spaces};
+
+    #[test]
+    fn python_grades() {
+        let branches = (0..10)
+            .map(|i| format!("    if a == {i}:\n        return {i}\n"))
+            .collect::<String>();
+        let source = format!("def foo(a):\n{branches}    return -1\n\ndef bar():\n    return 42\n");
+        let mut space = get_function_spaces(
+            &LANG::Python,
+            source.into_bytes(),
+            Path::new("foo.py"),
+            None,
+        )
+        .unwrap();
+
+        let grades: Vec<_> = space.spaces.iter().map(|space| space.grades).collect();
+        insta::assert_json_snapshot!(
+            grades,
+            @r###"
+        [
+          {
+            "cyclomatic": "C",
+            "mi": "A"
+          },
+          {
+            "cyclomatic": "A",
+            "mi": "A"
+          }
+        ]
+        "###
+        );
+
+        filter_by_grade(&mut space, Grade::B);
+        let names: Vec<_> = space
+            .spaces
+            .iter()
+            .map(|space| space.name.as_deref().unwrap())
+            .collect();
+        assert_eq!(names, vec!["foo"]);
+    }
+
+    #[test]
+    fn grade_boundaries() {
+        let options = GradeOptions::default();
+        assert_eq!(options.cyclomatic_grade(1.), Grade::A);
+        assert_eq!(options.cyclomatic_grade(5.), Grade::A);
+        assert_eq!(options.cyclomatic_grade(6.), Grade::B);
+        assert_eq!(options.cyclomatic_grade(41.), Grade::F);
+        assert_eq!(options.mi_grade(100.), Grade::A);
+        assert_eq!(options.mi_grade(15.), Grade::B);
+        assert_eq!(options.mi_grade(0.), Grade::C);
+
+        assert!(GradeOptions::new(vec![1., 2., 3., 4., 5., 6.], vec![]).is_none());
+        assert!(GradeOptions::new(vec![], vec![10., 20.]).is_none());
+    }
+}
@@ -0,0 +1,258 @@
+use std::fmt;
+use std::io::Write;
+use std::path::PathBuf;
+use std::sync::Arc;
+
+use tree_sitter::{Query, QueryCursor, QueryErrorKind, StreamingIterator};
+
+use crate::count::NodeSpan;
+use crate::langs::LANG;
+use crate::node::Node;
+use crate::traits::*;
+
+/// The name of the capture marking the matched nodes of a query.
+pub const MATCH_CAPTURE: &str = "match";
+
+/// Errors which might occur building a structural pattern.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum PatternError {
+    /// The pattern is not a valid query, with the reason.
+    Invalid(String),
+    /// The pattern refers to node kinds or fields which do not exist
+    /// in any language, with the reason.
+    Unknown(String),
+}
+
+impl fmt::Display for PatternError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            PatternError::Invalid(reason) => write!(f, "invalid pattern: {reason}"),
+            PatternError::Unknown(reason) => write!(f, "unknown pattern: {reason}"),
+        }
+    }
+}
+
+/// A structural pattern matched against the syntax trees of codes,
+/// compiled for each language where it can match.
+#[derive(Debug)]
+pub struct Pattern {
+    queries: Vec<(LANG, Query)>,
+}
+
+impl Pattern {
+    /// Builds a pattern from a node kind, optionally followed by
+    /// constraints on its children and its fields in the syntax of the
+    /// tree-sitter queries, the outer parentheses being optional.
+    ///
+    /// For example `goto_statement` matches the `goto` statements in C,
+    /// and `call_expression function: (field_expression field:
+    /// (field_identifier) @name (#eq? @name "unwrap"))` the `unwrap`
+    /// calls in Rust.
+    pub fn new(pattern: &str) -> Result<Self, PatternError> {
+        let pattern = pattern.trim();
+        let query = if pattern.starts_with('(') {
+            format!("{pattern} @{MATCH_CAPTURE}")
+        } else {
+            format!("({pattern}) @{MATCH_CAPTURE}")
+        };
+        Self::from_query(&query)
+    }
+
+    /// Builds a pattern from a tree-sitter query, such as the content
+    /// of a query file.
+    ///
+    /// The matched nodes are the ones captured as `@match`, or the ones
+    /// of all the captures if the query has no such capture.
+    pub fn from_query(query: &str) -> Result<Self, PatternError> {
+        let mut queries = Vec::new();
+        let mut unknown = None;
+        for lang in LANG::into_enum_iter() {
+            match Query::new(&lang.get_ts_language(), query) {
+                Ok(query) => queries.push((lang, query)),
+                // The pattern is for other languages
+                Err(e)
+                    if matches!(
+                        e.kind,
+                        QueryErrorKind::NodeType
+                            | QueryErrorKind::Field
+                            | QueryErrorKind::Structure
+                            | QueryErrorKind::Language
+                    ) =>
+                {
+                    unknown.get_or_insert(e);
+                }
+                Err(e) => return Err(PatternError::Invalid(e.to_string())),
+            }
+        }
+        match unknown {
+            Some(e) if queries.is_empty() => Err(PatternError::Unknown(e.to_string())),
+            _ => Ok(Self { queries }),
+        }
+    }
+
+    /// Returns the languages where the pattern can match.
+    pub fn languages(&self) -> impl Iterator<Item = LANG> + '_ {
+        self.queries.iter().map(|(lang, _)| *lang)
+    }
+
+    fn query(&self, lang: LANG) -> Option<&Query> {
+        self.queries
+            .iter()
+            .find(|(other, _)| *other == lang)
+            .map(|(_, query)| query)
+    }
+}
+
+/// Returns the spans of the nodes of a code matching a pattern,
+/// in the order of the code.
+///
+/// A node matched several times is returned once.
+pub fn grep<T: ParserTrait>(parser: &T, pattern: &Pattern) -> Vec<NodeSpan> {
+    let Some(query) = pattern.query(parser.get_language()) else {
+        return Vec::new();
+    };
+    let capture = query.capture_index_for_name(MATCH_CAPTURE);
+    let code = parser.get_code();
+    let mut cursor = QueryCursor::new();
+    let mut matches = cursor.matches(query, parser.get_root().0, code);
+    let mut spans = Vec::new();
+    while let Some(m) = matches.next() {
+        spans.extend(
+            m.captures
+                .iter()
+                .filter(|c| capture.is_none_or(|capture| c.index == capture))
+                .map(|c| NodeSpan::new(&Node(c.node))),
+        );
+    }
+    spans.sort_by_key(|span| (span.start_byte, std::cmp::Reverse(span.end_byte)));
+    spans.dedup();
+    spans
+}
+
+/// Configuration options for searching a structural pattern in a code.
+#[derive(Debug)]
+pub struct GrepCfg {
+    /// Path to the file containing the code
+    pub path: PathBuf,
+    /// The pattern to search
+    pub pattern: Arc<Pattern>,
+    /// The number of lines printed before and after each match
+    pub context: usize,
+}
+
+/// Prints the nodes of a code matching a structural pattern,
+/// with some lines of context.
+pub struct Grep {
+    _guard: (),
+}
+
+impl Callback for Grep {
+    type Res = std::io::Result<()>;
+    type Cfg = GrepCfg;
+
+    fn call<T: ParserTrait>(cfg: Self::Cfg, parser: &T) -> Self::Res {
+        let spans = grep(parser, &cfg.pattern);
+        if spans.is_empty() {
+            return Ok(());
+        }
+
+        let lines: Vec<_> = parser
+            .get_code()
+            .split(|c| *c == b'\n')
+            .map(|line| String::from_utf8_lossy(line.strip_suffix(b"\r").unwrap_or(line)))
+            .collect();
+        let width = lines.len().to_string().len();
+
+        // Lock stdout so that the matches of a file are not interleaved
+        let mut stdout = std::io::stdout().lock();
+        for span in spans {
+            writeln!(
+                stdout,
+                "{}:{}:{}: {}",
+                cfg.path.display(),
+                span.start_line,
+                span.start_column,
+                span.kind
+            )?;
+            let first = span.start_line.saturating_sub(cfg.context).max(1);
+            let last = (span.end_line + cfg.context).min(lines.len());
+            for n in first..=last {
+                let marker = if (span.start_line..=span.end_line).contains(&n) {
+                    '>'
+                } else {
+                    ' '
+                };
+                writeln!(stdout, "{marker} {n:>width$} | {}", lines[n - 1])?;
+            }
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::path::Path;
+
+    use pretty_assertions::assert_eq;
+
+    use crate::get_grep;
+
+    use super::*;
+
+    fn lines(
+        pattern: &Pattern,
+        source: &str,
+        path: &str,
+        lang: LANG,
+    ) -> Vec<(usize, &'static str)> {
+        get_grep(
+            &lang,
+            source.as_bytes().to_vec(),
+            Path::new(path),
+            None,
+            pattern,
+        )
+        .into_iter()
+        .map(|span| (span.start_line, span.kind))
+        .collect()
+    }
+
+    #[test]
+    fn grep_patterns() {
+        let rust = "fn foo() {
+    let a = bar().unwrap();
+    let b = a.unwrap().len();
+}
+";
+        let unwrap = Pattern::new(
+            r#"call_expression function: (field_expression field: (field_identifier) @name (#eq? @name "unwrap"))"#,
+        )
+        .unwrap();
+        assert_eq!(
+            lines(&unwrap, rust, "foo.rs", LANG::Rust),
+            [(2, "call_expression"), (3, "call_expression")]
+        );
+        assert!(unwrap.languages().any(|lang| lang == LANG::Rust));
+        assert!(unwrap.languages().all(|lang| lang != LANG::Python));
+
+        let goto = Pattern::new("goto_statement").unwrap();
+        let c = "void foo() {\nstart:\n    goto start;\n}\n";
+        assert_eq!(lines(&goto, c, "foo.c", LANG::Cpp), [(3, "goto_statement")]);
+        assert_eq!(lines(&goto, rust, "foo.rs", LANG::Rust), []);
+
+        let query = Pattern::from_query("(function_definition name: (identifier) @name)").unwrap();
+        assert_eq!(
+            lines(&query, "def foo():\n    pass\n", "foo.py", LANG::Python),
+            [(1, "identifier")]
+        );
+
+        assert!(matches!(
+            Pattern::new("call_expression ("),
+            Err(PatternError::Invalid(_))
+        ));
+        assert!(matches!(
+            Pattern::new("no_such_node"),
+            Err(PatternError::Unknown(_))
+        ));
+    }
+}
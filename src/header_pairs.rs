@@ -0,0 +1,690 @@
+use std::collections::{BTreeMap, HashMap};
+use std::fmt;
+use std::path::Path;
+
+use serde::Serialize;
+
+use crate::langs::CppParser;
+use crate::languages::Cpp;
+use crate::node::Node;
+use crate::spaces::{FuncSpace, SpaceKind, metrics};
+use crate::traits::*;
+
+const HEADER_EXTENSIONS: &[&str] = &["h", "hh", "hpp", "hxx"];
+const SOURCE_EXTENSIONS: &[&str] = &["c", "cc", "cpp", "cxx"];
+
+/// A function declared or defined in a `C/C++` file.
+#[derive(Clone, Debug, PartialEq, Serialize)]
+pub struct CppFunction {
+    /// The name of the function, qualified by the names of the
+    /// namespaces and of the classes containing it
+    pub name: String,
+    /// The number of parameters of the function
+    pub parameters: usize,
+    /// The first line of the function
+    pub start_line: usize,
+    /// The last line of the function
+    pub end_line: usize,
+    /// Whether the function is defined, and not only declared
+    pub defined: bool,
+    /// Whether the function is defined in the body of a class
+    pub member: bool,
+    /// Whether the function has an internal linkage, being `static`
+    /// or in an anonymous namespace
+    pub internal: bool,
+    /// The source lines of code of the definition
+    pub sloc: f64,
+    /// The cyclomatic complexity of the definition
+    pub cyclomatic: f64,
+    /// The cognitive complexity of the definition
+    pub cognitive: f64,
+}
+
+/// The functions and the classes of a `C/C++` file.
+#[derive(Clone, Debug, Default, PartialEq, Serialize)]
+pub struct CppFile {
+    /// The path of the file
+    pub name: String,
+    /// The functions declared or defined in the file,
+    /// in the order of the code
+    pub functions: Vec<CppFunction>,
+    /// The qualified names of the classes, the structs and
+    /// the unions defined in the file
+    pub classes: Vec<String>,
+}
+
+/// The kind of a mismatch between the declarations of a header
+/// and the definitions of its implementation file.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum MismatchKind {
+    /// A function is declared in the header but never defined
+    Undefined,
+    /// A function is defined in the implementation file
+    /// but not declared in the header
+    Undeclared,
+    /// A function is defined with a number of parameters
+    /// different from the one of its declaration
+    Parameters,
+}
+
+/// A mismatch between a header and its implementation file.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize)]
+pub struct Mismatch {
+    /// The kind of the mismatch
+    pub kind: MismatchKind,
+    /// The qualified name of the function
+    pub name: String,
+    /// The path of the file containing the declaration of an undefined
+    /// function, or else the definition of the function
+    pub path: String,
+    /// The line of the declaration or of the definition
+    pub line: usize,
+    /// The number of parameters of the declaration, if any
+    pub declared_parameters: Option<usize>,
+    /// The number of parameters of the definition, if any
+    pub defined_parameters: Option<usize>,
+}
+
+impl fmt::Display for Mismatch {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}:{}: {} ", self.path, self.line, self.name)?;
+        match (self.declared_parameters, self.defined_parameters) {
+            (Some(declared), Some(defined)) => write!(
+                f,
+                "defined with {defined} parameters but declared with {declared}"
+            ),
+            (Some(_), None) => write!(f, "declared but not defined"),
+            _ => write!(f, "defined but not declared in the header"),
+        }
+    }
+}
+
+/// The metrics of a class declared in a header, combining the methods
+/// defined in the header and in its implementation file.
+#[derive(Clone, Debug, PartialEq, Serialize)]
+pub struct ClassMetrics {
+    /// The qualified name of the class
+    pub name: String,
+    /// The number of distinct methods declared or defined,
+    /// the overloads of a method being counted once
+    pub methods: usize,
+    /// The number of methods defined in the header
+    pub header_definitions: usize,
+    /// The number of methods defined in the implementation file
+    pub source_definitions: usize,
+    /// The sum of the source lines of code of the methods
+    pub sloc: f64,
+    /// The sum of the cyclomatic complexities of the methods
+    pub cyclomatic: f64,
+    /// The sum of the cognitive complexities of the methods
+    pub cognitive: f64,
+}
+
+/// A header paired with its implementation file.
+#[derive(Clone, Debug, PartialEq, Serialize)]
+pub struct HeaderPair {
+    /// The path of the header
+    pub header: String,
+    /// The path of the implementation file, if any
+    pub source: Option<String>,
+    /// The mismatches between the declarations of the header and the
+    /// definitions of the implementation file, in the order of the files
+    pub mismatches: Vec<Mismatch>,
+    /// The metrics of the classes defined in the header
+    pub classes: Vec<ClassMetrics>,
+}
+
+impl fmt::Display for HeaderPair {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match &self.source {
+            Some(source) => writeln!(f, "{} <-> {source}", self.header)?,
+            None => writeln!(f, "{} (no implementation file)", self.header)?,
+        }
+        for mismatch in &self.mismatches {
+            writeln!(f, "  {mismatch}")?;
+        }
+        for class in &self.classes {
+            writeln!(
+                f,
+                "  class {}: {} methods ({} defined in the header, {} in the source), sloc {}, cyclomatic {}, cognitive {}",
+                class.name,
+                class.methods,
+                class.header_definitions,
+                class.source_definitions,
+                class.sloc,
+                class.cyclomatic,
+                class.cognitive
+            )?;
+        }
+        Ok(())
+    }
+}
+
+fn text<'a>(node: &Node, code: &'a [u8]) -> &'a str {
+    std::str::from_utf8(&code[node.start_byte()..node.end_byte()]).unwrap_or_default()
+}
+
+fn has_extension(path: &Path, extensions: &[&str]) -> bool {
+    path.extension()
+        .and_then(|ext| ext.to_str())
+        .is_some_and(|ext| extensions.contains(&ext.to_lowercase().as_str()))
+}
+
+fn is_function_definition(node: &Node) -> bool {
+    matches!(
+        node.kind_id().into(),
+        Cpp::FunctionDefinition | Cpp::FunctionDefinition2 | Cpp::FunctionDefinition3
+    )
+}
+
+fn is_class(node: &Node) -> bool {
+    matches!(
+        node.kind_id().into(),
+        Cpp::ClassSpecifier | Cpp::StructSpecifier | Cpp::UnionSpecifier
+    )
+}
+
+// Returns the function declarator of a declaration or of a definition,
+// skipping the pointer and the reference declarators of the return type
+fn function_declarator<'a>(node: &Node<'a>) -> Option<Node<'a>> {
+    let mut declarator = node.child_by_field_name("declarator")?;
+    loop {
+        match declarator.kind_id().into() {
+            Cpp::FunctionDeclarator | Cpp::FunctionDeclarator2 | Cpp::FunctionDeclarator3 => {
+                return Some(declarator);
+            }
+            Cpp::PointerDeclarator
+            | Cpp::PointerDeclarator2
+            | Cpp::ReferenceDeclarator
+            | Cpp::ReferenceDeclarator2
+            | Cpp::ReferenceDeclarator3
+            | Cpp::ReferenceDeclarator4 => {
+                // The declarator of a reference is not in a field
+                declarator = declarator
+                    .child_by_field_name("declarator")
+                    .or_else(|| declarator.children().last())?;
+            }
+            _ => return None,
+        }
+    }
+}
+
+fn parameters(declarator: &Node, code: &[u8]) -> usize {
+    let Some(list) = declarator.child_by_field_name("parameters") else {
+        return 0;
+    };
+    if text(&list, code).split_whitespace().collect::<String>() == "(void)" {
+        return 0;
+    }
+    list.children()
+        .filter(|child| {
+            !matches!(
+                child.kind_id().into(),
+                Cpp::LPAREN | Cpp::COMMA | Cpp::RPAREN | Cpp::Comment
+            )
+        })
+        .count()
+}
+
+// Returns the qualified name of the scope of a node, whether the node
+// is in the body of a class, and whether it is in an anonymous namespace
+fn scope(node: &Node, code: &[u8]) -> (Vec<String>, bool, bool) {
+    let mut names = Vec::new();
+    let mut member = false;
+    let mut anonymous = false;
+    let mut ancestor = node.parent();
+    while let Some(parent) = ancestor {
+        if is_class(&parent) || Cpp::NamespaceDefinition == parent.kind_id() {
+            match parent.child_by_field_name("name") {
+                Some(name) => names.push(text(&name, code).to_owned()),
+                None => anonymous |= Cpp::NamespaceDefinition == parent.kind_id(),
+            }
+            member |= is_class(&parent);
+        }
+        ancestor = parent.parent();
+    }
+    names.reverse();
+    (names, member && !anonymous, anonymous)
+}
+
+fn qualify(scope: &[String], name: &str) -> String {
+    let name: String = name.split_whitespace().collect();
+    if scope.is_empty() {
+        name
+    } else {
+        format!("{}::{name}", scope.join("::"))
+    }
+}
+
+fn is_static(node: &Node, code: &[u8]) -> bool {
+    node.children().any(|child| {
+        Cpp::StorageClassSpecifier == child.kind_id() && text(&child, code) == "static"
+    })
+}
+
+fn function_spaces<'a>(space: &'a FuncSpace, spaces: &mut HashMap<usize, &'a FuncSpace>) {
+    if space.kind == SpaceKind::Function {
+        spaces.entry(space.start_line).or_insert(space);
+    }
+    for subspace in &space.spaces {
+        function_spaces(subspace, spaces);
+    }
+}
+
+/// Finds the functions declared or defined in a `C/C++` code
+/// and the classes it defines.
+///
+/// The friend declarations and the pure virtual methods are not listed,
+/// since they do not need a definition.
+///
+/// * `parser` - The parser of the code.
+/// * `path` - The path of the file.
+pub fn cpp_file(parser: &CppParser, path: &Path) -> CppFile {
+    let code = parser.get_code();
+    let root = parser.get_root();
+    let space = metrics(parser, path);
+    let mut spaces = HashMap::new();
+    if let Some(space) = &space {
+        function_spaces(space, &mut spaces);
+    }
+
+    let mut file = CppFile {
+        name: path.to_str().unwrap_or_default().to_owned(),
+        ..Default::default()
+    };
+    root.act_on_node(&mut |node| {
+        if is_class(node) {
+            if let Some(name) = node.child_by_field_name("name")
+                && node.child_by_field_name("body").is_some()
+            {
+                let (scope, _, _) = scope(node, code);
+                file.classes.push(qualify(&scope, text(&name, code)));
+            }
+            return;
+        }
+
+        let defined = is_function_definition(node);
+        let declared = matches!(
+            node.kind_id().into(),
+            Cpp::Declaration
+                | Cpp::Declaration2
+                | Cpp::Declaration3
+                | Cpp::Declaration4
+                | Cpp::FieldDeclaration
+        ) && node.child_by_field_name("default_value").is_none()
+            && node
+                .parent()
+                .is_none_or(|parent| Cpp::FriendDeclaration != parent.kind_id());
+        if !defined && !declared {
+            return;
+        }
+        let Some(declarator) = function_declarator(node) else {
+            return;
+        };
+        let Some(name) = declarator.child_by_field_name("declarator") else {
+            return;
+        };
+        let (scope, member, anonymous) = scope(node, code);
+        // The prototypes declared in a function body
+        let mut ancestor = node.parent();
+        while let Some(parent) = ancestor.filter(|_| !defined) {
+            if is_function_definition(&parent) {
+                return;
+            }
+            ancestor = parent.parent();
+        }
+
+        let start_line = node.start_row() + 1;
+        let space = spaces.get(&start_line).filter(|_| defined);
+        file.functions.push(CppFunction {
+            name: qualify(&scope, text(&name, code)),
+            parameters: parameters(&declarator, code),
+            start_line,
+            end_line: node.end_row() + 1,
+            defined,
+            member,
+            internal: anonymous || (!member && is_static(node, code)),
+            sloc: space.map_or(0., |space| space.metrics.loc.sloc()),
+            cyclomatic: space.map_or(0., |space| space.metrics.cyclomatic.cyclomatic()),
+            cognitive: space.map_or(0., |space| space.metrics.cognitive.cognitive()),
+        });
+    });
+    file
+}
+
+// Returns the implementation file of a header, which has the same stem,
+// preferring the one in the same directory
+fn implementation<'a>(header: &Path, files: &'a [CppFile]) -> Option<&'a CppFile> {
+    let stem = header.file_stem();
+    let mut candidates = files.iter().filter(|file| {
+        let path = Path::new(&file.name);
+        has_extension(path, SOURCE_EXTENSIONS) && path.file_stem() == stem
+    });
+    let first = candidates.next()?;
+    Some(
+        std::iter::once(first)
+            .chain(candidates)
+            .find(|file| Path::new(&file.name).parent() == header.parent())
+            .unwrap_or(first),
+    )
+}
+
+// Returns the class of a method among the classes of a header
+fn class_of<'a>(name: &str, classes: &'a [String]) -> Option<&'a String> {
+    let scope = name.rsplit_once("::")?.0;
+    classes.iter().find(|class| *class == scope)
+}
+
+fn mismatches(header: &CppFile, source: &CppFile) -> Vec<Mismatch> {
+    type Functions<'a> = (Vec<&'a CppFunction>, Vec<&'a CppFunction>);
+
+    let mut names: BTreeMap<&str, Functions> = BTreeMap::new();
+    let mut inline: Vec<(&str, usize)> = Vec::new();
+    for function in &header.functions {
+        if function.defined {
+            inline.push((&function.name, function.parameters));
+        } else {
+            names.entry(&function.name).or_default().0.push(function);
+        }
+    }
+    for function in &source.functions {
+        let local = class_of(&function.name, &source.classes).is_some();
+        if function.defined
+            && !function.internal
+            && !local
+            && function.name != "main"
+            && !inline.contains(&(function.name.as_str(), function.parameters))
+        {
+            names.entry(&function.name).or_default().1.push(function);
+        }
+    }
+
+    let mismatch = |kind, function: &CppFunction, path: &str, declared, defined| Mismatch {
+        kind,
+        name: function.name.clone(),
+        path: path.to_owned(),
+        line: function.start_line,
+        declared_parameters: declared,
+        defined_parameters: defined,
+    };
+    let mut mismatches = Vec::new();
+    for (name, (mut declarations, mut definitions)) in names {
+        declarations.retain(|declaration| !inline.contains(&(name, declaration.parameters)));
+        // The overloads with the same number of parameters match
+        declarations.retain(|declaration| {
+            let found = definitions
+                .iter()
+                .position(|definition| definition.parameters == declaration.parameters);
+            found.map(|i| definitions.remove(i)).is_none()
+        });
+
+        let has_inline = inline.iter().any(|(other, _)| *other == name);
+        let mut definitions = definitions.into_iter();
+        for declaration in declarations {
+            mismatches.push(match definitions.next() {
+                Some(definition) => mismatch(
+                    MismatchKind::Parameters,
+                    definition,
+                    &source.name,
+                    Some(declaration.parameters),
+                    Some(definition.parameters),
+                ),
+                None => mismatch(
+                    MismatchKind::Undefined,
+                    declaration,
+                    &header.name,
+                    Some(declaration.parameters),
+                    None,
+                ),
+            });
+        }
+        mismatches.extend(definitions.map(|definition| {
+            let declared = has_inline.then(|| {
+                inline
+                    .iter()
+                    .find(|(other, _)| *other == name)
+                    .map_or(0, |(_, parameters)| *parameters)
+            });
+            let kind = if declared.is_some() {
+                MismatchKind::Parameters
+            } else {
+                MismatchKind::Undeclared
+            };
+            mismatch(
+                kind,
+                definition,
+                &source.name,
+                declared,
+                Some(definition.parameters),
+            )
+        }));
+    }
+    mismatches.sort_by_key(|mismatch| (mismatch.path != header.name, mismatch.line));
+    mismatches
+}
+
+fn class_metrics(header: &CppFile, source: Option<&CppFile>) -> Vec<ClassMetrics> {
+    let mut classes: Vec<_> = header
+        .classes
+        .iter()
+        .map(|name| ClassMetrics {
+            name: name.clone(),
+            methods: 0,
+            header_definitions: 0,
+            source_definitions: 0,
+            sloc: 0.,
+            cyclomatic: 0.,
+            cognitive: 0.,
+        })
+        .collect();
+    let mut methods = Vec::new();
+    let functions = header
+        .functions
+        .iter()
+        .map(|function| (function, true))
+        .chain(
+            source
+                .iter()
+                .flat_map(|source| source.functions.iter().map(|function| (function, false))),
+        );
+    for (function, in_header) in functions {
+        let Some(index) = header
+            .classes
+            .iter()
+            .position(|class| Some(class) == class_of(&function.name, &header.classes))
+        else {
+            continue;
+        };
+        let class = &mut classes[index];
+        if !methods.contains(&&function.name) {
+            methods.push(&function.name);
+            class.methods += 1;
+        }
+        if function.defined {
+            if in_header {
+                class.header_definitions += 1;
+            } else {
+                class.source_definitions += 1;
+            }
+            class.sloc += function.sloc;
+            class.cyclomatic += function.cyclomatic;
+            class.cognitive += function.cognitive;
+        }
+    }
+    classes
+}
+
+/// Pairs the `C/C++` headers with their implementation files, which
+/// have the same stem, and reports the mismatches between their
+/// declarations and their definitions with the metrics of the classes
+/// of the headers, combining the methods defined in both the files.
+///
+/// The functions with an internal linkage, the methods of the classes
+/// of the implementation files and `main` are not considered undeclared.
+///
+/// * `files` - The functions and the classes of the analyzed files.
+///
+/// # Examples
+///
+/// ```
+/// use std::path::Path;
+///
+/// use rust_code_analysis::{CppParser, MismatchKind, ParserTrait, cpp_file, pair_headers};
+///
+/// let header = Path::new("foo.h");
+/// let header = cpp_file(&CppParser::new(b"int foo(int a);\n".to_vec(), header, None), header);
+/// let source = Path::new("foo.cpp");
+/// let source = cpp_file(
+///     &CppParser::new(b"int foo(int a, int b) { return a; }\n".to_vec(), source, None),
+///     source,
+/// );
+///
+/// let pairs = pair_headers(&[header, source]);
+///
+/// assert_eq!(pairs[0].source.as_deref(), Some("foo.cpp"));
+/// assert_eq!(pairs[0].mismatches[0].kind, MismatchKind::Parameters);
+/// ```
+pub fn pair_headers(files: &[CppFile]) -> Vec<HeaderPair> {
+    files
+        .iter()
+        .filter(|file| has_extension(Path::new(&file.name), HEADER_EXTENSIONS))
+        .map(|header| {
+            let source = implementation(Path::new(&header.name), files);
+            HeaderPair {
+                header: header.name.clone(),
+                source: source.map(|source| source.name.clone()),
+                mismatches: source.map_or_else(Vec::new, |source| mismatches(header, source)),
+                classes: class_metrics(header, source),
+            }
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use std::path::Path;
+
+    use pretty_assertions::assert_eq;
+
+    use super::*;
+
+    fn file(source: &str, path: &str) -> CppFile {
+        let path = Path::new(path);
+        let parser = CppParser::new(source.as_bytes().to_vec(), path, None);
+        cpp_file(&parser, path)
+    }
+
+    #[test]
+    fn cpp_file_functions() {
+        let file = file(
+            "struct B {
+    virtual int f(int) = 0;
+    B() = default;
+    int *g(int a, ...);
+    int &h();
+    friend void fr();
+    void i(void) {}
+};
+namespace {
+int anon() { return 0; }
+}
+static int local();
+int *B::g(int a, ...) { return nullptr; }
+",
+            "b.h",
+        );
+        let functions: Vec<_> = file
+            .functions
+            .iter()
+            .map(|f| {
+                (
+                    f.name.as_str(),
+                    f.parameters,
+                    f.start_line,
+                    f.defined,
+                    f.member,
+                    f.internal,
+                )
+            })
+            .collect();
+        assert_eq!(
+            functions,
+            [
+                ("B::B", 0, 3, true, true, false),
+                ("B::g", 2, 4, false, true, false),
+                ("B::h", 0, 5, false, true, false),
+                ("B::i", 0, 7, true, true, false),
+                ("anon", 0, 10, true, false, true),
+                ("local", 0, 12, false, false, true),
+                ("B::g", 2, 13, true, false, false),
+            ]
+        );
+        assert_eq!(file.classes, ["B"]);
+    }
+
+    #[test]
+    fn cpp_header_pairs() {
+        let files = [
+            file(
+                "namespace ns {
+
+class Foo {
+public:
+    Foo(int a);
+    int bar(int x, int y) const;
+    int baz() { return 1; }
+    static void qux();
+private:
+    int a_;
+};
+
+int helper(int x);
+void missing();
+
+}
+",
+                "include/foo.h",
+            ),
+            file(
+                "#include \"foo.h\"
+
+namespace ns {
+
+Foo::Foo(int a) : a_(a) {}
+
+int Foo::bar(int x) const {
+    if (x > 0) {
+        return x;
+    }
+    return 0;
+}
+
+int helper(int x) { return x + 1; }
+
+int extra() { return 2; }
+
+static int local() { return 3; }
+
+}
+",
+                "src/foo.cpp",
+            ),
+            file("int foo();\n", "src/bar.hpp"),
+        ];
+        let pairs = pair_headers(&files);
+        assert_eq!(pairs.len(), 2);
+        assert_eq!(pairs[1].source, None);
+
+        let pair = &pairs[0];
+        assert_eq!(pair.source.as_deref(), Some("src/foo.cpp"));
+        insta::assert_snapshot!(pair.to_string(), @r"
+        include/foo.h <-> src/foo.cpp
+          include/foo.h:8: ns::Foo::qux declared but not defined
+          include/foo.h:14: ns::missing declared but not defined
+          src/foo.cpp:7: ns::Foo::bar defined with 1 parameters but declared with 2
+          src/foo.cpp:16: ns::extra defined but not declared in the header
+          class ns::Foo: 4 methods (1 defined in the header, 2 in the source), sloc 8, cyclomatic 4, cognitive 1
+        ");
+    }
+}
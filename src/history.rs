@@ -0,0 +1,327 @@
+use std::path::Path;
+
+use rusqlite::{Connection, OptionalExtension, params};
+use serde::Serialize;
+
+use crate::grades::Grade;
+use crate::spaces::{FuncSpace, SpaceKind};
+
+const SCHEMA: &str = "
+CREATE TABLE IF NOT EXISTS runs (
+    id INTEGER PRIMARY KEY,
+    timestamp INTEGER NOT NULL
+);
+CREATE TABLE IF NOT EXISTS grades (
+    run INTEGER NOT NULL REFERENCES runs (id),
+    path TEXT NOT NULL,
+    qualified_name TEXT NOT NULL,
+    start_line INTEGER NOT NULL,
+    grade INTEGER NOT NULL,
+    PRIMARY KEY (run, path, qualified_name)
+);
+";
+
+/// The grade of a function in a run.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize)]
+pub struct FunctionGrade {
+    /// The path of the file containing the function
+    pub path: String,
+    /// The qualified name of the function, which identifies it
+    /// in its file from one run to the next
+    pub qualified_name: String,
+    /// The first line of the function
+    pub start_line: usize,
+    /// The worst grade of the metrics of the function
+    pub grade: Grade,
+}
+
+/// Returns the grades of the functions of a file.
+///
+/// * `space` - The root function space of the file, named after its path.
+pub fn function_grades(space: &FuncSpace) -> Vec<FunctionGrade> {
+    let mut grades = Vec::new();
+    let path = space.name.as_deref().unwrap_or_default();
+    collect_grades(space, path, &mut grades);
+    grades
+}
+
+fn collect_grades(space: &FuncSpace, path: &str, grades: &mut Vec<FunctionGrade>) {
+    for subspace in &space.spaces {
+        if subspace.kind == SpaceKind::Function
+            && let Some(name) = subspace.qualified_name.as_ref().or(subspace.name.as_ref())
+        {
+            grades.push(FunctionGrade {
+                path: path.to_owned(),
+                qualified_name: name.clone(),
+                start_line: subspace.start_line,
+                grade: subspace.grades.worst(),
+            });
+        }
+        collect_grades(subspace, path, grades);
+    }
+}
+
+/// A function whose grade got worse since a previous run.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize)]
+pub struct GradeDrop {
+    /// The path of the file containing the function
+    pub path: String,
+    /// The qualified name of the function
+    pub qualified_name: String,
+    /// The first line of the function in the last run
+    pub start_line: usize,
+    /// The grade of the function in the previous run
+    pub old: Grade,
+    /// The grade of the function in the last run
+    pub new: Grade,
+    /// The time of the previous run, in seconds since the Unix epoch
+    pub since: i64,
+}
+
+/// The grades of the functions of the successive runs of an analysis,
+/// kept in a SQLite database.
+///
+/// The grades of each run are recorded with its time, so that the
+/// functions whose grade dropped since a date can be found without
+/// keeping all the results of the previous runs.
+/// The store requires the `sqlite` feature.
+pub struct GradeHistory {
+    connection: Connection,
+}
+
+impl GradeHistory {
+    /// Opens the history kept in a database, creating it if needed.
+    pub fn open(path: &Path) -> rusqlite::Result<Self> {
+        Self::with_connection(Connection::open(path)?)
+    }
+
+    /// Opens a history kept in memory, which is lost when it is dropped.
+    pub fn open_in_memory() -> rusqlite::Result<Self> {
+        Self::with_connection(Connection::open_in_memory()?)
+    }
+
+    fn with_connection(connection: Connection) -> rusqlite::Result<Self> {
+        connection.execute_batch(SCHEMA)?;
+        Ok(Self { connection })
+    }
+
+    /// Records the grades of the functions of a run.
+    ///
+    /// * `timestamp` - The time of the run, in seconds since the Unix epoch.
+    /// * `grades` - The grades of all the functions analyzed by the run.
+    ///
+    /// The functions with the same qualified name in a file,
+    /// such as overloads, are recorded with the worst of their grades.
+    pub fn record(&mut self, timestamp: i64, grades: &[FunctionGrade]) -> rusqlite::Result<()> {
+        let transaction = self.connection.transaction()?;
+        transaction.execute("INSERT INTO runs (timestamp) VALUES (?1)", [timestamp])?;
+        let run = transaction.last_insert_rowid();
+        {
+            let mut insert = transaction.prepare(
+                "INSERT INTO grades (run, path, qualified_name, start_line, grade)
+                 VALUES (?1, ?2, ?3, ?4, ?5)
+                 ON CONFLICT (run, path, qualified_name)
+                 DO UPDATE SET grade = max(grade, excluded.grade)",
+            )?;
+            for grade in grades {
+                insert.execute(params![
+                    run,
+                    grade.path,
+                    grade.qualified_name,
+                    grade.start_line as i64,
+                    grade.grade as i64,
+                ])?;
+            }
+        }
+        transaction.commit()
+    }
+
+    /// Returns the times of the recorded runs, from the oldest one.
+    pub fn runs(&self) -> rusqlite::Result<Vec<i64>> {
+        let mut select = self
+            .connection
+            .prepare("SELECT timestamp FROM runs ORDER BY timestamp, id")?;
+        select.query_map([], |row| row.get(0))?.collect()
+    }
+
+    /// Returns the functions whose grade is worse in the last run
+    /// than in the last run recorded at or before a time, or in the
+    /// first run when there is none, sorted by path and line.
+    ///
+    /// * `since` - The time, in seconds since the Unix epoch.
+    ///
+    /// The functions which did not exist in the previous run are not
+    /// returned, since their grade did not drop.
+    pub fn grade_drops(&self, since: i64) -> rusqlite::Result<Vec<GradeDrop>> {
+        let last = self
+            .connection
+            .query_row(
+                "SELECT id FROM runs ORDER BY timestamp DESC, id DESC LIMIT 1",
+                [],
+                |row| row.get::<_, i64>(0),
+            )
+            .optional()?;
+        let run = |query| {
+            self.connection
+                .query_row(query, [since], |row| {
+                    Ok((row.get::<_, i64>(0)?, row.get::<_, i64>(1)?))
+                })
+                .optional()
+        };
+        let previous = match run("SELECT id, timestamp FROM runs WHERE timestamp <= ?1
+             ORDER BY timestamp DESC, id DESC LIMIT 1")?
+        {
+            Some(previous) => Some(previous),
+            None => run("SELECT id, timestamp FROM runs WHERE timestamp > ?1
+                 ORDER BY timestamp, id LIMIT 1")?,
+        };
+        let (Some(last), Some((previous, since))) = (last, previous) else {
+            return Ok(Vec::new());
+        };
+
+        let mut select = self.connection.prepare(
+            "SELECT new.path, new.qualified_name, new.start_line, old.grade, new.grade
+             FROM grades AS new JOIN grades AS old
+             ON old.path = new.path AND old.qualified_name = new.qualified_name
+             WHERE new.run = ?1 AND old.run = ?2 AND new.grade > old.grade
+             ORDER BY new.path, new.start_line",
+        )?;
+        select
+            .query_map([last, previous], |row| {
+                Ok(GradeDrop {
+                    path: row.get(0)?,
+                    qualified_name: row.get(1)?,
+                    start_line: row.get::<_, i64>(2)? as usize,
+                    old: Grade::from_index(row.get::<_, i64>(3)? as usize),
+                    new: Grade::from_index(row.get::<_, i64>(4)? as usize),
+                    since,
+                })
+            })?
+            .collect()
+    }
+}
+
+/// Returns the time, in seconds since the Unix epoch, of the beginning
+/// of a day in UTC written as `YYYY-MM-DD`.
+///
+/// Returns `None` if the date is not valid.
+///
+/// # Examples
+///
+/// ```
+/// use rust_code_analysis::{date_timestamp, timestamp_date};
+///
+/// let timestamp = date_timestamp("2024-02-29").unwrap();
+///
+/// assert_eq!(timestamp, 1_709_164_800);
+/// assert_eq!(timestamp_date(timestamp + 3600), "2024-02-29");
+/// assert_eq!(date_timestamp("2023-02-29"), None);
+/// ```
+pub fn date_timestamp(date: &str) -> Option<i64> {
+    let mut fields = date.splitn(3, '-');
+    let mut field = || fields.next().and_then(|field| field.parse::<i64>().ok());
+    let (year, month, day) = (field()?, field()?, field()?);
+
+    let timestamp = days_from_civil(year, month, day) * 86400;
+    // The days past the end of their month are not valid
+    (timestamp_date(timestamp) == format!("{year:04}-{month:02}-{day:02}")).then_some(timestamp)
+}
+
+/// Returns the day in UTC, written as `YYYY-MM-DD`, of a time
+/// in seconds since the Unix epoch.
+pub fn timestamp_date(timestamp: i64) -> String {
+    let (year, month, day) = civil_from_days(timestamp.div_euclid(86400));
+    format!("{year:04}-{month:02}-{day:02}")
+}
+
+// The number of days since the Unix epoch of a date
+// of the proleptic Gregorian calendar
+fn days_from_civil(year: i64, month: i64, day: i64) -> i64 {
+    let year = if month <= 2 { year - 1 } else { year };
+    let era = year.div_euclid(400);
+    let year_of_era = year - era * 400;
+    let day_of_year = (153 * (month + if month > 2 { -3 } else { 9 }) + 2) / 5 + day - 1;
+    let day_of_era = year_of_era * 365 + year_of_era / 4 - year_of_era / 100 + day_of_year;
+    era * 146097 + day_of_era - 719468
+}
+
+// The date of the proleptic Gregorian calendar of a number
+// of days since the Unix epoch
+fn civil_from_days(days: i64) -> (i64, i64, i64) {
+    let days = days + 719468;
+    let era = days.div_euclid(146097);
+    let day_of_era = days - era * 146097;
+    let year_of_era =
+        (day_of_era - day_of_era / 1460 + day_of_era / 36524 - day_of_era / 146096) / 365;
+    let day_of_year = day_of_era - (365 * year_of_era + year_of_era / 4 - year_of_era / 100);
+    let month = (5 * day_of_year + 2) / 153;
+    let day = day_of_year - (153 * month + 2) / 5 + 1;
+    let month = if month < 10 { month + 3 } else { month - 9 };
+    let year = year_of_era + era * 400 + i64::from(month <= 2);
+    (year, month, day)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::path::Path;
+
+    use pretty_assertions::assert_eq;
+
+    use crate::{LANG, get_function_spaces};
+
+    use super::*;
+
+    fn grades(code: &str) -> Vec<FunctionGrade> {
+        let space = get_function_spaces(
+            &LANG::Python,
+            code.as_bytes().to_vec(),
+            Path::new("foo.py"),
+            None,
+        )
+        .unwrap();
+        function_grades(&space)
+    }
+
+    #[test]
+    fn grade_history_drops() {
+        let simple = "class A:\n    def foo(self, a):\n        return a\n\ndef bar():\n    pass\n";
+        let complex = format!(
+            "class A:\n    def foo(self, a):\n{}        return a\n\ndef bar():\n    pass\n",
+            "        if a:\n            a += 1\n".repeat(6)
+        );
+        let day = date_timestamp("2026-09-01").unwrap();
+
+        let mut history = GradeHistory::open_in_memory().unwrap();
+        assert_eq!(history.grade_drops(day).unwrap(), []);
+        history.record(day, &grades(simple)).unwrap();
+        history.record(day + 86400, &grades(simple)).unwrap();
+        history.record(day + 2 * 86400, &grades(&complex)).unwrap();
+        assert_eq!(history.runs().unwrap(), [day, day + 86400, day + 2 * 86400]);
+
+        let drops = history.grade_drops(day + 86400).unwrap();
+        assert_eq!(
+            drops,
+            [GradeDrop {
+                path: "foo.py".to_owned(),
+                qualified_name: "foo.A.foo".to_owned(),
+                start_line: 2,
+                old: Grade::A,
+                new: Grade::B,
+                since: day + 86400,
+            }]
+        );
+        // Before the first run, the grades are compared with the first one
+        assert_eq!(history.grade_drops(day - 86400).unwrap()[0].since, day);
+        // Nothing dropped since the last run
+        assert_eq!(history.grade_drops(day + 3 * 86400).unwrap(), []);
+    }
+
+    #[test]
+    fn dates() {
+        assert_eq!(date_timestamp("1970-01-01"), Some(0));
+        assert_eq!(timestamp_date(-1), "1969-12-31");
+        assert_eq!(date_timestamp("2026-13-01"), None);
+        assert_eq!(date_timestamp("2026-01"), None);
+        assert_eq!(date_timestamp("last month"), None);
+    }
+}
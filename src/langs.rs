@@ -3,7 +3,8 @@ use std::sync::Arc;
 use tree_sitter::Language;
 
 use crate::macros::{
-    get_language, mk_action, mk_code, mk_emacs_mode, mk_extensions, mk_lang, mk_langs,
+    get_language, mk_action, mk_code, mk_emacs_mode, mk_extensions, mk_interpreters, mk_lang,
+    mk_langs,
 };
 use crate::preproc::PreprocResults;
 use crate::*;
@@ -17,6 +18,7 @@ mk_langs!(
     // 6) tree-sitter function to call to get a Language
     // 7) file extensions
     // 8) emacs modes
+    // 9) shebang interpreters
     (
         Mozjs,
         "The `Mozjs` language is variant of the `JavaScript` language",
@@ -25,7 +27,8 @@ mk_langs!(
         MozjsParser,
         tree_sitter_mozjs,
         [js, jsm, mjs, jsx],
-        ["js", "js2"]
+        ["js", "js2", "javascript"],
+        ["node", "nodejs"]
     ),
     (
         Javascript,
@@ -35,6 +38,7 @@ mk_langs!(
         JavascriptParser,
         tree_sitter_javascript,
         [],
+        [],
         []
     ),
     (
@@ -45,6 +49,7 @@ mk_langs!(
         JavaParser,
         tree_sitter_java,
         [java],
+        ["java"],
         ["java"]
     ),
     (
@@ -55,7 +60,8 @@ mk_langs!(
         KotlinParser,
         tree_sitter_kotlin_ng,
         [kt, kts],
-        ["kotlin"]
+        ["kotlin"],
+        ["kotlin", "kscript"]
     ),
     (
         Rust,
@@ -65,7 +71,8 @@ mk_langs!(
         RustParser,
         tree_sitter_rust,
         [rs],
-        ["rust"]
+        ["rust"],
+        ["rust-script", "run-cargo-script"]
     ),
     (
         Cpp,
@@ -75,7 +82,16 @@ mk_langs!(
         CppParser,
         tree_sitter_cpp,
         [cpp, cxx, cc, hxx, hpp, c, h, hh, inc, mm, m],
-        ["c++", "c", "objc", "objc++", "objective-c++", "objective-c"]
+        [
+            "c++",
+            "c",
+            "cpp",
+            "objc",
+            "objc++",
+            "objective-c++",
+            "objective-c"
+        ],
+        []
     ),
     (
         Python,
@@ -85,7 +101,8 @@ mk_langs!(
         PythonParser,
         tree_sitter_python,
         [py],
-        ["python"]
+        ["python"],
+        ["python", "pypy"]
     ),
     (
         Tsx,
@@ -95,6 +112,7 @@ mk_langs!(
         TsxParser,
         tree_sitter_tsx,
         [tsx],
+        [],
         []
     ),
     (
@@ -105,7 +123,8 @@ mk_langs!(
         TypescriptParser,
         tree_sitter_typescript,
         [ts, jsw, jsmw],
-        ["typescript"]
+        ["typescript"],
+        ["ts-node", "deno"]
     ),
     (
         Ccomment,
@@ -115,6 +134,7 @@ mk_langs!(
         CcommentParser,
         tree_sitter_ccomment,
         [],
+        [],
         []
     ),
     (
@@ -125,6 +145,7 @@ mk_langs!(
         PreprocParser,
         tree_sitter_preproc,
         [],
+        [],
         []
     )
 );
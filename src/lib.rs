@@ -43,6 +43,24 @@
 //! - NEXITS: it counts the number of possible exit points
 //!   from a method/function.
 //! - NARGS: it counts the number of arguments of a function/method.
+//! - LOCALS: it counts the local variables declared in a function/method.
+//! - PPC: it counts the preprocessor conditionals, their branches
+//!   and their nesting in a C/C++ source file.
+//! - ANNOTATIONS: it counts the annotations and the exceptions declared
+//!   in the `throws` clauses in a Java source file.
+//! - ACCESSORS: it counts the property getters and setters, and the other
+//!   functions, in a Python/JavaScript/TypeScript/Java/Kotlin source file.
+//! - ASSERTIONS: it counts the assertions of the functions, such as the
+//!   `assert` statements and the calls to the assertion APIs of the tests.
+//! - CALLBACKS: it measures the nesting of the callbacks and the longest
+//!   chain of `.then()` calls in a JavaScript/TypeScript source file.
+//! - UNSAFETY: it counts the unsafe blocks and functions, and the lines
+//!   they contain, in a Rust source file.
+//! - KOTLIN_FUNCS: it counts the suspend functions, the extension functions
+//!   and the function types with a receiver in a Kotlin source file.
+//! - INDENTATION: it describes the indentation style of a space, the maximum
+//!   and the average logical indentation level of its lines and the lines
+//!   ending with whitespace.
 
 #![allow(clippy::upper_case_acronyms)]
 
@@ -72,6 +90,78 @@ pub use output::*;
 mod spaces;
 pub use crate::spaces::*;
 
+mod grades;
+pub use crate::grades::*;
+
+mod range;
+pub use crate::range::*;
+
+mod space_kinds;
+pub use crate::space_kinds::*;
+
+mod line_map;
+pub use crate::line_map::*;
+
+mod summary;
+pub use crate::summary::*;
+
+mod test_code;
+pub use crate::test_code::*;
+
+mod metadata;
+pub use crate::metadata::*;
+
+mod cocomo;
+pub use crate::cocomo::*;
+
+mod rules;
+pub use crate::rules::*;
+
+mod debt;
+pub use crate::debt::*;
+
+mod crap;
+pub use crate::crap::*;
+
+mod delta;
+pub use crate::delta::*;
+
+mod diff;
+pub use crate::diff::*;
+
+mod review;
+pub use crate::review::*;
+
+mod api;
+pub use crate::api::*;
+
+mod unreferenced;
+pub use crate::unreferenced::*;
+
+mod outliers;
+pub use crate::outliers::*;
+
+mod top;
+pub use crate::top::*;
+
+mod snapshot;
+pub use crate::snapshot::*;
+
+mod query;
+pub use crate::query::*;
+
+mod embedded;
+pub use crate::embedded::*;
+
+mod describe;
+pub use crate::describe::*;
+
+mod dialect;
+pub use crate::dialect::*;
+
+mod encoding;
+pub use crate::encoding::*;
+
 mod ops;
 pub use crate::ops::*;
 
@@ -81,12 +171,21 @@ pub use crate::find::*;
 mod function;
 pub use crate::function::*;
 
+mod tokens;
+pub use crate::tokens::*;
+
 mod ast;
 pub use crate::ast::*;
 
 mod count;
 pub use crate::count::*;
 
+mod grep;
+pub use crate::grep::*;
+
+mod header_pairs;
+pub use crate::header_pairs::*;
+
 mod preproc;
 pub use crate::preproc::*;
 
@@ -96,9 +195,28 @@ pub use crate::langs::*;
 mod tools;
 pub use crate::tools::*;
 
+mod generated;
+pub use crate::generated::*;
+
+#[cfg(feature = "archive")]
+mod archive;
+#[cfg(feature = "archive")]
+pub use crate::archive::*;
+
+#[cfg(feature = "sqlite")]
+mod history;
+#[cfg(feature = "sqlite")]
+pub use crate::history::*;
+
+mod classification;
+pub use crate::classification::*;
+
 mod concurrent_files;
 pub use crate::concurrent_files::*;
 
+mod batch;
+pub use crate::batch::*;
+
 mod traits;
 pub use crate::traits::*;
 
@@ -0,0 +1,199 @@
+use serde::Serialize;
+
+use crate::spaces::{FuncSpace, SpaceKind};
+
+/// A space of a [`LineMap`].
+///
+/// [`LineMap`]: struct.LineMap.html
+#[derive(Clone, Debug, PartialEq, Eq, Serialize)]
+pub struct LineSpace {
+    /// The identifier of the space, as in [`FuncSpace`]
+    ///
+    /// [`FuncSpace`]: struct.FuncSpace.html
+    pub id: String,
+    /// The name of the space prefixed by the names of its enclosing spaces
+    pub qualified_name: Option<String>,
+    /// The kind of the space
+    pub kind: SpaceKind,
+    /// The first line of the space
+    pub start_line: usize,
+    /// The last line of the space
+    pub end_line: usize,
+}
+
+/// A run of consecutive lines belonging to the same space.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize)]
+pub struct LineRun {
+    /// The first line of the run
+    pub start_line: usize,
+    /// The last line of the run
+    pub end_line: usize,
+    /// The index of the space of the lines in [`LineMap::spaces`]
+    ///
+    /// [`LineMap::spaces`]: struct.LineMap.html#structfield.spaces
+    pub space: usize,
+}
+
+/// A run-length encoded map from the lines of a file to the innermost
+/// space containing each of them, so that the hits of a coverage report
+/// or of a profiler can be attributed to the functions in constant time.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize)]
+pub struct LineMap {
+    /// The spaces of the file, depth first in the order of the code,
+    /// starting from the unit space
+    pub spaces: Vec<LineSpace>,
+    /// The runs of lines covering all the lines of the unit space,
+    /// in the order of the code
+    pub runs: Vec<LineRun>,
+}
+
+impl LineMap {
+    /// Builds the map of the lines of a unit space.
+    ///
+    /// A line shared by some nested spaces, such as a closure defined
+    /// on the first line of a function, belongs to the innermost one.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use std::path::Path;
+    ///
+    /// use rust_code_analysis::{LANG, LineMap, get_function_spaces};
+    ///
+    /// let source_code = "def foo():\n    return 1\n\ndef bar():\n    return 2\n";
+    /// let path = Path::new("foo.py");
+    ///
+    /// let space =
+    ///     get_function_spaces(&LANG::Python, source_code.as_bytes().to_vec(), path, None).unwrap();
+    /// let map = LineMap::new(&space);
+    ///
+    /// assert_eq!(map.runs.len(), 3);
+    /// assert_eq!(map.space_at(5).unwrap().qualified_name.as_deref(), Some("foo.bar"));
+    /// ```
+    pub fn new(space: &FuncSpace) -> Self {
+        let mut map = Self::default();
+        let mut lines = vec![0; space.end_line + 1];
+        map.paint(space, &mut lines);
+
+        for (line, &space) in lines.iter().enumerate().skip(space.start_line.max(1)) {
+            match map.runs.last_mut() {
+                Some(run) if run.space == space => run.end_line = line,
+                _ => map.runs.push(LineRun {
+                    start_line: line,
+                    end_line: line,
+                    space,
+                }),
+            }
+        }
+        map
+    }
+
+    // Assigns the lines of a space to it, and then the ones
+    // of its subspaces to them
+    fn paint(&mut self, space: &FuncSpace, lines: &mut [usize]) {
+        let index = self.spaces.len();
+        self.spaces.push(LineSpace {
+            id: space.id.clone(),
+            qualified_name: space.qualified_name.clone(),
+            kind: space.kind,
+            start_line: space.start_line,
+            end_line: space.end_line,
+        });
+        let end_line = space.end_line.min(lines.len() - 1);
+        if space.start_line <= end_line {
+            lines[space.start_line..=end_line].fill(index);
+        }
+        for subspace in &space.spaces {
+            self.paint(subspace, lines);
+        }
+    }
+
+    /// Returns the index in [`spaces`] of the space of each line, the
+    /// index of the line 0 and of the lines out of the unit space being
+    /// the one of the unit space.
+    ///
+    /// [`spaces`]: #structfield.spaces
+    pub fn to_lines(&self) -> Vec<usize> {
+        let last = self.runs.last().map_or(0, |run| run.end_line);
+        let mut lines = vec![0; last + 1];
+        for run in &self.runs {
+            lines[run.start_line..=run.end_line].fill(run.space);
+        }
+        lines
+    }
+
+    /// Returns the innermost space containing a line, if any.
+    pub fn space_at(&self, line: usize) -> Option<&LineSpace> {
+        let index = self.runs.partition_point(|run| run.end_line < line);
+        self.runs
+            .get(index)
+            .filter(|run| run.start_line <= line)
+            .map(|run| &self.spaces[run.space])
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::path::Path;
+
+    use pretty_assertions::assert_eq;
+
+    use crate::{LANG, get_function_spaces};
+
+    use super::*;
+
+    #[test]
+    fn rust_line_map() {
+        let source = "use foo;
+
+fn foo() {
+    let f = |x| {
+        x
+    };
+}
+
+impl Bar {
+    fn bar(&self) {}
+}
+";
+        let space = get_function_spaces(
+            &LANG::Rust,
+            source.as_bytes().to_vec(),
+            Path::new("foo.rs"),
+            None,
+        )
+        .unwrap();
+        let map = LineMap::new(&space);
+
+        let names: Vec<_> = map
+            .spaces
+            .iter()
+            .map(|space| space.qualified_name.as_deref().unwrap_or(""))
+            .collect();
+        let runs: Vec<_> = map
+            .runs
+            .iter()
+            .map(|run| (run.start_line, run.end_line, names[run.space]))
+            .collect();
+        assert_eq!(
+            runs,
+            [
+                (1, 2, ""),
+                (3, 3, "foo::foo"),
+                (4, 6, "foo::foo::<anonymous>"),
+                (7, 7, "foo::foo"),
+                (8, 8, ""),
+                (9, 9, "foo::Bar"),
+                (10, 10, "foo::Bar::bar"),
+                (11, 11, "foo::Bar"),
+            ]
+        );
+
+        let lines = map.to_lines();
+        assert_eq!(lines.len(), map.runs.last().unwrap().end_line + 1);
+        for (line, &space) in lines.iter().enumerate().skip(1) {
+            assert_eq!(map.space_at(line), Some(&map.spaces[space]));
+        }
+        assert_eq!(map.space_at(lines.len()), None);
+    }
+}
@@ -21,10 +21,59 @@ macro_rules! implement_metric_trait {
            }
         )+
     );
+    (Accessors, $($code:ident),+) => (
+        $(
+           impl Accessors for $code {
+               fn compute(_node: &Node, _code: &[u8], _stats: &mut Stats) {}
+           }
+        )+
+    );
+    (Assertions, $($code:ident),+) => (
+        $(
+           impl Assertions for $code {
+               fn compute(_node: &Node, _code: &[u8], _stats: &mut Stats) {}
+           }
+        )+
+    );
+    (Locals, $($code:ident),+) => (
+        $(
+           impl Locals for $code {
+               fn compute(_node: &Node, _code: &[u8], _stats: &mut Stats) {}
+           }
+        )+
+    );
+    (Callbacks, $($code:ident),+) => (
+        $(
+           impl Callbacks for $code {
+               fn compute(_node: &Node, _code: &[u8], _stats: &mut Stats) {}
+           }
+        )+
+    );
+    (Npm, $($code:ident),+) => (
+        $(
+           impl Npm for $code {
+               fn compute(_node: &Node, _code: &[u8], _stats: &mut Stats) {}
+           }
+        )+
+    );
+    (Npa, $($code:ident),+) => (
+        $(
+           impl Npa for $code {
+               fn compute(_node: &Node, _code: &[u8], _stats: &mut Stats) {}
+           }
+        )+
+    );
     (Cognitive, $($code:ident),+) => (
         $(
            impl Cognitive for $code {
-               fn compute(_node: &Node, _stats: &mut Stats, _nesting_map: &mut HashMap<usize, (usize, usize, usize)>,) {}
+               fn compute(_node: &Node, _code: &[u8], _stats: &mut Stats, _nesting_map: &mut HashMap<usize, (usize, usize, usize)>, _mode: CognitiveMode, _comprehensions: ComprehensionMode) {}
+           }
+        )+
+    );
+    (Cyclomatic, $($code:ident),+) => (
+        $(
+           impl Cyclomatic for $code {
+               fn compute(_node: &Node, _stats: &mut Stats, _options: &CyclomaticOptions) {}
            }
         )+
     );
@@ -38,14 +87,14 @@ macro_rules! implement_metric_trait {
     (Loc, $($code:ident),+) => (
         $(
            impl Loc for $code {
-               fn compute(_node: &Node, _stats: &mut Stats, _is_func_space: bool, _is_unit: bool) {}
+               fn compute(_node: &Node, _stats: &mut Stats, _is_func_space: bool, _is_unit: bool, _options: &LocOptions) {}
            }
         )+
     );
     (Wmc, $($code:ident),+) => (
         $(
            impl Wmc for $code {
-               fn compute(_space_kind: SpaceKind, _cyclomatic: &cyclomatic::Stats, _stats: &mut Stats) {}
+               fn compute(_space_kind: SpaceKind, _cyclomatic: &cyclomatic::Stats, _cognitive: &cognitive::Stats, _stats: &mut Stats) {}
            }
         )+
     );
@@ -183,11 +232,41 @@ macro_rules! mk_action {
         /// ```
         #[inline(always)]
         pub fn get_function_spaces(lang: &LANG, source: Vec<u8>, path: &Path, pr: Option<Arc<PreprocResults>>) -> Option<FuncSpace> {
+            get_function_spaces_with_options(lang, source, path, pr, &MetricsOptions::default())
+        }
+
+        /// Returns all function spaces data of a code, computing the metrics
+        /// with the given options.
+        ///
+        /// # Examples
+        ///
+        /// ```
+        /// use std::path::PathBuf;
+        ///
+        /// use rust_code_analysis::cyclomatic::CyclomaticOptions;
+        /// use rust_code_analysis::{get_function_spaces_with_options, MetricsOptions, LANG};
+        ///
+        /// let source_code = "int a = 42;";
+        /// let language = LANG::Cpp;
+        ///
+        /// // The path to a dummy file used to contain the source code
+        /// let path = PathBuf::from("foo.c");
+        /// let source_as_vec = source_code.as_bytes().to_vec();
+        ///
+        /// let options = MetricsOptions {
+        ///     cyclomatic: CyclomaticOptions::preset("sonar").unwrap(),
+        ///     ..Default::default()
+        /// };
+        ///
+        /// get_function_spaces_with_options(&language, source_as_vec, &path, None, &options).unwrap();
+        /// ```
+        #[inline(always)]
+        pub fn get_function_spaces_with_options(lang: &LANG, source: Vec<u8>, path: &Path, pr: Option<Arc<PreprocResults>>, options: &MetricsOptions) -> Option<FuncSpace> {
             match lang {
                 $(
                     LANG::$camel => {
                         let parser = $parser::new(source, &path, pr);
-                        metrics(&parser, &path)
+                        metrics_with_options(&parser, &path, options)
                     },
                 )*
             }
@@ -224,6 +303,210 @@ macro_rules! mk_action {
                 )*
             }
         }
+
+        /// Returns the public API of a code.
+        ///
+        /// # Examples
+        ///
+        /// ```
+        /// use std::path::PathBuf;
+        ///
+        /// use rust_code_analysis::{get_public_api, LANG};
+        ///
+        /// # fn main() {
+        /// let source_code = "pub fn foo() {}";
+        /// let language = LANG::Rust;
+        ///
+        /// // The path to a dummy file used to contain the source code
+        /// let path = PathBuf::from("foo.rs");
+        /// let source_as_vec = source_code.as_bytes().to_vec();
+        ///
+        /// let api = get_public_api(&language, source_as_vec, &path, None);
+        /// assert_eq!(api.symbols[0].name, "foo");
+        /// # }
+        /// ```
+        #[inline(always)]
+        pub fn get_public_api(lang: &LANG, source: Vec<u8>, path: &Path, pr: Option<Arc<PreprocResults>>) -> PublicApiSymbols {
+            match lang {
+                $(
+                    LANG::$camel => {
+                        let parser = $parser::new(source, &path, pr);
+                        public_api_symbols(&parser, &path)
+                    },
+                )*
+            }
+        }
+
+        /// Returns the tokens of a code, in the order of the code.
+        ///
+        /// # Examples
+        ///
+        /// ```
+        /// use std::path::PathBuf;
+        ///
+        /// use rust_code_analysis::{get_tokens, LANG};
+        ///
+        /// # fn main() {
+        /// let source_code = "int a = 42;";
+        /// let language = LANG::Cpp;
+        ///
+        /// // The path to a dummy file used to contain the source code
+        /// let path = PathBuf::from("foo.c");
+        /// let source_as_vec = source_code.as_bytes().to_vec();
+        ///
+        /// let tokens = get_tokens(&language, source_as_vec, &path, None);
+        /// assert_eq!(tokens[1].text, "a");
+        /// # }
+        /// ```
+        #[inline(always)]
+        pub fn get_tokens(lang: &LANG, source: Vec<u8>, path: &Path, pr: Option<Arc<PreprocResults>>) -> Vec<Token> {
+            match lang {
+                $(
+                    LANG::$camel => {
+                        let parser = $parser::new(source, &path, pr);
+                        tokens(&parser)
+                    },
+                )*
+            }
+        }
+
+        /// Counts the occurrences of each type of nodes of a code, returning
+        /// one count per type in the same order, with the spans of the nodes.
+        ///
+        /// # Examples
+        ///
+        /// ```
+        /// use std::path::PathBuf;
+        ///
+        /// use rust_code_analysis::{get_node_counts, LANG};
+        ///
+        /// # fn main() {
+        /// let source_code = "fn foo() { bar().unwrap(); }";
+        /// let language = LANG::Rust;
+        ///
+        /// // The path to a dummy file used to contain the source code
+        /// let path = PathBuf::from("foo.rs");
+        /// let source_as_vec = source_code.as_bytes().to_vec();
+        ///
+        /// let kinds = ["call_expression".to_owned()];
+        /// let counts = get_node_counts(&language, source_as_vec, &path, None, &kinds);
+        /// assert_eq!(counts[0].count, 2);
+        /// # }
+        /// ```
+        #[inline(always)]
+        pub fn get_node_counts(lang: &LANG, source: Vec<u8>, path: &Path, pr: Option<Arc<PreprocResults>>, kinds: &[String]) -> Vec<NodeCount> {
+            match lang {
+                $(
+                    LANG::$camel => {
+                        let parser = $parser::new(source, &path, pr);
+                        count_nodes(&parser, kinds)
+                    },
+                )*
+            }
+        }
+
+        /// Returns the spans of the nodes of a code matching a structural
+        /// pattern, in the order of the code.
+        ///
+        /// # Examples
+        ///
+        /// ```
+        /// use std::path::PathBuf;
+        ///
+        /// use rust_code_analysis::{get_grep, Pattern, LANG};
+        ///
+        /// # fn main() {
+        /// let source_code = "void foo() { start: goto start; }";
+        /// let language = LANG::Cpp;
+        ///
+        /// // The path to a dummy file used to contain the source code
+        /// let path = PathBuf::from("foo.c");
+        /// let source_as_vec = source_code.as_bytes().to_vec();
+        ///
+        /// let pattern = Pattern::new("goto_statement").unwrap();
+        /// let spans = get_grep(&language, source_as_vec, &path, None, &pattern);
+        /// assert_eq!(spans[0].start_column, 21);
+        /// # }
+        /// ```
+        #[inline(always)]
+        pub fn get_grep(lang: &LANG, source: Vec<u8>, path: &Path, pr: Option<Arc<PreprocResults>>, pattern: &Pattern) -> Vec<NodeSpan> {
+            match lang {
+                $(
+                    LANG::$camel => {
+                        let parser = $parser::new(source, &path, pr);
+                        grep(&parser, pattern)
+                    },
+                )*
+            }
+        }
+
+        /// Returns the s-expression of the `AST` of a code, in the format
+        /// of tree-sitter, optionally with the byte range of each node.
+        ///
+        /// # Examples
+        ///
+        /// ```
+        /// use std::path::PathBuf;
+        ///
+        /// use rust_code_analysis::{get_sexp, LANG};
+        ///
+        /// # fn main() {
+        /// let source_code = "x = 1";
+        /// let language = LANG::Python;
+        ///
+        /// // The path to a dummy file used to contain the source code
+        /// let path = PathBuf::from("foo.py");
+        /// let source_as_vec = source_code.as_bytes().to_vec();
+        ///
+        /// let sexp = get_sexp(&language, source_as_vec, &path, None, true);
+        /// assert!(sexp.starts_with("(module [0..5]"));
+        /// # }
+        /// ```
+        #[inline(always)]
+        pub fn get_sexp(lang: &LANG, source: Vec<u8>, path: &Path, pr: Option<Arc<PreprocResults>>, byte_ranges: bool) -> String {
+            match lang {
+                $(
+                    LANG::$camel => {
+                        let parser = $parser::new(source, &path, pr);
+                        to_sexp(&parser.get_root(), byte_ranges)
+                    },
+                )*
+            }
+        }
+
+        /// Returns the functions defined in a code and the names it references,
+        /// used to find the functions never referenced in a set of files.
+        ///
+        /// # Examples
+        ///
+        /// ```
+        /// use std::path::PathBuf;
+        ///
+        /// use rust_code_analysis::{get_function_references, unreferenced_functions, LANG};
+        ///
+        /// # fn main() {
+        /// let source_code = "fn foo() {}";
+        /// let language = LANG::Rust;
+        ///
+        /// // The path to a dummy file used to contain the source code
+        /// let path = PathBuf::from("foo.rs");
+        /// let source_as_vec = source_code.as_bytes().to_vec();
+        ///
+        /// let references = get_function_references(&language, source_as_vec, &path, None);
+        /// assert_eq!(unreferenced_functions(&[references])[0].name, "foo");
+        /// # }
+        /// ```
+        #[inline(always)]
+        pub fn get_function_references(lang: &LANG, source: Vec<u8>, path: &Path, pr: Option<Arc<PreprocResults>>) -> FunctionReferences {
+            match lang {
+                $(
+                    LANG::$camel => {
+                        let parser = $parser::new(source, &path, pr);
+                        function_references(&parser, &path)
+                    },
+                )*
+            }
+        }
     };
 }
 
@@ -282,6 +565,35 @@ macro_rules! mk_emacs_mode {
     };
 }
 
+macro_rules! mk_interpreters {
+    ( $( ($camel:ident, [ $( $interpreter:expr ),* ]) ),* ) => {
+        /// Detects the language associated to the input interpreter.
+        ///
+        /// An interpreter is the program named in the shebang line
+        /// of a script, without its path and its version number.
+        ///
+        /// # Examples
+        ///
+        /// ```
+        /// use rust_code_analysis::get_from_interpreter;
+        ///
+        /// let interpreter = "python";
+        ///
+        /// get_from_interpreter(interpreter).unwrap();
+        /// ```
+        pub fn get_from_interpreter(interpreter: &str) -> Option<LANG>{
+            match interpreter {
+                $(
+                    $(
+                        $interpreter => Some(LANG::$camel),
+                    )*
+                )*
+                _ => None,
+            }
+        }
+    };
+}
+
 macro_rules! mk_code {
     ( $( ($camel:ident, $code:ident, $parser:ident, $name:ident, $docname:expr) ),* ) => {
         $(
@@ -308,16 +620,18 @@ macro_rules! mk_code {
 }
 
 macro_rules! mk_langs {
-    ( $( ($camel:ident, $description: expr, $display: expr, $code:ident, $parser:ident, $name:ident, [ $( $ext:ident ),* ], [ $( $emacs_mode:expr ),* ]) ),* ) => {
+    ( $( ($camel:ident, $description: expr, $display: expr, $code:ident, $parser:ident, $name:ident, [ $( $ext:ident ),* ], [ $( $emacs_mode:expr ),* ], [ $( $interpreter:expr ),* ]) ),* ) => {
         mk_lang!($( ($camel, $name, $display, $description) ),*);
         mk_action!($( ($camel, $parser) ),*);
         mk_extensions!($( ($camel, [ $( $ext ),* ]) ),*);
         mk_emacs_mode!($( ($camel, [ $( $emacs_mode ),* ]) ),*);
+        mk_interpreters!($( ($camel, [ $( $interpreter ),* ]) ),*);
         mk_code!($( ($camel, $code, $parser, $name, stringify!($camel)) ),*);
     };
 }
 
 pub(crate) use implement_metric_trait;
 pub(crate) use {
-    get_language, mk_action, mk_code, mk_emacs_mode, mk_extensions, mk_lang, mk_langs,
+    get_language, mk_action, mk_code, mk_emacs_mode, mk_extensions, mk_interpreters, mk_lang,
+    mk_langs,
 };
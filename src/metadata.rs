@@ -0,0 +1,135 @@
+use std::path::Path;
+use std::time::UNIX_EPOCH;
+
+use serde::Serialize;
+
+use crate::encoding::LineEnding;
+use crate::langs::LANG;
+use crate::spaces::{FNV_OFFSET_BASIS, fnv1a};
+
+/// The metadata of an analyzed file.
+///
+/// It is attached to the unit space of a file, so that the results
+/// can be cached and deduplicated without reading the file again.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize)]
+pub struct FileMetadata {
+    /// The size of the file in bytes
+    ///
+    /// When the file is not on the filesystem, such as a file
+    /// of an archive or a code sent to the server, it is the size
+    /// of the analyzed code
+    pub size: u64,
+    /// The 64-bit FNV-1a hash of the analyzed code, as 16 hexadecimal digits
+    pub hash: String,
+    /// The language of the code
+    pub language: &'static str,
+    /// The number of lines of the code
+    pub lines: usize,
+    /// The line endings of the code, before the lone `CR`s
+    /// are replaced with `LF`s for the analysis
+    pub line_ending: LineEnding,
+    /// The last modification time of the file,
+    /// in seconds since the Unix epoch
+    ///
+    /// It is `None` when the file is not on the filesystem
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub modified: Option<u64>,
+    /// The path of the first file with the same code,
+    /// when the file is analyzed in a batch
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub duplicate_of: Option<String>,
+    /// The number of non-blank lines of the template
+    /// of a Vue or a Svelte component
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub template_lines: Option<usize>,
+}
+
+/// Returns the hash of a code as in [`FileMetadata::hash`], so that the code
+/// of a file can be compared with the one of its cached results.
+///
+/// [`FileMetadata::hash`]: struct.FileMetadata.html#structfield.hash
+pub fn code_hash(code: &[u8]) -> String {
+    format!("{:016x}", fnv1a(FNV_OFFSET_BASIS, code))
+}
+
+// Returns the size and the last modification time of a file,
+// or `None` when the file is not on the filesystem
+fn file_stats(path: &Path) -> Option<(u64, Option<u64>)> {
+    let file = std::fs::metadata(path).ok().filter(|file| file.is_file())?;
+    let modified = file
+        .modified()
+        .ok()
+        .and_then(|time| time.duration_since(UNIX_EPOCH).ok())
+        .map(|duration| duration.as_secs());
+    Some((file.len(), modified))
+}
+
+impl FileMetadata {
+    /// Computes the metadata of a file from its analyzed code.
+    pub fn new(path: &Path, code: &[u8], language: LANG) -> Self {
+        let (size, modified) = file_stats(path).unwrap_or((code.len() as u64, None));
+        let lines = code.iter().filter(|byte| **byte == b'\n').count()
+            + usize::from(code.last().is_some_and(|byte| *byte != b'\n'));
+
+        Self {
+            size,
+            hash: code_hash(code),
+            language: language.get_name(),
+            lines,
+            line_ending: LineEnding::detect(code),
+            modified,
+            duplicate_of: None,
+            template_lines: None,
+        }
+    }
+
+    // Returns the metadata of another file with the same code
+    pub(crate) fn for_file(&self, path: &Path) -> Self {
+        let (size, modified) = file_stats(path).unwrap_or((self.size, None));
+        Self {
+            size,
+            modified,
+            ..self.clone()
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use pretty_assertions::assert_eq;
+
+    use super::*;
+
+    #[test]
+    fn metadata_of_code() {
+        let metadata = FileMetadata::new(Path::new("missing.py"), b"a = 1\nb = 2", LANG::Python);
+        assert_eq!(
+            metadata,
+            FileMetadata {
+                size: 11,
+                hash: format!("{:016x}", fnv1a(FNV_OFFSET_BASIS, b"a = 1\nb = 2")),
+                language: "python",
+                lines: 2,
+                line_ending: LineEnding::Lf,
+                modified: None,
+                duplicate_of: None,
+                template_lines: None,
+            }
+        );
+    }
+
+    #[test]
+    fn metadata_of_file() {
+        let dir = std::env::temp_dir().join("test_file_metadata");
+        std::fs::create_dir_all(&dir).unwrap();
+        let path = dir.join("foo.rs");
+        std::fs::write(&path, b"\xEF\xBB\xBFfn main() {}\n").unwrap();
+
+        // The size is the one of the file, whose BOM is not analyzed
+        let metadata = FileMetadata::new(&path, b"fn main() {}\n", LANG::Rust);
+        assert_eq!(metadata.size, 16);
+        assert_eq!(metadata.lines, 1);
+        assert_eq!(metadata.language, "rust");
+        assert!(metadata.modified.is_some_and(|modified| modified > 0));
+    }
+}
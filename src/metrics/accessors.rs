@@ -0,0 +1,323 @@
+use serde::Serialize;
+use serde::ser::{SerializeStruct, Serializer};
+use std::fmt;
+
+use crate::checker::Checker;
+use crate::getter::Getter;
+use crate::langs::*;
+use crate::macros::implement_metric_trait;
+use crate::node::Node;
+use crate::*;
+
+/// The `Accessors` metric.
+///
+/// This metric counts the property accessors of a space, that is its
+/// getters and its setters, and the functions which are not accessors.
+/// Accessors are mostly boilerplate, so separating them from the other
+/// functions keeps the count of the methods of a class meaningful.
+///
+/// The accessors are the `Python` methods decorated with `@property`
+/// or with a `.setter`, the `get` and `set` methods of `JavaScript`
+/// and `TypeScript`, the `get` and `set` accessors of the `Kotlin`
+/// properties and the `Java` methods following the bean conventions,
+/// such as `getName()`, `isEmpty()` or `setName(name)`.
+#[derive(Clone, Debug, Default)]
+pub struct Stats {
+    getters: usize,
+    setters: usize,
+    functions: usize,
+    getters_sum: usize,
+    setters_sum: usize,
+    functions_sum: usize,
+    is_enabled: bool,
+}
+
+impl Serialize for Stats {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        let mut st = serializer.serialize_struct("accessors", 4)?;
+        st.serialize_field("getters", &self.getters_sum())?;
+        st.serialize_field("setters", &self.setters_sum())?;
+        st.serialize_field("total", &self.total())?;
+        st.serialize_field("functions", &self.functions_sum())?;
+        st.end()
+    }
+}
+
+impl fmt::Display for Stats {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "getters: {}, setters: {}, total: {}, functions: {}",
+            self.getters_sum(),
+            self.setters_sum(),
+            self.total(),
+            self.functions_sum()
+        )
+    }
+}
+
+impl Stats {
+    /// Merges a second `Accessors` metric into the first one
+    pub fn merge(&mut self, other: &Stats) {
+        self.getters_sum += other.getters_sum;
+        self.setters_sum += other.setters_sum;
+        self.functions_sum += other.functions_sum;
+    }
+
+    /// Returns the number of getters in a space.
+    #[inline(always)]
+    pub fn getters(&self) -> f64 {
+        self.getters as f64
+    }
+
+    /// Returns the number of setters in a space.
+    #[inline(always)]
+    pub fn setters(&self) -> f64 {
+        self.setters as f64
+    }
+
+    /// Returns the number of functions which are not accessors in a space.
+    #[inline(always)]
+    pub fn functions(&self) -> f64 {
+        self.functions as f64
+    }
+
+    /// Returns the number of getters sum in a space.
+    #[inline(always)]
+    pub fn getters_sum(&self) -> f64 {
+        self.getters_sum as f64
+    }
+
+    /// Returns the number of setters sum in a space.
+    #[inline(always)]
+    pub fn setters_sum(&self) -> f64 {
+        self.setters_sum as f64
+    }
+
+    /// Returns the number of functions which are not accessors
+    /// sum in a space.
+    #[inline(always)]
+    pub fn functions_sum(&self) -> f64 {
+        self.functions_sum as f64
+    }
+
+    /// Returns the total number of accessors in a space.
+    #[inline(always)]
+    pub fn total(&self) -> f64 {
+        self.getters_sum() + self.setters_sum()
+    }
+
+    #[inline(always)]
+    pub(crate) fn compute_sum(&mut self) {
+        self.getters_sum += self.getters;
+        self.setters_sum += self.setters;
+        self.functions_sum += self.functions;
+    }
+
+    // Checks if the `Accessors` metric is disabled
+    #[inline(always)]
+    pub(crate) fn is_disabled(&self) -> bool {
+        !self.is_enabled
+    }
+
+    // Counts a function, either as an accessor or as a regular function,
+    // through the flags found in its signature
+    fn add_function<T: Checker + Getter>(&mut self, node: &Node, code: &[u8]) {
+        if !T::is_func(node) {
+            return;
+        }
+        let flags = T::get_function_flags(node, code);
+        if flags.is_getter {
+            self.getters += 1;
+        } else if flags.is_setter {
+            self.setters += 1;
+        } else {
+            self.functions += 1;
+        }
+    }
+}
+
+pub trait Accessors
+where
+    Self: Checker,
+{
+    fn compute(node: &Node, code: &[u8], stats: &mut Stats);
+}
+
+impl Accessors for PythonCode {
+    fn compute(node: &Node, code: &[u8], stats: &mut Stats) {
+        // Enables the `Accessors` metric for all the spaces
+        stats.is_enabled = true;
+        stats.add_function::<Self>(node, code);
+    }
+}
+
+impl Accessors for MozjsCode {
+    fn compute(node: &Node, code: &[u8], stats: &mut Stats) {
+        stats.is_enabled = true;
+        stats.add_function::<Self>(node, code);
+    }
+}
+
+impl Accessors for JavascriptCode {
+    fn compute(node: &Node, code: &[u8], stats: &mut Stats) {
+        stats.is_enabled = true;
+        stats.add_function::<Self>(node, code);
+    }
+}
+
+impl Accessors for TypescriptCode {
+    fn compute(node: &Node, code: &[u8], stats: &mut Stats) {
+        stats.is_enabled = true;
+        stats.add_function::<Self>(node, code);
+    }
+}
+
+impl Accessors for TsxCode {
+    fn compute(node: &Node, code: &[u8], stats: &mut Stats) {
+        stats.is_enabled = true;
+        stats.add_function::<Self>(node, code);
+    }
+}
+
+impl Accessors for JavaCode {
+    fn compute(node: &Node, code: &[u8], stats: &mut Stats) {
+        stats.is_enabled = true;
+        stats.add_function::<Self>(node, code);
+    }
+}
+
+impl Accessors for KotlinCode {
+    fn compute(node: &Node, code: &[u8], stats: &mut Stats) {
+        use Kotlin::*;
+
+        stats.is_enabled = true;
+
+        // The accessors of the properties are not functions,
+        // so they are counted in the space of their class
+        match node.kind_id().into() {
+            Getter => stats.getters += 1,
+            Setter => stats.setters += 1,
+            _ => stats.add_function::<Self>(node, code),
+        }
+    }
+}
+
+implement_metric_trait!(Accessors, RustCode, CppCode, PreprocCode, CcommentCode);
+
+#[cfg(test)]
+mod tests {
+    use crate::tools::check_metrics;
+
+    use super::*;
+
+    #[test]
+    fn python_properties() {
+        check_metrics::<PythonParser>(
+            "class Circle:
+                def __init__(self, radius):
+                    self._radius = radius
+
+                @property
+                def radius(self):
+                    return self._radius
+
+                @radius.setter
+                def radius(self, value):
+                    self._radius = value
+
+                @functools.cached_property
+                def area(self):
+                    return 3.14 * self._radius ** 2
+
+                def scale(self, factor):
+                    self._radius *= factor",
+            "foo.py",
+            |metric| {
+                insta::assert_json_snapshot!(
+                    metric.accessors,
+                    @r###"
+                    {
+                      "getters": 2.0,
+                      "setters": 1.0,
+                      "total": 3.0,
+                      "functions": 2.0
+                    }"###
+                );
+            },
+        );
+    }
+
+    #[test]
+    fn javascript_accessors() {
+        check_metrics::<JavascriptParser>(
+            "class Circle {
+                constructor(radius) { this._radius = radius; }
+                get radius() { return this._radius; }
+                set radius(value) { this._radius = value; }
+                get() { return this; }
+            }",
+            "foo.js",
+            |metric| {
+                assert_eq!(metric.accessors.getters_sum(), 1.);
+                assert_eq!(metric.accessors.setters_sum(), 1.);
+                assert_eq!(metric.accessors.functions_sum(), 2.);
+            },
+        );
+    }
+
+    #[test]
+    fn java_beans() {
+        check_metrics::<JavaParser>(
+            "class Person {
+                private String name;
+                private boolean active;
+
+                public String getName() { return name; }
+                public boolean isActive() { return active; }
+                public void setName(String name) { this.name = name; }
+                public void getReady() {}
+                public String getter() { return name; }
+                public void settle(int a, int b) {}
+                public static Person getInstance() { return null; }
+            }",
+            "foo.java",
+            |metric| {
+                insta::assert_json_snapshot!(
+                    metric.accessors,
+                    @r###"
+                    {
+                      "getters": 2.0,
+                      "setters": 1.0,
+                      "total": 3.0,
+                      "functions": 4.0
+                    }"###
+                );
+            },
+        );
+    }
+
+    #[test]
+    fn kotlin_property_accessors() {
+        check_metrics::<KotlinParser>(
+            "class Person {
+                var name: String = \"\"
+                    get() = field.trim()
+                    set(value) { field = value }
+                val upper: String
+                    get() = name.uppercase()
+
+                fun greet() = \"Hello $name\"
+            }",
+            "foo.kt",
+            |metric| {
+                assert_eq!(metric.accessors.getters_sum(), 2.);
+                assert_eq!(metric.accessors.setters_sum(), 1.);
+                assert_eq!(metric.accessors.functions_sum(), 1.);
+            },
+        );
+    }
+}
@@ -0,0 +1,217 @@
+use serde::Serialize;
+use serde::ser::{SerializeStruct, Serializer};
+use std::fmt;
+
+use crate::checker::Checker;
+use crate::langs::*;
+use crate::macros::implement_metric_trait;
+use crate::node::Node;
+use crate::*;
+
+/// The `Annotations` metric.
+///
+/// This metric counts the annotations of a space, that is the annotations
+/// of its declaration, of its parameters and of its fields, and the
+/// exceptions declared in the `throws` clauses of its methods.
+/// Heavily annotated code moves part of its behavior into frameworks,
+/// while long `throws` clauses spread the error handling to the callers.
+#[derive(Clone, Debug, Default)]
+pub struct Stats {
+    annotations: usize,
+    throws: usize,
+    annotations_sum: usize,
+    throws_sum: usize,
+    is_enabled: bool,
+}
+
+impl Serialize for Stats {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        let mut st = serializer.serialize_struct("annotations", 2)?;
+        st.serialize_field("annotations", &self.annotations_sum())?;
+        st.serialize_field("throws", &self.throws_sum())?;
+        st.end()
+    }
+}
+
+impl fmt::Display for Stats {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "annotations: {}, throws: {}",
+            self.annotations_sum(),
+            self.throws_sum()
+        )
+    }
+}
+
+impl Stats {
+    /// Merges a second `Annotations` metric into the first one
+    pub fn merge(&mut self, other: &Stats) {
+        self.annotations_sum += other.annotations_sum;
+        self.throws_sum += other.throws_sum;
+    }
+
+    /// Returns the number of annotations in a space.
+    #[inline(always)]
+    pub fn annotations(&self) -> f64 {
+        self.annotations as f64
+    }
+
+    /// Returns the number of exceptions declared
+    /// in the `throws` clauses of a space.
+    #[inline(always)]
+    pub fn throws(&self) -> f64 {
+        self.throws as f64
+    }
+
+    /// Returns the number of annotations sum in a space.
+    #[inline(always)]
+    pub fn annotations_sum(&self) -> f64 {
+        self.annotations_sum as f64
+    }
+
+    /// Returns the number of declared exceptions sum in a space.
+    #[inline(always)]
+    pub fn throws_sum(&self) -> f64 {
+        self.throws_sum as f64
+    }
+
+    #[inline(always)]
+    pub(crate) fn compute_sum(&mut self) {
+        self.annotations_sum += self.annotations;
+        self.throws_sum += self.throws;
+    }
+
+    // Checks if the `Annotations` metric is disabled
+    #[inline(always)]
+    pub(crate) fn is_disabled(&self) -> bool {
+        !self.is_enabled
+    }
+}
+
+pub trait Annotations
+where
+    Self: Checker,
+{
+    fn compute(node: &Node, stats: &mut Stats);
+}
+
+impl Annotations for JavaCode {
+    fn compute(node: &Node, stats: &mut Stats) {
+        use Java::*;
+
+        // Enables the `Annotations` metric for all the spaces
+        stats.is_enabled = true;
+
+        match node.kind_id().into() {
+            MarkerAnnotation | Annotation => {
+                stats.annotations += 1;
+            }
+            Throws => {
+                stats.throws += node
+                    .children()
+                    .filter(|child| !matches!(child.kind_id().into(), Throws2 | COMMA))
+                    .count();
+            }
+            _ => {}
+        }
+    }
+}
+
+implement_metric_trait!(
+    Annotations,
+    PythonCode,
+    MozjsCode,
+    JavascriptCode,
+    TypescriptCode,
+    TsxCode,
+    RustCode,
+    CppCode,
+    PreprocCode,
+    CcommentCode,
+    KotlinCode
+);
+
+#[cfg(test)]
+mod tests {
+    use crate::tools::{check_func_space, check_metrics};
+
+    use super::*;
+
+    #[test]
+    fn java_no_annotations() {
+        check_metrics::<JavaParser>("class X {}", "foo.java", |metric| {
+            insta::assert_json_snapshot!(
+                metric.annotations,
+                @r###"
+                {
+                  "annotations": 0.0,
+                  "throws": 0.0
+                }"###
+            );
+        });
+    }
+
+    #[test]
+    fn java_annotations() {
+        check_metrics::<JavaParser>(
+            "@Entity
+            @Table(name = \"users\")
+            class User {
+                @Id private long id;
+                @Override
+                public String toString() { return \"\"; }
+                void load(@NotNull String path) throws IOException {}
+                void save() throws IOException, SQLException {}
+            }",
+            "foo.java",
+            |metric| {
+                // Spaces: 5
+                insta::assert_json_snapshot!(
+                    metric.annotations,
+                    @r###"
+                    {
+                      "annotations": 5.0,
+                      "throws": 3.0
+                    }"###
+                );
+            },
+        );
+    }
+
+    #[test]
+    fn java_annotations_methods() {
+        check_func_space::<JavaParser, _>(
+            "class X {
+                @Deprecated
+                @SuppressWarnings(\"unchecked\")
+                X(int a) throws Exception {}
+                void f() {}
+            }",
+            "foo.java",
+            |func_space| {
+                let class = &func_space.spaces[0];
+                assert_eq!(class.metrics.annotations.annotations(), 0.);
+                assert_eq!(class.metrics.annotations.annotations_sum(), 2.);
+
+                let constructor = &class.spaces[0].metrics.annotations;
+                assert_eq!(constructor.annotations(), 2.);
+                assert_eq!(constructor.throws(), 1.);
+
+                let method = &class.spaces[1].metrics.annotations;
+                assert_eq!(method.annotations(), 0.);
+                assert_eq!(method.throws(), 0.);
+            },
+        );
+    }
+
+    #[test]
+    fn python_no_annotations() {
+        check_metrics::<PythonParser>("a = 42", "foo.py", |metric| {
+            assert!(metric.annotations.is_disabled());
+        });
+    }
+}
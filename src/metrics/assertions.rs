@@ -0,0 +1,417 @@
+use serde::Serialize;
+use serde::ser::{SerializeStruct, Serializer};
+use std::fmt;
+
+use crate::checker::Checker;
+use crate::langs::*;
+use crate::macros::implement_metric_trait;
+use crate::node::Node;
+use crate::*;
+
+/// The `Assertions` metric.
+///
+/// This metric counts the assertions of a function/method,
+/// such as the `assert` statements, the `assert_eq!` macros or
+/// the calls to the assertion APIs of the test frameworks,
+/// like `self.assertEqual`, `expect(value)` or `ASSERT_EQ`.
+/// Along with the number of test functions and their lines of code,
+/// it measures how thoroughly a code is tested.
+#[derive(Debug, Clone)]
+pub struct Stats {
+    assertions: usize,
+    assertions_sum: usize,
+    total_space_functions: usize,
+    assertions_min: usize,
+    assertions_max: usize,
+    is_enabled: bool,
+}
+
+impl Default for Stats {
+    fn default() -> Self {
+        Self {
+            assertions: 0,
+            assertions_sum: 0,
+            total_space_functions: 1,
+            assertions_min: usize::MAX,
+            assertions_max: 0,
+            is_enabled: false,
+        }
+    }
+}
+
+impl Serialize for Stats {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        let mut st = serializer.serialize_struct("assertions", 4)?;
+        st.serialize_field("sum", &self.assertions_sum())?;
+        st.serialize_field("average", &self.assertions_average())?;
+        st.serialize_field("min", &self.assertions_min())?;
+        st.serialize_field("max", &self.assertions_max())?;
+        st.end()
+    }
+}
+
+impl fmt::Display for Stats {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "sum: {}, average: {}, min: {}, max: {}",
+            self.assertions_sum(),
+            self.assertions_average(),
+            self.assertions_min(),
+            self.assertions_max()
+        )
+    }
+}
+
+impl Stats {
+    /// Merges a second `Assertions` metric into the first one
+    pub fn merge(&mut self, other: &Stats) {
+        self.assertions_max = self.assertions_max.max(other.assertions_max);
+        self.assertions_min = self.assertions_min.min(other.assertions_min);
+        self.assertions_sum += other.assertions_sum;
+    }
+
+    /// Returns the `Assertions` metric value
+    pub fn assertions(&self) -> f64 {
+        self.assertions as f64
+    }
+
+    /// Returns the `Assertions` metric sum value
+    pub fn assertions_sum(&self) -> f64 {
+        self.assertions_sum as f64
+    }
+
+    /// Returns the `Assertions` metric minimum value
+    pub fn assertions_min(&self) -> f64 {
+        self.assertions_min as f64
+    }
+
+    /// Returns the `Assertions` metric maximum value
+    pub fn assertions_max(&self) -> f64 {
+        self.assertions_max as f64
+    }
+
+    /// Returns the `Assertions` metric average value
+    ///
+    /// This value is computed dividing the `Assertions` value
+    /// for the total number of functions/closures in a space.
+    ///
+    /// If there are no functions in a code, its value is `NAN`.
+    pub fn assertions_average(&self) -> f64 {
+        self.assertions_sum() / self.total_space_functions as f64
+    }
+
+    #[inline(always)]
+    pub(crate) fn compute_minmax(&mut self) {
+        self.assertions_max = self.assertions_max.max(self.assertions);
+        self.assertions_min = self.assertions_min.min(self.assertions);
+        self.assertions_sum += self.assertions;
+    }
+
+    pub(crate) fn finalize(&mut self, total_space_functions: usize) {
+        self.total_space_functions = total_space_functions;
+    }
+
+    // Checks if the `Assertions` metric is disabled
+    #[inline(always)]
+    pub(crate) fn is_disabled(&self) -> bool {
+        !self.is_enabled
+    }
+
+    // Counts a call when the name of the called function
+    // or macro is the name of an assertion
+    fn add_call(&mut self, callee: Option<Node>, code: &[u8]) {
+        if callee
+            .and_then(|callee| callee.utf8_text(code))
+            .is_some_and(is_assertion)
+        {
+            self.assertions += 1;
+        }
+    }
+}
+
+// Checks if the last segment of a path, such as `self.assertEqual`
+// or `pretty_assertions::assert_eq`, is the name of an assertion
+fn is_assertion(path: &str) -> bool {
+    let name = path.rsplit(['.', ':', '>']).next().unwrap_or(path).trim();
+    name.starts_with("assert")
+        || name.starts_with("debug_assert")
+        || name.starts_with("ASSERT")
+        || name.starts_with("EXPECT_")
+}
+
+pub trait Assertions
+where
+    Self: Checker,
+{
+    fn compute(node: &Node, code: &[u8], stats: &mut Stats);
+}
+
+impl Assertions for PythonCode {
+    fn compute(node: &Node, code: &[u8], stats: &mut Stats) {
+        use Python::*;
+
+        // Enables the `Assertions` metric for all the spaces
+        stats.is_enabled = true;
+
+        match node.kind_id().into() {
+            AssertStatement => stats.assertions += 1,
+            // `self.assertEqual(a, b)`, `np.testing.assert_allclose(a, b)`
+            Call => stats.add_call(node.child_by_field_name("function"), code),
+            _ => {}
+        }
+    }
+}
+
+// The assertions of `Node.js` and `Chai`, such as `assert(value)`
+// and `assert.equal(a, b)`, and the expectations of `Jest`,
+// such as `expect(value).toBe(1)`
+macro_rules! js_assertions {
+    ($lang:ident) => {
+        fn compute(node: &Node, code: &[u8], stats: &mut Stats) {
+            use $lang::*;
+
+            stats.is_enabled = true;
+
+            if matches!(node.kind_id().into(), CallExpression | CallExpression2) {
+                let function = node.child_by_field_name("function");
+                if function
+                    .and_then(|function| function.utf8_text(code))
+                    .is_some_and(|name| name == "expect" || name.starts_with("assert."))
+                {
+                    stats.assertions += 1;
+                } else {
+                    stats.add_call(function, code);
+                }
+            }
+        }
+    };
+}
+
+impl Assertions for MozjsCode {
+    js_assertions!(Mozjs);
+}
+
+impl Assertions for JavascriptCode {
+    js_assertions!(Javascript);
+}
+
+impl Assertions for TypescriptCode {
+    js_assertions!(Typescript);
+}
+
+impl Assertions for TsxCode {
+    js_assertions!(Tsx);
+}
+
+impl Assertions for JavaCode {
+    fn compute(node: &Node, code: &[u8], stats: &mut Stats) {
+        use Java::*;
+
+        stats.is_enabled = true;
+
+        match node.kind_id().into() {
+            AssertStatement => stats.assertions += 1,
+            // `assertEquals(a, b)`, `Assertions.assertThrows(...)`
+            MethodInvocation => stats.add_call(node.child_by_field_name("name"), code),
+            _ => {}
+        }
+    }
+}
+
+impl Assertions for KotlinCode {
+    fn compute(node: &Node, code: &[u8], stats: &mut Stats) {
+        stats.is_enabled = true;
+
+        // `assert(value)`, `assertEquals(a, b)`, `kotlin.test.assertTrue(value)`
+        if matches!(node.kind_id().into(), Kotlin::CallExpression) {
+            stats.add_call(node.child(0), code);
+        }
+    }
+}
+
+impl Assertions for RustCode {
+    fn compute(node: &Node, code: &[u8], stats: &mut Stats) {
+        stats.is_enabled = true;
+
+        // `assert!`, `assert_eq!`, `debug_assert_ne!`, `insta::assert_snapshot!`
+        if matches!(node.kind_id().into(), Rust::MacroInvocation) {
+            stats.add_call(node.child_by_field_name("macro"), code);
+        }
+    }
+}
+
+impl Assertions for CppCode {
+    fn compute(node: &Node, code: &[u8], stats: &mut Stats) {
+        use Cpp::*;
+
+        stats.is_enabled = true;
+
+        match node.kind_id().into() {
+            StaticAssertDeclaration => stats.assertions += 1,
+            // `assert(value)` and the `GoogleTest` macros,
+            // such as `ASSERT_EQ(a, b)` or `EXPECT_TRUE(value)`
+            CallExpression | CallExpression2 => {
+                stats.add_call(node.child_by_field_name("function"), code)
+            }
+            _ => {}
+        }
+    }
+}
+
+implement_metric_trait!(Assertions, PreprocCode, CcommentCode);
+
+#[cfg(test)]
+mod tests {
+    use crate::tools::check_metrics;
+
+    use super::*;
+
+    #[test]
+    fn python_assertions() {
+        check_metrics::<PythonParser>(
+            "import unittest
+
+            class TestCircle(unittest.TestCase):
+                def test_area(self):
+                    c = Circle(2)
+                    self.assertEqual(c.area(), 12.56)
+                    self.assertTrue(c.radius > 0)
+
+                def test_scale(self):
+                    c = Circle(2)
+                    c.scale(2)
+                    assert c.radius == 4
+                    np.testing.assert_allclose(c.area(), 50.24)
+                    mock.assert_called_once()
+
+            def helper():
+                return Circle(1)",
+            "foo.py",
+            |metric| {
+                insta::assert_json_snapshot!(
+                    metric.assertions,
+                    @r###"
+                    {
+                      "sum": 5.0,
+                      "average": 1.6666666666666667,
+                      "min": 0.0,
+                      "max": 3.0
+                    }"###
+                );
+            },
+        );
+    }
+
+    #[test]
+    fn rust_assertions() {
+        check_metrics::<RustParser>(
+            "fn check(a: i32) {
+                debug_assert!(a > 0);
+            }
+
+            #[test]
+            fn test_check() {
+                assert_eq!(1 + 1, 2);
+                assert!(true);
+                pretty_assertions::assert_ne!(1, 2);
+                println!(\"done\");
+            }",
+            "foo.rs",
+            |metric| {
+                insta::assert_json_snapshot!(
+                    metric.assertions,
+                    @r###"
+                    {
+                      "sum": 4.0,
+                      "average": 2.0,
+                      "min": 0.0,
+                      "max": 3.0
+                    }"###
+                );
+            },
+        );
+    }
+
+    #[test]
+    fn javascript_assertions() {
+        check_metrics::<JavascriptParser>(
+            "test('area', () => {
+                expect(area(2)).toBe(12.56);
+                expect(area(0)).toBe(0);
+            });
+            test('scale', () => {
+                assert.equal(scale(2, 2), 4);
+                assert(scale(1, 1));
+                console.log('done');
+            });",
+            "foo.js",
+            |metric| {
+                assert_eq!(metric.assertions.assertions_sum(), 4.);
+                assert_eq!(metric.assertions.assertions_max(), 2.);
+            },
+        );
+    }
+
+    #[test]
+    fn java_assertions() {
+        check_metrics::<JavaParser>(
+            "class CircleTest {
+                @Test
+                void area() {
+                    assertEquals(12.56, new Circle(2).area());
+                    Assertions.assertThrows(Exception.class, () -> new Circle(-1));
+                    assertThat(list).isEmpty();
+                }
+                void check(int a) {
+                    assert a > 0;
+                    System.out.println(a);
+                }
+            }",
+            "foo.java",
+            |metric| {
+                assert_eq!(metric.assertions.assertions_sum(), 4.);
+            },
+        );
+    }
+
+    #[test]
+    fn kotlin_assertions() {
+        check_metrics::<KotlinParser>(
+            "fun testArea() {
+                assertEquals(12.56, Circle(2).area())
+                kotlin.test.assertTrue(Circle(2).radius > 0)
+                assert(true)
+                println(\"done\")
+            }",
+            "foo.kt",
+            |metric| {
+                assert_eq!(metric.assertions.assertions_sum(), 3.);
+            },
+        );
+    }
+
+    #[test]
+    fn cpp_assertions() {
+        check_metrics::<CppParser>(
+            "static_assert(sizeof(int) == 4, \"int\");
+
+            TEST(Circle, Area) {
+                ASSERT_EQ(area(2), 12.56);
+                EXPECT_TRUE(area(0) == 0);
+            }
+
+            void check(int a) {
+                assert(a > 0);
+                printf(\"%d\", a);
+            }",
+            "foo.cpp",
+            |metric| {
+                assert_eq!(metric.assertions.assertions_sum(), 4.);
+            },
+        );
+    }
+}
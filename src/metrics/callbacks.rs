@@ -0,0 +1,280 @@
+use serde::Serialize;
+use serde::ser::{SerializeStruct, Serializer};
+use std::fmt;
+
+use crate::checker::Checker;
+use crate::langs::*;
+use crate::macros::implement_metric_trait;
+use crate::node::Node;
+use crate::*;
+
+/// The `Callbacks` metric.
+///
+/// This metric measures the maximum nesting level of the callbacks
+/// of a space, that is the functions passed as arguments to a call,
+/// and the length of its longest chain of `.then()` calls.
+/// Deeply nested callbacks and long promise chains make the control flow
+/// hard to follow, even when the `Cyclomatic` metric stays low.
+#[derive(Clone, Debug, Default)]
+pub struct Stats {
+    nesting: usize,
+    chain: usize,
+    nesting_max: usize,
+    chain_max: usize,
+    is_enabled: bool,
+}
+
+impl Serialize for Stats {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        let mut st = serializer.serialize_struct("callbacks", 2)?;
+        st.serialize_field("nesting_max", &self.nesting_max())?;
+        st.serialize_field("chain_max", &self.chain_max())?;
+        st.end()
+    }
+}
+
+impl fmt::Display for Stats {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "nesting_max: {}, chain_max: {}",
+            self.nesting_max(),
+            self.chain_max()
+        )
+    }
+}
+
+impl Stats {
+    /// Merges a second `Callbacks` metric into the first one
+    pub fn merge(&mut self, other: &Stats) {
+        self.nesting_max = self.nesting_max.max(other.nesting_max);
+        self.chain_max = self.chain_max.max(other.chain_max);
+    }
+
+    /// Returns the maximum nesting level of the callbacks in a space.
+    ///
+    /// A callback which is not contained in another one
+    /// has a nesting level of 1.
+    #[inline(always)]
+    pub fn nesting(&self) -> f64 {
+        self.nesting as f64
+    }
+
+    /// Returns the length of the longest chain of `.then()` calls
+    /// in a space.
+    ///
+    /// The `.catch()` and `.finally()` calls do not break a chain,
+    /// but they are not counted.
+    #[inline(always)]
+    pub fn chain(&self) -> f64 {
+        self.chain as f64
+    }
+
+    /// Returns the maximum nesting level of the callbacks
+    /// in a space and in its subspaces.
+    #[inline(always)]
+    pub fn nesting_max(&self) -> f64 {
+        self.nesting_max as f64
+    }
+
+    /// Returns the length of the longest chain of `.then()` calls
+    /// in a space and in its subspaces.
+    #[inline(always)]
+    pub fn chain_max(&self) -> f64 {
+        self.chain_max as f64
+    }
+
+    #[inline(always)]
+    pub(crate) fn compute_sum(&mut self) {
+        self.nesting_max = self.nesting_max.max(self.nesting);
+        self.chain_max = self.chain_max.max(self.chain);
+    }
+
+    // Checks if the `Callbacks` metric is disabled
+    #[inline(always)]
+    pub(crate) fn is_disabled(&self) -> bool {
+        !self.is_enabled
+    }
+}
+
+pub trait Callbacks
+where
+    Self: Checker,
+{
+    fn compute(node: &Node, code: &[u8], stats: &mut Stats);
+}
+
+// Returns the name of the method called by a call expression
+// and the object it is called on
+fn get_method<'a>(call: &Node<'a>, code: &'a [u8]) -> Option<(&'a str, Node<'a>)> {
+    let function = call.child(0)?;
+    let object = function.child_by_field_name("object")?;
+    let property = function.child_by_field_name("property")?;
+    Some((property.utf8_text(code)?, object))
+}
+
+macro_rules! js_callbacks {
+    ($lang:ident) => {
+        fn compute(node: &Node, code: &[u8], stats: &mut Stats) {
+            use $lang::*;
+
+            // Enables the `Callbacks` metric for all the spaces
+            stats.is_enabled = true;
+
+            let is_callback = |node: &Node| {
+                (Self::is_func(node) || Self::is_closure(node))
+                    && node
+                        .parent()
+                        .is_some_and(|parent| parent.kind_id() == Arguments)
+            };
+            let is_chain_link = |node: &Node| {
+                matches!(node.kind_id().into(), CallExpression | CallExpression2)
+                    && get_method(node, code)
+                        .is_some_and(|(name, _)| matches!(name, "then" | "catch" | "finally"))
+            };
+
+            if is_callback(node) {
+                let mut nesting = 1;
+                let mut ancestor = node.parent();
+                while let Some(parent) = ancestor {
+                    if is_callback(&parent) {
+                        nesting += 1;
+                    }
+                    ancestor = parent.parent();
+                }
+                stats.nesting = stats.nesting.max(nesting);
+            } else if is_chain_link(node) {
+                // Only the outermost call of a chain is measured
+                let is_outermost =
+                    node.parent()
+                        .and_then(|member| member.parent())
+                        .is_none_or(|call| {
+                            !is_chain_link(&call)
+                                || get_method(&call, code)
+                                    .is_none_or(|(_, object)| object.id() != node.id())
+                        });
+                if !is_outermost {
+                    return;
+                }
+
+                let mut chain = 0;
+                let mut link = Some(*node);
+                while let Some(call) = link.filter(is_chain_link) {
+                    let (name, object) = get_method(&call, code).unwrap();
+                    if name == "then" {
+                        chain += 1;
+                    }
+                    link = Some(object);
+                }
+                stats.chain = stats.chain.max(chain);
+            }
+        }
+    };
+}
+
+impl Callbacks for MozjsCode {
+    js_callbacks!(Mozjs);
+}
+
+impl Callbacks for JavascriptCode {
+    js_callbacks!(Javascript);
+}
+
+impl Callbacks for TypescriptCode {
+    js_callbacks!(Typescript);
+}
+
+impl Callbacks for TsxCode {
+    js_callbacks!(Tsx);
+}
+
+implement_metric_trait!(
+    Callbacks,
+    PythonCode,
+    RustCode,
+    CppCode,
+    JavaCode,
+    PreprocCode,
+    CcommentCode,
+    KotlinCode
+);
+
+#[cfg(test)]
+mod tests {
+    use crate::tools::{check_func_space, check_metrics};
+
+    use super::*;
+
+    #[test]
+    fn javascript_no_callbacks() {
+        check_metrics::<JavascriptParser>("var a = 42;", "foo.js", |metric| {
+            insta::assert_json_snapshot!(
+                metric.callbacks,
+                @r###"
+                {
+                  "nesting_max": 0.0,
+                  "chain_max": 0.0
+                }"###
+            );
+        });
+    }
+
+    #[test]
+    fn javascript_callbacks() {
+        check_metrics::<JavascriptParser>(
+            "function f(path) {
+                fs.readFile(path, function (err, data) {
+                    parse(data, (err, json) => {
+                        save(json, () => done());
+                    });
+                });
+            }
+            function g(url) {
+                return fetch(url)
+                    .then(res => res.json())
+                    .catch(err => null)
+                    .then(json => json.data)
+                    .finally(() => log(url));
+            }",
+            "foo.js",
+            |metric| {
+                insta::assert_json_snapshot!(
+                    metric.callbacks,
+                    @r###"
+                    {
+                      "nesting_max": 3.0,
+                      "chain_max": 2.0
+                    }"###
+                );
+            },
+        );
+    }
+
+    #[test]
+    fn typescript_callbacks_function() {
+        check_func_space::<TypescriptParser, _>(
+            "function f(p: Promise<number>) {
+                const g = () => p.then((a) => a + 1).then((a) => a * 2);
+                return p.then((a) => a);
+            }",
+            "foo.ts",
+            |func_space| {
+                let function = &func_space.spaces[0].metrics.callbacks;
+                assert_eq!(function.chain(), 1.);
+                assert_eq!(function.chain_max(), 2.);
+                assert_eq!(function.nesting(), 0.);
+                assert_eq!(function.nesting_max(), 1.);
+            },
+        );
+    }
+
+    #[test]
+    fn python_no_callbacks() {
+        check_metrics::<PythonParser>("a = 42", "foo.py", |metric| {
+            assert!(metric.callbacks.is_disabled());
+        });
+    }
+}
@@ -5,11 +5,13 @@ use serde::ser::{SerializeStruct, Serializer};
 use std::fmt;
 
 use crate::checker::Checker;
+use crate::getter::Getter;
 use crate::macros::implement_metric_trait;
 use crate::*;
 
 // TODO: Find a way to increment the cognitive complexity value
-// for recursive code. For some kind of languages, such as C++, it is pretty
+// for indirect recursion, the Sonar mode only detects direct recursion
+// comparing function names. For some kind of languages, such as C++, it is pretty
 // hard to detect, just parsing the code, if a determined function is recursive
 // because the call graph of a function is solved at runtime.
 // So a possible solution could be searching for a crate which implements
@@ -121,14 +123,104 @@ impl Stats {
     }
 }
 
+/// The rules used to compute the `Cognitive Complexity` metric.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum CognitiveMode {
+    /// The rules of rust-code-analysis
+    #[default]
+    Default,
+    /// The rules of the SonarSource specification, which differ on:
+    ///
+    /// - each expression starting a new sequence of boolean operators
+    /// - each call to the function containing it, a direct recursion,
+    ///   incrementing the complexity
+    /// - ternary operators, labeled `break` and `continue`
+    ///   incrementing the complexity in all languages
+    /// - `except` clauses increasing the nesting and `finally`
+    ///   clauses not incrementing the complexity in Python
+    Sonar,
+}
+
+impl CognitiveMode {
+    /// The names of the available modes.
+    pub const MODES: &'static [&'static str] = &["default", "sonar"];
+
+    /// Returns the mode with the given name.
+    ///
+    /// Returns `None` if the mode does not exist.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rust_code_analysis::cognitive::CognitiveMode;
+    ///
+    /// assert_eq!(CognitiveMode::from_name("sonar"), Some(CognitiveMode::Sonar));
+    /// ```
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name {
+            "default" => Some(Self::Default),
+            "sonar" => Some(Self::Sonar),
+            _ => None,
+        }
+    }
+}
+
+/// The rules used to analyze the Python comprehensions,
+/// generator expressions and lambdas.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum ComprehensionMode {
+    /// The comprehensions do not increment the `Cognitive Complexity`
+    #[default]
+    Default,
+    /// Each `if` clause of a comprehension increments the
+    /// `Cognitive Complexity` like an `if` statement, and each `for`
+    /// clause following the first one, a nested loop, increments it by 1
+    Complexity,
+    /// The comprehensions increment the `Cognitive Complexity` as
+    /// in the `Complexity` mode, while the comprehensions and the lambdas
+    /// are analyzed as closure spaces
+    Spaces,
+}
+
+impl ComprehensionMode {
+    /// The names of the available modes.
+    pub const MODES: &'static [&'static str] = &["default", "complexity", "spaces"];
+
+    /// Returns the mode with the given name.
+    ///
+    /// Returns `None` if the mode does not exist.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rust_code_analysis::cognitive::ComprehensionMode;
+    ///
+    /// assert_eq!(
+    ///     ComprehensionMode::from_name("spaces"),
+    ///     Some(ComprehensionMode::Spaces)
+    /// );
+    /// ```
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name {
+            "default" => Some(Self::Default),
+            "complexity" => Some(Self::Complexity),
+            "spaces" => Some(Self::Spaces),
+            _ => None,
+        }
+    }
+}
+
 pub trait Cognitive
 where
     Self: Checker,
 {
     fn compute(
         node: &Node,
+        code: &[u8],
         stats: &mut Stats,
         nesting_map: &mut HashMap<usize, (usize, usize, usize)>,
+        mode: CognitiveMode,
+        comprehensions: ComprehensionMode,
     );
 }
 
@@ -181,6 +273,49 @@ impl BoolSequence {
     }
 }
 
+// Checks whether a boolean expression is not an operand of another
+// boolean expression, so it starts a new sequence of boolean operators
+fn starts_boolean_sequence(
+    node: &Node,
+    is_boolean: fn(&Node) -> bool,
+    is_transparent: fn(&Node) -> bool,
+) -> bool {
+    let mut parent = node.parent();
+    while let Some(node) = parent.filter(is_transparent) {
+        parent = node.parent();
+    }
+    !parent.is_some_and(|parent| is_boolean(&parent))
+}
+
+// The last identifier of a name, such as `bar` in `self.bar` or `Foo::bar`
+fn last_identifier(name: &str) -> &str {
+    name.rsplit(|c: char| !(c.is_alphanumeric() || c == '_' || c == '$'))
+        .find(|s| !s.is_empty())
+        .unwrap_or(name)
+}
+
+// Checks whether a function calls the function which contains it.
+// Only the names are compared, so a call to a method with the same name
+// of another object is considered as a recursion too.
+fn is_recursive_call<T: Checker + Getter>(callee: Option<Node>, code: &[u8]) -> bool {
+    let Some(callee) = callee else {
+        return false;
+    };
+    let Some(name) = callee.utf8_text(code) else {
+        return false;
+    };
+
+    let mut node = callee;
+    while let Some(parent) = node.parent() {
+        if T::is_func(&parent) {
+            return T::get_func_space_name(&parent, code)
+                .is_some_and(|func| last_identifier(func) == last_identifier(name));
+        }
+        node = parent;
+    }
+    false
+}
+
 #[inline(always)]
 fn increment(stats: &mut Stats) {
     stats.structural += stats.nesting + 1;
@@ -233,8 +368,11 @@ fn increase_nesting(stats: &mut Stats, nesting: &mut usize, depth: usize, lambda
 impl Cognitive for PythonCode {
     fn compute(
         node: &Node,
+        code: &[u8],
         stats: &mut Stats,
         nesting_map: &mut HashMap<usize, (usize, usize, usize)>,
+        mode: CognitiveMode,
+        comprehensions: ComprehensionMode,
     ) {
         use Python::*;
 
@@ -252,14 +390,38 @@ impl Cognitive for PythonCode {
                 // Reset the boolean sequence
                 stats.boolean_seq.reset();
             }
-            ElseClause | FinallyClause => {
+            ElseClause => {
                 // No nesting increment for them because their cost has already
                 // been paid by the if construct
                 increment_by_one(stats);
             }
+            FinallyClause if mode != CognitiveMode::Sonar => {
+                increment_by_one(stats);
+            }
             ExceptClause => {
-                nesting += 1;
+                if mode == CognitiveMode::Sonar {
+                    increase_nesting(stats, &mut nesting, depth, lambda);
+                } else {
+                    nesting += 1;
+                    increment(stats);
+                }
+            }
+            IfClause if comprehensions != ComprehensionMode::Default => {
+                stats.nesting = nesting + depth + lambda;
                 increment(stats);
+                stats.boolean_seq.reset();
+            }
+            ForInClause if comprehensions != ComprehensionMode::Default => {
+                // The first `for` clause is the loop of the comprehension itself,
+                // while the following ones are nested loops
+                let mut previous = node.previous_sibling();
+                while let Some(sibling) = previous {
+                    if sibling.kind_id() == ForInClause {
+                        increment_by_one(stats);
+                        break;
+                    }
+                    previous = sibling.previous_sibling();
+                }
             }
             ExpressionList | ExpressionStatement | Tuple => {
                 stats.boolean_seq.reset();
@@ -268,6 +430,17 @@ impl Cognitive for PythonCode {
                 stats.boolean_seq.not_operator(node.kind_id());
             }
             BooleanOperator => {
+                if mode == CognitiveMode::Sonar
+                    && starts_boolean_sequence(
+                        node,
+                        |node| node.kind_id() == BooleanOperator,
+                        |node| {
+                            matches!(node.kind_id().into(), ParenthesizedExpression | NotOperator)
+                        },
+                    )
+                {
+                    stats.boolean_seq.reset();
+                }
                 if node.count_specific_ancestors::<PythonParser>(
                     |node| node.kind_id() == BooleanOperator,
                     |node| node.kind_id() == Lambda,
@@ -285,6 +458,11 @@ impl Cognitive for PythonCode {
                 }
                 compute_booleans::<language_python::Python>(node, stats, And, Or);
             }
+            Call if mode == CognitiveMode::Sonar
+                && is_recursive_call::<Self>(node.child_by_field_name("function"), code) =>
+            {
+                increment_by_one(stats);
+            }
             Lambda => {
                 // Increase lambda nesting
                 lambda += 1;
@@ -307,19 +485,20 @@ impl Cognitive for PythonCode {
 impl Cognitive for RustCode {
     fn compute(
         node: &Node,
+        code: &[u8],
         stats: &mut Stats,
         nesting_map: &mut HashMap<usize, (usize, usize, usize)>,
+        mode: CognitiveMode,
+        _comprehensions: ComprehensionMode,
     ) {
         use Rust::*;
         //TODO: Implement macros
         let (mut nesting, mut depth, mut lambda) = get_nesting_from_map(node, nesting_map);
 
         match node.kind_id().into() {
-            IfExpression => {
-                // Check if a node is not an else-if
-                if !Self::is_else_if(node) {
-                    increase_nesting(stats,&mut nesting, depth, lambda);
-                }
+            // Check if a node is not an else-if
+            IfExpression if !Self::is_else_if(node) => {
+                increase_nesting(stats,&mut nesting, depth, lambda);
             }
             ForExpression | WhileExpression | MatchExpression => {
                 increase_nesting(stats,&mut nesting, depth, lambda);
@@ -338,8 +517,22 @@ impl Cognitive for RustCode {
                 stats.boolean_seq.not_operator(node.kind_id());
             }
             BinaryExpression => {
+                if mode == CognitiveMode::Sonar
+                    && starts_boolean_sequence(
+                        node,
+                        |node| node.kind_id() == BinaryExpression,
+                        |node| matches!(node.kind_id().into(), ParenthesizedExpression | UnaryExpression),
+                    )
+                {
+                    stats.boolean_seq.reset();
+                }
                 compute_booleans::<language_rust::Rust>(node, stats, AMPAMP, PIPEPIPE);
             }
+            CallExpression if mode == CognitiveMode::Sonar
+                && is_recursive_call::<Self>(node.child_by_field_name("function"), code) =>
+            {
+                increment_by_one(stats);
+            }
             FunctionItem  => {
                 nesting = 0;
                 // Increase depth function nesting if needed
@@ -357,8 +550,11 @@ impl Cognitive for RustCode {
 impl Cognitive for CppCode {
     fn compute(
         node: &Node,
+        code: &[u8],
         stats: &mut Stats,
         nesting_map: &mut HashMap<usize, (usize, usize, usize)>,
+        mode: CognitiveMode,
+        _comprehensions: ComprehensionMode,
     ) {
         use Cpp::*;
 
@@ -366,14 +562,15 @@ impl Cognitive for CppCode {
         let (mut nesting, depth, mut lambda) = get_nesting_from_map(node, nesting_map);
 
         match node.kind_id().into() {
-            IfStatement => {
-                if !Self::is_else_if(node) {
-                    increase_nesting(stats,&mut nesting, depth, lambda);
-                }
+            IfStatement if !Self::is_else_if(node) => {
+                increase_nesting(stats,&mut nesting, depth, lambda);
             }
             ForStatement | WhileStatement | DoStatement | SwitchStatement | CatchClause => {
                 increase_nesting(stats,&mut nesting, depth, lambda);
             }
+            ConditionalExpression if mode == CognitiveMode::Sonar => {
+                increase_nesting(stats,&mut nesting, depth, lambda);
+            }
             GotoStatement | Else /* else-if also */ => {
                 increment_by_one(stats);
             }
@@ -381,8 +578,27 @@ impl Cognitive for CppCode {
                 stats.boolean_seq.not_operator(node.kind_id());
             }
             BinaryExpression2 => {
+                if mode == CognitiveMode::Sonar
+                    && starts_boolean_sequence(
+                        node,
+                        |node| node.kind_id() == BinaryExpression2,
+                        |node| {
+                            matches!(
+                                node.kind_id().into(),
+                                ParenthesizedExpression | ParenthesizedExpression2 | UnaryExpression2
+                            )
+                        },
+                    )
+                {
+                    stats.boolean_seq.reset();
+                }
                 compute_booleans::<language_cpp::Cpp>(node, stats, AMPAMP, PIPEPIPE);
             }
+            CallExpression | CallExpression2 if mode == CognitiveMode::Sonar
+                && is_recursive_call::<Self>(node.child_by_field_name("function"), code) =>
+            {
+                increment_by_one(stats);
+            }
             LambdaExpression => {
                 lambda += 1;
             }
@@ -394,15 +610,13 @@ impl Cognitive for CppCode {
 
 macro_rules! js_cognitive {
     ($lang:ident) => {
-        fn compute(node: &Node, stats: &mut Stats, nesting_map: &mut HashMap<usize, (usize, usize, usize)>) {
+        fn compute(node: &Node, code: &[u8], stats: &mut Stats, nesting_map: &mut HashMap<usize, (usize, usize, usize)>, mode: CognitiveMode, _comprehensions: ComprehensionMode) {
             use $lang::*;
             let (mut nesting, mut depth, mut lambda) = get_nesting_from_map(node, nesting_map);
 
             match node.kind_id().into() {
-                IfStatement => {
-                    if !Self::is_else_if(&node) {
-                        increase_nesting(stats,&mut nesting, depth, lambda);
-                    }
+                IfStatement if !Self::is_else_if(&node) => {
+                    increase_nesting(stats,&mut nesting, depth, lambda);
                 }
                 ForStatement | ForInStatement | WhileStatement | DoStatement | SwitchStatement | CatchClause | TernaryExpression => {
                     increase_nesting(stats,&mut nesting, depth, lambda);
@@ -410,6 +624,11 @@ macro_rules! js_cognitive {
                 Else /* else-if also */ => {
                     increment_by_one(stats);
                 }
+                BreakStatement | ContinueStatement if mode == CognitiveMode::Sonar
+                    && node.child_by_field_name("label").is_some() =>
+                {
+                    increment_by_one(stats);
+                }
                 ExpressionStatement => {
                     // Reset the boolean sequence
                     stats.boolean_seq.reset();
@@ -418,8 +637,22 @@ macro_rules! js_cognitive {
                     stats.boolean_seq.not_operator(node.kind_id());
                 }
                 BinaryExpression => {
+                    if mode == CognitiveMode::Sonar
+                        && starts_boolean_sequence(
+                            node,
+                            |node| node.kind_id() == BinaryExpression,
+                            |node| matches!(node.kind_id().into(), ParenthesizedExpression | UnaryExpression),
+                        )
+                    {
+                        stats.boolean_seq.reset();
+                    }
                     compute_booleans::<$lang>(node, stats, AMPAMP, PIPEPIPE);
                 }
+                CallExpression | CallExpression2 if mode == CognitiveMode::Sonar
+                    && is_recursive_call::<Self>(node.child_by_field_name("function"), code) =>
+                {
+                    increment_by_one(stats);
+                }
                 FunctionDeclaration => {
                     // Reset lambda nesting at function for JS
                     nesting = 0;
@@ -456,31 +689,54 @@ impl Cognitive for TsxCode {
 impl Cognitive for JavaCode {
     fn compute(
         node: &Node,
+        code: &[u8],
         stats: &mut Stats,
         nesting_map: &mut HashMap<usize, (usize, usize, usize)>,
+        mode: CognitiveMode,
+        _comprehensions: ComprehensionMode,
     ) {
         use Java::*;
 
         let (mut nesting, depth, mut lambda) = get_nesting_from_map(node, nesting_map);
 
         match node.kind_id().into() {
-            IfStatement => {
-                if !Self::is_else_if(node) {
-                    increase_nesting(stats,&mut nesting, depth, lambda);
-                }
+            IfStatement if !Self::is_else_if(node) => {
+                increase_nesting(stats,&mut nesting, depth, lambda);
             }
             ForStatement | WhileStatement | DoStatement | SwitchBlock | CatchClause => {
                 increase_nesting(stats,&mut nesting, depth, lambda);
             }
+            TernaryExpression if mode == CognitiveMode::Sonar => {
+                increase_nesting(stats,&mut nesting, depth, lambda);
+            }
             Else /* else-if also */ => {
                 increment_by_one(stats);
             }
+            BreakStatement | ContinueStatement if mode == CognitiveMode::Sonar
+                && node.children().any(|child| child.kind_id() == Identifier) =>
+            {
+                increment_by_one(stats);
+            }
             UnaryExpression => {
                 stats.boolean_seq.not_operator(node.kind_id());
             }
             BinaryExpression => {
+                if mode == CognitiveMode::Sonar
+                    && starts_boolean_sequence(
+                        node,
+                        |node| node.kind_id() == BinaryExpression,
+                        |node| matches!(node.kind_id().into(), ParenthesizedExpression | UnaryExpression),
+                    )
+                {
+                    stats.boolean_seq.reset();
+                }
                 compute_booleans::<language_java::Java>(node, stats, AMPAMP, PIPEPIPE);
             }
+            MethodInvocation if mode == CognitiveMode::Sonar
+                && is_recursive_call::<Self>(node.child_by_field_name("name"), code) =>
+            {
+                increment_by_one(stats);
+            }
             LambdaExpression => {
                 lambda += 1;
             }
@@ -1927,4 +2183,156 @@ mod tests {
             },
         );
     }
+
+    // The cognitive complexity of the first function, with the default and the Sonar mode
+    fn cognitive_modes(lang: LANG, source: &str, path: &str) -> (f64, f64) {
+        let cognitive = |mode| {
+            let options = MetricsOptions {
+                cognitive: mode,
+                ..Default::default()
+            };
+            let mut space = get_function_spaces_with_options(
+                &lang,
+                source.as_bytes().to_vec(),
+                std::path::Path::new(path),
+                None,
+                &options,
+            )
+            .unwrap();
+            while space.kind != SpaceKind::Function {
+                space = space.spaces.swap_remove(0);
+            }
+            space.metrics.cognitive.cognitive()
+        };
+
+        (
+            cognitive(CognitiveMode::Default),
+            cognitive(CognitiveMode::Sonar),
+        )
+    }
+
+    #[test]
+    fn java_sonar_labeled_continue() {
+        // The example of the SonarSource specification
+        let source = "class A {
+            int sumOfPrimes(int max) {
+                int total = 0;
+                OUT: for (int i = 1; i <= max; ++i) { // +1
+                    for (int j = 2; j < i; ++j) { // +2
+                        if (i % j == 0) { // +3
+                            continue OUT; // +1 (Sonar)
+                        }
+                    }
+                    total += i;
+                }
+                return total;
+            }
+        }";
+        assert_eq!(cognitive_modes(LANG::Java, source, "foo.java"), (6., 7.));
+    }
+
+    #[test]
+    fn java_sonar_boolean_sequences() {
+        let source = "class A {
+            void f(boolean a, boolean b, boolean c, boolean d) {
+                boolean x = a && b; // +1
+                boolean y = c && d; // +1 (Sonar)
+                boolean z = a ? b : c; // +1 (Sonar)
+            }
+        }";
+        assert_eq!(cognitive_modes(LANG::Java, source, "foo.java"), (1., 3.));
+    }
+
+    #[test]
+    fn python_sonar_recursion() {
+        let source = "def fact(n):
+    if n <= 1: # +1
+        return 1
+    return n * fact(n - 1) # +1 (Sonar)
+";
+        assert_eq!(cognitive_modes(LANG::Python, source, "foo.py"), (1., 2.));
+    }
+
+    #[test]
+    fn python_sonar_try() {
+        let source = "def f(a):
+    try:
+        pass
+    except ValueError: # +1
+        if a: # +2
+            pass
+    finally: # +1 (default)
+        pass
+";
+        assert_eq!(cognitive_modes(LANG::Python, source, "foo.py"), (4., 3.));
+    }
+
+    #[test]
+    fn cpp_sonar_ternary_recursion() {
+        let source = "int fib(int n) {
+            return n < 2 ? n : fib(n - 1) + fib(n - 2); // +1 +1 +1 (Sonar)
+        }";
+        assert_eq!(cognitive_modes(LANG::Cpp, source, "foo.cpp"), (0., 3.));
+    }
+
+    #[test]
+    fn javascript_sonar_recursion() {
+        let source = "function walk(node) {
+            if (node.left && node.right) { // +2
+                walk(node.left); // +1 (Sonar)
+            }
+        }";
+        assert_eq!(
+            cognitive_modes(LANG::Javascript, source, "foo.js"),
+            (2., 3.)
+        );
+        assert_eq!(
+            cognitive_modes(LANG::Typescript, source, "foo.ts"),
+            (2., 3.)
+        );
+    }
+
+    #[test]
+    fn rust_sonar_recursion() {
+        let source = "fn depth(node: &Node) -> usize {
+            match node.child() { // +1
+                Some(child) => 1 + depth(child), // +1 (Sonar)
+                None => 0,
+            }
+        }";
+        assert_eq!(cognitive_modes(LANG::Rust, source, "foo.rs"), (1., 2.));
+    }
+
+    #[test]
+    fn python_comprehensions() {
+        let source = "def f(rows):
+    return [
+        cell
+        for row in rows
+        if row
+        for cell in row
+        if cell > 0 and cell < 9
+    ]";
+        let cognitive = |comprehensions| {
+            let options = MetricsOptions {
+                comprehensions,
+                ..Default::default()
+            };
+            let space = get_function_spaces_with_options(
+                &LANG::Python,
+                source.as_bytes().to_vec(),
+                std::path::Path::new("foo.py"),
+                None,
+                &options,
+            )
+            .unwrap();
+            space.metrics.cognitive.cognitive_sum()
+        };
+
+        // The boolean operator
+        assert_eq!(cognitive(ComprehensionMode::Default), 1.);
+        // The two `if` clauses, the nested `for` clause and the boolean operator
+        assert_eq!(cognitive(ComprehensionMode::Complexity), 4.);
+        assert_eq!(cognitive(ComprehensionMode::Spaces), 4.);
+    }
 }
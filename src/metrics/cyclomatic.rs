@@ -102,28 +102,103 @@ impl Stats {
     }
 }
 
+/// The rules used to count the decision points of the `Cyclomatic` metric.
+///
+/// The standards disagree on some constructs: the conditions, the loops and
+/// the ternary operators always increase the complexity, while the constructs
+/// below can be excluded.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct CyclomaticOptions {
+    /// Whether each `&&` and `||` operator, or `and` and `or` in Python,
+    /// increases the complexity
+    pub boolean_operators: bool,
+    /// Whether each `case` label of a switch, or arm of a match,
+    /// increases the complexity
+    pub case_labels: bool,
+    /// Whether each `catch` clause, or `except` in Python,
+    /// increases the complexity
+    pub catch_clauses: bool,
+}
+
+impl Default for CyclomaticOptions {
+    fn default() -> Self {
+        Self {
+            boolean_operators: true,
+            case_labels: true,
+            catch_clauses: true,
+        }
+    }
+}
+
+impl CyclomaticOptions {
+    /// The names of the available presets.
+    pub const PRESETS: &'static [&'static str] = &["default", "strict-mccabe", "sonar"];
+
+    /// Returns the options of a preset.
+    ///
+    /// - `default`: all the constructs increase the complexity.
+    /// - `strict-mccabe`: only the branches of the control flow graph
+    ///   increase the complexity, so the boolean operators and the
+    ///   `catch` clauses are not counted.
+    /// - `sonar`: the rules of SonarQube, which do not count
+    ///   the `catch` clauses.
+    ///
+    /// Returns `None` if the preset does not exist.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rust_code_analysis::cyclomatic::CyclomaticOptions;
+    ///
+    /// let options = CyclomaticOptions::preset("strict-mccabe").unwrap();
+    ///
+    /// assert!(!options.boolean_operators);
+    /// ```
+    pub fn preset(name: &str) -> Option<Self> {
+        match name {
+            "default" => Some(Self::default()),
+            "strict-mccabe" => Some(Self {
+                boolean_operators: false,
+                case_labels: true,
+                catch_clauses: false,
+            }),
+            "sonar" => Some(Self {
+                boolean_operators: true,
+                case_labels: true,
+                catch_clauses: false,
+            }),
+            _ => None,
+        }
+    }
+}
+
 pub trait Cyclomatic
 where
     Self: Checker,
 {
-    fn compute(node: &Node, stats: &mut Stats);
+    fn compute(node: &Node, stats: &mut Stats, options: &CyclomaticOptions);
 }
 
 impl Cyclomatic for PythonCode {
-    fn compute(node: &Node, stats: &mut Stats) {
+    fn compute(node: &Node, stats: &mut Stats, options: &CyclomaticOptions) {
         use Python::*;
 
         match node.kind_id().into() {
-            If | Elif | For | While | Except | With | Assert | And | Or => {
+            If | Elif | For | While | With | Assert => {
                 stats.cyclomatic += 1.;
             }
-            Else => {
-                if node.has_ancestors(
-                    |node| matches!(node.kind_id().into(), ForStatement | WhileStatement),
-                    |node| node.kind_id() == ElseClause,
-                ) {
-                    stats.cyclomatic += 1.;
-                }
+            Except if options.catch_clauses => {
+                stats.cyclomatic += 1.;
+            }
+            And | Or if options.boolean_operators => {
+                stats.cyclomatic += 1.;
+            }
+            Else if node.has_ancestors(
+                |node| matches!(node.kind_id().into(), ForStatement | WhileStatement),
+                |node| node.kind_id() == ElseClause,
+            ) =>
+            {
+                stats.cyclomatic += 1.;
             }
             _ => {}
         }
@@ -131,11 +206,20 @@ impl Cyclomatic for PythonCode {
 }
 
 impl Cyclomatic for MozjsCode {
-    fn compute(node: &Node, stats: &mut Stats) {
+    fn compute(node: &Node, stats: &mut Stats, options: &CyclomaticOptions) {
         use Mozjs::*;
 
         match node.kind_id().into() {
-            If | For | While | Case | Catch | TernaryExpression | AMPAMP | PIPEPIPE => {
+            If | For | While | TernaryExpression => {
+                stats.cyclomatic += 1.;
+            }
+            Case if options.case_labels => {
+                stats.cyclomatic += 1.;
+            }
+            Catch if options.catch_clauses => {
+                stats.cyclomatic += 1.;
+            }
+            AMPAMP | PIPEPIPE if options.boolean_operators => {
                 stats.cyclomatic += 1.;
             }
             _ => {}
@@ -144,11 +228,20 @@ impl Cyclomatic for MozjsCode {
 }
 
 impl Cyclomatic for JavascriptCode {
-    fn compute(node: &Node, stats: &mut Stats) {
+    fn compute(node: &Node, stats: &mut Stats, options: &CyclomaticOptions) {
         use Javascript::*;
 
         match node.kind_id().into() {
-            If | For | While | Case | Catch | TernaryExpression | AMPAMP | PIPEPIPE => {
+            If | For | While | TernaryExpression => {
+                stats.cyclomatic += 1.;
+            }
+            Case if options.case_labels => {
+                stats.cyclomatic += 1.;
+            }
+            Catch if options.catch_clauses => {
+                stats.cyclomatic += 1.;
+            }
+            AMPAMP | PIPEPIPE if options.boolean_operators => {
                 stats.cyclomatic += 1.;
             }
             _ => {}
@@ -157,11 +250,20 @@ impl Cyclomatic for JavascriptCode {
 }
 
 impl Cyclomatic for TypescriptCode {
-    fn compute(node: &Node, stats: &mut Stats) {
+    fn compute(node: &Node, stats: &mut Stats, options: &CyclomaticOptions) {
         use Typescript::*;
 
         match node.kind_id().into() {
-            If | For | While | Case | Catch | TernaryExpression | AMPAMP | PIPEPIPE => {
+            If | For | While | TernaryExpression => {
+                stats.cyclomatic += 1.;
+            }
+            Case if options.case_labels => {
+                stats.cyclomatic += 1.;
+            }
+            Catch if options.catch_clauses => {
+                stats.cyclomatic += 1.;
+            }
+            AMPAMP | PIPEPIPE if options.boolean_operators => {
                 stats.cyclomatic += 1.;
             }
             _ => {}
@@ -170,11 +272,20 @@ impl Cyclomatic for TypescriptCode {
 }
 
 impl Cyclomatic for TsxCode {
-    fn compute(node: &Node, stats: &mut Stats) {
+    fn compute(node: &Node, stats: &mut Stats, options: &CyclomaticOptions) {
         use Tsx::*;
 
         match node.kind_id().into() {
-            If | For | While | Case | Catch | TernaryExpression | AMPAMP | PIPEPIPE => {
+            If | For | While | TernaryExpression => {
+                stats.cyclomatic += 1.;
+            }
+            Case if options.case_labels => {
+                stats.cyclomatic += 1.;
+            }
+            Catch if options.catch_clauses => {
+                stats.cyclomatic += 1.;
+            }
+            AMPAMP | PIPEPIPE if options.boolean_operators => {
                 stats.cyclomatic += 1.;
             }
             _ => {}
@@ -183,11 +294,17 @@ impl Cyclomatic for TsxCode {
 }
 
 impl Cyclomatic for RustCode {
-    fn compute(node: &Node, stats: &mut Stats) {
+    fn compute(node: &Node, stats: &mut Stats, options: &CyclomaticOptions) {
         use Rust::*;
 
         match node.kind_id().into() {
-            If | For | While | Loop | MatchArm | MatchArm2 | TryExpression | AMPAMP | PIPEPIPE => {
+            If | For | While | Loop | TryExpression => {
+                stats.cyclomatic += 1.;
+            }
+            MatchArm | MatchArm2 if options.case_labels => {
+                stats.cyclomatic += 1.;
+            }
+            AMPAMP | PIPEPIPE if options.boolean_operators => {
                 stats.cyclomatic += 1.;
             }
             _ => {}
@@ -196,11 +313,20 @@ impl Cyclomatic for RustCode {
 }
 
 impl Cyclomatic for CppCode {
-    fn compute(node: &Node, stats: &mut Stats) {
+    fn compute(node: &Node, stats: &mut Stats, options: &CyclomaticOptions) {
         use Cpp::*;
 
         match node.kind_id().into() {
-            If | For | While | Case | Catch | ConditionalExpression | AMPAMP | PIPEPIPE => {
+            If | For | While | ConditionalExpression => {
+                stats.cyclomatic += 1.;
+            }
+            Case if options.case_labels => {
+                stats.cyclomatic += 1.;
+            }
+            Catch if options.catch_clauses => {
+                stats.cyclomatic += 1.;
+            }
+            AMPAMP | PIPEPIPE if options.boolean_operators => {
                 stats.cyclomatic += 1.;
             }
             _ => {}
@@ -209,11 +335,20 @@ impl Cyclomatic for CppCode {
 }
 
 impl Cyclomatic for JavaCode {
-    fn compute(node: &Node, stats: &mut Stats) {
+    fn compute(node: &Node, stats: &mut Stats, options: &CyclomaticOptions) {
         use Java::*;
 
         match node.kind_id().into() {
-            If | For | While | Case | Catch | TernaryExpression | AMPAMP | PIPEPIPE => {
+            If | For | While | TernaryExpression => {
+                stats.cyclomatic += 1.;
+            }
+            Case if options.case_labels => {
+                stats.cyclomatic += 1.;
+            }
+            Catch if options.catch_clauses => {
+                stats.cyclomatic += 1.;
+            }
+            AMPAMP | PIPEPIPE if options.boolean_operators => {
                 stats.cyclomatic += 1.;
             }
             _ => {}
@@ -600,4 +735,64 @@ mod tests {
             },
         );
     }
+
+    #[test]
+    fn java_presets() {
+        let source = "class A {
+            int f(int a, int b) { // +1
+                try {
+                    if (a > 0 && b > 0) { // +2 (+1 &&)
+                        return 1;
+                    }
+                } catch (Exception e) { // +1
+                    return -1;
+                }
+                switch (a) {
+                    case 1: // +1
+                        return 2;
+                    default:
+                        return 3;
+                }
+            }
+        }";
+        let cyclomatic = |preset: &str| {
+            let options = MetricsOptions {
+                cyclomatic: CyclomaticOptions::preset(preset).unwrap(),
+                ..Default::default()
+            };
+            let space = get_function_spaces_with_options(
+                &LANG::Java,
+                source.as_bytes().to_vec(),
+                std::path::Path::new("foo.java"),
+                None,
+                &options,
+            )
+            .unwrap();
+            space.spaces[0].spaces[0].metrics.cyclomatic.cyclomatic()
+        };
+
+        assert_eq!(cyclomatic("default"), 5.);
+        assert_eq!(cyclomatic("sonar"), 4.);
+        assert_eq!(cyclomatic("strict-mccabe"), 3.);
+        assert!(CyclomaticOptions::preset("foo").is_none());
+    }
+
+    #[test]
+    fn python_strict_mccabe() {
+        let options = MetricsOptions {
+            cyclomatic: CyclomaticOptions::preset("strict-mccabe").unwrap(),
+            ..Default::default()
+        };
+        let space = get_function_spaces_with_options(
+            &LANG::Python,
+            b"def f(a, b):\n    if a and b or a:\n        return 1\n".to_vec(),
+            std::path::Path::new("foo.py"),
+            None,
+            &options,
+        )
+        .unwrap();
+
+        // The and/or operators are not counted
+        assert_eq!(space.spaces[0].metrics.cyclomatic.cyclomatic(), 2.);
+    }
 }
@@ -17,6 +17,31 @@ pub struct Stats {
     operators: u64,
     u_operands: u64,
     operands: u64,
+    options: HalsteadOptions,
+}
+
+/// The parameters of the models estimating the time required
+/// to program a code and the number of its delivered bugs.
+///
+/// Their published values differ, so they can be calibrated,
+/// for example against the historical defects of a project.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct HalsteadOptions {
+    /// The Stroud number, the elementary mental discriminations
+    /// per second, which divides the effort to get the time
+    pub stroud: f64,
+    /// The elementary mental discriminations between two errors,
+    /// which divides the effort raised to 2/3 to get the bugs
+    pub bugs_divisor: f64,
+}
+
+impl Default for HalsteadOptions {
+    fn default() -> Self {
+        Self {
+            stroud: 18.,
+            bugs_divisor: 3000.,
+        }
+    }
 }
 
 /// Specifies the type of nodes accepted by the `Halstead` metric.
@@ -123,6 +148,11 @@ impl fmt::Display for Stats {
 impl Stats {
     pub(crate) fn merge(&mut self, _other: &Stats) {}
 
+    #[inline(always)]
+    pub(crate) fn set_options(&mut self, options: HalsteadOptions) {
+        self.options = options;
+    }
+
     /// Returns `η1`, the number of distinct operators
     #[inline(always)]
     pub fn u_operators(&self) -> f64 {
@@ -201,12 +231,14 @@ impl Stats {
 
     /// Returns the estimated time required to program.
     ///
+    /// The effort is divided by the Stroud number of the
+    /// [`HalsteadOptions`].
+    ///
     /// Unit of measurement: seconds
     #[inline(always)]
     pub fn time(&self) -> f64 {
-        // The floating point `18.` aims to describe the processing rate of the
-        // human brain. It is called Stoud number, S, and its
-        // unit of measurement is moments/seconds.
+        // The Stroud number S, `18.` by default, aims to describe the processing
+        // rate of the human brain. Its unit of measurement is moments/seconds.
         // A moment is the time required by the human brain to carry out the
         // most elementary decision.
         // 5 <= S <= 20. Halstead uses 18.
@@ -215,16 +247,17 @@ impl Stats {
         // programming applications is 18.
         //
         // Source: https://www.geeksforgeeks.org/software-engineering-halsteads-software-metrics/
-        self.effort() / 18.
+        self.effort() / self.options.stroud
     }
 
     /// Returns the estimated number of delivered bugs.
     ///
     /// This metric represents the average amount of work a programmer can do
-    /// without introducing an error.
+    /// without introducing an error. The effort raised to 2/3 is divided by
+    /// the bugs divisor of the [`HalsteadOptions`].
     #[inline(always)]
     pub fn bugs(&self) -> f64 {
-        // The floating point `3000.` represents the number of elementary
+        // The divisor, `3000.` by default, represents the number of elementary
         // mental discriminations.
         // A mental discrimination, in psychology, is the ability to perceive
         // and respond to differences among stimuli.
@@ -244,7 +277,7 @@ impl Stats {
         // mental discriminations.
         //
         // Source: https://docs.lib.purdue.edu/cgi/viewcontent.cgi?article=1145&context=cstech
-        self.effort().powf(2. / 3.) / 3000.
+        self.effort().powf(2. / 3.) / self.options.bugs_divisor
     }
 }
 
@@ -698,4 +731,28 @@ mod tests {
             },
         );
     }
+
+    #[test]
+    fn halstead_options() {
+        let source = "def f(a, b):\n    return a + b * 2\n";
+        let path = std::path::Path::new("foo.py");
+        let parser = PythonParser::new(source.as_bytes().to_vec(), path, None);
+
+        let default = metrics(&parser, path).unwrap().metrics.halstead;
+        let options = MetricsOptions {
+            halstead: HalsteadOptions {
+                stroud: 9.,
+                bugs_divisor: 1500.,
+            },
+            ..Default::default()
+        };
+        let calibrated = metrics_with_options(&parser, path, &options)
+            .unwrap()
+            .metrics
+            .halstead;
+
+        assert_eq!(calibrated.effort(), default.effort());
+        assert!((calibrated.time() - 2. * default.time()).abs() < 1e-9);
+        assert!((calibrated.bugs() - 2. * default.bugs()).abs() < 1e-9);
+    }
 }
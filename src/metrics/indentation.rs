@@ -0,0 +1,304 @@
+use serde::Serialize;
+use serde::ser::{SerializeStruct, Serializer};
+use std::fmt;
+
+// The indentation width used when a code contains no indentation
+// made of spaces
+const DEFAULT_INDENT_WIDTH: usize = 4;
+
+/// The indentation style of the lines of a space.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Style {
+    /// No line is indented
+    #[default]
+    None,
+    /// The lines are indented with tabs
+    Tabs,
+    /// The lines are indented with spaces
+    Spaces,
+    /// The lines are indented with both tabs and spaces
+    Mixed,
+}
+
+impl fmt::Display for Style {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let s = match self {
+            Style::None => "none",
+            Style::Tabs => "tabs",
+            Style::Spaces => "spaces",
+            Style::Mixed => "mixed",
+        };
+        write!(f, "{s}")
+    }
+}
+
+/// The `Indentation` metric.
+///
+/// This metric describes the indentation of the lines of a space,
+/// subspaces included: the characters used to indent them, their
+/// logical indentation level and the lines ending with whitespace.
+///
+/// The logical indentation level of a line is its number of
+/// indentation units, a tab or the indentation width of the file,
+/// relative to the first line of the space. It grows with the nesting
+/// of the code, so it is a simple proxy of its complexity.
+#[derive(Clone, Debug, Default)]
+pub struct Stats {
+    lines: usize,
+    tab_lines: usize,
+    space_lines: usize,
+    mixed_lines: usize,
+    trailing_whitespace: usize,
+    level_sum: usize,
+    level_max: usize,
+}
+
+impl Serialize for Stats {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        let mut st = serializer.serialize_struct("indentation", 7)?;
+        st.serialize_field("style", &self.style())?;
+        st.serialize_field("tab_lines", &self.tab_lines())?;
+        st.serialize_field("space_lines", &self.space_lines())?;
+        st.serialize_field("mixed_lines", &self.mixed_lines())?;
+        st.serialize_field("level_max", &self.level_max())?;
+        st.serialize_field("level_average", &self.level_average())?;
+        st.serialize_field("trailing_whitespace", &self.trailing_whitespace())?;
+        st.end()
+    }
+}
+
+impl fmt::Display for Stats {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "style: {}, level_max: {}, level_average: {}, trailing_whitespace: {}",
+            self.style(),
+            self.level_max(),
+            self.level_average(),
+            self.trailing_whitespace()
+        )
+    }
+}
+
+impl Stats {
+    /// Returns the indentation style of a space.
+    ///
+    /// The style is mixed when some lines are indented with tabs
+    /// and others with spaces, or when a line is indented with both.
+    pub fn style(&self) -> Style {
+        match (self.tab_lines, self.space_lines, self.mixed_lines) {
+            (0, 0, 0) => Style::None,
+            (_, 0, 0) => Style::Tabs,
+            (0, _, 0) => Style::Spaces,
+            _ => Style::Mixed,
+        }
+    }
+
+    /// Returns the number of lines indented with tabs only in a space.
+    #[inline(always)]
+    pub fn tab_lines(&self) -> f64 {
+        self.tab_lines as f64
+    }
+
+    /// Returns the number of lines indented with spaces only in a space.
+    #[inline(always)]
+    pub fn space_lines(&self) -> f64 {
+        self.space_lines as f64
+    }
+
+    /// Returns the number of lines indented with both tabs
+    /// and spaces in a space.
+    #[inline(always)]
+    pub fn mixed_lines(&self) -> f64 {
+        self.mixed_lines as f64
+    }
+
+    /// Returns the maximum logical indentation level of the lines
+    /// of a space.
+    #[inline(always)]
+    pub fn level_max(&self) -> f64 {
+        self.level_max as f64
+    }
+
+    /// Returns the average logical indentation level of the non-blank
+    /// lines of a space.
+    #[inline(always)]
+    pub fn level_average(&self) -> f64 {
+        if self.lines == 0 {
+            0.
+        } else {
+            self.level_sum as f64 / self.lines as f64
+        }
+    }
+
+    /// Returns the number of lines ending with whitespace in a space.
+    #[inline(always)]
+    pub fn trailing_whitespace(&self) -> f64 {
+        self.trailing_whitespace as f64
+    }
+
+    // Computes the indentation of the lines of a space,
+    // from its first row to its last one
+    pub(crate) fn compute(&mut self, lines: &Lines, start_row: usize, end_row: usize) {
+        let Some(rows) = lines
+            .lines
+            .get(start_row..=end_row.min(lines.lines.len() - 1))
+        else {
+            return;
+        };
+        let mut base = None;
+        for line in rows {
+            if line.trailing_whitespace {
+                self.trailing_whitespace += 1;
+            }
+            if line.blank {
+                continue;
+            }
+            match (line.tabs, line.spaces) {
+                (0, 0) => {}
+                (_, 0) => self.tab_lines += 1,
+                (0, _) => self.space_lines += 1,
+                _ => self.mixed_lines += 1,
+            }
+            let level = line.tabs + line.spaces / lines.width;
+            let base = *base.get_or_insert(level);
+            let level = level.saturating_sub(base);
+            self.lines += 1;
+            self.level_sum += level;
+            self.level_max = self.level_max.max(level);
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, Default)]
+struct Line {
+    tabs: usize,
+    spaces: usize,
+    blank: bool,
+    trailing_whitespace: bool,
+}
+
+/// The indentation of the lines of a code, computed once
+/// and shared by all its spaces.
+#[derive(Clone, Debug)]
+pub(crate) struct Lines {
+    lines: Vec<Line>,
+    width: usize,
+}
+
+impl Lines {
+    pub(crate) fn new(code: &[u8]) -> Self {
+        let lines: Vec<_> = code
+            .split(|byte| *byte == b'\n')
+            .map(|line| {
+                let line = line.strip_suffix(b"\r").unwrap_or(line);
+                let indent = line
+                    .iter()
+                    .take_while(|byte| matches!(byte, b' ' | b'\t'))
+                    .count();
+                let tabs = line[..indent].iter().filter(|byte| **byte == b'\t').count();
+                Line {
+                    tabs,
+                    spaces: indent - tabs,
+                    blank: indent == line.len(),
+                    trailing_whitespace: matches!(line.last(), Some(b' ' | b'\t')),
+                }
+            })
+            .collect();
+        let width = indent_width(&lines);
+        Self { lines, width }
+    }
+}
+
+// Detects the indentation width of a code as the most common increase
+// of the indentation made of spaces between two consecutive lines
+fn indent_width(lines: &[Line]) -> usize {
+    let mut increases = [0usize; 9];
+    let mut previous = 0;
+    for line in lines.iter().filter(|line| !line.blank && line.tabs == 0) {
+        let increase = line.spaces.saturating_sub(previous);
+        if let Some(count) = increases.get_mut(increase) {
+            *count += 1;
+        }
+        previous = line.spaces;
+    }
+    // The smallest width wins a tie
+    (1..increases.len())
+        .rev()
+        .max_by_key(|width| increases[*width])
+        .filter(|width| increases[*width] > 0)
+        .unwrap_or(DEFAULT_INDENT_WIDTH)
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::tools::{check_func_space, check_metrics};
+    use crate::{CppParser, PythonParser, RustParser};
+
+    use super::*;
+
+    #[test]
+    fn python_indentation() {
+        check_metrics::<PythonParser>(
+            "def foo(a):\n    if a:\n        for b in a:\n            print(b)  \n    return a\n",
+            "foo.py",
+            |metric| {
+                insta::assert_json_snapshot!(
+                    metric.indentation,
+                    @r###"
+                    {
+                      "style": "spaces",
+                      "tab_lines": 0.0,
+                      "space_lines": 4.0,
+                      "mixed_lines": 0.0,
+                      "level_max": 3.0,
+                      "level_average": 1.4,
+                      "trailing_whitespace": 1.0
+                    }"###
+                );
+            },
+        );
+    }
+
+    #[test]
+    fn cpp_mixed_indentation() {
+        check_metrics::<CppParser>(
+            "int f(int a) {\n\tif (a) {\n\t\treturn 1;\n\t}\n  return 0;\n}\n",
+            "foo.c",
+            |metric| {
+                assert_eq!(metric.indentation.style(), Style::Mixed);
+                assert_eq!(metric.indentation.tab_lines(), 3.);
+                assert_eq!(metric.indentation.space_lines(), 1.);
+                assert_eq!(metric.indentation.level_max(), 2.);
+            },
+        );
+    }
+
+    #[test]
+    fn rust_method_indentation() {
+        // The indentation of a method is relative to its first line
+        check_func_space::<RustParser, _>(
+            "impl Foo {\n  fn foo(&self) {\n    if self.a {\n      bar();\n    }\n  }\n}\n",
+            "foo.rs",
+            |func_space| {
+                let method = &func_space.spaces[0].spaces[0].metrics.indentation;
+                assert_eq!(method.level_max(), 2.);
+                assert_eq!(method.level_average(), 0.8);
+                assert_eq!(func_space.metrics.indentation.level_max(), 3.);
+                assert_eq!(func_space.metrics.indentation.style(), Style::Spaces);
+            },
+        );
+    }
+
+    #[test]
+    fn indentation_width() {
+        let lines = Lines::new(b"a\n  b\n    c\n  d\n      e\n");
+        assert_eq!(lines.width, 2);
+        let lines = Lines::new(b"a\n\tb\n");
+        assert_eq!(lines.width, DEFAULT_INDENT_WIDTH);
+    }
+}
@@ -0,0 +1,241 @@
+use serde::Serialize;
+use serde::ser::{SerializeStruct, Serializer};
+use std::fmt;
+
+use crate::checker::Checker;
+use crate::langs::*;
+use crate::macros::implement_metric_trait;
+use crate::node::Node;
+use crate::*;
+
+/// The `KotlinFuncs` metric.
+///
+/// This metric counts the Kotlin constructs which change the context
+/// in which the code of a function runs: the `suspend` functions,
+/// which run in a coroutine, the extension functions, which run on
+/// a receiver, and the function types with a receiver, which are
+/// the types of the lambdas with a receiver used to build DSLs.
+#[derive(Clone, Debug, Default)]
+pub struct Stats {
+    suspend_functions: usize,
+    extension_functions: usize,
+    receiver_lambdas: usize,
+    suspend_functions_sum: usize,
+    extension_functions_sum: usize,
+    receiver_lambdas_sum: usize,
+    is_enabled: bool,
+}
+
+impl Serialize for Stats {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        let mut st = serializer.serialize_struct("kotlin_funcs", 3)?;
+        st.serialize_field("suspend_functions", &self.suspend_functions_sum())?;
+        st.serialize_field("extension_functions", &self.extension_functions_sum())?;
+        st.serialize_field("receiver_lambdas", &self.receiver_lambdas_sum())?;
+        st.end()
+    }
+}
+
+impl fmt::Display for Stats {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "suspend_functions: {}, extension_functions: {}, receiver_lambdas: {}",
+            self.suspend_functions_sum(),
+            self.extension_functions_sum(),
+            self.receiver_lambdas_sum()
+        )
+    }
+}
+
+impl Stats {
+    /// Merges a second `KotlinFuncs` metric into the first one
+    pub fn merge(&mut self, other: &Stats) {
+        self.suspend_functions_sum += other.suspend_functions_sum;
+        self.extension_functions_sum += other.extension_functions_sum;
+        self.receiver_lambdas_sum += other.receiver_lambdas_sum;
+    }
+
+    /// Returns the number of `suspend` functions in a space.
+    #[inline(always)]
+    pub fn suspend_functions(&self) -> f64 {
+        self.suspend_functions as f64
+    }
+
+    /// Returns the number of extension functions in a space.
+    #[inline(always)]
+    pub fn extension_functions(&self) -> f64 {
+        self.extension_functions as f64
+    }
+
+    /// Returns the number of function types with a receiver in a space.
+    #[inline(always)]
+    pub fn receiver_lambdas(&self) -> f64 {
+        self.receiver_lambdas as f64
+    }
+
+    /// Returns the number of `suspend` functions sum in a space.
+    #[inline(always)]
+    pub fn suspend_functions_sum(&self) -> f64 {
+        self.suspend_functions_sum as f64
+    }
+
+    /// Returns the number of extension functions sum in a space.
+    #[inline(always)]
+    pub fn extension_functions_sum(&self) -> f64 {
+        self.extension_functions_sum as f64
+    }
+
+    /// Returns the number of function types with a receiver sum in a space.
+    #[inline(always)]
+    pub fn receiver_lambdas_sum(&self) -> f64 {
+        self.receiver_lambdas_sum as f64
+    }
+
+    #[inline(always)]
+    pub(crate) fn compute_sum(&mut self) {
+        self.suspend_functions_sum += self.suspend_functions;
+        self.extension_functions_sum += self.extension_functions;
+        self.receiver_lambdas_sum += self.receiver_lambdas;
+    }
+
+    // Checks if the `KotlinFuncs` metric is disabled
+    #[inline(always)]
+    pub(crate) fn is_disabled(&self) -> bool {
+        !self.is_enabled
+    }
+}
+
+pub trait KotlinFuncs
+where
+    Self: Checker,
+{
+    fn compute(node: &Node, stats: &mut Stats);
+}
+
+impl KotlinFuncs for KotlinCode {
+    fn compute(node: &Node, stats: &mut Stats) {
+        use Kotlin::*;
+
+        // Enables the `KotlinFuncs` metric for all the spaces
+        stats.is_enabled = true;
+
+        match node.kind_id().into() {
+            FunctionDeclaration => {
+                if node.children().any(|child| {
+                    child.kind_id() == Modifiers
+                        && child.children().any(|modifier| {
+                            modifier.kind_id() == FunctionModifier
+                                && modifier.is_child(Suspend as u16)
+                        })
+                }) {
+                    stats.suspend_functions += 1;
+                }
+                // The receiver type of an extension function
+                // is followed by a dot before its name
+                if node.is_child(DOT as u16) {
+                    stats.extension_functions += 1;
+                }
+            }
+            FunctionType if node.is_child(DOT as u16) => {
+                stats.receiver_lambdas += 1;
+            }
+            _ => {}
+        }
+    }
+}
+
+implement_metric_trait!(
+    KotlinFuncs,
+    PythonCode,
+    MozjsCode,
+    JavascriptCode,
+    TypescriptCode,
+    TsxCode,
+    RustCode,
+    CppCode,
+    JavaCode,
+    PreprocCode,
+    CcommentCode
+);
+
+#[cfg(test)]
+mod tests {
+    use crate::tools::{check_func_space, check_metrics};
+
+    use super::*;
+
+    #[test]
+    fn kotlin_no_funcs() {
+        check_metrics::<KotlinParser>("val a = 42", "foo.kt", |metric| {
+            insta::assert_json_snapshot!(
+                metric.kotlin_funcs,
+                @r###"
+                {
+                  "suspend_functions": 0.0,
+                  "extension_functions": 0.0,
+                  "receiver_lambdas": 0.0
+                }"###
+            );
+        });
+    }
+
+    #[test]
+    fn kotlin_funcs() {
+        check_metrics::<KotlinParser>(
+            "class Foo {
+                suspend fun load(): Int = 1
+                fun String.shout(): String = uppercase()
+                fun build(block: StringBuilder.() -> Unit) = buildString(block)
+            }
+            suspend fun <T> List<T>.second(): T = this[1]
+            fun apply(f: (Int) -> Int) = f(1)",
+            "foo.kt",
+            |metric| {
+                // Spaces: 7
+                insta::assert_json_snapshot!(
+                    metric.kotlin_funcs,
+                    @r###"
+                    {
+                      "suspend_functions": 2.0,
+                      "extension_functions": 2.0,
+                      "receiver_lambdas": 1.0
+                    }"###
+                );
+            },
+        );
+    }
+
+    #[test]
+    fn kotlin_funcs_class() {
+        check_func_space::<KotlinParser, _>(
+            "object Foo {
+                suspend fun load(): Int = 1
+                fun Int.twice(): Int = this * 2
+            }
+            fun f() {}",
+            "foo.kt",
+            |func_space| {
+                let object = &func_space.spaces[0];
+                assert_eq!(object.name.as_deref(), Some("Foo"));
+                assert_eq!(object.kind, SpaceKind::Class);
+                assert_eq!(object.metrics.kotlin_funcs.suspend_functions_sum(), 1.);
+                assert_eq!(object.metrics.kotlin_funcs.extension_functions_sum(), 1.);
+
+                let function = &func_space.spaces[1].metrics.kotlin_funcs;
+                assert_eq!(function.suspend_functions_sum(), 0.);
+                assert_eq!(function.extension_functions_sum(), 0.);
+            },
+        );
+    }
+
+    #[test]
+    fn java_no_kotlin_funcs() {
+        check_metrics::<JavaParser>("class X {}", "foo.java", |metric| {
+            assert!(metric.kotlin_funcs.is_disabled());
+        });
+    }
+}
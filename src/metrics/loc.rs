@@ -75,6 +75,8 @@ impl Sloc {
 #[derive(Debug, Clone)]
 pub struct Ploc {
     lines: HashSet<usize>,
+    // Lines containing braces, which are not counted as instruction lines
+    brace_lines: HashSet<usize>,
     ploc_min: usize,
     ploc_max: usize,
 }
@@ -83,6 +85,7 @@ impl Default for Ploc {
     fn default() -> Self {
         Self {
             lines: HashSet::default(),
+            brace_lines: HashSet::default(),
             ploc_min: usize::MAX,
             ploc_max: 0,
         }
@@ -115,11 +118,25 @@ impl Ploc {
         for l in other.lines.iter() {
             self.lines.insert(*l);
         }
+        for l in other.brace_lines.iter() {
+            self.brace_lines.insert(*l);
+        }
 
         self.ploc_min = self.ploc_min.min(other.ploc() as usize);
         self.ploc_max = self.ploc_max.max(other.ploc() as usize);
     }
 
+    // Lines containing only braces
+    #[inline(always)]
+    fn brace_only_lines(&self) -> usize {
+        self.brace_lines.difference(&self.lines).count()
+    }
+
+    #[inline(always)]
+    fn is_code_line(&self, line: usize) -> bool {
+        self.lines.contains(&line) || self.brace_lines.contains(&line)
+    }
+
     #[inline(always)]
     pub(crate) fn compute_minmax(&mut self) {
         if self.ploc_min == usize::MAX {
@@ -134,7 +151,7 @@ impl Ploc {
 pub struct Cloc {
     only_comment_lines: usize,
     code_comment_lines: usize,
-    comment_line_end: Option<usize>,
+    comment_lines: Option<(usize, usize)>,
     cloc_min: usize,
     cloc_max: usize,
 }
@@ -144,7 +161,7 @@ impl Default for Cloc {
         Self {
             only_comment_lines: 0,
             code_comment_lines: 0,
-            comment_line_end: Option::default(),
+            comment_lines: Option::default(),
             cloc_min: usize::MAX,
             cloc_max: 0,
         }
@@ -384,7 +401,10 @@ impl Stats {
     /// Counts the number of blank lines in a scope
     #[inline(always)]
     pub fn blank(&self) -> f64 {
-        self.sloc() - self.ploc() - self.cloc.only_comment_lines as f64
+        self.sloc()
+            - self.ploc()
+            - self.ploc.brace_only_lines() as f64
+            - self.cloc.only_comment_lines as f64
     }
 
     /// The `Sloc` metric average value.
@@ -501,11 +521,78 @@ impl Stats {
     }
 }
 
+/// The rules used to count the lines of the `Loc` metric suite
+/// in the corner cases where the compliance tools disagree.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct LocOptions {
+    /// Whether the lines containing only braces are instruction lines,
+    /// counted by `Ploc`
+    ///
+    /// When they are not, they are not counted as blank lines either.
+    pub brace_lines: bool,
+    /// Whether a comment on the same line of some code, such as
+    /// `a = 1; // one`, makes that line a comment line too, counted by `Cloc`
+    pub trailing_comments: bool,
+    /// Whether the lines that a block comment spanning many lines
+    /// shares with some code are comment lines too, counted by `Cloc`
+    pub block_comments_on_code: bool,
+    /// Whether the docstrings of Python modules, classes and functions
+    /// are only comment lines, counted by `Cloc`, and not logical lines
+    /// counted by `Lloc` too
+    ///
+    /// It is `false` by default.
+    pub docstrings_as_comments: bool,
+}
+
+impl Default for LocOptions {
+    fn default() -> Self {
+        Self {
+            brace_lines: true,
+            trailing_comments: true,
+            block_comments_on_code: true,
+            docstrings_as_comments: false,
+        }
+    }
+}
+
+impl LocOptions {
+    /// The names of the rules which can be excluded.
+    pub const RULES: &'static [&'static str] =
+        &["brace-lines", "trailing-comments", "block-comments-on-code"];
+
+    /// Returns the options without the rule with the given name.
+    ///
+    /// Returns `None` if the rule does not exist.
+    pub fn without(self, rule: &str) -> Option<Self> {
+        match rule {
+            "brace-lines" => Some(Self {
+                brace_lines: false,
+                ..self
+            }),
+            "trailing-comments" => Some(Self {
+                trailing_comments: false,
+                ..self
+            }),
+            "block-comments-on-code" => Some(Self {
+                block_comments_on_code: false,
+                ..self
+            }),
+            _ => None,
+        }
+    }
+}
+
 pub trait Loc
 where
     Self: Checker,
 {
-    fn compute(node: &Node, stats: &mut Stats, is_func_space: bool, is_unit: bool);
+    fn compute(
+        node: &Node,
+        stats: &mut Stats,
+        is_func_space: bool,
+        is_unit: bool,
+        options: &LocOptions,
+    );
 }
 
 #[inline(always)]
@@ -526,25 +613,29 @@ fn init(node: &Node, stats: &mut Stats, is_func_space: bool, is_unit: bool) -> (
 // the ones that are on an independent line.
 // This difference is necessary in order to avoid having
 // a wrong count for the blank metric.
-fn add_cloc_lines(stats: &mut Stats, start: usize, end: usize) {
+fn add_cloc_lines(stats: &mut Stats, start: usize, end: usize, options: &LocOptions) {
     let comment_diff = end - start;
-    let is_comment_after_code_line = stats.ploc.lines.contains(&start);
+    let is_comment_after_code_line = stats.ploc.is_code_line(start);
     if is_comment_after_code_line && comment_diff == 0 {
         // A comment is *entirely* next to a code line
-        stats.cloc.code_comment_lines += 1;
+        if options.trailing_comments {
+            stats.cloc.code_comment_lines += 1;
+        }
     } else if is_comment_after_code_line && comment_diff > 0 {
         // A block comment that starts next to a code line and ends on
         // independent lines.
-        stats.cloc.code_comment_lines += 1;
+        if options.block_comments_on_code {
+            stats.cloc.code_comment_lines += 1;
+        }
         stats.cloc.only_comment_lines += comment_diff;
     } else {
         // A comment on an independent line AND
         // a block comment on independent lines OR
         // a comment *before* a code line
         stats.cloc.only_comment_lines += (end - start) + 1;
-        // Save line end of a comment to check whether
+        // Save the lines of a comment to check whether
         // a comment *before* a code line is considered
-        stats.cloc.comment_line_end = Some(end);
+        stats.cloc.comment_lines = Some((start, end));
     }
 }
 
@@ -552,19 +643,88 @@ fn add_cloc_lines(stats: &mut Stats, start: usize, end: usize) {
 // Detects the comments that are on a code line but *before* the code part.
 // This difference is necessary in order to avoid having
 // a wrong count for the blank metric.
-fn check_comment_ends_on_code_line(stats: &mut Stats, start_code_line: usize) {
-    if let Some(end) = stats.cloc.comment_line_end
+fn check_comment_ends_on_code_line(
+    stats: &mut Stats,
+    start_code_line: usize,
+    options: &LocOptions,
+) {
+    if let Some((start, end)) = stats.cloc.comment_lines
         && end == start_code_line
-        && !stats.ploc.lines.contains(&start_code_line)
+        && !stats.ploc.is_code_line(start_code_line)
     {
         // Comment entirely *before* a code line
         stats.cloc.only_comment_lines -= 1;
-        stats.cloc.code_comment_lines += 1;
+        let is_counted = if start == end {
+            options.trailing_comments
+        } else {
+            options.block_comments_on_code
+        };
+        if is_counted {
+            stats.cloc.code_comment_lines += 1;
+        }
+    }
+}
+
+#[inline(always)]
+fn add_ploc_line(node: &Node, stats: &mut Stats, start: usize, options: &LocOptions) {
+    if options.brace_lines {
+        check_comment_ends_on_code_line(stats, start, options);
+        stats.ploc.lines.insert(start);
+    } else if matches!(node.kind(), "{" | "}") {
+        check_comment_ends_on_code_line(stats, start, options);
+        stats.ploc.brace_lines.insert(start);
+    } else if node.child_count() == 0 {
+        // A node, such as a block, can start with a brace,
+        // so only the lines of the tokens are taken into account
+        check_comment_ends_on_code_line(stats, start, options);
+        stats.ploc.lines.insert(start);
     }
 }
 
+// Checks whether an expression statement is the docstring of a module,
+// a class or a function, that is a string starting its body
+fn is_python_docstring(statement: &Node) -> bool {
+    use Python::*;
+
+    let is_string = statement.child_count() == 1
+        && statement
+            .child(0)
+            .is_some_and(|child| child.kind_id() == String);
+    let is_body = statement
+        .parent()
+        .is_some_and(|parent| match parent.kind_id().into() {
+            Module => true,
+            Block | Block2 => parent.parent().is_some_and(|definition| {
+                matches!(
+                    definition.kind_id().into(),
+                    FunctionDefinition | ClassDefinition
+                )
+            }),
+            _ => false,
+        });
+    if !is_string || !is_body {
+        return false;
+    }
+
+    // Only comments can precede a docstring
+    let mut previous = statement.previous_sibling();
+    while let Some(node) = previous {
+        if node.kind_id() != Comment {
+            return false;
+        }
+        previous = node.previous_sibling();
+    }
+    true
+}
+
 impl Loc for PythonCode {
-    fn compute(node: &Node, stats: &mut Stats, is_func_space: bool, is_unit: bool) {
+    fn compute(
+        node: &Node,
+        stats: &mut Stats,
+        is_func_space: bool,
+        is_unit: bool,
+        options: &LocOptions,
+    ) {
         use Python::*;
 
         let (start, end) = init(node, stats, is_func_space, is_unit);
@@ -572,15 +732,14 @@ impl Loc for PythonCode {
         match node.kind_id().into() {
             StringStart | StringEnd | StringContent | Block | Module => {}
             Comment => {
-                add_cloc_lines(stats, start, end);
+                add_cloc_lines(stats, start, end, options);
             }
             String => {
                 let parent = node.parent().unwrap();
                 if let ExpressionStatement = parent.kind_id().into() {
-                    add_cloc_lines(stats, start, end);
+                    add_cloc_lines(stats, start, end, options);
                 } else if parent.start_row() != start {
-                    check_comment_ends_on_code_line(stats, start);
-                    stats.ploc.lines.insert(start);
+                    add_ploc_line(node, stats, start, options);
                 }
             }
             Statement
@@ -603,20 +762,29 @@ impl Loc for PythonCode {
             | WithStatement
             | GlobalStatement
             | NonlocalStatement
-            | ExecStatement
-            | ExpressionStatement => {
+            | ExecStatement => {
                 stats.lloc.logical_lines += 1;
             }
+            ExpressionStatement => {
+                if !(options.docstrings_as_comments && is_python_docstring(node)) {
+                    stats.lloc.logical_lines += 1;
+                }
+            }
             _ => {
-                check_comment_ends_on_code_line(stats, start);
-                stats.ploc.lines.insert(start);
+                add_ploc_line(node, stats, start, options);
             }
         }
     }
 }
 
 impl Loc for MozjsCode {
-    fn compute(node: &Node, stats: &mut Stats, is_func_space: bool, is_unit: bool) {
+    fn compute(
+        node: &Node,
+        stats: &mut Stats,
+        is_func_space: bool,
+        is_unit: bool,
+        options: &LocOptions,
+    ) {
         use Mozjs::*;
 
         let (start, end) = init(node, stats, is_func_space, is_unit);
@@ -624,7 +792,7 @@ impl Loc for MozjsCode {
         match node.kind_id().into() {
             String | DQUOTE | Program => {}
             Comment => {
-                add_cloc_lines(stats, start, end);
+                add_cloc_lines(stats, start, end, options);
             }
             ExpressionStatement | ExportStatement | ImportStatement | StatementBlock
             | IfStatement | SwitchStatement | ForStatement | ForInStatement | WhileStatement
@@ -634,15 +802,20 @@ impl Loc for MozjsCode {
                 stats.lloc.logical_lines += 1;
             }
             _ => {
-                check_comment_ends_on_code_line(stats, start);
-                stats.ploc.lines.insert(start);
+                add_ploc_line(node, stats, start, options);
             }
         }
     }
 }
 
 impl Loc for JavascriptCode {
-    fn compute(node: &Node, stats: &mut Stats, is_func_space: bool, is_unit: bool) {
+    fn compute(
+        node: &Node,
+        stats: &mut Stats,
+        is_func_space: bool,
+        is_unit: bool,
+        options: &LocOptions,
+    ) {
         use Javascript::*;
 
         let (start, end) = init(node, stats, is_func_space, is_unit);
@@ -650,7 +823,7 @@ impl Loc for JavascriptCode {
         match node.kind_id().into() {
             String | DQUOTE | Program => {}
             Comment => {
-                add_cloc_lines(stats, start, end);
+                add_cloc_lines(stats, start, end, options);
             }
             ExpressionStatement | ExportStatement | ImportStatement | StatementBlock
             | IfStatement | SwitchStatement | ForStatement | ForInStatement | WhileStatement
@@ -660,15 +833,20 @@ impl Loc for JavascriptCode {
                 stats.lloc.logical_lines += 1;
             }
             _ => {
-                check_comment_ends_on_code_line(stats, start);
-                stats.ploc.lines.insert(start);
+                add_ploc_line(node, stats, start, options);
             }
         }
     }
 }
 
 impl Loc for TypescriptCode {
-    fn compute(node: &Node, stats: &mut Stats, is_func_space: bool, is_unit: bool) {
+    fn compute(
+        node: &Node,
+        stats: &mut Stats,
+        is_func_space: bool,
+        is_unit: bool,
+        options: &LocOptions,
+    ) {
         use Typescript::*;
 
         let (start, end) = init(node, stats, is_func_space, is_unit);
@@ -676,7 +854,7 @@ impl Loc for TypescriptCode {
         match node.kind_id().into() {
             String | DQUOTE | Program => {}
             Comment => {
-                add_cloc_lines(stats, start, end);
+                add_cloc_lines(stats, start, end, options);
             }
             ExpressionStatement | ExportStatement | ImportStatement | StatementBlock
             | IfStatement | SwitchStatement | ForStatement | ForInStatement | WhileStatement
@@ -686,15 +864,20 @@ impl Loc for TypescriptCode {
                 stats.lloc.logical_lines += 1;
             }
             _ => {
-                check_comment_ends_on_code_line(stats, start);
-                stats.ploc.lines.insert(start);
+                add_ploc_line(node, stats, start, options);
             }
         }
     }
 }
 
 impl Loc for TsxCode {
-    fn compute(node: &Node, stats: &mut Stats, is_func_space: bool, is_unit: bool) {
+    fn compute(
+        node: &Node,
+        stats: &mut Stats,
+        is_func_space: bool,
+        is_unit: bool,
+        options: &LocOptions,
+    ) {
         use Tsx::*;
 
         let (start, end) = init(node, stats, is_func_space, is_unit);
@@ -702,7 +885,7 @@ impl Loc for TsxCode {
         match node.kind_id().into() {
             String | DQUOTE | Program => {}
             Comment => {
-                add_cloc_lines(stats, start, end);
+                add_cloc_lines(stats, start, end, options);
             }
             ExpressionStatement | ExportStatement | ImportStatement | StatementBlock
             | IfStatement | SwitchStatement | ForStatement | ForInStatement | WhileStatement
@@ -712,15 +895,20 @@ impl Loc for TsxCode {
                 stats.lloc.logical_lines += 1;
             }
             _ => {
-                check_comment_ends_on_code_line(stats, start);
-                stats.ploc.lines.insert(start);
+                add_ploc_line(node, stats, start, options);
             }
         }
     }
 }
 
 impl Loc for RustCode {
-    fn compute(node: &Node, stats: &mut Stats, is_func_space: bool, is_unit: bool) {
+    fn compute(
+        node: &Node,
+        stats: &mut Stats,
+        is_func_space: bool,
+        is_unit: bool,
+        options: &LocOptions,
+    ) {
         use Rust::*;
 
         let (start, end) = init(node, stats, is_func_space, is_unit);
@@ -740,7 +928,7 @@ impl Loc for RustCode {
             | InnerDocCommentMarker
             | BANG => {}
             BlockComment => {
-                add_cloc_lines(stats, start, end);
+                add_cloc_lines(stats, start, end, options);
             }
             LineComment => {
                 // Exclude the last line for `LineComment` containing a `DocComment`,
@@ -751,7 +939,7 @@ impl Loc for RustCode {
                 } else {
                     end
                 };
-                add_cloc_lines(stats, start, end);
+                add_cloc_lines(stats, start, end, options);
             }
             Statement
             | EmptyStatement
@@ -762,15 +950,20 @@ impl Loc for RustCode {
                 stats.lloc.logical_lines += 1;
             }
             _ => {
-                check_comment_ends_on_code_line(stats, start);
-                stats.ploc.lines.insert(start);
+                add_ploc_line(node, stats, start, options);
             }
         }
     }
 }
 
 impl Loc for CppCode {
-    fn compute(node: &Node, stats: &mut Stats, is_func_space: bool, is_unit: bool) {
+    fn compute(
+        node: &Node,
+        stats: &mut Stats,
+        is_func_space: bool,
+        is_unit: bool,
+        options: &LocOptions,
+    ) {
         use Cpp::*;
 
         let (start, end) = init(node, stats, is_func_space, is_unit);
@@ -779,7 +972,7 @@ impl Loc for CppCode {
             RawStringLiteral | StringLiteral | DeclarationList | FieldDeclarationList
             | TranslationUnit => {}
             Comment => {
-                add_cloc_lines(stats, start, end);
+                add_cloc_lines(stats, start, end, options);
             }
             WhileStatement | SwitchStatement | CaseStatement | IfStatement | ForStatement
             | ReturnStatement | BreakStatement | ContinueStatement | GotoStatement
@@ -802,8 +995,7 @@ impl Loc for CppCode {
                 }
             }
             _ => {
-                check_comment_ends_on_code_line(stats, start);
-                stats.ploc.lines.insert(start);
+                add_ploc_line(node, stats, start, options);
 
                 // As reported here: https://github.com/tree-sitter/tree-sitter-cpp/issues/276
                 // `tree-sitter-cpp` doesn't expand macros, providing a single `PreprocArg` node for the entire macro argument.
@@ -819,7 +1011,13 @@ impl Loc for CppCode {
 }
 
 impl Loc for JavaCode {
-    fn compute(node: &Node, stats: &mut Stats, is_func_space: bool, is_unit: bool) {
+    fn compute(
+        node: &Node,
+        stats: &mut Stats,
+        is_func_space: bool,
+        is_unit: bool,
+        options: &LocOptions,
+    ) {
         use Java::*;
 
         let (start, end) = init(node, stats, is_func_space, is_unit);
@@ -829,7 +1027,7 @@ impl Loc for JavaCode {
         match kind_id {
             Program => {}
             LineComment | BlockComment => {
-                add_cloc_lines(stats, start, end);
+                add_cloc_lines(stats, start, end, options);
             }
             AssertStatement | BreakStatement | ContinueStatement | DoStatement
             | EnhancedForStatement | ExpressionStatement | ForStatement | IfStatement
@@ -850,8 +1048,7 @@ impl Loc for JavaCode {
                 }
             }
             _ => {
-                check_comment_ends_on_code_line(stats, start);
-                stats.ploc.lines.insert(start);
+                add_ploc_line(node, stats, start, options);
             }
         }
     }
@@ -3482,4 +3679,93 @@ mod tests {
             },
         );
     }
+
+    #[test]
+    fn cpp_loc_options() {
+        let source = "int f(int a)
+{
+    int b = a; // Trailing comment
+
+    /* Block comment
+       ending on code */ int c = b;
+    return c;
+}
+";
+        let loc = |options: LocOptions| {
+            let options = MetricsOptions {
+                loc: options,
+                ..Default::default()
+            };
+            let space = get_function_spaces_with_options(
+                &LANG::Cpp,
+                source.as_bytes().to_vec(),
+                std::path::Path::new("foo.cpp"),
+                None,
+                &options,
+            )
+            .unwrap();
+            let loc = &space.metrics.loc;
+            (loc.sloc(), loc.ploc(), loc.cloc(), loc.blank())
+        };
+
+        assert_eq!(loc(LocOptions::default()), (8., 6., 3., 1.));
+        // The braces on lines 2 and 8 are not counted, neither as blank lines
+        assert_eq!(
+            loc(LocOptions {
+                brace_lines: false,
+                ..Default::default()
+            }),
+            (8., 4., 3., 1.)
+        );
+        // The comment on line 3 is not counted
+        assert_eq!(
+            loc(LocOptions {
+                trailing_comments: false,
+                ..Default::default()
+            }),
+            (8., 6., 2., 1.)
+        );
+        // The line 6 of the block comment is not counted
+        assert_eq!(
+            loc(LocOptions {
+                block_comments_on_code: false,
+                ..Default::default()
+            }),
+            (8., 6., 2., 1.)
+        );
+    }
+
+    #[test]
+    fn python_docstrings_as_comments() {
+        let source = "def f(a):
+    \"\"\"Docstring
+    of f.\"\"\"
+    \"not a docstring\"
+    return a
+";
+        let loc = |docstrings_as_comments: bool| {
+            let options = MetricsOptions {
+                loc: LocOptions {
+                    docstrings_as_comments,
+                    ..Default::default()
+                },
+                ..Default::default()
+            };
+            let space = get_function_spaces_with_options(
+                &LANG::Python,
+                source.as_bytes().to_vec(),
+                std::path::Path::new("foo.py"),
+                None,
+                &options,
+            )
+            .unwrap();
+            let loc = &space.spaces[0].metrics.loc;
+            (loc.sloc(), loc.ploc(), loc.lloc(), loc.cloc())
+        };
+
+        // The strings are comment lines, and logical lines too
+        assert_eq!(loc(false), (5., 3., 3., 3.));
+        // The docstring is not a logical line, unlike the other string
+        assert_eq!(loc(true), (5., 3., 2., 3.));
+    }
 }
@@ -0,0 +1,535 @@
+use serde::Serialize;
+use serde::ser::{SerializeStruct, Serializer};
+use std::collections::HashSet;
+use std::fmt;
+
+use crate::checker::Checker;
+use crate::langs::*;
+use crate::macros::implement_metric_trait;
+use crate::node::Node;
+use crate::*;
+
+/// The `Locals` metric.
+///
+/// This metric counts the local variables declared in a function/method,
+/// such as the ones introduced by a `let` declaration or by the loop
+/// variables of a `for` statement, each name bound by a destructuring
+/// pattern being a variable.
+/// The parameters of a function are not local variables, and
+/// the variables declared in a closure belong to the closure.
+#[derive(Debug, Clone)]
+pub struct Stats {
+    locals: usize,
+    locals_sum: usize,
+    total_space_functions: usize,
+    locals_min: usize,
+    locals_max: usize,
+    // The names assigned in a space, for the languages
+    // where a variable is declared by its first assignment
+    names: HashSet<String>,
+    is_enabled: bool,
+}
+
+impl Default for Stats {
+    fn default() -> Self {
+        Self {
+            locals: 0,
+            locals_sum: 0,
+            total_space_functions: 1,
+            locals_min: usize::MAX,
+            locals_max: 0,
+            names: HashSet::new(),
+            is_enabled: false,
+        }
+    }
+}
+
+impl Serialize for Stats {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        let mut st = serializer.serialize_struct("locals", 4)?;
+        st.serialize_field("sum", &self.locals_sum())?;
+        st.serialize_field("average", &self.locals_average())?;
+        st.serialize_field("min", &self.locals_min())?;
+        st.serialize_field("max", &self.locals_max())?;
+        st.end()
+    }
+}
+
+impl fmt::Display for Stats {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "sum: {}, average: {}, min: {}, max: {}",
+            self.locals_sum(),
+            self.locals_average(),
+            self.locals_min(),
+            self.locals_max()
+        )
+    }
+}
+
+impl Stats {
+    /// Merges a second `Locals` metric into the first one
+    pub fn merge(&mut self, other: &Stats) {
+        self.locals_max = self.locals_max.max(other.locals_max);
+        self.locals_min = self.locals_min.min(other.locals_min);
+        self.locals_sum += other.locals_sum;
+    }
+
+    /// Returns the `Locals` metric value
+    pub fn locals(&self) -> f64 {
+        self.locals as f64
+    }
+
+    /// Returns the `Locals` metric sum value
+    pub fn locals_sum(&self) -> f64 {
+        self.locals_sum as f64
+    }
+
+    /// Returns the `Locals` metric minimum value
+    pub fn locals_min(&self) -> f64 {
+        self.locals_min as f64
+    }
+
+    /// Returns the `Locals` metric maximum value
+    pub fn locals_max(&self) -> f64 {
+        self.locals_max as f64
+    }
+
+    /// Returns the `Locals` metric average value
+    ///
+    /// This value is computed dividing the `Locals` value
+    /// for the total number of functions/closures in a space.
+    ///
+    /// If there are no functions in a code, its value is `NAN`.
+    pub fn locals_average(&self) -> f64 {
+        self.locals_sum() / self.total_space_functions as f64
+    }
+
+    #[inline(always)]
+    pub(crate) fn compute_minmax(&mut self) {
+        self.locals_max = self.locals_max.max(self.locals);
+        self.locals_min = self.locals_min.min(self.locals);
+        self.locals_sum += self.locals;
+    }
+
+    pub(crate) fn finalize(&mut self, total_space_functions: usize) {
+        self.total_space_functions = total_space_functions;
+    }
+
+    // Checks if the `Locals` metric is disabled
+    #[inline(always)]
+    pub(crate) fn is_disabled(&self) -> bool {
+        !self.is_enabled
+    }
+
+    // Counts a name the first time it is assigned
+    fn add_name(&mut self, name: Option<&str>) {
+        if let Some(name) = name
+            && self.names.insert(name.to_owned())
+        {
+            self.locals += 1;
+        }
+    }
+}
+
+// Checks if a node is in the body of a function or of a closure,
+// rather than in the one of a class or at the top level of a file
+fn is_local<T: Checker>(node: &Node) -> bool {
+    let mut ancestor = node.parent();
+    while let Some(parent) = ancestor {
+        if T::is_func(&parent) || T::is_closure(&parent) {
+            return true;
+        }
+        if T::is_func_space(&parent) {
+            return false;
+        }
+        ancestor = parent.parent();
+    }
+    false
+}
+
+pub trait Locals
+where
+    Self: Checker,
+{
+    fn compute(node: &Node, code: &[u8], stats: &mut Stats);
+}
+
+// Adds the names bound by an assignment target, such as `a`
+// or `a, (b, c)`, but not by `self.a` or `a[0]`
+fn python_targets(node: &Node, code: &[u8], stats: &mut Stats) {
+    use Python::*;
+
+    match node.kind_id().into() {
+        Identifier => stats.add_name(node.utf8_text(code)),
+        PatternList
+        | TuplePattern
+        | TuplePattern2
+        | ListPattern
+        | ListPattern2
+        | ListSplatPattern
+        | Tuple
+        | List
+        | ParenthesizedExpression
+        | AsPatternTarget => {
+            for child in node.children() {
+                python_targets(&child, code, stats);
+            }
+        }
+        _ => {}
+    }
+}
+
+impl Locals for PythonCode {
+    fn compute(node: &Node, code: &[u8], stats: &mut Stats) {
+        // Enables the `Locals` metric for all the spaces
+        stats.is_enabled = true;
+
+        // A variable is declared by its first assignment
+        let target = match node.kind_id().into() {
+            Python::Assignment | Python::ForStatement => node.child_by_field_name("left"),
+            Python::NamedExpression => node.child_by_field_name("name"),
+            Python::AsPatternTarget => Some(*node),
+            _ => None,
+        };
+        if let Some(target) = target
+            && is_local::<Self>(node)
+        {
+            python_targets(&target, code, stats);
+        }
+    }
+}
+
+// The variables of `let`, `const` and `var` declarations and of the
+// `for...in` and `for...of` statements, including the ones bound by the
+// destructuring patterns, such as `const { a, b: c, ...d } = value`
+macro_rules! js_locals {
+    ($lang:ident) => {
+        fn compute(node: &Node, _code: &[u8], stats: &mut Stats) {
+            use $lang::*;
+
+            stats.is_enabled = true;
+
+            fn bindings(node: &Node) -> usize {
+                match node.kind_id().into() {
+                    Identifier | ShorthandPropertyIdentifierPattern => 1,
+                    AssignmentPattern | ObjectAssignmentPattern => node
+                        .child_by_field_name("left")
+                        .map_or(0, |left| bindings(&left)),
+                    PairPattern => node
+                        .child_by_field_name("value")
+                        .map_or(0, |value| bindings(&value)),
+                    ObjectPattern | ArrayPattern | RestPattern => {
+                        node.children().map(|child| bindings(&child)).sum()
+                    }
+                    _ => 0,
+                }
+            }
+
+            let pattern = match node.kind_id().into() {
+                VariableDeclarator => node.child_by_field_name("name"),
+                // `for (const a of b)`, but not `for (a of b)`
+                ForInStatement => node
+                    .child_by_field_name("kind")
+                    .and(node.child_by_field_name("left")),
+                _ => None,
+            };
+            if let Some(pattern) = pattern
+                && is_local::<Self>(node)
+            {
+                stats.locals += bindings(&pattern);
+            }
+        }
+    };
+}
+
+impl Locals for MozjsCode {
+    js_locals!(Mozjs);
+}
+
+impl Locals for JavascriptCode {
+    js_locals!(Javascript);
+}
+
+impl Locals for TypescriptCode {
+    js_locals!(Typescript);
+}
+
+impl Locals for TsxCode {
+    js_locals!(Tsx);
+}
+
+impl Locals for JavaCode {
+    fn compute(node: &Node, _code: &[u8], stats: &mut Stats) {
+        use Java::*;
+
+        stats.is_enabled = true;
+
+        match node.kind_id().into() {
+            // `int a = 1, b;`
+            LocalVariableDeclaration => {
+                stats.locals += node
+                    .children()
+                    .filter(|child| child.kind_id() == VariableDeclarator)
+                    .count();
+            }
+            // `for (String a : b)` and `try (var a = open())`
+            EnhancedForStatement | Resource if node.child_by_field_name("name").is_some() => {
+                stats.locals += 1;
+            }
+            _ => {}
+        }
+    }
+}
+
+impl Locals for KotlinCode {
+    fn compute(node: &Node, _code: &[u8], stats: &mut Stats) {
+        use Kotlin::*;
+
+        stats.is_enabled = true;
+
+        // `val a = 1`, `val (a, b) = pair` and `for (a in b)`,
+        // but not the parameters of the lambdas
+        if Kotlin::VariableDeclaration == node.kind_id()
+            && let Some(mut parent) = node.parent()
+        {
+            if parent.kind_id() == MultiVariableDeclaration
+                && let Some(declaration) = parent.parent()
+            {
+                parent = declaration;
+            }
+            if matches!(parent.kind_id().into(), PropertyDeclaration | ForStatement)
+                && is_local::<Self>(&parent)
+            {
+                stats.locals += 1;
+            }
+        }
+    }
+}
+
+// The names bound by a pattern, such as `(a, mut b)`,
+// `Some(a)` or `Point { x, y: b }`
+fn rust_bindings(node: &Node) -> usize {
+    use Rust::*;
+
+    match node.kind_id().into() {
+        Identifier => 1,
+        ShorthandFieldIdentifier => 1,
+        // The paths of the constants and of the enum variants
+        ScopedIdentifier => 0,
+        TupleStructPattern => node
+            .children()
+            .skip(1)
+            .map(|child| rust_bindings(&child))
+            .sum(),
+        _ => node.children().map(|child| rust_bindings(&child)).sum(),
+    }
+}
+
+impl Locals for RustCode {
+    fn compute(node: &Node, _code: &[u8], stats: &mut Stats) {
+        use Rust::*;
+
+        stats.is_enabled = true;
+
+        // `let (a, b) = pair;` and `for a in b {}`
+        if matches!(node.kind_id().into(), LetDeclaration | ForExpression)
+            && let Some(pattern) = node.child_by_field_name("pattern")
+        {
+            stats.locals += rust_bindings(&pattern);
+        }
+    }
+}
+
+impl Locals for CppCode {
+    fn compute(node: &Node, _code: &[u8], stats: &mut Stats) {
+        use Cpp::*;
+
+        stats.is_enabled = true;
+
+        match node.kind_id().into() {
+            // `int a = 1, *b;` and `auto [a, b] = pair;`,
+            // but not the prototypes of the functions
+            Declaration | Declaration2 | Declaration3 | Declaration4 if is_local::<Self>(node) => {
+                for child in node.children() {
+                    let declarator = match child.kind_id().into() {
+                        InitDeclarator => child.child_by_field_name("declarator").unwrap_or(child),
+                        _ => child,
+                    };
+                    match declarator.kind_id().into() {
+                        Identifier | PointerDeclarator | PointerDeclarator2
+                        | ReferenceDeclarator | ReferenceDeclarator2 | ReferenceDeclarator3
+                        | ReferenceDeclarator4 | ArrayDeclarator | ArrayDeclarator2
+                        | ArrayDeclarator3 => stats.locals += 1,
+                        StructuredBindingDeclarator => {
+                            stats.locals += declarator
+                                .children()
+                                .filter(|name| name.kind_id() == Identifier)
+                                .count();
+                        }
+                        _ => {}
+                    }
+                }
+            }
+            // `for (auto a : b)`
+            ForRangeLoop => stats.locals += 1,
+            _ => {}
+        }
+    }
+}
+
+implement_metric_trait!(Locals, PreprocCode, CcommentCode);
+
+#[cfg(test)]
+mod tests {
+    use crate::tools::check_metrics;
+
+    use super::*;
+
+    #[test]
+    fn python_locals() {
+        check_metrics::<PythonParser>(
+            "x = 1
+
+            def f(a):
+                b = 1
+                b = 2
+                c, (d, e) = a
+                self.x = 1
+                for i in range(3):
+                    pass
+                with open(a) as g:
+                    pass
+                if (h := len(a)) > 0:
+                    pass
+
+            class A:
+                y = 1
+
+                def g(self):
+                    return self.y",
+            "foo.py",
+            |metric| {
+                insta::assert_json_snapshot!(
+                    metric.locals,
+                    @r###"
+                    {
+                      "sum": 7.0,
+                      "average": 3.5,
+                      "min": 0.0,
+                      "max": 7.0
+                    }"###
+                );
+            },
+        );
+    }
+
+    #[test]
+    fn rust_locals() {
+        check_metrics::<RustParser>(
+            "fn f(a: i32) {
+                let b = 1;
+                let (c, mut d) = (1, 2);
+                let Some(e) = Some(1) else { return };
+                let Point { x, y: g, .. } = p;
+                let _ = 1;
+                for i in 0..2 {}
+                let h = |k| {
+                    let l = k;
+                    l
+                };
+            }",
+            "foo.rs",
+            |metric| {
+                insta::assert_json_snapshot!(
+                    metric.locals,
+                    @r###"
+                    {
+                      "sum": 9.0,
+                      "average": 4.5,
+                      "min": 0.0,
+                      "max": 8.0
+                    }"###
+                );
+            },
+        );
+    }
+
+    #[test]
+    fn javascript_locals() {
+        check_metrics::<JavascriptParser>(
+            "const top = 1;
+            function f() {
+                let a = 1, b;
+                const { c, d: e, g = h, ...i } = x;
+                var [j, k = 2] = y;
+                for (const l of m) {}
+                for (n of m) {}
+            }",
+            "foo.js",
+            |metric| {
+                assert_eq!(metric.locals.locals_sum(), 9.);
+                assert_eq!(metric.locals.locals_max(), 9.);
+            },
+        );
+    }
+
+    #[test]
+    fn java_locals() {
+        check_metrics::<JavaParser>(
+            "class A {
+                int field = 1;
+                void f() {
+                    int a = 1, b;
+                    for (int c = 0; c < 1; c++) {}
+                    for (String d : xs) {}
+                    try (var e = open()) {}
+                }
+            }",
+            "foo.java",
+            |metric| {
+                assert_eq!(metric.locals.locals_sum(), 5.);
+            },
+        );
+    }
+
+    #[test]
+    fn kotlin_locals() {
+        check_metrics::<KotlinParser>(
+            "val top = 1
+            fun f() {
+                val a = 1
+                var b: Int = 2
+                val (c, d) = p
+                for (e in xs) {}
+            }",
+            "foo.kt",
+            |metric| {
+                assert_eq!(metric.locals.locals_sum(), 5.);
+            },
+        );
+    }
+
+    #[test]
+    fn cpp_locals() {
+        check_metrics::<CppParser>(
+            "int top = 1;
+
+            void f() {
+                int a = 1, *b, c[2];
+                auto [d, e] = pair;
+                int g(int);
+                for (int i = 0; i < 2; i++) {}
+                for (auto j : xs) {}
+            }",
+            "foo.cpp",
+            |metric| {
+                assert_eq!(metric.locals.locals_sum(), 7.);
+            },
+        );
+    }
+}
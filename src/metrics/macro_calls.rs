@@ -0,0 +1,285 @@
+use serde::Serialize;
+use serde::ser::{SerializeStruct, Serializer};
+use std::fmt;
+
+use crate::checker::Checker;
+use crate::langs::*;
+use crate::macros::implement_metric_trait;
+use crate::node::Node;
+use crate::*;
+
+/// The `MacroCalls` metric.
+///
+/// This metric counts the macro invocations of a space and
+/// the statements of the function bodies which are macro invocations.
+/// A function is dominated by macros when more than half of
+/// the statements of its body are macro invocations, since their
+/// logic is not taken into account by the other metrics.
+#[derive(Clone, Debug, Default)]
+pub struct Stats {
+    invocations: usize,
+    statements: usize,
+    macro_statements: usize,
+    invocations_sum: usize,
+    statements_sum: usize,
+    macro_statements_sum: usize,
+    dominated_sum: usize,
+    is_enabled: bool,
+}
+
+impl Serialize for Stats {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        let mut st = serializer.serialize_struct("macro_calls", 4)?;
+        st.serialize_field("invocations", &self.invocations_sum())?;
+        st.serialize_field("statements", &self.statements_sum())?;
+        st.serialize_field("macro_statements", &self.macro_statements_sum())?;
+        st.serialize_field("dominated_functions", &self.dominated_sum())?;
+        st.end()
+    }
+}
+
+impl fmt::Display for Stats {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "invocations: {}, statements: {}, macro_statements: {}, dominated_functions: {}",
+            self.invocations_sum(),
+            self.statements_sum(),
+            self.macro_statements_sum(),
+            self.dominated_sum()
+        )
+    }
+}
+
+impl Stats {
+    /// Merges a second `MacroCalls` metric into the first one
+    pub fn merge(&mut self, other: &Stats) {
+        self.invocations_sum += other.invocations_sum;
+        self.statements_sum += other.statements_sum;
+        self.macro_statements_sum += other.macro_statements_sum;
+        self.dominated_sum += other.dominated_sum;
+    }
+
+    /// Returns the number of macro invocations in a space.
+    #[inline(always)]
+    pub fn invocations(&self) -> f64 {
+        self.invocations as f64
+    }
+
+    /// Returns the number of statements of the body of a function.
+    #[inline(always)]
+    pub fn statements(&self) -> f64 {
+        self.statements as f64
+    }
+
+    /// Returns the number of statements of the body of a function
+    /// which are macro invocations.
+    #[inline(always)]
+    pub fn macro_statements(&self) -> f64 {
+        self.macro_statements as f64
+    }
+
+    /// Returns the number of macro invocations sum in a space.
+    #[inline(always)]
+    pub fn invocations_sum(&self) -> f64 {
+        self.invocations_sum as f64
+    }
+
+    /// Returns the number of statements sum in a space.
+    #[inline(always)]
+    pub fn statements_sum(&self) -> f64 {
+        self.statements_sum as f64
+    }
+
+    /// Returns the number of macro statements sum in a space.
+    #[inline(always)]
+    pub fn macro_statements_sum(&self) -> f64 {
+        self.macro_statements_sum as f64
+    }
+
+    /// Returns the number of functions dominated by macros in a space.
+    #[inline(always)]
+    pub fn dominated_sum(&self) -> f64 {
+        self.dominated_sum as f64
+    }
+
+    /// Checks whether more than half of the statements of the body
+    /// of a function are macro invocations.
+    #[inline(always)]
+    pub fn is_dominated(&self) -> bool {
+        2 * self.macro_statements > self.statements
+    }
+
+    #[inline(always)]
+    pub(crate) fn compute_sum(&mut self) {
+        self.invocations_sum += self.invocations;
+        self.statements_sum += self.statements;
+        self.macro_statements_sum += self.macro_statements;
+        self.dominated_sum += self.is_dominated() as usize;
+    }
+
+    // Checks if the `MacroCalls` metric is disabled
+    #[inline(always)]
+    pub(crate) fn is_disabled(&self) -> bool {
+        !self.is_enabled
+    }
+}
+
+pub trait MacroCalls
+where
+    Self: Checker,
+{
+    fn compute(node: &Node, stats: &mut Stats);
+}
+
+impl MacroCalls for RustCode {
+    fn compute(node: &Node, stats: &mut Stats) {
+        use Rust::*;
+
+        // Enables the `MacroCalls` metric for all the spaces
+        stats.is_enabled = true;
+
+        match node.kind_id().into() {
+            MacroInvocation => {
+                stats.invocations += 1;
+            }
+            Block
+                if node
+                    .parent()
+                    .is_some_and(|parent| Self::is_func(&parent) || Self::is_closure(&parent)) =>
+            {
+                for statement in node.children() {
+                    if matches!(statement.kind_id().into(), LBRACE | RBRACE)
+                        || Self::is_comment(&statement)
+                    {
+                        continue;
+                    }
+                    stats.statements += 1;
+
+                    let expression = match statement.kind_id().into() {
+                        ExpressionStatement => statement.child(0),
+                        LetDeclaration => statement.child_by_field_name("value"),
+                        _ => Some(statement),
+                    };
+                    if expression.is_some_and(|expression| expression.kind_id() == MacroInvocation)
+                    {
+                        stats.macro_statements += 1;
+                    }
+                }
+            }
+            _ => {}
+        }
+    }
+}
+
+implement_metric_trait!(
+    MacroCalls,
+    PythonCode,
+    MozjsCode,
+    JavascriptCode,
+    TypescriptCode,
+    TsxCode,
+    CppCode,
+    JavaCode,
+    PreprocCode,
+    CcommentCode,
+    KotlinCode
+);
+
+#[cfg(test)]
+mod tests {
+    use std::path::Path;
+
+    use crate::langs::{LANG, get_function_spaces_with_options};
+    use crate::spaces::{FuncSpace, MetricsOptions, SpaceKind};
+
+    fn spaces(source: &str, macros: bool) -> FuncSpace {
+        let options = MetricsOptions {
+            macros,
+            ..Default::default()
+        };
+        get_function_spaces_with_options(
+            &LANG::Rust,
+            source.as_bytes().to_vec(),
+            Path::new("foo.rs"),
+            None,
+            &options,
+        )
+        .unwrap()
+    }
+
+    #[test]
+    fn rust_macro_calls() {
+        let space = spaces(
+            "fn f(a: i32) {
+    println!(\"{}\", a);
+    assert!(a > 0);
+    let b = vec![a];
+    b.len()
+}
+
+fn g(a: i32) -> i32 {
+    let b = a + 1;
+    // A comment
+    debug_assert!(b > a);
+    b * 2
+}",
+            true,
+        );
+
+        let f = &space.spaces[0].metrics.macro_calls;
+        assert_eq!(f.invocations(), 3.);
+        assert_eq!(f.statements(), 4.);
+        assert_eq!(f.macro_statements(), 3.);
+        assert!(f.is_dominated());
+
+        let g = &space.spaces[1].metrics.macro_calls;
+        assert_eq!(g.statements(), 3.);
+        assert_eq!(g.macro_statements(), 1.);
+        assert!(!g.is_dominated());
+
+        insta::assert_json_snapshot!(
+            space.metrics.macro_calls,
+            @r###"
+            {
+              "invocations": 4.0,
+              "statements": 7.0,
+              "macro_statements": 4.0,
+              "dominated_functions": 1.0
+            }"###
+        );
+    }
+
+    #[test]
+    fn rust_macro_spaces() {
+        let source = "macro_rules! square {
+    ($x:expr) => {
+        $x * $x
+    };
+}
+
+fn f(a: i32) -> i32 {
+    square!(a)
+}";
+
+        let space = spaces(source, true);
+        let kinds: Vec<_> = space
+            .spaces
+            .iter()
+            .map(|space| (space.name.as_deref().unwrap(), space.kind))
+            .collect();
+        assert_eq!(
+            kinds,
+            vec![("square", SpaceKind::Macro), ("f", SpaceKind::Function)]
+        );
+        assert_eq!(space.spaces[0].metrics.loc.sloc(), 5.);
+
+        // Macros are neither spaces nor counted by default
+        let space = spaces(source, false);
+        assert_eq!(space.spaces.len(), 1);
+        assert!(space.metrics.macro_calls.is_disabled());
+    }
+}
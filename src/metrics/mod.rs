@@ -1,12 +1,22 @@
 pub mod abc;
+pub mod accessors;
+pub mod annotations;
+pub mod assertions;
+pub mod callbacks;
 pub mod cognitive;
 pub mod cyclomatic;
 pub mod exit;
 pub mod halstead;
+pub mod indentation;
+pub mod kotlin_funcs;
 pub mod loc;
+pub mod locals;
+pub mod macro_calls;
 pub mod mi;
 pub mod nargs;
 pub mod nom;
 pub mod npa;
 pub mod npm;
+pub mod ppc;
+pub mod unsafety;
 pub mod wmc;
@@ -171,6 +171,11 @@ impl Stats {
     pub fn total(&self) -> f64 {
         self.functions_sum() + self.closures_sum()
     }
+    #[inline(always)]
+    pub(crate) fn add_closure(&mut self) {
+        self.closures += 1;
+    }
+
     #[inline(always)]
     pub(crate) fn compute_sum(&mut self) {
         self.functions_sum += self.functions;
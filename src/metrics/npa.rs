@@ -6,6 +6,10 @@ use crate::checker::Checker;
 use crate::langs::*;
 use crate::macros::implement_metric_trait;
 use crate::node::Node;
+use crate::npm::{
+    is_python_exported, is_python_public_name, is_python_special_name, is_ts_interface_body,
+    is_ts_public_member, python_definition,
+};
 use crate::*;
 
 /// The `Npa` metric.
@@ -200,11 +204,216 @@ pub trait Npa
 where
     Self: Checker,
 {
-    fn compute(node: &Node, stats: &mut Stats);
+    fn compute(node: &Node, code: &[u8], stats: &mut Stats);
+}
+
+macro_rules! ts_npa {
+    ($lang:ident) => {
+        fn compute(node: &Node, code: &[u8], stats: &mut Stats) {
+            use $lang::*;
+
+            // Enables the `Npa` metric if computing stats of a class space
+            if Self::is_func_space(node)
+                && matches!(
+                    node.kind_id().into(),
+                    Program
+                        | Class
+                        | ClassDeclaration
+                        | AbstractClassDeclaration
+                        | InterfaceDeclaration
+                        | TypeAliasDeclaration
+                )
+                && stats.is_disabled()
+            {
+                stats.is_class_space = true;
+            }
+
+            if node.kind_id() == ClassBody {
+                let fields = node
+                    .children()
+                    .filter(|node| node.kind_id() == PublicFieldDefinition);
+                // The constructor parameters with an accessibility
+                // or a `readonly` modifier are attributes too
+                let parameters = node
+                    .children()
+                    .filter(|node| {
+                        node.kind_id() == MethodDefinition
+                            && node
+                                .child_by_field_name("name")
+                                .and_then(|name| name.utf8_text(code))
+                                == Some("constructor")
+                    })
+                    .filter_map(|constructor| constructor.child_by_field_name("parameters"))
+                    .flat_map(|parameters| parameters.children())
+                    .filter(|parameter| {
+                        matches!(
+                            parameter.kind_id().into(),
+                            RequiredParameter | OptionalParameter
+                        ) && parameter.children().any(|child| {
+                            matches!(child.kind_id().into(), AccessibilityModifier | Readonly)
+                        })
+                    });
+                for attribute in fields.chain(parameters) {
+                    stats.class_na += 1;
+                    if is_ts_public_member(&attribute, code) {
+                        stats.class_npa += 1;
+                    }
+                }
+            } else if is_ts_interface_body(node) {
+                // All the members of an interface are public
+                stats.interface_na += node
+                    .children()
+                    .filter(|node| node.kind_id() == PropertySignature)
+                    .count();
+                stats.interface_npa = stats.interface_na;
+            }
+        }
+    };
+}
+
+impl Npa for PythonCode {
+    fn compute(node: &Node, code: &[u8], stats: &mut Stats) {
+        use Python::*;
+
+        // Enables the `Npa` metric if computing stats of a class space
+        if matches!(node.kind_id().into(), Module | ClassDefinition) && stats.is_disabled() {
+            stats.is_class_space = true;
+        }
+
+        let Some(class) = node.parent().filter(|parent| {
+            matches!(node.kind_id().into(), Block | Block2) && parent.kind_id() == ClassDefinition
+        }) else {
+            return;
+        };
+
+        // The attributes of a class are the names assigned in its body
+        // and the attributes of `self` assigned in its methods
+        let mut attributes = Vec::new();
+        for assignment in node
+            .children()
+            .filter(|statement| statement.kind_id() == ExpressionStatement)
+            .filter_map(|statement| statement.child(0))
+            .filter(|assignment| assignment.kind_id() == Assignment)
+        {
+            if let Some(name) = assignment
+                .child_by_field_name("left")
+                .filter(|left| left.kind_id() == Identifier)
+                .and_then(|left| left.utf8_text(code))
+            {
+                attributes.push(name);
+            }
+        }
+        for method in node.children().filter(|node| {
+            matches!(
+                node.kind_id().into(),
+                FunctionDefinition | DecoratedDefinition
+            )
+        }) {
+            if let Some(receiver) = python_receiver(&method, code) {
+                python_instance_attributes(
+                    &python_definition(&method),
+                    receiver,
+                    code,
+                    &mut attributes,
+                );
+            }
+        }
+
+        let exported = is_python_exported(&class, code);
+        let mut names = Vec::new();
+        for name in attributes {
+            // The special attributes, such as `__slots__`, configure the class
+            if !is_python_special_name(name) && !names.contains(&name) {
+                names.push(name);
+            }
+        }
+        stats.class_na += names.len();
+        if exported {
+            stats.class_npa += names
+                .iter()
+                .filter(|name| is_python_public_name(name))
+                .count();
+        }
+    }
+}
+
+// Returns the name of the first parameter of a Python method,
+// `self` by convention, unless the method is static
+fn python_receiver<'a>(method: &Node<'a>, code: &'a [u8]) -> Option<&'a str> {
+    let is_static = method.kind_id() == Python::DecoratedDefinition
+        && method
+            .children()
+            .filter(|child| child.kind_id() == Python::Decorator)
+            .filter_map(|decorator| decorator.utf8_text(code))
+            .any(|decorator| decorator.trim_start_matches('@').trim() == "staticmethod");
+    let definition = python_definition(method);
+    if is_static || definition.kind_id() != Python::FunctionDefinition {
+        return None;
+    }
+    let parameter = definition
+        .child_by_field_name("parameters")?
+        .children()
+        .find(|parameter| {
+            matches!(
+                parameter.kind_id().into(),
+                Python::Identifier
+                    | Python::TypedParameter
+                    | Python::DefaultParameter
+                    | Python::TypedDefaultParameter
+            )
+        })?;
+    if parameter.kind_id() == Python::Identifier {
+        parameter.utf8_text(code)
+    } else {
+        parameter
+            .child_by_field_name("name")
+            .or_else(|| parameter.child(0))
+            .and_then(|name| name.utf8_text(code))
+    }
+}
+
+// Collects the attributes of the receiver of a Python method assigned
+// in its body, such as `self.a = 1`, without looking into the nested classes
+fn python_instance_attributes<'a>(
+    method: &Node<'a>,
+    receiver: &str,
+    code: &'a [u8],
+    attributes: &mut Vec<&'a str>,
+) {
+    let Some(body) = method.child_by_field_name("body") else {
+        return;
+    };
+    let mut stack = vec![body];
+    while let Some(node) = stack.pop() {
+        match node.kind_id().into() {
+            Python::ClassDefinition => continue,
+            Python::Assignment => {
+                if let Some(left) = node.child_by_field_name("left") {
+                    // The targets of an assignment can be a tuple,
+                    // such as `self.a, self.b = a, b`
+                    let targets = std::iter::once(left).chain(left.children());
+                    for target in targets.filter(|target| target.kind_id() == Python::Attribute) {
+                        let object = target
+                            .child_by_field_name("object")
+                            .and_then(|object| object.utf8_text(code));
+                        if object == Some(receiver)
+                            && let Some(name) = target
+                                .child_by_field_name("attribute")
+                                .and_then(|name| name.utf8_text(code))
+                        {
+                            attributes.push(name);
+                        }
+                    }
+                }
+            }
+            _ => {}
+        }
+        stack.extend(node.children());
+    }
 }
 
 impl Npa for JavaCode {
-    fn compute(node: &Node, stats: &mut Stats) {
+    fn compute(node: &Node, _code: &[u8], stats: &mut Stats) {
         use Java::*;
 
         // Enables the `Npa` metric if computing stats of a class space
@@ -255,13 +464,18 @@ impl Npa for JavaCode {
     }
 }
 
+impl Npa for TypescriptCode {
+    ts_npa!(Typescript);
+}
+
+impl Npa for TsxCode {
+    ts_npa!(Tsx);
+}
+
 implement_metric_trait!(
     Npa,
-    PythonCode,
     MozjsCode,
     JavascriptCode,
-    TypescriptCode,
-    TsxCode,
     RustCode,
     CppCode,
     PreprocCode,
@@ -733,4 +947,101 @@ mod tests {
             },
         );
     }
+
+    #[test]
+    fn python_attributes() {
+        check_metrics::<PythonParser>(
+            "class X:
+                kind = 'x'                  # +1
+                _cache = None
+                __slots__ = ('a', '_b')
+
+                def __init__(self, a, b):
+                    self.a = a              # +1
+                    self._b = b
+                    self.a, self.c = a, b   # +1
+
+                def _reset(self):
+                    self.d = None           # +1
+
+                @staticmethod
+                def make(other):
+                    other.e = 1",
+            "foo.py",
+            |metric| {
+                insta::assert_json_snapshot!(
+                    metric.npa,
+                    @r###"
+                    {
+                      "classes": 4.0,
+                      "interfaces": 0.0,
+                      "class_attributes": 6.0,
+                      "interface_attributes": 0.0,
+                      "classes_average": 0.6666666666666666,
+                      "interfaces_average": null,
+                      "total": 4.0,
+                      "total_attributes": 6.0,
+                      "average": 0.6666666666666666
+                    }"###
+                );
+            },
+        );
+    }
+
+    #[test]
+    fn python_all() {
+        check_metrics::<PythonParser>(
+            "__all__ = ('Exported',)
+
+            class Exported:
+                a = 1                   # +1
+
+            class Hidden:
+                def __init__(self):
+                    self.a = 1",
+            "foo.py",
+            |metric| {
+                assert_eq!(metric.npa.class_npa_sum(), 1.);
+                assert_eq!(metric.npa.class_na_sum(), 2.);
+            },
+        );
+    }
+
+    #[test]
+    fn typescript_attributes() {
+        check_metrics::<TypescriptParser>(
+            "interface Shape {
+                name: string;               // +1
+                readonly sides?: number;    // +1
+                area(): number;
+            }
+            type Point = { x: number; y: number }; // +2
+            class Circle {
+                kind = 'circle';            // +1
+                static count: number;       // +1
+                private r: number;
+                protected c = 1;
+                #secret = 2;
+                constructor(public radius: number, readonly unit: string, private z: number, other: number) {} // +2
+            }",
+            "foo.ts",
+            |metric| {
+                insta::assert_json_snapshot!(
+                    metric.npa,
+                    @r###"
+                    {
+                      "classes": 4.0,
+                      "interfaces": 4.0,
+                      "class_attributes": 8.0,
+                      "interface_attributes": 4.0,
+                      "classes_average": 0.5,
+                      "interfaces_average": 1.0,
+                      "total": 8.0,
+                      "total_attributes": 12.0,
+                      "average": 0.6666666666666666
+                    }"###
+                );
+            },
+        );
+    }
 }
@@ -200,11 +200,188 @@ pub trait Npm
 where
     Self: Checker,
 {
-    fn compute(node: &Node, stats: &mut Stats);
+    fn compute(node: &Node, code: &[u8], stats: &mut Stats);
+}
+
+// Checks if a Python name is public by convention: the names starting
+// with an underscore are internal, except the special ones such as `__init__`
+pub(crate) fn is_python_public_name(name: &str) -> bool {
+    !name.starts_with('_') || is_python_special_name(name)
+}
+
+// Checks if a Python name is a special one, such as `__init__` or `__slots__`
+pub(crate) fn is_python_special_name(name: &str) -> bool {
+    name.len() > 4 && name.starts_with("__") && name.ends_with("__")
+}
+
+// Returns the definition of a Python decorated definition
+pub(crate) fn python_definition<'a>(node: &Node<'a>) -> Node<'a> {
+    if node.kind_id() == Python::DecoratedDefinition {
+        node.child_by_field_name("definition").unwrap_or(*node)
+    } else {
+        *node
+    }
+}
+
+// Checks if the members of a Python class are exported by its module.
+//
+// When a module defines `__all__`, only the names listed in it are public,
+// so the classes defined in a module statement which is not listed,
+// and the classes nested in them, are not public.
+pub(crate) fn is_python_exported(class: &Node, code: &[u8]) -> bool {
+    let mut statement = *class;
+    let module = loop {
+        match statement.parent() {
+            Some(parent) if parent.kind_id() == Python::Module => break parent,
+            Some(parent) => statement = parent,
+            None => return true,
+        }
+    };
+    let Some(name) = python_definition(&statement)
+        .child_by_field_name("name")
+        .and_then(|name| name.utf8_text(code))
+    else {
+        return true;
+    };
+
+    let mut all = module
+        .children()
+        .filter(|statement| statement.kind_id() == Python::ExpressionStatement)
+        .filter_map(|statement| statement.child(0))
+        .filter(|assignment| {
+            matches!(
+                assignment.kind_id().into(),
+                Python::Assignment | Python::AugmentedAssignment
+            ) && assignment
+                .child_by_field_name("left")
+                .and_then(|left| left.utf8_text(code))
+                == Some("__all__")
+        })
+        .filter_map(|assignment| assignment.child_by_field_name("right"))
+        .peekable();
+    if all.peek().is_none() {
+        return true;
+    }
+    all.flat_map(|names| names.children())
+        .filter(|name| name.kind_id() == Python::String)
+        .filter_map(|string| string.utf8_text(code))
+        .any(|string| string.trim_matches(|c| c == '"' || c == '\'') == name)
+}
+
+// Returns the methods of the body of a Python class
+fn python_methods<'a>(body: &Node<'a>) -> impl Iterator<Item = Node<'a>> + use<'a> {
+    body.children()
+        .filter(|node| {
+            matches!(
+                node.kind_id().into(),
+                Python::FunctionDefinition | Python::DecoratedDefinition
+            )
+        })
+        .filter(|node| python_definition(node).kind_id() == Python::FunctionDefinition)
+}
+
+// Checks if a member of a TypeScript class is public: its name does not
+// start with `#` and it has no `private` or `protected` modifier
+pub(crate) fn is_ts_public_member(member: &Node, code: &[u8]) -> bool {
+    let is_private_name = member
+        .child_by_field_name("name")
+        .and_then(|name| name.utf8_text(code))
+        .is_some_and(|name| name.starts_with('#'));
+    !is_private_name
+        && member
+            .children()
+            .filter(|child| child.kind() == "accessibility_modifier")
+            .all(|modifier| modifier.utf8_text(code) == Some("public"))
+}
+
+// Checks if a TypeScript node is the body of an interface
+// or the object type of a type alias, whose members are all public
+pub(crate) fn is_ts_interface_body(node: &Node) -> bool {
+    match node.kind() {
+        "interface_body" => true,
+        "object_type" => node
+            .parent()
+            .is_some_and(|parent| parent.kind() == "type_alias_declaration"),
+        _ => false,
+    }
+}
+
+macro_rules! ts_npm {
+    ($lang:ident) => {
+        fn compute(node: &Node, code: &[u8], stats: &mut Stats) {
+            use $lang::*;
+
+            // Enables the `Npm` metric if computing stats of a class space
+            if Self::is_func_space(node)
+                && matches!(
+                    node.kind_id().into(),
+                    Program
+                        | Class
+                        | ClassDeclaration
+                        | AbstractClassDeclaration
+                        | InterfaceDeclaration
+                        | TypeAliasDeclaration
+                )
+                && stats.is_disabled()
+            {
+                stats.is_class_space = true;
+            }
+
+            if node.kind_id() == ClassBody {
+                for method in node.children().filter(|node| {
+                    matches!(
+                        node.kind_id().into(),
+                        MethodDefinition | AbstractMethodSignature
+                    )
+                }) {
+                    stats.class_nm += 1;
+                    if is_ts_public_member(&method, code) {
+                        stats.class_npm += 1;
+                    }
+                }
+            } else if is_ts_interface_body(node) {
+                // All the members of an interface are public
+                stats.interface_nm += node
+                    .children()
+                    .filter(|node| node.kind_id() == MethodSignature)
+                    .count();
+                stats.interface_npm = stats.interface_nm;
+            }
+        }
+    };
+}
+
+impl Npm for PythonCode {
+    fn compute(node: &Node, code: &[u8], stats: &mut Stats) {
+        use Python::*;
+
+        // Enables the `Npm` metric if computing stats of a class space
+        if matches!(node.kind_id().into(), Module | ClassDefinition) && stats.is_disabled() {
+            stats.is_class_space = true;
+        }
+
+        // Python has no access modifiers, so the methods whose names
+        // start with an underscore are considered as not public
+        if let Some(class) = node.parent().filter(|parent| {
+            matches!(node.kind_id().into(), Block | Block2) && parent.kind_id() == ClassDefinition
+        }) {
+            let exported = is_python_exported(&class, code);
+            for method in python_methods(node) {
+                stats.class_nm += 1;
+                let name = python_definition(&method)
+                    .child_by_field_name("name")
+                    .and_then(|name| name.utf8_text(code))
+                    .unwrap_or_default();
+                if exported && is_python_public_name(name) {
+                    stats.class_npm += 1;
+                }
+            }
+        }
+    }
 }
 
 impl Npm for JavaCode {
-    fn compute(node: &Node, stats: &mut Stats) {
+    fn compute(node: &Node, _code: &[u8], stats: &mut Stats) {
         use Java::*;
 
         // Enables the `Npm` metric if computing stats of a class space
@@ -246,13 +423,18 @@ impl Npm for JavaCode {
     }
 }
 
+impl Npm for TypescriptCode {
+    ts_npm!(Typescript);
+}
+
+impl Npm for TsxCode {
+    ts_npm!(Tsx);
+}
+
 implement_metric_trait!(
     Npm,
-    PythonCode,
     MozjsCode,
     JavascriptCode,
-    TypescriptCode,
-    TsxCode,
     RustCode,
     CppCode,
     PreprocCode,
@@ -705,4 +887,104 @@ mod tests {
             },
         );
     }
+
+    #[test]
+    fn python_methods() {
+        check_metrics::<PythonParser>(
+            "class X:
+                def __init__(self):     # +1
+                    pass
+                def run(self):          # +1
+                    pass
+                @property
+                def value(self):        # +1
+                    return 1
+                def _helper(self):
+                    pass
+                def __mangled(self):
+                    pass",
+            "foo.py",
+            |metric| {
+                insta::assert_json_snapshot!(
+                    metric.npm,
+                    @r###"
+                    {
+                      "classes": 3.0,
+                      "interfaces": 0.0,
+                      "class_methods": 5.0,
+                      "interface_methods": 0.0,
+                      "classes_average": 0.6,
+                      "interfaces_average": null,
+                      "total": 3.0,
+                      "total_methods": 5.0,
+                      "average": 0.6
+                    }"###
+                );
+            },
+        );
+    }
+
+    #[test]
+    fn python_all() {
+        check_metrics::<PythonParser>(
+            "__all__ = ['Exported']
+
+            class Exported:
+                def run(self):          # +1
+                    pass
+                class Nested:
+                    def run(self):      # +1
+                        pass
+
+            class Hidden:
+                def run(self):
+                    pass",
+            "foo.py",
+            |metric| {
+                assert_eq!(metric.npm.class_npm_sum(), 2.);
+                assert_eq!(metric.npm.class_nm_sum(), 3.);
+            },
+        );
+    }
+
+    #[test]
+    fn typescript_methods() {
+        check_metrics::<TypescriptParser>(
+            "interface Shape {
+                name: string;
+                area(): number;             // +1
+                perimeter(): number;        // +1
+            }
+            type Point = {
+                move(dx: number): void;     // +1
+            };
+            abstract class Base {
+                constructor() {}            // +1
+                area(): number { return 0; } // +1
+                public get name() { return ''; } // +1
+                abstract perimeter(): number; // +1
+                private helper() {}
+                protected check() {}
+                #hidden() {}
+            }",
+            "foo.ts",
+            |metric| {
+                insta::assert_json_snapshot!(
+                    metric.npm,
+                    @r###"
+                    {
+                      "classes": 4.0,
+                      "interfaces": 3.0,
+                      "class_methods": 7.0,
+                      "interface_methods": 3.0,
+                      "classes_average": 0.5714285714285714,
+                      "interfaces_average": 1.0,
+                      "total": 7.0,
+                      "total_methods": 10.0,
+                      "average": 0.7
+                    }"###
+                );
+            },
+        );
+    }
 }
@@ -0,0 +1,282 @@
+use serde::Serialize;
+use serde::ser::{SerializeStruct, Serializer};
+use std::fmt;
+
+use crate::checker::Checker;
+use crate::langs::*;
+use crate::macros::implement_metric_trait;
+use crate::node::Node;
+use crate::*;
+
+/// The `Ppc` metric.
+///
+/// This metric counts the preprocessor conditionals of a space,
+/// that is the `#if`, `#ifdef` and `#ifndef` directives, their branches
+/// and their maximum nesting level. Each branch of a conditional
+/// selects a different code to compile, which is a complexity
+/// not taken into account by the `Cyclomatic` metric.
+#[derive(Clone, Debug, Default)]
+pub struct Stats {
+    conditionals: usize,
+    branches: usize,
+    nesting: usize,
+    conditionals_sum: usize,
+    branches_sum: usize,
+    nesting_max: usize,
+    is_enabled: bool,
+}
+
+impl Serialize for Stats {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        let mut st = serializer.serialize_struct("ppc", 3)?;
+        st.serialize_field("conditionals", &self.conditionals_sum())?;
+        st.serialize_field("branches", &self.branches_sum())?;
+        st.serialize_field("nesting_max", &self.nesting_max())?;
+        st.end()
+    }
+}
+
+impl fmt::Display for Stats {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "conditionals: {}, branches: {}, nesting_max: {}",
+            self.conditionals_sum(),
+            self.branches_sum(),
+            self.nesting_max()
+        )
+    }
+}
+
+impl Stats {
+    /// Merges a second `Ppc` metric into the first one
+    pub fn merge(&mut self, other: &Stats) {
+        self.conditionals_sum += other.conditionals_sum;
+        self.branches_sum += other.branches_sum;
+        self.nesting_max = self.nesting_max.max(other.nesting_max);
+    }
+
+    /// Returns the number of preprocessor conditionals in a space.
+    #[inline(always)]
+    pub fn conditionals(&self) -> f64 {
+        self.conditionals as f64
+    }
+
+    /// Returns the number of branches of the preprocessor
+    /// conditionals in a space.
+    ///
+    /// Each `#if`, `#ifdef`, `#ifndef`, `#elif`, `#elifdef`
+    /// and `#else` directive is a branch.
+    #[inline(always)]
+    pub fn branches(&self) -> f64 {
+        self.branches as f64
+    }
+
+    /// Returns the maximum nesting level of the preprocessor
+    /// conditionals in a space.
+    ///
+    /// A conditional which is not contained in another one
+    /// has a nesting level of 1.
+    #[inline(always)]
+    pub fn nesting(&self) -> f64 {
+        self.nesting as f64
+    }
+
+    /// Returns the number of preprocessor conditionals sum in a space.
+    #[inline(always)]
+    pub fn conditionals_sum(&self) -> f64 {
+        self.conditionals_sum as f64
+    }
+
+    /// Returns the number of branches sum in a space.
+    #[inline(always)]
+    pub fn branches_sum(&self) -> f64 {
+        self.branches_sum as f64
+    }
+
+    /// Returns the maximum nesting level of the preprocessor
+    /// conditionals in a space and in its subspaces.
+    #[inline(always)]
+    pub fn nesting_max(&self) -> f64 {
+        self.nesting_max as f64
+    }
+
+    #[inline(always)]
+    pub(crate) fn compute_sum(&mut self) {
+        self.conditionals_sum += self.conditionals;
+        self.branches_sum += self.branches;
+        self.nesting_max = self.nesting_max.max(self.nesting);
+    }
+
+    // Checks if the `Ppc` metric is disabled
+    #[inline(always)]
+    pub(crate) fn is_disabled(&self) -> bool {
+        !self.is_enabled
+    }
+}
+
+pub trait Ppc
+where
+    Self: Checker,
+{
+    fn compute(node: &Node, stats: &mut Stats);
+}
+
+impl Ppc for CppCode {
+    fn compute(node: &Node, stats: &mut Stats) {
+        use Cpp::*;
+
+        // Enables the `Ppc` metric for all the spaces of a language
+        // supporting a preprocessor
+        stats.is_enabled = true;
+
+        let is_conditional = |node: &Node| {
+            matches!(
+                node.kind_id().into(),
+                PreprocIf
+                    | PreprocIf2
+                    | PreprocIf3
+                    | PreprocIf4
+                    | PreprocIfdef
+                    | PreprocIfdef2
+                    | PreprocIfdef3
+                    | PreprocIfdef4
+            )
+        };
+
+        if is_conditional(node) {
+            stats.conditionals += 1;
+            stats.branches += 1;
+
+            let mut nesting = 1;
+            let mut ancestor = node.parent();
+            while let Some(parent) = ancestor {
+                if is_conditional(&parent) {
+                    nesting += 1;
+                }
+                ancestor = parent.parent();
+            }
+            stats.nesting = stats.nesting.max(nesting);
+        } else if matches!(
+            node.kind_id().into(),
+            PreprocElif
+                | PreprocElif2
+                | PreprocElif3
+                | PreprocElif4
+                | PreprocElifdef
+                | PreprocElifdef2
+                | PreprocElifdef3
+                | PreprocElifdef4
+                | PreprocElse
+                | PreprocElse2
+                | PreprocElse3
+                | PreprocElse4
+        ) {
+            stats.branches += 1;
+        }
+    }
+}
+
+implement_metric_trait!(
+    Ppc,
+    PythonCode,
+    MozjsCode,
+    JavascriptCode,
+    TypescriptCode,
+    TsxCode,
+    RustCode,
+    JavaCode,
+    PreprocCode,
+    CcommentCode,
+    KotlinCode
+);
+
+#[cfg(test)]
+mod tests {
+    use crate::tools::{check_func_space, check_metrics};
+
+    use super::*;
+
+    #[test]
+    fn cpp_no_ppc() {
+        check_metrics::<CppParser>("int a = 42;", "foo.c", |metric| {
+            insta::assert_json_snapshot!(
+                metric.ppc,
+                @r###"
+                {
+                  "conditionals": 0.0,
+                  "branches": 0.0,
+                  "nesting_max": 0.0
+                }"###
+            );
+        });
+    }
+
+    #[test]
+    fn cpp_ppc() {
+        check_metrics::<CppParser>(
+            "#ifdef _WIN32
+            #define SEP '\\\\'
+            #elif defined(__APPLE__)
+            #define SEP ':'
+            #else
+            #define SEP '/'
+            #endif
+
+            int f(int a) {
+            #if DEBUG
+            #ifndef NDEBUG
+                a++;
+            #endif
+            #else
+                a--;
+            #endif
+                return a;
+            }",
+            "foo.c",
+            |metric| {
+                // Spaces: 2
+                insta::assert_json_snapshot!(
+                    metric.ppc,
+                    @r###"
+                    {
+                      "conditionals": 3.0,
+                      "branches": 6.0,
+                      "nesting_max": 2.0
+                    }"###
+                );
+            },
+        );
+    }
+
+    #[test]
+    fn cpp_ppc_function() {
+        check_func_space::<CppParser, _>(
+            "#if A
+            int f() {
+            #if B
+                return 1;
+            #endif
+                return 0;
+            }
+            #endif",
+            "foo.c",
+            |func_space| {
+                let function = &func_space.spaces[0].metrics.ppc;
+                assert_eq!(function.conditionals_sum(), 1.);
+                assert_eq!(function.nesting_max(), 2.);
+                assert_eq!(func_space.metrics.ppc.conditionals_sum(), 2.);
+            },
+        );
+    }
+
+    #[test]
+    fn python_no_ppc() {
+        check_metrics::<PythonParser>("a = 42", "foo.py", |metric| {
+            assert!(metric.ppc.is_disabled());
+        });
+    }
+}
@@ -0,0 +1,244 @@
+use serde::Serialize;
+use serde::ser::{SerializeStruct, Serializer};
+use std::collections::HashSet;
+use std::fmt;
+
+use crate::checker::Checker;
+use crate::langs::*;
+use crate::macros::implement_metric_trait;
+use crate::node::Node;
+use crate::*;
+
+/// The `Unsafety` metric.
+///
+/// This metric counts the `unsafe` blocks and the `unsafe` functions
+/// of a space, and the lines of code contained in them.
+#[derive(Clone, Debug, Default)]
+pub struct Stats {
+    blocks: usize,
+    functions: usize,
+    blocks_sum: usize,
+    functions_sum: usize,
+    lines: HashSet<usize>,
+    sloc: f64,
+    is_enabled: bool,
+}
+
+impl Serialize for Stats {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        let mut st = serializer.serialize_struct("unsafety", 4)?;
+        st.serialize_field("blocks", &self.blocks_sum())?;
+        st.serialize_field("functions", &self.functions_sum())?;
+        st.serialize_field("lines", &self.lines())?;
+        st.serialize_field("ratio", &self.ratio())?;
+        st.end()
+    }
+}
+
+impl fmt::Display for Stats {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "blocks: {}, functions: {}, lines: {}, ratio: {}",
+            self.blocks_sum(),
+            self.functions_sum(),
+            self.lines(),
+            self.ratio()
+        )
+    }
+}
+
+impl Stats {
+    /// Merges a second `Unsafety` metric into the first one
+    pub fn merge(&mut self, other: &Stats) {
+        self.blocks_sum += other.blocks_sum;
+        self.functions_sum += other.functions_sum;
+        self.lines.extend(other.lines.iter());
+    }
+
+    /// Returns the number of `unsafe` blocks in a space.
+    #[inline(always)]
+    pub fn blocks(&self) -> f64 {
+        self.blocks as f64
+    }
+
+    /// Returns the number of `unsafe` functions in a space.
+    #[inline(always)]
+    pub fn functions(&self) -> f64 {
+        self.functions as f64
+    }
+
+    /// Returns the number of `unsafe` blocks sum in a space.
+    #[inline(always)]
+    pub fn blocks_sum(&self) -> f64 {
+        self.blocks_sum as f64
+    }
+
+    /// Returns the number of `unsafe` functions sum in a space.
+    #[inline(always)]
+    pub fn functions_sum(&self) -> f64 {
+        self.functions_sum as f64
+    }
+
+    /// Returns the number of lines contained in an `unsafe` block
+    /// or in an `unsafe` function, in a space and in its subspaces.
+    #[inline(always)]
+    pub fn lines(&self) -> f64 {
+        self.lines.len() as f64
+    }
+
+    /// Returns the share of the `Sloc` of a space contained in
+    /// an `unsafe` block or in an `unsafe` function.
+    ///
+    /// Its value ranges from 0 to 1.
+    #[inline(always)]
+    pub fn ratio(&self) -> f64 {
+        if self.sloc == 0. {
+            0.
+        } else {
+            self.lines() / self.sloc
+        }
+    }
+
+    #[inline(always)]
+    pub(crate) fn compute_sum(&mut self) {
+        self.blocks_sum += self.blocks;
+        self.functions_sum += self.functions;
+    }
+
+    pub(crate) fn finalize(&mut self, sloc: f64) {
+        self.sloc = sloc;
+    }
+
+    // Checks if the `Unsafety` metric is disabled
+    #[inline(always)]
+    pub(crate) fn is_disabled(&self) -> bool {
+        !self.is_enabled
+    }
+}
+
+pub trait Unsafety
+where
+    Self: Checker,
+{
+    fn compute(node: &Node, stats: &mut Stats);
+}
+
+impl Unsafety for RustCode {
+    fn compute(node: &Node, stats: &mut Stats) {
+        use Rust::*;
+
+        // Enables the `Unsafety` metric for all the spaces
+        // of a language supporting unsafe code
+        stats.is_enabled = true;
+
+        match node.kind_id().into() {
+            UnsafeBlock => {
+                stats.blocks += 1;
+            }
+            FunctionItem
+                if node.children().any(|child| {
+                    child.kind_id() == FunctionModifiers && child.is_child(Unsafe as u16)
+                }) =>
+            {
+                stats.functions += 1;
+            }
+            _ => return,
+        }
+        stats.lines.extend(node.start_row()..=node.end_row());
+    }
+}
+
+implement_metric_trait!(
+    Unsafety,
+    PythonCode,
+    MozjsCode,
+    JavascriptCode,
+    TypescriptCode,
+    TsxCode,
+    CppCode,
+    JavaCode,
+    PreprocCode,
+    CcommentCode,
+    KotlinCode
+);
+
+#[cfg(test)]
+mod tests {
+    use crate::tools::{check_func_space, check_metrics};
+
+    use super::*;
+
+    #[test]
+    fn rust_no_unsafety() {
+        check_metrics::<RustParser>("fn f() -> i32 { 42 }", "foo.rs", |metric| {
+            insta::assert_json_snapshot!(
+                metric.unsafety,
+                @r###"
+                {
+                  "blocks": 0.0,
+                  "functions": 0.0,
+                  "lines": 0.0,
+                  "ratio": 0.0
+                }"###
+            );
+        });
+    }
+
+    #[test]
+    fn rust_unsafety() {
+        check_metrics::<RustParser>(
+            "unsafe fn f() {}
+            fn g() {
+                unsafe { f() }
+                let c = || unsafe {
+                    f()
+                };
+            }
+            fn h() {}",
+            "foo.rs",
+            |metric| {
+                // Spaces: 5
+                insta::assert_json_snapshot!(
+                    metric.unsafety,
+                    @r###"
+                    {
+                      "blocks": 2.0,
+                      "functions": 1.0,
+                      "lines": 5.0,
+                      "ratio": 0.625
+                    }"###
+                );
+            },
+        );
+    }
+
+    #[test]
+    fn rust_unsafety_function() {
+        check_func_space::<RustParser, _>(
+            "fn g() {
+                let a = 1;
+                unsafe {
+                    f(a)
+                }
+            }",
+            "foo.rs",
+            |func_space| {
+                let function = &func_space.spaces[0].metrics.unsafety;
+                assert_eq!(function.blocks_sum(), 1.);
+                assert_eq!(function.lines(), 3.);
+                assert_eq!(function.ratio(), 0.5);
+            },
+        );
+    }
+
+    #[test]
+    fn python_no_unsafety() {
+        check_metrics::<PythonParser>("a = 42", "foo.py", |metric| {
+            assert!(metric.unsafety.is_disabled());
+        });
+    }
+}
@@ -16,15 +16,23 @@ use crate::*;
 /// This metric sums the cyclomatic complexities of all the methods defined in a class.
 /// The `Wmc` (Weighted Methods per Class) is an object-oriented metric for classes.
 ///
+/// The methods are weighted by their cognitive complexity too, which measures
+/// how hard they are to understand rather than the paths to test them.
+///
 /// Original paper and definition:
 /// <https://www.researchgate.net/publication/3187649_Kemerer_CF_A_metric_suite_for_object_oriented_design_IEEE_Trans_Softw_Eng_206_476-493>
 #[derive(Debug, Clone, Default)]
 pub struct Stats {
     cyclomatic: f64,
+    cognitive: f64,
     class_wmc: f64,
     interface_wmc: f64,
     class_wmc_sum: f64,
     interface_wmc_sum: f64,
+    class_cognitive_wmc: f64,
+    interface_cognitive_wmc: f64,
+    class_cognitive_wmc_sum: f64,
+    interface_cognitive_wmc_sum: f64,
     space_kind: SpaceKind,
 }
 
@@ -33,10 +41,13 @@ impl Serialize for Stats {
     where
         S: Serializer,
     {
-        let mut st = serializer.serialize_struct("wmc", 3)?;
+        let mut st = serializer.serialize_struct("wmc", 6)?;
         st.serialize_field("classes", &self.class_wmc_sum())?;
         st.serialize_field("interfaces", &self.interface_wmc_sum())?;
         st.serialize_field("total", &self.total_wmc())?;
+        st.serialize_field("classes_cognitive", &self.class_cognitive_wmc_sum())?;
+        st.serialize_field("interfaces_cognitive", &self.interface_cognitive_wmc_sum())?;
+        st.serialize_field("total_cognitive", &self.total_cognitive_wmc())?;
         st.end()
     }
 }
@@ -45,10 +56,14 @@ impl fmt::Display for Stats {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
         write!(
             f,
-            "classes: {}, interfaces: {}, total: {}",
+            "classes: {}, interfaces: {}, total: {}, \
+             classes_cognitive: {}, interfaces_cognitive: {}, total_cognitive: {}",
             self.class_wmc_sum(),
             self.interface_wmc_sum(),
-            self.total_wmc()
+            self.total_wmc(),
+            self.class_cognitive_wmc_sum(),
+            self.interface_cognitive_wmc_sum(),
+            self.total_cognitive_wmc()
         )
     }
 }
@@ -58,18 +73,26 @@ impl Stats {
     pub fn merge(&mut self, other: &Stats) {
         use SpaceKind::*;
 
-        // Merges the cyclomatic complexity of a method
-        // into the `Wmc` metric value of a class or interface
+        // Merges the cyclomatic and the cognitive complexities of a method
+        // into the `Wmc` metric values of a class or interface
         if let Function = other.space_kind {
             match self.space_kind {
-                Class => self.class_wmc += other.cyclomatic,
-                Interface => self.interface_wmc += other.cyclomatic,
+                Class => {
+                    self.class_wmc += other.cyclomatic;
+                    self.class_cognitive_wmc += other.cognitive;
+                }
+                Interface => {
+                    self.interface_wmc += other.cyclomatic;
+                    self.interface_cognitive_wmc += other.cognitive;
+                }
                 _ => {}
             }
         }
 
         self.class_wmc_sum += other.class_wmc_sum;
         self.interface_wmc_sum += other.interface_wmc_sum;
+        self.class_cognitive_wmc_sum += other.class_cognitive_wmc_sum;
+        self.interface_cognitive_wmc_sum += other.interface_cognitive_wmc_sum;
     }
 
     /// Returns the `Wmc` metric value of the classes in a space.
@@ -102,12 +125,48 @@ impl Stats {
         self.class_wmc_sum() + self.interface_wmc_sum()
     }
 
+    /// Returns the `Wmc` metric value of the classes in a space,
+    /// weighting the methods by their cognitive complexity.
+    #[inline(always)]
+    pub fn class_cognitive_wmc(&self) -> f64 {
+        self.class_cognitive_wmc
+    }
+
+    /// Returns the `Wmc` metric value of the interfaces in a space,
+    /// weighting the methods by their cognitive complexity.
+    #[inline(always)]
+    pub fn interface_cognitive_wmc(&self) -> f64 {
+        self.interface_cognitive_wmc
+    }
+
+    /// Returns the sum of the cognitive `Wmc` metric values
+    /// of the classes in a space.
+    #[inline(always)]
+    pub fn class_cognitive_wmc_sum(&self) -> f64 {
+        self.class_cognitive_wmc_sum
+    }
+
+    /// Returns the sum of the cognitive `Wmc` metric values
+    /// of the interfaces in a space.
+    #[inline(always)]
+    pub fn interface_cognitive_wmc_sum(&self) -> f64 {
+        self.interface_cognitive_wmc_sum
+    }
+
+    /// Returns the total cognitive `Wmc` metric value in a space.
+    #[inline(always)]
+    pub fn total_cognitive_wmc(&self) -> f64 {
+        self.class_cognitive_wmc_sum() + self.interface_cognitive_wmc_sum()
+    }
+
     // Accumulates the `Wmc` metric values
     // of classes and interfaces into the sums
     #[inline(always)]
     pub(crate) fn compute_sum(&mut self) {
         self.class_wmc_sum += self.class_wmc;
         self.interface_wmc_sum += self.interface_wmc;
+        self.class_cognitive_wmc_sum += self.class_cognitive_wmc;
+        self.interface_cognitive_wmc_sum += self.interface_cognitive_wmc;
     }
 
     // Checks if the `Wmc` metric is disabled
@@ -121,22 +180,66 @@ pub trait Wmc
 where
     Self: Checker,
 {
-    fn compute(space_kind: SpaceKind, cyclomatic: &cyclomatic::Stats, stats: &mut Stats);
+    fn compute(
+        space_kind: SpaceKind,
+        cyclomatic: &cyclomatic::Stats,
+        cognitive: &cognitive::Stats,
+        stats: &mut Stats,
+    );
+}
+
+// Computes the `Wmc` metric of the languages whose classes and interfaces
+// are spaces, so the methods are the function spaces they contain
+fn compute_class_wmc(
+    space_kind: SpaceKind,
+    cyclomatic: &cyclomatic::Stats,
+    cognitive: &cognitive::Stats,
+    stats: &mut Stats,
+) {
+    use SpaceKind::*;
+
+    if let Unit | Class | Interface | Function = space_kind {
+        if stats.space_kind == Unknown {
+            stats.space_kind = space_kind;
+        }
+        if space_kind == Function {
+            // Saves the cyclomatic and the cognitive complexities of the method
+            stats.cyclomatic = cyclomatic.cyclomatic_sum();
+            stats.cognitive = cognitive.cognitive_sum();
+        }
+    }
 }
 
 impl Wmc for JavaCode {
-    fn compute(space_kind: SpaceKind, cyclomatic: &cyclomatic::Stats, stats: &mut Stats) {
-        use SpaceKind::*;
+    fn compute(
+        space_kind: SpaceKind,
+        cyclomatic: &cyclomatic::Stats,
+        cognitive: &cognitive::Stats,
+        stats: &mut Stats,
+    ) {
+        compute_class_wmc(space_kind, cyclomatic, cognitive, stats);
+    }
+}
 
-        if let Unit | Class | Interface | Function = space_kind {
-            if stats.space_kind == Unknown {
-                stats.space_kind = space_kind;
-            }
-            if space_kind == Function {
-                // Saves the cyclomatic complexity of the method
-                stats.cyclomatic = cyclomatic.cyclomatic_sum();
-            }
-        }
+impl Wmc for TypescriptCode {
+    fn compute(
+        space_kind: SpaceKind,
+        cyclomatic: &cyclomatic::Stats,
+        cognitive: &cognitive::Stats,
+        stats: &mut Stats,
+    ) {
+        compute_class_wmc(space_kind, cyclomatic, cognitive, stats);
+    }
+}
+
+impl Wmc for TsxCode {
+    fn compute(
+        space_kind: SpaceKind,
+        cyclomatic: &cyclomatic::Stats,
+        cognitive: &cognitive::Stats,
+        stats: &mut Stats,
+    ) {
+        compute_class_wmc(space_kind, cyclomatic, cognitive, stats);
     }
 }
 
@@ -145,8 +248,6 @@ implement_metric_trait!(
     PythonCode,
     MozjsCode,
     JavascriptCode,
-    TypescriptCode,
-    TsxCode,
     RustCode,
     CppCode,
     PreprocCode,
@@ -212,7 +313,10 @@ mod tests {
                     {
                       "classes": 13.0,
                       "interfaces": 0.0,
-                      "total": 13.0
+                      "total": 13.0,
+                      "classes_cognitive": 8.0,
+                      "interfaces_cognitive": 0.0,
+                      "total_cognitive": 8.0
                     }"###
                 );
             },
@@ -255,7 +359,10 @@ mod tests {
                     {
                       "classes": 5.0,
                       "interfaces": 0.0,
-                      "total": 5.0
+                      "total": 5.0,
+                      "classes_cognitive": 0.0,
+                      "interfaces_cognitive": 0.0,
+                      "total_cognitive": 0.0
                     }"###
                 );
             },
@@ -281,7 +388,10 @@ mod tests {
                     {
                       "classes": 1.0,
                       "interfaces": 0.0,
-                      "total": 1.0
+                      "total": 1.0,
+                      "classes_cognitive": 0.0,
+                      "interfaces_cognitive": 0.0,
+                      "total_cognitive": 0.0
                     }"###
                 );
             },
@@ -349,7 +459,10 @@ mod tests {
                     {
                       "classes": 9.0,
                       "interfaces": 0.0,
-                      "total": 9.0
+                      "total": 9.0,
+                      "classes_cognitive": 1.0,
+                      "interfaces_cognitive": 0.0,
+                      "total_cognitive": 1.0
                     }"###
                 );
             },
@@ -386,7 +499,10 @@ mod tests {
                     {
                       "classes": 7.0,
                       "interfaces": 0.0,
-                      "total": 7.0
+                      "total": 7.0,
+                      "classes_cognitive": 0.0,
+                      "interfaces_cognitive": 0.0,
+                      "total_cognitive": 0.0
                     }"###
                 );
             },
@@ -420,7 +536,10 @@ mod tests {
                     {
                       "classes": 4.0,
                       "interfaces": 0.0,
-                      "total": 4.0
+                      "total": 4.0,
+                      "classes_cognitive": 1.0,
+                      "interfaces_cognitive": 0.0,
+                      "total_cognitive": 1.0
                     }"###
                 );
             },
@@ -468,7 +587,10 @@ mod tests {
                     {
                       "classes": 8.0,
                       "interfaces": 0.0,
-                      "total": 8.0
+                      "total": 8.0,
+                      "classes_cognitive": 1.0,
+                      "interfaces_cognitive": 0.0,
+                      "total_cognitive": 1.0
                     }"###
                 );
             },
@@ -503,7 +625,10 @@ mod tests {
                     {
                       "classes": 2.0,
                       "interfaces": 0.0,
-                      "total": 2.0
+                      "total": 2.0,
+                      "classes_cognitive": 0.0,
+                      "interfaces_cognitive": 0.0,
+                      "total_cognitive": 0.0
                     }"###
                 );
             },
@@ -531,7 +656,10 @@ mod tests {
                     {
                       "classes": 0.0,
                       "interfaces": 6.0,
-                      "total": 6.0
+                      "total": 6.0,
+                      "classes_cognitive": 0.0,
+                      "interfaces_cognitive": 2.0,
+                      "total_cognitive": 2.0
                     }"###
                 );
             },
@@ -561,7 +689,10 @@ mod tests {
                     {
                       "classes": 0.0,
                       "interfaces": 3.0,
-                      "total": 3.0
+                      "total": 3.0,
+                      "classes_cognitive": 0.0,
+                      "interfaces_cognitive": 0.0,
+                      "total_cognitive": 0.0
                     }"###
                 );
             },
@@ -595,7 +726,10 @@ mod tests {
                     {
                       "classes": 0.0,
                       "interfaces": 5.0,
-                      "total": 5.0
+                      "total": 5.0,
+                      "classes_cognitive": 0.0,
+                      "interfaces_cognitive": 0.0,
+                      "total_cognitive": 0.0
                     }"###
                 );
             },
@@ -629,7 +763,10 @@ mod tests {
                     {
                       "classes": 2.0,
                       "interfaces": 2.0,
-                      "total": 4.0
+                      "total": 4.0,
+                      "classes_cognitive": 0.0,
+                      "interfaces_cognitive": 0.0,
+                      "total_cognitive": 0.0
                     }"###
                 );
             },
@@ -663,10 +800,39 @@ mod tests {
                     {
                       "classes": 2.0,
                       "interfaces": 2.0,
-                      "total": 4.0
+                      "total": 4.0,
+                      "classes_cognitive": 0.0,
+                      "interfaces_cognitive": 0.0,
+                      "total_cognitive": 0.0
                     }"###
                 );
             },
         );
     }
+
+    #[test]
+    fn typescript_class() {
+        check_metrics::<TypescriptParser>(
+            "class Example { // wmc = 4, cognitive wmc = 2
+                m1(a: boolean, b: boolean): boolean { // +1
+                    if (a && b) { // +2, cognitive +2
+                        return true;
+                    }
+                    return false;
+                }
+                m2 = () => { // +1
+                    return 1;
+                };
+            }
+            interface Shape { // wmc = 0
+                area(): number;
+            }",
+            "foo.ts",
+            |metric| {
+                assert_eq!(metric.wmc.class_wmc_sum(), 4.);
+                assert_eq!(metric.wmc.class_cognitive_wmc_sum(), 2.);
+                assert_eq!(metric.wmc.interface_wmc_sum(), 0.);
+            },
+        );
+    }
 }
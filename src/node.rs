@@ -37,6 +37,18 @@ impl<'a> Node<'a> {
         self.0.has_error()
     }
 
+    pub(crate) fn is_error(&self) -> bool {
+        self.0.is_error()
+    }
+
+    pub(crate) fn is_missing(&self) -> bool {
+        self.0.is_missing()
+    }
+
+    pub(crate) fn is_named(&self) -> bool {
+        self.0.is_named()
+    }
+
     pub(crate) fn id(&self) -> usize {
         self.0.id()
     }
@@ -111,7 +123,7 @@ impl<'a> Node<'a> {
         self.0.child_count()
     }
 
-    pub(crate) fn child_by_field_name(&self, name: &str) -> Option<Node<'_>> {
+    pub(crate) fn child_by_field_name(&self, name: &str) -> Option<Node<'a>> {
         self.0.child_by_field_name(name).map(Node)
     }
 
@@ -202,6 +214,14 @@ impl<'a> Cursor<'a> {
         self.0.goto_first_child()
     }
 
+    pub(crate) fn goto_parent(&mut self) -> bool {
+        self.0.goto_parent()
+    }
+
+    pub(crate) fn field_name(&self) -> Option<&'static str> {
+        self.0.field_name()
+    }
+
     pub(crate) fn node(&self) -> Node<'a> {
         Node(self.0.node())
     }
@@ -0,0 +1,342 @@
+use serde::Serialize;
+
+use crate::delta::metric_values;
+use crate::describe::describe_metric_field;
+use crate::rules::{Finding, Severity};
+use crate::spaces::{FuncSpace, SpaceKind};
+
+/// The statistical methods detecting the outliers of a metric.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum OutlierMethod {
+    /// Tukey's fences: the values beyond the quartiles by more than
+    /// `factor` times the interquartile range, 1.5 by default
+    #[default]
+    Iqr,
+    /// The values farther from the mean than `factor` times
+    /// the standard deviation, 3 by default
+    ZScore,
+}
+
+impl OutlierMethod {
+    /// The names of the methods.
+    pub const NAMES: &'static [&'static str] = &["iqr", "zscore"];
+
+    /// Returns the method with the given name.
+    ///
+    /// Returns `None` if the method does not exist.
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name {
+            "iqr" => Some(Self::Iqr),
+            "zscore" => Some(Self::ZScore),
+            _ => None,
+        }
+    }
+
+    /// Returns the factor used by default with the method.
+    pub fn default_factor(self) -> f64 {
+        match self {
+            Self::Iqr => 1.5,
+            Self::ZScore => 3.,
+        }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq)]
+struct MeasuredFunction {
+    path: String,
+    name: Option<String>,
+    id: String,
+    start_line: usize,
+    end_line: usize,
+    value: f64,
+}
+
+/// The functions of all the analyzed files whose value of a metric is
+/// a statistical outlier with respect to the other functions.
+///
+/// The threshold of the outliers is computed from the distribution of
+/// the values, so it does not need to be tuned for each project.
+/// The outliers are the functions with the highest values of the metric,
+/// or with the lowest ones for the metrics whose higher values are better,
+/// such as the maintainability index.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Outliers {
+    /// The metric, such as `cognitive`
+    pub metric: String,
+    /// The field of the metric, such as `sum`
+    pub field: String,
+    /// The method detecting the outliers
+    pub method: OutlierMethod,
+    /// The factor of the method
+    pub factor: f64,
+    higher_is_better: bool,
+    functions: Vec<MeasuredFunction>,
+}
+
+impl Outliers {
+    /// Creates an empty set of functions whose outliers are detected
+    /// with a method and its default factor.
+    ///
+    /// The metric is named as in the serialized metrics, optionally
+    /// followed by one of its fields, such as `cognitive` or `loc.sloc`.
+    /// Without a field, the `sum` field is used, or the first field
+    /// of the metrics without one.
+    ///
+    /// Returns `None` if the metric or the field does not exist.
+    pub fn new(metric: &str, method: OutlierMethod) -> Option<Self> {
+        let (metric, field) = describe_metric_field(metric)?;
+        Some(Self {
+            metric: metric.key.to_owned(),
+            field: field.to_owned(),
+            method,
+            factor: method.default_factor(),
+            higher_is_better: metric.higher_is_better,
+            functions: Vec::new(),
+        })
+    }
+
+    /// Sets the factor of the method: the lower it is,
+    /// the more functions are outliers.
+    pub fn with_factor(mut self, factor: f64) -> Self {
+        self.factor = factor;
+        self
+    }
+
+    /// Adds the functions of a file.
+    ///
+    /// * `space` - The root function space of the file.
+    pub fn add(&mut self, space: &FuncSpace) {
+        let path = space.name.clone().unwrap_or_default();
+        self.add_functions(&path, space);
+    }
+
+    fn add_functions(&mut self, path: &str, space: &FuncSpace) {
+        for subspace in &space.spaces {
+            if subspace.kind == SpaceKind::Function
+                && let Some(value) = metric_values(&subspace.metrics)
+                    .get(&self.metric)
+                    .and_then(|fields| fields.get(&self.field))
+            {
+                self.functions.push(MeasuredFunction {
+                    path: path.to_owned(),
+                    name: subspace
+                        .qualified_name
+                        .clone()
+                        .or_else(|| subspace.name.clone()),
+                    id: subspace.id.clone(),
+                    start_line: subspace.start_line,
+                    end_line: subspace.end_line,
+                    value: *value,
+                });
+            }
+            self.add_functions(path, subspace);
+        }
+    }
+
+    /// Returns the value beyond which a function is an outlier.
+    ///
+    /// Returns `None` if there are less than two functions, or if most
+    /// of them have the same value, such as the functions without
+    /// branches for the cognitive complexity: the other values cannot be
+    /// told apart from the common one with the interquartile range,
+    /// but they may with the standard deviation.
+    pub fn threshold(&self) -> Option<f64> {
+        if self.functions.len() < 2 {
+            return None;
+        }
+        let mut values: Vec<_> = self.functions.iter().map(|f| f.value).collect();
+        values.sort_by(f64::total_cmp);
+        let sign = if self.higher_is_better { -1. } else { 1. };
+
+        let (center, spread) = match self.method {
+            OutlierMethod::Iqr => {
+                let (q1, q3) = (quantile(&values, 0.25), quantile(&values, 0.75));
+                let quartile = if self.higher_is_better { q1 } else { q3 };
+                (quartile, q3 - q1)
+            }
+            OutlierMethod::ZScore => {
+                let count = values.len() as f64;
+                let mean = values.iter().sum::<f64>() / count;
+                let variance = values.iter().map(|v| (v - mean).powi(2)).sum::<f64>() / count;
+                (mean, variance.sqrt())
+            }
+        };
+        (spread > 0.).then_some(center + sign * self.factor * spread)
+    }
+
+    /// Returns the outliers as findings, sorted by path and line.
+    ///
+    /// The rule of the findings is the metric, such as `cognitive.sum`,
+    /// and their threshold is the one computed by the method,
+    /// rounded to two decimals.
+    pub fn findings(&self) -> Vec<Finding> {
+        let Some(threshold) = self.threshold() else {
+            return Vec::new();
+        };
+        let mut findings: Vec<_> = self
+            .functions
+            .iter()
+            .filter(|function| {
+                if self.higher_is_better {
+                    function.value < threshold
+                } else {
+                    function.value > threshold
+                }
+            })
+            .map(|function| Finding {
+                rule: format!("{}.{}", self.metric, self.field),
+                severity: Severity::Minor,
+                path: function.path.clone(),
+                name: function.name.clone(),
+                id: function.id.clone(),
+                start_line: function.start_line,
+                end_line: function.end_line,
+                value: function.value,
+                // Rounded to be readable in the messages
+                threshold: (threshold * 100.).round() / 100.,
+                remediation: 0.,
+            })
+            .collect();
+        // The findings do not depend on the order in which the files are added
+        findings.sort_by(|a, b| {
+            a.path
+                .cmp(&b.path)
+                .then_with(|| a.start_line.cmp(&b.start_line))
+        });
+        findings
+    }
+}
+
+// Returns a quantile of sorted values, interpolating linearly
+// between the closest ones
+fn quantile(values: &[f64], q: f64) -> f64 {
+    let position = q * (values.len() - 1) as f64;
+    let (low, high) = (position.floor() as usize, position.ceil() as usize);
+    values[low] + (values[high] - values[low]) * (position - low as f64)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::path::Path;
+
+    use pretty_assertions::assert_eq;
+
+    use crate::{LANG, get_function_spaces};
+
+    use super::*;
+
+    fn spaces(path: &str, code: &str) -> FuncSpace {
+        get_function_spaces(
+            &LANG::Python,
+            code.as_bytes().to_vec(),
+            Path::new(path),
+            None,
+        )
+        .unwrap()
+    }
+
+    // Functions with 0, 1, 1, 2, 2, 3 and 12 conditions
+    fn project() -> Vec<FuncSpace> {
+        let function = |name: &str, conditions: usize| {
+            let branches = (0..conditions)
+                .map(|i| format!("    if a == {i}:\n        return {i}\n"))
+                .collect::<String>();
+            format!("def {name}(a):\n{branches}    return a\n\n")
+        };
+        vec![
+            spaces(
+                "b.py",
+                &[("f", 0), ("g", 1), ("h", 12)]
+                    .map(|(name, conditions)| function(name, conditions))
+                    .concat(),
+            ),
+            spaces(
+                "a.py",
+                &[("i", 1), ("j", 2), ("k", 2), ("l", 3)]
+                    .map(|(name, conditions)| function(name, conditions))
+                    .concat(),
+            ),
+        ]
+    }
+
+    #[test]
+    fn outliers_iqr() {
+        let mut outliers = Outliers::new("cyclomatic", OutlierMethod::Iqr).unwrap();
+        assert!(outliers.findings().is_empty());
+        for space in project() {
+            outliers.add(&space);
+        }
+
+        // The cyclomatic complexities are 1, 2, 2, 3, 3, 4 and 13:
+        // the quartiles are 2 and 3.5, so the threshold is 3.5 + 1.5 * 1.5
+        assert_eq!(outliers.threshold(), Some(5.75));
+        let findings = outliers.findings();
+        let findings: Vec<_> = findings
+            .iter()
+            .map(|finding| {
+                (
+                    finding.path.as_str(),
+                    finding.name.as_deref(),
+                    finding.value,
+                )
+            })
+            .collect();
+        assert_eq!(findings, [("b.py", Some("b.h"), 13.)]);
+        assert_eq!(
+            outliers.findings()[0].message(),
+            "Function `b.h` has a cyclomatic.sum of 13, above the threshold of 5.75"
+        );
+
+        // A higher factor does not flag any function
+        let outliers = Outliers {
+            factor: 10.,
+            ..outliers
+        };
+        assert!(outliers.findings().is_empty());
+    }
+
+    #[test]
+    fn outliers_zscore() {
+        let mut outliers = Outliers::new("cyclomatic", OutlierMethod::ZScore)
+            .unwrap()
+            .with_factor(2.);
+        for space in project() {
+            outliers.add(&space);
+        }
+
+        let threshold = outliers.threshold().unwrap();
+        assert!(4. < threshold && threshold < 13., "{threshold}");
+        let names: Vec<_> = outliers
+            .findings()
+            .into_iter()
+            .filter_map(|finding| finding.name)
+            .collect();
+        assert_eq!(names, ["b.h"]);
+
+        // The lowest values of the maintainability index are the outliers
+        let mut outliers = Outliers::new("mi", OutlierMethod::ZScore)
+            .unwrap()
+            .with_factor(2.);
+        for space in project() {
+            outliers.add(&space);
+        }
+        let findings = outliers.findings();
+        assert_eq!(findings.len(), 1);
+        assert_eq!(findings[0].name.as_deref(), Some("b.h"));
+        assert!(findings[0].message().contains("below the threshold"));
+
+        assert!(Outliers::new("foo", OutlierMethod::Iqr).is_none());
+        // Without spread, there are no outliers
+        let mut outliers = Outliers::new("cyclomatic", OutlierMethod::Iqr).unwrap();
+        outliers.add(&spaces(
+            "c.py",
+            "def f():\n    pass\n\ndef g():\n    pass\n\ndef h():\n    pass\n\ndef i():\n    pass\n\ndef j(a):\n    return a or 1\n",
+        ));
+        assert_eq!(outliers.threshold(), None);
+        assert_eq!(
+            OutlierMethod::from_name("zscore"),
+            Some(OutlierMethod::ZScore)
+        );
+    }
+}
@@ -0,0 +1,173 @@
+use crate::grades::{Grade, GradeOptions};
+use crate::metrics::cyclomatic;
+use crate::spaces::FuncSpace;
+
+// The colors of shields.io, from the best to the worst grade
+const COLORS: [&str; 6] = [
+    "#4c1", "#97ca00", "#a4a61d", "#dfb317", "#fe7d37", "#e05d44",
+];
+
+// The approximate width of a character of the badges, in pixels
+const CHAR_WIDTH: usize = 7;
+
+// The horizontal padding of each side of a badge, in pixels
+const PADDING: usize = 10;
+
+// Escapes the special characters of XML in a text or in an attribute
+pub(crate) fn escape_xml(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+/// Returns the color of a grade, from green to red.
+///
+/// * `grade` - The grade.
+/// * `boundaries` - The number of boundaries of the grades,
+///   so that the worst possible grade is always red.
+pub fn grade_color(grade: Grade, boundaries: usize) -> &'static str {
+    if boundaries == 0 {
+        return COLORS[0];
+    }
+    let index = (grade as usize).min(boundaries) * (COLORS.len() - 1) / boundaries;
+    COLORS[index]
+}
+
+/// Returns a badge in the flat style of shields.io, as an SVG image.
+///
+/// * `label` - The text on the left side of the badge.
+/// * `message` - The text on the right side of the badge.
+/// * `color` - The color of the right side of the badge.
+///
+/// # Examples
+///
+/// ```
+/// use rust_code_analysis::badge_svg;
+///
+/// let svg = badge_svg("complexity", "B", "#97ca00");
+///
+/// assert!(svg.starts_with("<svg"));
+/// assert!(svg.contains("<title>complexity: B</title>"));
+/// ```
+pub fn badge_svg(label: &str, message: &str, color: &str) -> String {
+    let label_width = label.chars().count() * CHAR_WIDTH + 2 * PADDING;
+    let message_width = message.chars().count() * CHAR_WIDTH + 2 * PADDING;
+    let width = label_width + message_width;
+    let label_x = label_width / 2;
+    let message_x = label_width + message_width / 2;
+    let (label, message, color) = (escape_xml(label), escape_xml(message), escape_xml(color));
+
+    format!(
+        r##"<svg xmlns="http://www.w3.org/2000/svg" width="{width}" height="20" role="img" aria-label="{label}: {message}"><title>{label}: {message}</title><linearGradient id="s" x2="0" y2="100%"><stop offset="0" stop-color="#bbb" stop-opacity=".1"/><stop offset="1" stop-opacity=".1"/></linearGradient><clipPath id="r"><rect width="{width}" height="20" rx="3" fill="#fff"/></clipPath><g clip-path="url(#r)"><rect width="{label_width}" height="20" fill="#555"/><rect x="{label_width}" width="{message_width}" height="20" fill="{color}"/><rect width="{width}" height="20" fill="url(#s)"/></g><g fill="#fff" text-anchor="middle" font-family="Verdana,Geneva,DejaVu Sans,sans-serif" font-size="11"><text x="{label_x}" y="15" fill="#010101" fill-opacity=".3">{label}</text><text x="{label_x}" y="14">{label}</text><text x="{message_x}" y="15" fill="#010101" fill-opacity=".3">{message}</text><text x="{message_x}" y="14">{message}</text></g></svg>"##
+    )
+}
+
+/// The metrics of all the files of an analysis, used to build its badges.
+#[derive(Clone, Debug, Default)]
+pub struct Badges {
+    cyclomatic: Option<cyclomatic::Stats>,
+    // The maintainability indices of the files weighted by their lines
+    mi_sum: f64,
+    sloc: f64,
+}
+
+impl Badges {
+    /// Adds the metrics of a file.
+    ///
+    /// * `space` - The root function space of the file.
+    pub fn add(&mut self, space: &FuncSpace) {
+        let metrics = &space.metrics;
+        match &mut self.cyclomatic {
+            Some(cyclomatic) => cyclomatic.merge(&metrics.cyclomatic),
+            None => self.cyclomatic = Some(metrics.cyclomatic.clone()),
+        }
+        self.mi_sum += metrics.mi.mi_visual_studio() * metrics.loc.sloc();
+        self.sloc += metrics.loc.sloc();
+    }
+
+    /// Returns the average cyclomatic complexity of the spaces of the files.
+    pub fn cyclomatic_average(&self) -> f64 {
+        self.cyclomatic
+            .as_ref()
+            .map_or(0., cyclomatic::Stats::cyclomatic_average)
+    }
+
+    /// Returns the maintainability index of the files, on the Visual Studio
+    /// scale, that is the average of their indices weighted by their lines.
+    pub fn mi(&self) -> f64 {
+        if self.sloc == 0. {
+            0.
+        } else {
+            self.mi_sum / self.sloc
+        }
+    }
+
+    /// Returns a badge with the grade of the average cyclomatic complexity,
+    /// such as `complexity: B`.
+    ///
+    /// * `options` - The boundaries of the grades.
+    pub fn complexity_svg(&self, options: &GradeOptions) -> String {
+        let grade = options.cyclomatic_grade(self.cyclomatic_average());
+        badge_svg(
+            "complexity",
+            &grade.to_string(),
+            grade_color(grade, options.cyclomatic().len()),
+        )
+    }
+
+    /// Returns a badge with the maintainability index, such as `MI: 78`,
+    /// colored by its grade.
+    ///
+    /// * `options` - The boundaries of the grades.
+    pub fn mi_svg(&self, options: &GradeOptions) -> String {
+        let mi = self.mi();
+        let grade = options.mi_grade(mi);
+        badge_svg(
+            "MI",
+            &format!("{mi:.0}"),
+            grade_color(grade, options.mi().len()),
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::path::Path;
+
+    use super::*;
+    use crate::langs::{LANG, get_function_spaces};
+
+    #[test]
+    fn badge_colors() {
+        assert_eq!(grade_color(Grade::A, 5), "#4c1");
+        assert_eq!(grade_color(Grade::F, 5), "#e05d44");
+        assert_eq!(grade_color(Grade::B, 2), "#a4a61d");
+        assert_eq!(grade_color(Grade::C, 2), "#e05d44");
+        assert_eq!(grade_color(Grade::C, 0), "#4c1");
+    }
+
+    #[test]
+    fn python_badges() {
+        let mut badges = Badges::default();
+        for source in ["def foo(a):\n    if a:\n        return 1\n", "b = 2\n"] {
+            let space = get_function_spaces(
+                &LANG::Python,
+                source.as_bytes().to_vec(),
+                Path::new("foo.py"),
+                None,
+            )
+            .unwrap();
+            badges.add(&space);
+        }
+
+        // The spaces are the two units and the function
+        assert_eq!(badges.cyclomatic_average(), 4. / 3.);
+        let options = GradeOptions::default();
+        insta::assert_snapshot!(badges.complexity_svg(&options), @r###"
+        <svg xmlns="http://www.w3.org/2000/svg" width="117" height="20" role="img" aria-label="complexity: A"><title>complexity: A</title><linearGradient id="s" x2="0" y2="100%"><stop offset="0" stop-color="#bbb" stop-opacity=".1"/><stop offset="1" stop-opacity=".1"/></linearGradient><clipPath id="r"><rect width="117" height="20" rx="3" fill="#fff"/></clipPath><g clip-path="url(#r)"><rect width="90" height="20" fill="#555"/><rect x="90" width="27" height="20" fill="#4c1"/><rect width="117" height="20" fill="url(#s)"/></g><g fill="#fff" text-anchor="middle" font-family="Verdana,Geneva,DejaVu Sans,sans-serif" font-size="11"><text x="45" y="15" fill="#010101" fill-opacity=".3">complexity</text><text x="45" y="14">complexity</text><text x="103" y="15" fill="#010101" fill-opacity=".3">A</text><text x="103" y="14">A</text></g></svg>
+        "###);
+        assert!(badges.mi_svg(&options).contains("<title>MI: "));
+        assert_eq!(Badges::default().mi(), 0.);
+    }
+}
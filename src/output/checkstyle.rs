@@ -0,0 +1,115 @@
+use std::collections::BTreeMap;
+use std::fmt::Write;
+
+use crate::output::badge::escape_xml;
+use crate::rules::{Finding, Severity};
+
+// The version of the Checkstyle format expected by the tools reading it
+const CHECKSTYLE_VERSION: &str = "4.3";
+
+/// Returns the Checkstyle severity of a severity,
+/// that is `info`, `warning` or `error`.
+pub fn checkstyle_severity(severity: Severity) -> &'static str {
+    match severity {
+        Severity::Info => "info",
+        Severity::Minor | Severity::Major => "warning",
+        Severity::Critical | Severity::Blocker => "error",
+    }
+}
+
+/// Returns the violations of the rules as a Checkstyle XML report,
+/// with an element for each file containing the errors of its functions.
+///
+/// * `findings` - The violations of the rules.
+///
+/// # Examples
+///
+/// ```
+/// use std::path::Path;
+///
+/// use rust_code_analysis::{LANG, Rule, check_rules, checkstyle_xml, get_function_spaces};
+///
+/// let source_code = "def foo(a, b, c, d, e, f, g, h):\n    return a\n";
+/// let path = Path::new("foo.py");
+///
+/// let space =
+///     get_function_spaces(&LANG::Python, source_code.as_bytes().to_vec(), path, None).unwrap();
+/// let xml = checkstyle_xml(&check_rules(&space, &Rule::defaults()));
+///
+/// assert!(xml.contains(r#"<file name="foo.py">"#));
+/// ```
+pub fn checkstyle_xml(findings: &[Finding]) -> String {
+    let mut files: BTreeMap<&str, Vec<&Finding>> = BTreeMap::new();
+    for finding in findings {
+        files.entry(&finding.path).or_default().push(finding);
+    }
+
+    let mut xml = String::from("<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n");
+    writeln!(xml, "<checkstyle version=\"{CHECKSTYLE_VERSION}\">").unwrap();
+    for (path, findings) in files {
+        writeln!(xml, "  <file name=\"{}\">", escape_xml(path)).unwrap();
+        for finding in findings {
+            writeln!(
+                xml,
+                "    <error line=\"{}\" severity=\"{}\" message=\"{}\" source=\"rust-code-analysis.{}\"/>",
+                finding.start_line,
+                checkstyle_severity(finding.severity),
+                escape_xml(&finding.message()),
+                escape_xml(&finding.rule)
+            )
+            .unwrap();
+        }
+        writeln!(xml, "  </file>").unwrap();
+    }
+    xml.push_str("</checkstyle>");
+    xml
+}
+
+#[cfg(test)]
+mod tests {
+    use std::path::Path;
+
+    use super::*;
+    use crate::langs::{LANG, get_function_spaces};
+    use crate::rules::{Rule, check_rules};
+
+    #[test]
+    fn checkstyle_report() {
+        let mut findings = Vec::new();
+        for (path, source) in [
+            (
+                "b.rs",
+                "fn f(a: u8, b: u8, c: u8, d: u8, e: u8, f: u8, g: u8, h: u8) {}\n",
+            ),
+            (
+                "a.rs",
+                "fn f(a: u8) -> u8 {\n    if a == 0 { return 0; }\n    if a == 1 { return 1; }\n    if a == 2 { return 2; }\n    3\n}\n",
+            ),
+        ] {
+            let space = get_function_spaces(
+                &LANG::Rust,
+                source.as_bytes().to_vec(),
+                Path::new(path),
+                None,
+            )
+            .unwrap();
+            findings.extend(check_rules(&space, &Rule::defaults()));
+        }
+
+        insta::assert_snapshot!(checkstyle_xml(&findings), @r###"
+        <?xml version="1.0" encoding="UTF-8"?>
+        <checkstyle version="4.3">
+          <file name="a.rs">
+            <error line="1" severity="warning" message="Function `a::f` has a nexits.sum of 4, above the threshold of 3" source="rust-code-analysis.nexits.sum"/>
+          </file>
+          <file name="b.rs">
+            <error line="1" severity="warning" message="Function `b::f` has a nargs.total_functions of 8, above the threshold of 7" source="rust-code-analysis.nargs.total_functions"/>
+          </file>
+        </checkstyle>
+        "###);
+        assert_eq!(
+            checkstyle_xml(&[]),
+            "<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n<checkstyle version=\"4.3\">\n</checkstyle>"
+        );
+    }
+}
@@ -0,0 +1,128 @@
+use serde::Serialize;
+
+use crate::output::gitlab::{GitLabLines, GitLabLocation};
+use crate::rules::{Finding, Severity};
+
+/// The remediation points of a minute of remediation.
+///
+/// Code Climate estimates the time needed to fix an issue
+/// from its remediation points.
+pub const REMEDIATION_POINTS_PER_MINUTE: f64 = 10_000.;
+
+/// An issue of a Code Climate engine, as described by its specification.
+///
+/// The location is shared with the GitLab code quality reports,
+/// which are a subset of the Code Climate issues.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize)]
+pub struct CodeClimateIssue {
+    /// The type of the result, which is always `issue`
+    #[serde(rename = "type")]
+    pub kind: &'static str,
+    /// The key of the violated rule, such as `cyclomatic.sum`
+    pub check_name: String,
+    /// The description of the issue
+    pub description: String,
+    /// The categories of the issue
+    pub categories: Vec<&'static str>,
+    /// The location of the issue
+    pub location: GitLabLocation,
+    /// The estimated effort to fix the issue
+    pub remediation_points: u64,
+    /// The severity of the issue, whose levels are the same of Code Climate
+    pub severity: Severity,
+    /// The identifier used by Code Climate to compare the issues of two runs
+    pub fingerprint: String,
+}
+
+impl From<&Finding> for CodeClimateIssue {
+    fn from(finding: &Finding) -> Self {
+        Self {
+            kind: "issue",
+            check_name: finding.rule.clone(),
+            description: finding.message(),
+            // The rules are all on the size and the complexity of the functions
+            categories: vec!["Complexity"],
+            location: GitLabLocation {
+                path: finding.path.clone(),
+                lines: GitLabLines {
+                    begin: finding.start_line,
+                    end: finding.end_line,
+                },
+            },
+            remediation_points: (finding.remediation * REMEDIATION_POINTS_PER_MINUTE).round()
+                as u64,
+            severity: finding.severity,
+            fingerprint: finding.fingerprint(),
+        }
+    }
+}
+
+/// Returns the Code Climate issues from the violations of the rules.
+///
+/// * `findings` - The violations of the rules.
+///
+/// # Examples
+///
+/// ```
+/// use std::path::Path;
+///
+/// use rust_code_analysis::{LANG, Rule, check_rules, code_climate_issues, get_function_spaces};
+///
+/// let source_code = "def foo(a, b, c, d, e, f, g, h):\n    return a\n";
+/// let path = Path::new("foo.py");
+///
+/// let space =
+///     get_function_spaces(&LANG::Python, source_code.as_bytes().to_vec(), path, None).unwrap();
+/// let issues = code_climate_issues(&check_rules(&space, &Rule::defaults()));
+///
+/// assert_eq!(issues[0].kind, "issue");
+/// assert_eq!(issues[0].remediation_points, 200_000);
+/// ```
+pub fn code_climate_issues(findings: &[Finding]) -> Vec<CodeClimateIssue> {
+    findings.iter().map(CodeClimateIssue::from).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use std::path::Path;
+
+    use super::*;
+    use crate::langs::{LANG, get_function_spaces};
+    use crate::rules::{Rule, check_rules};
+
+    #[test]
+    fn code_climate_issue() {
+        let source = "def foo(a):\n    if a:\n        return 1\n    if a > 1:\n        return 2\n    if a > 2:\n        return 3\n    return 4\n";
+        let space = get_function_spaces(
+            &LANG::Python,
+            source.as_bytes().to_vec(),
+            Path::new("foo.py"),
+            None,
+        )
+        .unwrap();
+
+        let issues = code_climate_issues(&check_rules(&space, &Rule::defaults()));
+        insta::assert_json_snapshot!(issues, { "[].fingerprint" => "[fingerprint]" }, @r###"
+        [
+          {
+            "type": "issue",
+            "check_name": "nexits.sum",
+            "description": "Function `foo.foo` has a nexits.sum of 4, above the threshold of 3",
+            "categories": [
+              "Complexity"
+            ],
+            "location": {
+              "path": "foo.py",
+              "lines": {
+                "begin": 1,
+                "end": 8
+              }
+            },
+            "remediation_points": 200000,
+            "severity": "major",
+            "fingerprint": "[fingerprint]"
+          }
+        ]
+        "###);
+    }
+}
@@ -2,16 +2,26 @@ use std::io::Write;
 use termcolor::{Color, ColorChoice, StandardStream, StandardStreamLock};
 
 use crate::abc;
+use crate::accessors;
+use crate::annotations;
+use crate::assertions;
+use crate::callbacks;
 use crate::cognitive;
 use crate::cyclomatic;
 use crate::exit;
 use crate::halstead;
+use crate::indentation;
+use crate::kotlin_funcs;
 use crate::loc;
+use crate::locals;
+use crate::macro_calls;
 use crate::mi;
 use crate::nargs;
 use crate::nom;
 use crate::npa;
 use crate::npm;
+use crate::ppc;
+use crate::unsafety;
 use crate::wmc;
 
 use crate::spaces::{CodeMetrics, FuncSpace};
@@ -73,7 +83,24 @@ fn dump_space(
     write!(stdout, "{}", space.name.as_ref().map_or("", |name| name))?;
 
     intense_color(stdout, Color::Red)?;
-    writeln!(stdout, " (@{})", space.start_line)?;
+    write!(stdout, " (@{})", space.start_line)?;
+
+    intense_color(stdout, Color::Magenta)?;
+    write!(
+        stdout,
+        " [cyclomatic: {}, mi: {}]",
+        space.grades.cyclomatic, space.grades.mi
+    )?;
+
+    if let Some(quality) = space.parse_quality.filter(|quality| !quality.is_clean()) {
+        intense_color(stdout, Color::Red)?;
+        write!(
+            stdout,
+            " (parse errors: {}, missing: {}, score: {:.2})",
+            quality.errors, quality.missing, quality.score
+        )?;
+    }
+    writeln!(stdout)?;
 
     let prefix = format!("{prefix}{pref_child}");
     dump_metrics(&space.metrics, &prefix, space.spaces.is_empty(), stdout)?;
@@ -114,7 +141,17 @@ fn dump_metrics(
     dump_abc(&metrics.abc, &prefix, false, stdout)?;
     dump_wmc(&metrics.wmc, &prefix, false, stdout)?;
     dump_npm(&metrics.npm, &prefix, false, stdout)?;
-    dump_npa(&metrics.npa, &prefix, true, stdout)
+    dump_npa(&metrics.npa, &prefix, false, stdout)?;
+    dump_ppc(&metrics.ppc, &prefix, false, stdout)?;
+    dump_unsafety(&metrics.unsafety, &prefix, false, stdout)?;
+    dump_callbacks(&metrics.callbacks, &prefix, false, stdout)?;
+    dump_annotations(&metrics.annotations, &prefix, false, stdout)?;
+    dump_accessors(&metrics.accessors, &prefix, false, stdout)?;
+    dump_assertions(&metrics.assertions, &prefix, false, stdout)?;
+    dump_locals(&metrics.locals, &prefix, false, stdout)?;
+    dump_kotlin_funcs(&metrics.kotlin_funcs, &prefix, false, stdout)?;
+    dump_macro_calls(&metrics.macro_calls, &prefix, false, stdout)?;
+    dump_indentation(&metrics.indentation, &prefix, true, stdout)
 }
 
 fn dump_cognitive(
@@ -358,7 +395,28 @@ fn dump_wmc(
         false,
         stdout,
     )?;
-    dump_value("total", stats.total_wmc(), &prefix, true, stdout)
+    dump_value("total", stats.total_wmc(), &prefix, false, stdout)?;
+    dump_value(
+        "classes_cognitive",
+        stats.class_cognitive_wmc_sum(),
+        &prefix,
+        false,
+        stdout,
+    )?;
+    dump_value(
+        "interfaces_cognitive",
+        stats.interface_cognitive_wmc_sum(),
+        &prefix,
+        false,
+        stdout,
+    )?;
+    dump_value(
+        "total_cognitive",
+        stats.total_cognitive_wmc(),
+        &prefix,
+        true,
+        stdout,
+    )
 }
 
 fn dump_npm(
@@ -423,6 +481,321 @@ fn dump_npa(
     dump_value("average", stats.total_cda(), &prefix, true, stdout)
 }
 
+fn dump_ppc(
+    stats: &ppc::Stats,
+    prefix: &str,
+    last: bool,
+    stdout: &mut StandardStreamLock,
+) -> std::io::Result<()> {
+    if stats.is_disabled() {
+        return Ok(());
+    }
+
+    let (pref_child, pref) = if last { ("   ", "`- ") } else { ("|  ", "|- ") };
+
+    color(stdout, Color::Blue)?;
+    write!(stdout, "{prefix}{pref}")?;
+
+    intense_color(stdout, Color::Green)?;
+    writeln!(stdout, "ppc")?;
+
+    let prefix = format!("{prefix}{pref_child}");
+    dump_value(
+        "conditionals",
+        stats.conditionals_sum(),
+        &prefix,
+        false,
+        stdout,
+    )?;
+    dump_value("branches", stats.branches_sum(), &prefix, false, stdout)?;
+    dump_value("nesting_max", stats.nesting_max(), &prefix, true, stdout)
+}
+
+fn dump_unsafety(
+    stats: &unsafety::Stats,
+    prefix: &str,
+    last: bool,
+    stdout: &mut StandardStreamLock,
+) -> std::io::Result<()> {
+    if stats.is_disabled() {
+        return Ok(());
+    }
+
+    let (pref_child, pref) = if last { ("   ", "`- ") } else { ("|  ", "|- ") };
+
+    color(stdout, Color::Blue)?;
+    write!(stdout, "{prefix}{pref}")?;
+
+    intense_color(stdout, Color::Green)?;
+    writeln!(stdout, "unsafety")?;
+
+    let prefix = format!("{prefix}{pref_child}");
+    dump_value("blocks", stats.blocks_sum(), &prefix, false, stdout)?;
+    dump_value("functions", stats.functions_sum(), &prefix, false, stdout)?;
+    dump_value("lines", stats.lines(), &prefix, false, stdout)?;
+    dump_value("ratio", stats.ratio(), &prefix, true, stdout)
+}
+
+fn dump_callbacks(
+    stats: &callbacks::Stats,
+    prefix: &str,
+    last: bool,
+    stdout: &mut StandardStreamLock,
+) -> std::io::Result<()> {
+    if stats.is_disabled() {
+        return Ok(());
+    }
+
+    let (pref_child, pref) = if last { ("   ", "`- ") } else { ("|  ", "|- ") };
+
+    color(stdout, Color::Blue)?;
+    write!(stdout, "{prefix}{pref}")?;
+
+    intense_color(stdout, Color::Green)?;
+    writeln!(stdout, "callbacks")?;
+
+    let prefix = format!("{prefix}{pref_child}");
+    dump_value("nesting_max", stats.nesting_max(), &prefix, false, stdout)?;
+    dump_value("chain_max", stats.chain_max(), &prefix, true, stdout)
+}
+
+fn dump_annotations(
+    stats: &annotations::Stats,
+    prefix: &str,
+    last: bool,
+    stdout: &mut StandardStreamLock,
+) -> std::io::Result<()> {
+    if stats.is_disabled() {
+        return Ok(());
+    }
+
+    let (pref_child, pref) = if last { ("   ", "`- ") } else { ("|  ", "|- ") };
+
+    color(stdout, Color::Blue)?;
+    write!(stdout, "{prefix}{pref}")?;
+
+    intense_color(stdout, Color::Green)?;
+    writeln!(stdout, "annotations")?;
+
+    let prefix = format!("{prefix}{pref_child}");
+    dump_value(
+        "annotations",
+        stats.annotations_sum(),
+        &prefix,
+        false,
+        stdout,
+    )?;
+    dump_value("throws", stats.throws_sum(), &prefix, true, stdout)
+}
+
+fn dump_accessors(
+    stats: &accessors::Stats,
+    prefix: &str,
+    last: bool,
+    stdout: &mut StandardStreamLock,
+) -> std::io::Result<()> {
+    if stats.is_disabled() {
+        return Ok(());
+    }
+
+    let (pref_child, pref) = if last { ("   ", "`- ") } else { ("|  ", "|- ") };
+
+    color(stdout, Color::Blue)?;
+    write!(stdout, "{prefix}{pref}")?;
+
+    intense_color(stdout, Color::Green)?;
+    writeln!(stdout, "accessors")?;
+
+    let prefix = format!("{prefix}{pref_child}");
+    dump_value("getters", stats.getters_sum(), &prefix, false, stdout)?;
+    dump_value("setters", stats.setters_sum(), &prefix, false, stdout)?;
+    dump_value("total", stats.total(), &prefix, false, stdout)?;
+    dump_value("functions", stats.functions_sum(), &prefix, true, stdout)
+}
+
+fn dump_assertions(
+    stats: &assertions::Stats,
+    prefix: &str,
+    last: bool,
+    stdout: &mut StandardStreamLock,
+) -> std::io::Result<()> {
+    if stats.is_disabled() {
+        return Ok(());
+    }
+
+    let (pref_child, pref) = if last { ("   ", "`- ") } else { ("|  ", "|- ") };
+
+    color(stdout, Color::Blue)?;
+    write!(stdout, "{prefix}{pref}")?;
+
+    intense_color(stdout, Color::Green)?;
+    writeln!(stdout, "assertions")?;
+
+    let prefix = format!("{prefix}{pref_child}");
+    dump_value("sum", stats.assertions_sum(), &prefix, false, stdout)?;
+    dump_value(
+        "average",
+        stats.assertions_average(),
+        &prefix,
+        false,
+        stdout,
+    )?;
+    dump_value("min", stats.assertions_min(), &prefix, false, stdout)?;
+    dump_value("max", stats.assertions_max(), &prefix, true, stdout)
+}
+
+fn dump_locals(
+    stats: &locals::Stats,
+    prefix: &str,
+    last: bool,
+    stdout: &mut StandardStreamLock,
+) -> std::io::Result<()> {
+    if stats.is_disabled() {
+        return Ok(());
+    }
+
+    let (pref_child, pref) = if last { ("   ", "`- ") } else { ("|  ", "|- ") };
+
+    color(stdout, Color::Blue)?;
+    write!(stdout, "{prefix}{pref}")?;
+
+    intense_color(stdout, Color::Green)?;
+    writeln!(stdout, "locals")?;
+
+    let prefix = format!("{prefix}{pref_child}");
+    dump_value("sum", stats.locals_sum(), &prefix, false, stdout)?;
+    dump_value("average", stats.locals_average(), &prefix, false, stdout)?;
+    dump_value("min", stats.locals_min(), &prefix, false, stdout)?;
+    dump_value("max", stats.locals_max(), &prefix, true, stdout)
+}
+
+fn dump_kotlin_funcs(
+    stats: &kotlin_funcs::Stats,
+    prefix: &str,
+    last: bool,
+    stdout: &mut StandardStreamLock,
+) -> std::io::Result<()> {
+    if stats.is_disabled() {
+        return Ok(());
+    }
+
+    let (pref_child, pref) = if last { ("   ", "`- ") } else { ("|  ", "|- ") };
+
+    color(stdout, Color::Blue)?;
+    write!(stdout, "{prefix}{pref}")?;
+
+    intense_color(stdout, Color::Green)?;
+    writeln!(stdout, "kotlin_funcs")?;
+
+    let prefix = format!("{prefix}{pref_child}");
+    dump_value(
+        "suspend_functions",
+        stats.suspend_functions_sum(),
+        &prefix,
+        false,
+        stdout,
+    )?;
+    dump_value(
+        "extension_functions",
+        stats.extension_functions_sum(),
+        &prefix,
+        false,
+        stdout,
+    )?;
+    dump_value(
+        "receiver_lambdas",
+        stats.receiver_lambdas_sum(),
+        &prefix,
+        true,
+        stdout,
+    )
+}
+
+fn dump_macro_calls(
+    stats: &macro_calls::Stats,
+    prefix: &str,
+    last: bool,
+    stdout: &mut StandardStreamLock,
+) -> std::io::Result<()> {
+    if stats.is_disabled() {
+        return Ok(());
+    }
+
+    let (pref_child, pref) = if last { ("   ", "`- ") } else { ("|  ", "|- ") };
+
+    color(stdout, Color::Blue)?;
+    write!(stdout, "{prefix}{pref}")?;
+
+    intense_color(stdout, Color::Green)?;
+    writeln!(stdout, "macro_calls")?;
+
+    let prefix = format!("{prefix}{pref_child}");
+    dump_value(
+        "invocations",
+        stats.invocations_sum(),
+        &prefix,
+        false,
+        stdout,
+    )?;
+    dump_value("statements", stats.statements_sum(), &prefix, false, stdout)?;
+    dump_value(
+        "macro_statements",
+        stats.macro_statements_sum(),
+        &prefix,
+        false,
+        stdout,
+    )?;
+    dump_value(
+        "dominated_functions",
+        stats.dominated_sum(),
+        &prefix,
+        true,
+        stdout,
+    )
+}
+
+fn dump_indentation(
+    stats: &indentation::Stats,
+    prefix: &str,
+    last: bool,
+    stdout: &mut StandardStreamLock,
+) -> std::io::Result<()> {
+    let (pref_child, pref) = if last { ("   ", "`- ") } else { ("|  ", "|- ") };
+
+    color(stdout, Color::Blue)?;
+    write!(stdout, "{prefix}{pref}")?;
+
+    intense_color(stdout, Color::Green)?;
+    writeln!(stdout, "indentation")?;
+
+    let prefix = format!("{prefix}{pref_child}");
+
+    color(stdout, Color::Blue)?;
+    write!(stdout, "{prefix}|- ")?;
+
+    intense_color(stdout, Color::Magenta)?;
+    write!(stdout, "style: ")?;
+
+    color(stdout, Color::White)?;
+    writeln!(stdout, "{}", stats.style())?;
+
+    dump_value("level_max", stats.level_max(), &prefix, false, stdout)?;
+    dump_value(
+        "level_average",
+        stats.level_average(),
+        &prefix,
+        false,
+        stdout,
+    )?;
+    dump_value(
+        "trailing_whitespace",
+        stats.trailing_whitespace(),
+        &prefix,
+        true,
+        stdout,
+    )
+}
+
 fn dump_value(
     name: &str,
     val: f64,
@@ -0,0 +1,134 @@
+use serde::Serialize;
+
+use crate::rules::{Finding, Severity};
+
+/// The lines of an issue of a GitLab code quality report.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize)]
+pub struct GitLabLines {
+    /// The first line of the issue
+    pub begin: usize,
+    /// The last line of the issue
+    pub end: usize,
+}
+
+/// The location of an issue of a GitLab code quality report.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize)]
+pub struct GitLabLocation {
+    /// The path of the file, relative to the root of the repository
+    pub path: String,
+    /// The lines of the issue
+    pub lines: GitLabLines,
+}
+
+/// An issue of a GitLab code quality report, which GitLab shows
+/// in the merge requests introducing it.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize)]
+pub struct GitLabIssue {
+    /// The description of the issue
+    pub description: String,
+    /// The key of the violated rule, such as `cyclomatic.sum`
+    pub check_name: String,
+    /// The identifier used by GitLab to compare the issues of two reports
+    pub fingerprint: String,
+    /// The severity of the issue, whose levels are the same of GitLab
+    pub severity: Severity,
+    /// The location of the issue
+    pub location: GitLabLocation,
+}
+
+impl From<&Finding> for GitLabIssue {
+    fn from(finding: &Finding) -> Self {
+        Self {
+            description: finding.message(),
+            check_name: finding.rule.clone(),
+            fingerprint: finding.fingerprint(),
+            severity: finding.severity,
+            location: GitLabLocation {
+                path: finding.path.clone(),
+                lines: GitLabLines {
+                    begin: finding.start_line,
+                    end: finding.end_line,
+                },
+            },
+        }
+    }
+}
+
+/// Returns the issues of a GitLab code quality report
+/// from the violations of the rules.
+///
+/// * `findings` - The violations of the rules.
+///
+/// # Examples
+///
+/// ```
+/// use std::path::Path;
+///
+/// use rust_code_analysis::{LANG, Rule, check_rules, get_function_spaces, gitlab_code_quality};
+///
+/// let source_code = "def foo(a, b, c, d, e, f, g, h):\n    return a\n";
+/// let path = Path::new("foo.py");
+///
+/// let space =
+///     get_function_spaces(&LANG::Python, source_code.as_bytes().to_vec(), path, None).unwrap();
+/// let issues = gitlab_code_quality(&check_rules(&space, &Rule::defaults()));
+///
+/// assert_eq!(issues[0].check_name, "nargs.total_functions");
+/// assert_eq!(issues[0].location.lines.begin, 1);
+/// ```
+pub fn gitlab_code_quality(findings: &[Finding]) -> Vec<GitLabIssue> {
+    findings.iter().map(GitLabIssue::from).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use std::path::Path;
+
+    use super::*;
+    use crate::langs::{LANG, get_function_spaces};
+    use crate::rules::{Rule, check_rules};
+
+    fn issues(source: &str) -> Vec<GitLabIssue> {
+        let space = get_function_spaces(
+            &LANG::Rust,
+            source.as_bytes().to_vec(),
+            Path::new("src/foo.rs"),
+            None,
+        )
+        .unwrap();
+        gitlab_code_quality(&check_rules(&space, &Rule::defaults()))
+    }
+
+    #[test]
+    fn gitlab_issues() {
+        let source = "fn f(a: u8, b: u8, c: u8, d: u8, e: u8, f: u8, g: u8, h: u8) {}\n";
+        insta::assert_json_snapshot!(issues(source), @r###"
+        [
+          {
+            "description": "Function `foo::f` has a nargs.total_functions of 8, above the threshold of 7",
+            "check_name": "nargs.total_functions",
+            "fingerprint": "a81e47d807a44394",
+            "severity": "major",
+            "location": {
+              "path": "src/foo.rs",
+              "lines": {
+                "begin": 1,
+                "end": 1
+              }
+            }
+          }
+        ]
+        "###);
+    }
+
+    #[test]
+    fn gitlab_fingerprints() {
+        let source = "fn f(a: u8, b: u8, c: u8, d: u8, e: u8, f: u8, g: u8, h: u8) {}\n";
+        let moved = issues(&format!("fn g() {{}}\n\n{source}"));
+        let issues = issues(source);
+
+        // Moving a function does not change the fingerprints of its issues
+        assert_eq!(issues[0].fingerprint, moved[0].fingerprint);
+        assert_ne!(issues[0].location, moved[0].location);
+    }
+}
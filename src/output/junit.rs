@@ -0,0 +1,209 @@
+use std::collections::BTreeMap;
+use std::fmt::Write;
+
+use crate::output::badge::escape_xml;
+use crate::rules::{Finding, Rule};
+
+// The name of the test suite and the prefix of the classes of the test cases
+const SUITE_NAME: &str = "rust-code-analysis";
+
+/// The gates represented as the test cases of a JUnit report.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum JUnitCases {
+    /// A test case for each file, failing when a function of the file
+    /// violates a rule
+    #[default]
+    File,
+    /// A test case for each rule, failing when a function violates it
+    Rule,
+}
+
+impl JUnitCases {
+    /// The names of the kinds of test cases.
+    pub const NAMES: &'static [&'static str] = &["file", "rule"];
+
+    /// Returns the kind of test cases with the given name.
+    ///
+    /// Returns `None` if the kind of test cases does not exist.
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name {
+            "file" => Some(Self::File),
+            "rule" => Some(Self::Rule),
+            _ => None,
+        }
+    }
+}
+
+fn write_case(xml: &mut String, class: &str, name: &str, findings: &[&Finding]) {
+    let (class, name) = (escape_xml(class), escape_xml(name));
+    if findings.is_empty() {
+        writeln!(xml, "    <testcase classname=\"{class}\" name=\"{name}\"/>").unwrap();
+        return;
+    }
+
+    writeln!(xml, "    <testcase classname=\"{class}\" name=\"{name}\">").unwrap();
+    let message = match findings.len() {
+        1 => "1 violation".to_owned(),
+        n => format!("{n} violations"),
+    };
+    write!(xml, "      <failure message=\"{message}\">").unwrap();
+    let lines: Vec<_> = findings
+        .iter()
+        .map(|finding| {
+            format!(
+                "{}:{}: {}",
+                finding.path,
+                finding.start_line,
+                finding.message()
+            )
+        })
+        .collect();
+    write!(xml, "{}", escape_xml(&lines.join("\n"))).unwrap();
+    writeln!(xml, "</failure>").unwrap();
+    writeln!(xml, "    </testcase>").unwrap();
+}
+
+/// Returns the violations of the rules as a JUnit XML report, where each
+/// file or each rule is a test case failing when it has some violations.
+///
+/// * `files` - The violations of the rules, for each analyzed file.
+/// * `rules` - The checked rules.
+/// * `cases` - The gates represented as the test cases.
+///
+/// # Examples
+///
+/// ```
+/// use std::collections::BTreeMap;
+/// use std::path::Path;
+///
+/// use rust_code_analysis::{JUnitCases, LANG, Rule, check_rules, get_function_spaces, junit_xml};
+///
+/// let source_code = "def foo(a, b, c, d, e, f, g, h):\n    return a\n";
+/// let path = Path::new("foo.py");
+///
+/// let space =
+///     get_function_spaces(&LANG::Python, source_code.as_bytes().to_vec(), path, None).unwrap();
+/// let rules = Rule::defaults();
+/// let files = BTreeMap::from([("foo.py".to_owned(), check_rules(&space, &rules))]);
+/// let xml = junit_xml(&files, &rules, JUnitCases::Rule);
+///
+/// assert!(xml.contains(r#"<testsuite name="rust-code-analysis" tests="5" failures="1">"#));
+/// ```
+pub fn junit_xml(
+    files: &BTreeMap<String, Vec<Finding>>,
+    rules: &[Rule],
+    cases: JUnitCases,
+) -> String {
+    let (class, cases): (_, Vec<(String, Vec<&Finding>)>) = match cases {
+        JUnitCases::File => (
+            format!("{SUITE_NAME}.files"),
+            files
+                .iter()
+                .map(|(path, findings)| (path.clone(), findings.iter().collect()))
+                .collect(),
+        ),
+        JUnitCases::Rule => (
+            format!("{SUITE_NAME}.rules"),
+            rules
+                .iter()
+                .map(|rule| {
+                    let key = rule.key();
+                    let findings = files
+                        .values()
+                        .flatten()
+                        .filter(|finding| finding.rule == key)
+                        .collect();
+                    (key, findings)
+                })
+                .collect(),
+        ),
+    };
+
+    let tests = cases.len();
+    let failures = cases
+        .iter()
+        .filter(|(_, findings)| !findings.is_empty())
+        .count();
+
+    let mut xml = String::from("<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n");
+    writeln!(
+        xml,
+        "<testsuites name=\"{SUITE_NAME}\" tests=\"{tests}\" failures=\"{failures}\">"
+    )
+    .unwrap();
+    writeln!(
+        xml,
+        "  <testsuite name=\"{SUITE_NAME}\" tests=\"{tests}\" failures=\"{failures}\">"
+    )
+    .unwrap();
+    for (name, findings) in &cases {
+        write_case(&mut xml, &class, name, findings);
+    }
+    writeln!(xml, "  </testsuite>").unwrap();
+    xml.push_str("</testsuites>");
+    xml
+}
+
+#[cfg(test)]
+mod tests {
+    use std::path::Path;
+
+    use super::*;
+    use crate::langs::{LANG, get_function_spaces};
+    use crate::rules::check_rules;
+
+    fn files() -> BTreeMap<String, Vec<Finding>> {
+        [
+            ("a.rs", "fn f(a: u8) -> u8 {\n    a\n}\n"),
+            (
+                "b.rs",
+                "fn f(a: u8, b: u8, c: u8, d: u8, e: u8, f: u8, g: u8, h: u8) {}\nfn g(a: u8, b: u8, c: u8, d: u8, e: u8, f: u8, g: u8, h: u8) {}\n",
+            ),
+        ]
+        .into_iter()
+        .map(|(path, source)| {
+            let space = get_function_spaces(
+                &LANG::Rust,
+                source.as_bytes().to_vec(),
+                Path::new(path),
+                None,
+            )
+            .unwrap();
+            (path.to_owned(), check_rules(&space, &Rule::defaults()))
+        })
+        .collect()
+    }
+
+    #[test]
+    fn junit_files() {
+        insta::assert_snapshot!(junit_xml(&files(), &Rule::defaults(), JUnitCases::File), @r###"
+        <?xml version="1.0" encoding="UTF-8"?>
+        <testsuites name="rust-code-analysis" tests="2" failures="1">
+          <testsuite name="rust-code-analysis" tests="2" failures="1">
+            <testcase classname="rust-code-analysis.files" name="a.rs"/>
+            <testcase classname="rust-code-analysis.files" name="b.rs">
+              <failure message="2 violations">b.rs:1: Function `b::f` has a nargs.total_functions of 8, above the threshold of 7
+        b.rs:2: Function `b::g` has a nargs.total_functions of 8, above the threshold of 7</failure>
+            </testcase>
+          </testsuite>
+        </testsuites>
+        "###);
+    }
+
+    #[test]
+    fn junit_rules() {
+        let rules = &Rule::defaults()[1..3];
+        insta::assert_snapshot!(junit_xml(&files(), rules, JUnitCases::Rule), @r###"
+        <?xml version="1.0" encoding="UTF-8"?>
+        <testsuites name="rust-code-analysis" tests="2" failures="1">
+          <testsuite name="rust-code-analysis" tests="2" failures="1">
+            <testcase classname="rust-code-analysis.rules" name="cyclomatic.sum"/>
+            <testcase classname="rust-code-analysis.rules" name="nargs.total_functions">
+              <failure message="2 violations">b.rs:1: Function `b::f` has a nargs.total_functions of 8, above the threshold of 7
+        b.rs:2: Function `b::g` has a nargs.total_functions of 8, above the threshold of 7</failure>
+            </testcase>
+          </testsuite>
+        </testsuites>
+        "###);
+    }
+}
@@ -0,0 +1,218 @@
+use crate::spaces::{FuncSpace, SpaceKind};
+
+/// The measures of a function, as reported by lizard.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct LizardFunction {
+    /// The qualified name of the function
+    pub name: String,
+    /// The qualified name of the function followed by its parameters
+    pub long_name: String,
+    /// The first line of the function
+    pub start_line: usize,
+    /// The last line of the function
+    pub end_line: usize,
+    /// The number of lines of code of the function,
+    /// without blank lines and comments
+    pub nloc: usize,
+    /// The cyclomatic complexity of the function
+    pub ccn: usize,
+    /// The number of tokens of the function, that is its Halstead length
+    pub token_count: usize,
+    /// The number of parameters of the function
+    pub parameter_count: usize,
+}
+
+// Quotes a CSV field, doubling its quotes
+fn quote(field: &str) -> String {
+    format!("\"{}\"", field.replace('"', "\"\""))
+}
+
+impl LizardFunction {
+    /// Returns the number of lines of the function, blank lines
+    /// and comments included.
+    pub fn length(&self) -> usize {
+        self.end_line + 1 - self.start_line
+    }
+
+    /// Returns the row of the function in the output of lizard's
+    /// `--csv` option, whose columns are `NLOC`, `CCN`, `token`, `PARAM`,
+    /// `length`, `location`, `file`, `function`, `long_name`, `start` and `end`.
+    ///
+    /// * `file` - The path of the file containing the function.
+    pub fn to_csv(&self, file: &str) -> String {
+        format!(
+            "{},{},{},{},{},{},{},{},{},{},{}",
+            self.nloc,
+            self.ccn,
+            self.token_count,
+            self.parameter_count,
+            self.length(),
+            quote(&format!(
+                "{}@{}-{}@{file}",
+                self.name, self.start_line, self.end_line
+            )),
+            quote(file),
+            quote(&self.name),
+            quote(&self.long_name),
+            self.start_line,
+            self.end_line
+        )
+    }
+
+    /// Returns the warning printed by lizard's `--warnings_only` option
+    /// for the function.
+    ///
+    /// * `file` - The path of the file containing the function.
+    pub fn to_warning(&self, file: &str) -> String {
+        format!(
+            "{file}:{}: warning: {} has {} NLOC, {} CCN, {} token, {} PARAM, {} length",
+            self.start_line,
+            self.name,
+            self.nloc,
+            self.ccn,
+            self.token_count,
+            self.parameter_count,
+            self.length()
+        )
+    }
+}
+
+/// The thresholds above which lizard warns about a function.
+///
+/// The default thresholds are the ones of lizard.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct LizardThresholds {
+    /// The highest cyclomatic complexity of a function
+    pub ccn: usize,
+    /// The highest number of lines of a function
+    pub length: usize,
+    /// The highest number of parameters of a function
+    pub parameter_count: usize,
+    /// The highest number of lines of code of a function
+    pub nloc: usize,
+}
+
+impl Default for LizardThresholds {
+    fn default() -> Self {
+        Self {
+            ccn: 15,
+            length: 1000,
+            parameter_count: 100,
+            nloc: 1_000_000,
+        }
+    }
+}
+
+impl LizardThresholds {
+    /// Checks if a function exceeds at least one of the thresholds.
+    pub fn is_exceeded_by(&self, function: &LizardFunction) -> bool {
+        function.ccn > self.ccn
+            || function.length() > self.length
+            || function.parameter_count > self.parameter_count
+            || function.nloc > self.nloc
+    }
+}
+
+fn collect_functions(space: &FuncSpace, functions: &mut Vec<LizardFunction>) {
+    for subspace in &space.spaces {
+        if subspace.kind == SpaceKind::Function {
+            let metrics = &subspace.metrics;
+            let name = subspace
+                .qualified_name
+                .clone()
+                .or_else(|| subspace.name.clone())
+                .unwrap_or_default();
+            functions.push(LizardFunction {
+                long_name: format!("{name}{}", subspace.signature().unwrap_or_default()),
+                name,
+                start_line: subspace.start_line,
+                end_line: subspace.end_line,
+                nloc: metrics.loc.ploc() as usize,
+                ccn: metrics.cyclomatic.cyclomatic() as usize,
+                token_count: metrics.halstead.length() as usize,
+                parameter_count: metrics.nargs.fn_args() as usize,
+            });
+        }
+        collect_functions(subspace, functions);
+    }
+}
+
+/// Returns the functions of a file, nested functions included,
+/// with the measures reported by lizard, in the order of the source code.
+///
+/// * `space` - The root function space of a file.
+///
+/// # Examples
+///
+/// ```
+/// use std::path::Path;
+///
+/// use rust_code_analysis::{LANG, get_function_spaces, lizard_functions};
+///
+/// let source_code = "int foo(int a, int b) {\n    return a ? b : 0;\n}\n";
+/// let path = Path::new("foo.c");
+///
+/// let space =
+///     get_function_spaces(&LANG::Cpp, source_code.as_bytes().to_vec(), path, None).unwrap();
+/// let functions = lizard_functions(&space);
+///
+/// assert_eq!(functions[0].long_name, "foo(int a, int b)");
+/// assert_eq!(functions[0].ccn, 2);
+/// assert_eq!(functions[0].parameter_count, 2);
+/// ```
+pub fn lizard_functions(space: &FuncSpace) -> Vec<LizardFunction> {
+    let mut functions = Vec::new();
+    collect_functions(space, &mut functions);
+    functions
+}
+
+#[cfg(test)]
+mod tests {
+    use std::path::Path;
+
+    use super::*;
+    use crate::langs::{LANG, get_function_spaces};
+
+    #[test]
+    fn cpp_lizard() {
+        let source = "namespace ns {
+int foo(int a, int b) {
+    // A comment
+    if (a > b) {
+        return a;
+    }
+    return b;
+}
+}
+";
+        let space = get_function_spaces(
+            &LANG::Cpp,
+            source.as_bytes().to_vec(),
+            Path::new("foo.cpp"),
+            None,
+        )
+        .unwrap();
+        let functions = lizard_functions(&space);
+
+        insta::assert_snapshot!(
+            functions[0].to_csv("foo.cpp"),
+            @r###"
+            6,2,21,2,7,"ns::foo@2-8@foo.cpp","foo.cpp","ns::foo","ns::foo(int a, int b)",2,8
+            "###
+        );
+        insta::assert_snapshot!(
+            functions[0].to_warning("foo.cpp"),
+            @r###"
+            foo.cpp:2: warning: ns::foo has 6 NLOC, 2 CCN, 21 token, 2 PARAM, 7 length
+            "###
+        );
+        assert!(!LizardThresholds::default().is_exceeded_by(&functions[0]));
+        assert!(
+            LizardThresholds {
+                ccn: 1,
+                ..Default::default()
+            }
+            .is_exceeded_by(&functions[0])
+        );
+    }
+}
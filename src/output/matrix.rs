@@ -0,0 +1,158 @@
+use std::collections::{BTreeSet, HashMap};
+
+use crate::delta::metric_values;
+use crate::spaces::FuncSpace;
+
+/// The metrics of a series of function spaces as a dense matrix,
+/// with one row per space and one column per metric value.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct MetricsMatrix {
+    /// The names of the columns, as `{metric}_{field}` such as
+    /// `cyclomatic_sum` or `loc_sloc`, sorted by name
+    pub columns: Vec<String>,
+    /// The file and the identifier of the space of each row
+    pub index: Vec<(String, String)>,
+    /// The values of the matrix, row by row
+    ///
+    /// A metric which is not computed for a space is `NaN`.
+    pub values: Vec<f64>,
+}
+
+impl MetricsMatrix {
+    /// Returns the number of rows of the matrix.
+    pub fn rows(&self) -> usize {
+        self.index.len()
+    }
+}
+
+fn flatten_space<'a>(
+    file: &'a str,
+    space: &'a FuncSpace,
+    rows: &mut Vec<(&'a str, &'a FuncSpace, HashMap<String, f64>)>,
+) {
+    let metrics = metric_values(&space.metrics)
+        .into_iter()
+        .flat_map(|(metric, fields)| {
+            fields
+                .into_iter()
+                .map(move |(field, value)| (format!("{metric}_{field}"), value))
+        })
+        .collect();
+    rows.push((file, space, metrics));
+    for subspace in &space.spaces {
+        flatten_space(file, subspace, rows);
+    }
+}
+
+/// Flattens the metrics of the function spaces of a series of files
+/// into a dense matrix, with one row per space.
+///
+/// The rows are in the order of the files, and the spaces of each file
+/// are in the order of the code, starting from the unit space.
+/// Each row is indexed by the file the space belongs to, which is the name
+/// of the root space, and by the identifier of the space.
+///
+/// * `spaces` - The root function spaces, one for each file.
+///
+/// # Examples
+///
+/// ```
+/// use std::path::Path;
+///
+/// use rust_code_analysis::{LANG, get_function_spaces, spaces_to_matrix};
+///
+/// let source_code = "def foo():\n    return 42\n";
+/// let path = Path::new("foo.py");
+///
+/// let space =
+///     get_function_spaces(&LANG::Python, source_code.as_bytes().to_vec(), path, None).unwrap();
+/// let matrix = spaces_to_matrix(&[space]);
+///
+/// // The unit space and the function space
+/// assert_eq!(matrix.rows(), 2);
+/// assert_eq!(matrix.values.len(), 2 * matrix.columns.len());
+/// ```
+pub fn spaces_to_matrix<'a>(spaces: impl IntoIterator<Item = &'a FuncSpace>) -> MetricsMatrix {
+    let mut rows = Vec::new();
+    for space in spaces {
+        flatten_space(space.name.as_deref().unwrap_or_default(), space, &mut rows);
+    }
+
+    let columns: Vec<String> = rows
+        .iter()
+        .flat_map(|(_, _, metrics)| metrics.keys())
+        .collect::<BTreeSet<_>>()
+        .into_iter()
+        .cloned()
+        .collect();
+
+    // The values are allocated once, for all the rows
+    let mut values = Vec::with_capacity(rows.len() * columns.len());
+    for (_, _, metrics) in &rows {
+        values.extend(
+            columns
+                .iter()
+                .map(|column| metrics.get(column).copied().unwrap_or(f64::NAN)),
+        );
+    }
+
+    MetricsMatrix {
+        columns,
+        index: rows
+            .iter()
+            .map(|(file, space, _)| ((*file).to_owned(), space.id.clone()))
+            .collect(),
+        values,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::path::Path;
+
+    use pretty_assertions::assert_eq;
+
+    use super::*;
+    use crate::langs::{LANG, get_function_spaces};
+
+    fn space(source: &str, path: &str) -> FuncSpace {
+        get_function_spaces(
+            &LANG::Python,
+            source.as_bytes().to_vec(),
+            Path::new(path),
+            None,
+        )
+        .unwrap()
+    }
+
+    #[test]
+    fn test_spaces_to_matrix() {
+        let spaces = [
+            space("def foo(a):\n    if a:\n        return 1\n", "foo.py"),
+            space("b = 2\n", "bar.py"),
+        ];
+        let matrix = spaces_to_matrix(&spaces);
+
+        assert_eq!(matrix.rows(), 3);
+        assert_eq!(matrix.values.len(), 3 * matrix.columns.len());
+        assert_eq!(
+            matrix
+                .index
+                .iter()
+                .map(|(file, _)| file.as_str())
+                .collect::<Vec<_>>(),
+            vec!["foo.py", "foo.py", "bar.py"]
+        );
+        assert_eq!(matrix.index[1].1, spaces[0].spaces[0].id);
+
+        let cyclomatic = matrix
+            .columns
+            .iter()
+            .position(|column| column == "cyclomatic_sum")
+            .unwrap();
+        let value = |row: usize| matrix.values[row * matrix.columns.len() + cyclomatic];
+        assert_eq!(value(0), 3.);
+        assert_eq!(value(1), 2.);
+        assert_eq!(value(2), 1.);
+    }
+}
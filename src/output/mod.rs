@@ -1,3 +1,12 @@
+pub(crate) mod badge;
+pub use badge::*;
+
+pub(crate) mod checkstyle;
+pub use checkstyle::*;
+
+pub(crate) mod codeclimate;
+pub use codeclimate::*;
+
 pub(crate) mod dump;
 pub use dump::*;
 
@@ -6,3 +15,26 @@ pub use dump_metrics::*;
 
 pub(crate) mod dump_ops;
 pub use dump_ops::*;
+
+pub(crate) mod gitlab;
+pub use gitlab::*;
+
+pub(crate) mod junit;
+pub use junit::*;
+
+pub(crate) mod lizard;
+pub use lizard::*;
+
+pub(crate) mod matrix;
+pub use matrix::*;
+
+pub(crate) mod radon;
+pub use radon::*;
+
+pub(crate) mod sexp;
+pub use sexp::*;
+
+#[cfg(feature = "arrow")]
+pub(crate) mod table;
+#[cfg(feature = "arrow")]
+pub use table::*;
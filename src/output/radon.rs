@@ -0,0 +1,348 @@
+use serde::Serialize;
+
+use crate::grades::{Grade, GradeOptions};
+use crate::spaces::{FuncSpace, SpaceKind};
+
+/// The kind of a block in the output of radon.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum RadonBlockKind {
+    /// A function which is not contained in a class
+    Function,
+    /// A function contained in a class
+    Method,
+    /// A class
+    Class,
+}
+
+/// A block of code, as printed by radon's `cc -j` command.
+///
+/// The functions and the methods contain their `closures`, that is
+/// the functions defined within them, while the classes contain
+/// their `methods`.
+#[derive(Clone, Debug, PartialEq, Serialize)]
+pub struct RadonBlock {
+    /// The kind of the block
+    #[serde(rename = "type")]
+    pub kind: RadonBlockKind,
+    /// The grade of the complexity of the block
+    pub rank: Grade,
+    /// The name of the block
+    pub name: String,
+    /// The first line of the block
+    pub lineno: usize,
+    /// The column of the block, which is always 0 since the columns
+    /// of the spaces are not tracked
+    pub col_offset: usize,
+    /// The last line of the block
+    pub endline: usize,
+    /// The cyclomatic complexity of the block
+    pub complexity: f64,
+    /// The name of the class containing a method
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub classname: Option<String>,
+    /// The functions defined within a function or a method
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub closures: Option<Vec<RadonBlock>>,
+    /// The methods of a class
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub methods: Option<Vec<RadonBlock>>,
+    /// The cyclomatic complexity of a class and of all its methods
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub real_complexity: Option<f64>,
+}
+
+/// The maintainability index of a file, as printed by radon's `mi -j` command.
+#[derive(Clone, Copy, Debug, PartialEq, Serialize)]
+pub struct RadonMi {
+    /// The maintainability index on the Visual Studio scale
+    pub mi: f64,
+    /// The grade of the maintainability index
+    pub rank: Grade,
+}
+
+#[inline(always)]
+fn is_class(kind: SpaceKind) -> bool {
+    matches!(
+        kind,
+        SpaceKind::Class
+            | SpaceKind::Struct
+            | SpaceKind::Trait
+            | SpaceKind::Impl
+            | SpaceKind::Interface
+    )
+}
+
+fn function_block(
+    space: &FuncSpace,
+    classname: Option<&str>,
+    options: &GradeOptions,
+) -> RadonBlock {
+    let complexity = space.metrics.cyclomatic.cyclomatic();
+    let closures = space
+        .spaces
+        .iter()
+        .filter(|subspace| subspace.kind == SpaceKind::Function)
+        .map(|subspace| function_block(subspace, None, options))
+        .collect();
+
+    RadonBlock {
+        kind: if classname.is_some() {
+            RadonBlockKind::Method
+        } else {
+            RadonBlockKind::Function
+        },
+        rank: options.cyclomatic_grade(complexity),
+        name: space.name.clone().unwrap_or_default(),
+        lineno: space.start_line,
+        col_offset: 0,
+        endline: space.end_line,
+        complexity,
+        classname: classname.map(str::to_owned),
+        closures: Some(closures),
+        methods: None,
+        real_complexity: None,
+    }
+}
+
+fn class_block(space: &FuncSpace, options: &GradeOptions) -> RadonBlock {
+    let name = space.name.clone().unwrap_or_default();
+    let methods: Vec<_> = space
+        .spaces
+        .iter()
+        .filter(|subspace| subspace.kind == SpaceKind::Function)
+        .map(|subspace| function_block(subspace, Some(&name), options))
+        .collect();
+
+    // The complexity of a class is the average complexity of its methods,
+    // rounded down, plus one when it has more than one method
+    let real_complexity = space.metrics.cyclomatic.cyclomatic_sum();
+    let complexity = match methods.len() {
+        0 => real_complexity,
+        1 => real_complexity.trunc(),
+        len => (real_complexity / len as f64).trunc() + 1.,
+    };
+
+    RadonBlock {
+        kind: RadonBlockKind::Class,
+        rank: options.cyclomatic_grade(complexity),
+        name,
+        lineno: space.start_line,
+        col_offset: 0,
+        endline: space.end_line,
+        complexity,
+        classname: None,
+        closures: None,
+        methods: Some(methods),
+        real_complexity: Some(real_complexity),
+    }
+}
+
+fn collect_blocks(space: &FuncSpace, options: &GradeOptions, blocks: &mut Vec<RadonBlock>) {
+    for subspace in &space.spaces {
+        if subspace.kind == SpaceKind::Function {
+            blocks.push(function_block(subspace, None, options));
+        } else if is_class(subspace.kind) {
+            let class = class_block(subspace, options);
+            let methods = class.methods.clone().unwrap_or_default();
+            blocks.push(class);
+            blocks.extend(methods);
+        } else {
+            collect_blocks(subspace, options, blocks);
+        }
+    }
+}
+
+/// Returns the blocks of a file in the shape of the output
+/// of radon's `cc -j` command.
+///
+/// As radon does, the blocks contain the functions, the classes and,
+/// once more, the methods of the classes, from the most complex to the
+/// least complex one. The structs, the traits, the implementations and
+/// the interfaces are considered classes.
+///
+/// * `space` - The root function space of a file.
+/// * `options` - The boundaries used to rank the blocks.
+///
+/// # Examples
+///
+/// ```
+/// use std::path::Path;
+///
+/// use rust_code_analysis::{GradeOptions, LANG, get_function_spaces, radon_cc};
+///
+/// let source_code = "def foo():\n    return 42\n";
+/// let path = Path::new("foo.py");
+///
+/// let space =
+///     get_function_spaces(&LANG::Python, source_code.as_bytes().to_vec(), path, None).unwrap();
+/// let blocks = radon_cc(&space, &GradeOptions::default());
+///
+/// assert_eq!(blocks[0].name, "foo");
+/// assert_eq!(blocks[0].complexity, 1.);
+/// ```
+pub fn radon_cc(space: &FuncSpace, options: &GradeOptions) -> Vec<RadonBlock> {
+    let mut blocks = Vec::new();
+    collect_blocks(space, options, &mut blocks);
+    // The sort is stable, so the blocks with the same
+    // complexity keep the order of the source code
+    blocks.sort_by(|a, b| b.complexity.total_cmp(&a.complexity));
+    blocks
+}
+
+/// Returns the maintainability index of a file in the shape
+/// of the output of radon's `mi -j` command.
+///
+/// * `space` - The root function space of a file.
+/// * `options` - The boundaries used to rank the maintainability index.
+pub fn radon_mi(space: &FuncSpace, options: &GradeOptions) -> RadonMi {
+    let mi = space.metrics.mi.mi_visual_studio();
+    RadonMi {
+        mi,
+        rank: options.mi_grade(mi),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::path::Path;
+
+    use super::*;
+    use crate::langs::{LANG, get_function_spaces};
+
+    #[test]
+    fn python_radon_cc() {
+        let source = "class Foo:
+    def bar(self, a):
+        if a:
+            return 1
+        return 0
+
+    def baz(self):
+        def inner():
+            return 2
+        return inner()
+
+def qux(a, b):
+    return a and b
+";
+        let space = get_function_spaces(
+            &LANG::Python,
+            source.as_bytes().to_vec(),
+            Path::new("foo.py"),
+            None,
+        )
+        .unwrap();
+
+        insta::assert_json_snapshot!(
+            radon_cc(&space, &GradeOptions::default()),
+            @r###"
+            [
+              {
+                "type": "class",
+                "rank": "A",
+                "name": "Foo",
+                "lineno": 1,
+                "col_offset": 0,
+                "endline": 10,
+                "complexity": 3.0,
+                "methods": [
+                  {
+                    "type": "method",
+                    "rank": "A",
+                    "name": "bar",
+                    "lineno": 2,
+                    "col_offset": 0,
+                    "endline": 5,
+                    "complexity": 2.0,
+                    "classname": "Foo",
+                    "closures": []
+                  },
+                  {
+                    "type": "method",
+                    "rank": "A",
+                    "name": "baz",
+                    "lineno": 7,
+                    "col_offset": 0,
+                    "endline": 10,
+                    "complexity": 1.0,
+                    "classname": "Foo",
+                    "closures": [
+                      {
+                        "type": "function",
+                        "rank": "A",
+                        "name": "inner",
+                        "lineno": 8,
+                        "col_offset": 0,
+                        "endline": 9,
+                        "complexity": 1.0,
+                        "closures": []
+                      }
+                    ]
+                  }
+                ],
+                "real_complexity": 5.0
+              },
+              {
+                "type": "method",
+                "rank": "A",
+                "name": "bar",
+                "lineno": 2,
+                "col_offset": 0,
+                "endline": 5,
+                "complexity": 2.0,
+                "classname": "Foo",
+                "closures": []
+              },
+              {
+                "type": "function",
+                "rank": "A",
+                "name": "qux",
+                "lineno": 12,
+                "col_offset": 0,
+                "endline": 13,
+                "complexity": 2.0,
+                "closures": []
+              },
+              {
+                "type": "method",
+                "rank": "A",
+                "name": "baz",
+                "lineno": 7,
+                "col_offset": 0,
+                "endline": 10,
+                "complexity": 1.0,
+                "classname": "Foo",
+                "closures": [
+                  {
+                    "type": "function",
+                    "rank": "A",
+                    "name": "inner",
+                    "lineno": 8,
+                    "col_offset": 0,
+                    "endline": 9,
+                    "complexity": 1.0,
+                    "closures": []
+                  }
+                ]
+              }
+            ]
+            "###
+        );
+    }
+
+    #[test]
+    fn python_radon_mi() {
+        let space = get_function_spaces(
+            &LANG::Python,
+            b"def foo():\n    return 42\n".to_vec(),
+            Path::new("foo.py"),
+            None,
+        )
+        .unwrap();
+
+        let mi = radon_mi(&space, &GradeOptions::default());
+        assert_eq!(mi.rank, Grade::A);
+        assert_eq!(mi.mi, space.metrics.mi.mi_visual_studio());
+    }
+}
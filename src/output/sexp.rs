@@ -0,0 +1,142 @@
+use std::fmt::Write;
+use std::path::PathBuf;
+
+use crate::node::{Cursor, Node};
+
+use crate::traits::*;
+
+// Writes the named nodes under the cursor, and the missing ones,
+// prefixed by the name of their field as tree-sitter does
+fn write_sexp(cursor: &mut Cursor, byte_ranges: bool, sexp: &mut String) {
+    let node = cursor.node();
+    let visible = node.is_named() || node.is_missing();
+    if visible {
+        if !sexp.is_empty() {
+            sexp.push(' ');
+        }
+        if let Some(field) = cursor.field_name() {
+            write!(sexp, "{field}: ").unwrap();
+        }
+        sexp.push('(');
+        if node.is_missing() {
+            sexp.push_str("MISSING ");
+        }
+        if node.is_named() {
+            sexp.push_str(node.kind());
+        } else {
+            write!(sexp, "{:?}", node.kind()).unwrap();
+        }
+        if byte_ranges {
+            write!(sexp, " [{}..{}]", node.start_byte(), node.end_byte()).unwrap();
+        }
+    }
+    if cursor.goto_first_child() {
+        loop {
+            write_sexp(cursor, byte_ranges, sexp);
+            if !cursor.goto_next_sibling() {
+                break;
+            }
+        }
+        cursor.goto_parent();
+    }
+    if visible {
+        sexp.push(')');
+    }
+}
+
+/// Returns the s-expression of an `AST` node, in the format of tree-sitter.
+///
+/// With `byte_ranges`, each node is followed by the range of its bytes,
+/// such as `(identifier [4..5])`.
+///
+/// # Examples
+///
+/// ```
+/// use std::path::PathBuf;
+///
+/// use rust_code_analysis::{CppParser, ParserTrait, to_sexp};
+///
+/// let source_code = "int a = 42;";
+///
+/// // The path to a dummy file used to contain the source code
+/// let path = PathBuf::from("foo.c");
+/// let source_as_vec = source_code.as_bytes().to_vec();
+///
+/// // The parser of the code, in this case a CPP parser
+/// let parser = CppParser::new(source_as_vec, &path, None);
+///
+/// assert_eq!(
+///     to_sexp(&parser.get_root(), false),
+///     "(translation_unit (declaration type: (primitive_type) declarator: (init_declarator declarator: (identifier) value: (number_literal))))"
+/// );
+/// ```
+pub fn to_sexp(node: &Node, byte_ranges: bool) -> String {
+    let mut sexp = String::new();
+    write_sexp(&mut node.cursor(), byte_ranges, &mut sexp);
+    sexp
+}
+
+/// Configuration options for printing the s-expression of a code.
+#[derive(Debug)]
+pub struct SexpCfg {
+    /// The path of the file of the code, printed in a comment
+    /// before its s-expression
+    pub path: PathBuf,
+    /// If `true`, each node is followed by the range of its bytes
+    pub byte_ranges: bool,
+}
+
+pub struct Sexp {
+    _guard: (),
+}
+
+impl Callback for Sexp {
+    type Res = std::io::Result<()>;
+    type Cfg = SexpCfg;
+
+    fn call<T: ParserTrait>(cfg: Self::Cfg, parser: &T) -> Self::Res {
+        let sexp = to_sexp(&parser.get_root(), cfg.byte_ranges);
+        // The s-expression of a file is printed at once,
+        // so the ones of the files analyzed in parallel are not mixed
+        println!("; {}\n{sexp}", cfg.path.display());
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::path::Path;
+
+    use pretty_assertions::assert_eq;
+
+    use crate::{JavascriptParser, PythonParser, RustParser};
+
+    use super::*;
+
+    #[test]
+    fn sexp_like_tree_sitter() {
+        let check = |root: Node| assert_eq!(to_sexp(&root, false), root.0.to_sexp());
+
+        let parser = RustParser::new(
+            b"fn foo(a: u32) -> u32 {\n    // bar\n    a + 1\n}\n".to_vec(),
+            Path::new("foo.rs"),
+            None,
+        );
+        check(parser.get_root());
+        // The missing nodes are printed too
+        let parser = JavascriptParser::new(b"let a = (1 + 2;".to_vec(), Path::new("foo.js"), None);
+        check(parser.get_root());
+        let parser =
+            PythonParser::new(b"def foo(:\n    pass\n".to_vec(), Path::new("foo.py"), None);
+        check(parser.get_root());
+    }
+
+    #[test]
+    fn sexp_byte_ranges() {
+        let parser = PythonParser::new(b"x = 1\n".to_vec(), Path::new("foo.py"), None);
+        assert_eq!(
+            to_sexp(&parser.get_root(), true),
+            "(module [0..6] (expression_statement [0..5] (assignment [0..5] left: (identifier [0..1]) right: (integer [4..5]))))"
+        );
+    }
+}
@@ -0,0 +1,294 @@
+use std::collections::{BTreeSet, HashMap};
+use std::sync::Arc;
+
+use arrow_array::{ArrayRef, Float64Array, RecordBatch, StringArray, UInt64Array};
+use arrow_schema::{ArrowError, DataType, Field, Schema};
+
+use crate::delta::metric_values;
+use crate::spaces::FuncSpace;
+
+// A function space flattened into a row
+struct Row<'a> {
+    file: Option<&'a str>,
+    space: &'a FuncSpace,
+    metrics: HashMap<String, f64>,
+}
+
+// Flattens the metrics of a space into `{metric}_{field}` columns
+fn flatten_metrics(space: &FuncSpace) -> HashMap<String, f64> {
+    metric_values(&space.metrics)
+        .into_iter()
+        .flat_map(|(metric, fields)| {
+            fields
+                .into_iter()
+                .map(move |(field, value)| (format!("{metric}_{field}"), value))
+        })
+        .collect()
+}
+
+fn flatten_space<'a>(file: Option<&'a str>, space: &'a FuncSpace, rows: &mut Vec<Row<'a>>) {
+    rows.push(Row {
+        file,
+        space,
+        metrics: flatten_metrics(space),
+    });
+    for subspace in &space.spaces {
+        flatten_space(file, subspace, rows);
+    }
+}
+
+/// Flattens the function spaces of a series of files into
+/// an Arrow record batch, with one row per space.
+///
+/// Each row contains the `file` the space belongs to, which is the name
+/// of the root space, the `id`, `name`, `qualified_name`, `kind`, `start_line`
+/// and `end_line` of the space, its `grade_cyclomatic` and `grade_mi`, followed
+/// by one column per metric value, named `{metric}_{field}` such as
+/// `cyclomatic_sum` or `loc_sloc`.
+/// A metric which is not computed for a space is null.
+///
+/// * `spaces` - The root function spaces, one for each file.
+///
+/// # Examples
+///
+/// ```
+/// use std::path::Path;
+///
+/// use rust_code_analysis::{LANG, get_function_spaces, spaces_to_record_batch};
+///
+/// let source_code = "def foo():\n    return 42\n";
+/// let path = Path::new("foo.py");
+///
+/// let space =
+///     get_function_spaces(&LANG::Python, source_code.as_bytes().to_vec(), path, None).unwrap();
+/// let batch = spaces_to_record_batch(&[space]).unwrap();
+///
+/// // The unit space and the function space
+/// assert_eq!(batch.num_rows(), 2);
+/// ```
+pub fn spaces_to_record_batch(spaces: &[FuncSpace]) -> Result<RecordBatch, ArrowError> {
+    let mut rows = Vec::new();
+    for space in spaces {
+        flatten_space(space.name.as_deref(), space, &mut rows);
+    }
+
+    let metric_names: BTreeSet<&str> = rows
+        .iter()
+        .flat_map(|row| row.metrics.keys().map(String::as_str))
+        .collect();
+
+    let mut fields = vec![
+        Field::new("file", DataType::Utf8, true),
+        Field::new("id", DataType::Utf8, false),
+        Field::new("name", DataType::Utf8, true),
+        Field::new("qualified_name", DataType::Utf8, true),
+        Field::new("kind", DataType::Utf8, false),
+        Field::new("start_line", DataType::UInt64, false),
+        Field::new("end_line", DataType::UInt64, false),
+        Field::new("grade_cyclomatic", DataType::Utf8, false),
+        Field::new("grade_mi", DataType::Utf8, false),
+    ];
+    let mut columns: Vec<ArrayRef> = vec![
+        Arc::new(StringArray::from_iter(rows.iter().map(|row| row.file))),
+        Arc::new(StringArray::from_iter_values(
+            rows.iter().map(|row| row.space.id.as_str()),
+        )),
+        Arc::new(StringArray::from_iter(
+            rows.iter().map(|row| row.space.name.as_deref()),
+        )),
+        Arc::new(StringArray::from_iter(
+            rows.iter().map(|row| row.space.qualified_name.as_deref()),
+        )),
+        Arc::new(StringArray::from_iter_values(
+            rows.iter().map(|row| row.space.kind.to_string()),
+        )),
+        Arc::new(UInt64Array::from_iter_values(
+            rows.iter().map(|row| row.space.start_line as u64),
+        )),
+        Arc::new(UInt64Array::from_iter_values(
+            rows.iter().map(|row| row.space.end_line as u64),
+        )),
+        Arc::new(StringArray::from_iter_values(
+            rows.iter()
+                .map(|row| row.space.grades.cyclomatic.to_string()),
+        )),
+        Arc::new(StringArray::from_iter_values(
+            rows.iter().map(|row| row.space.grades.mi.to_string()),
+        )),
+    ];
+
+    for name in metric_names {
+        fields.push(Field::new(name, DataType::Float64, true));
+        columns.push(Arc::new(Float64Array::from_iter(
+            rows.iter().map(|row| row.metrics.get(name).copied()),
+        )));
+    }
+
+    RecordBatch::try_new(Arc::new(Schema::new(fields)), columns)
+}
+
+/// Writes the function spaces of a series of files as an Arrow IPC stream,
+/// with one row per space.
+///
+/// The stream can be read without any conversion by Arrow-based
+/// libraries, such as `pyarrow` or `polars`.
+/// See [`spaces_to_record_batch`] for the description of the columns.
+///
+/// * `spaces` - The root function spaces, one for each file.
+/// * `writer` - The destination of the Arrow data.
+///
+/// # Examples
+///
+/// ```
+/// use std::path::Path;
+///
+/// use rust_code_analysis::{LANG, get_function_spaces, write_arrow_ipc};
+///
+/// let source_code = "def foo():\n    return 42\n";
+/// let path = Path::new("foo.py");
+///
+/// let space =
+///     get_function_spaces(&LANG::Python, source_code.as_bytes().to_vec(), path, None).unwrap();
+/// let mut data = Vec::new();
+/// write_arrow_ipc(&[space], &mut data).unwrap();
+/// ```
+///
+/// [`spaces_to_record_batch`]: fn.spaces_to_record_batch.html
+pub fn write_arrow_ipc<W: std::io::Write>(
+    spaces: &[FuncSpace],
+    writer: W,
+) -> Result<(), ArrowError> {
+    let batch = spaces_to_record_batch(spaces)?;
+    let mut writer = arrow_ipc::writer::StreamWriter::try_new(writer, &batch.schema())?;
+    writer.write(&batch)?;
+    writer.finish()
+}
+
+/// Writes the function spaces of a series of files as a Parquet file,
+/// with one row per space.
+///
+/// See [`spaces_to_record_batch`] for the description of the columns.
+///
+/// * `spaces` - The root function spaces, one for each file.
+/// * `writer` - The destination of the Parquet data.
+///
+/// # Examples
+///
+/// ```no_run
+/// use std::fs::File;
+/// use std::path::Path;
+///
+/// use rust_code_analysis::{LANG, get_function_spaces, write_parquet};
+///
+/// let source_code = "def foo():\n    return 42\n";
+/// let path = Path::new("foo.py");
+///
+/// let space =
+///     get_function_spaces(&LANG::Python, source_code.as_bytes().to_vec(), path, None).unwrap();
+/// write_parquet(&[space], File::create("metrics.parquet").unwrap()).unwrap();
+/// ```
+///
+/// [`spaces_to_record_batch`]: fn.spaces_to_record_batch.html
+#[cfg(feature = "parquet")]
+pub fn write_parquet<W: std::io::Write + Send>(
+    spaces: &[FuncSpace],
+    writer: W,
+) -> Result<(), parquet::errors::ParquetError> {
+    use parquet::arrow::ArrowWriter;
+    use parquet::basic::Compression;
+    use parquet::file::properties::WriterProperties;
+
+    let batch = spaces_to_record_batch(spaces)?;
+    let props = WriterProperties::builder()
+        .set_compression(Compression::SNAPPY)
+        .build();
+    let mut writer = ArrowWriter::try_new(writer, batch.schema(), Some(props))?;
+    writer.write(&batch)?;
+    writer.close()?;
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use std::path::Path;
+
+    use arrow_array::Array;
+    use pretty_assertions::assert_eq;
+
+    use super::*;
+    use crate::langs::{LANG, get_function_spaces};
+
+    fn space(source: &str, path: &str) -> FuncSpace {
+        get_function_spaces(
+            &LANG::Python,
+            source.as_bytes().to_vec(),
+            Path::new(path),
+            None,
+        )
+        .unwrap()
+    }
+
+    #[test]
+    fn test_spaces_to_record_batch() {
+        let spaces = [
+            space("def foo(a):\n    if a:\n        return 1\n", "foo.py"),
+            space("b = 2\n", "bar.py"),
+        ];
+        let batch = spaces_to_record_batch(&spaces).unwrap();
+
+        assert_eq!(batch.num_rows(), 3);
+
+        let column = |name: &str| batch.column_by_name(name).unwrap().clone();
+        let files = column("file");
+        let files = files.as_any().downcast_ref::<StringArray>().unwrap();
+        assert_eq!(
+            files.iter().collect::<Vec<_>>(),
+            vec![Some("foo.py"), Some("foo.py"), Some("bar.py")]
+        );
+
+        let kinds = column("kind");
+        let kinds = kinds.as_any().downcast_ref::<StringArray>().unwrap();
+        assert_eq!(
+            kinds.iter().collect::<Vec<_>>(),
+            vec![Some("unit"), Some("function"), Some("unit")]
+        );
+
+        let cyclomatic = column("cyclomatic_sum");
+        let cyclomatic = cyclomatic.as_any().downcast_ref::<Float64Array>().unwrap();
+        assert_eq!(cyclomatic.value(1), 2.);
+        assert_eq!(cyclomatic.null_count(), 0);
+    }
+
+    #[test]
+    fn test_write_arrow_ipc() {
+        let spaces = [space("def foo():\n    return 42\n", "foo.py")];
+        let mut data = Vec::new();
+        write_arrow_ipc(&spaces, &mut data).unwrap();
+
+        let reader = arrow_ipc::reader::StreamReader::try_new(data.as_slice(), None).unwrap();
+        let batches: Vec<_> = reader.map(Result::unwrap).collect();
+
+        assert_eq!(batches.len(), 1);
+        assert_eq!(batches[0], spaces_to_record_batch(&spaces).unwrap());
+    }
+
+    #[cfg(feature = "parquet")]
+    #[test]
+    fn test_write_parquet() {
+        use parquet::arrow::arrow_reader::ParquetRecordBatchReaderBuilder;
+
+        let spaces = [space("def foo():\n    return 42\n", "foo.py")];
+        let path = std::env::temp_dir().join("test_write_parquet.parquet");
+        write_parquet(&spaces, std::fs::File::create(&path).unwrap()).unwrap();
+
+        let reader = ParquetRecordBatchReaderBuilder::try_new(std::fs::File::open(&path).unwrap())
+            .unwrap()
+            .build()
+            .unwrap();
+        let batches: Vec<_> = reader.map(Result::unwrap).collect();
+
+        assert_eq!(batches.len(), 1);
+        assert_eq!(batches[0], spaces_to_record_batch(&spaces).unwrap());
+    }
+}
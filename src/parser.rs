@@ -3,23 +3,34 @@ use std::path::Path;
 use std::sync::Arc;
 
 use crate::abc::Abc;
+use crate::accessors::Accessors;
+use crate::annotations::Annotations;
+use crate::assertions::Assertions;
+use crate::callbacks::Callbacks;
 use crate::checker::Checker;
 use crate::cognitive::Cognitive;
 use crate::cyclomatic::Cyclomatic;
 use crate::exit::Exit;
 use crate::halstead::Halstead;
+use crate::kotlin_funcs::KotlinFuncs;
 use crate::loc::Loc;
+use crate::locals::Locals;
+use crate::macro_calls::MacroCalls;
 use crate::mi::Mi;
 use crate::nargs::NArgs;
 use crate::nom::Nom;
 use crate::npa::Npa;
 use crate::npm::Npm;
+use crate::ppc::Ppc;
+use crate::unsafety::Unsafety;
 use crate::wmc::Wmc;
 
 use crate::alterator::Alterator;
+use crate::api::Exports;
 use crate::getter::Getter;
 
 use crate::c_macro;
+use crate::encoding::{LineEnding, normalize_line_endings};
 use crate::langs::*;
 use crate::node::{Node, Tree};
 use crate::preproc::{PreprocResults, get_macros};
@@ -35,16 +46,27 @@ pub struct Parser<
         + Cognitive
         + Cyclomatic
         + Exit
+        + Exports
         + Halstead
         + Loc
+        + MacroCalls
         + Mi
         + NArgs
         + Nom
         + Npa
         + Npm
+        + Ppc
+        + Unsafety
+        + Callbacks
+        + Accessors
+        + Assertions
+        + Locals
+        + Annotations
+        + KotlinFuncs
         + Wmc,
 > {
     code: Vec<u8>,
+    line_ending: LineEnding,
     tree: Tree,
     phantom: PhantomData<T>,
 }
@@ -104,13 +126,23 @@ impl<
         + Cognitive
         + Cyclomatic
         + Exit
+        + Exports
         + Halstead
         + Loc
+        + MacroCalls
         + Mi
         + NArgs
         + Nom
         + Npa
         + Npm
+        + Ppc
+        + Unsafety
+        + Callbacks
+        + Accessors
+        + Assertions
+        + Locals
+        + Annotations
+        + KotlinFuncs
         + Wmc,
 > ParserTrait for Parser<T>
 {
@@ -128,8 +160,24 @@ impl<
     type Abc = T;
     type Npm = T;
     type Npa = T;
+    type Ppc = T;
+    type Unsafety = T;
+    type Callbacks = T;
+    type Accessors = T;
+    type Assertions = T;
+    type Locals = T;
+    type Annotations = T;
+    type KotlinFuncs = T;
+    type MacroCalls = T;
+    type Exports = T;
 
     fn new(code: Vec<u8>, path: &Path, pr: Option<Arc<PreprocResults>>) -> Self {
+        let _span =
+            tracing::debug_span!("parse", language = T::get_lang_name(), bytes = code.len())
+                .entered();
+
+        let line_ending = LineEnding::detect(&code);
+        let code = normalize_line_endings(code);
         let fake_code = get_fake_code::<T>(&code, path, pr);
         let code = if let Some(fake) = fake_code {
             fake
@@ -141,6 +189,7 @@ impl<
 
         Self {
             code,
+            line_ending,
             tree,
             phantom: PhantomData,
         }
@@ -151,6 +200,11 @@ impl<
         T::get_lang()
     }
 
+    #[inline(always)]
+    fn get_line_ending(&self) -> LineEnding {
+        self.line_ending
+    }
+
     #[inline(always)]
     fn get_root(&self) -> Node<'_> {
         self.tree.get_root()
@@ -17,11 +17,14 @@ use crate::traits::*;
 #[derive(Debug, Default, Deserialize, Serialize)]
 pub struct PreprocFile {
     /// The set of include directives explicitly written in a file
+    #[serde(serialize_with = "serialize_sorted_set")]
     pub direct_includes: HashSet<String>,
     /// The set of include directives implicitly imported in a file
     /// from other files
+    #[serde(serialize_with = "serialize_sorted_set")]
     pub indirect_includes: HashSet<String>,
     /// The set of macros of a file
+    #[serde(serialize_with = "serialize_sorted_set")]
     pub macros: HashSet<String>,
 }
 
@@ -29,6 +32,7 @@ pub struct PreprocFile {
 #[derive(Debug, Default, Deserialize, Serialize)]
 pub struct PreprocResults {
     /// The preprocessor data of each `C/C++` file
+    #[serde(serialize_with = "serialize_sorted_map")]
     pub files: HashMap<PathBuf, PreprocFile>,
 }
 
@@ -241,3 +245,28 @@ pub fn preprocess(parser: &PreprocParser, path: &Path, results: &mut PreprocResu
 
     results.files.insert(path.to_path_buf(), file_result);
 }
+
+#[cfg(test)]
+mod tests {
+    use pretty_assertions::assert_eq;
+
+    use super::*;
+
+    #[test]
+    fn preproc_sorted_serialization() {
+        let mut results = PreprocResults::default();
+        for name in ["b.h", "c.h", "a.h"] {
+            results.files.insert(
+                PathBuf::from(name),
+                PreprocFile::new_macros(&["Z", "A", "M"]),
+            );
+        }
+
+        // The files and the macros are serialized in order
+        let file = r#"{"direct_includes":[],"indirect_includes":[],"macros":["A","M","Z"]}"#;
+        assert_eq!(
+            serde_json::to_string(&results).unwrap(),
+            format!(r#"{{"files":{{"a.h":{file},"b.h":{file},"c.h":{file}}}}}"#)
+        );
+    }
+}
@@ -0,0 +1,233 @@
+use regex::Regex;
+
+use crate::delta::metric_values;
+use crate::describe::describe_metric_field;
+use crate::spaces::{FuncSpace, SpaceKind};
+
+// The inclusive range of the values of a metric field
+#[derive(Clone, Debug)]
+struct MetricBound {
+    metric: &'static str,
+    field: &'static str,
+    min: Option<f64>,
+    max: Option<f64>,
+}
+
+/// A query selecting the spaces of a tree by their kind,
+/// their name and the values of their metrics.
+///
+/// A space matches the query when it satisfies all its conditions,
+/// so an empty query matches all the spaces.
+///
+/// # Examples
+///
+/// ```
+/// use std::path::Path;
+///
+/// use rust_code_analysis::{LANG, SpaceKind, SpaceQuery, get_function_spaces};
+///
+/// let source_code = "def handler_a(x):\n    return x or 1\n\ndef handler_b():\n    pass\n";
+/// let path = Path::new("foo.py");
+///
+/// let space =
+///     get_function_spaces(&LANG::Python, source_code.as_bytes().to_vec(), path, None).unwrap();
+/// let query = SpaceQuery::new()
+///     .with_kind(SpaceKind::Function)
+///     .with_name("^handler_")
+///     .unwrap()
+///     .with_bound("cyclomatic", Some(2.), None)
+///     .unwrap();
+///
+/// let spaces = query.select(&space);
+/// assert_eq!(spaces.len(), 1);
+/// assert_eq!(spaces[0].name.as_deref(), Some("handler_a"));
+/// ```
+#[derive(Clone, Debug, Default)]
+pub struct SpaceQuery {
+    kind: Option<SpaceKind>,
+    name: Option<Regex>,
+    bounds: Vec<MetricBound>,
+}
+
+impl SpaceQuery {
+    /// Creates a query matching all the spaces.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Keeps only the spaces of a kind.
+    pub fn with_kind(mut self, kind: SpaceKind) -> Self {
+        self.kind = Some(kind);
+        self
+    }
+
+    /// Keeps only the spaces whose name matches a regular expression.
+    ///
+    /// The expression can match any part of the name,
+    /// so it must be anchored to match the whole name.
+    pub fn with_name(mut self, pattern: &str) -> Result<Self, regex::Error> {
+        self.name = Some(Regex::new(pattern)?);
+        Ok(self)
+    }
+
+    /// Keeps only the spaces whose value of a metric is between
+    /// `min` and `max`, both included.
+    ///
+    /// The metric is named as in the serialized metrics, optionally
+    /// followed by one of its fields, such as `cyclomatic` or `loc.sloc`.
+    /// Without a field, the `sum` field is used, or the first field
+    /// of the metrics without one.
+    ///
+    /// Returns `None` if the metric or the field does not exist.
+    pub fn with_bound(mut self, metric: &str, min: Option<f64>, max: Option<f64>) -> Option<Self> {
+        let (metric, field) = describe_metric_field(metric)?;
+        self.bounds.push(MetricBound {
+            metric: metric.key,
+            field,
+            min,
+            max,
+        });
+        Some(self)
+    }
+
+    /// Checks if a space matches the query, regardless of its nested spaces.
+    pub fn matches(&self, space: &FuncSpace) -> bool {
+        if self.kind.is_some_and(|kind| space.kind != kind)
+            || self.name.as_ref().is_some_and(|name| {
+                !space
+                    .name
+                    .as_deref()
+                    .is_some_and(|space_name| name.is_match(space_name))
+            })
+        {
+            return false;
+        }
+        if self.bounds.is_empty() {
+            return true;
+        }
+
+        let values = metric_values(&space.metrics);
+        self.bounds.iter().all(|bound| {
+            // The spaces without a value for the metric never match
+            values
+                .get(bound.metric)
+                .and_then(|fields| fields.get(bound.field))
+                .is_some_and(|value| {
+                    bound.min.is_none_or(|min| *value >= min)
+                        && bound.max.is_none_or(|max| *value <= max)
+                })
+        })
+    }
+
+    /// Returns the spaces matching the query among a space and all its
+    /// nested spaces, depth first in the order of the code.
+    pub fn select<'a>(&self, space: &'a FuncSpace) -> Vec<&'a FuncSpace> {
+        let mut spaces = Vec::new();
+        self.collect(space, &mut spaces);
+        spaces
+    }
+
+    fn collect<'a>(&self, space: &'a FuncSpace, spaces: &mut Vec<&'a FuncSpace>) {
+        if self.matches(space) {
+            spaces.push(space);
+        }
+        for subspace in &space.spaces {
+            self.collect(subspace, spaces);
+        }
+    }
+}
+
+/// Returns the value of a metric of a space, named as in
+/// [`SpaceQuery::with_bound`], such as `cyclomatic` or `loc.sloc`.
+///
+/// Returns `None` if the metric or the field does not exist,
+/// or if the space has no finite value for it.
+///
+/// # Examples
+///
+/// ```
+/// use std::path::Path;
+///
+/// use rust_code_analysis::{LANG, get_function_spaces, metric_value};
+///
+/// let source_code = "def foo(x):\n    return x or 1\n";
+/// let path = Path::new("foo.py");
+///
+/// let space =
+///     get_function_spaces(&LANG::Python, source_code.as_bytes().to_vec(), path, None).unwrap();
+///
+/// assert_eq!(metric_value(&space.spaces[0], "loc.sloc"), Some(2.));
+/// ```
+///
+/// [`SpaceQuery::with_bound`]: struct.SpaceQuery.html#method.with_bound
+pub fn metric_value(space: &FuncSpace, metric: &str) -> Option<f64> {
+    let (metric, field) = describe_metric_field(metric)?;
+    metric_values(&space.metrics)
+        .get(metric.key)?
+        .get(field)
+        .copied()
+}
+
+#[cfg(test)]
+mod tests {
+    use std::path::Path;
+
+    use pretty_assertions::assert_eq;
+
+    use crate::{LANG, get_function_spaces};
+
+    use super::*;
+
+    fn names(spaces: &[&FuncSpace]) -> Vec<String> {
+        spaces
+            .iter()
+            .map(|space| space.name.clone().unwrap_or_default())
+            .collect()
+    }
+
+    #[test]
+    fn query_spaces() {
+        let source_code = "class Handler:\n    def handle(self, a, b):\n        if a and b:\n            return 1\n        return 2\n\ndef handler_x(a):\n    return a\n";
+        let space = get_function_spaces(
+            &LANG::Python,
+            source_code.as_bytes().to_vec(),
+            Path::new("foo.py"),
+            None,
+        )
+        .unwrap();
+
+        assert_eq!(SpaceQuery::new().select(&space).len(), 4);
+        let query = SpaceQuery::new().with_kind(SpaceKind::Function);
+        assert_eq!(names(&query.select(&space)), ["handle", "handler_x"]);
+        let query = SpaceQuery::new().with_name("(?i)^handle").unwrap();
+        assert_eq!(
+            names(&query.select(&space)),
+            ["Handler", "handle", "handler_x"]
+        );
+
+        // The bounds are included
+        let query = SpaceQuery::new()
+            .with_kind(SpaceKind::Function)
+            .with_bound("cyclomatic", Some(3.), None)
+            .unwrap();
+        assert_eq!(names(&query.select(&space)), ["handle"]);
+        let query = SpaceQuery::new()
+            .with_bound("loc.sloc", None, Some(2.))
+            .unwrap()
+            .with_name("_")
+            .unwrap();
+        assert_eq!(names(&query.select(&space)), ["handler_x"]);
+
+        assert_eq!(metric_value(&space, "cyclomatic"), Some(6.));
+        assert_eq!(metric_value(&space.spaces[1], "nargs.total"), Some(1.));
+        assert_eq!(metric_value(&space, "foo"), None);
+
+        assert!(SpaceQuery::new().with_name("(").is_err());
+        assert!(SpaceQuery::new().with_bound("foo", None, None).is_none());
+        assert!(
+            SpaceQuery::new()
+                .with_bound("loc.foo", None, None)
+                .is_none()
+        );
+    }
+}
@@ -0,0 +1,134 @@
+use std::path::Path;
+
+use crate::langs::get_function_spaces;
+use crate::spaces::FuncSpace;
+use crate::tools::guess_language;
+
+fn overlaps(space: &FuncSpace, start_line: usize, end_line: usize) -> bool {
+    space.start_line <= end_line && start_line <= space.end_line
+}
+
+// Removes the subspaces which do not overlap the lines, the subspaces
+// of a space outside the lines being outside of them too
+fn retain_lines(space: &mut FuncSpace, start_line: usize, end_line: usize) -> bool {
+    if !overlaps(space, start_line, end_line) {
+        return false;
+    }
+    space
+        .spaces
+        .retain_mut(|subspace| retain_lines(subspace, start_line, end_line));
+    true
+}
+
+/// Removes the subspaces of a space which do not overlap the lines
+/// from `start_line` to `end_line`, both included.
+///
+/// The space itself is always kept, and the kept subspaces keep
+/// the metrics of all their code.
+///
+/// # Examples
+///
+/// ```
+/// use std::path::Path;
+///
+/// use rust_code_analysis::{LANG, filter_by_range, get_function_spaces};
+///
+/// let source_code = "def foo():\n    return 1\n\ndef bar():\n    return 2\n";
+/// let path = Path::new("foo.py");
+///
+/// let mut space =
+///     get_function_spaces(&LANG::Python, source_code.as_bytes().to_vec(), path, None).unwrap();
+/// filter_by_range(&mut space, 4, 5);
+///
+/// assert_eq!(space.spaces.len(), 1);
+/// assert_eq!(space.spaces[0].name.as_deref(), Some("bar"));
+/// ```
+pub fn filter_by_range(space: &mut FuncSpace, start_line: usize, end_line: usize) {
+    space
+        .spaces
+        .retain_mut(|subspace| retain_lines(subspace, start_line, end_line));
+}
+
+/// Analyzes the spaces of a code overlapping the lines from `start_line`
+/// to `end_line`, both included, such as the lines selected in an editor.
+///
+/// The whole code is parsed, so that the spaces are analyzed in their
+/// context, and the spaces which do not overlap the lines are removed
+/// as in [`filter_by_range`].
+///
+/// Returns `None` if the language of the code cannot be guessed
+/// or if the code cannot be analyzed.
+///
+/// # Examples
+///
+/// ```
+/// use std::path::Path;
+///
+/// use rust_code_analysis::analyze_range;
+///
+/// let source_code = "def foo():\n    return 1\n\ndef bar():\n    return 2\n";
+///
+/// let space = analyze_range(source_code.as_bytes().to_vec(), Path::new("foo.py"), 1, 2).unwrap();
+///
+/// assert_eq!(space.spaces[0].name.as_deref(), Some("foo"));
+/// ```
+///
+/// [`filter_by_range`]: fn.filter_by_range.html
+pub fn analyze_range(
+    source: Vec<u8>,
+    path: &Path,
+    start_line: usize,
+    end_line: usize,
+) -> Option<FuncSpace> {
+    let language = guess_language(&source, path).0?;
+    let mut space = get_function_spaces(&language, source, path, None)?;
+    filter_by_range(&mut space, start_line, end_line);
+    Some(space)
+}
+
+#[cfg(test)]
+mod tests {
+    use pretty_assertions::assert_eq;
+
+    use super::*;
+
+    fn range_names(source: &str, start_line: usize, end_line: usize) -> Vec<String> {
+        fn names(space: &FuncSpace, acc: &mut Vec<String>) {
+            for subspace in &space.spaces {
+                acc.push(subspace.name.clone().unwrap_or_default());
+                names(subspace, acc);
+            }
+        }
+
+        let space = analyze_range(
+            source.as_bytes().to_vec(),
+            Path::new("foo.rs"),
+            start_line,
+            end_line,
+        )
+        .unwrap();
+        let mut acc = Vec::new();
+        names(&space, &mut acc);
+        acc
+    }
+
+    #[test]
+    fn rust_range() {
+        let source = "fn foo() {
+    let a = 1;
+}
+
+impl Bar {
+    fn bar(&self) {
+        let b = 2;
+    }
+
+    fn baz(&self) {}
+}
+";
+        assert_eq!(range_names(source, 2, 2), ["foo"]);
+        assert_eq!(range_names(source, 3, 6), ["foo", "Bar", "bar"]);
+        assert_eq!(range_names(source, 10, 10), ["Bar", "baz"]);
+        assert_eq!(range_names(source, 4, 4), Vec::<String>::new());
+    }
+}
@@ -0,0 +1,299 @@
+use std::collections::HashSet;
+use std::fmt::Write;
+
+use serde::Serialize;
+
+use crate::delta::{FuncSpaceDelta, SpaceChange, metric_values};
+use crate::diff::FileDiff;
+use crate::rules::Rule;
+use crate::spaces::{FuncSpace, SpaceKind};
+
+/// A review comment on a function whose metrics have been changed by a diff.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize)]
+pub struct ReviewComment {
+    /// The path of the new file
+    pub path: String,
+    /// The path of the old file, which is the new one if the file
+    /// has been added
+    pub old_path: String,
+    /// The line of the new file commented, which is shown in the diff
+    pub line: usize,
+    /// The body of the comment, in Markdown
+    pub body: String,
+}
+
+/// A review comment in the shape expected by the GitHub API
+/// creating the reviews of a pull request.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize)]
+pub struct GitHubReviewComment {
+    /// The path of the file
+    pub path: String,
+    /// The line of the file
+    pub line: usize,
+    /// The side of the diff, which is always `RIGHT`, that is the new file
+    pub side: &'static str,
+    /// The body of the comment
+    pub body: String,
+}
+
+/// The position of a GitLab discussion on a line of a diff.
+///
+/// The `base_sha`, `start_sha` and `head_sha` fields required by GitLab
+/// are the `diff_refs` of the merge request, which are not known here.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize)]
+pub struct GitLabPosition {
+    /// The type of the position, which is always `text`
+    pub position_type: &'static str,
+    /// The path of the old file
+    pub old_path: String,
+    /// The path of the new file
+    pub new_path: String,
+    /// The line of the new file
+    pub new_line: usize,
+}
+
+/// A review comment in the shape expected by the GitLab API
+/// creating the discussions of a merge request.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize)]
+pub struct GitLabDiscussion {
+    /// The body of the comment
+    pub body: String,
+    /// The position of the comment
+    pub position: GitLabPosition,
+}
+
+impl From<&ReviewComment> for GitHubReviewComment {
+    fn from(comment: &ReviewComment) -> Self {
+        Self {
+            path: comment.path.clone(),
+            line: comment.line,
+            side: "RIGHT",
+            body: comment.body.clone(),
+        }
+    }
+}
+
+impl From<&ReviewComment> for GitLabDiscussion {
+    fn from(comment: &ReviewComment) -> Self {
+        Self {
+            body: comment.body.clone(),
+            position: GitLabPosition {
+                position_type: "text",
+                old_path: comment.old_path.clone(),
+                new_path: comment.path.clone(),
+                new_line: comment.line,
+            },
+        }
+    }
+}
+
+// Writes the body of a comment, with a table of the changed metrics
+// followed by the thresholds exceeded by the new values
+fn comment_body(space: &FuncSpace, delta: &FuncSpaceDelta, rules: &[Rule]) -> Option<String> {
+    let values = metric_values(&space.metrics);
+    let mut rows = String::new();
+    let mut warnings = String::new();
+    // Some rules can check the same metric with different thresholds
+    let mut keys = HashSet::new();
+    for rule in rules {
+        let key = rule.key();
+        let Some(value) = values
+            .get(&rule.metric)
+            .and_then(|fields| fields.get(&rule.field))
+            .copied()
+        else {
+            continue;
+        };
+        let change = delta
+            .metrics
+            .get(&rule.metric, &rule.field)
+            .unwrap_or_default();
+        if change == 0. {
+            continue;
+        }
+
+        if keys.insert(key.clone()) {
+            let old = match delta.change {
+                SpaceChange::Added => "-".to_owned(),
+                _ => (value - change).to_string(),
+            };
+            writeln!(rows, "| {key} | {old} | {value} | {change:+} |").unwrap();
+        }
+        if change > 0. && value > rule.threshold {
+            writeln!(
+                warnings,
+                "\n:warning: The {key} of {value} is above the threshold of {}.",
+                rule.threshold
+            )
+            .unwrap();
+        }
+    }
+    if rows.is_empty() {
+        return None;
+    }
+
+    let name = space.name.as_deref().unwrap_or("<anonymous>");
+    let change = match delta.change {
+        SpaceChange::Added => "added",
+        _ => "modified",
+    };
+    Some(format!(
+        "The function `{name}` has been {change}:\n\n| Metric | Old | New | Delta |\n| --- | ---: | ---: | ---: |\n{rows}{warnings}"
+    ))
+}
+
+// Returns the first line of a function shown in the diff, if any
+fn commented_line(space: &FuncSpace, diff: &FileDiff) -> Option<usize> {
+    diff.hunks.iter().find_map(|hunk| {
+        let range = hunk.new_range();
+        let start = range.start.max(space.start_line);
+        (start < range.end && start <= space.end_line).then_some(start)
+    })
+}
+
+fn collect_comments(
+    space: &FuncSpace,
+    delta: &FuncSpaceDelta,
+    diff: &FileDiff,
+    rules: &[Rule],
+    comments: &mut Vec<ReviewComment>,
+) {
+    // The deltas of the removed subspaces follow the ones of the new subspaces
+    for (subspace, subdelta) in space.spaces.iter().zip(&delta.spaces) {
+        if subspace.kind == SpaceKind::Function
+            && matches!(subdelta.change, SpaceChange::Added | SpaceChange::Modified)
+            && let Some(line) = commented_line(subspace, diff)
+            && let Some(body) = comment_body(subspace, subdelta, rules)
+        {
+            let path = diff.new_path.clone().unwrap_or_default();
+            comments.push(ReviewComment {
+                old_path: diff.old_path.clone().unwrap_or_else(|| path.clone()),
+                path,
+                line,
+                body,
+            });
+        }
+        collect_comments(subspace, subdelta, diff, rules, comments);
+    }
+}
+
+/// Returns the review comments on the functions of a file whose metrics
+/// have been changed by a diff, in the order of the source code.
+///
+/// A function is commented on its first line shown in the diff,
+/// with the changes of the metrics checked by the rules.
+///
+/// * `diff` - The changes of the file.
+/// * `new` - The root function space of the new file.
+/// * `old` - The root function space of the old file,
+///   `None` if the file has been added.
+/// * `rules` - The rules whose metrics are reported.
+///
+/// # Examples
+///
+/// ```
+/// use std::path::Path;
+///
+/// use rust_code_analysis::{LANG, Rule, get_function_spaces, parse_diff, review_comments};
+///
+/// let diff = "--- a/foo.py\n+++ b/foo.py\n@@ -1,2 +1,4 @@\n def foo(a):\n+    if a:\n+        return 1\n     return 0\n";
+/// let new = "def foo(a):\n    if a:\n        return 1\n    return 0\n";
+///
+/// let diff = &parse_diff(diff)[0];
+/// let old = diff.old_source(new.as_bytes());
+/// let path = Path::new("foo.py");
+/// let new = get_function_spaces(&LANG::Python, new.as_bytes().to_vec(), path, None).unwrap();
+/// let old = get_function_spaces(&LANG::Python, old, path, None).unwrap();
+/// let comments = review_comments(diff, &new, Some(&old), &Rule::defaults());
+///
+/// assert_eq!(comments[0].line, 1);
+/// assert!(comments[0].body.contains("| cyclomatic.sum | 1 | 2 | +1 |"));
+/// ```
+pub fn review_comments(
+    diff: &FileDiff,
+    new: &FuncSpace,
+    old: Option<&FuncSpace>,
+    rules: &[Rule],
+) -> Vec<ReviewComment> {
+    let delta = match old {
+        Some(old) => new.diff(old),
+        None => FuncSpaceDelta::added(new),
+    };
+    let mut comments = Vec::new();
+    collect_comments(new, &delta, diff, rules, &mut comments);
+    comments
+}
+
+#[cfg(test)]
+mod tests {
+    use std::path::Path;
+
+    use super::*;
+    use crate::diff::parse_diff;
+    use crate::langs::{LANG, get_function_spaces};
+
+    fn space(source: &[u8]) -> FuncSpace {
+        get_function_spaces(&LANG::Python, source.to_vec(), Path::new("foo.py"), None).unwrap()
+    }
+
+    #[test]
+    fn review_modified_function() {
+        let branches: String = (0..10)
+            .map(|i| format!("+    if a == {i}:\n+        a += 1\n"))
+            .collect();
+        let diff = format!(
+            "--- a/foo.py\n+++ b/foo.py\n@@ -1,5 +1,25 @@\n def foo(a):\n{branches}     return a\n \n def bar():\n     pass\n"
+        );
+        let new: String = (0..10)
+            .map(|i| format!("    if a == {i}:\n        a += 1\n"))
+            .collect();
+        let new = format!("def foo(a):\n{new}    return a\n\ndef bar():\n    pass\n");
+
+        let diff = &parse_diff(&diff)[0];
+        let old = space(&diff.old_source(new.as_bytes()));
+        let new = space(new.as_bytes());
+
+        let comments = review_comments(diff, &new, Some(&old), &Rule::defaults());
+        assert_eq!(comments.len(), 1);
+        insta::assert_snapshot!(comments[0].body, @r###"
+        The function `foo` has been modified:
+        
+        | Metric | Old | New | Delta |
+        | --- | ---: | ---: | ---: |
+        | cognitive.sum | 0 | 10 | +10 |
+        | cyclomatic.sum | 1 | 11 | +10 |
+        | loc.sloc | 2 | 22 | +20 |
+        
+        :warning: The cyclomatic.sum of 11 is above the threshold of 10.
+        "###);
+        insta::assert_json_snapshot!(GitLabDiscussion::from(&comments[0]).position, @r###"
+        {
+          "position_type": "text",
+          "old_path": "foo.py",
+          "new_path": "foo.py",
+          "new_line": 1
+        }
+        "###);
+    }
+
+    #[test]
+    fn review_added_file() {
+        let diff = "--- /dev/null\n+++ b/foo.py\n@@ -0,0 +1,2 @@\n+def foo(a, b, c, d, e, f, g, h):\n+    return a\n";
+        let new = space(b"def foo(a, b, c, d, e, f, g, h):\n    return a\n");
+
+        let comments = review_comments(&parse_diff(diff)[0], &new, None, &Rule::defaults());
+        insta::assert_json_snapshot!(
+            comments.iter().map(GitHubReviewComment::from).collect::<Vec<_>>(),
+            @r###"
+            [
+              {
+                "path": "foo.py",
+                "line": 1,
+                "side": "RIGHT",
+                "body": "The function `foo` has been added:\n\n| Metric | Old | New | Delta |\n| --- | ---: | ---: | ---: |\n| cyclomatic.sum | - | 1 | +1 |\n| nargs.total_functions | - | 8 | +8 |\n| nexits.sum | - | 1 | +1 |\n| loc.sloc | - | 2 | +2 |\n\n:warning: The nargs.total_functions of 8 is above the threshold of 7.\n"
+              }
+            ]
+            "###
+        );
+    }
+}
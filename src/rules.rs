@@ -0,0 +1,286 @@
+use std::fmt;
+
+use serde::{Deserialize, Serialize};
+
+use crate::delta::metric_values;
+use crate::spaces::{FNV_OFFSET_BASIS, FuncSpace, SpaceKind, fnv1a};
+
+/// The severity of a finding, from the least to the most severe one,
+/// named as in SonarQube.
+#[derive(
+    Clone, Copy, Debug, Default, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize,
+)]
+#[serde(rename_all = "lowercase")]
+pub enum Severity {
+    Info,
+    Minor,
+    #[default]
+    Major,
+    Critical,
+    Blocker,
+}
+
+impl fmt::Display for Severity {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let name = match self {
+            Self::Info => "info",
+            Self::Minor => "minor",
+            Self::Major => "major",
+            Self::Critical => "critical",
+            Self::Blocker => "blocker",
+        };
+        write!(f, "{name}")
+    }
+}
+
+/// A rule flagging the functions whose value of a metric
+/// is above a threshold.
+///
+/// The metric and its field are named as in the serialized metrics,
+/// such as `cyclomatic` and `sum`.
+/// The remediation cost of a violation is `remediation` minutes plus
+/// `remediation_per_unit` minutes for each unit above the threshold,
+/// as in the linear with offset functions of SonarQube.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct Rule {
+    /// The metric, such as `cyclomatic`
+    pub metric: String,
+    /// The field of the metric, such as `sum`
+    pub field: String,
+    /// The highest value allowed
+    pub threshold: f64,
+    /// The severity of the violations
+    #[serde(default)]
+    pub severity: Severity,
+    /// The minutes needed to fix a violation
+    #[serde(default)]
+    pub remediation: f64,
+    /// The additional minutes needed to fix a violation
+    /// for each unit above the threshold
+    #[serde(default)]
+    pub remediation_per_unit: f64,
+}
+
+impl Rule {
+    /// Returns the key of the rule, such as `cyclomatic.sum`.
+    pub fn key(&self) -> String {
+        format!("{}.{}", self.metric, self.field)
+    }
+
+    /// Returns the default rules, whose thresholds and remediation costs
+    /// follow the ones of the SonarQube rules on the size and the complexity
+    /// of the functions.
+    pub fn defaults() -> Vec<Rule> {
+        let rule = |metric: &str, field: &str, threshold, severity, remediation, per_unit| Rule {
+            metric: metric.to_owned(),
+            field: field.to_owned(),
+            threshold,
+            severity,
+            remediation,
+            remediation_per_unit: per_unit,
+        };
+        vec![
+            rule("cognitive", "sum", 15., Severity::Critical, 5., 1.),
+            rule("cyclomatic", "sum", 10., Severity::Critical, 10., 1.),
+            rule("nargs", "total_functions", 7., Severity::Major, 20., 0.),
+            rule("nexits", "sum", 3., Severity::Major, 20., 0.),
+            rule("loc", "sloc", 100., Severity::Major, 20., 0.),
+        ]
+    }
+}
+
+/// A function violating a rule.
+#[derive(Clone, Debug, PartialEq, Serialize)]
+pub struct Finding {
+    /// The key of the violated rule, such as `cyclomatic.sum`
+    pub rule: String,
+    /// The severity of the violation
+    pub severity: Severity,
+    /// The path of the file containing the function
+    pub path: String,
+    /// The qualified name of the function
+    pub name: Option<String>,
+    /// The identifier of the function
+    pub id: String,
+    /// The first line of the function
+    pub start_line: usize,
+    /// The last line of the function
+    pub end_line: usize,
+    /// The value of the metric
+    pub value: f64,
+    /// The threshold of the rule
+    pub threshold: f64,
+    /// The minutes needed to fix the violation
+    pub remediation: f64,
+}
+
+impl Finding {
+    /// Returns a message describing the violation.
+    pub fn message(&self) -> String {
+        format!(
+            "Function `{}` has a {} of {}, {} the threshold of {}",
+            self.name.as_deref().unwrap_or("<anonymous>"),
+            self.rule,
+            self.value,
+            if self.value < self.threshold {
+                "below"
+            } else {
+                "above"
+            },
+            self.threshold
+        )
+    }
+
+    /// Returns an identifier of the violation, which is stable across runs
+    /// and does not change when the function is moved in its file.
+    pub fn fingerprint(&self) -> String {
+        let mut hash = FNV_OFFSET_BASIS;
+        for part in [self.rule.as_str(), self.path.as_str(), self.id.as_str()] {
+            hash = fnv1a(hash, part.as_bytes());
+            hash = fnv1a(hash, &[0]);
+        }
+        format!("{hash:016x}")
+    }
+}
+
+fn collect_findings(space: &FuncSpace, path: &str, rules: &[Rule], findings: &mut Vec<Finding>) {
+    for subspace in &space.spaces {
+        if subspace.kind == SpaceKind::Function {
+            let values = metric_values(&subspace.metrics);
+            for rule in rules {
+                let Some(value) = values
+                    .get(&rule.metric)
+                    .and_then(|fields| fields.get(&rule.field))
+                    .copied()
+                else {
+                    continue;
+                };
+                if value > rule.threshold {
+                    findings.push(Finding {
+                        rule: rule.key(),
+                        severity: rule.severity,
+                        path: path.to_owned(),
+                        name: subspace
+                            .qualified_name
+                            .clone()
+                            .or_else(|| subspace.name.clone()),
+                        id: subspace.id.clone(),
+                        start_line: subspace.start_line,
+                        end_line: subspace.end_line,
+                        value,
+                        threshold: rule.threshold,
+                        remediation: rule.remediation
+                            + rule.remediation_per_unit * (value - rule.threshold),
+                    });
+                }
+            }
+        }
+        collect_findings(subspace, path, rules, findings);
+    }
+}
+
+/// Returns the violations of some rules by the functions of a file,
+/// in the order of the source code.
+///
+/// * `space` - The root function space of a file.
+/// * `rules` - The rules to check.
+///
+/// # Examples
+///
+/// ```
+/// use std::path::Path;
+///
+/// use rust_code_analysis::{LANG, Rule, check_rules, get_function_spaces};
+///
+/// let source_code = "def foo(a, b, c, d, e, f, g, h):\n    return a\n";
+/// let path = Path::new("foo.py");
+///
+/// let space =
+///     get_function_spaces(&LANG::Python, source_code.as_bytes().to_vec(), path, None).unwrap();
+/// let findings = check_rules(&space, &Rule::defaults());
+///
+/// assert_eq!(findings.len(), 1);
+/// assert_eq!(findings[0].rule, "nargs.total_functions");
+/// ```
+pub fn check_rules(space: &FuncSpace, rules: &[Rule]) -> Vec<Finding> {
+    let mut findings = Vec::new();
+    collect_findings(
+        space,
+        space.name.as_deref().unwrap_or_default(),
+        rules,
+        &mut findings,
+    );
+    findings
+}
+
+#[cfg(test)]
+mod tests {
+    use std::path::Path;
+
+    use super::*;
+    use crate::langs::{LANG, get_function_spaces};
+
+    #[test]
+    fn python_findings() {
+        let branches = (0..12)
+            .map(|i| format!("    if a == {i}:\n        b += {i}\n"))
+            .collect::<String>();
+        let source =
+            format!("def foo(a):\n    b = 0\n{branches}    return b\n\ndef bar():\n    pass\n");
+        let space = get_function_spaces(
+            &LANG::Python,
+            source.into_bytes(),
+            Path::new("foo.py"),
+            None,
+        )
+        .unwrap();
+
+        let findings = check_rules(&space, &Rule::defaults());
+        insta::assert_json_snapshot!(
+            findings,
+            { "[].id" => "[id]" },
+            @r###"
+            [
+              {
+                "rule": "cyclomatic.sum",
+                "severity": "critical",
+                "path": "foo.py",
+                "name": "foo.foo",
+                "id": "[id]",
+                "start_line": 1,
+                "end_line": 27,
+                "value": 13.0,
+                "threshold": 10.0,
+                "remediation": 13.0
+              }
+            ]
+            "###
+        );
+        assert_eq!(
+            findings[0].message(),
+            "Function `foo.foo` has a cyclomatic.sum of 13, above the threshold of 10"
+        );
+    }
+
+    #[test]
+    fn custom_rules() {
+        let rules: Vec<Rule> = serde_json::from_str(
+            r#"[{"metric": "loc", "field": "sloc", "threshold": 1, "severity": "minor", "remediation": 3}]"#,
+        )
+        .unwrap();
+        assert_eq!(rules[0].remediation_per_unit, 0.);
+
+        let space = get_function_spaces(
+            &LANG::Rust,
+            b"fn f() {\n    g();\n}\nfn h() {}\n".to_vec(),
+            Path::new("foo.rs"),
+            None,
+        )
+        .unwrap();
+        let findings = check_rules(&space, &rules);
+        assert_eq!(findings.len(), 1);
+        assert_eq!(findings[0].severity, Severity::Minor);
+        assert_eq!(findings[0].remediation, 3.);
+        assert_eq!(findings[0].name.as_deref(), Some("foo::f"));
+    }
+}
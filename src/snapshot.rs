@@ -0,0 +1,262 @@
+use std::collections::BTreeMap;
+use std::fmt;
+use std::io::{Read, Write};
+
+use serde::de::{MapAccess, Visitor};
+use serde::ser::{Error, SerializeMap};
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+use serde_json::value::RawValue;
+
+use crate::metadata::code_hash;
+use crate::spaces::FuncSpace;
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+struct SnapshotFile {
+    hash: String,
+    results: SnapshotResults,
+}
+
+/// The serialized results of a file of a snapshot.
+///
+/// The results are kept as the JSON written when they were computed,
+/// so they are serialized again, in any format, with the fields
+/// in the same order and with the same values of the numbers.
+#[derive(Clone, Debug, Deserialize)]
+#[serde(transparent)]
+pub struct SnapshotResults(Box<RawValue>);
+
+impl SnapshotResults {
+    /// Serializes the results of a file.
+    pub fn new<T: Serialize>(results: &T) -> serde_json::Result<Self> {
+        serde_json::value::to_raw_value(results).map(Self)
+    }
+
+    /// Returns the results as JSON.
+    pub fn json(&self) -> &str {
+        self.0.get()
+    }
+}
+
+impl PartialEq for SnapshotResults {
+    fn eq(&self, other: &Self) -> bool {
+        self.json() == other.json()
+    }
+}
+
+impl Serialize for SnapshotResults {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        Json(&self.0).serialize(serializer)
+    }
+}
+
+// A JSON value serialized again as it is written, without going through
+// a `serde_json::Value`, which sorts the fields of the objects and
+// does not parse the floats exactly
+struct Json<'a>(&'a RawValue);
+
+impl Serialize for Json<'_> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let json = self.0.get();
+        match json.as_bytes().first() {
+            Some(b'{') => {
+                let Fields(fields) = serde_json::from_str(json).map_err(S::Error::custom)?;
+                let mut map = serializer.serialize_map(Some(fields.len()))?;
+                for (key, value) in fields {
+                    map.serialize_entry(&key, &Json(value))?;
+                }
+                map.end()
+            }
+            Some(b'[') => {
+                let items: Vec<&RawValue> = serde_json::from_str(json).map_err(S::Error::custom)?;
+                serializer.collect_seq(items.into_iter().map(Json))
+            }
+            Some(b'"') => {
+                let string: String = serde_json::from_str(json).map_err(S::Error::custom)?;
+                serializer.serialize_str(&string)
+            }
+            Some(b't' | b'f') => serializer.serialize_bool(json == "true"),
+            Some(b'n') => serializer.serialize_none(),
+            _ => {
+                if let Ok(number) = json.parse::<u64>() {
+                    serializer.serialize_u64(number)
+                } else if let Ok(number) = json.parse::<i64>() {
+                    serializer.serialize_i64(number)
+                } else {
+                    serializer.serialize_f64(json.parse().map_err(S::Error::custom)?)
+                }
+            }
+        }
+    }
+}
+
+// The fields of a JSON object, in the order in which they are written
+struct Fields<'a>(Vec<(String, &'a RawValue)>);
+
+impl<'de> Deserialize<'de> for Fields<'de> {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        struct FieldsVisitor;
+
+        impl<'de> Visitor<'de> for FieldsVisitor {
+            type Value = Fields<'de>;
+
+            fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+                formatter.write_str("a JSON object")
+            }
+
+            fn visit_map<A: MapAccess<'de>>(self, mut map: A) -> Result<Self::Value, A::Error> {
+                let mut fields = Vec::new();
+                while let Some(field) = map.next_entry()? {
+                    fields.push(field);
+                }
+                Ok(Fields(fields))
+            }
+        }
+
+        deserializer.deserialize_map(FieldsVisitor)
+    }
+}
+
+/// The results of the analysis of some files, together with the hashes
+/// of their code.
+///
+/// A snapshot exported at the end of an analysis can be imported
+/// in the next one, such as in the next run of a CI job, so that
+/// only the files whose code has changed are analyzed again.
+/// The results are stored as they are serialized, so they can be written
+/// again in any serialization format, but they cannot be aggregated.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct Snapshot {
+    /// The version of the crate which computed the results
+    pub version: String,
+    /// A description of the options which computed the results
+    pub options: String,
+    files: BTreeMap<String, SnapshotFile>,
+}
+
+impl Snapshot {
+    /// Creates an empty snapshot of the results computed
+    /// with some options.
+    ///
+    /// The options are described by a string, such as the debug
+    /// representation of the [`MetricsOptions`]: the results of
+    /// a snapshot cannot be reused with different options.
+    ///
+    /// [`MetricsOptions`]: struct.MetricsOptions.html
+    pub fn new(options: impl Into<String>) -> Self {
+        Self {
+            version: env!("CARGO_PKG_VERSION").to_owned(),
+            options: options.into(),
+            files: BTreeMap::new(),
+        }
+    }
+
+    /// Checks whether the results of the snapshot have been computed
+    /// by this version of the crate with the given options.
+    pub fn is_compatible(&self, options: &str) -> bool {
+        self.version == env!("CARGO_PKG_VERSION") && self.options == options
+    }
+
+    /// Adds the results of a file, replacing the previous ones.
+    ///
+    /// * `space` - The root function space of the file,
+    ///   named after its path and containing its metadata.
+    ///
+    /// Returns `false` if the space has no name or no metadata.
+    pub fn insert(&mut self, space: &FuncSpace) -> bool {
+        let (Some(path), Some(metadata)) = (&space.name, &space.metadata) else {
+            return false;
+        };
+        let Ok(results) = SnapshotResults::new(space) else {
+            return false;
+        };
+        self.insert_results(path, metadata.hash.clone(), results);
+        true
+    }
+
+    /// Adds the serialized results of a file, replacing the previous ones.
+    ///
+    /// * `path` - The path of the file.
+    /// * `hash` - The hash of the code of the file, as in
+    ///   [`FileMetadata::hash`].
+    /// * `results` - The serialized results.
+    ///
+    /// [`FileMetadata::hash`]: struct.FileMetadata.html#structfield.hash
+    pub fn insert_results(&mut self, path: &str, hash: String, results: SnapshotResults) {
+        self.files
+            .insert(path.to_owned(), SnapshotFile { hash, results });
+    }
+
+    /// Returns the serialized results of a file, if the file
+    /// is in the snapshot and its code has not changed.
+    ///
+    /// * `path` - The path of the file.
+    /// * `code` - The current code of the file.
+    pub fn get(&self, path: &str, code: &[u8]) -> Option<&SnapshotResults> {
+        self.files
+            .get(path)
+            .filter(|file| file.hash == code_hash(code))
+            .map(|file| &file.results)
+    }
+
+    /// Returns the number of files of the snapshot.
+    pub fn len(&self) -> usize {
+        self.files.len()
+    }
+
+    /// Checks whether the snapshot does not contain any file.
+    pub fn is_empty(&self) -> bool {
+        self.files.is_empty()
+    }
+
+    /// Reads a snapshot written by [`Snapshot::write`].
+    ///
+    /// [`Snapshot::write`]: struct.Snapshot.html#method.write
+    pub fn read<R: Read>(reader: R) -> std::io::Result<Self> {
+        Ok(serde_json::from_reader(reader)?)
+    }
+
+    /// Writes the snapshot as compact JSON.
+    pub fn write<W: Write>(&self, writer: W) -> std::io::Result<()> {
+        Ok(serde_json::to_writer(writer, self)?)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::path::Path;
+
+    use pretty_assertions::assert_eq;
+
+    use crate::{LANG, get_function_spaces};
+
+    use super::*;
+
+    #[test]
+    fn snapshot_round_trip() {
+        let code = b"def foo(a):\n    return a or 1\n";
+        let space =
+            get_function_spaces(&LANG::Python, code.to_vec(), Path::new("foo.py"), None).unwrap();
+
+        let mut snapshot = Snapshot::new("default");
+        assert!(snapshot.insert(&space));
+        let mut data = Vec::new();
+        snapshot.write(&mut data).unwrap();
+        let snapshot = Snapshot::read(data.as_slice()).unwrap();
+
+        assert!(snapshot.is_compatible("default"));
+        assert!(!snapshot.is_compatible("other"));
+        assert_eq!(snapshot.len(), 1);
+        assert_eq!(
+            snapshot.get("foo.py", code),
+            Some(&SnapshotResults::new(&space).unwrap())
+        );
+        // The fields and the floats are written again as they were computed
+        assert_eq!(
+            serde_json::to_string(snapshot.get("foo.py", code).unwrap()).unwrap(),
+            serde_json::to_string(&space).unwrap()
+        );
+        // The results of a changed or unknown file are not reused
+        assert_eq!(snapshot.get("foo.py", b"def foo():\n    pass\n"), None);
+        assert_eq!(snapshot.get("bar.py", code), None);
+    }
+}
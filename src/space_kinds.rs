@@ -0,0 +1,235 @@
+use std::collections::BTreeMap;
+use std::fmt;
+
+use crate::langs::{LANG, get_from_ext};
+use crate::spaces::SpaceKind;
+
+/// The name given to the node kinds which are not spaces
+/// in the entries of a [`SpaceKindMap`].
+///
+/// [`SpaceKindMap`]: struct.SpaceKindMap.html
+pub const NO_SPACE: &str = "none";
+
+/// Errors which might occur adding an entry to a [`SpaceKindMap`].
+///
+/// [`SpaceKindMap`]: struct.SpaceKindMap.html
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum SpaceKindMapError {
+    /// The entry is not written as `LANGUAGE:NODE=KIND`
+    Syntax(String),
+    /// The language of the entry does not exist
+    Language(String),
+    /// The space kind of the entry does not exist
+    Kind(String),
+}
+
+impl fmt::Display for SpaceKindMapError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            SpaceKindMapError::Syntax(entry) => {
+                write!(f, "invalid entry {entry:?}, expected LANGUAGE:NODE=KIND")
+            }
+            SpaceKindMapError::Language(language) => write!(f, "unknown language {language:?}"),
+            SpaceKindMapError::Kind(kind) => write!(f, "unknown space kind {kind:?}"),
+        }
+    }
+}
+
+/// The grammar node kinds of each language whose spaces are configured
+/// by the user, overriding the ones defined by the language.
+///
+/// A node kind can become a space of a given kind, such as the Rust
+/// `mod_item` blocks as namespaces, or be no longer a space, so that
+/// its metrics are added to the ones of its enclosing space.
+///
+/// The languages sharing a name, such as the JavaScript dialects,
+/// share their node kinds. The root of a code is always the unit space.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct SpaceKindMap {
+    // The space kinds of the node kinds of each language, by language name
+    languages: BTreeMap<&'static str, BTreeMap<String, Option<SpaceKind>>>,
+}
+
+impl SpaceKindMap {
+    /// Creates an empty map, keeping the spaces of the languages.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Creates a map from some entries, as in [`add_entry`].
+    ///
+    /// [`add_entry`]: #method.add_entry
+    pub fn from_entries<S: AsRef<str>>(entries: &[S]) -> Result<Self, SpaceKindMapError> {
+        let mut map = Self::new();
+        for entry in entries {
+            map.add_entry(entry.as_ref())?;
+        }
+        Ok(map)
+    }
+
+    /// Checks whether the map overrides no spaces.
+    pub fn is_empty(&self) -> bool {
+        self.languages.is_empty()
+    }
+
+    /// Makes the nodes of a kind spaces of the given kind,
+    /// or not spaces if the kind is `None`.
+    pub fn insert(
+        &mut self,
+        language: LANG,
+        node_kind: impl Into<String>,
+        space_kind: Option<SpaceKind>,
+    ) {
+        self.languages
+            .entry(language.get_name())
+            .or_default()
+            .insert(node_kind.into(), space_kind);
+    }
+
+    /// Adds an entry written as `LANGUAGE:NODE=KIND`, such as
+    /// `rust:mod_item=namespace`.
+    ///
+    /// The language is its name or one of its file extensions,
+    /// and the kind is a space kind or `none` for the nodes
+    /// which are not spaces.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rust_code_analysis::{LANG, SpaceKind, SpaceKindMap};
+    ///
+    /// let mut map = SpaceKindMap::new();
+    /// map.add_entry("rs:mod_item=namespace").unwrap();
+    /// map.add_entry("python:class_definition=none").unwrap();
+    ///
+    /// assert_eq!(map.get(LANG::Rust, "mod_item"), Some(Some(SpaceKind::Namespace)));
+    /// assert_eq!(map.get(LANG::Python, "class_definition"), Some(None));
+    /// assert!(map.add_entry("rust:mod_item").is_err());
+    /// ```
+    pub fn add_entry(&mut self, entry: &str) -> Result<(), SpaceKindMapError> {
+        let syntax = || SpaceKindMapError::Syntax(entry.to_owned());
+        let (language, rest) = entry.split_once(':').ok_or_else(syntax)?;
+        let (node_kind, space_kind) = rest.split_once('=').ok_or_else(syntax)?;
+        let (node_kind, space_kind) = (node_kind.trim(), space_kind.trim());
+        if node_kind.is_empty() {
+            return Err(syntax());
+        }
+
+        let language = language.trim().to_lowercase();
+        let lang = LANG::into_enum_iter()
+            .find(|lang| lang.get_name() == language)
+            .or_else(|| get_from_ext(&language))
+            .ok_or_else(|| SpaceKindMapError::Language(language.clone()))?;
+        let space_kind = if space_kind == NO_SPACE {
+            None
+        } else {
+            Some(
+                SpaceKind::from_name(space_kind)
+                    .ok_or_else(|| SpaceKindMapError::Kind(space_kind.to_owned()))?,
+            )
+        };
+        self.insert(lang, node_kind, space_kind);
+        Ok(())
+    }
+
+    /// Returns the space kind of the nodes of a kind, `Some(None)` if they
+    /// are not spaces, or `None` if the spaces of the language are kept.
+    pub fn get(&self, language: LANG, node_kind: &str) -> Option<Option<SpaceKind>> {
+        self.for_language(language)?.get(node_kind).copied()
+    }
+
+    pub(crate) fn for_language(
+        &self,
+        language: LANG,
+    ) -> Option<&BTreeMap<String, Option<SpaceKind>>> {
+        self.languages.get(language.get_name())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::path::Path;
+
+    use pretty_assertions::assert_eq;
+
+    use crate::{FuncSpace, MetricsOptions, get_function_spaces_with_options};
+
+    use super::*;
+
+    fn spaces(lang: LANG, source: &str, entries: &[&str]) -> Vec<(String, String)> {
+        fn collect(space: &FuncSpace, acc: &mut Vec<(String, String)>) {
+            for subspace in &space.spaces {
+                acc.push((
+                    subspace.name.clone().unwrap_or_default(),
+                    subspace.kind.to_string(),
+                ));
+                collect(subspace, acc);
+            }
+        }
+
+        let options = MetricsOptions {
+            space_kinds: SpaceKindMap::from_entries(entries).unwrap(),
+            ..Default::default()
+        };
+        let space = get_function_spaces_with_options(
+            &lang,
+            source.as_bytes().to_vec(),
+            Path::new("foo"),
+            None,
+            &options,
+        )
+        .unwrap();
+        let mut acc = Vec::new();
+        collect(&space, &mut acc);
+        acc
+    }
+
+    #[test]
+    fn rust_space_kinds() {
+        let source = "mod foo {
+    fn bar() {}
+}
+
+impl Baz {
+    fn baz(&self) {}
+}
+";
+        let names = |entries| {
+            spaces(LANG::Rust, source, entries)
+                .into_iter()
+                .map(|(name, kind)| format!("{name}:{kind}"))
+                .collect::<Vec<_>>()
+        };
+        assert_eq!(names(&[]), ["bar:function", "Baz:impl", "baz:function"]);
+        assert_eq!(
+            names(&["rust:mod_item=namespace", "rust:impl_item=none"]),
+            ["foo:namespace", "bar:function", "baz:function"]
+        );
+    }
+
+    #[test]
+    fn space_kind_map_errors() {
+        let error = |entry| SpaceKindMap::from_entries(&[entry]).unwrap_err();
+        assert_eq!(
+            error("rust:mod_item"),
+            SpaceKindMapError::Syntax("rust:mod_item".to_owned())
+        );
+        assert_eq!(
+            error("cobol:paragraph=function"),
+            SpaceKindMapError::Language("cobol".to_owned())
+        );
+        assert_eq!(
+            error("rust:mod_item=module"),
+            SpaceKindMapError::Kind("module".to_owned())
+        );
+
+        for name in SpaceKind::NAMES {
+            assert_eq!(SpaceKind::from_name(name).unwrap().to_string(), *name);
+        }
+
+        // The dialects share their node kinds
+        let map = SpaceKindMap::from_entries(&["js:class=none"]).unwrap();
+        assert_eq!(map.get(LANG::Javascript, "class"), Some(None));
+        assert_eq!(map.get(LANG::Javascript, "function"), None);
+    }
+}
@@ -5,20 +5,34 @@ use std::fmt;
 use std::path::{Path, PathBuf};
 
 use crate::checker::Checker;
+use crate::grades::{GradeOptions, Grades, set_grades};
+use crate::langs::LANG;
+use crate::metadata::FileMetadata;
 use crate::node::Node;
+use crate::space_kinds::SpaceKindMap;
 
 use crate::abc::{self, Abc};
-use crate::cognitive::{self, Cognitive};
-use crate::cyclomatic::{self, Cyclomatic};
+use crate::accessors::{self, Accessors};
+use crate::annotations::{self, Annotations};
+use crate::assertions::{self, Assertions};
+use crate::callbacks::{self, Callbacks};
+use crate::cognitive::{self, Cognitive, CognitiveMode, ComprehensionMode};
+use crate::cyclomatic::{self, Cyclomatic, CyclomaticOptions};
 use crate::exit::{self, Exit};
 use crate::getter::Getter;
-use crate::halstead::{self, Halstead, HalsteadMaps};
-use crate::loc::{self, Loc};
+use crate::halstead::{self, Halstead, HalsteadMaps, HalsteadOptions};
+use crate::indentation;
+use crate::kotlin_funcs::{self, KotlinFuncs};
+use crate::loc::{self, Loc, LocOptions};
+use crate::locals::{self, Locals};
+use crate::macro_calls::{self, MacroCalls};
 use crate::mi::{self, Mi};
 use crate::nargs::{self, NArgs};
 use crate::nom::{self, Nom};
 use crate::npa::{self, Npa};
 use crate::npm::{self, Npm};
+use crate::ppc::{self, Ppc};
+use crate::unsafety::{self, Unsafety};
 use crate::wmc::{self, Wmc};
 
 use crate::dump_metrics::*;
@@ -47,6 +61,44 @@ pub enum SpaceKind {
     Namespace,
     /// An interface
     Interface,
+    /// A `Rust` declarative macro space
+    Macro,
+}
+
+impl SpaceKind {
+    /// The names of the space kinds.
+    pub const NAMES: &'static [&'static str] = &[
+        "unknown",
+        "function",
+        "class",
+        "struct",
+        "trait",
+        "impl",
+        "unit",
+        "namespace",
+        "interface",
+        "macro",
+    ];
+
+    /// Returns the space kind with the given name.
+    ///
+    /// Returns `None` if the space kind does not exist.
+    pub fn from_name(name: &str) -> Option<Self> {
+        let kind = match name {
+            "unknown" => SpaceKind::Unknown,
+            "function" => SpaceKind::Function,
+            "class" => SpaceKind::Class,
+            "struct" => SpaceKind::Struct,
+            "trait" => SpaceKind::Trait,
+            "impl" => SpaceKind::Impl,
+            "unit" => SpaceKind::Unit,
+            "namespace" => SpaceKind::Namespace,
+            "interface" => SpaceKind::Interface,
+            "macro" => SpaceKind::Macro,
+            _ => return None,
+        };
+        Some(kind)
+    }
 }
 
 impl fmt::Display for SpaceKind {
@@ -61,11 +113,50 @@ impl fmt::Display for SpaceKind {
             SpaceKind::Unit => "unit",
             SpaceKind::Namespace => "namespace",
             SpaceKind::Interface => "interface",
+            SpaceKind::Macro => "macro",
         };
         write!(f, "{s}")
     }
 }
 
+/// The strategies naming the anonymous spaces, such as the closures
+/// and the lambdas, which are otherwise named `<anonymous>`, `<lambda>`,
+/// or not named at all.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum AnonymousNaming {
+    /// The names given by the grammar of each language are kept
+    #[default]
+    Default,
+    /// The name of the enclosing space followed by the index of the space
+    /// among the anonymous spaces it contains, such as `f#2`
+    Enclosing,
+    /// The name of the variable, field, key or keyword argument the space
+    /// is assigned to, such as `callback` in `callback = lambda x: x`,
+    /// or the name given by the `Line` strategy when it is not assigned
+    Variable,
+    /// The name given by the grammar followed by the starting line of the
+    /// space, such as `<lambda@12>`
+    Line,
+}
+
+impl AnonymousNaming {
+    /// The names of the available strategies.
+    pub const NAMES: &'static [&'static str] = &["default", "enclosing", "variable", "line"];
+
+    /// Returns the strategy with the given name.
+    ///
+    /// Returns `None` if the strategy does not exist.
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name {
+            "default" => Some(Self::Default),
+            "enclosing" => Some(Self::Enclosing),
+            "variable" => Some(Self::Variable),
+            "line" => Some(Self::Line),
+            _ => None,
+        }
+    }
+}
+
 /// All metrics data.
 #[derive(Default, Debug, Clone, Serialize)]
 pub struct CodeMetrics {
@@ -95,6 +186,35 @@ pub struct CodeMetrics {
     /// `Npa` data
     #[serde(skip_serializing_if = "npa::Stats::is_disabled")]
     pub npa: npa::Stats,
+    /// `Ppc` data
+    #[serde(skip_serializing_if = "ppc::Stats::is_disabled")]
+    pub ppc: ppc::Stats,
+    /// `Unsafety` data
+    #[serde(skip_serializing_if = "unsafety::Stats::is_disabled")]
+    pub unsafety: unsafety::Stats,
+    /// `Callbacks` data
+    #[serde(skip_serializing_if = "callbacks::Stats::is_disabled")]
+    pub callbacks: callbacks::Stats,
+    /// `Annotations` data
+    #[serde(skip_serializing_if = "annotations::Stats::is_disabled")]
+    pub annotations: annotations::Stats,
+    /// `Accessors` data
+    #[serde(skip_serializing_if = "accessors::Stats::is_disabled")]
+    pub accessors: accessors::Stats,
+    /// `Assertions` data
+    #[serde(skip_serializing_if = "assertions::Stats::is_disabled")]
+    pub assertions: assertions::Stats,
+    /// `Locals` data
+    #[serde(skip_serializing_if = "locals::Stats::is_disabled")]
+    pub locals: locals::Stats,
+    /// `KotlinFuncs` data
+    #[serde(skip_serializing_if = "kotlin_funcs::Stats::is_disabled")]
+    pub kotlin_funcs: kotlin_funcs::Stats,
+    /// `MacroCalls` data
+    #[serde(skip_serializing_if = "macro_calls::Stats::is_disabled")]
+    pub macro_calls: macro_calls::Stats,
+    /// `Indentation` data
+    pub indentation: indentation::Stats,
 }
 
 impl fmt::Display for CodeMetrics {
@@ -124,9 +244,157 @@ impl CodeMetrics {
         self.wmc.merge(&other.wmc);
         self.npm.merge(&other.npm);
         self.npa.merge(&other.npa);
+        self.ppc.merge(&other.ppc);
+        self.unsafety.merge(&other.unsafety);
+        self.callbacks.merge(&other.callbacks);
+        self.annotations.merge(&other.annotations);
+        self.accessors.merge(&other.accessors);
+        self.assertions.merge(&other.assertions);
+        self.locals.merge(&other.locals);
+        self.kotlin_funcs.merge(&other.kotlin_funcs);
+        self.macro_calls.merge(&other.macro_calls);
+        // The `Indentation` metric of a space already covers
+        // the lines of its subspaces
     }
 }
 
+/// The properties of a function declared by its signature.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize)]
+pub struct FunctionFlags {
+    /// If `true`, the function is asynchronous, such as an `async`
+    /// function or a Kotlin `suspend` function
+    pub is_async: bool,
+    /// If `true`, the function is a generator, which yields its values
+    pub is_generator: bool,
+    /// If `true`, the function is a constructor
+    pub is_constructor: bool,
+    /// If `true`, the function is a static method or an associated function
+    pub is_static: bool,
+    /// If `true`, the function is a test, found through its attributes,
+    /// its annotations or its name
+    pub is_test: bool,
+    /// If `true`, the function is a property getter, such as a `Python`
+    /// `@property`, a `JavaScript` `get` method or a `Java` bean getter
+    pub is_getter: bool,
+    /// If `true`, the function is a property setter, such as a `Python`
+    /// `@name.setter`, a `JavaScript` `set` method or a `Java` bean setter
+    pub is_setter: bool,
+}
+
+impl FunctionFlags {
+    // Checks if no flag is set
+    #[inline(always)]
+    pub(crate) fn is_empty(&self) -> bool {
+        *self == Self::default()
+    }
+}
+
+/// The visibility of a space outside of its scope.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum SpaceVisibility {
+    /// A space visible everywhere
+    Public,
+    /// A space visible in its class and in the derived classes
+    Protected,
+    /// A space visible only in its class or in its module
+    Private,
+    /// A space visible in its crate, such as a `Rust` `pub(crate)` item
+    /// or a `Kotlin` `internal` declaration
+    Crate,
+    /// A space visible in its module or in its package, such as a `Java`
+    /// package-private method or a `Python` function starting with `_`
+    ModulePrivate,
+}
+
+impl fmt::Display for SpaceVisibility {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let s = match self {
+            SpaceVisibility::Public => "public",
+            SpaceVisibility::Protected => "protected",
+            SpaceVisibility::Private => "private",
+            SpaceVisibility::Crate => "crate",
+            SpaceVisibility::ModulePrivate => "module-private",
+        };
+        write!(f, "{s}")
+    }
+}
+
+/// The quality of the parse of a code, to know when its metrics are
+/// computed on a partially broken syntax tree.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Serialize)]
+pub struct ParseQuality {
+    /// The number of `ERROR` nodes, the code the parser could not recognize
+    pub errors: usize,
+    /// The number of `MISSING` nodes, the tokens the parser inserted
+    /// to recover from an error, such as a missing `;`
+    pub missing: usize,
+    /// The number of nodes of the syntax tree
+    pub nodes: usize,
+    /// The share of the nodes of the syntax tree which are neither
+    /// in an `ERROR` node nor `MISSING`, from 0 to 1
+    pub score: f64,
+}
+
+impl ParseQuality {
+    /// Checks if the code was parsed without errors.
+    pub fn is_clean(&self) -> bool {
+        self.errors == 0 && self.missing == 0
+    }
+}
+
+/// The kinds of the syntax errors of a code.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ParseWarningKind {
+    /// Code the parser could not recognize
+    Error,
+    /// A token the parser inserted to recover from an error
+    Missing,
+}
+
+/// A syntax error of a code, whose metrics are computed anyway
+/// on the recovered syntax tree.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize)]
+pub struct ParseWarning {
+    /// The kind of the syntax error
+    pub kind: ParseWarningKind,
+    /// The first line of the syntax error
+    pub start_line: usize,
+    /// The first column of the syntax error
+    pub start_column: usize,
+    /// The last line of the syntax error
+    pub end_line: usize,
+    /// The column after the syntax error
+    pub end_column: usize,
+    /// A description of the syntax error
+    pub message: String,
+}
+
+impl ParseWarning {
+    fn new(node: &Node, kind: ParseWarningKind) -> Self {
+        let (start_row, start_column) = node.start_position();
+        let (end_row, end_column) = node.end_position();
+        let message = match kind {
+            ParseWarningKind::Error => "Syntax error".to_string(),
+            ParseWarningKind::Missing => format!("Missing `{}`", node.kind()),
+        };
+        Self {
+            kind,
+            start_line: start_row + 1,
+            start_column: start_column + 1,
+            end_line: end_row + 1,
+            end_column: end_column + 1,
+            message,
+        }
+    }
+}
+
+// Serializes the parse quality of the unit space only when the parse is broken
+fn is_clean_parse(quality: &Option<ParseQuality>) -> bool {
+    quality.is_none_or(|quality| quality.is_clean())
+}
+
 /// Function space data.
 #[derive(Debug, Clone, Serialize)]
 pub struct FuncSpace {
@@ -135,16 +403,72 @@ pub struct FuncSpace {
     /// If `None`, an error is occurred in parsing
     /// the name of a function space
     pub name: Option<String>,
+    /// The name of a function space prefixed by the module of the file
+    /// and by the names of its enclosing spaces, such as `foo::Foo::bar`
+    /// in Rust or `foo.Foo.bar` in Python
+    ///
+    /// The module is the package declared by a Java or a Kotlin file,
+    /// and it is named after the file in Python, Rust and JavaScript.
+    /// The anonymous spaces, such as the closures, do not interrupt
+    /// the names of the spaces they contain.
+    ///
+    /// It is `None` for the unit space and when the name
+    /// of the space is `None`
+    pub qualified_name: Option<String>,
+    /// An identifier of a function space which does not depend
+    /// on its position in the file
+    ///
+    /// It is derived from the kind, the qualified name and the parameters
+    /// of the space, so it can be used to track the same space across
+    /// different versions of a file, even when the space is moved.
+    /// Spaces with the same kind, qualified name and parameters are
+    /// distinguished by their order of appearance
+    pub id: String,
     /// The first line of a function space
     pub start_line: usize,
     /// The last line of a function space
     pub end_line: usize,
     /// The space kind
     pub kind: SpaceKind,
+    /// The visibility of a space
+    ///
+    /// It is `None` for the unit space, the closures and the spaces
+    /// of the languages without visibility rules
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub visibility: Option<SpaceVisibility>,
+    /// The properties of a function space declared by its signature
+    #[serde(skip_serializing_if = "FunctionFlags::is_empty")]
+    pub flags: FunctionFlags,
+    /// The names of the decorators, the annotations or the attributes
+    /// of a function space, such as `staticmethod`, `Override` or `test`
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub decorators: Vec<String>,
+    /// The quality of the parse of the code
+    ///
+    /// It is set for the unit space only, and it is serialized
+    /// only when the code contains syntax errors
+    #[serde(skip_serializing_if = "is_clean_parse")]
+    pub parse_quality: Option<ParseQuality>,
+    /// The syntax errors of the code, in the order of the code
+    ///
+    /// They are set for the unit space only. An error containing
+    /// other errors is reported once.
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub warnings: Vec<ParseWarning>,
+    /// The metadata of the file, such as its size and the hash of its code
+    ///
+    /// It is set for the unit space only
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub metadata: Option<FileMetadata>,
     /// All subspaces contained in a function space
     pub spaces: Vec<FuncSpace>,
     /// All metrics of a function space
     pub metrics: CodeMetrics,
+    /// The grades of the metrics of a function space
+    pub grades: Grades,
+    // The parameters of the space, used to compute its identifier
+    #[serde(skip)]
+    signature: Option<String>,
 }
 
 impl FuncSpace {
@@ -163,13 +487,73 @@ impl FuncSpace {
         Self {
             name: T::get_func_space_name(node, code)
                 .map(|name| name.split_whitespace().collect::<Vec<_>>().join(" ")),
+            qualified_name: None,
+            id: String::new(),
+            signature: get_signature(node, code, kind),
             spaces: Vec::new(),
             metrics: CodeMetrics::default(),
+            grades: Grades::default(),
             kind,
+            visibility: T::get_space_visibility(node, code),
+            flags: T::get_function_flags(node, code),
+            decorators: T::get_decorators(node, code)
+                .into_iter()
+                .map(|decorator| decorator.split_whitespace().collect())
+                .collect(),
+            parse_quality: None,
+            warnings: Vec::new(),
+            metadata: None,
             start_line: start_position,
             end_line: end_position,
         }
     }
+
+    // Returns the parameters of the space, as written in the code
+    #[inline(always)]
+    pub(crate) fn signature(&self) -> Option<&str> {
+        self.signature.as_deref()
+    }
+}
+
+// Maximum depth at which the parameters of a space are searched
+const SIGNATURE_DEPTH: usize = 3;
+
+// Finds the parameters of a function without looking into its body.
+// The other spaces have no signature, so that the identifiers of a class
+// or an impl do not change when the parameters of one of their methods do
+fn get_signature(node: &Node, code: &[u8], kind: SpaceKind) -> Option<String> {
+    if kind != SpaceKind::Function {
+        return None;
+    }
+    if let Some(parameters) = node.child_by_field_name("parameters") {
+        return parameters
+            .utf8_text(code)
+            .map(|text| text.split_whitespace().collect::<Vec<_>>().join(" "));
+    }
+    let mut nodes = vec![(*node, 0)];
+    let mut i = 0;
+    while let Some(&(node, depth)) = nodes.get(i) {
+        i += 1;
+        let kind = node.kind();
+        if kind.ends_with("parameters") || kind == "parameter_list" {
+            return node
+                .utf8_text(code)
+                .map(|text| text.split_whitespace().collect::<Vec<_>>().join(" "));
+        }
+        if depth < SIGNATURE_DEPTH {
+            nodes.extend(
+                node.children()
+                    .filter(|child| {
+                        let kind = child.kind();
+                        !kind.contains("body")
+                            && !kind.contains("block")
+                            && kind != "compound_statement"
+                    })
+                    .map(|child| (child, depth + 1)),
+            );
+        }
+    }
+    None
 }
 
 #[inline(always)]
@@ -186,6 +570,7 @@ fn compute_halstead_mi_and_wmc<T: ParserTrait>(state: &mut State) {
     T::Wmc::compute(
         state.space.kind,
         &state.space.metrics.cyclomatic,
+        &state.space.metrics.cognitive,
         &mut state.space.metrics.wmc,
     );
 }
@@ -199,6 +584,13 @@ fn compute_averages(state: &mut State) {
     state.space.metrics.cognitive.finalize(nom_total);
     // Nexit average
     state.space.metrics.nexits.finalize(nom_total);
+    // Assertions average
+    state.space.metrics.assertions.finalize(nom_total);
+    // Locals average
+    state.space.metrics.locals.finalize(nom_total);
+    // Unsafety ratio
+    let sloc = state.space.metrics.loc.sloc();
+    state.space.metrics.unsafety.finalize(sloc);
     // Nargs average
     state
         .space
@@ -216,6 +608,8 @@ fn compute_minmax(state: &mut State) {
     state.space.metrics.nom.compute_minmax();
     state.space.metrics.loc.compute_minmax();
     state.space.metrics.abc.compute_minmax();
+    state.space.metrics.assertions.compute_minmax();
+    state.space.metrics.locals.compute_minmax();
 }
 
 #[inline(always)]
@@ -223,6 +617,13 @@ fn compute_sum(state: &mut State) {
     state.space.metrics.wmc.compute_sum();
     state.space.metrics.npm.compute_sum();
     state.space.metrics.npa.compute_sum();
+    state.space.metrics.ppc.compute_sum();
+    state.space.metrics.unsafety.compute_sum();
+    state.space.metrics.callbacks.compute_sum();
+    state.space.metrics.annotations.compute_sum();
+    state.space.metrics.accessors.compute_sum();
+    state.space.metrics.kotlin_funcs.compute_sum();
+    state.space.metrics.macro_calls.compute_sum();
 }
 
 fn finalize<T: ParserTrait>(state_stack: &mut Vec<State>, diff_level: usize) {
@@ -284,6 +685,40 @@ struct State<'a> {
 /// metrics(&parser, &path).unwrap();
 /// ```
 pub fn metrics<'a, T: ParserTrait>(parser: &'a T, path: &'a Path) -> Option<FuncSpace> {
+    metrics_with_options(parser, path, &MetricsOptions::default())
+}
+
+/// Returns all function spaces data of a code, computing the metrics
+/// with the given options.
+///
+/// # Examples
+///
+/// ```
+/// use std::path::Path;
+///
+/// use rust_code_analysis::cyclomatic::CyclomaticOptions;
+/// use rust_code_analysis::{CppParser, MetricsOptions, ParserTrait, metrics_with_options};
+///
+/// let source_code = "int f(int a, int b) { return a && b; }";
+/// let path = Path::new("foo.c");
+/// let parser = CppParser::new(source_code.as_bytes().to_vec(), &path, None);
+///
+/// let options = MetricsOptions {
+///     cyclomatic: CyclomaticOptions::preset("strict-mccabe").unwrap(),
+///     ..Default::default()
+/// };
+/// let space = metrics_with_options(&parser, &path, &options).unwrap();
+///
+/// // The && operator is not counted
+/// assert_eq!(space.spaces[0].metrics.cyclomatic.cyclomatic(), 1.);
+/// ```
+pub fn metrics_with_options<'a, T: ParserTrait>(
+    parser: &'a T,
+    path: &'a Path,
+    options: &MetricsOptions,
+) -> Option<FuncSpace> {
+    let _span = tracing::debug_span!("metrics", path = %path.display()).entered();
+
     let code = parser.get_code();
     let node = parser.get_root();
     let mut cursor = node.cursor();
@@ -295,24 +730,102 @@ pub fn metrics<'a, T: ParserTrait>(parser: &'a T, path: &'a Path) -> Option<Func
     // Three type of nesting info: conditionals, functions and lambdas
     let mut nesting_map = HashMap::<usize, (usize, usize, usize)>::default();
     nesting_map.insert(node.id(), (0, 0, 0));
-    stack.push((node, 0));
+    // Ids of the ancestors of the current node, the nesting information
+    // of the other nodes is not needed anymore. This keeps the size of
+    // nesting_map bounded by the depth of the tree rather than its size.
+    let mut ancestors = Vec::new();
+    stack.push((node, 0, 0));
+    let mut parse_quality = ParseQuality::default();
+    // The nodes of the syntax tree in an ERROR node, and its depth
+    let mut broken_nodes = 0;
+    let mut error_depth = None;
+    let mut warnings = Vec::new();
+    let lines = indentation::Lines::new(code);
+    let space_kinds = options.space_kinds.for_language(parser.get_language());
+
+    // All the metrics are computed in a single traversal of the tree,
+    // the aggregated values are computed when a space is closed
+    let traversal_span = tracing::trace_span!("traversal").entered();
+    while let Some((node, level, depth)) = stack.pop() {
+        for id in ancestors.drain(depth..) {
+            nesting_map.remove(&id);
+        }
+        ancestors.push(node.id());
+
+        parse_quality.nodes += 1;
+        if error_depth.is_some_and(|error_depth| depth <= error_depth) {
+            error_depth = None;
+        }
+        if node.is_error() {
+            parse_quality.errors += 1;
+            if error_depth.is_none() {
+                warnings.push(ParseWarning::new(&node, ParseWarningKind::Error));
+                error_depth = Some(depth);
+            }
+        }
+        if node.is_missing() {
+            parse_quality.missing += 1;
+            broken_nodes += 1;
+            warnings.push(ParseWarning::new(&node, ParseWarningKind::Missing));
+        } else if error_depth.is_some() {
+            broken_nodes += 1;
+        }
 
-    while let Some((node, level)) = stack.pop() {
         if level < last_level {
             finalize::<T>(&mut state_stack, last_level - level);
             last_level = level;
         }
 
-        let kind = T::Getter::get_space_kind(&node);
+        // A root which is a syntax error is still the unit space,
+        // so that the metrics of the recovered code are computed
+        let root_error = depth == 0 && node.is_error();
+        // The spaces configured by the user, except for the unit space
+        let mapped = space_kinds
+            .filter(|_| depth > 0)
+            .and_then(|kinds| kinds.get(node.kind()));
+        let kind = match mapped {
+            _ if root_error => SpaceKind::Unit,
+            Some(Some(kind)) => *kind,
+            _ => T::Getter::get_space_kind(&node),
+        };
 
-        let func_space = T::Checker::is_func(&node) || T::Checker::is_func_space(&node);
+        let func_space = root_error
+            || (depth == 0 || !options.unit_only)
+                && mapped.map_or_else(
+                    || {
+                        T::Checker::is_func(&node)
+                            || T::Checker::is_func_space(&node)
+                            || options.macros && T::Checker::is_macro_definition(&node)
+                            || options.comprehensions == ComprehensionMode::Spaces
+                                && (T::Checker::is_closure(&node)
+                                    || T::Checker::is_comprehension(&node))
+                            || options.closure_spaces.is_some_and(|lines| {
+                                T::Checker::is_closure(&node)
+                                    && node.end_row() - node.start_row() + 1 >= lines
+                            })
+                    },
+                    Option::is_some,
+                );
         let unit = kind == SpaceKind::Unit;
 
         let new_level = if func_space {
-            let state = State {
+            let mut state = State {
                 space: FuncSpace::new::<T::Getter>(&node, code, kind),
                 halstead_maps: HalsteadMaps::new(),
             };
+            if options.anonymous == AnonymousNaming::Variable
+                && !unit
+                && is_anonymous(state.space.name.as_deref())
+                && let Some(name) = assigned_name(&node, code)
+            {
+                state.space.name = Some(name.to_owned());
+            }
+            state
+                .space
+                .metrics
+                .indentation
+                .compute(&lines, node.start_row(), node.end_row());
+            state.space.metrics.halstead.set_options(options.halstead);
             state_stack.push(state);
             last_level = level + 1;
             last_level
@@ -322,22 +835,47 @@ pub fn metrics<'a, T: ParserTrait>(parser: &'a T, path: &'a Path) -> Option<Func
 
         if let Some(state) = state_stack.last_mut() {
             let last = &mut state.space;
-            T::Cognitive::compute(&node, &mut last.metrics.cognitive, &mut nesting_map);
-            T::Cyclomatic::compute(&node, &mut last.metrics.cyclomatic);
-            T::Halstead::compute(&node, code, &mut state.halstead_maps);
-            T::Loc::compute(&node, &mut last.metrics.loc, func_space, unit);
+            T::Cognitive::compute(
+                &node,
+                code,
+                &mut last.metrics.cognitive,
+                &mut nesting_map,
+                options.cognitive,
+                options.comprehensions,
+            );
+            T::Cyclomatic::compute(&node, &mut last.metrics.cyclomatic, &options.cyclomatic);
+            if !options.unit_only {
+                T::Halstead::compute(&node, code, &mut state.halstead_maps);
+            }
+            T::Loc::compute(&node, &mut last.metrics.loc, func_space, unit, &options.loc);
             T::Nom::compute(&node, &mut last.metrics.nom);
+            if options.comprehensions == ComprehensionMode::Spaces
+                && T::Checker::is_comprehension(&node)
+            {
+                last.metrics.nom.add_closure();
+            }
             T::NArgs::compute(&node, &mut last.metrics.nargs);
             T::Exit::compute(&node, &mut last.metrics.nexits);
             T::Abc::compute(&node, &mut last.metrics.abc);
-            T::Npm::compute(&node, &mut last.metrics.npm);
-            T::Npa::compute(&node, &mut last.metrics.npa);
+            T::Npm::compute(&node, code, &mut last.metrics.npm);
+            T::Npa::compute(&node, code, &mut last.metrics.npa);
+            T::Ppc::compute(&node, &mut last.metrics.ppc);
+            T::Unsafety::compute(&node, &mut last.metrics.unsafety);
+            T::Callbacks::compute(&node, code, &mut last.metrics.callbacks);
+            T::Annotations::compute(&node, &mut last.metrics.annotations);
+            T::Accessors::compute(&node, code, &mut last.metrics.accessors);
+            T::Assertions::compute(&node, code, &mut last.metrics.assertions);
+            T::Locals::compute(&node, code, &mut last.metrics.locals);
+            T::KotlinFuncs::compute(&node, &mut last.metrics.kotlin_funcs);
+            if options.macros {
+                T::MacroCalls::compute(&node, &mut last.metrics.macro_calls);
+            }
         }
 
         cursor.reset(&node);
         if cursor.goto_first_child() {
             loop {
-                children.push((cursor.node(), new_level));
+                children.push((cursor.node(), new_level, depth + 1));
                 if !cursor.goto_next_sibling() {
                     break;
                 }
@@ -348,14 +886,255 @@ pub fn metrics<'a, T: ParserTrait>(parser: &'a T, path: &'a Path) -> Option<Func
         }
     }
 
-    finalize::<T>(&mut state_stack, usize::MAX);
+    traversal_span.exit();
+
+    tracing::trace_span!("finalize").in_scope(|| finalize::<T>(&mut state_stack, usize::MAX));
 
     state_stack.pop().map(|mut state| {
+        if options.anonymous != AnonymousNaming::Default {
+            name_anonymous_spaces(&mut state.space, options.anonymous);
+        }
         state.space.name = path.to_str().map(|name| name.to_string());
+        let module = module_name(parser.get_language(), &node, code, path);
+        set_qualified_names(
+            &mut state.space,
+            module.as_deref(),
+            qualified_name_separator(parser.get_language()),
+        );
+        set_ids(&mut state.space, &mut HashMap::new());
+        set_grades(&mut state.space, &options.grades);
+        parse_quality.score = 1. - broken_nodes as f64 / parse_quality.nodes as f64;
+        state.space.parse_quality = Some(parse_quality);
+        state.space.warnings = warnings;
+        state.space.metadata = Some(FileMetadata {
+            line_ending: parser.get_line_ending(),
+            ..FileMetadata::new(path, code, parser.get_language())
+        });
         state.space
     })
 }
 
+// The anonymous spaces have no name or a placeholder name, such as `<lambda>`
+fn is_anonymous(name: Option<&str>) -> bool {
+    name.is_none_or(|name| name.starts_with('<') && name.ends_with('>'))
+}
+
+// Returns the name of the variable, field or key a space is assigned to
+fn assigned_name<'a>(node: &Node, code: &'a [u8]) -> Option<&'a str> {
+    let parent = node.parent()?;
+    ["name", "left", "declarator", "pattern", "key"]
+        .into_iter()
+        .filter_map(|field| parent.child_by_field_name(field))
+        .find(|target| target.start_byte() != node.start_byte())
+        .and_then(|target| std::str::from_utf8(&code[target.start_byte()..target.end_byte()]).ok())
+        .filter(|name| {
+            !name.is_empty()
+                && name
+                    .chars()
+                    .all(|c| c.is_alphanumeric() || matches!(c, '_' | '$' | '.'))
+        })
+}
+
+fn name_anonymous_spaces(space: &mut FuncSpace, naming: AnonymousNaming) {
+    let enclosing = match space.kind {
+        SpaceKind::Unit => None,
+        _ => space.name.clone(),
+    };
+    let mut index = 0;
+    for subspace in &mut space.spaces {
+        if is_anonymous(subspace.name.as_deref()) {
+            let placeholder = subspace.name.as_deref().unwrap_or("<anonymous>");
+            index += 1;
+            subspace.name = Some(match (naming, &enclosing) {
+                (AnonymousNaming::Enclosing, Some(enclosing)) => format!("{enclosing}#{index}"),
+                (AnonymousNaming::Enclosing, None) => format!("{placeholder}#{index}"),
+                _ => format!(
+                    "{}@{}>",
+                    &placeholder[..placeholder.len() - 1],
+                    subspace.start_line
+                ),
+            });
+        }
+        name_anonymous_spaces(subspace, naming);
+    }
+}
+
+pub(crate) fn qualified_name_separator(lang: LANG) -> &'static str {
+    match lang {
+        LANG::Cpp | LANG::Ccomment | LANG::Preproc | LANG::Rust => "::",
+        _ => ".",
+    }
+}
+
+// Returns the module of a file: the package declared by the file,
+// or a name derived from its path
+fn module_name(lang: LANG, root: &Node, code: &[u8], path: &Path) -> Option<String> {
+    let package = match lang {
+        LANG::Java => "package_declaration",
+        LANG::Kotlin => "package_header",
+        _ => return path_module_name(lang, path),
+    };
+    let package = root.children().find(|child| child.kind() == package)?;
+    let name = package
+        .children()
+        .find(|child| child.is_named() && !child.kind().contains("annotation"))?;
+    name.utf8_text(code)
+        .map(|name| name.split_whitespace().collect())
+}
+
+// Returns the module of a file named after its path, such as `foo` for
+// `foo.py` and `foo/__init__.py`, in the languages without packages
+pub(crate) fn path_module_name(lang: LANG, path: &Path) -> Option<String> {
+    let stem = path.file_stem()?.to_str()?;
+    let index = match lang {
+        LANG::Python => "__init__",
+        LANG::Rust if stem == "lib" || stem == "main" => return Some("crate".to_owned()),
+        LANG::Rust => "mod",
+        LANG::Mozjs | LANG::Javascript | LANG::Typescript | LANG::Tsx => "index",
+        _ => return None,
+    };
+    if stem == index {
+        path.parent()?.file_name()?.to_str().map(str::to_owned)
+    } else {
+        Some(stem.to_owned())
+    }
+}
+
+fn set_qualified_names(space: &mut FuncSpace, prefix: Option<&str>, separator: &str) {
+    for subspace in &mut space.spaces {
+        subspace.qualified_name = subspace.name.as_ref().map(|name| match prefix {
+            Some(prefix) => format!("{prefix}{separator}{name}"),
+            None => name.clone(),
+        });
+        // The spaces inside an anonymous space, such as a closure,
+        // are named after its closest named ancestor
+        let prefix = if is_anonymous(subspace.name.as_deref()) {
+            prefix.map(str::to_owned)
+        } else {
+            subspace.qualified_name.clone()
+        };
+        set_qualified_names(subspace, prefix.as_deref(), separator);
+    }
+}
+
+// Renames the unit space of a file after another path with the same code,
+// updating the names and the identifiers depending on the path
+pub(crate) fn rename_unit(space: &mut FuncSpace, path: &Path, lang: LANG) {
+    let old_path = space.name.as_deref().map(PathBuf::from);
+    space.name = path.to_str().map(str::to_owned);
+    let old_module = old_path.and_then(|old_path| path_module_name(lang, &old_path));
+    let new_module = path_module_name(lang, path);
+    if let (Some(old_module), Some(new_module)) = (old_module, new_module)
+        && old_module != new_module
+    {
+        rename_module(
+            space,
+            &old_module,
+            &new_module,
+            qualified_name_separator(lang),
+        );
+    }
+    set_ids(space, &mut HashMap::new());
+}
+
+fn rename_module(space: &mut FuncSpace, old_module: &str, new_module: &str, separator: &str) {
+    for subspace in &mut space.spaces {
+        if let Some(name) = &mut subspace.qualified_name
+            && let Some(rest) = name
+                .strip_prefix(old_module)
+                .filter(|rest| rest.starts_with(separator))
+        {
+            *name = format!("{new_module}{rest}");
+        }
+        rename_module(subspace, old_module, new_module, separator);
+    }
+}
+
+// The initial value of an FNV-1a hash
+pub(crate) const FNV_OFFSET_BASIS: u64 = 0xcbf2_9ce4_8422_2325;
+
+// 64-bit FNV-1a, which is stable across runs and platforms
+// unlike the hashers of the standard library
+pub(crate) fn fnv1a(hash: u64, bytes: &[u8]) -> u64 {
+    bytes.iter().fold(hash, |hash, byte| {
+        (hash ^ u64::from(*byte)).wrapping_mul(0x0000_0100_0000_01b3)
+    })
+}
+
+pub(crate) fn set_ids(space: &mut FuncSpace, occurrences: &mut HashMap<u64, usize>) {
+    let name = match space.kind {
+        SpaceKind::Unit => space.name.as_deref(),
+        _ => space.qualified_name.as_deref(),
+    };
+    let mut hash = FNV_OFFSET_BASIS;
+    for part in [
+        space.kind.to_string().as_str(),
+        name.unwrap_or_default(),
+        space.signature.as_deref().unwrap_or_default(),
+    ] {
+        hash = fnv1a(hash, part.as_bytes());
+        hash = fnv1a(hash, &[0]);
+    }
+
+    // Spaces with the same key are told apart by their order of appearance
+    let occurrence = occurrences.entry(hash).or_default();
+    if *occurrence > 0 {
+        hash = fnv1a(hash, &occurrence.to_le_bytes());
+    }
+    *occurrence += 1;
+
+    space.id = format!("{hash:016x}");
+    for subspace in &mut space.spaces {
+        set_ids(subspace, occurrences);
+    }
+}
+
+/// The options changing how the metrics are computed.
+///
+/// The default options compute the metrics as described
+/// in the documentation of each metric.
+#[derive(Clone, Debug, Default)]
+pub struct MetricsOptions {
+    /// The rules used to count the decision points of the `Cyclomatic` metric
+    pub cyclomatic: CyclomaticOptions,
+    /// The rules used to compute the `Cognitive Complexity` metric
+    pub cognitive: CognitiveMode,
+    /// The rules used to analyze the Python comprehensions,
+    /// generator expressions and lambdas
+    pub comprehensions: ComprehensionMode,
+    /// The rules used to count the lines of the `Loc` metric suite
+    pub loc: LocOptions,
+    /// The parameters of the time and bugs estimates
+    /// of the `Halstead` metric suite
+    pub halstead: HalsteadOptions,
+    /// Whether the macros are analyzed
+    ///
+    /// When enabled, the macro invocations are counted by
+    /// the `MacroCalls` metric and the bodies of the declarative
+    /// macros are analyzed as their own spaces.
+    pub macros: bool,
+    /// The minimum number of lines of the closures analyzed as their own spaces
+    ///
+    /// By default, the closures and the lambdas are part of the space
+    /// containing them, so the metrics of a large inline closure are
+    /// added to the ones of its parent. In the languages whose closures
+    /// are always spaces, such as Rust and JavaScript, this has no effect.
+    pub closure_spaces: Option<usize>,
+    /// The strategy naming the anonymous spaces
+    pub anonymous: AnonymousNaming,
+    /// The boundaries used to grade the metrics of each space
+    pub grades: GradeOptions,
+    /// The node kinds whose spaces are configured by the user,
+    /// overriding the ones of the languages
+    pub space_kinds: SpaceKindMap,
+    /// Whether only the unit space is analyzed, without the `Halstead` metrics
+    ///
+    /// The functions are counted but not analyzed as their own spaces,
+    /// and the operators and operands are not collected, so that the
+    /// files too large for a full analysis need much less memory.
+    pub unit_only: bool,
+}
+
 /// Configuration options for computing
 /// the metrics of a code.
 #[derive(Debug)]
@@ -382,7 +1161,11 @@ impl Callback for Metrics {
 
 #[cfg(test)]
 mod tests {
-    use crate::{CppParser, check_func_space};
+    use super::*;
+    use crate::{
+        CppParser, JavascriptParser, LANG, PythonParser, RustParser, TypescriptParser,
+        check_func_space,
+    };
 
     #[test]
     fn c_scope_resolution_operator() {
@@ -399,4 +1182,1009 @@ mod tests {
             },
         );
     }
+
+    #[test]
+    fn rust_qualified_names() {
+        check_func_space::<RustParser, _>(
+            "mod foo {
+                struct Bar;
+                impl Bar {
+                    fn baz() {}
+                }
+            }",
+            "foo.rs",
+            |func_space| {
+                let imp = &func_space.spaces[0];
+                insta::assert_json_snapshot!(
+                    (func_space.qualified_name.as_deref(), imp.qualified_name.as_deref(), imp.spaces[0].qualified_name.as_deref()),
+                    @r###"
+                [
+                  null,
+                  "foo::Bar",
+                  "foo::Bar::baz"
+                ]
+                "###
+                );
+            },
+        );
+    }
+
+    #[test]
+    fn typescript_interface_spaces() {
+        check_func_space::<TypescriptParser, _>(
+            "interface Shape { area(): number; }
+            type Point = { x: number; y: number };
+            type Id = string | number;
+            abstract class Base { abstract area(): number; }",
+            "foo.ts",
+            |func_space| {
+                let spaces: Vec<_> = func_space
+                    .spaces
+                    .iter()
+                    .map(|space| {
+                        format!(
+                            "{}: {}",
+                            space.kind,
+                            space.name.as_deref().unwrap_or_default()
+                        )
+                    })
+                    .collect();
+                insta::assert_debug_snapshot!(spaces, @r###"
+                [
+                    "interface: Shape",
+                    "interface: Point",
+                    "class: Base",
+                ]
+                "###);
+            },
+        );
+    }
+
+    #[test]
+    fn python_space_ids() {
+        let ids = |source: &str| {
+            let path = std::path::Path::new("foo.py");
+            let space =
+                crate::get_function_spaces(&LANG::Python, source.as_bytes().to_vec(), path, None)
+                    .unwrap();
+            space
+                .spaces
+                .iter()
+                .map(|space| space.id.clone())
+                .collect::<Vec<_>>()
+        };
+
+        let before =
+            ids("def foo(a, b):\n    pass\ndef foo(a):\n    pass\ndef foo(a):\n    pass\n");
+        let after = ids("\n\ndef foo(a):\n    return 1\ndef foo(a,\n        b):\n    pass\n");
+
+        // Moving or editing a function does not change its identifier
+        assert_eq!(before[0], after[1]);
+        assert_eq!(before[1], after[0]);
+        // Functions with the same name and parameters are distinguished
+        assert_ne!(before[1], before[2]);
+        insta::assert_json_snapshot!(
+            before,
+            @r###"
+        [
+          "b0a750bfa82e9d99",
+          "2b0b640319b0a517",
+          "584c93f65b07a3d6"
+        ]
+        "###
+        );
+    }
+
+    #[test]
+    fn rust_impl_id_ignores_method_parameters() {
+        let impl_id = |source: &str| {
+            let path = std::path::Path::new("foo.rs");
+            let space =
+                crate::get_function_spaces(&LANG::Rust, source.as_bytes().to_vec(), path, None)
+                    .unwrap();
+            let space = &space.spaces[0];
+            assert_eq!(space.kind, SpaceKind::Impl);
+            (space.id.clone(), space.spaces[0].id.clone())
+        };
+
+        let (before, method_before) = impl_id("impl Foo {\n    fn bar(&self, a: u32) {}\n}\n");
+        let (after, method_after) = impl_id("impl Foo {\n    fn bar(&self) {}\n}\n");
+
+        // Only the identifier of the edited method changes
+        assert_eq!(before, after);
+        assert_ne!(method_before, method_after);
+    }
+
+    #[test]
+    fn python_qualified_names() {
+        check_func_space::<PythonParser, _>(
+            "class Foo:
+    def bar(self):
+        def baz():
+            pass",
+            "foo.py",
+            |func_space| {
+                let bar = &func_space.spaces[0].spaces[0];
+                insta::assert_json_snapshot!(
+                    (bar.qualified_name.as_deref(), bar.spaces[0].qualified_name.as_deref()),
+                    @r###"
+                [
+                  "foo.Foo.bar",
+                  "foo.Foo.bar.baz"
+                ]
+                "###
+                );
+            },
+        );
+    }
+
+    #[test]
+    fn module_qualified_names() {
+        let names = |lang: LANG, path: &str, source: &str| {
+            let space = crate::get_function_spaces(
+                &lang,
+                source.as_bytes().to_vec(),
+                std::path::Path::new(path),
+                None,
+            )
+            .unwrap();
+            let mut names = Vec::new();
+            let mut stack = vec![&space];
+            while let Some(space) = stack.pop() {
+                names.extend(space.qualified_name.clone());
+                stack.extend(space.spaces.iter().rev());
+            }
+            names
+        };
+
+        assert_eq!(
+            names(
+                LANG::Java,
+                "src/Foo.java",
+                "package com.example;\nclass Foo { void bar() {} }"
+            ),
+            ["com.example.Foo", "com.example.Foo.bar"]
+        );
+        assert_eq!(
+            names(
+                LANG::Kotlin,
+                "Foo.kt",
+                "package com.example\nclass Foo { fun bar() {} }"
+            ),
+            ["com.example.Foo", "com.example.Foo.bar"]
+        );
+        assert_eq!(
+            names(LANG::Python, "pkg/__init__.py", "def foo():\n    pass\n"),
+            ["pkg.foo"]
+        );
+        assert_eq!(
+            names(LANG::Rust, "src/lib.rs", "fn foo() {}"),
+            ["crate::foo"]
+        );
+        assert_eq!(names(LANG::Cpp, "foo.cpp", "void foo() {}"), ["foo"]);
+    }
+
+    #[test]
+    fn anonymous_qualified_names() {
+        check_func_space::<JavascriptParser, _>(
+            "function f(items) {
+                items.map((item) => {
+                    function helper() {}
+                });
+            }",
+            "foo.js",
+            |func_space| {
+                let f = &func_space.spaces[0];
+                let closure = &f.spaces[0];
+                insta::assert_json_snapshot!(
+                    (
+                        closure.qualified_name.as_deref(),
+                        closure.spaces[0].qualified_name.as_deref(),
+                    ),
+                    @r###"
+                [
+                  "foo.f.<anonymous>",
+                  "foo.f.helper"
+                ]
+                "###
+                );
+            },
+        );
+    }
+
+    #[test]
+    fn c_unit_only() {
+        let source = "int f(int a) { return a ? 1 : 0; }
+int g(int b) { if (b) { return 1; } return 0; }
+";
+        let options = MetricsOptions {
+            unit_only: true,
+            ..Default::default()
+        };
+        let space = crate::get_function_spaces_with_options(
+            &LANG::Cpp,
+            source.as_bytes().to_vec(),
+            std::path::Path::new("foo.c"),
+            None,
+            &options,
+        )
+        .unwrap();
+
+        assert!(space.spaces.is_empty());
+        assert_eq!(space.metrics.nom.functions_sum(), 2.);
+        assert_eq!(space.metrics.cyclomatic.cyclomatic_sum(), 3.);
+        assert_eq!(space.metrics.loc.sloc(), 2.);
+        assert_eq!(space.metrics.halstead.length(), 0.);
+    }
+
+    #[test]
+    fn python_comprehension_spaces() {
+        let source = "def f(rows):
+    key = lambda row: row[0]
+    return sorted((row for row in rows if row), key=key)";
+        let options = MetricsOptions {
+            comprehensions: ComprehensionMode::Spaces,
+            ..Default::default()
+        };
+        let space = crate::get_function_spaces_with_options(
+            &LANG::Python,
+            source.as_bytes().to_vec(),
+            std::path::Path::new("foo.py"),
+            None,
+            &options,
+        )
+        .unwrap();
+
+        let function = &space.spaces[0];
+        let closures: Vec<_> = function
+            .spaces
+            .iter()
+            .map(|space| (space.name.as_deref().unwrap(), space.kind))
+            .collect();
+        assert_eq!(
+            closures,
+            vec![
+                ("<lambda>", SpaceKind::Function),
+                ("<genexpr>", SpaceKind::Function)
+            ]
+        );
+        assert_eq!(function.metrics.nom.closures_sum(), 2.);
+        assert_eq!(function.spaces[1].metrics.cyclomatic.cyclomatic(), 3.);
+    }
+
+    #[test]
+    fn java_closure_spaces() {
+        let source = "class A {
+    void f(List<Integer> list) {
+        list.forEach(x -> {
+            if (x > 0) {
+                System.out.println(x);
+            }
+        });
+        list.forEach(x -> System.out.println(x));
+    }
+}";
+        let spaces = |closure_spaces| {
+            let options = MetricsOptions {
+                closure_spaces,
+                ..Default::default()
+            };
+            crate::get_function_spaces_with_options(
+                &LANG::Java,
+                source.as_bytes().to_vec(),
+                std::path::Path::new("A.java"),
+                None,
+                &options,
+            )
+            .unwrap()
+        };
+
+        // By default, the closures are part of their function
+        let space = spaces(None);
+        let function = &space.spaces[0].spaces[0];
+        assert!(function.spaces.is_empty());
+        assert_eq!(function.metrics.cyclomatic.cyclomatic(), 2.);
+
+        // Only the closures with enough lines are promoted
+        let space = spaces(Some(3));
+        let function = &space.spaces[0].spaces[0];
+        let closures: Vec<_> = function
+            .spaces
+            .iter()
+            .map(|space| (space.kind, space.start_line, space.end_line))
+            .collect();
+        assert_eq!(closures, vec![(SpaceKind::Function, 3, 7)]);
+        assert_eq!(function.metrics.cyclomatic.cyclomatic(), 1.);
+        assert_eq!(function.spaces[0].metrics.cyclomatic.cyclomatic(), 2.);
+        assert_eq!(function.metrics.nom.closures_sum(), 2.);
+
+        assert_eq!(spaces(Some(1)).spaces[0].spaces[0].spaces.len(), 2);
+    }
+
+    #[test]
+    fn anonymous_naming() {
+        let source = "def f(rows):
+    key = lambda row: row[0]
+    return sorted(rows, key=key, reverse=bool(lambda: 1))";
+        let names = |anonymous| {
+            let options = MetricsOptions {
+                comprehensions: ComprehensionMode::Spaces,
+                anonymous,
+                ..Default::default()
+            };
+            let space = crate::get_function_spaces_with_options(
+                &LANG::Python,
+                source.as_bytes().to_vec(),
+                std::path::Path::new("foo.py"),
+                None,
+                &options,
+            )
+            .unwrap();
+            space.spaces[0]
+                .spaces
+                .iter()
+                .map(|space| space.qualified_name.clone().unwrap())
+                .collect::<Vec<_>>()
+        };
+
+        assert_eq!(
+            names(AnonymousNaming::Default),
+            ["foo.f.<lambda>", "foo.f.<lambda>"]
+        );
+        assert_eq!(
+            names(AnonymousNaming::Enclosing),
+            ["foo.f.f#1", "foo.f.f#2"]
+        );
+        assert_eq!(
+            names(AnonymousNaming::Variable),
+            ["foo.f.key", "foo.f.<lambda@3>"]
+        );
+        assert_eq!(
+            names(AnonymousNaming::Line),
+            ["foo.f.<lambda@2>", "foo.f.<lambda@3>"]
+        );
+    }
+
+    // Returns the names of the function spaces with some flags
+    fn flagged_spaces(lang: LANG, source: &str, path: &str) -> Vec<String> {
+        fn collect(space: &FuncSpace, flagged: &mut Vec<String>) {
+            if !space.flags.is_empty() {
+                let FunctionFlags {
+                    is_async,
+                    is_generator,
+                    is_constructor,
+                    is_static,
+                    is_test,
+                    is_getter,
+                    is_setter,
+                } = space.flags;
+                let flags = [
+                    (is_async, "async"),
+                    (is_generator, "generator"),
+                    (is_constructor, "constructor"),
+                    (is_static, "static"),
+                    (is_test, "test"),
+                    (is_getter, "getter"),
+                    (is_setter, "setter"),
+                ];
+                let flags: Vec<_> = flags
+                    .iter()
+                    .filter(|(set, _)| *set)
+                    .map(|(_, flag)| *flag)
+                    .collect();
+                flagged.push(format!(
+                    "{}: {}",
+                    space.name.as_deref().unwrap_or_default(),
+                    flags.join(", ")
+                ));
+            }
+            for subspace in &space.spaces {
+                collect(subspace, flagged);
+            }
+        }
+
+        let space = crate::get_function_spaces(
+            &lang,
+            source.as_bytes().to_vec(),
+            std::path::Path::new(path),
+            None,
+        )
+        .unwrap();
+        let mut flagged = Vec::new();
+        collect(&space, &mut flagged);
+        flagged
+    }
+
+    #[test]
+    fn python_function_flags() {
+        let flagged = flagged_spaces(
+            LANG::Python,
+            "class Foo:
+    def __init__(self):
+        pass
+
+    @staticmethod
+    async def bar():
+        yield 1
+
+    def baz(self):
+        return lambda: (yield)
+
+def test_foo():
+    pass",
+            "foo.py",
+        );
+        insta::assert_debug_snapshot!(flagged, @r###"
+        [
+            "__init__: constructor",
+            "bar: async, generator, static",
+            "test_foo: test",
+        ]
+        "###);
+    }
+
+    #[test]
+    fn rust_function_flags() {
+        let flagged = flagged_spaces(
+            LANG::Rust,
+            "struct Foo;
+impl Foo {
+    fn new() -> Self { Foo }
+    fn with_name(name: &str) -> Foo { Foo }
+    pub async fn bar(&self) {}
+}
+#[cfg(test)]
+fn helper() {}
+#[test]
+fn check() {}
+#[tokio::test]
+async fn check_async() {}",
+            "foo.rs",
+        );
+        insta::assert_debug_snapshot!(flagged, @r###"
+        [
+            "new: constructor, static",
+            "with_name: constructor, static",
+            "bar: async",
+            "check: test",
+            "check_async: async, test",
+        ]
+        "###);
+    }
+
+    #[test]
+    fn javascript_function_flags() {
+        let flagged = flagged_spaces(
+            LANG::Javascript,
+            "class Foo {
+    constructor() {}
+    static async *bar() {}
+}
+function* baz() {}
+it('works', async () => {});",
+            "foo.js",
+        );
+        insta::assert_debug_snapshot!(flagged, @r###"
+        [
+            "constructor: constructor",
+            "bar: async, generator, static",
+            "baz: generator",
+            "<anonymous>: async, test",
+        ]
+        "###);
+    }
+
+    #[test]
+    fn java_function_flags() {
+        let flagged = flagged_spaces(
+            LANG::Java,
+            "class Foo {
+    Foo() {}
+    static void bar() {}
+    @Test
+    void baz() {}
+    @ParameterizedTest
+    void qux(int a) {}
+}",
+            "Foo.java",
+        );
+        insta::assert_debug_snapshot!(flagged, @r###"
+        [
+            "Foo: constructor",
+            "bar: static",
+            "baz: test",
+            "qux: test",
+        ]
+        "###);
+    }
+
+    #[test]
+    fn kotlin_function_flags() {
+        let flagged = flagged_spaces(
+            LANG::Kotlin,
+            "class Foo {
+    companion object {
+        fun create(): Foo = Foo()
+    }
+    suspend fun bar() {}
+    @Test
+    fun baz() {}
+}",
+            "foo.kt",
+        );
+        insta::assert_debug_snapshot!(flagged, @r###"
+        [
+            "create: static",
+            "bar: async",
+            "baz: test",
+        ]
+        "###);
+    }
+
+    #[test]
+    fn cpp_function_flags() {
+        let flagged = flagged_spaces(
+            LANG::Cpp,
+            "class Foo {
+    Foo() {}
+    static int bar() { return 0; }
+};
+Foo::Foo(int a) {}
+static void baz() {}",
+            "foo.cpp",
+        );
+        insta::assert_debug_snapshot!(flagged, @r###"
+        [
+            "Foo: constructor",
+            "bar: static",
+            "Foo::Foo: constructor",
+        ]
+        "###);
+    }
+
+    // Returns the names of the spaces with their decorators
+    fn decorated_spaces(lang: LANG, source: &str, path: &str) -> Vec<String> {
+        fn collect(space: &FuncSpace, decorated: &mut Vec<String>) {
+            if !space.decorators.is_empty() {
+                decorated.push(format!(
+                    "{}: {}",
+                    space.name.as_deref().unwrap_or_default(),
+                    space.decorators.join(", ")
+                ));
+            }
+            for subspace in &space.spaces {
+                collect(subspace, decorated);
+            }
+        }
+
+        let space = crate::get_function_spaces(
+            &lang,
+            source.as_bytes().to_vec(),
+            std::path::Path::new(path),
+            None,
+        )
+        .unwrap();
+        let mut decorated = Vec::new();
+        collect(&space, &mut decorated);
+        decorated
+    }
+
+    #[test]
+    fn python_decorators() {
+        let decorated = decorated_spaces(
+            LANG::Python,
+            "@dataclass
+class Foo:
+    @property
+    def bar(self):
+        pass
+
+    @app.route(\"/baz\", methods=[\"GET\"])
+    @login_required
+    def baz(self):
+        pass
+
+def qux():
+    pass",
+            "foo.py",
+        );
+        insta::assert_debug_snapshot!(decorated, @r###"
+        [
+            "Foo: dataclass",
+            "bar: property",
+            "baz: app.route, login_required",
+        ]
+        "###);
+    }
+
+    #[test]
+    fn rust_decorators() {
+        let decorated = decorated_spaces(
+            LANG::Rust,
+            "#[derive(Debug)]
+struct Foo;
+#[cfg(feature = \"foo\")]
+impl Foo {
+    #[inline]
+    #[must_use]
+    fn bar(&self) -> u32 { 0 }
+}
+/// Doc comment
+#[tokio::main]
+async fn main() {}",
+            "foo.rs",
+        );
+        insta::assert_debug_snapshot!(decorated, @r###"
+        [
+            "Foo: cfg",
+            "bar: inline, must_use",
+            "main: tokio::main",
+        ]
+        "###);
+    }
+
+    #[test]
+    fn java_decorators() {
+        let decorated = decorated_spaces(
+            LANG::Java,
+            "@Service
+public class Foo {
+    @Override
+    public String toString() { return \"\"; }
+    @Transactional(readOnly = true)
+    public void bar() {}
+    @org.junit.jupiter.api.Test
+    void baz() {}
+}",
+            "Foo.java",
+        );
+        insta::assert_debug_snapshot!(decorated, @r###"
+        [
+            "Foo: Service",
+            "toString: Override",
+            "bar: Transactional",
+            "baz: org.junit.jupiter.api.Test",
+        ]
+        "###);
+    }
+
+    #[test]
+    fn kotlin_decorators() {
+        let decorated = decorated_spaces(
+            LANG::Kotlin,
+            "@Serializable
+class Foo {
+    @Test
+    fun bar() {}
+    @Deprecated(\"Use bar\")
+    fun baz() {}
+}",
+            "foo.kt",
+        );
+        insta::assert_debug_snapshot!(decorated, @r###"
+        [
+            "Foo: Serializable",
+            "bar: Test",
+            "baz: Deprecated",
+        ]
+        "###);
+    }
+
+    #[test]
+    fn typescript_decorators() {
+        let decorated = decorated_spaces(
+            LANG::Typescript,
+            "@Component({ selector: 'app' })
+class Foo {
+    @HostListener('click')
+    onClick() {}
+    bar() {}
+}",
+            "foo.ts",
+        );
+        insta::assert_debug_snapshot!(decorated, @r###"
+        [
+            "Foo: Component",
+            "onClick: HostListener",
+        ]
+        "###);
+    }
+
+    // Returns the names of the spaces with their visibility
+    fn space_visibilities(lang: LANG, source: &str, path: &str) -> Vec<String> {
+        fn collect(space: &FuncSpace, visibilities: &mut Vec<String>) {
+            if let Some(visibility) = space.visibility {
+                visibilities.push(format!(
+                    "{}: {visibility}",
+                    space.name.as_deref().unwrap_or_default()
+                ));
+            }
+            for subspace in &space.spaces {
+                collect(subspace, visibilities);
+            }
+        }
+
+        let space = crate::get_function_spaces(
+            &lang,
+            source.as_bytes().to_vec(),
+            std::path::Path::new(path),
+            None,
+        )
+        .unwrap();
+        let mut visibilities = Vec::new();
+        collect(&space, &mut visibilities);
+        visibilities
+    }
+
+    #[test]
+    fn python_space_visibility() {
+        let visibilities = space_visibilities(
+            LANG::Python,
+            "class Foo:
+    def __init__(self):
+        pass
+
+    def _protected(self):
+        pass
+
+    def __private(self):
+        pass
+
+def _internal():
+    return lambda: 1
+
+def public():
+    pass",
+            "foo.py",
+        );
+        insta::assert_debug_snapshot!(visibilities, @r###"
+        [
+            "Foo: public",
+            "__init__: public",
+            "_protected: protected",
+            "__private: private",
+            "_internal: module-private",
+            "public: public",
+        ]
+        "###);
+    }
+
+    #[test]
+    fn rust_space_visibility() {
+        let visibilities = space_visibilities(
+            LANG::Rust,
+            "pub trait Foo {
+    fn foo(&self);
+}
+struct Bar;
+impl Foo for Bar {
+    fn foo(&self) {}
+}
+impl Bar {
+    pub fn new() -> Self { Bar }
+    pub(crate) fn krate(&self) {}
+    pub(super) fn parent(&self) {}
+    fn private(&self) {}
+}",
+            "foo.rs",
+        );
+        insta::assert_debug_snapshot!(visibilities, @r###"
+        [
+            "Foo: public",
+            "foo: public",
+            "new: public",
+            "krate: crate",
+            "parent: module-private",
+            "private: private",
+        ]
+        "###);
+    }
+
+    #[test]
+    fn java_space_visibility() {
+        let visibilities = space_visibilities(
+            LANG::Java,
+            "public class Foo {
+    public void a() {}
+    protected void b() {}
+    private void c() {}
+    void d() {}
+}
+interface Bar {
+    void e();
+    default void f() {}
+}",
+            "Foo.java",
+        );
+        insta::assert_debug_snapshot!(visibilities, @r###"
+        [
+            "Foo: public",
+            "a: public",
+            "b: protected",
+            "c: private",
+            "d: module-private",
+            "Bar: module-private",
+            "e: public",
+            "f: public",
+        ]
+        "###);
+    }
+
+    #[test]
+    fn kotlin_space_visibility() {
+        let visibilities = space_visibilities(
+            LANG::Kotlin,
+            "class Foo {
+    fun a() {}
+    private fun b() {}
+    protected fun c() {}
+    internal fun d() {}
+}",
+            "foo.kt",
+        );
+        insta::assert_debug_snapshot!(visibilities, @r###"
+        [
+            "Foo: public",
+            "a: public",
+            "b: private",
+            "c: protected",
+            "d: crate",
+        ]
+        "###);
+    }
+
+    #[test]
+    fn typescript_space_visibility() {
+        let visibilities = space_visibilities(
+            LANG::Typescript,
+            "export class Foo {
+    a() {}
+    private b() {}
+    protected c() {}
+    #d() {}
+}
+function e() {}
+export function f() {}",
+            "foo.ts",
+        );
+        insta::assert_debug_snapshot!(visibilities, @r###"
+        [
+            "Foo: public",
+            "a: public",
+            "b: private",
+            "c: protected",
+            "#d: private",
+            "e: module-private",
+            "f: public",
+        ]
+        "###);
+    }
+
+    #[test]
+    fn cpp_space_visibility() {
+        let visibilities = space_visibilities(
+            LANG::Cpp,
+            "class Foo {
+    void a() {}
+public:
+    void b() {}
+protected:
+    void c() {}
+};
+struct Bar {
+    void d() {}
+};
+void Foo::e() {}
+static void f() {}
+void g() {}",
+            "foo.cpp",
+        );
+        insta::assert_debug_snapshot!(visibilities, @r###"
+        [
+            "Foo: public",
+            "a: private",
+            "b: public",
+            "c: protected",
+            "Bar: public",
+            "d: public",
+            "f: module-private",
+            "g: public",
+        ]
+        "###);
+    }
+
+    fn parse_quality(source: &str) -> ParseQuality {
+        crate::get_function_spaces(
+            &LANG::Python,
+            source.as_bytes().to_vec(),
+            Path::new("foo.py"),
+            None,
+        )
+        .unwrap()
+        .parse_quality
+        .unwrap()
+    }
+
+    #[test]
+    fn clean_parse_quality() {
+        let quality = parse_quality("def foo(a):\n    return a\n");
+        assert!(quality.is_clean());
+        assert_eq!(quality.score, 1.);
+    }
+
+    #[test]
+    fn broken_parse_quality() {
+        insta::assert_debug_snapshot!(parse_quality("def foo(a:\n    return a\n\nb = 1\n"), @r###"
+        ParseQuality {
+            errors: 2,
+            missing: 0,
+            nodes: 15,
+            score: 0.06666666666666665,
+        }
+        "###);
+        insta::assert_debug_snapshot!(parse_quality("x = (1 + 2\n"), @r###"
+        ParseQuality {
+            errors: 1,
+            missing: 0,
+            nodes: 9,
+            score: 0.11111111111111116,
+        }
+        "###);
+    }
+
+    #[test]
+    fn parse_quality_serialization() {
+        let space = |source: &str| {
+            let space = crate::get_function_spaces(
+                &LANG::Python,
+                source.as_bytes().to_vec(),
+                Path::new("foo.py"),
+                None,
+            )
+            .unwrap();
+            serde_json::to_value(space).unwrap()
+        };
+        assert!(space("a = 1\n").get("parse_quality").is_none());
+        assert!(space("a = (1\n").get("parse_quality").is_some());
+    }
+
+    #[test]
+    fn parse_warnings() {
+        let space = crate::get_function_spaces(
+            &LANG::Rust,
+            b"fn foo() {\n    let a = ;\n}\n\nfn bar(a: i32) -> i32 {\n    a + 1\n}\n\nfn baz() { 1 + }\n"
+                .to_vec(),
+            Path::new("foo.rs"),
+            None,
+        )
+        .unwrap();
+
+        // The functions are computed despite the syntax errors
+        let names: Vec<_> = space
+            .spaces
+            .iter()
+            .map(|space| space.name.as_deref().unwrap_or_default())
+            .collect();
+        assert_eq!(names, ["foo", "bar", "baz"]);
+        insta::assert_debug_snapshot!(space.warnings, @r###"
+        [
+            ParseWarning {
+                kind: Error,
+                start_line: 2,
+                start_column: 11,
+                end_line: 2,
+                end_column: 12,
+                message: "Syntax error",
+            },
+            ParseWarning {
+                kind: Missing,
+                start_line: 9,
+                start_column: 15,
+                end_line: 9,
+                end_column: 15,
+                message: "Missing `identifier`",
+            },
+        ]
+        "###);
+    }
 }
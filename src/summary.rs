@@ -0,0 +1,731 @@
+use std::collections::BTreeMap;
+use std::fmt;
+use std::path::Path;
+
+use serde::Serialize;
+use serde::ser::{SerializeMap, Serializer};
+
+use crate::cocomo::{Cocomo, CocomoOptions};
+use crate::langs::LANG;
+use crate::spaces::{FuncSpace, SpaceKind};
+use crate::test_code::TestPatterns;
+
+// Width of the tables printed by cloc
+const TABLE_WIDTH: usize = 79;
+
+/// The lines of the files written in a language.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize)]
+pub struct LanguageSummary {
+    /// The number of files
+    #[serde(rename = "nFiles")]
+    pub files: usize,
+    /// The number of blank lines
+    pub blank: usize,
+    /// The number of lines containing only comments
+    pub comment: usize,
+    /// The number of lines containing code
+    pub code: usize,
+}
+
+impl LanguageSummary {
+    /// Merges a second summary into the first one.
+    pub fn merge(&mut self, other: &LanguageSummary) {
+        self.files += other.files;
+        self.blank += other.blank;
+        self.comment += other.comment;
+        self.code += other.code;
+    }
+
+    /// Returns the total number of lines.
+    pub fn lines(&self) -> usize {
+        self.blank + self.comment + self.code
+    }
+}
+
+/// The lines of the analyzed files, grouped by language,
+/// in the shape of the output of cloc.
+#[derive(Clone, Debug, Default)]
+pub struct Summary {
+    languages: BTreeMap<&'static str, LanguageSummary>,
+}
+
+// Returns the name given by cloc to a language,
+// which distinguishes C from C++ by the extension of the file
+fn cloc_name(language: LANG, path: &Path) -> &'static str {
+    match language {
+        LANG::Mozjs | LANG::Javascript => "JavaScript",
+        LANG::Java => "Java",
+        LANG::Kotlin => "Kotlin",
+        LANG::Rust => "Rust",
+        LANG::Python => "Python",
+        LANG::Tsx | LANG::Typescript => "TypeScript",
+        LANG::Cpp | LANG::Ccomment | LANG::Preproc => {
+            match path.extension().and_then(|ext| ext.to_str()) {
+                Some("c") => "C",
+                Some("h") => "C/C++ Header",
+                _ => "C++",
+            }
+        }
+    }
+}
+
+impl Summary {
+    /// Adds the lines of a file to the summary.
+    ///
+    /// * `language` - The language of the file.
+    /// * `space` - The root function space of the file.
+    ///
+    /// The lines containing both code and comments are counted as code.
+    pub fn add(&mut self, language: LANG, space: &FuncSpace) {
+        let path = Path::new(space.name.as_deref().unwrap_or_default());
+        let loc = &space.metrics.loc;
+        let code = loc.ploc() as usize;
+        let blank = loc.blank() as usize;
+        let comment = (loc.sloc() as usize).saturating_sub(code + blank);
+
+        self.languages
+            .entry(cloc_name(language, path))
+            .or_default()
+            .merge(&LanguageSummary {
+                files: 1,
+                blank,
+                comment,
+                code,
+            });
+    }
+
+    /// Merges a second summary into the first one.
+    pub fn merge(&mut self, other: &Summary) {
+        for (name, language) in &other.languages {
+            self.languages.entry(name).or_default().merge(language);
+        }
+    }
+
+    /// Returns the summaries of the languages, sorted as cloc does
+    /// from the one with the most lines of code to the one with the least.
+    pub fn languages(&self) -> Vec<(&'static str, LanguageSummary)> {
+        let mut languages: Vec<_> = self
+            .languages
+            .iter()
+            .map(|(name, language)| (*name, *language))
+            .collect();
+        languages.sort_by_key(|(_, language)| std::cmp::Reverse(language.code));
+        languages
+    }
+
+    /// Estimates the development of the project with the COCOMO model,
+    /// from the lines of code of all the languages.
+    pub fn cocomo(&self, options: &CocomoOptions) -> Cocomo {
+        Cocomo::new(self.total().code, options)
+    }
+
+    /// Returns the summary of all the languages.
+    pub fn total(&self) -> LanguageSummary {
+        let mut total = LanguageSummary::default();
+        for language in self.languages.values() {
+            total.merge(language);
+        }
+        total
+    }
+}
+
+fn write_row(f: &mut fmt::Formatter, name: &str, columns: [&dyn fmt::Display; 4]) -> fmt::Result {
+    let [files, blank, comment, code] = columns;
+    writeln!(f, "{name:<25}{files:>9}{blank:>15}{comment:>15}{code:>15}")
+}
+
+/// Prints the summary as the table printed by cloc.
+impl fmt::Display for Summary {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let separator = "-".repeat(TABLE_WIDTH);
+
+        writeln!(f, "{separator}")?;
+        write_row(f, "Language", [&"files", &"blank", &"comment", &"code"])?;
+        writeln!(f, "{separator}")?;
+        for (name, language) in self.languages() {
+            write_row(
+                f,
+                name,
+                [
+                    &language.files,
+                    &language.blank,
+                    &language.comment,
+                    &language.code,
+                ],
+            )?;
+        }
+        let total = self.total();
+        writeln!(f, "{separator}")?;
+        write_row(
+            f,
+            "SUM:",
+            [&total.files, &total.blank, &total.comment, &total.code],
+        )?;
+        write!(f, "{separator}")
+    }
+}
+
+#[derive(Serialize)]
+struct Header {
+    n_files: usize,
+    n_lines: usize,
+}
+
+/// Serializes the summary as the output of cloc's `--json` option,
+/// with a `header`, an entry for each language and a `SUM` entry.
+impl Serialize for Summary {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        let total = self.total();
+        let mut map = serializer.serialize_map(Some(self.languages.len() + 2))?;
+        map.serialize_entry(
+            "header",
+            &Header {
+                n_files: total.files,
+                n_lines: total.lines(),
+            },
+        )?;
+        for (name, language) in self.languages() {
+            map.serialize_entry(name, &language)?;
+        }
+        map.serialize_entry("SUM", &total)?;
+        map.end()
+    }
+}
+
+/// The metrics of the files written in a language, aggregated
+/// over their functions.
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct LanguageMetrics {
+    /// The number of files
+    pub files: usize,
+    /// The number of source lines
+    pub sloc: usize,
+    /// The number of functions
+    pub functions: usize,
+    /// The sum of the cyclomatic complexities of the functions
+    pub cyclomatic: f64,
+    /// The highest cyclomatic complexity of a function
+    pub cyclomatic_max: f64,
+    /// The sum of the cognitive complexities of the functions
+    pub cognitive: f64,
+}
+
+impl LanguageMetrics {
+    /// Merges a second summary into the first one.
+    pub fn merge(&mut self, other: &LanguageMetrics) {
+        self.files += other.files;
+        self.sloc += other.sloc;
+        self.functions += other.functions;
+        self.cyclomatic += other.cyclomatic;
+        self.cyclomatic_max = self.cyclomatic_max.max(other.cyclomatic_max);
+        self.cognitive += other.cognitive;
+    }
+
+    /// Returns the mean cyclomatic complexity of the functions.
+    pub fn cyclomatic_average(&self) -> f64 {
+        if self.functions == 0 {
+            0.
+        } else {
+            self.cyclomatic / self.functions as f64
+        }
+    }
+
+    /// Returns the mean cognitive complexity of the functions.
+    pub fn cognitive_average(&self) -> f64 {
+        if self.functions == 0 {
+            0.
+        } else {
+            self.cognitive / self.functions as f64
+        }
+    }
+
+    fn add_function(&mut self, space: &FuncSpace) {
+        // The complexity of each function does not include
+        // the complexity of its nested functions
+        let cyclomatic = space.metrics.cyclomatic.cyclomatic();
+        self.functions += 1;
+        self.cyclomatic += cyclomatic;
+        self.cyclomatic_max = self.cyclomatic_max.max(cyclomatic);
+        self.cognitive += space.metrics.cognitive.cognitive();
+    }
+}
+
+// Adds the functions of a space to the production metrics or,
+// for the test functions and the functions nested in them,
+// to the test metrics, which also get the lines of the test functions
+fn add_functions(
+    space: &FuncSpace,
+    production: &mut LanguageMetrics,
+    test: &mut LanguageMetrics,
+    in_test: bool,
+) {
+    let is_test = in_test || space.flags.is_test;
+    if is_test && !in_test {
+        let sloc = space.metrics.loc.sloc() as usize;
+        production.sloc = production.sloc.saturating_sub(sloc);
+        test.sloc += sloc;
+    }
+    if space.kind == SpaceKind::Function {
+        if is_test {
+            test.add_function(space);
+        } else {
+            production.add_function(space);
+        }
+    }
+    for subspace in &space.spaces {
+        add_functions(subspace, production, test, is_test);
+    }
+}
+
+impl Serialize for LanguageMetrics {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        use serde::ser::SerializeStruct;
+
+        let mut st = serializer.serialize_struct("language_metrics", 8)?;
+        st.serialize_field("files", &self.files)?;
+        st.serialize_field("sloc", &self.sloc)?;
+        st.serialize_field("functions", &self.functions)?;
+        st.serialize_field("cyclomatic_sum", &self.cyclomatic)?;
+        st.serialize_field("cyclomatic_average", &self.cyclomatic_average())?;
+        st.serialize_field("cyclomatic_max", &self.cyclomatic_max)?;
+        st.serialize_field("cognitive_sum", &self.cognitive)?;
+        st.serialize_field("cognitive_average", &self.cognitive_average())?;
+        st.end()
+    }
+}
+
+// Returns the metrics of some languages, from the one with
+// the most source lines to the one with the least
+fn sorted_languages(
+    languages: &BTreeMap<&'static str, LanguageMetrics>,
+) -> Vec<(&'static str, LanguageMetrics)> {
+    let mut languages: Vec<_> = languages
+        .iter()
+        .map(|(name, language)| (*name, *language))
+        .collect();
+    languages.sort_by_key(|(_, language)| std::cmp::Reverse(language.sloc));
+    languages
+}
+
+fn total_metrics(languages: &BTreeMap<&'static str, LanguageMetrics>) -> LanguageMetrics {
+    let mut total = LanguageMetrics::default();
+    for language in languages.values() {
+        total.merge(language);
+    }
+    total
+}
+
+/// The metrics of the analyzed files, aggregated by language,
+/// to report separately the languages of a mixed-language project.
+///
+/// The test code is aggregated apart from the production code,
+/// so that the tests do not distort the metrics of the production code.
+#[derive(Clone, Debug, Default)]
+pub struct MetricsSummary {
+    languages: BTreeMap<&'static str, LanguageMetrics>,
+    tests: BTreeMap<&'static str, LanguageMetrics>,
+    test_patterns: TestPatterns,
+}
+
+impl MetricsSummary {
+    /// Creates a summary classifying the test files with some patterns.
+    pub fn new(test_patterns: TestPatterns) -> Self {
+        Self {
+            test_patterns,
+            ..Self::default()
+        }
+    }
+
+    /// Adds the metrics of a file to the summary.
+    ///
+    /// * `language` - The language of the file.
+    /// * `space` - The root function space of the file.
+    ///
+    /// All the code of a test file is test code, while the test functions
+    /// of a production file are test code in a production file.
+    pub fn add(&mut self, language: LANG, space: &FuncSpace) {
+        let path = Path::new(space.name.as_deref().unwrap_or_default());
+        let is_test_file = self.test_patterns.is_test_file(path);
+        let file = LanguageMetrics {
+            files: 1,
+            sloc: space.metrics.loc.sloc() as usize,
+            ..LanguageMetrics::default()
+        };
+        let (mut production, mut test) = if is_test_file {
+            (LanguageMetrics::default(), file)
+        } else {
+            (file, LanguageMetrics::default())
+        };
+        add_functions(space, &mut production, &mut test, is_test_file);
+
+        let name = cloc_name(language, path);
+        if production != LanguageMetrics::default() {
+            self.languages.entry(name).or_default().merge(&production);
+        }
+        if test != LanguageMetrics::default() {
+            self.tests.entry(name).or_default().merge(&test);
+        }
+    }
+
+    /// Merges a second summary into the first one.
+    pub fn merge(&mut self, other: &MetricsSummary) {
+        for (name, language) in &other.languages {
+            self.languages.entry(name).or_default().merge(language);
+        }
+        for (name, language) in &other.tests {
+            self.tests.entry(name).or_default().merge(language);
+        }
+    }
+
+    /// Returns the metrics of the production code of the languages,
+    /// from the one with the most source lines to the one with the least.
+    pub fn languages(&self) -> Vec<(&'static str, LanguageMetrics)> {
+        sorted_languages(&self.languages)
+    }
+
+    /// Returns the metrics of the production code of all the languages.
+    pub fn total(&self) -> LanguageMetrics {
+        total_metrics(&self.languages)
+    }
+
+    /// Returns the metrics of the test code of the languages,
+    /// from the one with the most source lines to the one with the least.
+    pub fn test_languages(&self) -> Vec<(&'static str, LanguageMetrics)> {
+        sorted_languages(&self.tests)
+    }
+
+    /// Returns the metrics of the test code of all the languages.
+    pub fn test_total(&self) -> LanguageMetrics {
+        total_metrics(&self.tests)
+    }
+}
+
+fn write_metrics_row(
+    f: &mut fmt::Formatter,
+    name: &str,
+    columns: [&dyn fmt::Display; 6],
+) -> fmt::Result {
+    let [
+        files,
+        sloc,
+        functions,
+        cyclomatic,
+        cyclomatic_max,
+        cognitive,
+    ] = columns;
+    writeln!(
+        f,
+        "{name:<19}{files:>8}{sloc:>10}{functions:>11}{cyclomatic:>10}{cyclomatic_max:>10}{cognitive:>11}"
+    )
+}
+
+fn write_language_metrics(
+    f: &mut fmt::Formatter,
+    name: &str,
+    language: &LanguageMetrics,
+) -> fmt::Result {
+    write_metrics_row(
+        f,
+        name,
+        [
+            &language.files,
+            &language.sloc,
+            &language.functions,
+            &format!("{:.2}", language.cyclomatic_average()),
+            &language.cyclomatic_max,
+            &format!("{:.2}", language.cognitive_average()),
+        ],
+    )
+}
+
+fn write_metrics_table(
+    f: &mut fmt::Formatter,
+    title: &str,
+    languages: &[(&'static str, LanguageMetrics)],
+    total: &LanguageMetrics,
+) -> fmt::Result {
+    let separator = "-".repeat(TABLE_WIDTH);
+
+    writeln!(f, "{separator}")?;
+    write_metrics_row(
+        f,
+        title,
+        [
+            &"files",
+            &"sloc",
+            &"functions",
+            &"mean CC",
+            &"max CC",
+            &"mean cogn",
+        ],
+    )?;
+    writeln!(f, "{separator}")?;
+    for (name, language) in languages {
+        write_language_metrics(f, name, language)?;
+    }
+    writeln!(f, "{separator}")?;
+    write_language_metrics(f, "SUM:", total)?;
+    write!(f, "{separator}")
+}
+
+/// Prints the summary as a table, with a row for each language
+/// and the mean complexities of their functions, followed by
+/// the table of the test code when there is some.
+impl fmt::Display for MetricsSummary {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write_metrics_table(f, "Language", &self.languages(), &self.total())?;
+        if !self.tests.is_empty() {
+            write!(f, "\n\n")?;
+            write_metrics_table(f, "Tests", &self.test_languages(), &self.test_total())?;
+        }
+        Ok(())
+    }
+}
+
+// The metrics of some languages, serialized as an entry
+// for each language and a `SUM` entry
+struct LanguagesMap(Vec<(&'static str, LanguageMetrics)>, LanguageMetrics);
+
+impl Serialize for LanguagesMap {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        let LanguagesMap(languages, total) = self;
+        let mut map = serializer.serialize_map(Some(languages.len() + 1))?;
+        for (name, language) in languages {
+            map.serialize_entry(name, language)?;
+        }
+        map.serialize_entry("SUM", total)?;
+        map.end()
+    }
+}
+
+/// Serializes the summary as an entry for each language
+/// and a `SUM` entry, with the same entries for the test code
+/// in a `tests` entry.
+impl Serialize for MetricsSummary {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        let mut map = serializer.serialize_map(Some(self.languages.len() + 2))?;
+        for (name, language) in self.languages() {
+            map.serialize_entry(name, &language)?;
+        }
+        map.serialize_entry("SUM", &self.total())?;
+        map.serialize_entry(
+            "tests",
+            &LanguagesMap(self.test_languages(), self.test_total()),
+        )?;
+        map.end()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::langs::get_function_spaces;
+
+    fn summary() -> Summary {
+        let mut summary = Summary::default();
+        for (language, path, source) in [
+            (
+                LANG::Python,
+                "foo.py",
+                "# A comment\n\ndef foo():\n    return 42  # The answer\n",
+            ),
+            (LANG::Python, "bar.py", "a = 1\n\n\nb = 2\n"),
+            (LANG::Cpp, "foo.c", "/* A\n   comment */\nint a = 42;\n"),
+        ] {
+            let space =
+                get_function_spaces(&language, source.as_bytes().to_vec(), Path::new(path), None)
+                    .unwrap();
+            summary.add(language, &space);
+        }
+        summary
+    }
+
+    #[test]
+    fn cloc_table() {
+        insta::assert_snapshot!(summary(), @r###"
+        -------------------------------------------------------------------------------
+        Language                     files          blank        comment           code
+        -------------------------------------------------------------------------------
+        Python                           2              3              1              4
+        C                                1              0              2              1
+        -------------------------------------------------------------------------------
+        SUM:                             3              3              3              5
+        -------------------------------------------------------------------------------
+        "###);
+    }
+
+    #[test]
+    fn cloc_json() {
+        insta::assert_json_snapshot!(summary(), @r###"
+        {
+          "header": {
+            "n_files": 3,
+            "n_lines": 11
+          },
+          "Python": {
+            "nFiles": 2,
+            "blank": 3,
+            "comment": 1,
+            "code": 4
+          },
+          "C": {
+            "nFiles": 1,
+            "blank": 0,
+            "comment": 2,
+            "code": 1
+          },
+          "SUM": {
+            "nFiles": 3,
+            "blank": 3,
+            "comment": 3,
+            "code": 5
+          }
+        }
+        "###);
+    }
+
+    fn metrics_summary() -> MetricsSummary {
+        let mut summary = MetricsSummary::default();
+        for (language, path, source) in [
+            (
+                LANG::Python,
+                "foo.py",
+                "def foo(a):\n    if a:\n        return 1\n    return lambda: 2\n",
+            ),
+            (LANG::Python, "bar.py", "a = 1\n"),
+            (
+                LANG::Rust,
+                "foo.rs",
+                "fn foo(a: bool) -> u8 {\n    if a { 1 } else if !a { 2 } else { 3 }\n}\n\nfn bar() {}\n",
+            ),
+        ] {
+            let space =
+                get_function_spaces(&language, source.as_bytes().to_vec(), Path::new(path), None)
+                    .unwrap();
+            summary.add(language, &space);
+        }
+        summary
+    }
+
+    #[test]
+    fn metrics_summary_table() {
+        insta::assert_snapshot!(metrics_summary(), @r###"
+        -------------------------------------------------------------------------------
+        Language              files      sloc  functions   mean CC    max CC  mean cogn
+        -------------------------------------------------------------------------------
+        Python                    2         5          1      2.00         2       1.00
+        Rust                      1         5          2      2.00         3       1.50
+        -------------------------------------------------------------------------------
+        SUM:                      3        10          3      2.00         3       1.33
+        -------------------------------------------------------------------------------
+        "###);
+    }
+
+    #[test]
+    fn metrics_summary_json() {
+        insta::assert_json_snapshot!(metrics_summary(), @r###"
+        {
+          "Python": {
+            "files": 2,
+            "sloc": 5,
+            "functions": 1,
+            "cyclomatic_sum": 2.0,
+            "cyclomatic_average": 2.0,
+            "cyclomatic_max": 2.0,
+            "cognitive_sum": 1.0,
+            "cognitive_average": 1.0
+          },
+          "Rust": {
+            "files": 1,
+            "sloc": 5,
+            "functions": 2,
+            "cyclomatic_sum": 4.0,
+            "cyclomatic_average": 2.0,
+            "cyclomatic_max": 3.0,
+            "cognitive_sum": 3.0,
+            "cognitive_average": 1.5
+          },
+          "SUM": {
+            "files": 3,
+            "sloc": 10,
+            "functions": 3,
+            "cyclomatic_sum": 6.0,
+            "cyclomatic_average": 2.0,
+            "cyclomatic_max": 3.0,
+            "cognitive_sum": 4.0,
+            "cognitive_average": 1.3333333333333333
+          },
+          "tests": {
+            "SUM": {
+              "files": 0,
+              "sloc": 0,
+              "functions": 0,
+              "cyclomatic_sum": 0.0,
+              "cyclomatic_average": 0.0,
+              "cyclomatic_max": 0.0,
+              "cognitive_sum": 0.0,
+              "cognitive_average": 0.0
+            }
+          }
+        }
+        "###);
+    }
+
+    #[test]
+    fn metrics_summary_test_code() {
+        let mut summary = MetricsSummary::default();
+        for (language, path, source) in [
+            (
+                LANG::Python,
+                "src/foo.py",
+                "def foo(a):\n    if a:\n        return 1\n    return 2\n\n\ndef test_foo():\n    assert foo(1) == 1\n",
+            ),
+            (
+                LANG::Python,
+                "tests/bar.py",
+                "def helper(a):\n    return a or 1\n",
+            ),
+            (
+                LANG::Rust,
+                "src/foo.rs",
+                "fn foo() {}\n\n#[test]\nfn check() {\n    let f = || if true { 1 } else { 2 };\n}\n",
+            ),
+        ] {
+            let space =
+                get_function_spaces(&language, source.as_bytes().to_vec(), Path::new(path), None)
+                    .unwrap();
+            summary.add(language, &space);
+        }
+        insta::assert_snapshot!(summary, @r###"
+        -------------------------------------------------------------------------------
+        Language              files      sloc  functions   mean CC    max CC  mean cogn
+        -------------------------------------------------------------------------------
+        Python                    1         6          1      2.00         2       1.00
+        Rust                      1         3          1      1.00         1       0.00
+        -------------------------------------------------------------------------------
+        SUM:                      2         9          2      1.50         2       0.50
+        -------------------------------------------------------------------------------
+
+        -------------------------------------------------------------------------------
+        Tests                 files      sloc  functions   mean CC    max CC  mean cogn
+        -------------------------------------------------------------------------------
+        Python                    1         4          2      2.00         2       0.50
+        Rust                      0         3          2      1.50         2       1.50
+        -------------------------------------------------------------------------------
+        SUM:                      1         7          4      1.75         2       1.00
+        -------------------------------------------------------------------------------
+        "###);
+    }
+}
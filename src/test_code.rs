@@ -0,0 +1,102 @@
+use std::path::Path;
+
+use globset::{Glob, GlobSet, GlobSetBuilder};
+
+/// The patterns of the paths of the test files, used when none is given.
+///
+/// They match the files in the `tests`, `test` and `__tests__` directories,
+/// and the files named as tests by the usual conventions of the languages,
+/// such as `test_foo.py`, `foo.spec.ts` or `FooTest.java`.
+pub const DEFAULT_TEST_PATTERNS: &[&str] = &[
+    "**/tests/**",
+    "**/test/**",
+    "**/__tests__/**",
+    "**/test_*.py",
+    "**/*_test.py",
+    "**/conftest.py",
+    "**/*.spec.*",
+    "**/*.test.*",
+    "**/*Test.java",
+    "**/*Tests.java",
+    "**/*Test.kt",
+    "**/*Tests.kt",
+    "**/*_test.rs",
+    "**/*_test.c",
+    "**/*_test.cc",
+    "**/*_test.cpp",
+    "**/*_unittest.cc",
+];
+
+/// The patterns classifying the files as test code.
+///
+/// A function space is test code when it is in a test file, when it is
+/// a test function, found through its attributes, its annotations
+/// or its name, or when it is nested in a test function.
+#[derive(Clone, Debug)]
+pub struct TestPatterns {
+    globs: GlobSet,
+}
+
+impl Default for TestPatterns {
+    fn default() -> Self {
+        Self::new(DEFAULT_TEST_PATTERNS).unwrap()
+    }
+}
+
+impl TestPatterns {
+    /// Builds the patterns from a list of globs,
+    /// such as `**/tests/**` or `**/*.spec.ts`.
+    pub fn new<S: AsRef<str>>(patterns: &[S]) -> Result<Self, globset::Error> {
+        let mut globs = GlobSetBuilder::new();
+        for pattern in patterns {
+            globs.add(Glob::new(pattern.as_ref())?);
+        }
+        Ok(Self {
+            globs: globs.build()?,
+        })
+    }
+
+    /// Checks if a file is a test file.
+    pub fn is_test_file(&self, path: &Path) -> bool {
+        self.globs.is_match(path)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn default_test_patterns() {
+        let patterns = TestPatterns::default();
+        for path in [
+            "tests/foo.py",
+            "/src/project/test/Foo.java",
+            "web/__tests__/app.js",
+            "pkg/test_foo.py",
+            "pkg/foo_test.py",
+            "src/app.spec.ts",
+            "src/App.test.tsx",
+            "src/main/FooTest.java",
+            "src/parser_test.rs",
+        ] {
+            assert!(patterns.is_test_file(Path::new(path)), "{path}");
+        }
+        for path in [
+            "src/foo.py",
+            "src/testing.rs",
+            "src/Tester.java",
+            "latest/a.py",
+        ] {
+            assert!(!patterns.is_test_file(Path::new(path)), "{path}");
+        }
+    }
+
+    #[test]
+    fn custom_test_patterns() {
+        let patterns = TestPatterns::new(&["**/*_check.py"]).unwrap();
+        assert!(patterns.is_test_file(Path::new("src/foo_check.py")));
+        assert!(!patterns.is_test_file(Path::new("tests/foo.py")));
+        assert!(TestPatterns::new(&["[a"]).is_err());
+    }
+}
@@ -0,0 +1,149 @@
+use serde::Serialize;
+
+use crate::node::Node;
+use crate::traits::*;
+
+/// A token of a code, a leaf of its syntax tree.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize)]
+pub struct Token {
+    /// The kind of the token in the grammar of the language,
+    /// such as `identifier`, `(` or `comment`
+    pub kind: &'static str,
+    /// The text of the token
+    pub text: String,
+    /// The line where the token starts, starting from 1
+    pub start_line: usize,
+    /// The column where the token starts, in bytes, starting from 1
+    pub start_column: usize,
+    /// The line where the token ends, starting from 1
+    pub end_line: usize,
+    /// The column following the end of the token, in bytes, starting from 1
+    pub end_column: usize,
+    /// The offset of the first byte of the token in the code
+    pub start_byte: usize,
+    /// The offset following the last byte of the token in the code
+    pub end_byte: usize,
+}
+
+// Checks if the children of a node do not cover all its text, except for
+// the whitespaces, as the `//` child of a comment, so it is a single token
+fn is_partially_covered(node: &Node, code: &[u8]) -> bool {
+    let mut start = node.start_byte();
+    node.children()
+        .map(|child| (child.start_byte(), child.end_byte()))
+        .chain(std::iter::once((node.end_byte(), node.end_byte())))
+        .any(|(child_start, child_end)| {
+            let gap = &code[start.min(child_start)..child_start];
+            start = start.max(child_end);
+            !gap.iter().all(u8::is_ascii_whitespace)
+        })
+}
+
+/// Returns the tokens of a code, in the order of the code.
+///
+/// The tokens are the leaves of the syntax tree, including the comments,
+/// and the nodes whose children do not cover all their text, such as the
+/// comments starting with a `//` child, which are not split.
+/// The empty tokens, such as the ones inserted to recover
+/// from a syntax error, are skipped.
+pub fn tokens<T: ParserTrait>(parser: &T) -> Vec<Token> {
+    let code = parser.get_code();
+    let mut tokens = Vec::new();
+    let mut stack = vec![parser.get_root()];
+    while let Some(node) = stack.pop() {
+        if node.child_count() > 0 && !is_partially_covered(&node, code) {
+            let start = stack.len();
+            stack.extend(node.children());
+            stack[start..].reverse();
+        } else if node.start_byte() < node.end_byte() {
+            let (start_row, start_column) = node.start_position();
+            let (end_row, end_column) = node.end_position();
+            tokens.push(Token {
+                kind: node.kind(),
+                text: String::from_utf8_lossy(&code[node.start_byte()..node.end_byte()])
+                    .into_owned(),
+                start_line: start_row + 1,
+                start_column: start_column + 1,
+                end_line: end_row + 1,
+                end_column: end_column + 1,
+                start_byte: node.start_byte(),
+                end_byte: node.end_byte(),
+            });
+        }
+    }
+    tokens
+}
+
+#[cfg(test)]
+mod tests {
+    use std::path::Path;
+
+    use pretty_assertions::assert_eq;
+
+    use crate::{LANG, get_tokens};
+
+    fn kinds_and_texts(language: LANG, path: &str, code: &str) -> Vec<(&'static str, String)> {
+        get_tokens(&language, code.as_bytes().to_vec(), Path::new(path), None)
+            .into_iter()
+            .map(|token| (token.kind, token.text))
+            .collect()
+    }
+
+    #[test]
+    fn rust_tokens() {
+        let tokens = get_tokens(
+            &LANG::Rust,
+            b"fn foo() {\n    // bar\n    1\n}\n".to_vec(),
+            Path::new("foo.rs"),
+            None,
+        );
+        insta::assert_json_snapshot!(tokens[..2], @r###"
+        [
+          {
+            "kind": "fn",
+            "text": "fn",
+            "start_line": 1,
+            "start_column": 1,
+            "end_line": 1,
+            "end_column": 3,
+            "start_byte": 0,
+            "end_byte": 2
+          },
+          {
+            "kind": "identifier",
+            "text": "foo",
+            "start_line": 1,
+            "start_column": 4,
+            "end_line": 1,
+            "end_column": 7,
+            "start_byte": 3,
+            "end_byte": 6
+          }
+        ]
+        "###);
+        assert_eq!(
+            tokens
+                .iter()
+                .map(|token| token.text.as_str())
+                .collect::<Vec<_>>(),
+            ["fn", "foo", "(", ")", "{", "// bar", "1", "}"]
+        );
+    }
+
+    #[test]
+    fn python_tokens() {
+        assert_eq!(
+            kinds_and_texts(LANG::Python, "foo.py", "x = f'{a}'  # b\n"),
+            [
+                ("identifier", "x".to_owned()),
+                ("=", "=".to_owned()),
+                ("string_start", "f'".to_owned()),
+                ("{", "{".to_owned()),
+                ("identifier", "a".to_owned()),
+                ("}", "}".to_owned()),
+                ("string_end", "'".to_owned()),
+                ("comment", "# b".to_owned()),
+            ]
+        );
+    }
+}
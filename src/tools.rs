@@ -1,13 +1,15 @@
 use std::cmp::Ordering;
-use std::collections::HashMap;
+use std::collections::{BTreeMap, BTreeSet, HashMap, HashSet};
 use std::fs::{self, File};
 use std::io::{Read, Write};
 use std::path::{Component, Path, PathBuf};
 use std::sync::OnceLock;
 
 use regex::bytes::Regex;
+use serde::{Serialize, Serializer};
 use termcolor::{Color, ColorSpec, StandardStreamLock, WriteColor};
 
+use crate::encoding::{Encoding, detect_encoding};
 use crate::langs::fake;
 use crate::langs::*;
 
@@ -33,8 +35,24 @@ pub fn read_file(path: &Path) -> std::io::Result<Vec<u8>> {
     Ok(data)
 }
 
+// Transcodes a code to UTF-8 without a bom, if it is a text
+fn transcode(mut data: Vec<u8>) -> Option<Vec<u8>> {
+    match detect_encoding(&data)? {
+        Encoding::Utf8 => {
+            if data.starts_with(b"\xEF\xBB\xBF") {
+                data.drain(..3);
+            }
+            Some(data)
+        }
+        encoding => Some(encoding.decode(&data)),
+    }
+}
+
 /// Reads a file and adds an `EOL` at its end.
 ///
+/// The code is transcoded to `UTF-8` from the encoding detected
+/// by [`detect_encoding`], and `None` is returned if it is not a text.
+///
 /// # Examples
 ///
 /// ```
@@ -55,38 +73,122 @@ pub fn read_file_with_eol(path: &Path) -> std::io::Result<Option<Vec<u8>>> {
     let mut file = File::open(path)?;
 
     let mut start = vec![0; 64.min(file_size)];
-    let start = if file.read_exact(&mut start).is_ok() {
-        // Skip the bom if one
-        if start[..2] == [b'\xFE', b'\xFF'] || start[..2] == [b'\xFF', b'\xFE'] {
-            &start[2..]
-        } else if start[..3] == [b'\xEF', b'\xBB', b'\xBF'] {
-            &start[3..]
-        } else {
-            &start
-        }
-    } else {
+    if file.read_exact(&mut start).is_err() {
         return Ok(None);
-    };
+    }
 
     // so start contains more or less 64 chars
-    let mut head = String::from_utf8_lossy(start).into_owned();
-    // The last char could be wrong because we were in the middle of an utf-8 sequence
-    head.pop();
-    // now check if there is an invalid char
-    if head.contains('\u{FFFD}') {
+    if detect_encoding(&start).is_none() {
         return Ok(None);
     }
 
     let mut data = Vec::with_capacity(file_size + 2);
-    data.extend_from_slice(start);
+    data.extend_from_slice(&start);
 
     file.read_to_end(&mut data)?;
 
+    let Some(mut data) = transcode(data) else {
+        return Ok(None);
+    };
     remove_blank_lines(&mut data);
 
     Ok(Some(data))
 }
 
+/// Prepares an in-memory code in the same way [`read_file_with_eol`]
+/// does for a file, adding an `EOL` at its end.
+///
+/// Returns `None` if the code is almost empty or is not a text.
+///
+/// # Examples
+///
+/// ```
+/// use rust_code_analysis::read_bytes_with_eol;
+///
+/// let data = b"\xEF\xBB\xBFint a = 42;";
+///
+/// assert_eq!(read_bytes_with_eol(data), Some(b"int a = 42;\n".to_vec()));
+/// ```
+///
+/// [`read_file_with_eol`]: fn.read_file_with_eol.html
+pub fn read_bytes_with_eol(data: &[u8]) -> Option<Vec<u8>> {
+    if data.len() <= 3 {
+        return None;
+    }
+
+    detect_encoding(data)?;
+
+    let mut data = transcode(data.to_vec())?;
+    remove_blank_lines(&mut data);
+
+    Some(data)
+}
+
+// Peak memory needed to analyze a byte of code, measured on large
+// machine-generated C files: the syntax tree takes about a third of it,
+// the spaces and their Halstead operators and operands most of the rest
+const MEMORY_PER_CODE_BYTE: u64 = 64;
+// Peak memory needed to analyze a byte of code with `MetricsOptions::unit_only`
+const UNIT_MEMORY_PER_CODE_BYTE: u64 = 24;
+
+/// Estimates the peak amount of memory, in bytes, needed to compute
+/// the metrics of a code of the given size, in bytes.
+///
+/// The estimate can be used to skip the files which would exhaust
+/// the available memory before reading them.
+///
+/// # Examples
+///
+/// ```
+/// use rust_code_analysis::estimate_analysis_memory;
+///
+/// let max_memory = 1 << 30;
+/// let size = 300 << 20;
+///
+/// // The analysis of a 300 MiB file requires more than 1 GiB
+/// assert!(estimate_analysis_memory(size) > max_memory);
+/// ```
+pub fn estimate_analysis_memory(code_size: u64) -> u64 {
+    code_size.saturating_mul(MEMORY_PER_CODE_BYTE)
+}
+
+/// How the analysis of a code fits in a memory cap.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum MemoryFit {
+    /// All the metrics can be computed
+    Full,
+    /// Only the metrics of the unit space can be computed,
+    /// as [`MetricsOptions::unit_only`] does
+    ///
+    /// [`MetricsOptions::unit_only`]: crate::MetricsOptions::unit_only
+    UnitOnly,
+    /// The code cannot be analyzed
+    TooLarge,
+}
+
+/// Checks how the analysis of a code of the given size, in bytes,
+/// fits in the given amount of memory, in bytes.
+///
+/// # Examples
+///
+/// ```
+/// use rust_code_analysis::{MemoryFit, fit_analysis_memory};
+///
+/// let max_memory = 1 << 30;
+///
+/// assert_eq!(fit_analysis_memory(1 << 20, max_memory), MemoryFit::Full);
+/// assert_eq!(fit_analysis_memory(300 << 20, max_memory), MemoryFit::TooLarge);
+/// ```
+pub fn fit_analysis_memory(code_size: u64, max_memory: u64) -> MemoryFit {
+    if estimate_analysis_memory(code_size) <= max_memory {
+        MemoryFit::Full
+    } else if code_size.saturating_mul(UNIT_MEMORY_PER_CODE_BYTE) <= max_memory {
+        MemoryFit::UnitOnly
+    } else {
+        MemoryFit::TooLarge
+    }
+}
+
 /// Writes data to a file.
 ///
 /// # Examples
@@ -137,11 +239,38 @@ fn mode_to_str(mode: &[u8]) -> Option<String> {
 static RE1_EMACS: OnceLock<Regex> = OnceLock::new();
 static RE2_EMACS: OnceLock<Regex> = OnceLock::new();
 static RE1_VIM: OnceLock<Regex> = OnceLock::new();
+static RE_CONTENT: OnceLock<Vec<(LANG, Regex)>> = OnceLock::new();
 
 // Regular expressions
 const FIRST_EMACS_EXPRESSION: &str = r"(?i)-\*-.*[^-\w]mode\s*:\s*([^:;\s]+)";
 const SECOND_EMACS_EXPRESSION: &str = r"-\*-\s*([^:;\s]+)\s*-\*-";
-const VIM_EXPRESSION: &str = r"(?i)vim\s*:.*[^\w]ft\s*=\s*([^:\s]+)";
+const VIM_EXPRESSION: &str = r"(?i)vim?\s*:.*[^\w](?:ft|filetype|syntax)\s*=\s*([^:\s]+)";
+
+// Statements which are very unlikely to be found in other languages,
+// checked in order against the first lines of an extensionless file
+const CONTENT_EXPRESSIONS: &[(LANG, &str)] = &[
+    (LANG::Cpp, r"(?m)^\s*#\s*include\s*[<\x22]"),
+    (
+        LANG::Rust,
+        r"(?m)^\s*(?:#!?\[\w+|use \w+::|(?:pub(?:\(crate\))? )?fn \w+(?:<.*>)?\(.*\)\s*(?:->.*)?\{)",
+    ),
+    (LANG::Java, r"(?m)^\s*(?:package [\w.]+;|import java\.)"),
+    (
+        LANG::Kotlin,
+        r"(?m)^\s*(?:package [\w.]+\s*$|fun (?:main|\w+)\(.*\)\s*(?::.*)?[={])",
+    ),
+    (
+        LANG::Python,
+        r"(?m)^\s*(?:def \w+\(.*\)\s*(?:->.*)?:\s*$|from [\w.]+ import |if __name__ == ['\x22]__main__['\x22])",
+    ),
+    (
+        LANG::Mozjs,
+        r"(?m)^\s*(?:(?:const|let|var) \w+ = require\(|import .+ from ['\x22]|module\.exports\b)",
+    ),
+];
+
+// Number of lines used to guess the language of a code from its content
+const CONTENT_LINES: usize = 50;
 
 #[inline(always)]
 fn get_regex<'a>(
@@ -182,8 +311,50 @@ fn get_emacs_mode(buf: &[u8]) -> Option<String> {
     None
 }
 
+fn get_shebang_interpreter(buf: &[u8]) -> Option<String> {
+    let line = buf.strip_prefix(b"#!")?.split(|c| *c == b'\n').next()?;
+    let line = std::str::from_utf8(line).ok()?;
+    let mut words = line.split_whitespace();
+    let mut program = words.next()?.rsplit('/').next()?;
+    if program == "env" {
+        // Skip the options and the variables passed to env
+        program = words.find(|word| !word.starts_with('-') && !word.contains('='))?;
+    }
+
+    // Remove the version number, as in python3.11
+    let interpreter = program.trim_end_matches(|c: char| c.is_ascii_digit() || c == '.');
+    Some(interpreter.to_lowercase())
+}
+
+fn get_from_content(buf: &[u8]) -> Option<LANG> {
+    let head_len = buf
+        .iter()
+        .enumerate()
+        .filter(|(_, c)| **c == b'\n')
+        .nth(CONTENT_LINES - 1)
+        .map_or(buf.len(), |(i, _)| i);
+    let head = &buf[..head_len];
+
+    RE_CONTENT
+        .get_or_init(|| {
+            CONTENT_EXPRESSIONS
+                .iter()
+                .map(|(lang, re)| (*lang, Regex::new(re).unwrap()))
+                .collect()
+        })
+        .iter()
+        .find(|(_, re)| re.is_match(head))
+        .map(|(lang, _)| *lang)
+}
+
 /// Guesses the language of a code.
 ///
+/// The language is detected using, in order, the extension of the file,
+/// the `Emacs` or `Vim` modeline and the shebang line of the code.
+/// When the file has no extension, the first lines of the code
+/// are also compared against a series of statements typical
+/// of each language.
+///
 /// Returns a tuple containing a [`LANG`] as first argument
 /// and the language name as a second one.
 ///
@@ -240,6 +411,11 @@ pub fn guess_language<'a, P: AsRef<Path>>(buf: &[u8], path: P) -> (Option<LANG>,
             Some(lang_mode),
             fake::get_true(&ext, &mode).unwrap_or_else(|| lang_mode.get_name()),
         )
+    } else if let Some(lang) = get_shebang_interpreter(buf)
+        .and_then(|interpreter| get_from_interpreter(&interpreter))
+        .or_else(|| ext.is_empty().then(|| get_from_content(buf)).flatten())
+    {
+        (Some(lang), lang.get_name())
     } else {
         (None, fake::get_true(&ext, &mode).unwrap_or_default())
     }
@@ -247,6 +423,16 @@ pub fn guess_language<'a, P: AsRef<Path>>(buf: &[u8], path: P) -> (Option<LANG>,
 
 /// Replaces \n and \r ending characters with a single generic \n
 pub(crate) fn remove_blank_lines(data: &mut Vec<u8>) {
+    // The code is ended by its first line ending, not to mix them
+    let eol: &[u8] = match data
+        .iter()
+        .position(|c| *c == b'\n' || *c == b'\r')
+        .map(|start| &data[start..])
+    {
+        Some([b'\r', b'\n', ..]) => b"\r\n",
+        Some([b'\r', ..]) => b"\r",
+        _ => b"\n",
+    };
     let count_trailing = data
         .iter()
         .rev()
@@ -255,7 +441,7 @@ pub(crate) fn remove_blank_lines(data: &mut Vec<u8>) {
     if count_trailing > 0 {
         data.truncate(data.len() - count_trailing);
     }
-    data.push(b'\n');
+    data.extend_from_slice(eol);
 }
 
 pub(crate) fn normalize_path<P: AsRef<Path>>(path: P) -> PathBuf {
@@ -369,6 +555,30 @@ pub(crate) fn guess_file<S: ::std::hash::BuildHasher>(
 }
 
 #[inline(always)]
+// Serializes a set in the order of its elements, so that the output
+// does not depend on the order of the hashes
+pub(crate) fn serialize_sorted_set<S, T>(set: &HashSet<T>, serializer: S) -> Result<S::Ok, S::Error>
+where
+    S: Serializer,
+    T: Serialize + Ord,
+{
+    serializer.collect_seq(set.iter().collect::<BTreeSet<_>>())
+}
+
+// Serializes a map in the order of its keys, so that the output
+// does not depend on the order of the hashes
+pub(crate) fn serialize_sorted_map<S, K, V>(
+    map: &HashMap<K, V>,
+    serializer: S,
+) -> Result<S::Ok, S::Error>
+where
+    S: Serializer,
+    K: Serialize + Ord,
+    V: Serialize,
+{
+    serializer.collect_map(map.iter().collect::<BTreeMap<_, _>>())
+}
+
 pub(crate) fn color(stdout: &mut StandardStreamLock, color: Color) -> std::io::Result<()> {
     stdout.set_color(ColorSpec::new().set_fg(Some(color)))
 }
@@ -408,23 +618,43 @@ mod tests {
 
     use super::*;
 
+    #[test]
+    fn test_fit_analysis_memory() {
+        let size = 10 << 20;
+        assert_eq!(fit_analysis_memory(size, 1 << 30), MemoryFit::Full);
+        // Over the budget of a full analysis, only the unit is analyzed
+        assert_eq!(fit_analysis_memory(size, 512 << 20), MemoryFit::UnitOnly);
+        assert_eq!(fit_analysis_memory(size, 128 << 20), MemoryFit::TooLarge);
+    }
+
     #[test]
     fn test_read() {
         let tmp_dir = std::env::temp_dir();
         let tmp_path = tmp_dir.join("test_read");
         let data = vec![
-            (b"\xFF\xFEabc".to_vec(), Some(b"abc\n".to_vec())),
-            (b"\xFE\xFFabc".to_vec(), Some(b"abc\n".to_vec())),
+            (b"\xFF\xFEa\x00b\x00c\x00".to_vec(), Some(b"abc\n".to_vec())),
+            (b"\xFE\xFF\x00a\x00b\x00c".to_vec(), Some(b"abc\n".to_vec())),
             (b"\xEF\xBB\xBFabc".to_vec(), Some(b"abc\n".to_vec())),
             (b"\xEF\xBB\xBFabc\n".to_vec(), Some(b"abc\n".to_vec())),
-            (b"\xEF\xBBabc\n".to_vec(), None),
+            (
+                b"\xEF\xBBabc\n".to_vec(),
+                Some("ï»abc\n".as_bytes().to_vec()),
+            ),
+            (b"\x7FELF\x02\x01\x01\x00".to_vec(), None),
             (b"abcdef\n".to_vec(), Some(b"abcdef\n".to_vec())),
             (b"abcdef".to_vec(), Some(b"abcdef\n".to_vec())),
+            // The code is ended by its own line ending
+            (
+                b"abc\r\ndef\r\n\r\n".to_vec(),
+                Some(b"abc\r\ndef\r\n".to_vec()),
+            ),
+            (b"abc\rdef".to_vec(), Some(b"abc\rdef\r".to_vec())),
         ];
         for (d, expected) in data {
             write_file(&tmp_path, &d).unwrap();
             let res = read_file_with_eol(&tmp_path).unwrap();
             assert_eq!(res, expected);
+            assert_eq!(read_bytes_with_eol(&d), expected);
         }
     }
 
@@ -456,5 +686,48 @@ mod tests {
             guess_language(buf, "foo.mm"),
             (Some(LANG::Cpp), "obj-c/c++")
         );
+
+        let buf = b"# vim: set filetype=python:\nfoo = 42\n";
+        assert_eq!(guess_language(buf, "foo"), (Some(LANG::Python), "python"));
+    }
+
+    #[test]
+    fn test_guess_language_shebang() {
+        let buf = b"#!/usr/bin/env python3\nprint(42)\n";
+        assert_eq!(guess_language(buf, "foo"), (Some(LANG::Python), "python"));
+
+        let buf = b"#!/usr/bin/python3.11 -u\nprint(42)\n";
+        assert_eq!(
+            guess_language(buf, "foo.cgi"),
+            (Some(LANG::Python), "python")
+        );
+
+        let buf = b"#!/usr/bin/env -S node --experimental-modules\nfoo();\n";
+        assert_eq!(
+            guess_language(buf, "foo"),
+            (Some(LANG::Mozjs), "javascript")
+        );
+
+        let buf = b"#!/bin/sh\necho 42\n";
+        assert_eq!(guess_language(buf, "foo"), (None, ""));
+    }
+
+    #[test]
+    fn test_guess_language_content() {
+        let buf = b"#include <stdio.h>\n\nint main() {}\n";
+        assert_eq!(guess_language(buf, "foo"), (Some(LANG::Cpp), "c/c++"));
+
+        let buf = b"use std::io;\n\nfn main() {\n}\n";
+        assert_eq!(guess_language(buf, "foo"), (Some(LANG::Rust), "rust"));
+
+        let buf = b"import os\n\ndef main():\n    pass\n";
+        assert_eq!(guess_language(buf, "foo"), (Some(LANG::Python), "python"));
+
+        let buf = b"package foo;\n\npublic class Foo {}\n";
+        assert_eq!(guess_language(buf, "foo"), (Some(LANG::Java), "java"));
+
+        // Content heuristics are not used when the file has an extension
+        let buf = b"#include <stdio.h>\n";
+        assert_eq!(guess_language(buf, "foo.txt"), (None, ""));
     }
 }
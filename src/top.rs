@@ -0,0 +1,297 @@
+use std::fmt;
+
+use serde::Serialize;
+
+use crate::delta::metric_values;
+use crate::describe::describe_metric_field;
+use crate::spaces::{FuncSpace, SpaceKind};
+
+/// A function ranked by the value of a metric.
+#[derive(Clone, Debug, PartialEq, Serialize)]
+pub struct RankedFunction {
+    /// The rank of the function, starting from 1 for the worst one
+    pub rank: usize,
+    /// The path of the file containing the function
+    pub path: String,
+    /// The name of the function
+    pub name: String,
+    /// The first line of the function
+    pub start_line: usize,
+    /// The last line of the function
+    pub end_line: usize,
+    /// The value of the metric
+    pub value: f64,
+}
+
+/// The worst functions of all the analyzed files for a metric.
+///
+/// The worst functions are the ones with the highest values of the metric,
+/// or with the lowest ones for the metrics whose higher values are better,
+/// such as the maintainability index.
+#[derive(Clone, Debug, PartialEq, Serialize)]
+pub struct TopFunctions {
+    /// The metric, such as `cognitive`
+    pub metric: String,
+    /// The field of the metric, such as `sum`
+    pub field: String,
+    #[serde(skip)]
+    limit: usize,
+    #[serde(skip)]
+    higher_is_better: bool,
+    functions: Vec<RankedFunction>,
+}
+
+impl TopFunctions {
+    /// Creates an empty ranking of the `limit` worst functions.
+    ///
+    /// The metric is named as in the serialized metrics, optionally
+    /// followed by one of its fields, such as `cognitive` or `loc.sloc`.
+    /// Without a field, the `sum` field is used, or the first field
+    /// of the metrics without one.
+    ///
+    /// Returns `None` if the metric or the field does not exist.
+    pub fn new(metric: &str, limit: usize) -> Option<Self> {
+        let (metric, field) = describe_metric_field(metric)?;
+        Some(Self {
+            metric: metric.key.to_owned(),
+            field: field.to_owned(),
+            limit,
+            higher_is_better: metric.higher_is_better,
+            functions: Vec::new(),
+        })
+    }
+
+    /// Adds the functions of a file to the ranking.
+    ///
+    /// * `space` - The root function space of the file.
+    pub fn add(&mut self, space: &FuncSpace) {
+        let path = space.name.clone().unwrap_or_default();
+        self.add_functions(&path, space);
+        self.rank();
+    }
+
+    fn add_functions(&mut self, path: &str, space: &FuncSpace) {
+        for subspace in &space.spaces {
+            if subspace.kind == SpaceKind::Function
+                && let Some(value) = metric_values(&subspace.metrics)
+                    .get(&self.metric)
+                    .and_then(|fields| fields.get(&self.field))
+            {
+                self.functions.push(RankedFunction {
+                    rank: 0,
+                    path: path.to_owned(),
+                    name: subspace
+                        .qualified_name
+                        .clone()
+                        .or_else(|| subspace.name.clone())
+                        .unwrap_or_default(),
+                    start_line: subspace.start_line,
+                    end_line: subspace.end_line,
+                    value: *value,
+                });
+            }
+            self.add_functions(path, subspace);
+        }
+    }
+
+    // Sorts the functions from the worst one and keeps the first ones.
+    // The ties are broken by the position of the functions, so the ranking
+    // does not depend on the order in which the files are added.
+    fn rank(&mut self) {
+        let higher_is_better = self.higher_is_better;
+        self.functions.sort_by(|a, b| {
+            let order = if higher_is_better {
+                a.value.total_cmp(&b.value)
+            } else {
+                b.value.total_cmp(&a.value)
+            };
+            order
+                .then_with(|| a.path.cmp(&b.path))
+                .then_with(|| a.start_line.cmp(&b.start_line))
+        });
+        self.functions.truncate(self.limit);
+        for (i, function) in self.functions.iter_mut().enumerate() {
+            function.rank = i + 1;
+        }
+    }
+
+    /// Returns the ranked functions, from the worst one.
+    pub fn functions(&self) -> &[RankedFunction] {
+        &self.functions
+    }
+
+    /// Returns the ranking as a Markdown table.
+    pub fn to_markdown(&self) -> String {
+        let mut table = format!(
+            "| Rank | Function | Location | {}.{} |\n| ---: | --- | --- | ---: |\n",
+            self.metric, self.field
+        );
+        for function in &self.functions {
+            table.push_str(&format!(
+                "| {} | `{}` | {}:{}-{} | {} |\n",
+                function.rank,
+                function.name.replace('|', "\\|"),
+                function.path.replace('|', "\\|"),
+                function.start_line,
+                function.end_line,
+                format_value(function.value)
+            ));
+        }
+        table
+    }
+}
+
+fn format_value(value: f64) -> String {
+    if value.fract() == 0. {
+        format!("{value}")
+    } else {
+        format!("{value:.2}")
+    }
+}
+
+/// Prints the ranking as a table aligned on the widest values.
+impl fmt::Display for TopFunctions {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let header = [
+            "Rank".to_owned(),
+            "Function".to_owned(),
+            "Location".to_owned(),
+            format!("{}.{}", self.metric, self.field),
+        ];
+        let rows: Vec<_> = self
+            .functions
+            .iter()
+            .map(|function| {
+                [
+                    function.rank.to_string(),
+                    function.name.clone(),
+                    format!(
+                        "{}:{}-{}",
+                        function.path, function.start_line, function.end_line
+                    ),
+                    format_value(function.value),
+                ]
+            })
+            .collect();
+        let mut widths = header.clone().map(|cell| cell.chars().count());
+        for row in &rows {
+            for (width, cell) in widths.iter_mut().zip(row) {
+                *width = (*width).max(cell.chars().count());
+            }
+        }
+
+        let write_row = |f: &mut fmt::Formatter, [rank, name, location, value]: &[String; 4]| {
+            let [rank_width, name_width, location_width, value_width] = widths;
+            write!(
+                f,
+                "{rank:>rank_width$}  {name:<name_width$}  {location:<location_width$}  {value:>value_width$}"
+            )
+        };
+        write_row(f, &header)?;
+        write!(f, "\n{}", "-".repeat(widths.iter().sum::<usize>() + 6))?;
+        for row in &rows {
+            writeln!(f)?;
+            write_row(f, row)?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::path::Path;
+
+    use pretty_assertions::assert_eq;
+
+    use crate::{LANG, get_function_spaces};
+
+    use super::*;
+
+    fn spaces(path: &str, code: &str) -> FuncSpace {
+        get_function_spaces(
+            &LANG::Python,
+            code.as_bytes().to_vec(),
+            Path::new(path),
+            None,
+        )
+        .unwrap()
+    }
+
+    #[test]
+    fn top_metric_names() {
+        let top = TopFunctions::new("cognitive", 10).unwrap();
+        assert_eq!(
+            (top.metric.as_str(), top.field.as_str()),
+            ("cognitive", "sum")
+        );
+        let top = TopFunctions::new("loc.sloc", 10).unwrap();
+        assert_eq!((top.metric.as_str(), top.field.as_str()), ("loc", "sloc"));
+        let top = TopFunctions::new("mi", 10).unwrap();
+        assert_eq!(top.field, "mi_original");
+
+        assert!(TopFunctions::new("foo", 10).is_none());
+        assert!(TopFunctions::new("loc.foo", 10).is_none());
+    }
+
+    #[test]
+    fn top_functions() {
+        let mut top = TopFunctions::new("cyclomatic", 2).unwrap();
+        top.add(&spaces(
+            "b.py",
+            "def foo(a):\n    if a:\n        return 1\n    return 2\n\ndef bar():\n    pass\n",
+        ));
+        top.add(&spaces(
+            "a.py",
+            "def baz(a, b):\n    if a and b:\n        return 1\n    return 2\n\ndef qux(a):\n    return a or 1\n",
+        ));
+
+        insta::assert_snapshot!(top, @r###"
+        Rank  Function  Location  cyclomatic.sum
+        ----------------------------------------
+           1  a.baz     a.py:1-4               3
+           2  a.qux     a.py:6-7               2
+        "###);
+        insta::assert_snapshot!(top.to_markdown(), @r###"
+        | Rank | Function | Location | cyclomatic.sum |
+        | ---: | --- | --- | ---: |
+        | 1 | `a.baz` | a.py:1-4 | 3 |
+        | 2 | `a.qux` | a.py:6-7 | 2 |
+        "###);
+        insta::assert_json_snapshot!(top, @r###"
+{
+  "metric": "cyclomatic",
+  "field": "sum",
+  "functions": [
+    {
+      "rank": 1,
+      "path": "a.py",
+      "name": "a.baz",
+      "start_line": 1,
+      "end_line": 4,
+      "value": 3.0
+    },
+    {
+      "rank": 2,
+      "path": "a.py",
+      "name": "a.qux",
+      "start_line": 6,
+      "end_line": 7,
+      "value": 2.0
+    }
+  ]
+}
+"###);
+    }
+
+    #[test]
+    fn top_functions_higher_is_better() {
+        let mut top = TopFunctions::new("mi", 1).unwrap();
+        top.add(&spaces(
+            "foo.py",
+            "def foo(a):\n    if a:\n        return 1\n    return 2\n\ndef bar():\n    pass\n",
+        ));
+
+        // The least maintainable function is the most complex one
+        assert_eq!(top.functions()[0].name, "foo.foo");
+    }
+}
@@ -2,15 +2,24 @@ use std::path::Path;
 use std::sync::Arc;
 
 use crate::abc::Abc;
+use crate::accessors::Accessors;
 use crate::alterator::Alterator;
+use crate::annotations::Annotations;
+use crate::api::Exports;
+use crate::assertions::Assertions;
+use crate::callbacks::Callbacks;
 use crate::checker::Checker;
 use crate::cognitive::Cognitive;
 use crate::cyclomatic::Cyclomatic;
+use crate::encoding::LineEnding;
 use crate::exit::Exit;
 use crate::getter::Getter;
 use crate::halstead::Halstead;
+use crate::kotlin_funcs::KotlinFuncs;
 use crate::langs::*;
 use crate::loc::Loc;
+use crate::locals::Locals;
+use crate::macro_calls::MacroCalls;
 use crate::mi::Mi;
 use crate::nargs::NArgs;
 use crate::node::Node;
@@ -18,7 +27,9 @@ use crate::nom::Nom;
 use crate::npa::Npa;
 use crate::npm::Npm;
 use crate::parser::Filter;
+use crate::ppc::Ppc;
 use crate::preproc::PreprocResults;
+use crate::unsafety::Unsafety;
 use crate::wmc::Wmc;
 
 /// A trait for callback functions.
@@ -58,9 +69,20 @@ pub trait ParserTrait {
     type Abc: Abc;
     type Npm: Npm;
     type Npa: Npa;
+    type Ppc: Ppc;
+    type Unsafety: Unsafety;
+    type Callbacks: Callbacks;
+    type Accessors: Accessors;
+    type Assertions: Assertions;
+    type Locals: Locals;
+    type Annotations: Annotations;
+    type KotlinFuncs: KotlinFuncs;
+    type MacroCalls: MacroCalls;
+    type Exports: Exports;
 
     fn new(code: Vec<u8>, path: &Path, pr: Option<Arc<PreprocResults>>) -> Self;
     fn get_language(&self) -> LANG;
+    fn get_line_ending(&self) -> LineEnding;
     fn get_root(&self) -> Node<'_>;
     fn get_code(&self) -> &[u8];
     fn get_filters(&self, filters: &[String]) -> Filter;
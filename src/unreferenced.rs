@@ -0,0 +1,413 @@
+use std::collections::HashMap;
+use std::fmt;
+use std::path::Path;
+
+use serde::Serialize;
+
+use crate::api::{ApiSymbolKind, public_api};
+use crate::checker::Checker;
+use crate::getter::Getter;
+use crate::node::Node;
+use crate::spaces::SpaceKind;
+use crate::traits::*;
+
+/// The confidence that an unreferenced function is dead code.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Confidence {
+    /// The function overrides a method, so it can be called
+    /// through its parent class or interface
+    Low,
+    /// The function is a method, which can be called dynamically
+    /// or through an interface
+    Medium,
+    /// The function is a free function
+    High,
+}
+
+impl fmt::Display for Confidence {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let s = match self {
+            Confidence::Low => "low",
+            Confidence::Medium => "medium",
+            Confidence::High => "high",
+        };
+        write!(f, "{s}")
+    }
+}
+
+/// A function defined in a file.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize)]
+pub struct DefinedFunction {
+    /// The name of the function
+    pub name: String,
+    /// The first line of the function
+    pub start_line: usize,
+    /// The last line of the function
+    pub end_line: usize,
+    /// The occurrences of the name inside the function,
+    /// such as its definition and its recursive calls
+    pub inner_references: usize,
+    /// The confidence that the function is dead code if it is unreferenced
+    pub confidence: Confidence,
+}
+
+/// The functions defined in a file and the names referenced by its code.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize)]
+pub struct FunctionReferences {
+    /// The path of the file
+    pub name: String,
+    /// The functions which can be dead code, that is all the named
+    /// functions except for the public ones, the entry points and the tests
+    pub functions: Vec<DefinedFunction>,
+    /// The number of occurrences of each identifier in the code
+    #[serde(serialize_with = "crate::tools::serialize_sorted_map")]
+    pub references: HashMap<String, usize>,
+}
+
+/// A function never referenced in the analyzed files.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize)]
+pub struct UnreferencedFunction {
+    /// The path of the file containing the function
+    pub path: String,
+    /// The name of the function
+    pub name: String,
+    /// The first line of the function
+    pub start_line: usize,
+    /// The last line of the function
+    pub end_line: usize,
+    /// The confidence that the function is dead code
+    pub confidence: Confidence,
+}
+
+// The functions called implicitly by the runtime
+fn is_entry_point(name: &str) -> bool {
+    name == "main" || (name.len() > 4 && name.starts_with("__") && name.ends_with("__"))
+}
+
+fn is_identifier(text: &str) -> bool {
+    let mut chars = text.chars();
+    chars
+        .next()
+        .is_some_and(|c| c.is_alphabetic() || c == '_' || c == '$')
+        && chars.all(|c| c.is_alphanumeric() || c == '_' || c == '$')
+}
+
+// Returns the name referenced by the calls of a function,
+// such as `bar` for the C++ method `Foo::bar`
+fn short_name(name: &str) -> &str {
+    name.rsplit(['.', ':']).next().unwrap_or(name)
+}
+
+fn text<'a>(node: &Node, code: &'a [u8]) -> &'a str {
+    std::str::from_utf8(&code[node.start_byte()..node.end_byte()]).unwrap_or_default()
+}
+
+// Returns the attributes, the annotations and the decorators of a function,
+// which are before its name in its node, in the decorated definition
+// containing it or in the attributes preceding it
+fn annotations<'a>(node: &Node, name: &str, code: &'a [u8]) -> Vec<&'a str> {
+    let mut annotations = Vec::new();
+    let definition = node
+        .parent()
+        .filter(|parent| text(parent, code).starts_with('@'))
+        .unwrap_or(*node);
+    let definition = text(&definition, code);
+    annotations.push(definition.find(name).map_or("", |end| &definition[..end]));
+
+    let mut sibling = node.previous_sibling();
+    while let Some(attribute) = sibling.filter(|sibling| text(sibling, code).starts_with("#[")) {
+        annotations.push(text(&attribute, code));
+        sibling = attribute.previous_sibling();
+    }
+    annotations
+}
+
+// Counts the identifiers equal to a name in a node
+fn count_references(node: &Node, name: &str, code: &[u8]) -> usize {
+    let mut count = 0;
+    node.act_on_node(&mut |node| {
+        if node.child_count() == 0 && text(node, code) == name {
+            count += 1;
+        }
+    });
+    count
+}
+
+fn defined_function<T: ParserTrait>(
+    node: &Node,
+    name: &str,
+    code: &[u8],
+) -> Option<DefinedFunction> {
+    let short_name = short_name(name);
+    if is_entry_point(short_name) {
+        return None;
+    }
+    let annotations = annotations(node, short_name, code);
+    let has_annotation = |annotation: &str| {
+        annotations
+            .iter()
+            .any(|text| text.to_lowercase().contains(annotation))
+    };
+    if short_name.starts_with("test") || has_annotation("test") {
+        return None;
+    }
+
+    let mut ancestor = node.parent();
+    let mut is_method = false;
+    while let Some(parent) = ancestor {
+        if T::Checker::is_func(&parent) {
+            break;
+        }
+        if matches!(
+            T::Getter::get_space_kind(&parent),
+            SpaceKind::Class
+                | SpaceKind::Struct
+                | SpaceKind::Trait
+                | SpaceKind::Impl
+                | SpaceKind::Interface
+        ) {
+            is_method = true;
+            break;
+        }
+        ancestor = parent.parent();
+    }
+    let confidence = if has_annotation("override") {
+        Confidence::Low
+    } else if is_method {
+        Confidence::Medium
+    } else {
+        Confidence::High
+    };
+
+    Some(DefinedFunction {
+        name: name.to_owned(),
+        start_line: node.start_row() + 1,
+        end_line: node.end_row() + 1,
+        inner_references: count_references(node, short_name, code),
+        confidence,
+    })
+}
+
+/// Finds the functions defined in a code and the names it references.
+///
+/// The public functions, the entry points such as `main` and the Python
+/// special methods, and the tests are not considered dead code,
+/// so they are not listed.
+///
+/// * `parser` - The parser of the code.
+/// * `path` - The path of the file.
+pub fn function_references<T: ParserTrait>(parser: &T, path: &Path) -> FunctionReferences {
+    let code = parser.get_code();
+    let root = parser.get_root();
+    let public: Vec<_> = public_api(parser)
+        .into_iter()
+        .filter(|symbol| matches!(symbol.kind, ApiSymbolKind::Function | ApiSymbolKind::Method))
+        .map(|symbol| (symbol.start_line, symbol.end_line))
+        .collect();
+
+    let mut functions = Vec::new();
+    let mut references = HashMap::new();
+    root.act_on_node(&mut |node| {
+        if node.child_count() == 0 {
+            let text = text(node, code);
+            if is_identifier(text) {
+                *references.entry(text.to_owned()).or_insert(0) += 1;
+            }
+        } else if T::Checker::is_func(node)
+            && let Some(name) = T::Getter::get_func_name(node, code)
+            && is_identifier(short_name(name))
+            && !public.contains(&(node.start_row() + 1, node.end_row() + 1))
+            && let Some(function) = defined_function::<T>(node, name, code)
+        {
+            functions.push(function);
+        }
+    });
+
+    FunctionReferences {
+        name: path.to_str().unwrap_or_default().to_owned(),
+        functions,
+        references,
+    }
+}
+
+/// Returns the functions never referenced in a set of files,
+/// which are candidates for dead code.
+///
+/// A function is referenced when its name is found in a file
+/// outside of the functions with the same name, so the functions
+/// called only through a different name or through reflection
+/// are reported too.
+///
+/// * `files` - The functions and the references of the analyzed files.
+///
+/// # Examples
+///
+/// ```
+/// use std::path::Path;
+///
+/// use rust_code_analysis::{Confidence, PythonParser, ParserTrait, function_references, unreferenced_functions};
+///
+/// let source_code = "def _foo():\n    pass\n\ndef _bar():\n    _foo()\n";
+/// let path = Path::new("foo.py");
+/// let parser = PythonParser::new(source_code.as_bytes().to_vec(), path, None);
+///
+/// let unreferenced = unreferenced_functions(&[function_references(&parser, path)]);
+///
+/// assert_eq!(unreferenced.len(), 1);
+/// assert_eq!(unreferenced[0].name, "_bar");
+/// assert_eq!(unreferenced[0].confidence, Confidence::High);
+/// ```
+pub fn unreferenced_functions(files: &[FunctionReferences]) -> Vec<UnreferencedFunction> {
+    let mut references: HashMap<&str, usize> = HashMap::new();
+    for (name, count) in files.iter().flat_map(|file| &file.references) {
+        *references.entry(name).or_insert(0) += count;
+    }
+    // The occurrences of the names inside the functions having them
+    // are not references
+    for function in files.iter().flat_map(|file| &file.functions) {
+        if let Some(count) = references.get_mut(short_name(&function.name)) {
+            *count = count.saturating_sub(function.inner_references);
+        }
+    }
+
+    files
+        .iter()
+        .flat_map(|file| {
+            file.functions
+                .iter()
+                .filter(|function| {
+                    references
+                        .get(short_name(&function.name))
+                        .is_none_or(|count| *count == 0)
+                })
+                .map(|function| UnreferencedFunction {
+                    path: file.name.clone(),
+                    name: function.name.clone(),
+                    start_line: function.start_line,
+                    end_line: function.end_line,
+                    confidence: function.confidence,
+                })
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use std::path::Path;
+
+    use super::*;
+    use crate::langs::{JavaParser, PythonParser, RustParser};
+
+    fn references<T: ParserTrait>(source: &str, path: &str) -> FunctionReferences {
+        let path = Path::new(path);
+        let parser = T::new(source.as_bytes().to_vec(), path, None);
+        function_references(&parser, path)
+    }
+
+    fn unreferenced(files: &[FunctionReferences]) -> Vec<String> {
+        unreferenced_functions(files)
+            .into_iter()
+            .map(|function| {
+                format!(
+                    "{}:{} {} {:?}",
+                    function.path, function.start_line, function.name, function.confidence
+                )
+            })
+            .collect()
+    }
+
+    #[test]
+    fn unreferenced_python_functions() {
+        let files = [
+            references::<PythonParser>(
+                "def _used():
+    pass
+
+def _unused():
+    return _unused()
+
+def _called_elsewhere():
+    pass
+
+class _A:
+    def __init__(self):
+        pass
+
+    def _method(self):
+        _used()
+
+def test_foo():
+    pass
+
+def public():
+    pass",
+                "a.py",
+            ),
+            references::<PythonParser>("from a import _called_elsewhere\n", "b.py"),
+        ];
+        insta::assert_debug_snapshot!(unreferenced(&files), @r###"
+        [
+            "a.py:4 _unused High",
+            "a.py:14 _method Medium",
+        ]
+        "###);
+    }
+
+    #[test]
+    fn unreferenced_rust_functions() {
+        let files = [references::<RustParser>(
+            "fn main() {
+    used();
+}
+
+fn used() {}
+
+fn unused() {}
+
+struct S;
+
+impl S {
+    fn method(&self) {}
+}
+
+#[test]
+fn check() {}
+
+#[cfg(test)]
+mod tests {
+    #[tokio::test]
+    async fn check_async() {}
+}
+
+pub fn public() {}",
+            "foo.rs",
+        )];
+        insta::assert_debug_snapshot!(unreferenced(&files), @r###"
+        [
+            "foo.rs:7 unused High",
+            "foo.rs:12 method Medium",
+        ]
+        "###);
+    }
+
+    #[test]
+    fn unreferenced_java_methods() {
+        let files = [references::<JavaParser>(
+            "public class A {
+    private void unused() {}
+    private int used() { return 1; }
+    @Override
+    protected String toString() { return used() + \"\"; }
+    public static void main(String[] args) {}
+}",
+            "A.java",
+        )];
+        insta::assert_debug_snapshot!(unreferenced(&files), @r###"
+        [
+            "A.java:2 unused Medium",
+            "A.java:4 toString Low",
+        ]
+        "###);
+    }
+}
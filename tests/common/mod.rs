@@ -84,6 +84,7 @@ pub fn compare_rca_output_with_files(repo_name: &str, include: &[&str], exclude:
         include: gsbi.build().unwrap(),
         exclude: gsbe.build().unwrap(),
         paths: vec![Path::new(REPO).join(repo_name)],
+        symlinks: SymlinkPolicy::default(),
     };
 
     if let Err(e) = ConcurrentRunner::new(num_jobs, act_on_file).run(cfg, files_data) {